@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind::UnexpectedEof;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{bail, Result};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::errors::Error;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::probe::Hint;
+use symphonia::default::get_codecs;
+use tempfile::NamedTempFile;
+
+const CACHE_EXTENSION: &str = "wav";
+
+/// A bounded on-disk cache of decoded samples. Entries are stored as 32bit float WAV files
+/// (which are trivially cheap to decode) named after the hash of the source file's content, so
+/// changing a sample's content will naturally cause a miss.
+#[derive(Debug)]
+pub struct SampleCache {
+    path: PathBuf,
+    enabled: AtomicBool,
+    limit: AtomicU64,
+
+    hits: AtomicU64,
+    misses: AtomicU64,
+
+    // Hashing a file requires reading it, so we remember the hash until the file's size or
+    // modified time changes.
+    hashes: Mutex<HashMap<PathBuf, (SystemTime, u64, String)>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SampleCacheStats {
+    pub size: u64,
+    pub entries: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl SampleCache {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            enabled: AtomicBool::new(false),
+            limit: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            hashes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Enables or disables the cache, with a size limit in megabytes.
+    pub fn configure(&self, enabled: bool, limit_mb: u32) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        self.limit
+            .store(limit_mb as u64 * 1024 * 1024, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the path of a complete cached decode for the source, if one is present. This is
+    /// called as a sample starts playing, so the source isn't read, its hash is looked up by its
+    /// path, size and modified time. A source which hasn't been stored since it last changed is
+    /// a miss, it's hashed when it's stored.
+    pub fn get(&self, source: &Path) -> Option<PathBuf> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let cached = self
+            .get_known_hash(source)
+            .map(|hash| self.get_entry_path(&hash))
+            .filter(|cached| cached.exists());
+        let Some(cached) = cached else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+
+        if !is_complete(&cached) {
+            debug!("Cached sample {:?} is incomplete, discarding", cached);
+            self.invalidate(&cached);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        // Bump the modified time, this is what we use to determine what was least recently used.
+        if let Ok(file) = File::options().append(true).open(&cached) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(cached)
+    }
+
+    /// Discards a cache entry, used if the entry turns out to be unusable.
+    pub fn invalidate(&self, cached: &Path) {
+        if cached.starts_with(&self.path) {
+            let _ = fs::remove_file(cached);
+        }
+    }
+
+    /// Decodes the source file into the cache (if it's not already there), then evicts any
+    /// older entries which would push the cache over its limit.
+    pub fn store(&self, source: &Path) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let cached = self.get_cache_path(source)?;
+        if cached.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.path)?;
+
+        // Decode into a temporary file, and only move it into place once complete. Each store
+        // gets its own, so two decodes of the same source can't write over each other, and one
+        // which fails is removed when dropped..
+        let temp = NamedTempFile::new_in(&self.path)?;
+        decode_to_wav(source, temp.path())?;
+        temp.persist(&cached)?;
+        debug!("Cached {:?} as {:?}", source, cached);
+
+        self.evict();
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        for (path, _, _) in self.get_entries() {
+            fs::remove_file(path)?;
+        }
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn get_stats(&self) -> SampleCacheStats {
+        let entries = self.get_entries();
+        SampleCacheStats {
+            size: entries.iter().map(|(_, size, _)| size).sum(),
+            entries: entries.len() as u64,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn evict(&self) {
+        let limit = self.limit.load(Ordering::Relaxed);
+
+        let mut entries = self.get_entries();
+        let mut size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+
+        // Oldest first..
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, entry_size, _) in entries {
+            if size <= limit {
+                break;
+            }
+            debug!("Evicting {:?} from the Sample Cache", path);
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Unable to remove cached sample {:?}: {}", path, e);
+                continue;
+            }
+            size -= entry_size;
+        }
+    }
+
+    fn get_entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let mut entries = vec![];
+        if let Ok(dir) = fs::read_dir(&self.path) {
+            for entry in dir.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(CACHE_EXTENSION) {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((path, metadata.len(), modified));
+                }
+            }
+        }
+        entries
+    }
+
+    fn get_cache_path(&self, source: &Path) -> Result<PathBuf> {
+        let hash = self.get_hash(source)?;
+        Ok(self.get_entry_path(&hash))
+    }
+
+    fn get_entry_path(&self, hash: &str) -> PathBuf {
+        self.path.join(format!("{}.{}", hash, CACHE_EXTENSION))
+    }
+
+    fn get_hash(&self, source: &Path) -> Result<String> {
+        if let Some(hash) = self.get_known_hash(source) {
+            return Ok(hash);
+        }
+
+        // The lock isn't held while hashing, so lookups aren't kept waiting for the read
+        let metadata = fs::metadata(source)?;
+        let hash = hash_file(source)?;
+        let mut hashes = self.hashes.lock().unwrap();
+        let entry = (metadata.modified()?, metadata.len(), hash.clone());
+        hashes.insert(source.to_path_buf(), entry);
+        Ok(hash)
+    }
+
+    fn get_known_hash(&self, source: &Path) -> Option<String> {
+        let metadata = fs::metadata(source).ok()?;
+        let modified = metadata.modified().ok()?;
+
+        let hashes = self.hashes.lock().unwrap();
+        match hashes.get(source) {
+            Some((known_modified, known_length, hash))
+                if *known_modified == modified && *known_length == metadata.len() =>
+            {
+                Some(hash.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+// Entries are moved into place once they've been written, so one which is shorter than its
+// header says (or doesn't have a header at all) has been damaged since.
+fn is_complete(cached: &Path) -> bool {
+    let Ok(length) = fs::metadata(cached).map(|metadata| metadata.len()) else {
+        return false;
+    };
+    let Ok(reader) = WavReader::open(cached) else {
+        return false;
+    };
+    let data = reader.len() as u64 * (reader.spec().bits_per_sample / 8) as u64;
+    match reader.into_inner().stream_position() {
+        Ok(start) => length >= start + data,
+        Err(_) => false,
+    }
+}
+
+/// A SHA-256 digest of the file's content, used to name files derived from it. This needs to
+/// be the same between builds and platforms, so files named by an older daemon are still found.
+pub(crate) fn hash_file(source: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file = File::open(source)?;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn decode_to_wav(source: &Path, target: &Path) -> Result<()> {
+    let mut hint = Hint::new();
+    if let Some(extension) = source.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let stream = MediaSourceStream::new(Box::new(File::open(source)?), Default::default());
+    let probe = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &Default::default(),
+        &Default::default(),
+    )?;
+    let mut reader = probe.format;
+
+    let track = match reader.default_track() {
+        Some(track) => track,
+        None => bail!("Unable to find Default Track"),
+    };
+    let track_id = track.id;
+
+    let channels = match track.codec_params.channels {
+        None => bail!("Unable to obtain channel count"),
+        Some(channels) => channels.count(),
+    };
+    let sample_rate = match track.codec_params.sample_rate {
+        None => bail!("Unable to Determine the Audio File's Sample Rate"),
+        Some(rate) => rate,
+    };
+
+    let mut decoder = get_codecs().make(&track.codec_params, &Default::default())?;
+    let spec = WavSpec {
+        channels: channels as u16,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(target, spec)?;
+    let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(error)) if error.kind() == UnexpectedEof => break,
+            Err(error) => bail!(error),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = decoder.decode(&packet)?;
+        let buffer = sample_buffer
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        buffer.copy_interleaved_ref(decoded);
+        for sample in buffer.samples() {
+            writer.write_sample(*sample)?;
+        }
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn file_hashes_are_stable() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("Sample.wav");
+        fs::write(&source, b"abc").unwrap();
+
+        // Files named by an earlier run must still be found, so this can never change
+        assert_eq!(
+            hash_file(&source).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    // A mono source of the given length, seeded so each one has its own content (and hash)
+    fn write_source(path: &Path, samples: u32, seed: u32) {
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        for sample in 0..samples {
+            writer.write_sample(((sample + seed) % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn enabled_cache(root: &Path, limit_mb: u32) -> SampleCache {
+        let cache = SampleCache::new(root.join("cache"));
+        cache.configure(true, limit_mb);
+        cache
+    }
+
+    #[test]
+    fn oldest_entries_are_evicted_at_the_limit() {
+        let root = tempfile::tempdir().unwrap();
+        let cache = enabled_cache(root.path(), 1);
+
+        // Each decodes to a little under 400KB, so the third takes the cache over 1MB
+        let sources: Vec<PathBuf> = (0..3)
+            .map(|seed| {
+                let source = root.path().join(format!("{}.wav", seed));
+                write_source(&source, 100_000, seed);
+                source
+            })
+            .collect();
+
+        for (age, source) in sources.iter().enumerate() {
+            cache.store(source).unwrap();
+
+            // Stores can be quicker than the filesystem's timestamps, so set them apart
+            let cached = cache.get_cache_path(source).unwrap();
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1000 * (age as u64 + 1));
+            File::options()
+                .append(true)
+                .open(cached)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.entries, 2);
+        assert!(stats.size <= 1024 * 1024);
+        assert!(cache.get(&sources[0]).is_none());
+        assert!(cache.get(&sources[1]).is_some());
+        assert!(cache.get(&sources[2]).is_some());
+    }
+
+    #[test]
+    fn damaged_entries_fall_back_to_decoding() {
+        let root = tempfile::tempdir().unwrap();
+        let cache = enabled_cache(root.path(), 10);
+        let source = root.path().join("Sample.wav");
+        write_source(&source, 10_000, 0);
+
+        // Nothing is read to look a source up, so one which hasn't been stored is a miss
+        assert!(cache.get(&source).is_none());
+        cache.store(&source).unwrap();
+        let cached = cache.get(&source).unwrap();
+        let length = fs::metadata(&cached).unwrap().len();
+
+        let truncate = |cached: &Path| {
+            let file = File::options().write(true).open(cached).unwrap();
+            file.set_len(length / 2).unwrap();
+        };
+        let corrupt = |cached: &Path| fs::write(cached, b"Not a WAV file").unwrap();
+        for damage in [&truncate as &dyn Fn(&Path), &corrupt] {
+            damage(&cached);
+
+            // The damaged entry is discarded, so playback uses the source, which is decoded again
+            assert!(cache.get(&source).is_none());
+            assert!(!cached.exists());
+            cache.store(&source).unwrap();
+            assert_eq!(cache.get(&source), Some(cached.clone()));
+            assert_eq!(fs::metadata(&cached).unwrap().len(), length);
+        }
+        assert_eq!(cache.get_stats().misses, 3);
+    }
+}
@@ -0,0 +1,350 @@
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind::UnexpectedEof;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use anyhow::{bail, Result};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use log::debug;
+use rubato::{FftFixedIn, Resampler};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::CODEC_TYPE_PCM_S16LE;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatReader;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::probe::Hint;
+use symphonia::default::get_codecs;
+use tempfile::NamedTempFile;
+
+use crate::cache::hash_file;
+
+/// The format samples are converted to, which can be played without any resampling.
+pub const CONVERTED_SAMPLE_RATE: u32 = 48000;
+const CONVERTED_EXTENSION: &str = "wav";
+
+// How many frames are passed to the resampler at a time
+const RESAMPLE_CHUNK: usize = 1024;
+
+/// Whether the file is already in the converted format, so converting it would gain nothing.
+pub fn is_converted_format(source: &Path) -> bool {
+    let Ok(reader) = open(source) else {
+        return false;
+    };
+    let Some(track) = reader.default_track() else {
+        return false;
+    };
+
+    let params = &track.codec_params;
+    params.codec == CODEC_TYPE_PCM_S16LE && params.sample_rate == Some(CONVERTED_SAMPLE_RATE)
+}
+
+/// Converts the source to a 48kHz 16bit WAV in the directory, and returns its path. The file is
+/// named after the source and the SHA-256 digest of its content (as the Sample Cache names its
+/// entries), so converting the same file again, even after a restart, simply returns the previous
+/// conversion. The source itself is never changed.
+pub fn convert_sample(
+    source: &Path,
+    directory: &Path,
+    progress: &AtomicU8,
+    cancel: &AtomicBool,
+) -> Result<PathBuf> {
+    let suffix = format!("-{}.{}", hash_file(source)?, CONVERTED_EXTENSION);
+    if let Some(existing) = find_conversion(directory, &suffix) {
+        debug!("Reusing {:?} as the conversion of {:?}", existing, source);
+        return Ok(existing);
+    }
+
+    let stem = match source.file_stem() {
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => bail!("Unable to Extract Filename from Path"),
+    };
+    let target = directory.join(format!("{}{}", stem, suffix));
+    fs::create_dir_all(directory)?;
+
+    // Convert into a temporary file of its own, and only move it into place once complete (one
+    // which fails or is cancelled is removed when dropped)..
+    let temp = NamedTempFile::new_in(directory)?;
+    transcode(source, temp.path(), progress, cancel)?;
+    temp.persist(&target)?;
+    debug!("Converted {:?} to {:?}", source, target);
+
+    Ok(target)
+}
+
+fn find_conversion(directory: &Path, suffix: &str) -> Option<PathBuf> {
+    fs::read_dir(directory)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().ends_with(suffix))
+        })
+}
+
+fn open(source: &Path) -> Result<Box<dyn FormatReader>> {
+    let mut hint = Hint::new();
+    if let Some(extension) = source.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let stream = MediaSourceStream::new(Box::new(File::open(source)?), Default::default());
+    let probe = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &Default::default(),
+        &Default::default(),
+    )?;
+    Ok(probe.format)
+}
+
+fn transcode(source: &Path, target: &Path, progress: &AtomicU8, cancel: &AtomicBool) -> Result<()> {
+    let mut reader = open(source)?;
+
+    let track = match reader.default_track() {
+        Some(track) => track,
+        None => bail!("Unable to find Default Track"),
+    };
+    let track_id = track.id;
+    let frames = track.codec_params.n_frames;
+
+    let channels = match track.codec_params.channels {
+        None => bail!("Unable to obtain channel count"),
+        Some(channels) => channels.count(),
+    };
+    let sample_rate = match track.codec_params.sample_rate {
+        None => bail!("Unable to Determine the Audio File's Sample Rate"),
+        Some(rate) => rate,
+    };
+
+    // Samples are short, so the whole thing is decoded before being resampled in one go
+    let mut decoder = get_codecs().make(&track.codec_params, &Default::default())?;
+    let mut decoded_channels: Vec<Vec<f32>> = vec![vec![]; channels];
+    let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            bail!("Conversion Cancelled");
+        }
+
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(error)) if error.kind() == UnexpectedEof => break,
+            Err(error) => bail!(error),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = decoder.decode(&packet)?;
+        let buffer = sample_buffer
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        buffer.copy_interleaved_ref(decoded);
+        for frame in buffer.samples().chunks_exact(channels) {
+            for (channel, sample) in frame.iter().enumerate() {
+                decoded_channels[channel].push(*sample);
+            }
+        }
+
+        // Decoding is the slow part, resampling and writing are quick by comparison
+        if let Some(frames) = frames {
+            let decoded_frames = decoded_channels[0].len() as u64;
+            let percent = (decoded_frames * 100 / frames.max(1)).min(99) as u8;
+            progress.store(percent, Ordering::Relaxed);
+        }
+    }
+
+    let resampled = match sample_rate {
+        CONVERTED_SAMPLE_RATE => decoded_channels,
+        _ => resample(decoded_channels, sample_rate)?,
+    };
+
+    let spec = WavSpec {
+        channels: channels as u16,
+        sample_rate: CONVERTED_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(target, spec)?;
+    let length = resampled.first().map(Vec::len).unwrap_or(0);
+    for frame in 0..length {
+        for channel in &resampled {
+            let sample = (channel[frame].clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+            writer.write_sample(sample)?;
+        }
+    }
+    writer.finalize()?;
+
+    progress.store(100, Ordering::Relaxed);
+    Ok(())
+}
+
+fn resample(input: Vec<Vec<f32>>, sample_rate: u32) -> Result<Vec<Vec<f32>>> {
+    let channels = input.len();
+    let frames = input.first().map(Vec::len).unwrap_or(0);
+    let expected = (frames as u64 * CONVERTED_SAMPLE_RATE as u64).div_ceil(sample_rate as u64);
+
+    let mut resampler = FftFixedIn::<f32>::new(
+        sample_rate as usize,
+        CONVERTED_SAMPLE_RATE as usize,
+        RESAMPLE_CHUNK,
+        2,
+        channels,
+    )?;
+
+    // The resampler delays its output, so that's dropped from the start, and made up at the end
+    let delay = resampler.output_delay();
+    let mut output: Vec<Vec<f32>> = vec![vec![]; channels];
+
+    let mut position = 0;
+    while position < frames {
+        let end = (position + RESAMPLE_CHUNK).min(frames);
+        let chunk: Vec<&[f32]> = input.iter().map(|c| &c[position..end]).collect();
+        let resampled = match end - position {
+            RESAMPLE_CHUNK => resampler.process(&chunk, None)?,
+            _ => resampler.process_partial(Some(&chunk), None)?,
+        };
+        for (channel, samples) in resampled.into_iter().enumerate() {
+            output[channel].extend(samples);
+        }
+        position = end;
+    }
+
+    while (output[0].len() as u64) < expected + delay as u64 {
+        let flushed = resampler.process_partial::<Vec<f32>>(None, None)?;
+        for (channel, samples) in flushed.into_iter().enumerate() {
+            output[channel].extend(samples);
+        }
+    }
+
+    for channel in output.iter_mut() {
+        channel.drain(..delay);
+        channel.truncate(expected as usize);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::WavReader;
+
+    // A tenth of a second of a 440Hz tone, as a 96kHz 32bit float stereo WAV
+    fn write_source(path: &Path) {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 96000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        for frame in 0..9600 {
+            let sample = (frame as f32 * 440. * std::f32::consts::TAU / 96000.).sin() * 0.5;
+            writer.write_sample(sample).unwrap();
+            writer.write_sample(-sample).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn convert(source: &Path, directory: &Path) -> PathBuf {
+        let progress = AtomicU8::new(0);
+        let converted = convert_sample(source, directory, &progress, &AtomicBool::new(false));
+        assert_eq!(progress.load(Ordering::Relaxed), 100);
+        converted.unwrap()
+    }
+
+    #[test]
+    fn samples_are_converted_to_48khz_16bit() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("Tone.wav");
+        write_source(&source);
+        let original = fs::read(&source).unwrap();
+        assert!(!is_converted_format(&source));
+
+        let converted = convert(&source, &root.path().join("Converted"));
+        assert!(converted.starts_with(root.path().join("Converted")));
+        assert!(is_converted_format(&converted));
+
+        let reader = WavReader::open(&converted).unwrap();
+        let spec = reader.spec();
+        assert_eq!(
+            (spec.channels, spec.sample_rate),
+            (2, CONVERTED_SAMPLE_RATE)
+        );
+        assert_eq!(spec.bits_per_sample, 16);
+        assert_eq!(reader.duration(), 4800);
+
+        // The tone (and the channels) should survive the trip
+        let samples: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+        let peak = samples.iter().map(|s| s.unsigned_abs()).max().unwrap();
+        assert!((15000..=17500).contains(&peak), "Peak was {}", peak);
+        assert!(samples
+            .chunks(2)
+            .all(|frame| frame[0].abs_diff(-frame[1]) <= 1));
+
+        assert_eq!(fs::read(&source).unwrap(), original);
+    }
+
+    #[test]
+    fn conversions_are_reused() {
+        let root = tempfile::tempdir().unwrap();
+        let directory = root.path().join("Converted");
+        let source = root.path().join("Tone.wav");
+        write_source(&source);
+
+        let first = convert(&source, &directory);
+
+        // The same content under another name is still the same conversion..
+        let copy = root.path().join("Copy.wav");
+        fs::copy(&source, &copy).unwrap();
+        assert_eq!(convert(&copy, &directory), first);
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+
+        // ..but different content isn't
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let other = root.path().join("Other.wav");
+        let mut writer = WavWriter::create(&other, spec).unwrap();
+        (0..4410).for_each(|_| writer.write_sample(0_i16).unwrap());
+        writer.finalize().unwrap();
+
+        let second = convert(&other, &directory);
+        assert_ne!(second, first);
+        assert_eq!(WavReader::open(&second).unwrap().duration(), 4800);
+    }
+
+    #[test]
+    fn conversions_are_named_by_the_content_digest() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("Tone.wav");
+        write_source(&source);
+
+        // The same digest as the cache, so a conversion is found again after a restart
+        let digest = hash_file(&source).unwrap();
+        assert_eq!(digest.len(), 64);
+        let converted = convert(&source, &root.path().join("Converted"));
+        assert_eq!(
+            converted.file_name().unwrap().to_string_lossy(),
+            format!("Tone-{}.wav", digest)
+        );
+    }
+
+    #[test]
+    fn cancelled_conversions_leave_nothing_behind() {
+        let root = tempfile::tempdir().unwrap();
+        let directory = root.path().join("Converted");
+        let source = root.path().join("Tone.wav");
+        write_source(&source);
+
+        let progress = AtomicU8::new(0);
+        let cancel = AtomicBool::new(true);
+        assert!(convert_sample(&source, &directory, &progress, &cancel).is_err());
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+    }
+}
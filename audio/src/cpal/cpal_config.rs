@@ -1,9 +1,26 @@
+use crate::monitor::OutputBackend;
 use anyhow::{bail, Result};
 use cpal::traits::{DeviceTrait, HostTrait};
 use cpal::Device;
 
 pub struct CpalConfiguration {}
 
+/// Lets the OutputMonitor see cpal's devices, names are in the same `host*device` format as
+/// get_outputs().
+pub(crate) struct CpalBackend;
+
+impl OutputBackend for CpalBackend {
+    fn outputs(&self) -> Vec<String> {
+        CpalConfiguration::get_outputs()
+    }
+
+    fn default_output(&self) -> Option<String> {
+        let host = cpal::default_host();
+        let device = host.default_output_device()?;
+        Some(format!("{}*{}", host.id().name(), device.name().ok()?))
+    }
+}
+
 impl CpalConfiguration {
     pub(crate) fn get_device(device: Option<String>, input: bool) -> Result<Device> {
         let mut cpal_device = None;
@@ -69,12 +86,17 @@ impl CpalConfiguration {
     pub(crate) fn get_outputs() -> Vec<String> {
         let mut list: Vec<String> = vec![];
 
+        // Devices can disappear while we're listing them, so skip anything we can't read
         let available_hosts = cpal::available_hosts();
         for host_id in available_hosts {
-            let host = cpal::host_from_id(host_id).unwrap();
-            let devices = host.output_devices().unwrap();
+            let devices = match cpal::host_from_id(host_id).map(|host| host.output_devices()) {
+                Ok(Ok(devices)) => devices,
+                _ => continue,
+            };
             for device in devices {
-                list.push(format!("{}*{}", host_id.name(), device.name().unwrap()));
+                if let Ok(name) = device.name() {
+                    list.push(format!("{}*{}", host_id.name(), name));
+                }
             }
         }
         list
@@ -85,10 +107,14 @@ impl CpalConfiguration {
 
         let available_hosts = cpal::available_hosts();
         for host_id in available_hosts {
-            let host = cpal::host_from_id(host_id).unwrap();
-            let devices = host.input_devices().unwrap();
+            let devices = match cpal::host_from_id(host_id).map(|host| host.input_devices()) {
+                Ok(Ok(devices)) => devices,
+                _ => continue,
+            };
             for device in devices {
-                list.push(format!("{}*{}", host_id.name(), device.name().unwrap()));
+                if let Ok(name) = device.name() {
+                    list.push(format!("{}*{}", host_id.name(), name));
+                }
             }
         }
         list
@@ -1,21 +1,39 @@
 use crate::audio::{AudioOutput, AudioSpecification, OpenOutputStream};
-use crate::cpal::cpal_config::CpalConfiguration;
+use crate::cpal::cpal_config::{CpalBackend, CpalConfiguration};
+use crate::monitor::{OutputChange, OutputMonitor};
 use anyhow::{bail, Result};
 use cpal::traits::{DeviceTrait, StreamTrait};
-use cpal::Stream;
-use log::{debug, warn};
+use cpal::{Device, Stream};
+use log::{debug, info, warn};
 use rb::{Producer, RbConsumer, RbInspector, RbProducer, SpscRb, RB};
 use rubato::{FftFixedIn, Resampler};
 use std::panic::catch_unwind;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use symphonia::core::audio::SignalSpec;
 
 // Create a 50ms Buffer Size for playback, this should be short enough to ensure there aren't
 // any obvious delays when playing samples.
 const BUFFER_SIZE: usize = 50;
 
+// How often to look for changes to the output device while playing
+const DEVICE_CHECK: Duration = Duration::from_secs(1);
+
+// If the stream stops reading from the buffer for this long, the device has most likely gone
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
 pub(crate) struct CpalPlayback {
+    device: Option<String>,
+    spec: SignalSpec,
+    buffer: usize,
+
+    output: CpalStream,
+    monitor: OutputMonitor,
+    last_check: Instant,
+}
+
+struct CpalStream {
     stream: Option<Stream>,
     stream_closed: Arc<AtomicBool>,
 
@@ -36,12 +54,61 @@ struct CpalResampler {
 
 impl OpenOutputStream for CpalPlayback {
     fn open(spec: AudioSpecification) -> Result<Box<dyn AudioOutput>> {
-        let device = match catch_unwind(|| CpalConfiguration::get_device(spec.device, false)) {
-            Ok(Ok(device)) => device,
+        let device = Self::find_device(spec.device.clone())?;
+        let output = CpalStream::open(&device, spec.spec, spec.buffer)?;
+
+        // Seed the monitor with where we've started, so only later changes are reported.
+        let mut monitor = OutputMonitor::new(spec.device.clone());
+        monitor.poll(&CpalBackend);
+
+        Ok(Box::new(Self {
+            device: spec.device,
+            spec: spec.spec,
+            buffer: spec.buffer,
+
+            output,
+            monitor,
+            last_check: Instant::now(),
+        }))
+    }
+}
+
+impl CpalPlayback {
+    fn find_device(device: Option<String>) -> Result<Device> {
+        match catch_unwind(|| CpalConfiguration::get_device(device, false)) {
+            Ok(Ok(device)) => Ok(device),
             Ok(Err(e)) => bail!("Error Fetching Device: {}", e),
             Err(e) => bail!("PANIC attempting to Fetch Device! {:#?}", e),
-        };
+        }
+    }
+
+    // Rebuilds the stream if the device has changed, or fails if there's nowhere left to play.
+    fn check_device(&mut self) -> Result<()> {
+        self.last_check = Instant::now();
+
+        match self.monitor.poll(&CpalBackend) {
+            Some(OutputChange::Lost(device)) => {
+                warn!("Output Device {} has gone, stopping playback", device);
+                self.output.stop();
+                bail!("Output Device {} is no longer available", device);
+            }
+            Some(change) => {
+                info!("{}, rebuilding the playback stream", change);
+                let device = Self::find_device(self.device.clone())?;
+
+                // Whatever was still buffered for the old device is lost, but that's at most
+                // BUFFER_SIZE worth of audio.
+                self.output.stop();
+                self.output = CpalStream::open(&device, self.spec, self.buffer)?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
 
+impl CpalStream {
+    fn open(device: &Device, spec: SignalSpec, buffer: usize) -> Result<Self> {
         let config = if cfg!(target_os = "windows") {
             // Windows expects the file to be resampled to the output config, so we can't use the
             // input audio. Instead, we gotta resample.
@@ -53,14 +120,14 @@ impl OpenOutputStream for CpalPlayback {
         } else {
             // MacOS will resample inside CoreAudio, so we send the samples directly.
             cpal::StreamConfig {
-                channels: spec.spec.channels.count() as cpal::ChannelCount,
-                sample_rate: cpal::SampleRate(spec.spec.rate),
+                channels: spec.channels.count() as cpal::ChannelCount,
+                sample_rate: cpal::SampleRate(spec.rate),
                 buffer_size: cpal::BufferSize::Fixed(64),
             }
         };
 
         // Before we go any further, is the channel count of the audio correct?
-        if spec.spec.channels.count() != 2 {
+        if spec.channels.count() != 2 {
             bail!("Only stereo audio is supported");
         }
 
@@ -69,9 +136,9 @@ impl OpenOutputStream for CpalPlayback {
         let buffer_length = size * config.channels as usize;
 
         // Create the Actual Buffer
-        let buffer = SpscRb::<f32>::new(buffer_length);
-        let buffer_producer = buffer.producer();
-        let buffer_consumer = buffer.consumer();
+        let ring_buffer = SpscRb::<f32>::new(buffer_length);
+        let buffer_producer = ring_buffer.producer();
+        let buffer_consumer = ring_buffer.consumer();
 
         // Prepare a bool to close the reader if CPAL throws an error..
         let stream_closed = Arc::new(AtomicBool::new(false));
@@ -96,26 +163,26 @@ impl OpenOutputStream for CpalPlayback {
         stream.play()?;
 
         // Do we need to resample?
-        let resampler = if spec.spec.rate != config.sample_rate.0 {
+        let resampler = if spec.rate != config.sample_rate.0 {
             debug!(
                 "Creating Resampler from {} to {} using Buffer: {}",
-                spec.spec.rate, config.sample_rate.0, spec.buffer
+                spec.rate, config.sample_rate.0, buffer
             );
 
             // Create a resampler..
             let resampler = FftFixedIn::<f32>::new(
-                spec.spec.rate as usize,
+                spec.rate as usize,
                 config.sample_rate.0 as usize,
-                spec.buffer,
+                buffer,
                 2,
-                spec.spec.channels.count(),
+                spec.channels.count(),
             )?;
 
             // Create a buffer to hold samples until we can resample..
-            let input_buffer = Vec::with_capacity(spec.buffer * spec.spec.channels.count());
+            let input_buffer = Vec::with_capacity(buffer * spec.channels.count());
 
             // Allocate the Input and Output Buffers..
-            let input = vec![vec![0_f32; spec.buffer]; spec.spec.channels.count()];
+            let input = vec![vec![0_f32; buffer]; spec.channels.count()];
             let output = Resampler::output_buffer_allocate(&resampler, true);
 
             Some(CpalResampler {
@@ -129,19 +196,42 @@ impl OpenOutputStream for CpalPlayback {
             None
         };
 
-        Ok(Box::new(Self {
+        Ok(Self {
             stream: Some(stream),
             stream_closed,
 
-            buffer,
+            buffer: ring_buffer,
             buffer_producer,
 
             resampler,
-        }))
+        })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.stream_closed.load(Ordering::Relaxed)
     }
 }
 
 impl AudioOutput for CpalPlayback {
+    fn write(&mut self, samples: &[f32]) -> Result<()> {
+        // A device going away will often close the stream before we'd otherwise check, so look
+        // straight away in case there's somewhere else to play to.
+        if self.output.is_closed() || self.last_check.elapsed() > DEVICE_CHECK {
+            self.check_device()?;
+        }
+        self.output.write(samples)
+    }
+
+    fn flush(&mut self) {
+        self.output.flush();
+    }
+
+    fn stop(&mut self) {
+        self.output.stop();
+    }
+}
+
+impl CpalStream {
     fn write(&mut self, samples: &[f32]) -> Result<()> {
         if self.stream_closed.load(Ordering::Relaxed) {
             bail!("Stream has been closed");
@@ -218,11 +308,16 @@ impl AudioOutput for CpalPlayback {
         };
 
         let mut position = 0;
-        while let Some(written) = self
-            .buffer_producer
-            .write_blocking(out_samples.split_at(position).1)
-        {
-            position += written;
+        while position < out_samples.len() {
+            let remaining = out_samples.split_at(position).1;
+            match self
+                .buffer_producer
+                .write_blocking_timeout(remaining, WRITE_TIMEOUT)
+            {
+                Ok(Some(written)) => position += written,
+                Ok(None) => break,
+                Err(_) => bail!("Playback Stream has stopped reading samples"),
+            }
         }
 
         Ok(())
@@ -243,9 +338,10 @@ impl AudioOutput for CpalPlayback {
 
         // Make sure the playback buffer is empty, to prevent premature pausing at
         // the end of playback
+        let started = Instant::now();
         while !self.buffer.is_empty() {
-            // Make sure the Stream hasn't closed while handling the buffer..
-            if self.stream_closed.load(Ordering::Relaxed) {
+            // Make sure the Stream hasn't closed (or stalled) while handling the buffer..
+            if self.is_closed() || started.elapsed() > WRITE_TIMEOUT {
                 return;
             }
 
@@ -0,0 +1,105 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+const SAMPLE_RATE: f32 = 48000.;
+
+/// A 12dB/octave (Butterworth) high-pass filter for interleaved stereo samples. The position in
+/// the frame is carried between calls, so it doesn't matter where a block of samples ends.
+pub struct LowCut {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+
+    // The last two inputs and outputs of each channel
+    history: [[f32; 4]; 2],
+    channel: usize,
+}
+
+impl LowCut {
+    pub fn new(frequency: u8) -> Self {
+        // Coefficients from the Audio EQ Cookbook
+        let omega = 2. * PI * frequency as f32 / SAMPLE_RATE;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2. * FRAC_1_SQRT_2);
+        let a0 = 1. + alpha;
+
+        Self {
+            b0: (1. + cos) / 2. / a0,
+            b1: -(1. + cos) / a0,
+            b2: (1. + cos) / 2. / a0,
+            a1: -2. * cos / a0,
+            a2: (1. - alpha) / a0,
+
+            history: [[0.; 4]; 2],
+            channel: 0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let [x1, x2, y1, y2] = self.history[self.channel];
+            let x0 = *sample;
+            let y0 = self.b0 * x0 + self.b1 * x1 + self.b2 * x2 - self.a1 * y1 - self.a2 * y2;
+
+            self.history[self.channel] = [x0, x1, y0, y1];
+            self.channel = (self.channel + 1) % 2;
+            *sample = y0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The peak level of one channel over the last half of the samples, once the filter settles
+    fn settled_peak(samples: &[f32], channel: usize) -> f32 {
+        samples[samples.len() / 2..]
+            .iter()
+            .skip(channel)
+            .step_by(2)
+            .fold(0., |peak, sample| peak.max(sample.abs()))
+    }
+
+    // A second of a sine wave on the left channel, leaving the right silent
+    fn left_sine(frequency: f32) -> Vec<f32> {
+        (0..SAMPLE_RATE as usize)
+            .flat_map(|frame| {
+                let phase = 2. * PI * frequency * frame as f32 / SAMPLE_RATE;
+                [phase.sin(), 0.]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn low_frequencies_are_removed() {
+        let mut samples = left_sine(10.);
+        LowCut::new(200).process(&mut samples);
+        assert!(settled_peak(&samples, 0) < 0.01);
+
+        let mut samples = left_sine(2000.);
+        LowCut::new(200).process(&mut samples);
+        assert!(settled_peak(&samples, 0) > 0.99);
+    }
+
+    #[test]
+    fn channels_are_filtered_separately() {
+        let mut samples = left_sine(2000.);
+        LowCut::new(80).process(&mut samples);
+        assert_eq!(settled_peak(&samples, 1), 0.);
+    }
+
+    #[test]
+    fn blocks_can_end_part_way_through_a_frame() {
+        let mut whole = left_sine(500.);
+        let mut split = whole.clone();
+        LowCut::new(120).process(&mut whole);
+
+        let mut filter = LowCut::new(120);
+        let (first, second) = split.split_at_mut(1001);
+        filter.process(first);
+        filter.process(second);
+        assert_eq!(whole, split);
+    }
+}
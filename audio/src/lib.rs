@@ -1,9 +1,15 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
 mod audio;
+pub mod cache;
+pub mod convert;
+mod filter;
+pub mod markers;
+pub mod monitor;
 pub mod player;
 pub mod recorder;
 mod ringbuffer;
+pub mod server;
 
 #[cfg(target_os = "linux")]
 mod pulse;
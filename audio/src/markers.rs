@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use log::debug;
+
+/// A cue point or region embedded in a sample, positions are a percentage of the sample length
+/// to match how track start and stop positions are stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioMarker {
+    pub label: Option<String>,
+    pub start_pct: f64,
+    pub end_pct: Option<f64>,
+}
+
+impl AudioMarker {
+    pub fn is_region(&self) -> bool {
+        self.end_pct.is_some()
+    }
+}
+
+/// Reads any markers embedded in the file. Markers are a nicety, so if the file doesn't have
+/// any, or they can't be understood, we simply return nothing.
+pub fn read_markers(path: &Path) -> Vec<AudioMarker> {
+    let is_wav = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false);
+
+    if !is_wav {
+        return vec![];
+    }
+
+    match read_wav_markers(path) {
+        Ok(markers) => markers,
+        Err(error) => {
+            debug!("Ignoring markers in {:?}: {}", path, error);
+            vec![]
+        }
+    }
+}
+
+#[derive(Default)]
+struct CuePoint {
+    position: u32,
+    label: Option<String>,
+    length: Option<u32>,
+}
+
+fn read_wav_markers(path: &Path) -> Result<Vec<AudioMarker>> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        bail!("Not a RIFF WAVE file");
+    }
+
+    let mut block_align: Option<u32> = None;
+    let mut data_length: Option<u32> = None;
+    let mut cues: HashMap<u32, CuePoint> = HashMap::new();
+
+    // Walk the chunks, we only read the small ones we care about and skip over the rest (most
+    // importantly, the audio data itself).
+    loop {
+        let mut chunk_header = [0; 8];
+        if reader.read_exact(&mut chunk_header).is_err() {
+            break;
+        }
+        let id = &chunk_header[0..4];
+        let length = read_u32(&chunk_header, 4)?;
+
+        match id {
+            b"fmt " => {
+                let chunk = read_chunk(&mut reader, length)?;
+                block_align = Some(read_u16(&chunk, 12)? as u32);
+            }
+            b"data" => {
+                data_length = Some(length);
+                reader.seek(SeekFrom::Current(length as i64))?;
+            }
+            b"cue " => {
+                let chunk = read_chunk(&mut reader, length)?;
+                let count = read_u32(&chunk, 0)? as usize;
+                for index in 0..count {
+                    let offset = 4 + (index * 24);
+                    let cue_id = read_u32(&chunk, offset)?;
+                    let position = read_u32(&chunk, offset + 20)?;
+                    cues.entry(cue_id).or_default().position = position;
+                }
+            }
+            b"LIST" => {
+                let chunk = read_chunk(&mut reader, length)?;
+                if chunk.get(0..4) == Some(b"adtl") {
+                    parse_associated_data(&chunk[4..], &mut cues)?;
+                }
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64))?;
+            }
+        }
+
+        // Chunks are word aligned..
+        if length % 2 == 1 {
+            reader.seek(SeekFrom::Current(1))?;
+        }
+    }
+
+    if cues.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let frames = match (block_align, data_length) {
+        (Some(align), Some(length)) if align > 0 => (length / align) as f64,
+        _ => bail!("Unable to determine sample length"),
+    };
+    if frames == 0. {
+        bail!("Sample has no audio");
+    }
+
+    let mut cues: Vec<CuePoint> = cues.into_values().collect();
+    cues.sort_by_key(|cue| cue.position);
+
+    let mut markers = vec![];
+    for cue in cues {
+        let start_pct = (cue.position as f64 / frames) * 100.;
+        if start_pct > 100. {
+            debug!("Ignoring cue point beyond the end of the sample");
+            continue;
+        }
+
+        let end_pct = cue.length.filter(|length| *length > 0).map(|length| {
+            (((cue.position as u64 + length as u64) as f64 / frames) * 100.).min(100.)
+        });
+
+        markers.push(AudioMarker {
+            label: cue.label,
+            start_pct,
+            end_pct,
+        });
+    }
+    Ok(markers)
+}
+
+fn parse_associated_data(mut data: &[u8], cues: &mut HashMap<u32, CuePoint>) -> Result<()> {
+    while data.len() >= 8 {
+        let id = &data[0..4];
+        let length = read_u32(data, 4)? as usize;
+        let chunk = match data.get(8..8 + length) {
+            Some(chunk) => chunk,
+            None => bail!("Associated data chunk is truncated"),
+        };
+
+        match id {
+            b"labl" => {
+                let cue_id = read_u32(chunk, 0)?;
+                let label = read_string(&chunk[4..]);
+                if !label.is_empty() {
+                    cues.entry(cue_id).or_default().label = Some(label);
+                }
+            }
+            b"ltxt" => {
+                // Labelled text is how a region's length is defined
+                let cue_id = read_u32(chunk, 0)?;
+                let length = read_u32(chunk, 4)?;
+                cues.entry(cue_id).or_default().length = Some(length);
+            }
+            _ => {}
+        }
+
+        let padded = length + (length % 2);
+        data = data.get(8 + padded..).unwrap_or_default();
+    }
+    Ok(())
+}
+
+fn read_chunk<R: Read>(reader: &mut R, length: u32) -> Result<Vec<u8>> {
+    // Metadata chunks are small, anything this large is likely garbage.
+    if length > 1024 * 1024 {
+        bail!("Chunk too large");
+    }
+    let mut chunk = vec![0; length as usize];
+    reader.read_exact(&mut chunk)?;
+    Ok(chunk)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes(bytes.try_into()?)),
+        None => bail!("Chunk is truncated"),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_le_bytes(bytes.try_into()?)),
+        None => bail!("Chunk is truncated"),
+    }
+}
+
+fn read_string(data: &[u8]) -> String {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).trim().to_string()
+}
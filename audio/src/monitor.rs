@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+
+/*
+ * Output devices come and go while we're running (headsets get unplugged, docks get
+ * disconnected, the default output gets changed in the OS settings), this keeps track of which
+ * device a stream should be playing to, and reports when that changes so the stream can be
+ * rebuilt or stopped, rather than writing to a device which is no longer there.
+ */
+
+pub trait OutputBackend {
+    fn outputs(&self) -> Vec<String>;
+    fn default_output(&self) -> Option<String>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputState {
+    Connected(String),
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputChange {
+    /// The device has gone, anything playing to it needs to stop.
+    Lost(String),
+
+    /// A device is available again after being lost.
+    Reconnected(String),
+
+    /// A different device should now be used (normally the default output changing).
+    Moved { from: String, to: String },
+}
+
+impl Display for OutputChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputChange::Lost(device) => write!(f, "Output Device {} was lost", device),
+            OutputChange::Reconnected(device) => {
+                write!(f, "Output Device {} was reconnected", device)
+            }
+            OutputChange::Moved { from, to } => {
+                write!(f, "Output Device changed from {} to {}", from, to)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OutputMonitor {
+    // The device to follow, None follows the default output
+    device: Option<String>,
+    state: Option<OutputState>,
+}
+
+impl OutputMonitor {
+    pub fn new(device: Option<String>) -> Self {
+        Self {
+            device,
+            state: None,
+        }
+    }
+
+    /// The last known state, None until the first check.
+    pub fn state(&self) -> Option<&OutputState> {
+        self.state.as_ref()
+    }
+
+    /// Looks for the device in the backend, reporting any change since the last check.
+    pub fn poll(&mut self, backend: &impl OutputBackend) -> Option<OutputChange> {
+        let current = match &self.device {
+            Some(device) => backend.outputs().into_iter().find(|name| name == device),
+            None => backend.default_output(),
+        };
+        self.update(current)
+    }
+
+    /// Records the device currently in use (for callers which find it themselves), reporting
+    /// any change since the last update. The first update never reports a change.
+    pub fn update(&mut self, current: Option<String>) -> Option<OutputChange> {
+        let change = match (&self.state, &current) {
+            (Some(OutputState::Connected(from)), Some(to)) if from != to => {
+                Some(OutputChange::Moved {
+                    from: from.clone(),
+                    to: to.clone(),
+                })
+            }
+            (Some(OutputState::Connected(from)), None) => Some(OutputChange::Lost(from.clone())),
+            (Some(OutputState::Missing), Some(to)) => Some(OutputChange::Reconnected(to.clone())),
+            _ => None,
+        };
+
+        self.state = Some(match current {
+            Some(device) => OutputState::Connected(device),
+            None => OutputState::Missing,
+        });
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct SimulatedBackend {
+        outputs: Vec<String>,
+        default: Option<String>,
+    }
+
+    impl SimulatedBackend {
+        fn plug(&mut self, device: &str) {
+            self.outputs.push(device.to_string());
+        }
+
+        fn unplug(&mut self, device: &str) {
+            self.outputs.retain(|output| output != device);
+            if self.default.as_deref() == Some(device) {
+                self.default = None;
+            }
+        }
+    }
+
+    impl OutputBackend for SimulatedBackend {
+        fn outputs(&self) -> Vec<String> {
+            self.outputs.clone()
+        }
+
+        fn default_output(&self) -> Option<String> {
+            self.default.clone()
+        }
+    }
+
+    const SAMPLE: &str = "WASAPI*Sample (TC-Helicon GoXLR)";
+    const SPEAKERS: &str = "WASAPI*Speakers (Realtek Audio)";
+
+    #[test]
+    fn named_device_is_lost_and_reconnected() {
+        let mut backend = SimulatedBackend::default();
+        backend.plug(SPEAKERS);
+        backend.plug(SAMPLE);
+
+        let mut monitor = OutputMonitor::new(Some(SAMPLE.to_string()));
+        assert_eq!(monitor.poll(&backend), None);
+        assert_eq!(
+            monitor.state(),
+            Some(&OutputState::Connected(SAMPLE.to_string()))
+        );
+
+        backend.unplug(SAMPLE);
+        assert_eq!(
+            monitor.poll(&backend),
+            Some(OutputChange::Lost(SAMPLE.to_string()))
+        );
+        assert_eq!(monitor.poll(&backend), None);
+        assert_eq!(monitor.state(), Some(&OutputState::Missing));
+
+        // Other devices coming and going don't matter
+        backend.unplug(SPEAKERS);
+        assert_eq!(monitor.poll(&backend), None);
+
+        backend.plug(SAMPLE);
+        assert_eq!(
+            monitor.poll(&backend),
+            Some(OutputChange::Reconnected(SAMPLE.to_string()))
+        );
+        assert_eq!(monitor.poll(&backend), None);
+    }
+
+    #[test]
+    fn default_device_is_followed() {
+        let mut backend = SimulatedBackend::default();
+        backend.plug(SPEAKERS);
+        backend.plug(SAMPLE);
+        backend.default = Some(SPEAKERS.to_string());
+
+        let mut monitor = OutputMonitor::new(None);
+        assert_eq!(monitor.poll(&backend), None);
+
+        backend.default = Some(SAMPLE.to_string());
+        assert_eq!(
+            monitor.poll(&backend),
+            Some(OutputChange::Moved {
+                from: SPEAKERS.to_string(),
+                to: SAMPLE.to_string()
+            })
+        );
+
+        // With no default at all there's nothing to play to
+        backend.unplug(SAMPLE);
+        assert_eq!(
+            monitor.poll(&backend),
+            Some(OutputChange::Lost(SAMPLE.to_string()))
+        );
+
+        backend.default = Some(SPEAKERS.to_string());
+        assert_eq!(
+            monitor.poll(&backend),
+            Some(OutputChange::Reconnected(SPEAKERS.to_string()))
+        );
+    }
+
+    #[test]
+    fn missing_on_first_check_is_not_a_change() {
+        let mut monitor = OutputMonitor::new(Some(SAMPLE.to_string()));
+        assert_eq!(monitor.poll(&SimulatedBackend::default()), None);
+        assert_eq!(monitor.state(), Some(&OutputState::Missing));
+
+        assert_eq!(
+            monitor.update(Some(SAMPLE.to_string())),
+            Some(OutputChange::Reconnected(SAMPLE.to_string()))
+        );
+    }
+}
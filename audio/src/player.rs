@@ -8,8 +8,10 @@ use std::io::ErrorKind::UnexpectedEof;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::audio::{get_output, AudioSpecification};
+use crate::markers::{read_markers, AudioMarker};
 use crate::AtomicF64;
 use symphonia::core::audio::{Layout, SampleBuffer, SignalSpec};
 use symphonia::core::errors::Error;
@@ -18,6 +20,9 @@ use symphonia::core::io::MediaSourceStream;
 use symphonia::core::probe::{Hint, ProbeResult};
 use symphonia::default::get_codecs;
 
+// The loudness (in LUFS) samples are normalised to when they're added
+pub const DEFAULT_LOUDNESS_TARGET: f64 = -23.0;
+
 pub struct Player {
     file: PathBuf,
     probe: ProbeResult,
@@ -40,7 +45,10 @@ pub struct Player {
 
     // Used for processing Gain..
     process_only: bool,
+    loudness_target: f64,
     normalized_gain: Arc<AtomicF64>,
+    markers: Arc<Mutex<Vec<AudioMarker>>>,
+    duration: Arc<Mutex<Option<Duration>>>,
 }
 
 impl Player {
@@ -77,10 +85,23 @@ impl Player {
             gain,
 
             process_only: false,
+            loudness_target: DEFAULT_LOUDNESS_TARGET,
             normalized_gain: Arc::new(AtomicF64::new(1.0)),
+            markers: Arc::new(Mutex::new(vec![])),
+            duration: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// The length of a file according to its headers, this doesn't decode the file so it's
+    /// quick, but not every format declares it.
+    pub fn get_duration(file: &PathBuf) -> Option<Duration> {
+        let probe = Player::load_file(file).ok()?;
+        let track = probe.format.default_track()?;
+        let frames = track.codec_params.n_frames?;
+        let rate = track.codec_params.sample_rate?;
+        Some(frames_to_duration(frames, rate))
+    }
+
     fn load_file(file: &PathBuf) -> symphonia::core::errors::Result<ProbeResult> {
         // Use the file extension to get a type hint..
         let mut hint = Hint::new();
@@ -99,6 +120,21 @@ impl Player {
         symphonia::default::get_probe().format(&hint, stream, &format_options, &metadata_options)
     }
 
+    /// Decodes from a different file (such as a conversion of this one) from now on. Markers
+    /// are still read from the original, conversions don't carry them across.
+    pub fn decode_from(&mut self, file: &PathBuf) -> Result<()> {
+        match Player::load_file(file) {
+            Ok(probe) => self.probe = probe,
+            Err(_) => bail!("Unable to Probe Audio File"),
+        }
+        Ok(())
+    }
+
+    /// Sets the loudness (in LUFS) that calculate_gain will produce a gain for.
+    pub fn set_loudness_target(&mut self, target: f64) {
+        self.loudness_target = target;
+    }
+
     pub fn calculate_gain(&mut self) {
         self.process_only = true;
 
@@ -106,7 +142,11 @@ impl Player {
         if let Err(error) = result {
             let mut res = self.error.lock().unwrap();
             *res = Some(error.to_string());
+            return;
         }
+
+        // Symphonia skips over cue chunks, so we pick them out here while we're processing.
+        *self.markers.lock().unwrap() = read_markers(&self.file);
     }
 
     pub fn play_loop(&mut self) -> Result<()> {
@@ -212,6 +252,8 @@ impl Player {
         };
 
         let mut mono_playback = false;
+        let mut frames_decoded: u64 = 0;
+        let mut output_error = None;
 
         // Loop over the input file..
         let result = 'main: loop {
@@ -256,6 +298,8 @@ impl Player {
                     }
 
                     if let Some(ref mut buf) = sample_buffer {
+                        frames_decoded += decoded.frames() as u64;
+
                         // Grab out the samples..
                         buf.copy_interleaved_ref(decoded);
                         let mut samples = buf.samples().to_vec();
@@ -270,6 +314,11 @@ impl Player {
                         }
 
                         if let Some(ref mut ebu_r128) = ebu_r128 {
+                            // Calculations can be cancelled, the result will be thrown away
+                            if self.force_stop.load(Ordering::Relaxed) {
+                                break 'main Ok(());
+                            }
+
                             ebu_r128.add_frames_f32(samples.as_slice())?;
                             samples_processed += samples.len() as u64;
 
@@ -319,7 +368,11 @@ impl Player {
 
                         // Flush the samples to the Audio Stream..
                         if let Some(audio_output) = &mut audio_output {
-                            audio_output.write(&samples).unwrap()
+                            // Normally the device has gone away, there's nowhere left to play
+                            if let Err(e) = audio_output.write(&samples) {
+                                output_error.replace(e);
+                                break 'main Ok(());
+                            }
                         }
 
                         samples_processed += samples.len() as u64;
@@ -362,7 +415,7 @@ impl Player {
                 Err(err) => break Err(err),
             }
         };
-        if !self.force_stop.load(Ordering::Relaxed) {
+        if !self.force_stop.load(Ordering::Relaxed) && output_error.is_none() {
             if let Some(ref mut audio_output) = audio_output {
                 // We should always flush the last samples, unless forced to stop
                 audio_output.flush();
@@ -374,6 +427,11 @@ impl Player {
             audio_output.stop();
         }
 
+        if let Some(error) = output_error {
+            *self.error.lock().unwrap() = Some(error.to_string());
+            bail!("Playback Stopped: {}", error);
+        }
+
         if let Some(ebu_r128) = ebu_r128 {
             // Calculate Gain..
             let mut loudness = ebu_r128.loudness_global()?;
@@ -386,12 +444,16 @@ impl Player {
                 debug!("Unable to Obtain loudness in Mode M, Setting Default..");
                 self.normalized_gain.store(1.0, Ordering::Relaxed);
             } else {
-                let target = -23.0;
-                let gain_db = target - loudness;
+                let gain_db = self.loudness_target - loudness;
                 let value = f64::powf(10., gain_db / 20.);
 
                 self.normalized_gain.store(value, Ordering::Relaxed);
             }
+
+            // Everything has been decoded, so this is accurate even if the headers aren't
+            if let Some(rate) = sample_rate {
+                *self.duration.lock().unwrap() = Some(frames_to_duration(frames_decoded, rate));
+            }
         }
         decoder.finalize();
 
@@ -434,6 +496,8 @@ impl Player {
             progress: self.progress.clone(),
             error: self.error.clone(),
             calculated_gain: self.normalized_gain.clone(),
+            markers: self.markers.clone(),
+            duration: self.duration.clone(),
         }
     }
 }
@@ -455,4 +519,10 @@ pub struct PlayerState {
 
     // Specifically for calculating the gain..
     pub calculated_gain: Arc<AtomicF64>,
+    pub markers: Arc<Mutex<Vec<AudioMarker>>>,
+    pub duration: Arc<Mutex<Option<Duration>>>,
+}
+
+fn frames_to_duration(frames: u64, rate: u32) -> Duration {
+    Duration::from_secs_f64(frames as f64 / rate as f64)
 }
@@ -1,3 +1,4 @@
 pub(crate) mod pulse_config;
 pub(crate) mod pulse_playback;
 pub(crate) mod pulse_record;
+pub(crate) mod pulse_server;
@@ -2,12 +2,14 @@ use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 
+use anyhow::{anyhow, bail, Result};
 use libpulse_binding as pulse;
 use libpulse_binding::callbacks::ListResult;
 use libpulse_binding::context::introspect::{SinkInfo, SourceInfo};
 use libpulse_binding::context::{Context, FlagSet, State};
 use libpulse_binding::mainloop::standard::{IterateResult, Mainloop};
 use libpulse_binding::proplist::Proplist;
+use log::debug;
 
 pub(crate) struct PulseAudioConfiguration;
 pub struct PulseRuntime {
@@ -16,33 +18,35 @@ pub struct PulseRuntime {
 }
 
 impl PulseRuntime {
-    fn connect() -> Self {
+    pub(crate) fn connect() -> Result<Self> {
         // Connect to the PulseAudio Server..
         let app_name: &str = env!("CARGO_PKG_NAME");
 
-        let mut proplist = Proplist::new().unwrap();
+        let mut proplist = Proplist::new().ok_or_else(|| anyhow!("Unable to create Proplist"))?;
         proplist
             .set_str(pulse::proplist::properties::APPLICATION_NAME, app_name)
-            .unwrap();
+            .map_err(|_| anyhow!("Unable to set Application Name"))?;
 
         let main_loop = Rc::new(RefCell::new(
-            Mainloop::new().expect("Failed to create MainLoop"),
+            Mainloop::new().ok_or_else(|| anyhow!("Failed to create MainLoop"))?,
         ));
         let context = Rc::new(RefCell::new(
             Context::new_with_proplist(main_loop.borrow().deref(), app_name, &proplist)
-                .expect("Unable to create context"),
+                .ok_or_else(|| anyhow!("Unable to create context"))?,
         ));
 
         context
             .borrow_mut()
             .connect(None, FlagSet::NOFLAGS, None)
-            .expect("Failed to connect context");
+            .map_err(|e| anyhow!("Failed to connect context: {}", e))?;
 
+        // The server may be restarting, so rather than waiting on it, fail and let the caller
+        // decide when to try again.
         loop {
             match main_loop.borrow_mut().iterate(true) {
                 IterateResult::Success(_) => {}
                 IterateResult::Quit(_) | IterateResult::Err(_) => {
-                    panic!("Failed to Connect to Pulse Audio!");
+                    bail!("Failed to Connect to Pulse Audio!");
                 }
             }
 
@@ -54,13 +58,32 @@ impl PulseRuntime {
                 State::Ready => {
                     break;
                 }
-                State::Failed => {}
-                State::Terminated => {}
+                State::Failed | State::Terminated => {
+                    bail!("Pulse Audio refused the Connection");
+                }
             }
         }
 
         // At this point, we're connected and ready to go :)
-        PulseRuntime { main_loop, context }
+        Ok(PulseRuntime { main_loop, context })
+    }
+
+    /// Runs the main loop without blocking, dispatching any callbacks. Returns false if the
+    /// main loop has failed.
+    pub(crate) fn iterate(&self) -> bool {
+        !matches!(
+            self.main_loop.borrow_mut().iterate(false),
+            IterateResult::Quit(_) | IterateResult::Err(_)
+        )
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        matches!(self.context.borrow().get_state(), State::Ready)
+    }
+
+    /// Calls back whenever the state of the connection changes, from inside iterate.
+    pub(crate) fn on_state_change(&self, callback: Box<dyn FnMut() + 'static>) {
+        self.context.borrow_mut().set_state_callback(Some(callback));
     }
 
     fn disconnect(&self) {
@@ -71,7 +94,9 @@ impl PulseRuntime {
 impl Drop for PulseRuntime {
     fn drop(&mut self) {
         // We need to disconnect our context before we go out of scope, otherwise we'll
-        // segfault when libpulse tries to drop.
+        // segfault when libpulse tries to drop. The callback goes first, it has nothing to
+        // report on while we tear down.
+        self.context.borrow_mut().set_state_callback(None);
         self.context.borrow_mut().disconnect();
     }
 }
@@ -82,7 +107,13 @@ impl PulseAudioConfiguration {
         let wrapped = Rc::new(RefCell::new(found));
         let insider = wrapped.clone();
 
-        let pulse = PulseRuntime::connect();
+        let pulse = match PulseRuntime::connect() {
+            Ok(pulse) => pulse,
+            Err(e) => {
+                debug!("Unable to list outputs: {}", e);
+                return vec![];
+            }
+        };
 
         let op = {
             pulse.context.borrow_mut().introspect().get_sink_info_list(
@@ -107,7 +138,13 @@ impl PulseAudioConfiguration {
     }
 
     pub(crate) fn get_inputs() -> Vec<String> {
-        let pulse = PulseRuntime::connect();
+        let pulse = match PulseRuntime::connect() {
+            Ok(pulse) => pulse,
+            Err(e) => {
+                debug!("Unable to list inputs: {}", e);
+                return vec![];
+            }
+        };
 
         // Basically identical to the above, except getting the Sources..
         let found: Vec<String> = vec![];
@@ -0,0 +1,54 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+
+use log::{debug, warn};
+
+use crate::pulse::pulse_config::PulseRuntime;
+use crate::server::{Backoff, ServerState};
+
+// How often the connection is checked on while it's up
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Holds a connection to the server open, and reconnects (backing off while the server is down)
+/// whenever it's lost. Runs until the ServerWatch is dropped.
+pub(crate) fn watch(state: Arc<ServerState>) {
+    let mut backoff = Backoff::default();
+    let mut was_connected = false;
+
+    while !state.stop.load(Ordering::Relaxed) {
+        let runtime = match PulseRuntime::connect() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                let delay = backoff.next_delay();
+                if was_connected {
+                    debug!("Sound Server unavailable, retrying in {:?}: {}", delay, e);
+                }
+                sleep(delay);
+                continue;
+            }
+        };
+
+        backoff.reset();
+        was_connected = true;
+        state.set_connected();
+
+        let changed = Rc::new(Cell::new(false));
+        let inner_changed = changed.clone();
+        runtime.on_state_change(Box::new(move || inner_changed.set(true)));
+
+        while !state.stop.load(Ordering::Relaxed) {
+            // The callback fires from inside iterate, only then is the state worth checking
+            if !runtime.iterate() || (changed.replace(false) && !runtime.is_ready()) {
+                warn!("Lost connection to the Sound Server");
+                state.set_disconnected();
+                break;
+            }
+            sleep(POLL_INTERVAL);
+        }
+    }
+    debug!("Sound Server Watcher Terminated");
+}
@@ -18,6 +18,7 @@ use rb::{Producer, RbConsumer, RbProducer, SpscRb, RB};
 use symphonia::core::audio::{Layout, SignalSpec};
 
 use crate::audio::{get_input, AudioInput, AudioSpecification};
+use crate::filter::LowCut;
 use crate::ringbuffer::RingBuffer;
 use crate::{get_audio_inputs, AtomicF64};
 
@@ -25,9 +26,13 @@ static NEXT_ID: AtomicU32 = AtomicU32::new(0);
 static READ_TIMEOUT: Duration = Duration::from_millis(100);
 static CHECK_PERIOD: Duration = Duration::from_secs(60 * 15);
 
+// A 24bit sample can't quite reach 1.0, so anything this close is treated as clipping
+static CLIP_THRESHOLD: f32 = 0.999;
+
 pub struct BufferedRecorder {
     devices: Vec<Regex>,
     producers: Mutex<Vec<RingProducer>>,
+    buffer_millis: usize,
     buffer_size: usize,
     buffer: RingBuffer<f32>,
     stop: Arc<AtomicBool>,
@@ -43,6 +48,21 @@ pub struct RingProducer {
 pub struct RecorderState {
     pub stop: Arc<AtomicBool>,
     pub gain: Arc<AtomicF64>,
+
+    // The running (linear) peak of the recording, and whether it's clipped
+    pub peak: Arc<AtomicF64>,
+    pub clipped: Arc<AtomicBool>,
+}
+
+impl RecorderState {
+    /// Returns the running peak in dBFS, or None if nothing has been heard yet.
+    pub fn get_peak_db(&self) -> Option<f64> {
+        let peak = self.peak.load(Ordering::Relaxed);
+        if peak <= 0. {
+            return None;
+        }
+        Some(20. * peak.log10())
+    }
 }
 
 impl Debug for BufferedRecorder {
@@ -92,6 +112,7 @@ impl BufferedRecorder {
             devices: regex,
             producers: Mutex::new(vec![]),
 
+            buffer_millis,
             buffer_size,
             //buffer: Mutex::new(BoundedVecDeque::new(buffer_size)),
             buffer: RingBuffer::new(buffer_size),
@@ -220,7 +241,9 @@ impl BufferedRecorder {
             .retain(|x| x.id != producer_id);
     }
 
-    pub fn record(&self, path: &Path, state: RecorderState) -> Result<()> {
+    /// Records until the state is stopped, with an optional Low Cut (in Hz) applied to
+    /// everything written.
+    pub fn record(&self, path: &Path, state: RecorderState, low_cut: Option<u8>) -> Result<()> {
         if !self.is_ready() {
             warn!("Possible problem locating the Sampler Output, available devices:");
             get_audio_inputs().iter().for_each(|name| info!("{}", name));
@@ -245,7 +268,13 @@ impl BufferedRecorder {
         });
 
         // Grab the contents of the buffer, and push it into a simple vec
-        let pre_samples = self.get_samples_from_buffer();
+        let mut pre_samples = self.get_samples_from_buffer();
+
+        // The filter runs over the pre-buffer first, so there's no step when the live audio starts
+        let mut filter = low_cut.map(LowCut::new);
+        if let Some(filter) = &mut filter {
+            filter.process(&mut pre_samples);
+        }
 
         // Get the read buffer to pull a quarter of a second at a time..
         let mut read_buffer: [f32; 24000] = [0.0; 24000];
@@ -278,6 +307,7 @@ impl BufferedRecorder {
                 &mut ebu_rec_r128,
                 writing,
                 &mut writer,
+                &state,
             ) {
                 Ok(result) => writing = result,
                 Err(error) => {
@@ -295,13 +325,17 @@ impl BufferedRecorder {
                 ring_buf_consumer.read_blocking_timeout(&mut read_buffer, READ_TIMEOUT)
             {
                 // Read these out into a vec..
-                let samples: Vec<f32> = Vec::from(&read_buffer[0..samples]);
+                let mut samples: Vec<f32> = Vec::from(&read_buffer[0..samples]);
+                if let Some(filter) = &mut filter {
+                    filter.process(&mut samples);
+                }
                 match self.handle_samples(
                     samples,
                     &mut ebu_prep_r128,
                     &mut ebu_rec_r128,
                     writing,
                     &mut writer,
+                    &state,
                 ) {
                     Ok(result) => writing = result,
                     Err(error) => {
@@ -357,6 +391,27 @@ impl BufferedRecorder {
         Ok(())
     }
 
+    /// Writes what's currently in the pre-buffer to a WAV, returning how long it is. Only the
+    /// configured duration is written, even if the buffer was padded out to a larger minimum.
+    pub fn save_buffer(&self, path: &Path, low_cut: Option<u8>) -> Result<Duration> {
+        if self.buffer_millis == 0 {
+            bail!("The Sampler Pre-Buffer is disabled");
+        }
+        if !self.is_ready() {
+            bail!("The Sampler is not ready (possibly missing device?)");
+        }
+
+        let mut samples = self.get_samples_from_buffer();
+        let limit = (48 * 2) * self.buffer_millis;
+        let start = samples.len().saturating_sub(limit);
+
+        // Filtered before trimming, so the filter has settled by the time the replay starts
+        if let Some(frequency) = low_cut {
+            LowCut::new(frequency).process(&mut samples);
+        }
+        write_buffer(path, &samples[start..])
+    }
+
     fn get_samples_from_buffer(&self) -> Vec<f32> {
         if self.buffer_size > 0 {
             return self.buffer.read_buffer().unwrap_or_else(|e| {
@@ -374,9 +429,22 @@ impl BufferedRecorder {
         ebu_rec_r128: &mut EbuR128,
         writing: bool,
         writer: &mut WavWriter<BufWriter<File>>,
+        state: &RecorderState,
     ) -> Result<bool> {
         let mut recording_started = writing;
 
+        // Track the peak of everything we hear, so the level can be seen before the recording
+        // starts (if it's too quiet, it may never start).
+        let peak = samples
+            .iter()
+            .fold(0., |peak: f32, sample| peak.max(sample.abs()));
+        if peak as f64 > state.peak.load(Ordering::Relaxed) {
+            state.peak.store(peak as f64, Ordering::Relaxed);
+        }
+        if peak >= CLIP_THRESHOLD {
+            state.clipped.store(true, Ordering::Relaxed);
+        }
+
         // Split into 50ms chunks
         for slice in samples.chunks(4800) {
             if !recording_started {
@@ -444,6 +512,31 @@ impl BufferedRecorder {
     }
 }
 
+/// Writes interleaved stereo samples to a 24bit WAV. The buffer may have been trimmed part way
+/// through a frame, so a leading half frame is dropped rather than swapping the channels.
+fn write_buffer(path: &Path, samples: &[f32]) -> Result<Duration> {
+    let samples = &samples[samples.len() % 2..];
+    if samples.is_empty() {
+        bail!("Nothing has been recorded into the buffer yet");
+    }
+
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 24,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for sample in samples {
+        // Scaled to 24bit as when recording, but clamped so a full scale sample still fits
+        writer.write_sample((sample.clamp(-1., 1.) * 8388607.0) as i32)?;
+    }
+    writer.finalize()?;
+
+    let frames = (samples.len() / 2) as u64;
+    Ok(Duration::from_millis(frames * 1000 / 48000))
+}
+
 impl Drop for BufferedRecorder {
     fn drop(&mut self) {
         debug!("Recorder Dropped, stopping thread..");
@@ -453,3 +546,49 @@ impl Drop for BufferedRecorder {
         self.stop.store(true, Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_back(path: &Path) -> Vec<i32> {
+        let mut reader = hound::WavReader::open(path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().bits_per_sample, 24);
+        reader
+            .samples::<i32>()
+            .map(|sample| sample.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn buffers_are_written_as_whole_frames() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("replay.wav");
+
+        // The first sample is the right half of a frame which was trimmed away
+        let samples = [0.5, 1.5, -1.5, 0.25, -0.25];
+        write_buffer(&path, &samples).unwrap();
+        assert_eq!(read_back(&path), vec![8388607, -8388607, 2097151, -2097151]);
+    }
+
+    #[test]
+    fn durations_are_measured_in_frames() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("replay.wav");
+
+        let samples = vec![0.; 48 * 2 * 250];
+        let duration = write_buffer(&path, &samples).unwrap();
+        assert_eq!(duration, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn empty_buffers_are_refused() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("replay.wav");
+
+        assert!(write_buffer(&path, &[]).is_err());
+        assert!(write_buffer(&path, &[0.5]).is_err());
+        assert!(!path.exists());
+    }
+}
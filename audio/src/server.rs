@@ -0,0 +1,203 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/*
+ * On Linux everything goes through a sound server (PulseAudio, or PipeWire pretending to be it),
+ * which can be restarted underneath us (package upgrades, crashes). When that happens every
+ * stream we're holding is dead, so this keeps an eye on the server and reports when it goes and
+ * comes back, so the streams can be torn down and rebuilt. Other platforms don't have a server
+ * which can disappear like this, so are always connected.
+ */
+
+const BACKOFF_MIN: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServerChange {
+    /// The server has gone, all existing streams are dead.
+    Lost,
+
+    /// The server is available again (possibly after going and coming back between checks), any
+    /// streams from before need to be rebuilt.
+    Restored,
+}
+
+impl Display for ServerChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerChange::Lost => write!(f, "Sound Server connection was lost"),
+            ServerChange::Restored => write!(f, "Sound Server connection was restored"),
+        }
+    }
+}
+
+/// Shared between the watcher thread and the ServerWatch.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) struct ServerState {
+    pub(crate) connected: AtomicBool,
+
+    // Bumped every time a connection is established, so a restart between checks is still seen
+    pub(crate) connections: AtomicU32,
+    pub(crate) stop: AtomicBool,
+}
+
+impl ServerState {
+    pub(crate) fn set_connected(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        self.connected.store(true, Ordering::Relaxed);
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn set_disconnected(&self) {
+        self.connected.store(false, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub struct ServerWatch {
+    state: Arc<ServerState>,
+    connected: bool,
+    connections: u32,
+}
+
+impl ServerWatch {
+    /// Starts watching the sound server, assuming it's there to begin with.
+    pub fn start() -> Self {
+        let state = Arc::new(ServerState::default());
+
+        #[cfg(target_os = "linux")]
+        {
+            let inner_state = state.clone();
+            std::thread::spawn(move || crate::pulse::pulse_server::watch(inner_state));
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        state.set_connected();
+
+        Self::new(state)
+    }
+
+    fn new(state: Arc<ServerState>) -> Self {
+        Self {
+            state,
+            connected: true,
+            connections: 0,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Reports any change since the last poll. The first connection isn't a change.
+    pub fn poll(&mut self) -> Option<ServerChange> {
+        let connected = self.state.connected.load(Ordering::Relaxed);
+        let connections = self.state.connections.load(Ordering::Relaxed);
+
+        // Until the watcher has connected once there's nothing to compare against
+        if self.connections == 0 {
+            if connections == 0 {
+                return None;
+            }
+            self.connections = connections;
+            self.connected = connected;
+            return None;
+        }
+
+        let change = match (self.connected, connected) {
+            (true, false) => Some(ServerChange::Lost),
+            (_, true) if connections != self.connections => Some(ServerChange::Restored),
+            _ => None,
+        };
+
+        self.connected = connected;
+        self.connections = connections;
+        change
+    }
+}
+
+impl Drop for ServerWatch {
+    fn drop(&mut self) {
+        self.state.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// How long to wait between attempts to reach the server, doubling with each failure.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) struct Backoff {
+    next: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { next: BACKOFF_MIN }
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl Backoff {
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(BACKOFF_MAX);
+        delay
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.next = BACKOFF_MIN;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_is_lost_and_restored() {
+        let state = Arc::new(ServerState::default());
+        let mut watch = ServerWatch::new(state.clone());
+        assert_eq!(watch.poll(), None);
+
+        state.set_connected();
+        assert_eq!(watch.poll(), None);
+        assert!(watch.is_connected());
+
+        state.set_disconnected();
+        assert_eq!(watch.poll(), Some(ServerChange::Lost));
+        assert_eq!(watch.poll(), None);
+        assert!(!watch.is_connected());
+
+        state.set_connected();
+        assert_eq!(watch.poll(), Some(ServerChange::Restored));
+        assert_eq!(watch.poll(), None);
+        assert!(watch.is_connected());
+    }
+
+    #[test]
+    fn restart_between_polls_is_a_restore() {
+        let state = Arc::new(ServerState::default());
+        let mut watch = ServerWatch::new(state.clone());
+        state.set_connected();
+        assert_eq!(watch.poll(), None);
+
+        state.set_disconnected();
+        state.set_connected();
+        assert_eq!(watch.poll(), Some(ServerChange::Restored));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let mut backoff = Backoff::default();
+        assert_eq!(backoff.next_delay(), BACKOFF_MIN);
+        assert_eq!(backoff.next_delay(), BACKOFF_MIN * 2);
+
+        let delay = (0..20).map(|_| backoff.next_delay()).last();
+        assert_eq!(delay, Some(BACKOFF_MAX));
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), BACKOFF_MIN);
+    }
+}
@@ -1,14 +1,16 @@
 use clap::{ArgAction, Args, Parser, Subcommand};
 
+use goxlr_types::colours::parse_colour;
 use goxlr_types::{
     AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
     CompressorAttackTime, CompressorRatio, CompressorReleaseTime, EchoStyle, EffectBankPresets,
-    EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName, GateTimes, GenderStyle,
-    HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle, MiniEqFrequencies, Mix,
-    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle,
-    SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode, SimpleColourTargets,
-    WaterfallDirection,
+    EffectSection, EncoderColourTargets, EncoderName, EqFrequencies, FaderDisplayStyle, FaderName,
+    GateTimes, GenderStyle, HardTuneSource, HardTuneStyle, HighContrastGroup, InputDevice,
+    MegaphoneStyle, MicProfileSection, MiniEqFrequencies, Mix, MuteFunction, MuteState,
+    OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle, SampleBank, SampleButtons,
+    SamplePlayOrder, SamplePlaybackMode, SimpleColourTargets, WaterfallDirection,
 };
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser, Debug)]
@@ -35,6 +37,11 @@ pub struct Cli {
     #[arg(long, num_args=0..=1, default_missing_value="http://localhost:14564")]
     pub use_http: Option<String>,
 
+    /// Connect to the daemon's IPC socket (or named pipe on Windows) at this location, can also
+    /// be set with the GOXLR_SOCKET_PATH environment variable
+    #[arg(long)]
+    pub socket_path: Option<String>,
+
     #[command(flatten, next_help_heading = "Microphone controls")]
     pub microphone_controls: MicrophoneControls,
 
@@ -58,6 +65,11 @@ pub struct MicrophoneControls {
     /// Value is in decibels and recommended to be lower than 72dB.
     #[arg(long)]
     pub jack_gain: Option<u16>,
+
+    /// Confirm that enabling 48V phantom power (by setting the condenser gain) is intended,
+    /// needed when the device requires phantom power to be confirmed
+    #[arg(long)]
+    pub confirm_phantom_power: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -92,11 +104,21 @@ pub enum SubCommands {
         command: SubmixCommands,
     },
 
+    /// Save and Recall Volume Scenes
+    VolumeScenes {
+        #[command(subcommand)]
+        command: VolumeSceneCommands,
+    },
+
     /// Configure the Bleep Button
     BleepVolume {
         /// Set Bleep Button Volume
-        #[arg(value_parser=percent_value)]
-        volume_percent: u8,
+        #[arg(value_parser=percent_value, required_unless_present = "adjust")]
+        volume_percent: Option<u8>,
+
+        /// Change the volume by this many dB instead (eg. +2 or -3)
+        #[arg(long, allow_hyphen_values = true, conflicts_with = "volume_percent")]
+        adjust: Option<i8>,
     },
 
     /// Commands to manipulate the individual GoXLR Faders
@@ -111,6 +133,18 @@ pub enum SubCommands {
         command: CoughButtonBehaviours,
     },
 
+    /// Keep the Microphone muted until it's unlocked
+    Privacy {
+        #[command(subcommand)]
+        command: PrivacyCommands,
+    },
+
+    /// Set the device up for a headset plugged into the Mic and Headphone jacks
+    Headset {
+        #[command(subcommand)]
+        command: HeadsetCommands,
+    },
+
     /// Commands to manipulate the GoXLR Router
     Router {
         /// The input device
@@ -126,6 +160,28 @@ pub enum SubCommands {
         enabled: bool,
     },
 
+    /// Set a Low Cut on the Line In or Console input, this is only applied to Sampler recordings
+    LowCut {
+        /// The input device
+        #[arg(value_enum)]
+        input: InputDevice,
+
+        /// The cutoff frequency in Hz, leave this out to remove the Low Cut
+        #[arg(value_parser = clap::value_parser!(u8).range(20..=250))]
+        frequency: Option<u8>,
+    },
+
+    /// Move an input towards the Left or Right of every output it's routed to
+    Balance {
+        /// The input channel
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// From -50 (Left only) to 50 (Right only), 0 is centred
+        #[arg(value_parser = clap::value_parser!(i8).range(-50..=50), allow_hyphen_values = true)]
+        balance: i8,
+    },
+
     /// Commands to control the GoXLR lighting
     Lighting {
         #[command(subcommand)]
@@ -147,6 +203,131 @@ pub enum SubCommands {
         #[clap[subcommand]]
         command: DeviceSettings,
     },
+
+    /// Inspect the Audio Devices available to the Sampler
+    Audio {
+        #[command(subcommand)]
+        command: AudioCommands,
+    },
+
+    /// Manage the default files shipped with the utility
+    Defaults {
+        #[command(subcommand)]
+        command: DefaultsCommands,
+    },
+
+    /// Print what the device reports about its current state (for debugging)
+    HardwareState,
+
+    /// Wait until the device has finished applying everything sent to it
+    Settle {
+        /// How long to wait before giving up, in milliseconds
+        #[arg(long, default_value = "5000")]
+        timeout: u32,
+    },
+
+    /// Show the daemon's health, including any startup problems and how its last run ended
+    Health,
+
+    /// Create a zip of the daemon's logs, settings and status to attach to a bug report
+    SupportBundle {
+        /// Also copy the bundle here, it's always written to the daemon's log directory
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Also include the loaded profile and mic profile files
+        #[arg(long)]
+        include_profiles: bool,
+    },
+
+    /// Read the daemon's log, or change what it logs without a restart
+    Log {
+        #[command(subcommand)]
+        command: LogCommands,
+    },
+
+    /// List, add or remove the icons which can be shown on the scribble strips
+    Icons {
+        #[command(subcommand)]
+        command: IconCommands,
+    },
+
+    /// Move the daemon's data (profiles, presets, samples and so on) between directories
+    Data {
+        #[command(subcommand)]
+        command: DataCommands,
+    },
+
+    /// Drive commands from the faders and buttons of a MIDI controller
+    Midi {
+        #[command(subcommand)]
+        command: MidiCommands,
+    },
+
+    /// Anonymous usage statistics, which are only collected once enabled
+    Telemetry {
+        #[command(subcommand)]
+        command: TelemetryCommands,
+    },
+
+    /// Load profiles as devices connect, such as on the first connection of each day
+    ProfileRules {
+        #[command(subcommand)]
+        command: ProfileRuleCommands,
+    },
+
+    /// Follow the GoXLR's mic mute in a voice chat application
+    MuteSync {
+        #[arg(value_enum)]
+        application: MuteSyncTarget,
+
+        #[command(subcommand)]
+        command: MuteSyncCommands,
+    },
+
+    /// Limit how quickly remote clients can use the HTTP API, 0 removes a limit. Anything not
+    /// given keeps its current value.
+    HttpRateLimit {
+        /// Commands each address may send per second
+        #[arg(long)]
+        commands_per_second: Option<u32>,
+
+        /// Status requests each address may make per second
+        #[arg(long)]
+        status_per_second: Option<u32>,
+
+        /// Commands from all addresses which can be waiting on the devices at once
+        #[arg(long)]
+        max_in_flight: Option<u32>,
+    },
+
+    /// Stop guarded sample buttons from being pressed (for example, while recording)
+    GuardMode {
+        /// Whether guard mode is active
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// List the clients talking to the daemon, or disconnect one of them
+    Clients {
+        /// The id of an IPC or websocket client to disconnect
+        #[arg(long)]
+        disconnect: Option<u64>,
+    },
+
+    /// Print events from the daemon (as JSON, one per line) until interrupted
+    Watch {
+        /// Only print notifications (the announcements normally spoken by TTS)
+        #[arg(long)]
+        notifications: bool,
+    },
+
+    /// An interactive mixer board, showing each fader's volume and mute state
+    Tui,
+}
+
+fn colour_value(s: &str) -> Result<String, String> {
+    parse_colour(s).map_err(|e| e.to_string())
 }
 
 fn percent_value(s: &str) -> Result<u8, String> {
@@ -162,6 +343,16 @@ fn percent_value(s: &str) -> Result<u8, String> {
     Ok(value)
 }
 
+fn group_levels(s: &str) -> Result<(HighContrastGroup, u8, u8), String> {
+    if let [group, active, inactive] = s.split(':').collect::<Vec<_>>()[..] {
+        let group: HighContrastGroup = clap::ValueEnum::from_str(group, true)?;
+        return Ok((group, percent_value(active)?, percent_value(inactive)?));
+    }
+    Err(String::from(
+        "Expected GROUP:ACTIVE:INACTIVE (eg. fader-mute:100:0)",
+    ))
+}
+
 fn percent_value_float(s: &str) -> Result<f32, String> {
     let value = f32::from_str(s);
     if value.is_err() {
@@ -200,6 +391,46 @@ pub enum CoughButtonBehaviours {
     },
 }
 
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum PrivacyCommands {
+    /// Enable Privacy Mode, the Microphone is locked muted straight away
+    Enable {
+        /// Lock again after this many seconds without a command or button press
+        #[arg(long)]
+        idle_relock: Option<u32>,
+
+        /// Lock again when the screen is locked
+        #[arg(long)]
+        relock_on_screen_lock: bool,
+    },
+
+    /// Disable Privacy Mode, the Microphone's mute state follows the profile again
+    Disable,
+
+    /// Lock the Microphone muted
+    Lock,
+
+    /// Allow the Microphone to be unmuted
+    Unlock,
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum HeadsetCommands {
+    /// Switch to the Jack Mic, hear it in the Headphones, and have Chat mute to Voice Chat
+    Enable,
+
+    /// Put back the settings from before Headset Mode was enabled
+    Disable,
+
+    /// How loud the Mic is heard in the Headphones while Headset Mode is enabled
+    Sidetone {
+        #[arg(value_parser=percent_value)]
+        sidetone: u8,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum ProfileType {
@@ -230,6 +461,10 @@ pub enum ProfileAction {
         /// Persist the Load
         #[arg(num_args=0..=1, default_missing_value="true")]
         persist: Option<bool>,
+
+        /// Discard any unsaved changes to the current profile without asking
+        #[arg(long)]
+        force: bool,
     },
 
     /// Load a Profiles Colours Only
@@ -238,6 +473,16 @@ pub enum ProfileAction {
         profile_name: String,
     },
 
+    /// Load one section of a Mic Profile, keeping the rest of the current one
+    LoadSection {
+        /// The name of the profile to load the section from
+        profile_name: String,
+
+        /// The section to load
+        #[arg(value_enum)]
+        section: MicProfileSection,
+    },
+
     /// Save the currently running profile
     Save,
 
@@ -246,6 +491,30 @@ pub enum ProfileAction {
         /// The new Profile Name
         profile_name: String,
     },
+
+    /// Rename a profile, including the active one
+    Rename {
+        /// The profile to rename
+        profile_name: String,
+
+        /// The new Profile Name
+        new_name: String,
+    },
+
+    /// Export the currently running profile for the official GoXLR App
+    ExportWindows {
+        /// The name to export as, the file is written to the profile directory
+        name: String,
+    },
+
+    /// Base the current profile on another, sections which match the base will follow it
+    Rebase {
+        /// The name of the base profile
+        base_name: String,
+    },
+
+    /// Stop the current profile following its base profile
+    ClearBase,
 }
 
 #[derive(Subcommand, Debug)]
@@ -263,6 +532,12 @@ pub enum MicrophoneCommands {
         command: EqualiserMiniCommands,
     },
 
+    /// Import or Export the Equaliser as a curve
+    EqCurve {
+        #[command(subcommand)]
+        command: EqCurveCommands,
+    },
+
     /// Configure the microphone noise gate
     NoiseGate {
         #[command(subcommand)]
@@ -277,8 +552,12 @@ pub enum MicrophoneCommands {
 
     /// Set the DeEss percentage
     DeEss {
-        #[arg(value_parser=percent_value)]
-        level: u8,
+        #[arg(value_parser=percent_value, required_unless_present = "adjust")]
+        level: Option<u8>,
+
+        /// Change the percentage by this much instead (eg. +2 or -3)
+        #[arg(long, allow_hyphen_values = true, conflicts_with = "level")]
+        adjust: Option<i8>,
     },
 
     /// Enable Microphone Monitor whenever FX are enabled
@@ -319,6 +598,22 @@ pub enum SubmixCommands {
         linked: bool,
     },
 
+    /// Link / Unlink every channel at once
+    LinkAll {
+        #[arg(value_parser, action = ArgAction::Set)]
+        linked: bool,
+    },
+
+    /// Whether a channel starts linked when Submixes are enabled on a profile which doesn't
+    /// already say
+    DefaultLinked {
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        #[arg(value_parser, action = ArgAction::Set)]
+        linked: bool,
+    },
+
     /// Set the output mix for a channel
     OutputMix {
         /// The Output Device to Change
@@ -338,6 +633,28 @@ pub enum SubmixCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum VolumeSceneCommands {
+    /// Store the current channel and submix volumes as a scene
+    Save {
+        /// The Name of the Scene
+        name: String,
+    },
+
+    /// Apply the volumes from a scene
+    Load {
+        /// The Name of the Scene
+        name: String,
+    },
+
+    /// Remove a scene from the profile
+    Delete {
+        /// The Name of the Scene
+        name: String,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum EqualiserMiniCommands {
@@ -363,6 +680,23 @@ pub enum EqualiserMiniCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum EqCurveCommands {
+    /// Apply an AutoEQ 'ParametricEQ.txt' file in the mic profile directory to the Equaliser
+    Import {
+        /// The name of the file to import, without the .txt
+        name: String,
+    },
+
+    /// Write the current Equaliser frequencies and gains to a CSV file in the mic profile
+    /// directory
+    Export {
+        /// The name of the file to write, without the .csv
+        name: String,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum EqualiserCommands {
@@ -391,8 +725,12 @@ pub enum EqualiserCommands {
 pub enum NoiseGateCommands {
     /// Activation Threshold in dB [-59 - 0]
     Threshold {
-        #[arg(allow_hyphen_values = true)]
-        value: i8,
+        #[arg(allow_hyphen_values = true, required_unless_present = "adjust")]
+        value: Option<i8>,
+
+        /// Change the threshold by this many dB instead (eg. +2 or -3)
+        #[arg(long, allow_hyphen_values = true, conflicts_with = "value")]
+        adjust: Option<i8>,
     },
 
     /// Attenuation Percentage [0 - 100]
@@ -425,8 +763,12 @@ pub enum NoiseGateCommands {
 pub enum CompressorCommands {
     /// Activation Threshold in dB [-24 - 0]
     Threshold {
-        #[arg(allow_hyphen_values = true)]
-        value: i8,
+        #[arg(allow_hyphen_values = true, required_unless_present = "adjust")]
+        value: Option<i8>,
+
+        /// Change the threshold by this many dB instead (eg. +2 or -3)
+        #[arg(long, allow_hyphen_values = true, conflicts_with = "value")]
+        adjust: Option<i8>,
     },
     Ratio {
         #[arg(value_enum)]
@@ -441,7 +783,12 @@ pub enum CompressorCommands {
         value: CompressorReleaseTime,
     },
     MakeUp {
-        value: i8,
+        #[arg(allow_hyphen_values = true, required_unless_present = "adjust")]
+        value: Option<i8>,
+
+        /// Change the gain by this many dB instead (eg. +2 or -3)
+        #[arg(long, allow_hyphen_values = true, conflicts_with = "value")]
+        adjust: Option<i8>,
     },
 }
 
@@ -499,6 +846,17 @@ pub enum Scribbles {
         name: Option<String>,
     },
 
+    /// Draw a character (such as an emoji) in place of the Icon
+    Glyph {
+        /// The Fader to Change
+        #[arg(value_enum)]
+        fader: FaderName,
+
+        /// The character(s) to draw, leave empty to remove the glyph
+        #[arg(default_value = "")]
+        glyph: String,
+    },
+
     /// Change the Text on a Scribble
     Text {
         /// The Fader to Change
@@ -519,6 +877,17 @@ pub enum Scribbles {
         text: String,
     },
 
+    /// Show a live value in the Number field, use 'number' to return to static text
+    NumberMode {
+        /// The Fader to Change
+        #[arg(value_enum)]
+        fader: FaderName,
+
+        /// The value to display
+        #[arg(value_enum)]
+        mode: ScribbleNumberSource,
+    },
+
     /// Inverts a scribble display
     Invert {
         /// The Fader to Change
@@ -552,7 +921,11 @@ pub enum LightingCommands {
     },
 
     /// Sets the Global GoXLR Colour
-    Global { colour: String },
+    Global {
+        /// The new colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
+        colour: String,
+    },
 
     /// Configure Lighting for a specific fader
     Fader {
@@ -585,6 +958,7 @@ pub enum LightingCommands {
         target: SimpleColourTargets,
 
         /// The New Colour
+        #[arg(value_parser = colour_value)]
         colour: String,
     },
 
@@ -595,14 +969,85 @@ pub enum LightingCommands {
         target: EncoderColourTargets,
 
         /// The 'Inactive' Colour?
+        #[arg(value_parser = colour_value)]
         colour_one: String,
 
         /// The 'Active' Colour
+        #[arg(value_parser = colour_value)]
         colour_two: String,
 
         /// The Knob Colour
+        #[arg(value_parser = colour_value)]
         colour_three: String,
     },
+
+    /// Make lighting follow the Microphone level
+    Reactive {
+        #[command(subcommand)]
+        command: ReactiveLightingCommands,
+    },
+
+    /// Show whether buttons are active by brightness, rather than by colour
+    HighContrast {
+        #[command(subcommand)]
+        command: HighContrastCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum HighContrastCommands {
+    /// Light active buttons brightly and inactive ones dimly, whatever their off style
+    Enable {
+        /// The brightness of active buttons (0 - 100)
+        #[arg(long, default_value = "100", value_parser = percent_value)]
+        active: u8,
+
+        /// The brightness of inactive buttons (0 - 100)
+        #[arg(long, default_value = "10", value_parser = percent_value)]
+        inactive: u8,
+
+        /// Levels for a group of buttons, as GROUP:ACTIVE:INACTIVE (may be repeated)
+        #[arg(long = "group", value_parser = group_levels)]
+        groups: Vec<(HighContrastGroup, u8, u8)>,
+    },
+
+    /// Return to the profile's off styles
+    Disable,
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum ReactiveLightingCommands {
+    /// Start following the Microphone level
+    Enable {
+        /// The colour shown while quiet [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
+        base_colour: String,
+
+        /// The colour shown at the peak level [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
+        peak_colour: String,
+
+        /// A Simple colour target to light (may be repeated)
+        #[arg(long = "simple", value_enum)]
+        simple: Vec<SimpleColourTargets>,
+
+        /// A Button to light (may be repeated)
+        #[arg(long = "button", value_enum)]
+        buttons: Vec<Button>,
+
+        /// How quiet the Microphone can be and still reach the peak colour (0 - 100)
+        #[arg(long, default_value = "50", value_parser = clap::value_parser!(u8).range(0..=100))]
+        sensitivity: u8,
+
+        /// How long (in milliseconds) to fade back to the base colour
+        #[arg(long, default_value = "300")]
+        decay: u16,
+    },
+
+    /// Return to the profile's colours
+    Disable,
 }
 
 #[derive(Subcommand, Debug)]
@@ -653,10 +1098,12 @@ pub enum FaderLightingCommands {
         #[arg(value_enum)]
         fader: FaderName,
 
-        /// Top colour in hex format [RRGGBB]
+        /// Top colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         top: String,
 
-        /// Bottom colour in hex format [RRGGBB]
+        /// Bottom colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         bottom: String,
     },
 }
@@ -673,10 +1120,12 @@ pub enum FadersAllLightingCommands {
 
     /// Sets the Top and Bottom colours of a fader
     Colour {
-        /// Top colour in hex format [RRGGBB]
+        /// Top colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         top: String,
 
-        /// Bottom colour in hex format [RRGGBB]
+        /// Bottom colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         bottom: String,
     },
 }
@@ -690,10 +1139,12 @@ pub enum ButtonLightingCommands {
         #[arg(value_enum)]
         button: Button,
 
-        /// The primary button colour [RRGGBB]
+        /// The primary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_one: String,
 
-        /// The secondary button colour [RRGGBB]
+        /// The secondary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_two: Option<String>,
     },
 
@@ -718,10 +1169,12 @@ pub enum ButtonGroupLightingCommands {
         #[arg(value_enum)]
         group: ButtonColourGroups,
 
-        /// The primary button colour [RRGGBB]
+        /// The primary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_one: String,
 
-        /// The secondary button colour [RRGGBB]
+        /// The secondary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_two: Option<String>,
     },
 
@@ -749,27 +1202,217 @@ pub enum AllFaderCommands {
 
     /// Set the colour of all GoXLR Faders
     Colour {
-        /// Top colour in hex format [RRGGBB]
+        /// Top colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         top: String,
 
-        /// Bottom colour in hex format [RRGGBB]
+        /// Bottom colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         bottom: String,
     },
 
     /// Set the colours of all the fader buttons
     ButtonColour {
-        /// The primary button colour [RRGGBB]
+        /// The primary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_one: String,
 
         /// How the button should be presented when 'off'
         #[arg(value_enum)]
         off_style: ButtonColourOffStyle,
 
-        /// The secondary button colour [RRGGBB]
+        /// The secondary button colour [RRGGBB, #RRGGBB or a basic CSS colour name]
+        #[arg(value_parser = colour_value)]
         colour_two: Option<String>,
     },
 }
 
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum LogCommands {
+    /// Print the most recent lines from the daemon's log
+    Tail {
+        #[arg(default_value_t = 50)]
+        lines: usize,
+    },
+
+    /// Set extra filter directives in the style of RUST_LOG (for example,
+    /// 'goxlr_daemon::device=trace,goxlr_usb=debug'), leave this out to remove them
+    Filter { directives: Option<String> },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum IconCommands {
+    /// List the icons, with their sizes and where their thumbnails can be found
+    List,
+
+    /// Add an image (PNG, JPEG or GIF) as an icon, it's stored as a greyscale PNG
+    Upload { path: PathBuf },
+
+    /// Remove an icon
+    Delete {
+        name: String,
+
+        /// Remove the icon even if a loaded profile is using it
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum DataCommands {
+    /// Copy the data to a new directory, then switch the daemon over to using it
+    Migrate { path: PathBuf },
+
+    /// Copy another installation's data into the current directories, without a path the
+    /// directory found when the daemon started is used
+    Import { path: Option<PathBuf> },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum MuteSyncCommands {
+    /// Enable or Disable the sync [true | false]
+    Enabled {
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Whether muting in the application should also mute the GoXLR
+    Direction {
+        #[arg(value_enum)]
+        direction: MuteSyncMode,
+    },
+
+    /// The details of the application registered with the service, this will require
+    /// authorising again
+    Client {
+        client_id: String,
+        client_secret: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum MidiCommands {
+    /// List the MIDI input ports which can be listened to
+    Ports,
+
+    /// Enable or Disable MIDI input [true | false]
+    Enabled {
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// The MIDI input port to listen to, leave this out to stop listening
+    Port { name: Option<String> },
+
+    /// Run a command when a control changes. The command is a GoXLRCommand as JSON, in which
+    /// "$value" is replaced by the control's value scaled to between min and max (for example,
+    /// '{"SetSubMixVolume": ["Chat", "$value"]}' with a max of 255)
+    Map {
+        #[arg(value_enum)]
+        control: MidiControlType,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(0..16))]
+        channel: u8,
+
+        /// The controller or note number
+        #[arg(value_parser = clap::value_parser!(u8).range(0..128))]
+        number: u8,
+
+        serial: String,
+        command: String,
+
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        min: i32,
+
+        #[arg(long, default_value_t = 127, allow_negative_numbers = true)]
+        max: i32,
+    },
+
+    /// Remove the mapping from a control
+    Unmap {
+        #[arg(value_enum)]
+        control: MidiControlType,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(0..16))]
+        channel: u8,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(0..128))]
+        number: u8,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum TelemetryCommands {
+    /// Enable or Disable collecting usage statistics [true | false]
+    Enabled {
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Where the statistics are sent, leave this out to stop sending them
+    Endpoint { url: Option<String> },
+
+    /// Show the statistics which will be sent next
+    Preview,
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum ProfileRuleCommands {
+    /// List the rules, in the order they're checked
+    List,
+
+    /// Set the profile and / or mic profile loaded for a trigger, replacing any existing rule
+    Set {
+        #[arg(value_enum)]
+        trigger: ProfileRuleTrigger,
+
+        #[arg(long)]
+        profile: Option<String>,
+
+        #[arg(long)]
+        mic_profile: Option<String>,
+    },
+
+    /// Remove the rule for a trigger
+    Remove {
+        #[arg(value_enum)]
+        trigger: ProfileRuleTrigger,
+    },
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum ProfileRuleTrigger {
+    /// The firmware is different to the last time the device connected
+    FirmwareChanged,
+    FirstConnectOfDay,
+    OnConnect,
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum MidiControlType {
+    /// A Control Change, usually sent by faders and knobs
+    Cc,
+    Note,
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum MuteSyncTarget {
+    Discord,
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum MuteSyncMode {
+    ToApplication,
+    Both,
+}
+
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum EffectsCommands {
@@ -780,6 +1423,29 @@ pub enum EffectsCommands {
         name: String,
     },
     SaveActivePreset,
+    LoadEffectSection {
+        #[arg(value_enum)]
+        section: EffectSection,
+        name: String,
+    },
+    SaveEffectSection {
+        #[arg(value_enum)]
+        section: EffectSection,
+        name: String,
+    },
+    /// Scale the Reverb, Echo and Pitch amounts together, as a percentage
+    Intensity {
+        #[arg(value_parser=percent_value)]
+        intensity: u8,
+    },
+    /// Share an encoder's value between every preset, so it stays put when the preset changes
+    GlobalEncoder {
+        #[arg(value_enum)]
+        encoder: EncoderName,
+
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
     SetActivePreset {
         #[arg(value_enum)]
         preset: EffectBankPresets,
@@ -1079,6 +1745,12 @@ pub enum SamplerCommands {
         button: SampleButtons,
     },
 
+    /// Save the last few seconds heard by the Sampler (its pre-buffer) as a Recording
+    SaveReplay {
+        /// The name of the file, by default it's named after the current time
+        name: Option<String>,
+    },
+
     PlaybackMode {
         #[arg(value_enum)]
         bank: SampleBank,
@@ -1101,6 +1773,39 @@ pub enum SamplerCommands {
         mode: SamplePlayOrder,
     },
 
+    /// Ignore presses of the button while guard mode is active
+    Guard {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        #[arg(value_parser, action = ArgAction::Set)]
+        guarded: bool,
+    },
+
+    /// Lower other channels while the button is playing, without any channels it stops ducking
+    Duck {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        /// A channel to lower (may be repeated)
+        #[arg(long = "channel", value_enum)]
+        channels: Vec<ChannelName>,
+
+        /// How much of the channels' volume to take away (0 - 100)
+        #[arg(long, default_value = "50", value_parser = clap::value_parser!(u8).range(0..=100))]
+        attenuation: u8,
+
+        /// How long (in milliseconds) the channels take to rise back once playback stops
+        #[arg(long, default_value = "500")]
+        release: u16,
+    },
+
     StartPercent {
         #[arg(value_enum)]
         bank: SampleBank,
@@ -1126,6 +1831,45 @@ pub enum SamplerCommands {
         #[arg(value_parser=percent_value_float)]
         stop_position: f32,
     },
+
+    /// Set where the sample starts playing from, in milliseconds
+    StartMs {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        sample_id: usize,
+
+        start_position: u64,
+    },
+
+    /// Set where the sample stops playing, in milliseconds
+    StopMs {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        sample_id: usize,
+
+        stop_position: u64,
+    },
+
+    /// Adjust the gain of every sample in a bank to reach the same loudness
+    NormaliseBank {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        /// The target loudness in LUFS
+        #[arg(allow_hyphen_values = true, default_value = "-23")]
+        target: f32,
+    },
+
+    /// Stop a bank normalisation, samples already processed keep their new gain
+    CancelNormalise,
 }
 
 #[derive(Subcommand, Debug)]
@@ -1145,6 +1889,20 @@ pub enum DeviceSettings {
         duration: u16,
     },
 
+    /// Seconds before an armed Sample Clear disarms itself (0 to never disarm)
+    SampleClearTimeout {
+        /// The timeout in Seconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        seconds: u16,
+    },
+
+    /// Convert samples to 48kHz 16bit WAVs when they're added, the originals are kept
+    SampleConversion {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
     /// Enable Mic Monitoring when FX are enabled
     MonitorWithFx {
         /// Whether the setting is enabled
@@ -1159,10 +1917,129 @@ pub enum DeviceSettings {
         enabled: bool,
     },
 
+    /// The outputs the Microphone is removed from while Voice Chat is Muted to All
+    ChatMuteOutputs {
+        /// The outputs, leave empty to keep the Microphone on all of them
+        #[arg(value_enum)]
+        outputs: Vec<OutputDevice>,
+    },
+
     /// Locks the Faders to their current value on MuteToAll
     LockFaders {
         /// Whether the setting is enabled
         #[arg(value_parser, action = ArgAction::Set)]
         enabled: bool,
     },
+
+    /// Avoids moving the Faders' motors, implies LockFaders while enabled
+    SilentFaders {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Holding an Effect Select button previews the bank until it's released
+    EffectPreviewOnHold {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Holding the FX button turns off Megaphone, Robot and Hard Tune
+    ClearEffectsOnHold {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Monitors the Line Out while the Headphones are unplugged (needs jack detection)
+    SwitchMonitorOnUnplug {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// On the Mini, moving a fader while holding its mute button adjusts the volume finely
+    /// (a held mute then mutes to all when the button is released)
+    FineFaderAdjust {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// How often the GoXLR is checked for button and fader changes, this slows down (to 100ms)
+    /// while the device isn't being used
+    PollInterval {
+        /// The interval in Milliseconds
+        #[arg(value_parser = clap::value_parser!(u16).range(5..=100))]
+        milliseconds: u16,
+    },
+
+    /// How long the GoXLR can go without being read before it's considered stalled, and reset
+    StallTimeout {
+        /// The timeout in Seconds
+        #[arg(value_parser = clap::value_parser!(u16).range(2..=60))]
+        seconds: u16,
+    },
+
+    /// Saves any unsaved Profile changes when the Daemon shuts down
+    SaveOnShutdown {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Require confirmation before switching to a Condenser Mic enables 48V phantom power
+    PhantomConfirmationRequired {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Keep the volumes the faders are at when the Daemon starts, instead of the Profile's
+    AdoptHardwareState {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum AudioCommands {
+    /// List the available Audio Devices, and those currently used by the Sampler
+    Devices,
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum DefaultsCommands {
+    /// Restore the default files of a type
+    Restore {
+        #[arg(value_enum)]
+        file_type: DefaultFileType,
+
+        /// Only restore the named file (may be specified multiple times)
+        #[arg(long)]
+        only: Vec<String>,
+
+        /// Replace files which already exist
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum DefaultFileType {
+    Profiles,
+    MicProfiles,
+    Presets,
+    Icons,
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum ScribbleNumberSource {
+    VolumePercent,
+    VolumeDb,
+    ActivePreset,
 }
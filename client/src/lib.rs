@@ -1,3 +1,4 @@
 mod cli;
 mod microphone;
 pub mod runner;
+mod tui;
@@ -1,8 +1,10 @@
 use crate::cli::MicrophoneControls;
-use anyhow::Result;
+use crate::runner::confirm;
+use anyhow::{bail, Result};
 use goxlr_ipc::client::Client;
-use goxlr_ipc::GoXLRCommand;
+use goxlr_ipc::{CommandError, GoXLRCommand};
 use goxlr_types::MicrophoneType;
+use std::io::{self, IsTerminal};
 
 pub async fn apply_microphone_controls(
     microphone_controls: &MicrophoneControls,
@@ -10,12 +12,25 @@ pub async fn apply_microphone_controls(
     serial: &str,
 ) -> Result<()> {
     if let Some(gain) = microphone_controls.condenser_gain {
-        client
-            .command(
-                serial,
-                GoXLRCommand::SetMicrophoneGain(MicrophoneType::Condenser, gain),
-            )
-            .await?;
+        let command = GoXLRCommand::SetMicrophoneGain(MicrophoneType::Condenser, gain);
+        match client.command(serial, command.clone()).await {
+            Err(error) if is_phantom_confirmation(&error) => {
+                if !microphone_controls.confirm_phantom_power {
+                    if !io::stdin().is_terminal() {
+                        bail!("{}, use --confirm-phantom-power to continue", error);
+                    }
+                    if !confirm(&format!("{}, continue?", error))? {
+                        bail!("Microphone not changed");
+                    }
+                }
+
+                // Confirm the switch, then set the gain as requested.
+                let confirmed = GoXLRCommand::ConfirmMicrophoneType(MicrophoneType::Condenser);
+                client.command(serial, confirmed).await?;
+                client.command(serial, command).await?;
+            }
+            result => result?,
+        }
     }
     if let Some(gain) = microphone_controls.dynamic_gain {
         client
@@ -26,12 +41,42 @@ pub async fn apply_microphone_controls(
             .await?;
     }
     if let Some(gain) = microphone_controls.jack_gain {
+        // Switching to the Jack is usually a headset being plugged in, so offer to set that up
+        let offer_headset = client.status().mixers.get(serial).is_some_and(|mixer| {
+            mixer.mic_status.mic_type != MicrophoneType::Jack && mixer.headset.snapshot.is_none()
+        });
+
         client
             .command(
                 serial,
                 GoXLRCommand::SetMicrophoneGain(MicrophoneType::Jack, gain),
             )
             .await?;
+
+        if offer_headset && io::stdin().is_terminal() {
+            let question = "Is this a headset? Headset Mode will also let you hear the \
+                Microphone in your Headphones, enable it?";
+            if confirm(question)? {
+                client
+                    .command(serial, GoXLRCommand::SetHeadsetMode(true))
+                    .await?;
+
+                // Headset Mode applies its own gain, but the one asked for here wins
+                client
+                    .command(
+                        serial,
+                        GoXLRCommand::SetMicrophoneGain(MicrophoneType::Jack, gain),
+                    )
+                    .await?;
+            }
+        }
     }
     Ok(())
 }
+
+fn is_phantom_confirmation(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<CommandError>(),
+        Some(CommandError::PhantomPowerConfirmation)
+    )
+}
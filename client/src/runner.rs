@@ -1,29 +1,49 @@
 use crate::cli::{
-    AnimationCommands, ButtonGroupLightingCommands, ButtonLightingCommands, CompressorCommands,
-    CoughButtonBehaviours, Echo, EffectsCommands, EqualiserCommands, EqualiserMiniCommands,
-    FaderCommands, FaderLightingCommands, FadersAllLightingCommands, Gender, HardTune,
-    LightingCommands, Megaphone, MicrophoneCommands, NoiseGateCommands, Pitch, ProfileAction,
-    ProfileType, Reverb, Robot, SamplerCommands, Scribbles, SubCommands, SubmixCommands,
+    AnimationCommands, AudioCommands, ButtonGroupLightingCommands, ButtonLightingCommands,
+    CompressorCommands, CoughButtonBehaviours, Echo, EffectsCommands, EqCurveCommands,
+    EqualiserCommands, EqualiserMiniCommands, FaderCommands, FaderLightingCommands,
+    FadersAllLightingCommands, Gender, HardTune, HeadsetCommands, HighContrastCommands,
+    LightingCommands, Megaphone, MicrophoneCommands, NoiseGateCommands, Pitch, PrivacyCommands,
+    ProfileAction, ProfileType, ReactiveLightingCommands, Reverb, Robot, SamplerCommands,
+    Scribbles, SubCommands, SubmixCommands, VolumeSceneCommands,
+};
+use crate::cli::{
+    Cli, DataCommands, DefaultFileType, DefaultsCommands, DeviceSettings, IconCommands,
+    LogCommands, MidiCommands, MidiControlType, MuteSyncCommands, MuteSyncMode, MuteSyncTarget,
+    ProfileRuleCommands, ProfileRuleTrigger, ScribbleNumberSource, TelemetryCommands,
 };
-use crate::cli::{Cli, DeviceSettings};
 use crate::microphone::apply_microphone_controls;
+use crate::tui::run_tui;
 use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
 use goxlr_ipc::client::Client;
 use goxlr_ipc::clients::ipc::ipc_client::IPCClient;
 use goxlr_ipc::clients::ipc::ipc_socket::Socket;
 use goxlr_ipc::clients::web::web_client::WebClient;
-use goxlr_ipc::GoXLRCommand;
-use goxlr_ipc::{DaemonRequest, DaemonResponse, MixerStatus, UsbProductInformation};
+use goxlr_ipc::{
+    AudioDevices, CommandError, ConnectedClient, DaemonCommand, DaemonRequest, DaemonResponse,
+    DaemonStatus, DriverStatus, DuckConfig, EqCurveFilterResult, EqCurveReport, EqTarget,
+    HighContrastConfig, HighContrastLevels, MidiControl, MidiMapping, MidiState, MigrationState,
+    MixerStatus, MuteSyncApplication, MuteSyncDirection, MuteSyncState, PathTypes,
+    PreviousRunOutcome, PrivacyConfig, ProfileRule, ProfileTrigger, ReactiveConfig, ReactiveZone,
+    RecoveredDefaults, ScribbleNumberMode, ShutdownReason, UsbProductInformation,
+};
+use goxlr_ipc::{GoXLRCommand, PROTOCOL_VERSION};
 use goxlr_types::{ChannelName, DeviceType, FaderName, InputDevice, MicrophoneType, OutputDevice};
 
 use interprocess::local_socket::tokio::prelude::LocalSocketStream;
 use interprocess::local_socket::traits::tokio::Stream;
 use interprocess::local_socket::{GenericFilePath, GenericNamespaced, ToFsName, ToNsName};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use strum::IntoEnumIterator;
+use tokio::time::sleep;
 
 static SOCKET_PATH: &str = "/tmp/goxlr.socket";
 static NAMED_PIPE: &str = "@goxlr.socket";
+static SOCKET_PATH_ENV: &str = "GOXLR_SOCKET_PATH";
 
 pub async fn run_cli() -> Result<()> {
     let cli: Cli = Cli::parse();
@@ -33,12 +53,20 @@ pub async fn run_cli() -> Result<()> {
     if let Some(url) = cli.use_http {
         client = Box::new(WebClient::new(format!("{}/api/command", url)));
     } else {
+        let socket_name = match cli.socket_path.or_else(|| env::var(SOCKET_PATH_ENV).ok()) {
+            Some(name) => name,
+            None => match cfg!(windows) {
+                true => String::from(NAMED_PIPE),
+                false => String::from(SOCKET_PATH),
+            },
+        };
+
         // Windows supports unix sockets now, but we want to maintain the historic behaviour
         // so we'll force it to a NameSpace here..
         let path = if cfg!(windows) {
-            NAMED_PIPE.to_ns_name::<GenericNamespaced>()
+            socket_name.as_str().to_ns_name::<GenericNamespaced>()
         } else {
-            SOCKET_PATH.to_fs_name::<GenericFilePath>()
+            socket_name.as_str().to_fs_name::<GenericFilePath>()
         };
 
         let path = match path {
@@ -50,14 +78,347 @@ pub async fn run_cli() -> Result<()> {
 
         let connection = LocalSocketStream::connect(path)
             .await
-            .context("Unable to connect to the GoXLR daemon Process")?;
+            .with_context(|| format!("Unable to connect to the GoXLR daemon at {}", socket_name))?;
 
         let socket: Socket<DaemonResponse, DaemonRequest> = Socket::new(connection);
         client = Box::new(IPCClient::new(socket));
     }
 
+    // An older daemon still handles everything it knows about, so this is only a warning
+    if let Ok(protocol) = client.hello().await {
+        if protocol.unwrap_or(0) < PROTOCOL_VERSION {
+            eprintln!(
+                "Warning: The GoXLR daemon is older than this client, some commands may not be \
+                understood"
+            );
+        }
+    }
+
     client.poll_status().await?;
 
+    // Audio devices and default files belong to the daemon rather than a specific GoXLR, so
+    // handle them before looking for a device to control.
+    if let Some(SubCommands::Audio { command }) = &cli.subcommands {
+        match command {
+            AudioCommands::Devices => print_audio_devices(&client.get_audio_devices().await?),
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Defaults { command }) = &cli.subcommands {
+        match command {
+            DefaultsCommands::Restore {
+                file_type,
+                only,
+                overwrite,
+            } => {
+                let path_type = match file_type {
+                    DefaultFileType::Profiles => PathTypes::Profiles,
+                    DefaultFileType::MicProfiles => PathTypes::MicProfiles,
+                    DefaultFileType::Presets => PathTypes::Presets,
+                    DefaultFileType::Icons => PathTypes::Icons,
+                };
+                let files = (!only.is_empty()).then(|| only.clone());
+                let result = client
+                    .recover_defaults(path_type, files, *overwrite)
+                    .await
+                    .context("Unable to restore Defaults")?;
+                print_recovered_defaults(&result);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Health) = &cli.subcommands {
+        print_health(client.status());
+        return Ok(());
+    }
+
+    if let Some(SubCommands::SupportBundle {
+        output,
+        include_profiles,
+    }) = &cli.subcommands
+    {
+        let mut path = client
+            .create_support_bundle(*include_profiles)
+            .await
+            .context("Unable to create the Support Bundle")?;
+        if let Some(output) = output {
+            fs::copy(&path, output).with_context(|| {
+                format!(
+                    "Unable to copy the Support Bundle to {}",
+                    output.to_string_lossy()
+                )
+            })?;
+            path = output.clone();
+        }
+        println!("Support Bundle written to {}", path.to_string_lossy());
+        println!(
+            "Please review its contents before sharing it, it contains your logs and settings."
+        );
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Log { command }) = &cli.subcommands {
+        match command {
+            LogCommands::Tail { lines } => {
+                for line in client.tail_log(*lines).await? {
+                    println!("{}", line);
+                }
+            }
+            LogCommands::Filter { directives } => {
+                client
+                    .send(DaemonRequest::Daemon(DaemonCommand::SetLogFilter(
+                        directives.clone(),
+                    )))
+                    .await
+                    .context("Unable to set the Log Filter")?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Icons { command }) = &cli.subcommands {
+        match command {
+            IconCommands::List => {
+                for icon in client.list_icons().await? {
+                    println!(
+                        "{} ({}x{}): {}",
+                        icon.name,
+                        icon.width,
+                        icon.height,
+                        icon.thumbnail.to_string_lossy()
+                    );
+                }
+            }
+            IconCommands::Upload { path } => {
+                let name = match path.file_name() {
+                    Some(name) => name.to_string_lossy().to_string(),
+                    None => bail!("{} is not a file", path.to_string_lossy()),
+                };
+                let data = std::fs::read(path)
+                    .with_context(|| format!("Unable to read {}", path.to_string_lossy()))?;
+
+                let icon = client.upload_icon(&name, data).await?;
+                println!("Added {} ({}x{})", icon.name, icon.width, icon.height);
+                println!("Preview: {}", icon.thumbnail.to_string_lossy());
+            }
+            IconCommands::Delete { name, force } => {
+                client.delete_icon(name, *force).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Data { command }) = &cli.subcommands {
+        // As with support bundles, relative paths are from where the client was run
+        let command = match command {
+            DataCommands::Migrate { path } => {
+                DaemonCommand::MigrateDataDirectory(env::current_dir()?.join(path))
+            }
+            DataCommands::Import { path } => {
+                let alternate = &client.status().config.data_migration.alternate_directory;
+                let path = match (path, alternate) {
+                    (Some(path), _) => env::current_dir()?.join(path),
+                    (None, Some(alternate)) => alternate.clone(),
+                    (None, None) => bail!("No other installation was found, please give a path"),
+                };
+                DaemonCommand::ImportDataDirectory(path)
+            }
+        };
+        client
+            .send(DaemonRequest::Daemon(command))
+            .await
+            .context("Unable to start the migration")?;
+        wait_for_migration(&mut client).await?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Midi { command }) = &cli.subcommands {
+        let command = match command {
+            MidiCommands::Ports => {
+                for port in client.get_midi_ports().await? {
+                    println!("{}", port);
+                }
+                return Ok(());
+            }
+            MidiCommands::Enabled { enabled } => DaemonCommand::SetMidiEnabled(*enabled),
+            MidiCommands::Port { name } => DaemonCommand::SetMidiPort(name.clone()),
+            MidiCommands::Map {
+                control,
+                channel,
+                number,
+                serial,
+                command,
+                min,
+                max,
+            } => DaemonCommand::SetMidiMapping(MidiMapping {
+                control: get_midi_control(*control, *channel, *number),
+                serial: serial.clone(),
+                command: serde_json::from_str(command).context("The command isn't valid JSON")?,
+                min: *min,
+                max: *max,
+            }),
+            MidiCommands::Unmap {
+                control,
+                channel,
+                number,
+            } => DaemonCommand::RemoveMidiMapping(get_midi_control(*control, *channel, *number)),
+        };
+        client
+            .send(DaemonRequest::Daemon(command))
+            .await
+            .context("Unable to update the MIDI settings")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Telemetry { command }) = &cli.subcommands {
+        let command = match command {
+            TelemetryCommands::Preview => {
+                let report = client.get_telemetry_preview().await?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            TelemetryCommands::Enabled { enabled } => DaemonCommand::SetTelemetryEnabled(*enabled),
+            TelemetryCommands::Endpoint { url } => DaemonCommand::SetTelemetryEndpoint(url.clone()),
+        };
+        client
+            .send(DaemonRequest::Daemon(command))
+            .await
+            .context("Unable to update the usage statistics settings")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::ProfileRules { command }) = &cli.subcommands {
+        let command = match command {
+            ProfileRuleCommands::List => {
+                client.poll_status().await?;
+                for rule in &client.status().config.profile_rules {
+                    println!(
+                        "{:?}: Profile {}, Mic Profile {}",
+                        rule.trigger,
+                        rule.profile.as_deref().unwrap_or("unchanged"),
+                        rule.mic_profile.as_deref().unwrap_or("unchanged")
+                    );
+                }
+                return Ok(());
+            }
+            ProfileRuleCommands::Set {
+                trigger,
+                profile,
+                mic_profile,
+            } => DaemonCommand::SetProfileRule(ProfileRule {
+                trigger: profile_trigger(*trigger),
+                profile: profile.clone(),
+                mic_profile: mic_profile.clone(),
+            }),
+            ProfileRuleCommands::Remove { trigger } => {
+                DaemonCommand::RemoveProfileRule(profile_trigger(*trigger))
+            }
+        };
+        client
+            .send(DaemonRequest::Daemon(command))
+            .await
+            .context("Unable to update the profile rules")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::MuteSync {
+        application,
+        command,
+    }) = &cli.subcommands
+    {
+        let application = match application {
+            MuteSyncTarget::Discord => MuteSyncApplication::Discord,
+        };
+        let command = match command {
+            MuteSyncCommands::Enabled { enabled } => {
+                DaemonCommand::SetMuteSyncEnabled(application, *enabled)
+            }
+            MuteSyncCommands::Direction { direction } => {
+                let direction = match direction {
+                    MuteSyncMode::ToApplication => MuteSyncDirection::ToApplication,
+                    MuteSyncMode::Both => MuteSyncDirection::Both,
+                };
+                DaemonCommand::SetMuteSyncDirection(application, direction)
+            }
+            MuteSyncCommands::Client {
+                client_id,
+                client_secret,
+            } => DaemonCommand::SetMuteSyncClient {
+                application,
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+            },
+        };
+        client
+            .send(DaemonRequest::Daemon(command))
+            .await
+            .context("Unable to update Mute Sync")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::HttpRateLimit {
+        commands_per_second,
+        status_per_second,
+        max_in_flight,
+    }) = &cli.subcommands
+    {
+        let mut limit = client.status().config.http_settings.rate_limit.clone();
+        if let Some(commands_per_second) = commands_per_second {
+            limit.commands_per_second = *commands_per_second;
+        }
+        if let Some(status_per_second) = status_per_second {
+            limit.status_per_second = *status_per_second;
+        }
+        if let Some(max_in_flight) = max_in_flight {
+            limit.max_in_flight = *max_in_flight;
+        }
+        client
+            .send(DaemonRequest::Daemon(DaemonCommand::SetHttpRateLimit(
+                limit,
+            )))
+            .await
+            .context("Unable to update the HTTP Rate Limit")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::GuardMode { enabled }) = &cli.subcommands {
+        client
+            .send(DaemonRequest::Daemon(DaemonCommand::SetGuardMode(*enabled)))
+            .await
+            .context("Unable to set Guard Mode")?;
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Clients { disconnect }) = &cli.subcommands {
+        match disconnect {
+            Some(id) => client
+                .send(DaemonRequest::DisconnectClient(*id))
+                .await
+                .context("Unable to disconnect the Client")?,
+            None => print_clients(&client.get_connected_clients().await?),
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Watch { notifications }) = &cli.subcommands {
+        client.subscribe(*notifications).await?;
+        loop {
+            let event = client.next_event().await?;
+
+            // If a device was specified, only show that device's notifications
+            if let (DaemonResponse::Notification(notification), Some(serial)) =
+                (&event, &cli.device)
+            {
+                if &notification.serial != serial {
+                    continue;
+                }
+            }
+            println!("{}", serde_json::to_string(&event)?);
+        }
+    }
+
     let serial = if let Some(serial) = &cli.device {
         serial.to_owned()
     } else if client.status().mixers.is_empty() {
@@ -87,6 +448,10 @@ pub async fn run_cli() -> Result<()> {
         .await
         .context("Could not apply microphone controls")?;
 
+    if let Some(SubCommands::Tui) = &cli.subcommands {
+        return run_tui(&mut client, &serial).await;
+    }
+
     // These will be moved around later :)
     match &cli.subcommands {
         None => {}
@@ -110,6 +475,23 @@ pub async fn run_cli() -> Result<()> {
                                 .await?;
                         }
                     },
+                    MicrophoneCommands::EqCurve { command } => match command {
+                        EqCurveCommands::Import { name } => {
+                            let report = client
+                                .import_eq_curve(&serial, name, EqTarget::Microphone)
+                                .await?;
+                            print_eq_curve_report(&report);
+                        }
+                        EqCurveCommands::Export { name } => {
+                            client
+                                .send(DaemonRequest::ExportEqCurve(
+                                    serial.clone(),
+                                    name.clone(),
+                                    EqTarget::Microphone,
+                                ))
+                                .await?;
+                        }
+                    },
                     MicrophoneCommands::EqualiserMini { command } => match command {
                         EqualiserMiniCommands::Frequency { frequency, value } => {
                             client
@@ -123,10 +505,16 @@ pub async fn run_cli() -> Result<()> {
                         }
                     },
                     MicrophoneCommands::NoiseGate { command } => match command {
-                        NoiseGateCommands::Threshold { value } => {
-                            client
-                                .command(&serial, GoXLRCommand::SetGateThreshold(*value))
-                                .await?;
+                        NoiseGateCommands::Threshold { value, adjust } => {
+                            set_or_adjust(
+                                client.as_mut(),
+                                &serial,
+                                *value,
+                                *adjust,
+                                GoXLRCommand::SetGateThreshold,
+                                GoXLRCommand::AdjustGateThreshold,
+                            )
+                            .await?;
                         }
                         NoiseGateCommands::Attenuation { value } => {
                             client
@@ -150,10 +538,16 @@ pub async fn run_cli() -> Result<()> {
                         }
                     },
                     MicrophoneCommands::Compressor { command } => match command {
-                        CompressorCommands::Threshold { value } => {
-                            client
-                                .command(&serial, GoXLRCommand::SetCompressorThreshold(*value))
-                                .await?;
+                        CompressorCommands::Threshold { value, adjust } => {
+                            set_or_adjust(
+                                client.as_mut(),
+                                &serial,
+                                *value,
+                                *adjust,
+                                GoXLRCommand::SetCompressorThreshold,
+                                GoXLRCommand::AdjustCompressorThreshold,
+                            )
+                            .await?;
                         }
                         CompressorCommands::Ratio { value } => {
                             client
@@ -170,16 +564,28 @@ pub async fn run_cli() -> Result<()> {
                                 .command(&serial, GoXLRCommand::SetCompressorReleaseTime(*value))
                                 .await?;
                         }
-                        CompressorCommands::MakeUp { value } => {
-                            client
-                                .command(&serial, GoXLRCommand::SetCompressorMakeupGain(*value))
-                                .await?;
+                        CompressorCommands::MakeUp { value, adjust } => {
+                            set_or_adjust(
+                                client.as_mut(),
+                                &serial,
+                                *value,
+                                *adjust,
+                                GoXLRCommand::SetCompressorMakeupGain,
+                                GoXLRCommand::AdjustCompressorMakeupGain,
+                            )
+                            .await?;
                         }
                     },
-                    MicrophoneCommands::DeEss { level } => {
-                        client
-                            .command(&serial, GoXLRCommand::SetDeeser(*level))
-                            .await?;
+                    MicrophoneCommands::DeEss { level, adjust } => {
+                        set_or_adjust(
+                            client.as_mut(),
+                            &serial,
+                            *level,
+                            *adjust,
+                            GoXLRCommand::SetDeeser,
+                            GoXLRCommand::AdjustDeEsser,
+                        )
+                        .await?;
                     }
                     MicrophoneCommands::MonitorMicWithFx { enabled } => {
                         client
@@ -218,6 +624,14 @@ pub async fn run_cli() -> Result<()> {
                                 )
                                 .await?;
                         }
+                        Scribbles::Glyph { fader, glyph } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::SetScribbleGlyph(*fader, glyph.clone()),
+                                )
+                                .await?;
+                        }
                         Scribbles::Text { fader, text } => {
                             client
                                 .command(
@@ -234,6 +648,20 @@ pub async fn run_cli() -> Result<()> {
                                 )
                                 .await?;
                         }
+                        Scribbles::NumberMode { fader, mode } => {
+                            let mode = match mode {
+                                ScribbleNumberSource::VolumePercent => {
+                                    ScribbleNumberMode::VolumePercent
+                                }
+                                ScribbleNumberSource::VolumeDb => ScribbleNumberMode::VolumeDb,
+                                ScribbleNumberSource::ActivePreset => {
+                                    ScribbleNumberMode::ActivePreset
+                                }
+                            };
+                            client
+                                .command(&serial, GoXLRCommand::SetScribbleNumberMode(*fader, mode))
+                                .await?;
+                        }
                         Scribbles::Invert { fader, inverted } => {
                             client
                                 .command(
@@ -253,6 +681,16 @@ pub async fn run_cli() -> Result<()> {
                         .command(&serial, GoXLRCommand::SetRouter(*input, *output, *enabled))
                         .await?;
                 }
+                SubCommands::LowCut { input, frequency } => {
+                    client
+                        .command(&serial, GoXLRCommand::SetInputLowCut(*input, *frequency))
+                        .await?;
+                }
+                SubCommands::Balance { channel, balance } => {
+                    client
+                        .command(&serial, GoXLRCommand::SetChannelBalance(*channel, *balance))
+                        .await?;
+                }
                 SubCommands::Volume {
                     channel,
                     volume_percent,
@@ -280,15 +718,54 @@ pub async fn run_cli() -> Result<()> {
                             .await?;
                     }
                 },
-                SubCommands::BleepVolume { volume_percent } => {
-                    // Ok, this is a value between -34 and 0, with 0 being loudest :D
-                    let value = (34 * *volume_percent as u16) / 100;
+                SubCommands::Privacy { command } => {
+                    let command = match command {
+                        PrivacyCommands::Enable {
+                            idle_relock,
+                            relock_on_screen_lock,
+                        } => GoXLRCommand::SetPrivacyMode(Some(PrivacyConfig {
+                            idle_relock: *idle_relock,
+                            relock_on_screen_lock: *relock_on_screen_lock,
+                        })),
+                        PrivacyCommands::Disable => GoXLRCommand::SetPrivacyMode(None),
+                        PrivacyCommands::Lock => GoXLRCommand::PrivacyLock(),
+                        PrivacyCommands::Unlock => GoXLRCommand::PrivacyUnlock(),
+                    };
                     client
-                        .command(
-                            &serial,
-                            GoXLRCommand::SetSwearButtonVolume(value as i8 - 34),
-                        )
-                        .await?;
+                        .command(&serial, command)
+                        .await
+                        .context("Unable to change Privacy Mode")?;
+                }
+                SubCommands::Headset { command } => {
+                    let command = match command {
+                        HeadsetCommands::Enable => GoXLRCommand::SetHeadsetMode(true),
+                        HeadsetCommands::Disable => GoXLRCommand::SetHeadsetMode(false),
+                        HeadsetCommands::Sidetone { sidetone } => {
+                            GoXLRCommand::SetHeadsetSidetone(*sidetone)
+                        }
+                    };
+                    client
+                        .command(&serial, command)
+                        .await
+                        .context("Unable to change Headset Mode")?;
+                }
+                SubCommands::BleepVolume {
+                    volume_percent,
+                    adjust,
+                } => {
+                    set_or_adjust(
+                        client.as_mut(),
+                        &serial,
+                        *volume_percent,
+                        *adjust,
+                        |percent| {
+                            // Ok, this is a value between -34 and 0, with 0 being loudest :D
+                            let value = (34 * percent as u16) / 100;
+                            GoXLRCommand::SetSwearButtonVolume(value as i8 - 34)
+                        },
+                        GoXLRCommand::AdjustBleepVolume,
+                    )
+                    .await?;
                 }
 
                 SubCommands::Lighting { command } => match command {
@@ -448,6 +925,73 @@ pub async fn run_cli() -> Result<()> {
                             )
                             .await?;
                     }
+                    LightingCommands::Reactive { command } => match command {
+                        ReactiveLightingCommands::Enable {
+                            base_colour,
+                            peak_colour,
+                            simple,
+                            buttons,
+                            sensitivity,
+                            decay,
+                        } => {
+                            let zones: Vec<ReactiveZone> = simple
+                                .iter()
+                                .map(|target| ReactiveZone::Simple(*target))
+                                .chain(buttons.iter().map(|button| ReactiveZone::Button(*button)))
+                                .collect();
+                            if zones.is_empty() {
+                                bail!("At least one --simple or --button target is needed");
+                            }
+
+                            let config = ReactiveConfig {
+                                zones,
+                                base_colour: base_colour.clone(),
+                                peak_colour: peak_colour.clone(),
+                                sensitivity: *sensitivity,
+                                decay: *decay,
+                            };
+                            client
+                                .command(&serial, GoXLRCommand::SetReactiveLighting(Some(config)))
+                                .await?;
+                        }
+                        ReactiveLightingCommands::Disable => {
+                            client
+                                .command(&serial, GoXLRCommand::SetReactiveLighting(None))
+                                .await?;
+                        }
+                    },
+                    LightingCommands::HighContrast { command } => match command {
+                        HighContrastCommands::Enable {
+                            active,
+                            inactive,
+                            groups,
+                        } => {
+                            let config = HighContrastConfig {
+                                levels: HighContrastLevels {
+                                    active: *active,
+                                    inactive: *inactive,
+                                },
+                                groups: groups
+                                    .iter()
+                                    .map(|(group, active, inactive)| {
+                                        let levels = HighContrastLevels {
+                                            active: *active,
+                                            inactive: *inactive,
+                                        };
+                                        (*group, levels)
+                                    })
+                                    .collect(),
+                            };
+                            client
+                                .command(&serial, GoXLRCommand::SetHighContrastMode(Some(config)))
+                                .await?;
+                        }
+                        HighContrastCommands::Disable => {
+                            client
+                                .command(&serial, GoXLRCommand::SetHighContrastMode(None))
+                                .await?;
+                        }
+                    },
                 },
 
                 SubCommands::Profiles { command } => match command {
@@ -464,17 +1008,27 @@ pub async fn run_cli() -> Result<()> {
                         ProfileAction::Load {
                             profile_name,
                             persist,
+                            force,
                         } => {
-                            client
-                                .command(
-                                    &serial,
-                                    GoXLRCommand::LoadProfile(
-                                        profile_name.to_string(),
-                                        persist.unwrap_or(true),
-                                    ),
-                                )
-                                .await
-                                .context("Unable to Load Profile")?;
+                            let persist = persist.unwrap_or(true);
+                            let command = |force| {
+                                GoXLRCommand::LoadProfile(profile_name.to_string(), persist, force)
+                            };
+
+                            let result = client.command(&serial, command(*force)).await;
+                            match result {
+                                Err(error) if is_unsaved_changes(&error) => {
+                                    if !io::stdin().is_terminal() {
+                                        bail!("{}, use --force to load anyway", error);
+                                    }
+                                    if !confirm(&format!("{}, load anyway?", error))? {
+                                        bail!("Profile not loaded");
+                                    }
+                                    client.command(&serial, command(true)).await
+                                }
+                                result => result,
+                            }
+                            .context("Unable to Load Profile")?;
                         }
                         ProfileAction::LoadColours { profile_name } => {
                             client
@@ -485,6 +1039,9 @@ pub async fn run_cli() -> Result<()> {
                                 .await
                                 .context("Unable to load Profile Colours")?;
                         }
+                        ProfileAction::LoadSection { .. } => {
+                            return Err(anyhow!("Only supported for Microphone Profiles"));
+                        }
                         ProfileAction::Save {} => {
                             client
                                 .command(&serial, GoXLRCommand::SaveProfile())
@@ -500,6 +1057,45 @@ pub async fn run_cli() -> Result<()> {
                                 .await
                                 .context("Unable to Save Profile")?;
                         }
+                        ProfileAction::Rename {
+                            profile_name,
+                            new_name,
+                        } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::RenameProfile(
+                                        profile_name.to_string(),
+                                        new_name.to_string(),
+                                    ),
+                                )
+                                .await
+                                .context("Unable to Rename Profile")?;
+                        }
+                        ProfileAction::ExportWindows { name } => {
+                            client
+                                .send(DaemonRequest::ExportWindowsProfile(
+                                    serial.clone(),
+                                    name.clone(),
+                                ))
+                                .await
+                                .context("Unable to Export Profile")?;
+                        }
+                        ProfileAction::Rebase { base_name } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::RebaseProfile(base_name.to_string()),
+                                )
+                                .await
+                                .context("Unable to Rebase Profile")?;
+                        }
+                        ProfileAction::ClearBase => {
+                            client
+                                .command(&serial, GoXLRCommand::ClearProfileBase())
+                                .await
+                                .context("Unable to Clear Profile Base")?;
+                        }
                     },
                     ProfileType::Microphone { command } => match command {
                         ProfileAction::New { profile_name } => {
@@ -514,6 +1110,7 @@ pub async fn run_cli() -> Result<()> {
                         ProfileAction::Load {
                             profile_name,
                             persist,
+                            ..
                         } => {
                             client
                                 .command(
@@ -529,6 +1126,21 @@ pub async fn run_cli() -> Result<()> {
                         ProfileAction::LoadColours { .. } => {
                             return Err(anyhow!("Not supported for Microphone"));
                         }
+                        ProfileAction::LoadSection {
+                            profile_name,
+                            section,
+                        } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::LoadMicProfileSection(
+                                        profile_name.to_string(),
+                                        *section,
+                                    ),
+                                )
+                                .await
+                                .context("Unable to Load Microphone Profile Section")?;
+                        }
                         ProfileAction::Save {} => {
                             client
                                 .command(&serial, GoXLRCommand::SaveMicProfile())
@@ -544,6 +1156,12 @@ pub async fn run_cli() -> Result<()> {
                                 .await
                                 .context("Unable to Save Microphone Profile")?;
                         }
+                        ProfileAction::ExportWindows { .. }
+                        | ProfileAction::Rename { .. }
+                        | ProfileAction::Rebase { .. }
+                        | ProfileAction::ClearBase => {
+                            return Err(anyhow!("Not supported for Microphone"));
+                        }
                     },
                 },
                 SubCommands::Effects { command } => match command {
@@ -575,6 +1193,43 @@ pub async fn run_cli() -> Result<()> {
                             .context("Unable to Save Preset")?;
                     }
 
+                    EffectsCommands::LoadEffectSection { section, name } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::LoadEffectSection(*section, name.to_string()),
+                            )
+                            .await
+                            .context("Unable to Load Effect Section")?;
+                    }
+
+                    EffectsCommands::SaveEffectSection { section, name } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SaveEffectSection(*section, name.to_string()),
+                            )
+                            .await
+                            .context("Unable to Save Effect Section")?;
+                    }
+
+                    EffectsCommands::Intensity { intensity } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetEffectsIntensity(*intensity))
+                            .await
+                            .context("Unable to Set Effects Intensity")?;
+                    }
+
+                    EffectsCommands::GlobalEncoder { encoder, enabled } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetEncoderGlobalMode(*encoder, *enabled),
+                            )
+                            .await
+                            .context("Unable to Set Encoder Global Mode")?;
+                    }
+
                     EffectsCommands::Reverb { command } => match command {
                         Reverb::Style { style } => {
                             client
@@ -903,6 +1558,17 @@ pub async fn run_cli() -> Result<()> {
                             .await
                             .context("Unable to Stop Sample Playback")?;
                     }
+                    SamplerCommands::SaveReplay { name } => {
+                        let capture = client
+                            .save_replay_buffer(&serial, name.clone())
+                            .await
+                            .context("Unable to save the Replay Buffer")?;
+                        println!(
+                            "Saved {:.1}s to {}",
+                            capture.duration_ms as f32 / 1000.,
+                            capture.path.to_string_lossy()
+                        );
+                    }
                     SamplerCommands::PlaybackMode { bank, button, mode } => {
                         client
                             .command(
@@ -925,6 +1591,39 @@ pub async fn run_cli() -> Result<()> {
                             .await
                             .context("Unable to set Play Order")?;
                     }
+                    SamplerCommands::Guard {
+                        bank,
+                        button,
+                        guarded,
+                    } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSampleButtonGuard(*bank, *button, *guarded),
+                            )
+                            .await
+                            .context("Unable to set the Sample Button Guard")?;
+                    }
+                    SamplerCommands::Duck {
+                        bank,
+                        button,
+                        channels,
+                        attenuation,
+                        release,
+                    } => {
+                        let config = (!channels.is_empty()).then(|| DuckConfig {
+                            channels: channels.clone(),
+                            attenuation: *attenuation,
+                            release: *release,
+                        });
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSampleDucking(*bank, *button, config),
+                            )
+                            .await
+                            .context("Unable to set Sample Ducking")?;
+                    }
                     SamplerCommands::StartPercent {
                         bank,
                         button,
@@ -963,6 +1662,55 @@ pub async fn run_cli() -> Result<()> {
                             .await
                             .context("Unable to set Stop Percent")?;
                     }
+                    SamplerCommands::StartMs {
+                        bank,
+                        button,
+                        sample_id,
+                        start_position,
+                    } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSampleStartMs(
+                                    *bank,
+                                    *button,
+                                    *sample_id,
+                                    *start_position,
+                                ),
+                            )
+                            .await
+                            .context("Unable to set Start Position")?;
+                    }
+                    SamplerCommands::StopMs {
+                        bank,
+                        button,
+                        sample_id,
+                        stop_position,
+                    } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSampleStopMs(
+                                    *bank,
+                                    *button,
+                                    *sample_id,
+                                    *stop_position,
+                                ),
+                            )
+                            .await
+                            .context("Unable to set Stop Position")?;
+                    }
+                    SamplerCommands::NormaliseBank { bank, target } => {
+                        client
+                            .command(&serial, GoXLRCommand::NormaliseBank(*bank, *target))
+                            .await
+                            .context("Unable to Normalise Bank")?;
+                    }
+                    SamplerCommands::CancelNormalise => {
+                        client
+                            .command(&serial, GoXLRCommand::CancelNormaliseBank())
+                            .await?;
+                    }
                 },
                 SubCommands::Submix { command } => match command {
                     SubmixCommands::Enabled { enabled } => {
@@ -987,6 +1735,19 @@ pub async fn run_cli() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetSubMixLinked(*channel, *linked))
                             .await?;
                     }
+                    SubmixCommands::LinkAll { linked } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSubMixLinkAll(*linked))
+                            .await?;
+                    }
+                    SubmixCommands::DefaultLinked { channel, linked } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSubMixDefaultLinked(*channel, *linked),
+                            )
+                            .await?;
+                    }
                     SubmixCommands::OutputMix { device, mix } => {
                         client
                             .command(&serial, GoXLRCommand::SetSubMixOutputMix(*device, *mix))
@@ -998,7 +1759,39 @@ pub async fn run_cli() -> Result<()> {
                             .await?;
                     }
                 },
+                SubCommands::VolumeScenes { command } => match command {
+                    VolumeSceneCommands::Save { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SaveVolumeScene(name.to_string()))
+                            .await
+                            .context("Unable to Save Volume Scene")?;
+                    }
+                    VolumeSceneCommands::Load { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::LoadVolumeScene(name.to_string()))
+                            .await
+                            .context("Unable to Load Volume Scene")?;
+                    }
+                    VolumeSceneCommands::Delete { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::DeleteVolumeScene(name.to_string()))
+                            .await
+                            .context("Unable to Delete Volume Scene")?;
+                    }
+                },
+                SubCommands::HardwareState => {
+                    let report = client.get_hardware_state(&serial).await?;
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+                SubCommands::Settle { timeout } => {
+                    client.settle(&serial, *timeout).await?;
+                }
                 SubCommands::Settings { command } => match command {
+                    DeviceSettings::AdoptHardwareState { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetAdoptHardwareState(*enabled))
+                            .await?;
+                    }
                     DeviceSettings::MuteHoldDuration { duration } => {
                         client
                             .command(&serial, GoXLRCommand::SetMuteHoldDuration(*duration))
@@ -1012,6 +1805,16 @@ pub async fn run_cli() -> Result<()> {
                             )
                             .await?;
                     }
+                    DeviceSettings::SampleClearTimeout { seconds } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSampleClearTimeout(*seconds))
+                            .await?;
+                    }
+                    DeviceSettings::SampleConversion { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSampleConversion(*enabled))
+                            .await?;
+                    }
                     DeviceSettings::MonitorWithFx { enabled } => {
                         client
                             .command(&serial, GoXLRCommand::SetMonitorWithFx(*enabled))
@@ -1022,12 +1825,80 @@ pub async fn run_cli() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetVCMuteAlsoMuteCM(*enabled))
                             .await?;
                     }
+                    DeviceSettings::ChatMuteOutputs { outputs } => {
+                        let command = GoXLRCommand::SetVCMuteAdditionalOutputs(outputs.clone());
+                        client.command(&serial, command).await?;
+                    }
                     DeviceSettings::LockFaders { enabled } => {
                         client
                             .command(&serial, GoXLRCommand::SetLockFaders(*enabled))
                             .await?;
                     }
+                    DeviceSettings::PhantomConfirmationRequired { enabled } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetPhantomConfirmationRequired(*enabled),
+                            )
+                            .await?;
+                    }
+                    DeviceSettings::SilentFaders { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSilentFaders(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::EffectPreviewOnHold { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetEffectPreviewOnHold(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::ClearEffectsOnHold { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetClearEffectsOnHold(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::SwitchMonitorOnUnplug { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSwitchMonitorOnUnplug(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::FineFaderAdjust { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetFineFaderAdjust(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::PollInterval { milliseconds } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetPollInterval(*milliseconds))
+                            .await?;
+                    }
+                    DeviceSettings::StallTimeout { seconds } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetStallTimeout(*seconds))
+                            .await?;
+                    }
+                    DeviceSettings::SaveOnShutdown { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSaveOnShutdown(*enabled))
+                            .await?;
+                    }
                 },
+                SubCommands::Audio { .. }
+                | SubCommands::Defaults { .. }
+                | SubCommands::Data { .. }
+                | SubCommands::Log { .. }
+                | SubCommands::Icons { .. }
+                | SubCommands::Midi { .. }
+                | SubCommands::Telemetry { .. }
+                | SubCommands::ProfileRules { .. }
+                | SubCommands::MuteSync { .. }
+                | SubCommands::HttpRateLimit { .. }
+                | SubCommands::Clients { .. }
+                | SubCommands::GuardMode { .. }
+                | SubCommands::Health
+                | SubCommands::SupportBundle { .. }
+                | SubCommands::Watch { .. }
+                | SubCommands::Tui => {}
             }
         }
     }
@@ -1078,6 +1949,278 @@ fn print_device(device: &MixerStatus) {
     print_mixer_info(device);
 }
 
+fn is_unsaved_changes(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<CommandError>(),
+        Some(CommandError::UnsavedChanges)
+    )
+}
+
+pub fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_eq_curve_report(report: &EqCurveReport) {
+    for filter in &report.filters {
+        match &filter.result {
+            EqCurveFilterResult::Mapped(band) => {
+                println!("{}Hz ({:+}dB) -> {}Hz", filter.frequency, filter.gain, band)
+            }
+            EqCurveFilterResult::Dropped(reason) => println!(
+                "{}Hz ({:+}dB) dropped: {}",
+                filter.frequency, filter.gain, reason
+            ),
+        }
+    }
+
+    println!("Applied Gains:");
+    for band in &report.gains {
+        let clamped = if band.clamped { " (clamped)" } else { "" };
+        println!("  {}Hz: {:+}dB{}", band.frequency, band.gain, clamped);
+    }
+
+    if let Some(preamp) = report.preamp {
+        println!("The curve's preamp of {}dB has not been applied", preamp);
+    }
+}
+
+fn print_recovered_defaults(result: &RecoveredDefaults) {
+    for file in &result.created {
+        println!("Restored: {}", file);
+    }
+    for file in &result.skipped {
+        println!("Skipped (already exists): {}", file);
+    }
+    for (file, error) in &result.failed {
+        println!("Failed: {} ({})", file, error);
+    }
+}
+
+/// Sends either an absolute value, or a relative adjustment (printing the value it resulted in).
+async fn set_or_adjust<T>(
+    client: &mut dyn Client,
+    serial: &str,
+    value: Option<T>,
+    adjust: Option<i8>,
+    set: fn(T) -> GoXLRCommand,
+    adjustment: fn(i8) -> GoXLRCommand,
+) -> Result<()> {
+    match (value, adjust) {
+        (_, Some(delta)) => {
+            let value = client.adjust(serial, adjustment(delta)).await?;
+            println!("{}", value);
+        }
+        (Some(value), None) => client.command(serial, set(value)).await?,
+        (None, None) => bail!("Either a value or an adjustment is required"),
+    }
+    Ok(())
+}
+
+async fn wait_for_migration(client: &mut Box<dyn Client>) -> Result<()> {
+    loop {
+        sleep(Duration::from_millis(250)).await;
+        client.poll_status().await?;
+
+        let progress = match &client.status().config.data_migration.progress {
+            Some(progress) => progress.clone(),
+            None => bail!("The daemon isn't reporting the migration's progress"),
+        };
+
+        match progress.state {
+            MigrationState::Copying => print!(
+                "\rCopying: {}/{}",
+                progress.files_copied, progress.files_total
+            ),
+            MigrationState::Verifying => print!(
+                "\rVerifying: {}/{}",
+                progress.files_verified, progress.files_total
+            ),
+            MigrationState::Complete => {
+                println!(
+                    "\rMigration Complete, {} files copied",
+                    progress.files_total
+                );
+                for renamed in &progress.renamed {
+                    println!(
+                        "A different file already existed, copied as {}",
+                        renamed.to_string_lossy()
+                    );
+                }
+                return Ok(());
+            }
+            MigrationState::Failed(error) => {
+                println!();
+                bail!("Migration Failed: {}", error);
+            }
+        }
+        io::stdout().flush()?;
+    }
+}
+
+fn get_midi_control(control: MidiControlType, channel: u8, number: u8) -> MidiControl {
+    match control {
+        MidiControlType::Cc => MidiControl::ControlChange {
+            channel,
+            controller: number,
+        },
+        MidiControlType::Note => MidiControl::Note {
+            channel,
+            note: number,
+        },
+    }
+}
+
+fn profile_trigger(trigger: ProfileRuleTrigger) -> ProfileTrigger {
+    match trigger {
+        ProfileRuleTrigger::FirmwareChanged => ProfileTrigger::FirmwareChanged,
+        ProfileRuleTrigger::FirstConnectOfDay => ProfileTrigger::FirstConnectOfDay,
+        ProfileRuleTrigger::OnConnect => ProfileTrigger::OnConnect,
+    }
+}
+
+fn print_clients(clients: &[ConnectedClient]) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+
+    for client in clients {
+        println!(
+            "{}: {:?} from {}, connected {}s ago, {} requests ({} commands)",
+            client.id,
+            client.transport,
+            client.address.as_deref().unwrap_or("this machine"),
+            (now - client.connected_at).max(0),
+            client.requests,
+            client.commands,
+        );
+    }
+}
+
+fn print_health(status: &DaemonStatus) {
+    let config = &status.config;
+    println!("Daemon Version: {}", config.daemon_version);
+    println!("Uptime: {}s", status.session.uptime_secs);
+    println!("IPC Socket: {}", config.socket_path);
+
+    let driver = &config.driver_interface;
+    match &driver.status {
+        DriverStatus::Ok => println!("Driver: {:?} {}", driver.interface, driver.version),
+        DriverStatus::NotInstalled => println!("Driver: {:?} not installed", driver.interface),
+        DriverStatus::Outdated { required } => println!(
+            "Driver: {:?} {} is too old, {} is required",
+            driver.interface, driver.version, required
+        ),
+    }
+
+    if config.startup_warnings.is_empty() {
+        println!("Startup Problems: None");
+    } else {
+        println!("Startup Problems:");
+        for warning in &config.startup_warnings {
+            println!("  {}: {}", warning.check, warning.message);
+        }
+    }
+
+    if let Some(warning) = &config.ui_version_warning {
+        println!("Web UI: {}", warning);
+    }
+
+    println!("Log Level: {:?}", config.log_level);
+    if let Some(filter) = &config.log_filter {
+        println!("Log Filter: {}", filter);
+    }
+
+    for (application, mute_sync) in &config.mute_sync {
+        let state = match &mute_sync.state {
+            MuteSyncState::Error(error) => format!("Error: {}", error),
+            state => format!("{:?}", state),
+        };
+        println!("Mute Sync ({:?}): {}", application, state);
+    }
+
+    if let Some(midi) = &config.midi {
+        let state = match &midi.state {
+            MidiState::Error(error) => format!("Error: {}", error),
+            state => format!("{:?}", state),
+        };
+        println!("MIDI: {}", state);
+    }
+
+    if let Some(telemetry) = &config.telemetry {
+        let state = match telemetry.enabled {
+            true => "Enabled",
+            false => "Disabled",
+        };
+        println!("Usage Statistics: {}", state);
+    }
+
+    for (serial, mixer) in &status.mixers {
+        let session = &mixer.session;
+        println!(
+            "Device {}: {} reconnects, {} commands processed",
+            serial, session.reconnect_count, session.commands_processed
+        );
+    }
+
+    let previous = match &config.previous_run {
+        Some(previous) => previous,
+        None => {
+            println!("Last Run: Unknown");
+            return;
+        }
+    };
+
+    let outcome = match &previous.outcome {
+        PreviousRunOutcome::Stopped(ShutdownReason::UserRequest) => String::from("stopped by user"),
+        PreviousRunOutcome::Stopped(ShutdownReason::OsSignal) => {
+            String::from("stopped by the system")
+        }
+        PreviousRunOutcome::Stopped(ShutdownReason::Error(error)) => {
+            format!("stopped with an error: {}", error)
+        }
+        PreviousRunOutcome::Crashed => match &previous.panic {
+            Some(panic) => format!("crashed: {}", panic),
+            None => String::from("crashed (or was killed)"),
+        },
+    };
+
+    match previous.duration_secs {
+        Some(duration) => println!("Last Run: {} after {}s", outcome, duration),
+        None => println!("Last Run: {}", outcome),
+    }
+}
+
+fn print_audio_devices(devices: &AudioDevices) {
+    println!("Audio Inputs:");
+    for input in &devices.inputs {
+        println!("  {}", input);
+    }
+    println!("Audio Outputs:");
+    for output in &devices.outputs {
+        println!("  {}", output);
+    }
+    println!(
+        "Sampler Input: {}",
+        devices
+            .current_sampler_input
+            .as_deref()
+            .unwrap_or("Not Found")
+    );
+    println!(
+        "Sampler Output: {}",
+        devices
+            .current_sampler_output
+            .as_deref()
+            .unwrap_or("Not Found")
+    );
+}
+
 fn print_usb_info(usb: &UsbProductInformation) {
     println!(
         "USB Device version: {}.{}.{}",
@@ -1101,6 +2244,12 @@ fn print_mixer_info(mixer: &MixerStatus) {
         mixer.hardware.manufactured_date
     );
     println!("Mixer profile: {}", mixer.profile_name);
+    if let Some(inheritance) = &mixer.profile_inheritance {
+        println!("Profile based on: {}", inheritance.chain.join(" -> "));
+        for (section, source) in &inheritance.sources {
+            println!("Profile {} from: {}", section, source);
+        }
+    }
 
     for fader in FaderName::iter() {
         println!(
@@ -1130,8 +2279,14 @@ fn print_mixer_info(mixer: &MixerStatus) {
         }
     }
 
+    // On the Mini, the Sampler output may be carrying the VOD mix, so name it as such.
+    let output_name = |output: OutputDevice| match &mixer.vod_output {
+        Some(vod) if vod.output == output => format!("{} ({})", vod.label, output),
+        _ => output.to_string(),
+    };
+
     let max_col_len = OutputDevice::iter()
-        .map(|s| s.to_string().len())
+        .map(|s| output_name(s).len())
         .max()
         .unwrap_or_default();
     let mut table_width = max_col_len + 1;
@@ -1145,14 +2300,54 @@ fn print_mixer_info(mixer: &MixerStatus) {
     println!("{}", "-".repeat(table_width));
 
     for output in OutputDevice::iter() {
-        let row_name = output.to_string();
+        let row_name = output_name(output);
         print!("|{}{}|", " ".repeat(max_col_len - row_name.len()), row_name,);
         for input in InputDevice::iter() {
             let col_name = input.to_string();
             let len = col_name.len() + 1;
-            print!("{}X{} ", " ".repeat(len / 2), " ".repeat(len - (len / 2)));
+            let cell = if mixer.router[input][output] {
+                "X"
+            } else {
+                " "
+            };
+            print!(
+                "{}{}{} ",
+                " ".repeat(len / 2),
+                cell,
+                " ".repeat(len - (len / 2))
+            );
         }
         println!();
     }
+    for output in OutputDevice::iter() {
+        let activity = &mixer.output_activity[output];
+        if activity.likely_silent {
+            let reason = if activity.inputs.is_empty() {
+                "nothing is routed to it"
+            } else if activity.all_muted {
+                "everything routed to it is muted"
+            } else {
+                "the volumes are at zero"
+            };
+            println!(
+                "Warning: {} is likely silent, {}",
+                output_name(output),
+                reason
+            );
+        }
+    }
+    if let Some(vod) = &mixer.vod_output {
+        if !vod.routable {
+            println!(
+                "The {} mix follows the Broadcast Mix (without Music)",
+                vod.label
+            );
+            if vod.routable_inputs.contains(&InputDevice::Samples) {
+                let samples = mixer.router[InputDevice::Samples][vod.output];
+                let state = if samples { "sent to" } else { "kept off" };
+                println!("Samples are {} the {} mix", state, vod.label);
+            }
+        }
+    }
     println!("{}", "-".repeat(table_width));
 }
@@ -0,0 +1,303 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use goxlr_ipc::client::Client;
+use goxlr_ipc::{GoXLRCommand, MixerStatus};
+use goxlr_types::{FaderName, MuteState};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc;
+use tokio::time::{interval, MissedTickBehavior};
+
+// Subscribing to events would stop us sending commands on this connection, so poll instead
+const STATUS_POLL: Duration = Duration::from_secs(1);
+
+// How far a single key press moves a fader, as a percentage
+const VOLUME_STEP: u8 = 5;
+
+// Smaller than this and the fader columns can't be drawn usefully
+const MIN_WIDTH: u16 = 48;
+const MIN_HEIGHT: u16 = 12;
+
+struct App {
+    selected: usize,
+    picker: Option<ListState>,
+    message: Option<String>,
+}
+
+enum Action {
+    None,
+    Command(GoXLRCommand),
+    Quit,
+}
+
+/// Runs the mixer board until the user quits. The terminal is put back how it was found on
+/// exit, including when something panics.
+pub async fn run_tui(client: &mut Box<dyn Client>, serial: &str) -> Result<()> {
+    let terminal = ratatui::try_init().context("Unable to set up the terminal")?;
+    let result = run(terminal, client, serial).await;
+    ratatui::try_restore().context("Unable to restore the terminal")?;
+    result
+}
+
+async fn run(
+    mut terminal: DefaultTerminal,
+    client: &mut Box<dyn Client>,
+    serial: &str,
+) -> Result<()> {
+    let mut app = App {
+        selected: 0,
+        picker: None,
+        message: None,
+    };
+
+    let mut events = spawn_event_reader();
+    let mut poll = interval(STATUS_POLL);
+    poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let status = client.status();
+        let profiles = &status.files.profiles;
+        let mixer = status
+            .mixers
+            .get(serial)
+            .with_context(|| format!("{} is no longer connected", serial))?;
+        terminal.draw(|frame| draw(frame, &mut app, mixer, profiles))?;
+
+        tokio::select! {
+            _ = poll.tick() => {
+                client.poll_status().await?;
+            }
+            event = events.recv() => {
+                let event = event.context("Lost the terminal's input")??;
+                let action = match event {
+                    Event::Key(key) if key.kind != KeyEventKind::Release => {
+                        handle_key(&mut app, mixer, profiles, key)
+                    }
+                    _ => Action::None,
+                };
+
+                match action {
+                    Action::None => {}
+                    Action::Quit => return Ok(()),
+                    Action::Command(command) => {
+                        app.message = client
+                            .command(serial, command)
+                            .await
+                            .err()
+                            .map(|e| format!("{:#}", e));
+                        client.poll_status().await?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// crossterm only offers blocking reads, so they happen on their own thread which stops once
+/// the receiver has gone.
+fn spawn_event_reader() -> mpsc::Receiver<Result<Event>> {
+    let (tx, rx) = mpsc::channel(16);
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            let event = match event::poll(Duration::from_millis(100)) {
+                Ok(false) => continue,
+                Ok(true) => event::read().map_err(Into::into),
+                Err(e) => Err(e.into()),
+            };
+            let failed = event.is_err();
+            if tx.blocking_send(event).is_err() || failed {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn handle_key(app: &mut App, mixer: &MixerStatus, profiles: &[String], key: KeyEvent) -> Action {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Action::Quit;
+    }
+
+    if let Some(picker) = &mut app.picker {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p') => app.picker = None,
+            KeyCode::Up | KeyCode::Char('k') => picker.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => picker.select_next(),
+            KeyCode::Enter => {
+                let profile = picker.selected().and_then(|index| profiles.get(index));
+                if let Some(profile) = profile {
+                    let command = GoXLRCommand::LoadProfile(profile.clone(), true, false);
+                    app.picker = None;
+                    return Action::Command(command);
+                }
+            }
+            _ => {}
+        }
+        return Action::None;
+    }
+
+    let fader = FaderName::iter().nth(app.selected).unwrap_or(FaderName::A);
+    let status = &mixer.fader_status[fader];
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Action::Quit,
+        KeyCode::Left | KeyCode::Char('h') => app.selected = app.selected.saturating_sub(1),
+        KeyCode::Right | KeyCode::Char('l') => {
+            app.selected = (app.selected + 1).min(FaderName::iter().count() - 1)
+        }
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Down | KeyCode::Char('j') => {
+            let percent = volume_percent(mixer.levels.volumes[status.channel]);
+            let percent = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => percent.saturating_add(VOLUME_STEP).min(100),
+                _ => percent.saturating_sub(VOLUME_STEP),
+            };
+            let volume = (255 * percent as u16) / 100;
+            return Action::Command(GoXLRCommand::SetVolume(status.channel, volume as u8));
+        }
+        KeyCode::Char('m') => {
+            let state = match status.mute_state {
+                MuteState::Unmuted => MuteState::MutedToX,
+                MuteState::MutedToX | MuteState::MutedToAll => MuteState::Unmuted,
+            };
+            return Action::Command(GoXLRCommand::SetFaderMuteState(fader, state));
+        }
+        KeyCode::Char('p') => {
+            let current = profiles
+                .iter()
+                .position(|profile| profile == &mixer.profile_name);
+            app.picker = Some(ListState::default().with_selected(current.or(Some(0))));
+        }
+        _ => {}
+    }
+    Action::None
+}
+
+fn volume_percent(volume: u8) -> u8 {
+    ((volume as u16 * 100 + 127) / 255) as u8
+}
+
+fn draw(frame: &mut Frame, app: &mut App, mixer: &MixerStatus, profiles: &[String]) {
+    let area = frame.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        let message = format!(
+            "Terminal too small ({}x{}), needs at least {}x{}. Press q to quit.",
+            area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+        );
+        frame.render_widget(Paragraph::new(message), area);
+        return;
+    }
+
+    let [header, board, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+
+    let title = format!(
+        "GoXLR {} - Profile: {}",
+        mixer.hardware.serial_number, mixer.profile_name
+    );
+    frame.render_widget(
+        Paragraph::new(title).style(Style::new().add_modifier(Modifier::BOLD)),
+        header,
+    );
+
+    let columns = Layout::horizontal(FaderName::iter().map(|_| Constraint::Fill(1))).split(board);
+    for (index, (fader, column)) in FaderName::iter().zip(columns.iter()).enumerate() {
+        draw_fader(frame, mixer, fader, *column, index == app.selected);
+    }
+
+    let footer_text = match &app.message {
+        Some(message) => Line::from(Span::styled(message.clone(), Style::new().fg(Color::Red))),
+        None => Line::from("←→/hl select  ↑↓/jk volume  m mute  p profiles  q quit"),
+    };
+    frame.render_widget(Paragraph::new(footer_text), footer);
+
+    if let Some(picker) = &mut app.picker {
+        draw_picker(frame, profiles, picker, board);
+    }
+}
+
+fn draw_fader(
+    frame: &mut Frame,
+    mixer: &MixerStatus,
+    fader: FaderName,
+    area: Rect,
+    selected: bool,
+) {
+    let status = &mixer.fader_status[fader];
+    let border = match selected {
+        true => Style::new().fg(Color::Yellow),
+        false => Style::new(),
+    };
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_style(border)
+        .title(format!(" {:?}: {:?} ", fader, status.channel));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let muted = status.mute_state != MuteState::Unmuted;
+    let mute_text = match status.mute_state {
+        MuteState::Unmuted => String::from("Unmuted"),
+        MuteState::MutedToAll => String::from("Muted"),
+        MuteState::MutedToX => format!("{:?}", status.mute_type),
+    };
+    let percent = volume_percent(mixer.levels.volumes[status.channel]);
+
+    // The two lines under the bar hold the volume and mute state
+    let bar_height = inner.height.saturating_sub(2);
+    let filled = (bar_height as u32 * percent as u32 / 100) as u16;
+    let bar_style = match muted {
+        true => Style::new().fg(Color::DarkGray),
+        false => Style::new().fg(Color::Green),
+    };
+    let bar_width = inner.width.saturating_sub(2) as usize;
+
+    let mut lines: Vec<Line> = (0..bar_height)
+        .map(|row| match bar_height - row <= filled {
+            true => Line::from(Span::styled("█".repeat(bar_width), bar_style)).centered(),
+            false => Line::from(""),
+        })
+        .collect();
+    lines.push(Line::from(format!("{}%", percent)).centered());
+
+    let mute_style = match muted {
+        true => Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+        false => Style::new(),
+    };
+    lines.push(Line::from(Span::styled(mute_text, mute_style)).centered());
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_picker(frame: &mut Frame, profiles: &[String], picker: &mut ListState, area: Rect) {
+    let width = (area.width / 2).max(MIN_WIDTH / 2);
+    let height = (profiles.len() as u16 + 2).clamp(3, area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = profiles
+        .iter()
+        .map(|profile| ListItem::new(profile.as_str()))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(" Load Profile (Enter) "),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, picker);
+}
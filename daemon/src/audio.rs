@@ -2,22 +2,66 @@ use crate::{OVERRIDE_SAMPLER_INPUT, OVERRIDE_SAMPLER_OUTPUT};
 use anyhow::{anyhow, bail, Result};
 use enum_map::EnumMap;
 use fancy_regex::Regex;
+use goxlr_audio::cache::{SampleCache, SampleCacheStats};
+use goxlr_audio::convert::{convert_sample, is_converted_format};
+use goxlr_audio::markers::AudioMarker;
+use goxlr_audio::monitor::{OutputChange, OutputMonitor};
 use goxlr_audio::player::{Player, PlayerState};
 use goxlr_audio::recorder::BufferedRecorder;
 use goxlr_audio::recorder::RecorderState;
-use goxlr_audio::{get_audio_inputs, AtomicF64};
+use goxlr_audio::server::{ServerChange, ServerWatch};
+use goxlr_audio::{get_audio_inputs, get_audio_outputs, AtomicF64};
+use goxlr_ipc::{AudioDevices, AudioOutputStatus};
 use goxlr_types::SampleBank;
 use goxlr_types::SampleButtons;
 use log::{debug, error, info, warn};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use strum::IntoEnumIterator;
 
+// Looking for the output device means asking the audio backend for every device it has, so this
+// is done much less often than playback is checked.
+const OUTPUT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Lists the devices currently available from the audio backend, and which of them the sampler
+/// would pick. This is done fresh on each call, so newly connected devices will show up.
+pub fn get_audio_devices() -> AudioDevices {
+    let inputs = get_audio_inputs();
+    let outputs = get_audio_outputs();
+
+    AudioDevices {
+        current_sampler_input: find_matching_device(
+            &inputs,
+            &AudioHandler::get_input_device_patterns(),
+        ),
+        current_sampler_output: find_matching_device(
+            &outputs,
+            &AudioHandler::get_output_device_patterns(),
+        ),
+        inputs,
+        outputs,
+    }
+}
+
+fn find_matching_device(devices: &[String], patterns: &[Regex]) -> Option<String> {
+    devices
+        .iter()
+        .find(|device| {
+            patterns.iter().any(|pattern| {
+                if let Ok(result) = pattern.is_match(device) {
+                    return result;
+                }
+                false
+            })
+        })
+        .cloned()
+}
+
 #[derive(Debug)]
 pub struct AudioHandler {
     output_device: Option<String>,
@@ -25,9 +69,14 @@ pub struct AudioHandler {
     buffered_input: Option<Arc<BufferedRecorder>>,
 
     last_device_check: Option<Instant>,
+    last_output_check: Option<Instant>,
+    output_monitor: OutputMonitor,
+    output_status: AudioOutputStatus,
+    server_watch: ServerWatch,
     active_streams: EnumMap<SampleBank, EnumMap<SampleButtons, Option<StateManager>>>,
 
     process_task: Option<ProcessTask>,
+    sample_cache: Arc<SampleCache>,
 }
 
 pub struct AudioFile {
@@ -45,6 +94,9 @@ pub struct ProcessTask {
     button: SampleButtons,
     file: PathBuf,
 
+    // Set once the file has been converted, if it's being converted
+    converted: Arc<Mutex<Option<PathBuf>>>,
+
     player: AudioPlaybackState,
 }
 
@@ -102,7 +154,7 @@ impl AudioRecordingState {
 }
 
 impl AudioHandler {
-    pub fn new(recorder_buffer: u16) -> Result<Self> {
+    pub fn new(recorder_buffer: u16, cache_path: PathBuf) -> Result<Self> {
         // Find the Input Device..
         let mut handler = Self {
             output_device: None,
@@ -110,9 +162,14 @@ impl AudioHandler {
             buffered_input: None,
 
             last_device_check: None,
+            last_output_check: None,
+            output_monitor: OutputMonitor::default(),
+            output_status: AudioOutputStatus::default(),
+            server_watch: ServerWatch::start(),
             active_streams: EnumMap::default(),
 
             process_task: None,
+            sample_cache: Arc::new(SampleCache::new(cache_path)),
         };
 
         // Immediately initialise the recorder, and let it try to handle stuff.
@@ -150,7 +207,7 @@ impl AudioHandler {
         Ok(())
     }
 
-    fn get_output_device_patterns(&self) -> Vec<Regex> {
+    fn get_output_device_patterns() -> Vec<Regex> {
         let override_output = OVERRIDE_SAMPLER_OUTPUT.lock().unwrap().deref().clone();
         if let Some(device) = override_output {
             return vec![Regex::new(&device).expect("Invalid Regex in Audio Handler")];
@@ -169,7 +226,7 @@ impl AudioHandler {
         patterns
     }
 
-    fn get_input_device_patterns(&self) -> Vec<Regex> {
+    fn get_input_device_patterns() -> Vec<Regex> {
         let override_input = OVERRIDE_SAMPLER_INPUT.lock().unwrap().deref().clone();
         if let Some(device) = override_input {
             return vec![Regex::new(&device).expect("Invalid Regex in Audio Handler")];
@@ -217,26 +274,16 @@ impl AudioHandler {
         }
 
         let device_list = match is_output {
-            true => goxlr_audio::get_audio_outputs(),
+            true => get_audio_outputs(),
             false => get_audio_inputs(),
         };
 
         let pattern_matchers = match is_output {
-            true => self.get_output_device_patterns(),
-            false => self.get_input_device_patterns(),
+            true => Self::get_output_device_patterns(),
+            false => Self::get_input_device_patterns(),
         };
 
-        let device = device_list
-            .iter()
-            .find(|output| {
-                pattern_matchers.iter().any(|pattern| {
-                    if let Ok(result) = pattern.is_match(output) {
-                        return result;
-                    }
-                    false
-                })
-            })
-            .cloned();
+        let device = find_matching_device(&device_list, &pattern_matchers);
 
         if let Some(device) = &device {
             debug!("Found Device: {}", device);
@@ -250,6 +297,91 @@ impl AudioHandler {
         }
     }
 
+    /// Follows the output device as it's unplugged and reconnected, stopping any samples which
+    /// were playing to it when it goes. Returns true if the device has changed.
+    pub fn check_output_device(&mut self) -> bool {
+        // There's nothing to find until the server is back
+        if !self.server_watch.is_connected() {
+            return false;
+        }
+
+        if let Some(last_check) = self.last_output_check {
+            if last_check.elapsed() < OUTPUT_CHECK_INTERVAL {
+                return false;
+            }
+        }
+        self.last_output_check = Some(Instant::now());
+
+        let device =
+            find_matching_device(&get_audio_outputs(), &Self::get_output_device_patterns());
+        let change = self.output_monitor.update(device.clone());
+        self.output_status.device.clone_from(&device);
+
+        let change = match change {
+            Some(change) => change,
+            None => return false,
+        };
+
+        if let OutputChange::Lost(_) = change {
+            warn!("{}, stopping any playing samples", change);
+            self.output_status.times_lost += 1;
+            for bank in SampleBank::iter() {
+                for button in SampleButtons::iter() {
+                    if let Some(playback) = self.get_playback(bank, button) {
+                        playback.state.stopping.store(true, Ordering::Relaxed);
+                        playback.state.force_stop.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        } else {
+            info!("{}", change);
+        }
+
+        // Whatever happened, new samples should go to the device we can see now
+        self.output_device = device;
+        self.output_status.last_change = Some(change.to_string());
+        true
+    }
+
+    /// Reports the sound server going away or coming back. Any streams from before it went are
+    /// dead, so the caller needs to stop them when it's lost, and rebuild the recorder once it's
+    /// restored.
+    pub fn check_server(&mut self) -> Option<ServerChange> {
+        let change = self.server_watch.poll()?;
+        match change {
+            ServerChange::Lost => {
+                warn!("{}", change);
+                self.output_status.server_lost = true;
+                self.output_status.server_interruptions += 1;
+            }
+            ServerChange::Restored => {
+                info!("{}", change);
+                self.output_status.server_lost = false;
+
+                // Devices may have been renamed by the restart, look again straight away
+                self.output_device = None;
+                self.last_device_check = None;
+                self.last_output_check = None;
+            }
+        }
+        Some(change)
+    }
+
+    pub fn get_output_status(&self) -> AudioOutputStatus {
+        self.output_status.clone()
+    }
+
+    fn get_playback(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+    ) -> Option<&mut AudioPlaybackState> {
+        match &mut self.active_streams[bank][button] {
+            Some(manager) => manager.playback.as_mut(),
+            None => None,
+        }
+    }
+
     pub async fn check_playing(&mut self) -> bool {
         let mut state_changed = false;
 
@@ -305,6 +437,15 @@ impl AudioHandler {
         false
     }
 
+    pub fn get_recording_peak(&self, bank: SampleBank, button: SampleButtons) -> Option<f64> {
+        if let Some(stream) = &self.active_streams[bank][button] {
+            if let Some(recording) = &stream.recording {
+                return recording.state.get_peak_db();
+            }
+        }
+        None
+    }
+
     pub fn is_sample_recording(&self) -> bool {
         for bank in SampleBank::iter() {
             for button in SampleButtons::iter() {
@@ -340,6 +481,10 @@ impl AudioHandler {
         audio: AudioFile,
         loop_track: bool,
     ) -> Result<()> {
+        if !self.server_watch.is_connected() {
+            bail!("Unable to play Sample, the Sound Server is unavailable");
+        }
+
         if self.output_device.is_none() {
             self.find_device(true);
         }
@@ -350,17 +495,43 @@ impl AudioHandler {
                 false => None,
             };
 
+            // If we have a decoded copy of this sample, play from that instead..
+            let mut player = None;
+            if let Some(cached) = self.sample_cache.get(&audio.file) {
+                match Player::new(
+                    &cached,
+                    Some(output_device.clone()),
+                    fade_duration,
+                    audio.start_pct,
+                    audio.stop_pct,
+                    audio.gain,
+                ) {
+                    Ok(cached_player) => player = Some(cached_player),
+                    Err(e) => {
+                        debug!("Unable to use cached sample, discarding: {}", e);
+                        self.sample_cache.invalidate(&cached);
+                    }
+                }
+            } else {
+                self.cache_sample(&audio.file);
+            }
+
             // Ok, we need to grab and configure the player..
-            let mut player = Player::new(
-                &audio.file,
-                Some(output_device.clone()),
-                fade_duration,
-                audio.start_pct,
-                audio.stop_pct,
-                audio.gain,
-            )?;
-
-            let state = player.get_state();
+            let mut player = match player {
+                Some(player) => player,
+                None => Player::new(
+                    &audio.file,
+                    Some(output_device.clone()),
+                    fade_duration,
+                    audio.start_pct,
+                    audio.stop_pct,
+                    audio.gain,
+                )?,
+            };
+
+            // Report the original file as playing, regardless of where it's being read from.
+            let mut state = player.get_state();
+            state.playing_file = audio.file.clone();
             let handler = thread::spawn(move || {
                 if !loop_track {
                     let result = player.play();
@@ -390,6 +561,38 @@ impl AudioHandler {
         Ok(())
     }
 
+    pub fn configure_sample_cache(&self, enabled: bool, limit_mb: u32) {
+        self.sample_cache.configure(enabled, limit_mb);
+    }
+
+    /// Decodes a sample into the cache in the background, failures here are harmless as playback
+    /// will simply continue to use the original file.
+    pub fn cache_sample(&self, file: &Path) {
+        if !self.sample_cache.is_enabled() {
+            return;
+        }
+
+        let cache = self.sample_cache.clone();
+        let file = file.to_path_buf();
+        thread::spawn(move || {
+            if let Err(e) = cache.store(&file) {
+                debug!("Unable to cache sample {:?}: {}", file, e);
+            }
+        });
+    }
+
+    pub fn get_sample_cache_stats(&self) -> SampleCacheStats {
+        self.sample_cache.get_stats()
+    }
+
+    pub fn is_sample_cache_enabled(&self) -> bool {
+        self.sample_cache.is_enabled()
+    }
+
+    pub fn clear_sample_cache(&self) -> Result<()> {
+        self.sample_cache.clear()
+    }
+
     pub async fn restart_for_button(
         &mut self,
         bank: SampleBank,
@@ -462,6 +665,7 @@ impl AudioHandler {
         path: PathBuf,
         bank: SampleBank,
         button: SampleButtons,
+        low_cut: Option<u8>,
     ) -> Result<()> {
         if let Some(recorder) = &self.buffered_input {
             if !recorder.is_ready() {
@@ -478,6 +682,8 @@ impl AudioHandler {
             let state = RecorderState {
                 stop: Arc::new(AtomicBool::new(false)),
                 gain: Arc::new(AtomicF64::new(1.)),
+                peak: Arc::new(AtomicF64::new(0.)),
+                clipped: Arc::new(AtomicBool::new(false)),
             };
 
             let inner_recorder = recorder.clone();
@@ -485,7 +691,7 @@ impl AudioHandler {
             let inner_state = state.clone();
 
             let handler = thread::spawn(move || {
-                let result = inner_recorder.record(&inner_path, inner_state);
+                let result = inner_recorder.record(&inner_path, inner_state, low_cut);
                 if result.is_err() {
                     error!("Error: {}", result.err().unwrap());
                 }
@@ -507,11 +713,19 @@ impl AudioHandler {
         Ok(())
     }
 
+    /// Saves the last few seconds heard by the sampler, without needing a button.
+    pub fn save_replay_buffer(&self, path: &Path, low_cut: Option<u8>) -> Result<Duration> {
+        match &self.buffered_input {
+            Some(recorder) => recorder.save_buffer(path, low_cut),
+            None => bail!("No valid Input Device was Found"),
+        }
+    }
+
     pub fn stop_record(
         &mut self,
         bank: SampleBank,
         button: SampleButtons,
-    ) -> Result<Option<(String, f64)>> {
+    ) -> Result<Option<RecordingResult>> {
         let mut file = None;
 
         if let Some(player) = &mut self.active_streams[bank][button] {
@@ -531,8 +745,13 @@ impl AudioHandler {
                 // Recording Complete, check the file was made...
                 if recording_state.file.exists() {
                     if let Some(file_name) = recording_state.file.file_name() {
-                        let gain = recording_state.state.gain.load(Ordering::Relaxed);
-                        file.replace((String::from(file_name.to_string_lossy()), gain));
+                        let state = &recording_state.state;
+                        file.replace(RecordingResult {
+                            file_name: String::from(file_name.to_string_lossy()),
+                            gain: state.gain.load(Ordering::Relaxed),
+                            peak: state.get_peak_db(),
+                            clipped: state.clipped.load(Ordering::Relaxed),
+                        });
                     } else {
                         bail!("Unable to Extract Filename from Path! (This shouldn't be possible!)")
                     }
@@ -547,11 +766,16 @@ impl AudioHandler {
         Ok(file)
     }
 
+    /// Calculates the gain of a sample in the background. With a conversion directory, the sample
+    /// is first converted into it (unless it's already in the converted format), and the gain is
+    /// calculated from the conversion.
     pub fn calculate_gain_thread(
         &mut self,
         path: PathBuf,
         bank: SampleBank,
         button: SampleButtons,
+        loudness_target: f64,
+        convert_to: Option<PathBuf>,
     ) -> Result<()> {
         if self.process_task.is_some() {
             bail!("Sample already being processed");
@@ -559,12 +783,33 @@ impl AudioHandler {
 
         // Create the player..
         let mut player = Player::new(&path, None, None, None, None, None)?;
+        player.set_loudness_target(loudness_target);
 
         // Grab the State..
         let state = player.get_state();
+        let converted = Arc::new(Mutex::new(None));
+
+        let source = path.clone();
+        let progress = state.progress.clone();
+        let cancel = state.force_stop.clone();
+        let error = state.error.clone();
+        let conversion = converted.clone();
 
         // Spawn the Thread and Grab the Handler..
         let handler = thread::spawn(move || {
+            if let Some(directory) = convert_to.filter(|_| !is_converted_format(&source)) {
+                let file = convert_sample(&source, &directory, &progress, &cancel)
+                    .and_then(|file| player.decode_from(&file).map(|_| file));
+
+                match file {
+                    Ok(file) => *conversion.lock().unwrap() = Some(file),
+                    Err(e) => {
+                        *error.lock().unwrap() = Some(format!("Unable to Convert Sample: {}", e));
+                        return;
+                    }
+                }
+                progress.store(0, Ordering::Relaxed);
+            }
             player.calculate_gain();
         });
 
@@ -573,6 +818,7 @@ impl AudioHandler {
             bank,
             button,
             file: path,
+            converted,
             player: AudioPlaybackState {
                 handle: Some(handler),
                 state,
@@ -582,6 +828,15 @@ impl AudioHandler {
         Ok(())
     }
 
+    /// Stops any calculation in progress, discarding its result.
+    pub fn cancel_calculation(&mut self) {
+        if let Some(mut task) = self.process_task.take() {
+            task.player.state.stopping.store(true, Ordering::Relaxed);
+            task.player.state.force_stop.store(true, Ordering::Relaxed);
+            task.player.wait();
+        }
+    }
+
     pub fn is_calculating(&self) -> bool {
         self.process_task.is_some()
     }
@@ -629,9 +884,12 @@ impl AudioHandler {
             result = CalculationResult {
                 result: task_result,
                 file: task.file.clone(),
+                converted: task.converted.lock().unwrap().clone(),
                 bank: task.bank,
                 button: task.button,
                 gain: task.player.state.calculated_gain.load(Ordering::Relaxed),
+                markers: task.player.state.markers.lock().unwrap().clone(),
+                duration: *task.player.state.duration.lock().unwrap(),
             };
         } else {
             bail!("Unable to obtain Task");
@@ -654,7 +912,19 @@ impl Drop for AudioHandler {
 pub struct CalculationResult {
     pub result: Result<()>,
     pub file: PathBuf,
+
+    // Where the file was converted to, if it was
+    pub converted: Option<PathBuf>,
     pub bank: SampleBank,
     pub button: SampleButtons,
     pub gain: f64,
+    pub markers: Vec<AudioMarker>,
+    pub duration: Option<Duration>,
+}
+
+pub struct RecordingResult {
+    pub file_name: String,
+    pub gain: f64,
+    pub peak: Option<f64>,
+    pub clipped: bool,
 }
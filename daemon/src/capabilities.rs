@@ -0,0 +1,579 @@
+use goxlr_ipc::{DeviceFeature, GoXLRCommand};
+use goxlr_types::{AnimationMode, Button, ButtonColourGroups, SimpleColourTargets};
+
+const NONE: &[DeviceFeature] = &[];
+const FULL_SIZE: &[DeviceFeature] = &[DeviceFeature::FullSize];
+const ANIMATIONS: &[DeviceFeature] = &[DeviceFeature::Animations];
+const FULL_SIZE_ANIMATIONS: &[DeviceFeature] =
+    &[DeviceFeature::Animations, DeviceFeature::FullSize];
+const SUBMIXES: &[DeviceFeature] = &[DeviceFeature::Submixes];
+const HEADPHONE_EQ: &[DeviceFeature] = &[DeviceFeature::HeadphoneEq];
+
+/// What a command needs from the device before it can be run, anything which isn't available
+/// causes the command to be refused before the profile or the device are touched. There's
+/// deliberately no catch-all here, new commands have to be placed somewhere.
+pub fn required_features(command: &GoXLRCommand) -> &'static [DeviceFeature] {
+    match command {
+        // Settings, profile handling and everything the Mini has (the faders, mute, cough and
+        // bleep buttons, routing and the microphone chain)
+        GoXLRCommand::SetShutdownCommands(..)
+        | GoXLRCommand::SetSleepCommands(..)
+        | GoXLRCommand::SetWakeCommands(..)
+        | GoXLRCommand::SetFader(..)
+        | GoXLRCommand::SetFaderMuteFunction(..)
+        | GoXLRCommand::SetVolume(..)
+        | GoXLRCommand::SaveVolumeScene(..)
+        | GoXLRCommand::LoadVolumeScene(..)
+        | GoXLRCommand::DeleteVolumeScene(..)
+        | GoXLRCommand::SetMicrophoneType(..)
+        | GoXLRCommand::ConfirmMicrophoneType(..)
+        | GoXLRCommand::SetMicrophoneGain(..)
+        | GoXLRCommand::SetRouter(..)
+        | GoXLRCommand::SetChannelBalance(..)
+        | GoXLRCommand::SetCoughMuteFunction(..)
+        | GoXLRCommand::SetCoughIsHold(..)
+        | GoXLRCommand::SetSwearButtonVolume(..)
+        | GoXLRCommand::SetEqMiniGain(..)
+        | GoXLRCommand::SetEqMiniFreq(..)
+        | GoXLRCommand::SetGateThreshold(..)
+        | GoXLRCommand::SetGateAttenuation(..)
+        | GoXLRCommand::SetGateAttack(..)
+        | GoXLRCommand::SetGateRelease(..)
+        | GoXLRCommand::SetGateActive(..)
+        | GoXLRCommand::SetCompressorThreshold(..)
+        | GoXLRCommand::SetCompressorRatio(..)
+        | GoXLRCommand::SetCompressorAttack(..)
+        | GoXLRCommand::SetCompressorReleaseTime(..)
+        | GoXLRCommand::SetCompressorMakeupGain(..)
+        | GoXLRCommand::SetElementDisplayMode(..)
+        | GoXLRCommand::SetDeeser(..)
+        | GoXLRCommand::AdjustGateThreshold(..)
+        | GoXLRCommand::AdjustCompressorThreshold(..)
+        | GoXLRCommand::AdjustCompressorMakeupGain(..)
+        | GoXLRCommand::AdjustDeEsser(..)
+        | GoXLRCommand::AdjustBleepVolume(..)
+        | GoXLRCommand::SetGlobalColour(..)
+        | GoXLRCommand::SetReactiveLighting(..)
+        | GoXLRCommand::SetHighContrastMode(..)
+        | GoXLRCommand::SetFaderDisplayStyle(..)
+        | GoXLRCommand::SetFaderColours(..)
+        | GoXLRCommand::SetAllFaderColours(..)
+        | GoXLRCommand::SetAllFaderDisplayStyle(..)
+        | GoXLRCommand::NewProfile(..)
+        | GoXLRCommand::LoadProfile(..)
+        | GoXLRCommand::LoadProfileColours(..)
+        | GoXLRCommand::SaveProfile(..)
+        | GoXLRCommand::SaveProfileAs(..)
+        | GoXLRCommand::DeleteProfile(..)
+        | GoXLRCommand::RenameProfile(..)
+        | GoXLRCommand::RebaseProfile(..)
+        | GoXLRCommand::ClearProfileBase(..)
+        | GoXLRCommand::ReloadSettings(..)
+        | GoXLRCommand::NewMicProfile(..)
+        | GoXLRCommand::LoadMicProfile(..)
+        | GoXLRCommand::LoadMicProfileSection(..)
+        | GoXLRCommand::SaveMicProfile(..)
+        | GoXLRCommand::SaveMicProfileAs(..)
+        | GoXLRCommand::DeleteMicProfile(..)
+        | GoXLRCommand::SetMuteHoldDuration(..)
+        | GoXLRCommand::SetVCMuteAlsoMuteCM(..)
+        | GoXLRCommand::SetVCMuteAdditionalOutputs(..)
+        | GoXLRCommand::SetLockFaders(..)
+        | GoXLRCommand::SetSilentFaders(..)
+        | GoXLRCommand::SetVodMode(..)
+        | GoXLRCommand::SetSwitchMonitorOnUnplug(..)
+        | GoXLRCommand::SetSaveOnShutdown(..)
+        | GoXLRCommand::SetAdoptHardwareState(..)
+        | GoXLRCommand::SetPhantomConfirmationRequired(..)
+        | GoXLRCommand::SetFineFaderAdjust(..)
+        | GoXLRCommand::SetPollInterval(..)
+        | GoXLRCommand::SetStallTimeout(..)
+        | GoXLRCommand::SetPrivacyMode(..)
+        | GoXLRCommand::SetHeadsetMode(..)
+        | GoXLRCommand::SetHeadsetSidetone(..)
+        | GoXLRCommand::SetFaderMuteState(..)
+        | GoXLRCommand::SetCoughMuteState(..)
+        | GoXLRCommand::PrivacyLock(..)
+        | GoXLRCommand::PrivacyUnlock(..) => NONE,
+
+        // The Sampler, Effects, Encoders and Scribbles, and the settings which only affect them
+        GoXLRCommand::SetSamplerPreBufferDuration(..)
+        | GoXLRCommand::SetInputLowCut(..)
+        | GoXLRCommand::SetEqGain(..)
+        | GoXLRCommand::SetEqFreq(..)
+        | GoXLRCommand::SetEncoderColour(..)
+        | GoXLRCommand::SetSampleColour(..)
+        | GoXLRCommand::SetSampleOffStyle(..)
+        | GoXLRCommand::LoadEffectPreset(..)
+        | GoXLRCommand::RenameActivePreset(..)
+        | GoXLRCommand::SaveActivePreset(..)
+        | GoXLRCommand::LoadEffectSection(..)
+        | GoXLRCommand::SaveEffectSection(..)
+        | GoXLRCommand::SetEffectsIntensity(..)
+        | GoXLRCommand::SetReverbStyle(..)
+        | GoXLRCommand::SetReverbAmount(..)
+        | GoXLRCommand::SetReverbDecay(..)
+        | GoXLRCommand::SetReverbEarlyLevel(..)
+        | GoXLRCommand::SetReverbTailLevel(..)
+        | GoXLRCommand::SetReverbPreDelay(..)
+        | GoXLRCommand::SetReverbLowColour(..)
+        | GoXLRCommand::SetReverbHighColour(..)
+        | GoXLRCommand::SetReverbHighFactor(..)
+        | GoXLRCommand::SetReverbDiffuse(..)
+        | GoXLRCommand::SetReverbModSpeed(..)
+        | GoXLRCommand::SetReverbModDepth(..)
+        | GoXLRCommand::SetEchoStyle(..)
+        | GoXLRCommand::SetEchoAmount(..)
+        | GoXLRCommand::SetEchoFeedback(..)
+        | GoXLRCommand::SetEchoTempo(..)
+        | GoXLRCommand::SetEchoDelayLeft(..)
+        | GoXLRCommand::SetEchoDelayRight(..)
+        | GoXLRCommand::SetEchoFeedbackLeft(..)
+        | GoXLRCommand::SetEchoFeedbackRight(..)
+        | GoXLRCommand::SetEchoFeedbackXFBLtoR(..)
+        | GoXLRCommand::SetEchoFeedbackXFBRtoL(..)
+        | GoXLRCommand::SetPitchStyle(..)
+        | GoXLRCommand::SetPitchAmount(..)
+        | GoXLRCommand::SetPitchCharacter(..)
+        | GoXLRCommand::SetGenderStyle(..)
+        | GoXLRCommand::SetGenderAmount(..)
+        | GoXLRCommand::SetEncoderGlobalMode(..)
+        | GoXLRCommand::SetMegaphoneStyle(..)
+        | GoXLRCommand::SetMegaphoneAmount(..)
+        | GoXLRCommand::SetMegaphonePostGain(..)
+        | GoXLRCommand::SetRobotStyle(..)
+        | GoXLRCommand::SetRobotGain(..)
+        | GoXLRCommand::SetRobotFreq(..)
+        | GoXLRCommand::SetRobotWidth(..)
+        | GoXLRCommand::SetRobotWaveform(..)
+        | GoXLRCommand::SetRobotPulseWidth(..)
+        | GoXLRCommand::SetRobotThreshold(..)
+        | GoXLRCommand::SetRobotDryMix(..)
+        | GoXLRCommand::SetHardTuneStyle(..)
+        | GoXLRCommand::SetHardTuneAmount(..)
+        | GoXLRCommand::SetHardTuneRate(..)
+        | GoXLRCommand::SetHardTuneWindow(..)
+        | GoXLRCommand::SetHardTuneSource(..)
+        | GoXLRCommand::ClearSampleProcessError(..)
+        | GoXLRCommand::SetSamplerFunction(..)
+        | GoXLRCommand::SetSamplerOrder(..)
+        | GoXLRCommand::SetSampleButtonGuard(..)
+        | GoXLRCommand::SetSampleDucking(..)
+        | GoXLRCommand::AddSample(..)
+        | GoXLRCommand::SetSampleStartPercent(..)
+        | GoXLRCommand::SetSampleStopPercent(..)
+        | GoXLRCommand::SetSampleStartMs(..)
+        | GoXLRCommand::SetSampleStopMs(..)
+        | GoXLRCommand::RemoveSampleByIndex(..)
+        | GoXLRCommand::PlaySampleByIndex(..)
+        | GoXLRCommand::PlayNextSample(..)
+        | GoXLRCommand::StopSamplePlayback(..)
+        | GoXLRCommand::NormaliseBank(..)
+        | GoXLRCommand::CancelNormaliseBank(..)
+        | GoXLRCommand::SetScribbleIcon(..)
+        | GoXLRCommand::SetScribbleGlyph(..)
+        | GoXLRCommand::SetScribbleText(..)
+        | GoXLRCommand::SetScribbleNumber(..)
+        | GoXLRCommand::SetScribbleNumberMode(..)
+        | GoXLRCommand::SetScribbleInvert(..)
+        | GoXLRCommand::SetMonitorWithFx(..)
+        | GoXLRCommand::SetSamplerResetOnClear(..)
+        | GoXLRCommand::SetSampleClearTimeout(..)
+        | GoXLRCommand::SetSampleConversion(..)
+        | GoXLRCommand::SetEffectPreviewOnHold(..)
+        | GoXLRCommand::SetClearEffectsOnHold(..)
+        | GoXLRCommand::SetActiveEffectPreset(..)
+        | GoXLRCommand::SetActiveSamplerBank(..)
+        | GoXLRCommand::SetMegaphoneEnabled(..)
+        | GoXLRCommand::SetRobotEnabled(..)
+        | GoXLRCommand::SetHardTuneEnabled(..)
+        | GoXLRCommand::SetFXEnabled(..) => FULL_SIZE,
+
+        // Lighting, which depends on what's being lit
+        GoXLRCommand::SetButtonColours(button, ..) | GoXLRCommand::SetButtonOffStyle(button, _) => {
+            match is_mini_button(*button) {
+                true => NONE,
+                false => FULL_SIZE,
+            }
+        }
+        GoXLRCommand::SetButtonGroupColours(group, ..)
+        | GoXLRCommand::SetButtonGroupOffStyle(group, _) => match group {
+            ButtonColourGroups::FaderMute => NONE,
+            ButtonColourGroups::EffectSelector | ButtonColourGroups::EffectTypes => FULL_SIZE,
+        },
+        GoXLRCommand::SetSimpleColour(target, _) => match target {
+            SimpleColourTargets::Global | SimpleColourTargets::Accent => NONE,
+            SimpleColourTargets::Scribble1
+            | SimpleColourTargets::Scribble2
+            | SimpleColourTargets::Scribble3
+            | SimpleColourTargets::Scribble4 => FULL_SIZE,
+        },
+
+        // Firmware dependant
+        GoXLRCommand::SetAnimationMode(mode) => match mode {
+            AnimationMode::Ripple => FULL_SIZE_ANIMATIONS,
+            _ => ANIMATIONS,
+        },
+        GoXLRCommand::SetAnimationMod1(..)
+        | GoXLRCommand::SetAnimationMod2(..)
+        | GoXLRCommand::SetAnimationWaterfall(..) => ANIMATIONS,
+
+        GoXLRCommand::SetSubMixEnabled(..)
+        | GoXLRCommand::SetSubMixVolume(..)
+        | GoXLRCommand::SetSubMixLinked(..)
+        | GoXLRCommand::SetSubMixOutputMix(..)
+        | GoXLRCommand::SetSubMixDefaultLinked(..)
+        | GoXLRCommand::SetSubMixLinkAll(..)
+        | GoXLRCommand::SetMonitorMix(..) => SUBMIXES,
+
+        // Not available on any device yet, see DeviceCapabilities::headphone_eq
+        GoXLRCommand::SetHeadphoneEqGain(..) => HEADPHONE_EQ,
+    }
+}
+
+fn is_mini_button(button: Button) -> bool {
+    matches!(
+        button,
+        Button::Fader1Mute
+            | Button::Fader2Mute
+            | Button::Fader3Mute
+            | Button::Fader4Mute
+            | Button::Bleep
+            | Button::Cough
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use goxlr_ipc::{status_schema, CommandError, PrivacyConfig, ScribbleNumberMode};
+    use goxlr_types::{
+        ButtonColourOffStyle, ChannelName, CompressorAttackTime, CompressorRatio,
+        CompressorReleaseTime, DisplayMode, DisplayModeComponents, EchoStyle, EffectBankPresets,
+        EffectSection, EncoderColourTargets, EncoderName, EqFrequencies, FaderDisplayStyle,
+        FaderName, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle, InputDevice,
+        MegaphoneStyle, MicProfileSection, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction,
+        MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle, SampleBank,
+        SampleButtons, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, VersionNumber,
+        VodMode, WaterfallDirection,
+    };
+    use serde_json::Value;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::device::Device;
+    use crate::mock_device::{mini, settings, MockGoXLR};
+
+    // Everything the status reports, except the diagnostics which change with every command
+    async fn snapshot(device: &Device<'_>) -> Value {
+        let mut status = serde_json::to_value(device.status().await).unwrap();
+        status.as_object_mut().unwrap().remove("diagnostics");
+        status
+    }
+
+    fn variant_name(command: &GoXLRCommand) -> String {
+        let debug = format!("{:?}", command);
+        match debug.split_once('(') {
+            Some((name, _)) => name.to_string(),
+            None => debug,
+        }
+    }
+
+    // At least one of every command, and both sides of the ones which depend on their arguments
+    fn all_commands() -> Vec<GoXLRCommand> {
+        let colour = || String::from("FF0000");
+        let name = || String::from("Missing");
+        let (bank, button) = (SampleBank::A, SampleButtons::TopLeft);
+
+        vec![
+            GoXLRCommand::SetShutdownCommands(vec![]),
+            GoXLRCommand::SetSleepCommands(vec![]),
+            GoXLRCommand::SetWakeCommands(vec![]),
+            GoXLRCommand::SetSamplerPreBufferDuration(0),
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic),
+            GoXLRCommand::SetFaderMuteFunction(FaderName::A, MuteFunction::All),
+            GoXLRCommand::SetVolume(ChannelName::Mic, 100),
+            GoXLRCommand::SaveVolumeScene(name()),
+            GoXLRCommand::LoadVolumeScene(name()),
+            GoXLRCommand::DeleteVolumeScene(name()),
+            GoXLRCommand::SetMicrophoneType(MicrophoneType::Dynamic),
+            GoXLRCommand::ConfirmMicrophoneType(MicrophoneType::Condenser),
+            GoXLRCommand::SetMicrophoneGain(MicrophoneType::Dynamic, 20),
+            GoXLRCommand::SetRouter(InputDevice::Microphone, OutputDevice::Headphones, true),
+            GoXLRCommand::SetInputLowCut(InputDevice::Microphone, Some(80)),
+            GoXLRCommand::SetChannelBalance(ChannelName::LineIn, -10),
+            GoXLRCommand::SetCoughMuteFunction(MuteFunction::All),
+            GoXLRCommand::SetCoughIsHold(true),
+            GoXLRCommand::SetSwearButtonVolume(-20),
+            GoXLRCommand::SetEqMiniGain(MiniEqFrequencies::Equalizer90Hz, 1),
+            GoXLRCommand::SetEqMiniFreq(MiniEqFrequencies::Equalizer90Hz, 100.0),
+            GoXLRCommand::SetEqGain(EqFrequencies::Equalizer31Hz, 1),
+            GoXLRCommand::SetEqFreq(EqFrequencies::Equalizer31Hz, 40.0),
+            GoXLRCommand::SetHeadphoneEqGain(EqFrequencies::Equalizer31Hz, 1),
+            GoXLRCommand::SetGateThreshold(-30),
+            GoXLRCommand::SetGateAttenuation(50),
+            GoXLRCommand::SetGateAttack(GateTimes::Gate10ms),
+            GoXLRCommand::SetGateRelease(GateTimes::Gate10ms),
+            GoXLRCommand::SetGateActive(true),
+            GoXLRCommand::SetCompressorThreshold(-10),
+            GoXLRCommand::SetCompressorRatio(CompressorRatio::Ratio1_0),
+            GoXLRCommand::SetCompressorAttack(CompressorAttackTime::Comp0ms),
+            GoXLRCommand::SetCompressorReleaseTime(CompressorReleaseTime::Comp0ms),
+            GoXLRCommand::SetCompressorMakeupGain(2),
+            GoXLRCommand::SetElementDisplayMode(
+                DisplayModeComponents::NoiseGate,
+                DisplayMode::Simple,
+            ),
+            GoXLRCommand::SetDeeser(10),
+            GoXLRCommand::AdjustGateThreshold(1),
+            GoXLRCommand::AdjustCompressorThreshold(1),
+            GoXLRCommand::AdjustCompressorMakeupGain(1),
+            GoXLRCommand::AdjustDeEsser(1),
+            GoXLRCommand::AdjustBleepVolume(1),
+            GoXLRCommand::SetAnimationMode(AnimationMode::Simple),
+            GoXLRCommand::SetAnimationMode(AnimationMode::Ripple),
+            GoXLRCommand::SetAnimationMod1(10),
+            GoXLRCommand::SetAnimationMod2(10),
+            GoXLRCommand::SetAnimationWaterfall(WaterfallDirection::Down),
+            GoXLRCommand::SetGlobalColour(colour()),
+            GoXLRCommand::SetFaderDisplayStyle(FaderName::A, FaderDisplayStyle::TwoColour),
+            GoXLRCommand::SetFaderColours(FaderName::A, colour(), colour()),
+            GoXLRCommand::SetAllFaderColours(colour(), colour()),
+            GoXLRCommand::SetAllFaderDisplayStyle(FaderDisplayStyle::TwoColour),
+            GoXLRCommand::SetButtonColours(Button::Cough, colour(), None),
+            GoXLRCommand::SetButtonColours(Button::EffectFx, colour(), None),
+            GoXLRCommand::SetButtonOffStyle(Button::Bleep, ButtonColourOffStyle::Dimmed),
+            GoXLRCommand::SetButtonOffStyle(Button::SamplerClear, ButtonColourOffStyle::Dimmed),
+            GoXLRCommand::SetButtonGroupColours(ButtonColourGroups::FaderMute, colour(), None),
+            GoXLRCommand::SetButtonGroupColours(ButtonColourGroups::EffectTypes, colour(), None),
+            GoXLRCommand::SetButtonGroupOffStyle(
+                ButtonColourGroups::FaderMute,
+                ButtonColourOffStyle::Dimmed,
+            ),
+            GoXLRCommand::SetButtonGroupOffStyle(
+                ButtonColourGroups::EffectSelector,
+                ButtonColourOffStyle::Dimmed,
+            ),
+            GoXLRCommand::SetSimpleColour(SimpleColourTargets::Accent, colour()),
+            GoXLRCommand::SetSimpleColour(SimpleColourTargets::Scribble1, colour()),
+            GoXLRCommand::SetReactiveLighting(None),
+            GoXLRCommand::SetHighContrastMode(None),
+            GoXLRCommand::SetEncoderColour(
+                EncoderColourTargets::Reverb,
+                colour(),
+                colour(),
+                colour(),
+            ),
+            GoXLRCommand::SetSampleColour(
+                SamplerColourTargets::SamplerSelectA,
+                colour(),
+                colour(),
+                colour(),
+            ),
+            GoXLRCommand::SetSampleOffStyle(
+                SamplerColourTargets::SamplerSelectA,
+                ButtonColourOffStyle::Dimmed,
+            ),
+            GoXLRCommand::LoadEffectPreset(name()),
+            GoXLRCommand::RenameActivePreset(name()),
+            GoXLRCommand::SaveActivePreset(),
+            GoXLRCommand::LoadEffectSection(EffectSection::Reverb, name()),
+            GoXLRCommand::SaveEffectSection(EffectSection::Reverb, name()),
+            GoXLRCommand::SetEffectsIntensity(50),
+            GoXLRCommand::SetReverbStyle(ReverbStyle::Library),
+            GoXLRCommand::SetReverbAmount(10),
+            GoXLRCommand::SetReverbDecay(100),
+            GoXLRCommand::SetReverbEarlyLevel(-10),
+            GoXLRCommand::SetReverbTailLevel(-10),
+            GoXLRCommand::SetReverbPreDelay(10),
+            GoXLRCommand::SetReverbLowColour(0),
+            GoXLRCommand::SetReverbHighColour(0),
+            GoXLRCommand::SetReverbHighFactor(0),
+            GoXLRCommand::SetReverbDiffuse(0),
+            GoXLRCommand::SetReverbModSpeed(0),
+            GoXLRCommand::SetReverbModDepth(0),
+            GoXLRCommand::SetEchoStyle(EchoStyle::Quarter),
+            GoXLRCommand::SetEchoAmount(10),
+            GoXLRCommand::SetEchoFeedback(10),
+            GoXLRCommand::SetEchoTempo(100),
+            GoXLRCommand::SetEchoDelayLeft(100),
+            GoXLRCommand::SetEchoDelayRight(100),
+            GoXLRCommand::SetEchoFeedbackLeft(10),
+            GoXLRCommand::SetEchoFeedbackRight(10),
+            GoXLRCommand::SetEchoFeedbackXFBLtoR(10),
+            GoXLRCommand::SetEchoFeedbackXFBRtoL(10),
+            GoXLRCommand::SetPitchStyle(PitchStyle::Narrow),
+            GoXLRCommand::SetPitchAmount(0),
+            GoXLRCommand::SetPitchCharacter(10),
+            GoXLRCommand::SetGenderStyle(GenderStyle::Narrow),
+            GoXLRCommand::SetGenderAmount(0),
+            GoXLRCommand::SetEncoderGlobalMode(EncoderName::Gender, true),
+            GoXLRCommand::SetMegaphoneStyle(MegaphoneStyle::Megaphone),
+            GoXLRCommand::SetMegaphoneAmount(10),
+            GoXLRCommand::SetMegaphonePostGain(0),
+            GoXLRCommand::SetRobotStyle(RobotStyle::Robot1),
+            GoXLRCommand::SetRobotGain(RobotRange::Low, 0),
+            GoXLRCommand::SetRobotFreq(RobotRange::Low, 10),
+            GoXLRCommand::SetRobotWidth(RobotRange::Low, 10),
+            GoXLRCommand::SetRobotWaveform(0),
+            GoXLRCommand::SetRobotPulseWidth(10),
+            GoXLRCommand::SetRobotThreshold(-10),
+            GoXLRCommand::SetRobotDryMix(-10),
+            GoXLRCommand::SetHardTuneStyle(HardTuneStyle::Natural),
+            GoXLRCommand::SetHardTuneAmount(10),
+            GoXLRCommand::SetHardTuneRate(10),
+            GoXLRCommand::SetHardTuneWindow(100),
+            GoXLRCommand::SetHardTuneSource(HardTuneSource::All),
+            GoXLRCommand::ClearSampleProcessError(),
+            GoXLRCommand::SetSamplerFunction(bank, button, SamplePlaybackMode::PlayNext),
+            GoXLRCommand::SetSamplerOrder(bank, button, SamplePlayOrder::Sequential),
+            GoXLRCommand::SetSampleButtonGuard(bank, button, true),
+            GoXLRCommand::SetSampleDucking(bank, button, None),
+            GoXLRCommand::AddSample(bank, button, name()),
+            GoXLRCommand::SetSampleStartPercent(bank, button, 0, 10.0),
+            GoXLRCommand::SetSampleStopPercent(bank, button, 0, 90.0),
+            GoXLRCommand::SetSampleStartMs(bank, button, 0, 10),
+            GoXLRCommand::SetSampleStopMs(bank, button, 0, 90),
+            GoXLRCommand::RemoveSampleByIndex(bank, button, 0),
+            GoXLRCommand::PlaySampleByIndex(bank, button, 0),
+            GoXLRCommand::PlayNextSample(bank, button),
+            GoXLRCommand::StopSamplePlayback(bank, button),
+            GoXLRCommand::NormaliseBank(bank, -18.0),
+            GoXLRCommand::CancelNormaliseBank(),
+            GoXLRCommand::SetScribbleIcon(FaderName::A, None),
+            GoXLRCommand::SetScribbleGlyph(FaderName::A, String::new()),
+            GoXLRCommand::SetScribbleText(FaderName::A, name()),
+            GoXLRCommand::SetScribbleNumber(FaderName::A, String::from("1")),
+            GoXLRCommand::SetScribbleNumberMode(FaderName::A, ScribbleNumberMode::Static(name())),
+            GoXLRCommand::SetScribbleInvert(FaderName::A, true),
+            GoXLRCommand::NewProfile(name()),
+            GoXLRCommand::LoadProfile(name(), false, false),
+            GoXLRCommand::LoadProfileColours(name()),
+            GoXLRCommand::SaveProfile(),
+            GoXLRCommand::SaveProfileAs(name()),
+            GoXLRCommand::DeleteProfile(name()),
+            GoXLRCommand::RenameProfile(name(), name()),
+            GoXLRCommand::RebaseProfile(name()),
+            GoXLRCommand::ClearProfileBase(),
+            GoXLRCommand::ReloadSettings(),
+            GoXLRCommand::NewMicProfile(name()),
+            GoXLRCommand::LoadMicProfile(name(), false),
+            GoXLRCommand::LoadMicProfileSection(name(), MicProfileSection::Microphone),
+            GoXLRCommand::SaveMicProfile(),
+            GoXLRCommand::SaveMicProfileAs(name()),
+            GoXLRCommand::DeleteMicProfile(name()),
+            GoXLRCommand::SetMuteHoldDuration(500),
+            GoXLRCommand::SetVCMuteAlsoMuteCM(true),
+            GoXLRCommand::SetVCMuteAdditionalOutputs(vec![OutputDevice::LineOut]),
+            GoXLRCommand::SetMonitorWithFx(true),
+            GoXLRCommand::SetSamplerResetOnClear(true),
+            GoXLRCommand::SetSampleClearTimeout(1000),
+            GoXLRCommand::SetSampleConversion(true),
+            GoXLRCommand::SetLockFaders(true),
+            GoXLRCommand::SetSilentFaders(true),
+            GoXLRCommand::SetVodMode(VodMode::Routable),
+            GoXLRCommand::SetEffectPreviewOnHold(true),
+            GoXLRCommand::SetClearEffectsOnHold(true),
+            GoXLRCommand::SetSwitchMonitorOnUnplug(true),
+            GoXLRCommand::SetSaveOnShutdown(true),
+            GoXLRCommand::SetAdoptHardwareState(true),
+            GoXLRCommand::SetPhantomConfirmationRequired(true),
+            GoXLRCommand::SetFineFaderAdjust(true),
+            GoXLRCommand::SetPollInterval(20),
+            GoXLRCommand::SetStallTimeout(5),
+            GoXLRCommand::SetPrivacyMode(Some(PrivacyConfig::default())),
+            GoXLRCommand::SetHeadsetMode(true),
+            GoXLRCommand::SetHeadsetSidetone(30),
+            GoXLRCommand::SetActiveEffectPreset(EffectBankPresets::Preset1),
+            GoXLRCommand::SetActiveSamplerBank(SampleBank::B),
+            GoXLRCommand::SetMegaphoneEnabled(true),
+            GoXLRCommand::SetRobotEnabled(true),
+            GoXLRCommand::SetHardTuneEnabled(true),
+            GoXLRCommand::SetFXEnabled(true),
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll),
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToAll),
+            GoXLRCommand::PrivacyLock(),
+            GoXLRCommand::PrivacyUnlock(),
+            GoXLRCommand::SetSubMixEnabled(true),
+            GoXLRCommand::SetSubMixVolume(ChannelName::Mic, 100),
+            GoXLRCommand::SetSubMixLinked(ChannelName::Mic, false),
+            GoXLRCommand::SetSubMixOutputMix(OutputDevice::Headphones, Mix::B),
+            GoXLRCommand::SetSubMixDefaultLinked(ChannelName::Music, false),
+            GoXLRCommand::SetSubMixLinkAll(false),
+            GoXLRCommand::SetMonitorMix(OutputDevice::Headphones),
+        ]
+    }
+
+    #[test]
+    fn every_command_is_swept() {
+        let schema = serde_json::to_value(status_schema()).unwrap();
+        let variants: BTreeSet<String> = schema["definitions"]["GoXLRCommand"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| variant["required"][0].as_str().unwrap().to_string())
+            .collect();
+
+        let swept: BTreeSet<String> = all_commands().iter().map(variant_name).collect();
+        assert_eq!(variants, swept);
+    }
+
+    // Sends every command to a Mini, those it can't handle must be refused without any USB
+    // traffic or changes to its state, and nothing else may be refused.
+    async fn sweep(firmware: VersionNumber, supported: bool) {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+
+        let (goxlr, requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, mini(firmware), &settings, events)
+            .await
+            .unwrap();
+        let capabilities = device.status().await.hardware.capabilities;
+        assert!(!capabilities.full_size);
+        assert_eq!(capabilities.animations, supported);
+        assert_eq!(capabilities.submixes, supported);
+
+        for command in all_commands() {
+            let description = format!("{:?}", command);
+            let missing =
+                required_features(&command)
+                    .iter()
+                    .copied()
+                    .find(|feature| match feature {
+                        DeviceFeature::FullSize => true,
+                        DeviceFeature::Animations => !capabilities.animations,
+                        DeviceFeature::Submixes => !capabilities.submixes,
+                        DeviceFeature::HeadphoneEq => !capabilities.headphone_eq,
+                    });
+
+            let before = snapshot(&device).await;
+            let sent = requests.lock().unwrap().len();
+            let result = device.perform_command(command).await;
+            let refused = match &result {
+                Err(e) => match e.downcast_ref::<CommandError>() {
+                    Some(CommandError::NotSupported(feature)) => Some(*feature),
+                    _ => None,
+                },
+                Ok(()) => None,
+            };
+
+            assert_eq!(refused, missing, "{}", description);
+            if missing.is_some() {
+                assert_eq!(requests.lock().unwrap().len(), sent, "{}", description);
+                assert_eq!(snapshot(&device).await, before, "{}", description);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn mini_refuses_full_size_commands() {
+        sweep(VersionNumber(1, 2, Some(0), Some(46)), true).await;
+    }
+
+    #[tokio::test]
+    async fn old_firmware_refuses_animations_and_submixes() {
+        sweep(VersionNumber(1, 0, Some(0), Some(0)), false).await;
+    }
+}
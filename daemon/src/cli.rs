@@ -48,6 +48,31 @@ pub struct Cli {
     /// Force regular expression to use when finding the Sampler Output
     #[arg(long)]
     pub override_sample_output_device: Option<String>,
+
+    /// Don't persist anything, all changes will be lost when the daemon stops
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Where to create the IPC socket (or named pipe on Windows), allowing more than one daemon
+    /// to run at once. Can also be set with the GOXLR_SOCKET_PATH environment variable
+    #[arg(long)]
+    pub socket_path: Option<String>,
+
+    /// Serve the Web UI from this directory instead of the built in copy, useful when working
+    /// on the UI. It must contain an index.html
+    #[arg(long)]
+    pub ui_path: Option<PathBuf>,
+
+    /// Check the device's mute states and lighting against the profile after every command,
+    /// warning about any differences (always enabled in debug builds)
+    #[arg(long)]
+    pub consistency_checks: bool,
+
+    /// Don't look for real devices, run against a simulated GoXLR instead. Nothing is sent to
+    /// any hardware, this is for testing clients (and the daemon) without a GoXLR attached. Give
+    /// this more than once to simulate a Full and a Mini together
+    #[arg(long, value_enum)]
+    pub simulate: Vec<SimulatedDevice>,
 }
 
 fn default_config_location() -> PathBuf {
@@ -73,3 +98,11 @@ pub enum LevelFilter {
     /// Corresponds to the `Trace` log level.
     Trace,
 }
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SimulatedDevice {
+    /// A full sized GoXLR
+    Full,
+    /// A GoXLR Mini
+    Mini,
+}
@@ -1,5 +1,8 @@
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{HashSet, VecDeque};
+use std::iter;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Result};
@@ -12,33 +15,67 @@ use strum::IntoEnumIterator;
 use tokio::sync::mpsc::Sender;
 use tokio::time::Instant;
 
+use goxlr_audio::markers::AudioMarker;
+use goxlr_audio::player::{Player, DEFAULT_LOUDNESS_TARGET};
+use goxlr_audio::server::ServerChange;
 use goxlr_ipc::{
-    Display, FaderStatus, GoXLRCommand, HardwareStatus, Levels, MicSettings, MixerStatus,
-    SampleProcessState, Settings,
+    BankNormalisation, ButtonEvent, CommandError, ControlQuery, DaemonResponse, DeviceFeature,
+    DeviceSession, DeviceSummary, DeviceTestStatus, Diagnostics, Display, EqCurveReport, EqTarget,
+    FaderStatus, FirmwareUpdateBlocker, FirmwareUpdateState, GoXLRCommand, HardwareReadback,
+    HardwareStateReport, HardwareStatus, HeadsetSnapshot, HeadsetStatus, HighContrastConfig,
+    HighContrastLevels, JackStatus, Levels, Lighting, MicSettings, MixerStatus, ModeChange,
+    ModeChangePreview, NormalisedSample, NormalisingSample, Notification, NotificationCategory,
+    OutputActivity, PhysicalControl, PickupDirection, PrivacyConfig, PrivacyStatus, ReactiveConfig,
+    ReactiveLighting, ReactiveZone, ReplayCapture, ResolvedControl, SampleProcessState,
+    ScribbleNumberMode, Settings, VodOutput,
 };
 use goxlr_profile_loader::components::mute::MuteFunction;
+use goxlr_profile_loader::components::sample::TrackMarker;
+use goxlr_scribbles::check_glyph;
+use goxlr_types::colours::parse_colour;
 use goxlr_types::{
     Button, ChannelName, DeviceType, DisplayModeComponents, EffectBankPresets, EffectKey,
-    EncoderName, FaderName, HardTuneSource, InputDevice as BasicInputDevice, MicrophoneParamKey,
-    Mix, MuteState, OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons,
-    SamplePlaybackMode, VersionNumber, VodMode, WaterfallDirection,
+    EffectName, EffectSection, EncoderName, EqFrequencies, FaderName, HardTuneSource,
+    HighContrastGroup, InputDevice as BasicInputDevice, MicProfileSection, MicrophoneParamKey,
+    MicrophoneType, MiniEqFrequencies, Mix, MuteFunction as BasicMuteFunction, MuteState,
+    OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons, SamplePlaybackMode,
+    SubMixChannelName, VersionNumber, VodMode, WaterfallDirection,
 };
 use goxlr_usb::animation::{AnimationMode, WaterFallDir};
 use goxlr_usb::buttonstate::{ButtonStates, Buttons};
 use goxlr_usb::channelstate::ChannelState;
 use goxlr_usb::channelstate::ChannelState::{Muted, Unmuted};
+use goxlr_usb::colouring::ColourTargets;
 use goxlr_usb::device::base::FullGoXLRDevice;
+use goxlr_usb::jackstate::JackStates;
 use goxlr_usb::routing::{InputDevice, OutputDevice};
 
-use crate::audio::{AudioFile, AudioHandler};
+use crate::audio::{AudioFile, AudioHandler, CalculationResult};
+use crate::capabilities::required_features;
+use crate::disk::{check_space, DiskFileSystem, RECORDING_SPACE};
+use crate::ducking::Ducking;
+use crate::eq_curve::{
+    map_eq_curve, write_eq_curve, EQ_CURVE_EXPORT_EXTENSION, EQ_CURVE_IMPORT_EXTENSION,
+};
 use crate::events::EventTriggers;
 use crate::events::EventTriggers::TTSMessage;
-use crate::files::find_file_in_path;
+use crate::files::{find_file_in_path, find_name_problem, normalise_name};
+use crate::headset::{check_sidetone, headset_commands, restore_commands, sidetone_volume};
 use crate::mic_profile::{MicProfileAdapter, DEFAULT_MIC_PROFILE_NAME};
+use crate::mode_change::{diff_mix_state, mode_change_command, MixLayout, MixState};
+use crate::polling::{check_poll_interval, PollRate};
+use crate::priority::{InteractiveQueue, PreemptedCommand};
 use crate::profile::{
-    usb_to_standard_button, version_newer_or_equal_to, ProfileAdapter, DEFAULT_PROFILE_NAME,
+    standard_to_colour_target, standard_to_profile_simple_colour, submix_to_channel_name,
+    usb_to_standard_button, version_newer_or_equal_to, ProfileAdapter, BALANCE_LIMIT,
+    DEFAULT_PROFILE_NAME,
 };
-use crate::SettingsHandle;
+use crate::settle::PendingWork;
+#[cfg(feature = "telemetry")]
+use crate::telemetry;
+use crate::timings::Timings;
+use crate::watchdog::check_stall_timeout;
+use crate::{SettingsHandle, CONSISTENCY_CHECKS};
 
 pub struct Device<'a> {
     goxlr: Box<dyn FullGoXLRDevice>,
@@ -47,30 +84,455 @@ pub struct Device<'a> {
     button_states: EnumMap<Buttons, ButtonState>,
     encoder_states: EnumMap<EncoderName, i8>,
     fader_last_seen: EnumMap<FaderName, u8>,
-    fader_pause_until: EnumMap<FaderName, PauseUntil>,
+    fader_targets: EnumMap<FaderName, FaderTarget>,
+    fader_pickups: EnumMap<FaderName, FaderPickup>,
+    fader_fine: EnumMap<FaderName, Option<FineFader>>,
     profile: ProfileAdapter,
     mic_profile: MicProfileAdapter,
     audio_handler: Option<AudioHandler>,
+
+    // Channels lowered by playing sample buttons
+    ducking: Ducking,
     hold_time: Duration,
-    vc_mute_also_mute_cm: bool,
+    vc_mute_outputs: Vec<BasicOutputDevice>,
     settings: &'a SettingsHandle,
     global_events: Sender<EventTriggers>,
 
     last_sample_error: Option<String>,
+    last_replay: Option<ReplayCapture>,
+    recording_level_published: Instant,
+
+    // Set when a SetFader had to reset the fader's Mute Function, for the command's response
+    mute_function_reset: Option<(FaderName, BasicMuteFunction)>,
+
+    // None until the device reports its jacks, and set when the monitor was moved to the Line
+    // Out because the Headphones were unplugged
+    jack_states: Option<JackStates>,
+    monitor_switched_on_unplug: bool,
+
+    identify: Option<IdentifyState>,
+    button_test: Option<ButtonTestState>,
+
+    // Presses seen during the Button Test, waiting to be passed on to the clients
+    button_events: Vec<ButtonEvent>,
+    reactive: Option<ReactiveState>,
+    high_contrast: Option<HighContrastConfig>,
+    privacy: Option<PrivacyState>,
+    effect_preview: Option<EffectPreview>,
+
+    // When phantom power has switched, the Mic is held muted until this passes
+    phantom_settle: Option<Instant>,
+    sample_analysis: Option<SampleAnalysis>,
+    normalise_task: Option<NormaliseTask>,
+    scribbles: EnumMap<FaderName, ScribbleState>,
+
+    // Set by the primary worker, guarded sample buttons are ignored while it's on, and flash
+    // when pressed
+    guard_mode: bool,
+    guard_flashes: EnumMap<SampleButtons, Option<Instant>>,
+
+    // When Sample Clear was armed, and how long (in seconds) it has left as last published
+    sample_clear_armed: Option<Instant>,
+    sample_clear_remaining: Option<u16>,
+
+    command_timings: Timings,
+    input_timings: Timings,
+    session: DeviceSession,
+    polling: PollRate,
+
+    // When the inputs were last read, if this stops moving the watchdog resets the device. The
+    // last command is kept to help work out why, and faulted is set by the watchdog. Nothing is
+    // expected while the system is asleep.
+    last_heartbeat: Instant,
+    asleep: bool,
+    last_command: Option<String>,
+    faulted: bool,
+
+    // What the device reported before the profile was applied to it
+    hardware_on_connect: Option<HardwareReadback>,
+
+    // The last mute states and lighting written, for the consistency check
+    sent: SentState,
+
+    // While an update is running the device is locked, nothing other than the update may be sent
+    // to it (see is_locked)
+    firmware_update: FirmwareUpdateState,
+
+    // Set by the primary worker, interactive commands waiting for this device are run from the
+    // preemption points in bulk work (see preempt), and kept here until the worker collects them
+    interactive: Option<InteractiveQueue>,
+    preempting: bool,
+    preempted: Vec<PreemptedCommand>,
+}
+
+/// Shadow copies of what's been written to the device, nothing is recorded until it's first
+/// been sent.
+#[derive(Default)]
+struct SentState {
+    mute_state: EnumMap<ChannelName, Option<ChannelState>>,
+    colour_map: Option<[u8; 520]>,
+    button_states: Option<[ButtonStates; 24]>,
+}
+
+// While a command is running (or inputs are being handled) nothing else on the device is, if
+// one takes longer than this, the delay is likely to be noticeable.
+const SLOW_OPERATION_THRESHOLD: Duration = Duration::from_millis(250);
+
+// How long the Mic is held muted while phantom power switches, to keep the pop off the monitors
+const PHANTOM_SETTLE_TIME: Duration = Duration::from_millis(300);
+
+// How often the level of an active recording is pushed to clients
+const RECORDING_LEVEL_INTERVAL: Duration = Duration::from_millis(250);
+
+// How long, and how quickly an Identify will cycle through its colours
+const IDENTIFY_DURATION: Duration = Duration::from_secs(3);
+const IDENTIFY_STEP: Duration = Duration::from_millis(250);
+const IDENTIFY_COLOURS: [[u8; 3]; 4] = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+
+// Reactive Lighting polls the Mic level at this rate, which is also the most often the colour map
+// will be written for it. The level in the status is published less often.
+const REACTIVE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const REACTIVE_LEVEL_INTERVAL: Duration = Duration::from_millis(250);
+
+// The base colour is shown this far (in dB) below the level which reaches the peak colour
+const REACTIVE_RANGE: f64 = 30.;
+
+// While Privacy Mode is locked, the Mic's mute buttons are lit in this (as sent, BGRA)
+const PRIVACY_COLOUR: [u8; 4] = [0, 0, 255, 255];
+
+// Where samples converted on import are stored, inside the samples directory
+const CONVERTED_SAMPLES_DIRECTORY: &str = "Converted";
+
+// The Button Test will end if no buttons have been pressed for this long
+const BUTTON_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+const BUTTON_TEST_LIT_DURATION: Duration = Duration::from_millis(500);
+
+// How long a guarded sample button flashes for when it's pressed
+const GUARD_FLASH_DURATION: Duration = Duration::from_millis(500);
+
+// Relative adjustments are clamped to these, which are the ranges the mic profile will accept
+const GATE_THRESHOLD_RANGE: RangeInclusive<i8> = -59..=0;
+const COMPRESSOR_THRESHOLD_RANGE: RangeInclusive<i8> = -40..=0;
+const COMPRESSOR_MAKEUP_RANGE: RangeInclusive<i8> = -6..=24;
+const DEESSER_RANGE: RangeInclusive<i8> = 0..=100;
+const BLEEP_VOLUME_RANGE: RangeInclusive<i8> = -36..=0;
+
+#[derive(Debug, Copy, Clone)]
+struct IdentifyState {
+    started: Instant,
+    step: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct ReactiveState {
+    config: ReactiveConfig,
+    base: [u8; 4],
+    peak: [u8; 4],
+
+    // Identify and the Button Test own the lighting while they run
+    suspended: bool,
+    polled: Option<Instant>,
+
+    // How far between the base (0) and peak (1) colours the lighting is, and the colour there
+    position: f64,
+    colour: Option<[u8; 4]>,
+
+    level: Option<f64>,
+    level_published: Instant,
+}
+
+impl ReactiveState {
+    fn new(config: ReactiveConfig, suspended: bool) -> Result<Self> {
+        if config.sensitivity > 100 {
+            bail!("Sensitivity must be between 0 and 100");
+        }
+
+        Ok(Self {
+            base: colour_map_bytes(&config.base_colour)?,
+            peak: colour_map_bytes(&config.peak_colour)?,
+            config,
+            suspended,
+            polled: None,
+            position: 0.,
+            colour: None,
+            level: None,
+            level_published: Instant::now(),
+        })
+    }
+
+    // Rises straight to the level, but falls no faster than the decay allows
+    fn follow(&mut self, level: f64, elapsed: Duration) -> [u8; 4] {
+        let target = reactive_position(level, self.config.sensitivity);
+        let fall = match self.config.decay {
+            0 => 1.,
+            decay => elapsed.as_secs_f64() * 1000. / decay as f64,
+        };
+        self.position = target.max(self.position - fall);
+        blend_colours(self.base, self.peak, self.position)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PrivacyState {
+    config: PrivacyConfig,
+    locked: bool,
+
+    // The last command or button press, for the idle relock
+    last_activity: Instant,
+}
+
+impl PrivacyState {
+    fn new(config: PrivacyConfig) -> Self {
+        Self {
+            config,
+            locked: true,
+            last_activity: Instant::now(),
+        }
+    }
+
+    fn idle_expired(&self) -> bool {
+        match self.config.idle_relock {
+            Some(seconds) => self.last_activity.elapsed() >= Duration::from_secs(seconds.into()),
+            None => false,
+        }
+    }
+}
+
+// Holding an Effect Select button (if enabled) temporarily loads its bank, this tracks what
+// needs to be put back when the button is released.
+#[derive(Debug, Copy, Clone)]
+struct EffectPreview {
+    button: Buttons,
+    preset: EffectBankPresets,
+    previous: EffectBankPresets,
+
+    // The previewed bank's encoder values before any were moved
+    encoders: [i8; 4],
+    was_dirty: bool,
+}
+
+// A sample which is being analysed before it's added to a button
+#[derive(Debug, Clone, PartialEq)]
+struct SampleAnalysis {
+    bank: SampleBank,
+    button: SampleButtons,
+    file: PathBuf,
+}
+
+// Samples in a bank being normalised are analysed one at a time, using the same calculation as
+// when a sample is added. The status is kept once finished so the results can be seen.
+#[derive(Debug)]
+struct NormaliseTask {
+    status: BankNormalisation,
+    pending: VecDeque<(SampleButtons, String)>,
+}
+
+// Dynamic scribble numbers are redrawn when their value changes, but no more often than this,
+// so sweeping a fader doesn't flood the device with images. Whatever the value settles on is
+// drawn once the interval has passed.
+const SCRIBBLE_NUMBER_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Default, Clone)]
+struct ScribbleState {
+    rendered: Option<String>,
+    uploaded: Option<Instant>,
+
+    // The image last sent, a profile load which draws the same image again doesn't resend it
+    image: Option<[u8; 1024]>,
+    keep_unchanged: bool,
+
+    // Rendering a scribble is slow enough to delay input handling when a profile loads all four
+    // at once, so they're marked as pending and drawn one per update instead.
+    pending: bool,
+
+    // Why the last attempt to draw it failed, cleared once one succeeds
+    error: Option<ScribbleError>,
+}
+
+#[derive(Debug, Clone)]
+struct ScribbleError {
+    // The number it was being drawn with, a dynamic number isn't retried until it changes
+    number: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Clone)]
+struct ButtonTestState {
+    last_activity: Instant,
+    lit: EnumMap<Buttons, Option<Instant>>,
+    registered: EnumSet<Buttons>,
+}
+
+// When a volume is set by a command, the motorised faders report their position as they travel
+// to it. Those reports are ignored until the fader arrives, a human pushes it away from the
+// target, or this window passes and the hardware position is taken as correct.
+const HARDWARE_WINS_AFTER: Duration = Duration::from_millis(1000);
+
+// How close the fader needs to be to the target to have 'arrived'
+const FADER_ARRIVAL_TOLERANCE: u8 = 5;
+
+// How far a fader needs to move away from the target before we treat it as a human
+const FADER_OVERRIDE_THRESHOLD: u8 = 10;
+
+#[derive(Debug, Default, Copy, Clone)]
+struct FaderTarget {
+    pending: Option<PendingFaderTarget>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct PendingFaderTarget {
+    volume: u8,
+    expires: Instant,
+
+    // The closest the fader has come to the target so far
+    closest: Option<u8>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FaderReport {
+    // No target is set, the report should be handled normally
+    Idle,
+
+    // The fader is moving towards (or has reached) the target, the report should be ignored
+    Commanded,
+
+    // The hardware has taken back control from the target
+    Override,
+}
+
+impl FaderTarget {
+    fn set(&mut self, volume: u8, now: Instant) {
+        self.pending = Some(PendingFaderTarget {
+            volume,
+            expires: now + HARDWARE_WINS_AFTER,
+            closest: None,
+        });
+    }
+
+    // Whether the fader may still be on its way to the target
+    fn is_moving(&self, now: Instant) -> bool {
+        self.pending.is_some_and(|pending| now < pending.expires)
+    }
+
+    fn check(&mut self, reported: u8, now: Instant) -> FaderReport {
+        let pending = match &mut self.pending {
+            Some(pending) => pending,
+            None => return FaderReport::Idle,
+        };
+
+        // abs_diff means targets near 0 and 255 don't need any special handling.
+        let distance = reported.abs_diff(pending.volume);
+        if distance <= FADER_ARRIVAL_TOLERANCE {
+            // The fader may settle slightly off the target, but the target still stands.
+            self.pending = None;
+            return FaderReport::Commanded;
+        }
+
+        if now >= pending.expires {
+            self.pending = None;
+            return FaderReport::Override;
+        }
+
+        if let Some(closest) = pending.closest {
+            if distance > closest.saturating_add(FADER_OVERRIDE_THRESHOLD) {
+                self.pending = None;
+                return FaderReport::Override;
+            }
+        }
+
+        pending.closest = Some(
+            pending
+                .closest
+                .map_or(distance, |closest| closest.min(distance)),
+        );
+        FaderReport::Commanded
+    }
 }
 
+// The Mini's faders can't be moved to match a volume set by the daemon, so rather than the
+// volume jumping to wherever the fader sits when it's next touched, the fader is ignored until
+// it's brought to (or past) the volume, being within this distance counts as reaching it.
+const FADER_PICKUP_WINDOW: u8 = 5;
+
 #[derive(Debug, Default, Copy, Clone)]
-struct PauseUntil {
-    paused: bool,
-    until: u8,
+struct FaderPickup {
+    pending: Option<PendingFaderPickup>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct PendingFaderPickup {
+    volume: u8,
+    direction: PickupDirection,
+}
+
+impl FaderPickup {
+    fn set(&mut self, volume: u8, position: u8) {
+        if position.abs_diff(volume) <= FADER_PICKUP_WINDOW {
+            self.pending = None;
+            return;
+        }
+
+        let direction = match position > volume {
+            true => PickupDirection::Down,
+            false => PickupDirection::Up,
+        };
+        self.pending = Some(PendingFaderPickup { volume, direction });
+    }
+
+    // Returns true if the report should be handled, which is once the fader has been picked up.
+    fn check(&mut self, reported: u8) -> bool {
+        let pending = match self.pending {
+            Some(pending) => pending,
+            None => return true,
+        };
+
+        // Being inclusive here means a volume of 0 or 255 can still be reached by crossing it.
+        let crossed = match pending.direction {
+            PickupDirection::Up => reported >= pending.volume,
+            PickupDirection::Down => reported <= pending.volume,
+        };
+
+        if crossed || reported.abs_diff(pending.volume) <= FADER_PICKUP_WINDOW {
+            self.pending = None;
+            return true;
+        }
+        false
+    }
+
+    fn direction(&self) -> Option<PickupDirection> {
+        self.pending.map(|pending| pending.direction)
+    }
+}
+
+// With fine adjustment enabled on the Mini, moving a fader while its mute button is held moves
+// the volume by this fraction of the fader's travel, relative to where the volume started.
+const FINE_FADER_RATIO: i16 = 10;
+
+#[derive(Debug, Copy, Clone)]
+struct FineFader {
+    position: u8,
+    volume: u8,
+}
+
+impl FineFader {
+    fn volume_at(&self, position: u8) -> u8 {
+        let travel = position as i16 - self.position as i16;
+        (self.volume as i16 + travel / FINE_FADER_RATIO).clamp(0, u8::MAX as i16) as u8
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone)]
 struct ButtonState {
     press_time: Option<Instant>,
     hold_handled: bool,
+
+    // Set when the press occurred during a Button Test, so it shouldn't be actioned.
+    ignored: bool,
 }
 
+// The left and right routing tables for an input, as written to the device
+type RoutingRow = ([u8; 22], [u8; 22]);
+
 // Used when loading profiles to provide the previous
 // profile's settings for comparison.
 #[derive(Default)]
@@ -78,8 +540,30 @@ pub(crate) struct CurrentState {
     pub(crate) faders: EnumMap<FaderName, ChannelName>,
     pub(crate) mute_state: EnumMap<ChannelName, ChannelState>,
     pub(crate) volumes: EnumMap<ChannelName, u8>,
+    pub(crate) fader_display: EnumMap<FaderName, (bool, bool)>,
+    pub(crate) animation: Option<ProfileAnimation>,
+
+    // These depend on more than the profile, so are filled in by the Device
+    pub(crate) routing: Option<EnumMap<BasicInputDevice, RoutingRow>>,
+    pub(crate) colour_map: Option<[u8; 520]>,
+    pub(crate) button_states: Option<[ButtonStates; 24]>,
+    pub(crate) submix_volumes: Option<EnumMap<SubMixChannelName, Option<u8>>>,
+    pub(crate) output_mixes: Option<EnumMap<BasicOutputDevice, Mix>>,
+    pub(crate) voice_fx: Option<VoiceFxState>,
+}
+
+/// The Voice FX as they're sent to the device, the effect values, the Pitch encoder's mode and
+/// resolution, and each encoder's position.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VoiceFxState {
+    effects: Vec<(EffectKey, i32)>,
+    pitch_mode: (u8, u8),
+    encoders: EnumMap<EncoderName, i8>,
 }
 
+// The profile's animation settings, in the order they're passed to the device
+pub(crate) type ProfileAnimation = (goxlr_types::AnimationMode, u8, u8, WaterfallDirection);
+
 impl<'a> Device<'a> {
     pub async fn new(
         goxlr: Box<dyn FullGoXLRDevice>,
@@ -177,7 +661,8 @@ impl<'a> Device<'a> {
         let mut audio_handler = None;
         if hardware.device_type == DeviceType::Full {
             let audio_buffer = settings_handle.get_device_sampler_pre_buffer(&serial).await;
-            let audio_loader = AudioHandler::new(audio_buffer);
+            let cache_path = settings_handle.get_sample_cache_directory();
+            let audio_loader = AudioHandler::new(audio_buffer, cache_path);
             debug!("Created Audio Handler..");
             debug!("{:?}", audio_loader);
 
@@ -187,6 +672,10 @@ impl<'a> Device<'a> {
 
             if let Ok(audio) = audio_loader {
                 debug!("Audio Handler Loaded OK..");
+                audio.configure_sample_cache(
+                    settings_handle.get_sample_cache_enabled().await,
+                    settings_handle.get_sample_cache_size().await,
+                );
                 audio_handler.replace(audio);
             }
         } else {
@@ -194,9 +683,8 @@ impl<'a> Device<'a> {
         }
 
         let hold_time = settings_handle.get_device_hold_time(&serial).await;
-        let vc_mute_also_mute_cm = settings_handle
-            .get_device_chat_mute_mutes_mic_to_chat(&serial)
-            .await;
+        let vc_mute_outputs = settings_handle.get_device_vc_mute_outputs(&serial).await;
+        let poll_interval = settings_handle.get_device_poll_interval(&serial).await;
 
         debug!("--- DEVICE INFO ---");
         debug!("Serial: {:?}", &serial);
@@ -211,19 +699,111 @@ impl<'a> Device<'a> {
             goxlr,
             hardware,
             hold_time: Duration::from_millis(hold_time.into()),
-            vc_mute_also_mute_cm,
+            vc_mute_outputs,
             last_buttons: EnumSet::empty(),
             button_states: EnumMap::default(),
             encoder_states: EnumMap::default(),
             fader_last_seen: EnumMap::default(),
-            fader_pause_until: EnumMap::default(),
+            fader_targets: EnumMap::default(),
+            fader_pickups: EnumMap::default(),
+            fader_fine: EnumMap::default(),
             audio_handler,
+            ducking: Ducking::default(),
             settings: settings_handle,
             global_events,
 
             last_sample_error: None,
+            last_replay: None,
+            recording_level_published: Instant::now(),
+
+            mute_function_reset: None,
+
+            jack_states: None,
+            monitor_switched_on_unplug: false,
+
+            identify: None,
+            button_test: None,
+            button_events: vec![],
+            reactive: None,
+            high_contrast: None,
+            privacy: None,
+            effect_preview: None,
+            phantom_settle: None,
+            sample_analysis: None,
+            normalise_task: None,
+            scribbles: EnumMap::default(),
+
+            guard_mode: false,
+            guard_flashes: EnumMap::default(),
+
+            sample_clear_armed: None,
+            sample_clear_remaining: None,
+
+            command_timings: Timings::default(),
+            input_timings: Timings::default(),
+            session: DeviceSession {
+                connected_at: Local::now().timestamp(),
+                ..Default::default()
+            },
+            polling: PollRate::new(poll_interval, Instant::now()),
+
+            last_heartbeat: Instant::now(),
+            asleep: false,
+            last_command: None,
+            faulted: false,
+
+            hardware_on_connect: None,
+            sent: SentState::default(),
+            firmware_update: FirmwareUpdateState::Idle,
+
+            interactive: None,
+            preempting: false,
+            preempted: vec![],
         };
 
+        device.hardware.capabilities.full_size = !device.is_device_mini();
+        device.hardware.capabilities.animations = device.device_supports_animations();
+        device.hardware.capabilities.submixes = device.device_supports_submixes();
+
+        // The Headphone EQ can't be detected from the firmware version, it's left as given
+        device
+            .goxlr
+            .set_poll_interval(Duration::from_millis(poll_interval.into()));
+
+        match device.read_hardware_state() {
+            Ok(readback) => {
+                device.log_hardware_differences(&readback);
+                device.fader_last_seen = readback.fader_volumes;
+                if settings_handle
+                    .get_device_adopt_hardware_state(device.serial())
+                    .await
+                {
+                    device.adopt_hardware_volumes(&readback)?;
+                }
+                device.hardware_on_connect = Some(readback);
+            }
+            Err(e) => warn!("Unable to read the current state of the device: {}", e),
+        }
+
+        if let Some(config) = settings_handle.get_device_reactive_lighting(&serial).await {
+            match ReactiveState::new(config, false) {
+                Ok(reactive) => device.reactive = Some(reactive),
+                Err(e) => warn!("Unable to restore Reactive Lighting: {}", e),
+            }
+        }
+
+        if let Some(config) = settings_handle.get_device_high_contrast(&serial).await {
+            match check_high_contrast(&config) {
+                Ok(()) => device.high_contrast = Some(config),
+                Err(e) => warn!("Unable to restore High Contrast mode: {}", e),
+            }
+        }
+
+        // Privacy Mode always starts locked, so the profile can't unmute the Mic as it's applied
+        if let Some(config) = settings_handle.get_device_privacy_mode(&serial).await {
+            device.privacy = Some(PrivacyState::new(config));
+        }
+
         device.apply_profile(None).await?;
         device.apply_mic_profile().await?;
 
@@ -234,6 +814,83 @@ impl<'a> Device<'a> {
         &self.hardware.serial_number
     }
 
+    pub fn device_type(&self) -> DeviceType {
+        self.hardware.device_type.clone()
+    }
+
+    /// Set by the primary worker, which sees the device come and go
+    pub fn set_reconnect_count(&mut self, count: u32) {
+        self.session.reconnect_count = count;
+    }
+
+    // Sent to the TTS service, which speaks the text (if enabled) and passes it on to clients.
+    async fn announce(&self, category: NotificationCategory, text: String) {
+        let notification = Notification {
+            serial: self.hardware.serial_number.clone(),
+            text,
+            category,
+        };
+        let _ = self.global_events.send(TTSMessage(notification)).await;
+    }
+
+    /// The small subset of the status served by GetStatusSummary, which is polled often, so this
+    /// should only ever need a few lookups.
+    pub fn get_summary(&self) -> DeviceSummary {
+        DeviceSummary {
+            serial: self.hardware.serial_number.clone(),
+            nickname: None,
+            device_type: self.device_type(),
+            profile_name: self.profile.name().to_owned(),
+            mic_muted: self.get_mic_channel_state() == Muted,
+            cough_state: self.profile.get_cough_status().state,
+            fx_enabled: self.profile.is_fx_enabled(),
+            mic_volume: self.profile.get_channel_volume(ChannelName::Mic),
+            headphones_volume: self.profile.get_channel_volume(ChannelName::Headphones),
+        }
+    }
+
+    /// Work left behind by earlier commands, which is finished off as the device is updated.
+    pub fn pending_work(&self) -> Vec<PendingWork> {
+        let now = Instant::now();
+        let mut work = vec![];
+
+        if !self.is_device_mini() {
+            for fader in FaderName::iter() {
+                if self.scribbles[fader].pending || self.scribble_number_changed(fader) {
+                    work.push(PendingWork::Scribble(fader));
+                }
+                if self.fader_targets[fader].is_moving(now) {
+                    work.push(PendingWork::FaderMoving(fader));
+                }
+            }
+        }
+
+        for channel in self.ducking.releasing() {
+            work.push(PendingWork::DuckRelease(channel));
+        }
+
+        if self.sample_analysis.is_some() {
+            work.push(PendingWork::SampleAnalysis);
+        }
+        if matches!(&self.normalise_task, Some(task) if task.status.current.is_some()) {
+            work.push(PendingWork::Normalising);
+        }
+
+        for (button, flash) in self.guard_flashes {
+            if flash.is_some() {
+                work.push(PendingWork::GuardFlash(button));
+            }
+        }
+
+        if self.identify.is_some() {
+            work.push(PendingWork::Identify);
+        }
+        if self.phantom_settle.is_some() {
+            work.push(PendingWork::PhantomPower);
+        }
+        work
+    }
+
     pub async fn status(&self) -> MixerStatus {
         let mut fader_map: EnumMap<FaderName, FaderStatus> = Default::default();
         for name in FaderName::iter() {
@@ -277,7 +934,49 @@ impl<'a> Device<'a> {
             .await;
 
         let locked_faders = self.settings.get_device_lock_faders(self.serial()).await;
+        let silent_faders = self.settings.get_device_silent_faders(self.serial()).await;
         let vod_mode = self.settings.get_device_vod_mode(self.serial()).await;
+        let sample_clear_timeout = self
+            .settings
+            .get_device_sample_clear_timeout(self.serial())
+            .await;
+        let effect_preview_on_hold = self
+            .settings
+            .get_device_effect_preview_on_hold(self.serial())
+            .await;
+        let clear_effects_on_hold = self
+            .settings
+            .get_device_clear_effects_on_hold(self.serial())
+            .await;
+        let switch_monitor_on_unplug = self
+            .settings
+            .get_device_switch_monitor_on_unplug(self.serial())
+            .await;
+        let save_on_shutdown = self
+            .settings
+            .get_device_save_on_shutdown(self.serial())
+            .await;
+        let adopt_hardware_state = self
+            .settings
+            .get_device_adopt_hardware_state(self.serial())
+            .await;
+        let phantom_confirmation_required = self
+            .settings
+            .get_device_phantom_confirmation_required(self.serial())
+            .await;
+        let fine_fader_adjust = self
+            .settings
+            .get_device_fine_fader_adjust(self.serial())
+            .await;
+        let convert_samples = self
+            .settings
+            .get_device_convert_samples(self.serial())
+            .await;
+        let stall_timeout = self.settings.get_device_stall_timeout(self.serial()).await;
+        let submix_default_linked = self
+            .settings
+            .get_device_submix_default_linked(self.serial())
+            .await;
 
         let submix_supported = self.device_supports_submixes();
 
@@ -312,19 +1011,22 @@ impl<'a> Device<'a> {
                 submix: self.profile.get_submixes_ipc(submix_supported),
                 bleep: self.mic_profile.bleep_level(),
                 deess: self.mic_profile.get_deesser(),
+                input_low_cut: EnumMap::from_fn(|input| self.profile.get_input_low_cut(input)),
+                input_balance: EnumMap::from_fn(|input| self.profile.get_input_balance(input)),
             },
-            router: self.profile.create_router(),
+            router: self.get_router_status().await,
+            output_activity: self.get_output_activity().await.unwrap_or_default(),
+            vod_output: self.get_vod_output().await,
             mic_status: MicSettings {
                 mic_type: self.mic_profile.mic_type(),
+                phantom_power_active: self.mic_profile.mic_type().has_phantom_power(),
                 mic_gains: self.mic_profile.mic_gains(),
                 noise_gate: self.mic_profile.noise_gate_ipc(),
                 equaliser: self.mic_profile.equalizer_ipc(),
                 equaliser_mini: self.mic_profile.equalizer_mini_ipc(),
                 compressor: self.mic_profile.compressor_ipc(),
             },
-            lighting: self
-                .profile
-                .get_lighting_ipc(is_mini, self.device_supports_animations()),
+            lighting: self.get_lighting_status(),
             effects: self.profile.get_effects_ipc(is_mini, self.encoder_states),
             sampler: self.profile.get_sampler_ipc(
                 is_mini,
@@ -333,7 +1035,10 @@ impl<'a> Device<'a> {
                 SampleProcessState {
                     progress: sample_progress,
                     last_error: sample_error,
+                    normalisation: self.normalise_task.as_ref().map(|task| task.status.clone()),
                 },
+                self.sample_clear_remaining,
+                self.last_replay.clone(),
             ),
             settings: Settings {
                 display: Display {
@@ -343,21 +1048,85 @@ impl<'a> Device<'a> {
                     equaliser_fine: self.mic_profile.get_eq_fine_display_mode(),
                 },
                 mute_hold_duration: self.hold_time.as_millis() as u16,
-                vc_mute_also_mute_cm: self.vc_mute_also_mute_cm,
+                vc_mute_also_mute_cm: self.vc_mute_outputs.contains(&BasicOutputDevice::ChatMic),
+                vc_mute_outputs: self.vc_mute_outputs.clone(),
+                vc_mute_outputs_active: !self.vc_mute_outputs.is_empty()
+                    && self.voice_chat_muted_to_all(),
                 enable_monitor_with_fx: monitor_with_fx,
                 reset_sampler_on_clear: sampler_reset_on_clear,
+                sample_clear_timeout,
                 lock_faders: locked_faders,
+                silent_faders,
                 vod_mode,
+                effect_preview_on_hold,
+                clear_effects_on_hold,
+                switch_monitor_on_unplug,
+                save_on_shutdown,
+                adopt_hardware_state,
+                phantom_confirmation_required,
+                fine_fader_adjust,
+                poll_interval: self.polling.configured(),
+                stall_timeout,
+                convert_samples,
+                submix_default_linked: EnumMap::from_fn(|mix| {
+                    submix_default_linked.get(&mix).copied()
+                }),
             },
             button_down: button_states,
+            device_test: self.get_device_test_status(),
+            locked: self.is_locked(),
+            firmware_update: self.firmware_update,
             profile_name: self.profile.name().to_owned(),
+            profile_from_newer_version: self.profile.is_from_newer_version(),
+            profile_dirty: self.profile.is_dirty(),
+            profile_inheritance: self.profile.get_inheritance(),
+            jacks: self.get_jack_status(),
             mic_profile_name: self.mic_profile.name().to_owned(),
+            mic_profile_dirty: self.mic_profile.is_dirty(),
+            volume_scenes: self.profile.get_volume_scene_names(),
+            diagnostics: Diagnostics {
+                commands: self.command_timings.stats(),
+                input_polls: self.input_timings.stats(),
+                poll_interval: self.polling.current(),
+                polling_idle: self.polling.is_idle(),
+                faulted: self.faulted,
+                sampler_output: self
+                    .audio_handler
+                    .as_ref()
+                    .map(|handler| handler.get_output_status()),
+            },
+            session: self.session.clone(),
+            privacy: PrivacyStatus {
+                config: self.privacy.as_ref().map(|privacy| privacy.config.clone()),
+                locked: self.privacy_locked(),
+            },
+            headset: HeadsetStatus {
+                snapshot: self.settings.get_device_headset_mode(self.serial()).await,
+                sidetone: self
+                    .settings
+                    .get_device_headset_sidetone(self.serial())
+                    .await,
+            },
         }
     }
 
     pub async fn shutdown(&mut self, avoid_save: bool) {
         debug!("Shutting Down Device: {}", self.hardware.serial_number);
 
+        // This needs to happen before the shutdown commands, as they may change things which
+        // the user doesn't want persisted.
+        if self
+            .settings
+            .get_device_save_on_shutdown(self.serial())
+            .await
+        {
+            if avoid_save {
+                warn!("Unable to save profiles on shutdown, disk writes are not permitted.");
+            } else {
+                self.save_dirty_profiles().await;
+            }
+        }
+
         let commands = self
             .settings
             .get_device_shutdown_commands(&self.hardware.serial_number)
@@ -366,8 +1135,32 @@ impl<'a> Device<'a> {
         self.execute_command_list(commands, avoid_save).await;
     }
 
+    async fn save_dirty_profiles(&mut self) {
+        if self.settings.write_policy().is_read_only() {
+            info!("Read-only mode, discarding unsaved profile changes");
+            return;
+        }
+
+        if self.profile.is_dirty() {
+            let directory = self.settings.get_profile_directory().await;
+            match self.profile.save(&directory, true) {
+                Ok(()) => info!("Saved Profile {} on Shutdown", self.profile.name()),
+                Err(e) => warn!("Unable to save Profile on Shutdown: {}", e),
+            }
+        }
+
+        if self.mic_profile.is_dirty() {
+            let directory = self.settings.get_mic_profile_directory().await;
+            match self.mic_profile.save(&directory, true) {
+                Ok(()) => info!("Saved Mic Profile {} on Shutdown", self.mic_profile.name()),
+                Err(e) => warn!("Unable to save Mic Profile on Shutdown: {}", e),
+            }
+        }
+    }
+
     pub async fn sleep(&mut self) {
         debug!("Sleeping...");
+        self.asleep = true;
 
         let commands = self
             .settings
@@ -379,6 +1172,8 @@ impl<'a> Device<'a> {
 
     pub async fn wake(&mut self) {
         debug!("Waking...");
+        self.asleep = false;
+        self.last_heartbeat = Instant::now();
 
         let commands = self
             .settings
@@ -392,6 +1187,14 @@ impl<'a> Device<'a> {
         for command in commands {
             debug!("{:?}", command);
 
+            // These run unattended, so there's nobody to ask about unsaved changes.
+            let command = match command {
+                GoXLRCommand::LoadProfile(name, persist, _) => {
+                    GoXLRCommand::LoadProfile(name, persist, true)
+                }
+                command => command,
+            };
+
             // Below is a list of all commands which will write to a disk, if any of them are
             // in our command list, we do nothing.
             match command {
@@ -401,11 +1204,13 @@ impl<'a> Device<'a> {
                 | GoXLRCommand::SetWakeCommands(_)
                 // Presets
                 | GoXLRCommand::SaveActivePreset()
+                | GoXLRCommand::SaveEffectSection(_, _)
                 // Profile Related Commands
                 | GoXLRCommand::NewProfile(_)
-                | GoXLRCommand::LoadProfile(_, true)
+                | GoXLRCommand::LoadProfile(_, true, _)
                 | GoXLRCommand::SaveProfile()
                 | GoXLRCommand::SaveProfileAs(_)
+                | GoXLRCommand::RenameProfile(_, _)
                 // Mic Profile Related Commands
                 | GoXLRCommand::NewMicProfile(_)
                 | GoXLRCommand::LoadMicProfile(_, true)
@@ -414,9 +1219,28 @@ impl<'a> Device<'a> {
                 // settings.json variables
                 | GoXLRCommand::SetSamplerPreBufferDuration(_)
                 | GoXLRCommand::SetVCMuteAlsoMuteCM(_)
+                | GoXLRCommand::SetVCMuteAdditionalOutputs(_)
                 | GoXLRCommand::SetMonitorWithFx(_)
                 | GoXLRCommand::SetSamplerResetOnClear(_)
+                | GoXLRCommand::SetSampleClearTimeout(_)
                 | GoXLRCommand::SetLockFaders(_)
+                | GoXLRCommand::SetSilentFaders(_)
+                | GoXLRCommand::SetEffectPreviewOnHold(_)
+                | GoXLRCommand::SetClearEffectsOnHold(_)
+                | GoXLRCommand::SetSwitchMonitorOnUnplug(_)
+                | GoXLRCommand::SetAdoptHardwareState(_)
+                | GoXLRCommand::SetPhantomConfirmationRequired(_)
+                | GoXLRCommand::SetFineFaderAdjust(_)
+                | GoXLRCommand::SetPollInterval(_)
+                | GoXLRCommand::SetStallTimeout(_)
+                | GoXLRCommand::SetSampleConversion(_)
+                | GoXLRCommand::SetSubMixDefaultLinked(..)
+                | GoXLRCommand::SetPrivacyMode(_)
+                | GoXLRCommand::SetHeadsetMode(_)
+                | GoXLRCommand::SetHeadsetSidetone(_)
+                | GoXLRCommand::SetReactiveLighting(_)
+                | GoXLRCommand::SetHighContrastMode(_)
+                | GoXLRCommand::SetSaveOnShutdown(_)
                 => {
                     if !avoid_write {
                         let _ = self.perform_command(command).await;
@@ -429,6 +1253,7 @@ impl<'a> Device<'a> {
                     let _ = self.perform_command(command).await;
                 }
             }
+            self.preempt(&mut None).await;
         }
     }
 
@@ -440,9 +1265,48 @@ impl<'a> Device<'a> {
         &self.mic_profile
     }
 
+    pub async fn load_sample_cache_settings(&mut self) {
+        let enabled = self.settings.get_sample_cache_enabled().await;
+        let size = self.settings.get_sample_cache_size().await;
+
+        if let Some(audio_handler) = &self.audio_handler {
+            audio_handler.configure_sample_cache(enabled, size);
+        }
+    }
+
+    pub fn clear_sample_cache(&mut self) -> Result<()> {
+        if let Some(audio_handler) = &self.audio_handler {
+            audio_handler.clear_sample_cache()?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_mic_muted(&mut self, muted: bool) -> Result<()> {
+        let state = if muted {
+            MuteState::MutedToAll
+        } else {
+            MuteState::Unmuted
+        };
+        self.perform_command(GoXLRCommand::SetCoughMuteState(state))
+            .await
+    }
+
     pub async fn update_state(&mut self) -> Result<bool> {
+        // Nothing other than the update is sent to a device while it's running
+        if self.is_locked() {
+            return Ok(false);
+        }
+
         let mut state_updated = false;
-        let mut refresh_colour_map = false;
+
+        let normalising =
+            matches!(&self.normalise_task, Some(task) if task.status.current.is_some());
+        let mut normalise_result = None;
+        let mut analysis_result = None;
+
+        if self.check_audio_server().await? {
+            state_updated = true;
+        }
 
         // Update any audio related states..
         if let Some(audio_handler) = &mut self.audio_handler {
@@ -451,24 +1315,10 @@ impl<'a> Device<'a> {
                 // Handling has been finished, pull all the data and add it to the profile.
 
                 let result = audio_handler.get_and_clear_calculating_result()?;
-                if result.result.is_err() {
-                    if let Err(error) = result.result {
-                        // We need to somehow push this to the user (via DaemonStatus probably)..
-                        self.last_sample_error = Some(error.to_string());
-                    }
+                if normalising {
+                    normalise_result.replace(result);
                 } else {
-                    let bank = result.bank;
-                    let button = result.button;
-
-                    let filename = result.file.file_name().unwrap();
-                    let filename = filename.to_string_lossy().to_string();
-
-                    debug!("Calculated Gain: {}", result.gain);
-
-                    let track = self.profile.add_sample_file(bank, button, filename);
-                    track.normalized_gain = result.gain;
-
-                    refresh_colour_map = true;
+                    analysis_result.replace(result);
                 }
                 state_updated = true;
             }
@@ -479,24 +1329,46 @@ impl<'a> Device<'a> {
                 state_updated = true;
             }
 
+            if audio_handler.check_output_device() {
+                state_updated = true;
+            }
+
             if audio_handler.check_playing().await && !state_updated {
                 state_updated = true;
             }
 
+            // Publish the recording level, but at a slower rate than we poll..
+            if audio_handler.is_sample_recording()
+                && self.recording_level_published.elapsed() > RECORDING_LEVEL_INTERVAL
+            {
+                self.recording_level_published = Instant::now();
+                state_updated = true;
+            }
+
             if self.sync_sample_lighting().await? && !state_updated {
                 state_updated = true;
             };
+        }
+        self.sync_sample_ducking()?;
 
-            if refresh_colour_map {
-                self.load_colour_map().await?;
-            }
+        if let Some(result) = analysis_result {
+            self.add_analysed_sample(result).await?;
+        }
+
+        if let Some(result) = normalise_result {
+            self.store_normalised_gain(result);
+            self.normalise_next_sample().await;
         }
 
         // Find any buttons that have been held, and action if needed.
         for button in self.last_buttons {
-            if !self.button_states[button].hold_handled {
+            if !self.button_states[button].hold_handled && !self.button_states[button].ignored {
                 if let Some(time) = self.button_states[button].press_time {
-                    if time.elapsed() > self.hold_time {
+                    // With fine adjustment, a held fader mute is handled on release
+                    let deferred = fader_for_mute_button(button).is_some()
+                        && self.is_fine_fader_adjust_enabled().await;
+
+                    if time.elapsed() > self.hold_time && !deferred {
                         if let Err(error) = self.on_button_hold(button).await {
                             error!("{}", error);
                         }
@@ -506,13 +1378,554 @@ impl<'a> Device<'a> {
             }
         }
 
-        Ok(state_updated)
-    }
+        if self.update_sample_clear().await? {
+            state_updated = true;
+        }
 
-    pub async fn monitor_inputs(&mut self) -> Result<bool> {
-        let state = self.goxlr.get_button_states()?;
-        let mut changed = self.update_volumes_to(state.volumes).await?;
-        let result = self.update_encoders_to(state.encoders).await?;
+        if self.update_scribbles().await? {
+            state_updated = true;
+        }
+
+        if self.update_identify().await? {
+            state_updated = true;
+        }
+
+        if self.update_button_test()? {
+            state_updated = true;
+        }
+
+        self.update_guard_flashes()?;
+
+        if self.update_phantom_settle()? {
+            state_updated = true;
+        }
+
+        if self.update_reactive_lighting().await? {
+            state_updated = true;
+        }
+
+        if self.update_privacy().await? {
+            state_updated = true;
+        }
+
+        if self.update_poll_interval(Instant::now()) {
+            state_updated = true;
+        }
+
+        Ok(state_updated)
+    }
+
+    pub async fn identify(&mut self) -> Result<()> {
+        debug!("Identifying Device: {}", self.serial());
+        if self.identify.is_none() && self.device_supports_animations() {
+            // Animations would override the colour map, so pause them while we run.
+            self.goxlr
+                .set_animation_mode(false, AnimationMode::None, 0, 0, WaterFallDir::Off)?;
+        }
+
+        self.identify = Some(IdentifyState {
+            started: Instant::now(),
+            step: None,
+        });
+        self.update_identify().await?;
+        Ok(())
+    }
+
+    async fn update_identify(&mut self) -> Result<bool> {
+        let identify = match self.identify {
+            Some(identify) => identify,
+            None => return Ok(false),
+        };
+
+        let elapsed = identify.started.elapsed();
+        if elapsed >= IDENTIFY_DURATION {
+            debug!("Identify Complete, restoring Lighting..");
+            self.identify = None;
+
+            // The colour map is never written to the profile, so rebuilding it restores it.
+            if self.device_supports_animations() {
+                self.load_animation(true).await?;
+                self.preempt(&mut None).await;
+            }
+            self.load_colour_map().await?;
+            self.update_button_states()?;
+            return Ok(true);
+        }
+
+        let step = (elapsed.as_millis() / IDENTIFY_STEP.as_millis()) as usize;
+        if identify.step != Some(step) {
+            let [red, green, blue] = IDENTIFY_COLOURS[step % IDENTIFY_COLOURS.len()];
+            let colour = [blue, green, red, 255];
+
+            let use_1_3_40_format = self.device_supports_animations();
+            let mut colour_map = [0; 520];
+            for target in ColourTargets::iter() {
+                for i in 0..target.get_colour_count() {
+                    let position = target.position(i, use_1_3_40_format);
+                    colour_map[position..position + 4].copy_from_slice(&colour);
+                }
+            }
+
+            self.write_colour_map(colour_map)?;
+            self.write_button_states([ButtonStates::Colour1; 24])?;
+            self.identify = Some(IdentifyState {
+                step: Some(step),
+                ..identify
+            });
+        }
+
+        // Only the start and end of the identify are visible in the status
+        Ok(identify.step.is_none())
+    }
+
+    /// Whether a firmware update is running, in which case nothing else may touch the device.
+    pub fn is_locked(&self) -> bool {
+        self.firmware_update.is_running()
+    }
+
+    /// Locks the device for a firmware update, unless it's busy with something the update would
+    /// break. The updater moves it through the rest of the update with set_firmware_state.
+    #[allow(dead_code)]
+    pub fn begin_firmware_update(&mut self) -> Result<()> {
+        let (recording, batch_running) = match &self.audio_handler {
+            Some(audio_handler) => (
+                audio_handler.is_sample_recording(),
+                audio_handler.is_calculating(),
+            ),
+            None => (false, false),
+        };
+        let batch_running = batch_running
+            || matches!(&self.normalise_task, Some(task) if task.status.current.is_some());
+
+        let blocker = if self.is_locked() {
+            Some(FirmwareUpdateBlocker::AlreadyUpdating)
+        } else if recording {
+            Some(FirmwareUpdateBlocker::Recording)
+        } else if batch_running {
+            Some(FirmwareUpdateBlocker::BatchRunning)
+        } else {
+            None
+        };
+        if let Some(blocker) = blocker {
+            return Err(CommandError::FirmwareUpdateBlocked(blocker).into());
+        }
+
+        info!("[{}] Starting a Firmware Update", self.serial());
+        self.firmware_update = FirmwareUpdateState::Starting;
+        Ok(())
+    }
+
+    /// Moves a running update on, the device stays locked until it's Complete or Failed.
+    #[allow(dead_code)]
+    pub fn set_firmware_state(&mut self, state: FirmwareUpdateState) {
+        if !self.is_locked() {
+            warn!("[{}] No Firmware Update is running", self.serial());
+            return;
+        }
+        debug!("[{}] Firmware Update: {:?}", self.serial(), state);
+        self.firmware_update = state;
+    }
+
+    pub fn set_button_test(&mut self, enabled: bool) -> Result<()> {
+        if enabled == self.button_test.is_some() {
+            return Ok(());
+        }
+
+        if enabled {
+            info!("Starting Button Test on {}", self.serial());
+            self.button_test = Some(ButtonTestState {
+                last_activity: Instant::now(),
+                lit: EnumMap::default(),
+                registered: EnumSet::empty(),
+            });
+        } else {
+            info!("Ending Button Test on {}", self.serial());
+            self.button_test = None;
+        }
+        self.update_button_states()
+    }
+
+    fn update_button_test(&mut self) -> Result<bool> {
+        let test = match &mut self.button_test {
+            Some(test) => test,
+            None => return Ok(false),
+        };
+
+        if test.last_activity.elapsed() > BUTTON_TEST_TIMEOUT {
+            info!("Button Test timed out, returning to normal operation");
+            self.set_button_test(false)?;
+            return Ok(true);
+        }
+
+        // Turn off any buttons which have been lit long enough
+        let mut expired = false;
+        for lit in test.lit.values_mut() {
+            if lit.is_some_and(|time| time.elapsed() > BUTTON_TEST_LIT_DURATION) {
+                *lit = None;
+                expired = true;
+            }
+        }
+
+        if expired {
+            self.update_button_states()?;
+        }
+        Ok(false)
+    }
+
+    fn lighting_overridden(&self) -> bool {
+        self.identify.is_some() || self.button_test.is_some()
+    }
+
+    async fn set_reactive_lighting(&mut self, config: Option<ReactiveConfig>) -> Result<()> {
+        self.reactive = match config.clone() {
+            Some(config) => Some(ReactiveState::new(config, self.lighting_overridden())?),
+            None => None,
+        };
+        self.settings
+            .set_device_reactive_lighting(self.serial(), config)
+            .await;
+        self.settings.save().await;
+
+        // Puts back the colours of any zones which are no longer reactive
+        self.load_colour_map().await
+    }
+
+    async fn set_high_contrast(&mut self, config: Option<HighContrastConfig>) -> Result<()> {
+        if let Some(config) = &config {
+            check_high_contrast(config)?;
+        }
+        self.high_contrast = config.clone();
+        self.settings
+            .set_device_high_contrast(self.serial(), config)
+            .await;
+        self.settings.save().await;
+
+        self.load_colour_map().await?;
+        self.update_button_states()
+    }
+
+    // High Contrast lights inactive buttons in their second colour, which it sets to a dimmed
+    // copy of the first. The Rainbow animations colour the buttons themselves, so under those
+    // the device's own dimming is used instead, which still leaves inactive buttons darker.
+    fn high_contrast_inactive_state(&self) -> ButtonStates {
+        let animated = matches!(
+            self.profile.get_animation_mode(),
+            goxlr_types::AnimationMode::RetroRainbow
+                | goxlr_types::AnimationMode::RainbowDark
+                | goxlr_types::AnimationMode::RainbowBright
+        );
+        match animated && self.device_supports_animations() {
+            true => ButtonStates::DimmedColour1,
+            false => ButtonStates::Colour2,
+        }
+    }
+
+    async fn set_headset_mode(&mut self, enabled: bool) -> Result<()> {
+        let serial = self.serial().to_owned();
+        let chat_fader = self.profile.get_fader_from_channel(ChannelName::Chat);
+
+        let current = self.settings.get_device_headset_mode(&serial).await;
+        let (commands, snapshot) = match (enabled, current) {
+            (true, Some(_)) | (false, None) => return Ok(()),
+            (true, None) => {
+                let snapshot = HeadsetSnapshot {
+                    mic_type: self.mic_profile.mic_type(),
+                    jack_gain: self.mic_profile.mic_gains()[MicrophoneType::Jack],
+                    mic_monitor: self.profile.get_router(BasicInputDevice::Microphone)
+                        [BasicOutputDevice::Headphones],
+                    mic_monitor_volume: self.profile.get_channel_volume(ChannelName::MicMonitor),
+                    chat_mute_function: chat_fader
+                        .map(|fader| self.profile.get_mute_button_behaviour(fader)),
+                };
+                let sidetone = self.settings.get_device_headset_sidetone(&serial).await;
+                (headset_commands(sidetone, chat_fader), Some(snapshot))
+            }
+            (false, Some(snapshot)) => (restore_commands(&snapshot, chat_fader), None),
+        };
+
+        // The mode is stored first, so whatever does get changed can still be put back
+        self.settings
+            .set_device_headset_mode(&serial, snapshot)
+            .await;
+        self.settings.save().await;
+
+        // Each setting is applied regardless of the others, so one failing doesn't stop the rest
+        let mut failures = vec![];
+        for command in commands {
+            let name = get_command_name(&command);
+            if let Err(error) = Box::pin(self.run_command(command)).await {
+                warn!("Headset Mode unable to apply {}: {}", name, error);
+                failures.push(format!("{} ({})", name, error));
+            }
+            self.preempt(&mut None).await;
+        }
+
+        let message = format!("Headset Mode {}", tts_bool_to_state(enabled));
+        self.announce(NotificationCategory::Microphone, message)
+            .await;
+
+        if !failures.is_empty() {
+            bail!(
+                "Headset Mode was {}, but some settings couldn't be changed: {}",
+                if enabled { "enabled" } else { "disabled" },
+                failures.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    async fn set_headset_sidetone(&mut self, sidetone: u8) -> Result<()> {
+        check_sidetone(sidetone)?;
+        let serial = self.serial().to_owned();
+        self.settings
+            .set_device_headset_sidetone(&serial, sidetone)
+            .await;
+        self.settings.save().await;
+
+        if self
+            .settings
+            .get_device_headset_mode(&serial)
+            .await
+            .is_some()
+        {
+            let volume = sidetone_volume(sidetone);
+            Box::pin(self.run_command(GoXLRCommand::SetVolume(ChannelName::MicMonitor, volume)))
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn privacy_locked(&self) -> bool {
+        self.privacy.as_ref().is_some_and(|privacy| privacy.locked)
+    }
+
+    async fn set_privacy_mode(&mut self, config: Option<PrivacyConfig>) -> Result<()> {
+        // Changing the options of an unlocked mode doesn't lock it again
+        self.privacy = match (config.clone(), self.privacy.take()) {
+            (Some(config), Some(privacy)) => Some(PrivacyState { config, ..privacy }),
+            (Some(config), None) => Some(PrivacyState::new(config)),
+            (None, _) => None,
+        };
+        self.settings
+            .set_device_privacy_mode(self.serial(), config)
+            .await;
+        self.settings.save().await;
+
+        self.apply_privacy().await
+    }
+
+    async fn set_privacy_locked(&mut self, locked: bool) -> Result<()> {
+        let privacy = match &mut self.privacy {
+            Some(privacy) => privacy,
+            None => bail!("Privacy Mode is not enabled"),
+        };
+        privacy.last_activity = Instant::now();
+        if privacy.locked == locked {
+            return Ok(());
+        }
+        privacy.locked = locked;
+        self.apply_privacy().await?;
+
+        let message = match locked {
+            true => String::from("Microphone Locked"),
+            false => String::from("Microphone Unlocked"),
+        };
+        self.announce(NotificationCategory::Mute, message).await;
+        Ok(())
+    }
+
+    /// Called by the primary worker when the screen is locked.
+    pub async fn screen_locked(&mut self) -> Result<()> {
+        match &self.privacy {
+            Some(privacy) if privacy.config.relock_on_screen_lock => {
+                debug!("Screen Locked, locking the Microphone on {}", self.serial());
+                self.set_privacy_locked(true).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn update_privacy(&mut self) -> Result<bool> {
+        match &self.privacy {
+            Some(privacy) if !privacy.locked && privacy.idle_expired() => {
+                debug!("Idle for too long, locking the Microphone");
+                self.set_privacy_locked(true).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Moves the polling between the configured and idle rates, returning true if it changed.
+    /// Reactive Lighting follows the Mic level, and the Button Test (or a held button) is
+    /// watching for changes, so neither lets it slow down.
+    fn update_poll_interval(&mut self, now: Instant) -> bool {
+        let reactive = matches!(&self.reactive, Some(reactive) if !reactive.suspended);
+        let stay_fast = reactive || self.button_test.is_some() || !self.last_buttons.is_empty();
+
+        match self.polling.update(now, stay_fast) {
+            Some(interval) => {
+                debug!("Polling the device every {}ms", interval);
+                self.goxlr
+                    .set_poll_interval(Duration::from_millis(interval.into()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Writes the Mic's mute state and lighting, which both depend on the lock
+    async fn apply_privacy(&mut self) -> Result<()> {
+        self.write_channel_state(ChannelName::Mic, self.get_mic_channel_state())?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
+        self.load_colour_map().await?;
+        self.update_button_states()
+    }
+
+    /// Commands which directly unmute the Mic, these are refused while Privacy Mode is locked.
+    /// Anything else which would unmute it (button presses, profile loads) carries on, but the
+    /// Mic is held muted regardless (see write_channel_state).
+    fn unmutes_mic(&self, command: &GoXLRCommand) -> bool {
+        match command {
+            GoXLRCommand::SetCoughMuteState(MuteState::Unmuted) => true,
+            GoXLRCommand::SetFaderMuteState(fader, MuteState::Unmuted) => {
+                self.profile.get_fader_assignment(*fader) == ChannelName::Mic
+            }
+            _ => false,
+        }
+    }
+
+    // The buttons which mute the Mic, lit distinctly while Privacy Mode is locked
+    fn mic_mute_buttons(&self) -> Vec<Buttons> {
+        let mut buttons = vec![Buttons::MicrophoneMute];
+        if let Some(fader) = self.profile.get_mic_fader() {
+            buttons.push(fader_mute_button(fader));
+        }
+        buttons
+    }
+
+    async fn update_reactive_lighting(&mut self) -> Result<bool> {
+        let suspended = self.lighting_overridden();
+        let reactive = match &mut self.reactive {
+            Some(reactive) => reactive,
+            None => return Ok(false),
+        };
+
+        if reactive.suspended != suspended {
+            debug!("Reactive Lighting Suspended: {}", suspended);
+            reactive.suspended = suspended;
+            reactive.polled = None;
+            reactive.colour = None;
+
+            // The Identify replaces the whole map itself, but the Button Test only changes the
+            // button states, so the static colours are put back underneath it.
+            if suspended {
+                self.load_colour_map().await?;
+            }
+            return Ok(true);
+        }
+
+        let elapsed = reactive.polled.map(|polled| polled.elapsed());
+        if suspended || elapsed.is_some_and(|elapsed| elapsed < REACTIVE_POLL_INTERVAL) {
+            return Ok(false);
+        }
+
+        let level = self.get_mic_level().await?;
+        let reactive = match &mut self.reactive {
+            Some(reactive) => reactive,
+            None => return Ok(false),
+        };
+        reactive.polled = Some(Instant::now());
+
+        let colour = reactive.follow(level, elapsed.unwrap_or_default());
+        let colour_changed = reactive.colour != Some(colour);
+        reactive.colour = Some(colour);
+
+        let mut state_updated = false;
+        let level = level.round();
+        if reactive.level != Some(level)
+            && reactive.level_published.elapsed() >= REACTIVE_LEVEL_INTERVAL
+        {
+            reactive.level = Some(level);
+            reactive.level_published = Instant::now();
+            state_updated = true;
+        }
+
+        if colour_changed {
+            self.load_colour_map().await?;
+        }
+        Ok(state_updated)
+    }
+
+    pub async fn monitor_inputs(&mut self) -> Result<bool> {
+        // Polling is suspended while the firmware updates
+        if self.is_locked() {
+            return Ok(false);
+        }
+
+        let started = Instant::now();
+        let result = self.handle_inputs().await;
+        if result.is_ok() {
+            self.last_heartbeat = Instant::now();
+        }
+
+        let elapsed = started.elapsed();
+        self.input_timings.record(elapsed);
+        if elapsed > SLOW_OPERATION_THRESHOLD {
+            warn!("Handling inputs took {:?}", elapsed);
+        }
+        result
+    }
+
+    /// Called between the steps of bulk work, so button presses and interactive commands which
+    /// arrived since it started don't wait for it to finish. Once anything has run, the state the
+    /// work started from no longer describes the device, so the rest of it is written in full.
+    async fn preempt(&mut self, current: &mut Option<CurrentState>) {
+        // Whatever runs from here is interactive, it never reaches another preemption point
+        if self.preempting {
+            return;
+        }
+        self.preempting = true;
+
+        let mut handled = match self.monitor_inputs().await {
+            Ok(changed) => changed,
+            Err(error) => {
+                warn!("Unable to check the inputs part way through: {}", error);
+                false
+            }
+        };
+
+        let queued = match &self.interactive {
+            Some(queue) => queue.take_for(self.serial()),
+            None => vec![],
+        };
+        for queued in queued {
+            debug!("Running {:?} ahead of the remaining work", queued.command);
+            let channels = self.channels_changed_by(&queued.command);
+            let result = Box::pin(self.perform_command(queued.command.clone()))
+                .await
+                .map(|()| self.take_command_response(&queued.command));
+            if result.is_ok() {
+                self.preempted.push(PreemptedCommand {
+                    command: queued.command,
+                    client_id: queued.client_id,
+                    channels,
+                });
+            }
+            let _ = queued.sender.send(result);
+            handled = true;
+        }
+        self.preempting = false;
+
+        if handled {
+            *current = None;
+        }
+    }
+
+    async fn handle_inputs(&mut self) -> Result<bool> {
+        let state = self.goxlr.get_button_states()?;
+        let mut changed = self.update_volumes_to(state.volumes).await?;
+        let result = self.update_encoders_to(state.encoders).await?;
         if !changed {
             // Only change the value if it's not already true..
             changed = result;
@@ -524,10 +1937,24 @@ impl<'a> Device<'a> {
             self.button_states[button] = ButtonState {
                 press_time: Some(Instant::now()),
                 hold_handled: false,
+                ignored: self.button_test.is_some(),
             };
 
-            if let Err(error) = self.on_button_down(button).await {
-                error!("{}", error);
+            if let Some(test) = &mut self.button_test {
+                debug!("Button Test: {:?} Pressed", button);
+                test.last_activity = Instant::now();
+                test.lit[button] = Some(Instant::now());
+                test.registered.insert(button);
+                self.button_events.push(ButtonEvent {
+                    serial: self.hardware.serial_number.clone(),
+                    button: usb_to_standard_button(button),
+                });
+                self.update_button_states()?;
+            } else {
+                if let Err(error) = self.on_button_down(button).await {
+                    error!("{}", error);
+                }
+                self.check_consistency().await;
             }
 
             changed = true;
@@ -538,24 +1965,94 @@ impl<'a> Device<'a> {
             let button_state = self.button_states[button];
 
             // Output errors, but don't throw them up the stack!
-            if let Err(error) = self.on_button_up(button, &button_state).await {
-                error!("{}", error);
+            if !button_state.ignored {
+                if let Err(error) = self.on_button_up(button, &button_state).await {
+                    error!("{}", error);
+                }
+                self.check_consistency().await;
             }
 
             self.button_states[button] = ButtonState {
                 press_time: None,
                 hold_handled: false,
+                ignored: false,
             };
 
             changed = true;
         }
 
         self.last_buttons = state.pressed;
+
+        let jack_states = self.goxlr.get_jack_states()?;
+        if jack_states != self.jack_states {
+            let previous = std::mem::replace(&mut self.jack_states, jack_states);
+            if let Err(error) = self.on_jacks_changed(previous, jack_states).await {
+                error!("{}", error);
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.polling.activity(Instant::now());
+        }
+        self.update_poll_interval(Instant::now());
         Ok(changed)
     }
 
+    fn get_jack_status(&self) -> JackStatus {
+        let mut status = JackStatus::default();
+        if let Some(jacks) = self.jack_states {
+            status.outputs[BasicOutputDevice::Headphones] = Some(jacks.headphones);
+            status.outputs[BasicOutputDevice::LineOut] = Some(jacks.line_out);
+            status.microphone = Some(jacks.microphone);
+        }
+        status
+    }
+
+    /// If enabled, monitoring moves to the Line Out while the Headphones are unplugged, and
+    /// back again when they return (as long as the monitor wasn't changed in the meantime).
+    async fn on_jacks_changed(
+        &mut self,
+        previous: Option<JackStates>,
+        current: Option<JackStates>,
+    ) -> Result<()> {
+        let headphones = (
+            previous.map(|jacks| jacks.headphones),
+            current.map(|jacks| jacks.headphones),
+        );
+        let enabled = self
+            .settings
+            .get_device_switch_monitor_on_unplug(self.serial())
+            .await;
+        let monitor = self.profile.get_monitoring_mix();
+
+        match headphones {
+            (Some(true), Some(false)) => {
+                info!("Headphones Unplugged");
+                if enabled && monitor == BasicOutputDevice::Headphones {
+                    debug!("Switching the Monitor to the Line Out");
+                    self.set_monitor_mix(BasicOutputDevice::LineOut).await?;
+                    self.monitor_switched_on_unplug = true;
+                }
+            }
+            (Some(false), Some(true)) => {
+                info!("Headphones Plugged In");
+                if self.monitor_switched_on_unplug && monitor == BasicOutputDevice::LineOut {
+                    debug!("Switching the Monitor back to the Headphones");
+                    self.set_monitor_mix(BasicOutputDevice::Headphones).await?;
+                }
+                self.monitor_switched_on_unplug = false;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn on_button_down(&mut self, button: Buttons) -> Result<()> {
         debug!("Handling Button Down: {:?}", button);
+        if let Some(privacy) = &mut self.privacy {
+            privacy.last_activity = Instant::now();
+        }
 
         match button {
             Buttons::MicrophoneMute => {
@@ -610,42 +2107,72 @@ impl<'a> Device<'a> {
             Buttons::MicrophoneMute => {
                 self.handle_cough_mute(false, false, true, false).await?;
             }
-            _ => {}
-        }
-        self.update_button_states()?;
-        Ok(())
-    }
-
-    async fn on_button_up(&mut self, button: Buttons, state: &ButtonState) -> Result<()> {
-        debug!(
-            "Handling Button Release: {:?}, Has Long Press Handled: {:?}",
-            button, state.hold_handled
-        );
-        match button {
-            Buttons::Fader1Mute => {
-                if !state.hold_handled {
-                    self.handle_fader_mute(FaderName::A, false).await?;
-                    return Ok(());
-                }
+            Buttons::EffectSelect1 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset1)
+                    .await?;
             }
-            Buttons::Fader2Mute => {
-                if !state.hold_handled {
-                    self.handle_fader_mute(FaderName::B, false).await?;
-                    return Ok(());
-                }
+            Buttons::EffectSelect2 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset2)
+                    .await?;
             }
-            Buttons::Fader3Mute => {
-                if !state.hold_handled {
-                    self.handle_fader_mute(FaderName::C, false).await?;
-                    return Ok(());
-                }
+            Buttons::EffectSelect3 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset3)
+                    .await?;
             }
-            Buttons::Fader4Mute => {
-                if !state.hold_handled {
-                    self.handle_fader_mute(FaderName::D, false).await?;
-                    return Ok(());
-                }
+            Buttons::EffectSelect4 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset4)
+                    .await?;
+            }
+            Buttons::EffectSelect5 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset5)
+                    .await?;
+            }
+            Buttons::EffectSelect6 => {
+                self.start_effect_preview(button, EffectBankPresets::Preset6)
+                    .await?;
+            }
+            Buttons::EffectFx => {
+                self.clear_effect_toggles().await?;
+            }
+            _ => {}
+        }
+        self.update_button_states()?;
+        Ok(())
+    }
+
+    async fn on_button_up(&mut self, button: Buttons, state: &ButtonState) -> Result<()> {
+        debug!(
+            "Handling Button Release: {:?}, Has Long Press Handled: {:?}",
+            button, state.hold_handled
+        );
+        if let Some(fader) = fader_for_mute_button(button) {
+            if self.fader_fine[fader].take().is_some() {
+                // The fader has been moved away from the volume, so rather than jumping to
+                // wherever it sits, it needs picking up again before it's followed.
+                debug!("Finished fine adjustment of Fader {}", fader);
+                let channel = self.profile.get_fader_assignment(fader);
+                let volume = self.profile.get_channel_volume(channel);
+                self.fader_pickups[fader].set(volume, self.fader_last_seen[fader]);
+                return Ok(());
+            }
+
+            if !state.hold_handled {
+                // Holds are only deferred to here when fine adjustment is enabled
+                let held = self.is_fine_fader_adjust_enabled().await
+                    && state
+                        .press_time
+                        .is_some_and(|time| time.elapsed() > self.hold_time);
+                self.handle_fader_mute(fader, held).await?;
+                return Ok(());
             }
+        }
+
+        match button {
+            // Held fader mutes were handled when the hold was reached
+            Buttons::Fader1Mute
+            | Buttons::Fader2Mute
+            | Buttons::Fader3Mute
+            | Buttons::Fader4Mute => {}
             Buttons::MicrophoneMute => {
                 self.handle_cough_mute(false, true, false, state.hold_handled)
                     .await?;
@@ -654,22 +2181,28 @@ impl<'a> Device<'a> {
                 self.handle_swear_button(false).await?;
             }
             Buttons::EffectSelect1 => {
-                self.load_effect_bank(EffectBankPresets::Preset1).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset1)
+                    .await?;
             }
             Buttons::EffectSelect2 => {
-                self.load_effect_bank(EffectBankPresets::Preset2).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset2)
+                    .await?;
             }
             Buttons::EffectSelect3 => {
-                self.load_effect_bank(EffectBankPresets::Preset3).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset3)
+                    .await?;
             }
             Buttons::EffectSelect4 => {
-                self.load_effect_bank(EffectBankPresets::Preset4).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset4)
+                    .await?;
             }
             Buttons::EffectSelect5 => {
-                self.load_effect_bank(EffectBankPresets::Preset5).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset5)
+                    .await?;
             }
             Buttons::EffectSelect6 => {
-                self.load_effect_bank(EffectBankPresets::Preset6).await?;
+                self.handle_effect_select(button, EffectBankPresets::Preset6)
+                    .await?;
             }
 
             // The following 3 are simple, but will need more work once effects are
@@ -686,7 +2219,15 @@ impl<'a> Device<'a> {
                     .await?;
             }
             Buttons::EffectFx => {
-                self.set_effects(!self.profile.is_fx_enabled()).await?;
+                // If the hold cleared the effects, the release shouldn't also toggle FX
+                let cleared = state.hold_handled
+                    && self
+                        .settings
+                        .get_device_clear_effects_on_hold(self.serial())
+                        .await;
+                if !cleared {
+                    self.set_effects(!self.profile.is_fx_enabled()).await?;
+                }
             }
 
             Buttons::SamplerSelectA => {
@@ -789,12 +2330,12 @@ impl<'a> Device<'a> {
 
             if mute_function == MuteFunction::All {
                 // In this scenario, we should just set cough_button_on and mute the channel.
-                self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+                self.write_channel_state(ChannelName::Mic, Muted)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             }
 
             let message = format!("Mic Muted{}", target);
-            let _ = self.global_events.send(TTSMessage(message)).await;
+            self.announce(NotificationCategory::Mute, message).await;
 
             self.apply_routing(BasicInputDevice::Microphone).await?;
             return Ok(());
@@ -812,9 +2353,9 @@ impl<'a> Device<'a> {
             self.profile.set_mute_chat_button_blink(true);
 
             let message = "Mic Muted".to_string();
-            let _ = self.global_events.send(TTSMessage(message)).await;
+            self.announce(NotificationCategory::Mute, message).await;
 
-            self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+            self.write_channel_state(ChannelName::Mic, Muted)?;
             self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             self.apply_routing(BasicInputDevice::Microphone).await?;
             return Ok(());
@@ -834,12 +2375,12 @@ impl<'a> Device<'a> {
                     if (muted_to_all || (muted_to_x && mute_function == MuteFunction::All))
                         && !self.mic_muted_by_fader()
                     {
-                        self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+                        self.write_channel_state(ChannelName::Mic, Unmuted)?;
                         self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
                     }
 
                     let message = "Mic Unmuted".to_string();
-                    let _ = self.global_events.send(TTSMessage(message)).await;
+                    self.announce(NotificationCategory::Mute, message).await;
                     self.apply_routing(BasicInputDevice::Microphone).await?;
                     return Ok(());
                 }
@@ -848,12 +2389,12 @@ impl<'a> Device<'a> {
                 self.profile.set_mute_chat_button_on(true);
 
                 if mute_function == MuteFunction::All {
-                    self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+                    self.write_channel_state(ChannelName::Mic, Muted)?;
                     self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
                 }
 
                 let message = format!("Mic Muted{}", target);
-                let _ = self.global_events.send(TTSMessage(message)).await;
+                self.announce(NotificationCategory::Mute, message).await;
 
                 // Update the transient routing..
                 self.apply_routing(BasicInputDevice::Microphone).await?;
@@ -862,12 +2403,12 @@ impl<'a> Device<'a> {
 
             self.profile.set_mute_chat_button_on(false);
             if mute_function == MuteFunction::All && !self.mic_muted_by_fader() {
-                self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+                self.write_channel_state(ChannelName::Mic, Unmuted)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             }
 
             let message = "Mic Unmuted".to_string();
-            let _ = self.global_events.send(TTSMessage(message)).await;
+            self.announce(NotificationCategory::Mute, message).await;
 
             // Disable button and refresh transient routing
             self.apply_routing(BasicInputDevice::Microphone).await?;
@@ -898,7 +2439,7 @@ impl<'a> Device<'a> {
         // Ok, we need to announce where we're muted to..
         let name = self.profile.get_fader_assignment(fader);
         let message = format!("{} Muted{}", name, target);
-        let _ = self.global_events.send(TTSMessage(message)).await;
+        self.announce(NotificationCategory::Mute, message).await;
 
         let input = self.get_basic_input_from_channel(channel);
         self.profile.set_mute_button_on(fader, true);
@@ -912,7 +2453,7 @@ impl<'a> Device<'a> {
     async fn mute_fader_to_all(&mut self, fader: FaderName, blink: bool) -> Result<()> {
         let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
         let channel = self.profile.get_fader_assignment(fader);
-        let lock_faders = self.settings.get_device_lock_faders(self.serial()).await;
+        let lock_faders = self.faders_locked().await;
 
         // Are we already muted to all?
         if muted_to_all {
@@ -934,13 +2475,13 @@ impl<'a> Device<'a> {
                     self.goxlr.set_volume(channel, 0)?;
                 }
             }
-            self.goxlr.set_channel_state(channel, Muted)?;
+            self.write_channel_state(channel, Muted)?;
             self.profile.set_mute_button_on(fader, true);
         }
 
         let name = self.profile.get_fader_assignment(fader);
         let message = format!("{} Muted", name);
-        let _ = self.global_events.send(TTSMessage(message)).await;
+        self.announce(NotificationCategory::Mute, message).await;
 
         if blink {
             self.profile.set_mute_button_blink(fader, true);
@@ -970,7 +2511,7 @@ impl<'a> Device<'a> {
     async fn unmute_fader(&mut self, fader: FaderName) -> Result<()> {
         let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
         let channel = self.profile.get_fader_assignment(fader);
-        let lock_faders = self.settings.get_device_lock_faders(self.serial()).await;
+        let lock_faders = self.faders_locked().await;
 
         if !muted_to_x && !muted_to_all {
             // Nothing to do.
@@ -988,7 +2529,7 @@ impl<'a> Device<'a> {
             if channel != ChannelName::Mic
                 || (channel == ChannelName::Mic && !self.mic_muted_by_cough())
             {
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.write_channel_state(channel, Unmuted)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             }
 
@@ -1027,12 +2568,37 @@ impl<'a> Device<'a> {
 
         let name = self.profile.get_fader_assignment(fader);
         let message = format!("{} unmuted", name);
-        let _ = self.global_events.send(TTSMessage(message)).await;
+        self.announce(NotificationCategory::Mute, message).await;
 
         self.update_button_states()?;
         Ok(())
     }
 
+    /// Whether Mute to All should leave the faders where they are. On the GoXLR the fader
+    /// position *is* the channel volume, there's no way to set one without the other, so Silent
+    /// Mode can't do any better than Lock Faders here and simply forces it on. Volume changes
+    /// which actually alter what's heard (profile and scene loads) still have to move them.
+    async fn faders_locked(&self) -> bool {
+        self.settings.get_device_lock_faders(self.serial()).await
+            || self.settings.get_device_silent_faders(self.serial()).await
+    }
+
+    /// Called when either Lock Faders or Silent Mode changes, if this changes whether the faders
+    /// are locked, any faders muted to all are moved (once) to where they should now sit.
+    async fn reconcile_fader_lock(&mut self, was_locked: bool) -> Result<()> {
+        let locked = self.faders_locked().await;
+        if locked == was_locked {
+            return Ok(());
+        }
+
+        if locked {
+            self.lock_faders()?;
+        } else {
+            self.unlock_faders()?;
+        }
+        self.load_colour_map().await
+    }
+
     fn lock_faders(&mut self) -> Result<()> {
         if self.is_device_mini() {
             return Ok(());
@@ -1097,7 +2663,8 @@ impl<'a> Device<'a> {
     async fn load_sample_bank(&mut self, bank: SampleBank) -> Result<()> {
         // Send the TTS Message..
         let tts_message = format!("Sample {}", bank);
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+        self.announce(NotificationCategory::Sampler, tts_message)
+            .await;
 
         self.profile.load_sample_bank(bank)?;
 
@@ -1113,23 +2680,34 @@ impl<'a> Device<'a> {
     }
 
     pub async fn validate_sampler(&mut self) -> Result<()> {
+        // The Mini has no sampler, its profile's samples are left for when it's loaded on a Full
+        if self.is_device_mini() {
+            return Ok(());
+        }
+
         let sample_path = self.settings.get_samples_directory().await;
+        let mut removed = false;
         for bank in SampleBank::iter() {
             for button in SampleButtons::iter() {
                 let tracks = self.profile.get_sample_bank(bank, button);
+                let count = tracks.len();
                 tracks.retain(|track| {
                     let file = PathBuf::from(track.track.clone());
 
                     // Simply, if this returns None, the file isn't present.
                     find_file_in_path(sample_path.clone(), file).is_some()
                 });
+                removed |= tracks.len() != count;
             }
         }
 
         // Because we may have removed the 'last' sample on a button, we need to refresh
         // the states to make sure everything is correctly updated.
-        self.load_colour_map().await?;
-        self.update_button_states()
+        if removed {
+            self.load_colour_map().await?;
+            self.update_button_states()?;
+        }
+        Ok(())
     }
 
     async fn handle_sample_button_down(&mut self, button: SampleButtons) -> Result<()> {
@@ -1144,6 +2722,12 @@ impl<'a> Device<'a> {
             return Ok(());
         }
 
+        // This covers recording as well as playback, an empty guarded button can't be recorded to
+        if self.is_sample_button_blocked(button) {
+            self.refuse_guarded_button(button).await;
+            return Ok(());
+        }
+
         if self.audio_handler.is_none() {
             return Err(anyhow!(
                 "Not handling button, audio handler not configured."
@@ -1211,6 +2795,89 @@ impl<'a> Device<'a> {
         };
     }
 
+    pub fn set_guard_mode(&mut self, enabled: bool) {
+        self.guard_mode = enabled;
+    }
+
+    pub fn set_interactive_queue(&mut self, queue: InteractiveQueue) {
+        self.interactive = Some(queue);
+    }
+
+    pub fn take_preempted(&mut self) -> Vec<PreemptedCommand> {
+        std::mem::take(&mut self.preempted)
+    }
+
+    pub fn take_button_events(&mut self) -> Vec<ButtonEvent> {
+        std::mem::take(&mut self.button_events)
+    }
+
+    /// When the device's inputs were last read, None if it isn't polled (so it's only read when
+    /// something changes), as there's no telling whether it's stalled.
+    pub fn heartbeat(&self) -> Option<Instant> {
+        (self.goxlr.is_polled() && !self.asleep).then_some(self.last_heartbeat)
+    }
+
+    pub async fn stall_timeout(&self) -> Duration {
+        let timeout = self.settings.get_device_stall_timeout(self.serial()).await;
+        Duration::from_secs(timeout.into())
+    }
+
+    pub fn is_faulted(&self) -> bool {
+        self.faulted
+    }
+
+    pub fn set_faulted(&mut self, faulted: bool) {
+        self.faulted = faulted;
+    }
+
+    /// Called by the watchdog when the device hasn't been read for too long. When resetting, the
+    /// polling is stopped here, and the device is dropped and connected again by the worker.
+    pub async fn report_stall(&mut self, resetting: bool) {
+        warn!(
+            "[{}] Not read for {:?}, last command: {:?}, last USB request: {:?}",
+            self.serial(),
+            self.last_heartbeat.elapsed(),
+            self.last_command,
+            self.goxlr.last_request()
+        );
+
+        let message = match resetting {
+            true => {
+                self.goxlr.stop_polling();
+                String::from("GoXLR stopped responding, resetting")
+            }
+            false => String::from("GoXLR keeps stopping responding, it won't be reset again"),
+        };
+        self.announce(NotificationCategory::Device, message).await;
+    }
+
+    fn is_sample_button_blocked(&self, button: SampleButtons) -> bool {
+        self.guard_mode && self.profile.is_sample_button_guarded(button)
+    }
+
+    async fn refuse_guarded_button(&mut self, button: SampleButtons) {
+        debug!("Sample button {} is guarded, ignoring..", button);
+        self.guard_flashes[button] = Some(Instant::now());
+
+        let message = format!("Sample button {} is guarded", button);
+        self.announce(NotificationCategory::Guard, message).await;
+    }
+
+    fn update_guard_flashes(&mut self) -> Result<()> {
+        let mut expired = false;
+        for flash in self.guard_flashes.values_mut() {
+            if flash.is_some_and(|time| time.elapsed() > GUARD_FLASH_DURATION) {
+                *flash = None;
+                expired = true;
+            }
+        }
+
+        if expired {
+            self.update_button_states()?;
+        }
+        Ok(())
+    }
+
     async fn stop_all_samples(&mut self, playback: bool, recording: bool) -> Result<()> {
         if let Some(audio) = &mut self.audio_handler {
             for bank in SampleBank::iter() {
@@ -1230,23 +2897,109 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    // Stops any recordings and replaces the recorder, picking up the current pre-buffer duration
+    async fn rebuild_audio_handler(&mut self) -> Result<()> {
+        self.stop_all_samples(false, true).await?;
+
+        let duration = self
+            .settings
+            .get_device_sampler_pre_buffer(self.serial())
+            .await;
+        if let Some(handler) = &mut self.audio_handler {
+            handler.update_record_buffer(duration)?;
+        }
+        Ok(())
+    }
+
+    async fn check_audio_server(&mut self) -> Result<bool> {
+        let change = match self.audio_handler.as_mut().and_then(|a| a.check_server()) {
+            Some(change) => change,
+            None => return Ok(false),
+        };
+
+        match change {
+            ServerChange::Lost => self.stop_all_samples(true, true).await?,
+            ServerChange::Restored => self.rebuild_audio_handler().await?,
+        }
+        self.announce(NotificationCategory::Sampler, change.to_string())
+            .await;
+        Ok(true)
+    }
+
     async fn handle_sample_clear(&mut self) -> Result<()> {
         if let Some(audio) = &self.audio_handler {
             let state = self.profile.is_sample_clear_active();
             if !audio.is_sample_recording() {
                 let message = format!("Sample Clear {}", tts_bool_to_state(!state));
-                self.global_events.send(TTSMessage(message)).await?;
+                self.announce(NotificationCategory::Sampler, message).await;
 
                 self.profile.set_sample_clear_active(!state);
+                self.sample_clear_armed = (!state).then(Instant::now);
             }
         }
         Ok(())
     }
 
+    // Disarms Sample Clear once it's been armed for longer than the configured timeout.
+    async fn update_sample_clear(&mut self) -> Result<bool> {
+        if !self.profile.is_sample_clear_active() {
+            self.sample_clear_armed = None;
+            return Ok(self.sample_clear_remaining.take().is_some());
+        }
+
+        // Clear can also be armed by loading a profile, so start timing from when we notice.
+        let armed = *self.sample_clear_armed.get_or_insert_with(Instant::now);
+        let timeout = self
+            .settings
+            .get_device_sample_clear_timeout(self.serial())
+            .await;
+        if timeout == 0 {
+            return Ok(self.sample_clear_remaining.take().is_some());
+        }
+
+        let remaining = Duration::from_secs(timeout.into()).saturating_sub(armed.elapsed());
+        if !remaining.is_zero() {
+            // Round up, so the countdown reaches 0 as it disarms.
+            let seconds = remaining.as_millis().div_ceil(1000) as u16;
+            return Ok(self.sample_clear_remaining.replace(seconds) != Some(seconds));
+        }
+
+        // If a sample button is being held, its release will perform the clear (a press is
+        // ignored while armed), so leave it armed until then rather than pulling it away.
+        let sample_buttons = [
+            Buttons::SamplerTopLeft,
+            Buttons::SamplerTopRight,
+            Buttons::SamplerBottomLeft,
+            Buttons::SamplerBottomRight,
+        ];
+        if sample_buttons
+            .iter()
+            .any(|button| self.last_buttons.contains(*button))
+        {
+            return Ok(false);
+        }
+
+        debug!("Sample Clear timed out, disarming..");
+        self.profile.set_sample_clear_active(false);
+        self.sample_clear_armed = None;
+        self.sample_clear_remaining = None;
+        self.update_button_states()?;
+
+        let message = String::from("Sample clear cancelled");
+        self.announce(NotificationCategory::Sampler, message).await;
+        Ok(true)
+    }
+
     async fn handle_sample_button_release(&mut self, button: SampleButtons) -> Result<()> {
         let active_bank = self.profile.get_active_sample_bank();
         // If clear is flashing, remove all samples from the button, disable the clearer and return..
         if self.profile.is_sample_clear_active() {
+            if self.is_sample_button_blocked(button) {
+                // Clear stays armed, another button can still be cleared
+                self.refuse_guarded_button(button).await;
+                return Ok(());
+            }
+
             debug!("Stopping any playing samples..");
             if let Some(handler) = &mut self.audio_handler {
                 // Force stop of anything playing back on this button.
@@ -1254,10 +3007,14 @@ impl<'a> Device<'a> {
             }
 
             debug!("Clearing Samples on Button..");
+            self.cancel_sample_analysis(|analysis| {
+                analysis.bank == active_bank && analysis.button == button
+            });
             self.profile.clear_all_samples(button);
 
             debug!("Cleared samples..");
             self.profile.set_sample_clear_active(false);
+            self.sample_clear_armed = None;
 
             // Check whether we should reset the Sampler Function..
             if self
@@ -1300,9 +3057,17 @@ impl<'a> Device<'a> {
                     .unwrap()
                     .stop_record(sample_bank, button)?;
 
-                if let Some((file_name, gain)) = file_name {
-                    let track = self.profile.add_sample_file(sample_bank, button, file_name);
-                    track.normalized_gain = gain;
+                if let Some(result) = file_name {
+                    if result.clipped {
+                        warn!("Recording {} clipped", result.file_name);
+                    }
+
+                    let track = self
+                        .profile
+                        .add_sample_file(sample_bank, button, result.file_name);
+                    track.normalized_gain = result.gain;
+                    track.recording_peak = result.peak.map(|peak| peak as f32);
+                    track.clipped = result.clipped;
                 }
             }
             // In all cases, we should stop the colour flashing.
@@ -1368,6 +3133,7 @@ impl<'a> Device<'a> {
 
             if result.is_ok() {
                 self.profile.set_sample_button_state(button, true);
+                self.start_sample_ducking(bank, button)?;
             } else {
                 error!("{}", result.err().unwrap());
             }
@@ -1375,6 +3141,61 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    /// Saves the sampler's pre-buffer as a new recording, this doesn't touch any buttons so can
+    /// be done while samples are playing.
+    pub async fn save_replay_buffer(&mut self, file_name: Option<String>) -> Result<ReplayCapture> {
+        if self.is_device_mini() {
+            bail!("The GoXLR Mini does not have a Sampler");
+        }
+        self.settings
+            .write_policy()
+            .check("save the replay buffer")?;
+
+        let file_name = match file_name {
+            Some(name) => {
+                let name = normalise_name(&name)?;
+                match name.to_lowercase().ends_with(".wav") {
+                    true => name,
+                    false => format!("{name}.wav"),
+                }
+            }
+            None => {
+                let file_date = Local::now().format("%Y-%m-%dT%H%M%S").to_string();
+                format!("Replay_{file_date}.wav")
+            }
+        };
+
+        let path = self
+            .settings
+            .get_samples_directory()
+            .await
+            .join("Recorded")
+            .join(file_name);
+        if path.exists() {
+            bail!("{} already exists", path.to_string_lossy());
+        }
+        check_space(&DiskFileSystem, &path, RECORDING_SPACE)?;
+
+        let audio_handler = match &self.audio_handler {
+            Some(audio_handler) => audio_handler,
+            None => bail!("Audio Handler not configured"),
+        };
+        let low_cut = self.profile.get_sampler_low_cut();
+        let duration = audio_handler.save_replay_buffer(&path, low_cut)?;
+        info!(
+            "Saved {}ms of the Sampler buffer to {}",
+            duration.as_millis(),
+            path.to_string_lossy()
+        );
+
+        let capture = ReplayCapture {
+            path,
+            duration_ms: duration.as_millis() as u32,
+        };
+        self.last_replay = Some(capture.clone());
+        Ok(capture)
+    }
+
     async fn stop_sample_playback(
         &mut self,
         bank: SampleBank,
@@ -1388,15 +3209,18 @@ impl<'a> Device<'a> {
     }
 
     async fn record_audio_file(&mut self, button: SampleButtons, file_name: String) -> Result<()> {
+        self.settings.write_policy().check("record a sample")?;
         let sample_bank = self.profile.get_active_sample_bank();
 
         // Create the full Path..
         let mut sample_path = self.settings.get_samples_directory().await;
         sample_path = sample_path.join("Recorded");
         sample_path = sample_path.join(file_name);
+        check_space(&DiskFileSystem, &sample_path, RECORDING_SPACE)?;
 
+        let low_cut = self.profile.get_sampler_low_cut();
         if let Some(audio_handler) = &mut self.audio_handler {
-            let result = audio_handler.record_for_button(sample_path, sample_bank, button);
+            let result = audio_handler.record_for_button(sample_path, sample_bank, button, low_cut);
             if result.is_ok() {
                 self.profile.set_sample_button_blink(button, true);
             }
@@ -1405,6 +3229,223 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    /// Starts calculating the gain for the next sample in the bank, any which can't be started
+    /// are recorded as failed, and the task is finished once nothing is left.
+    async fn normalise_next_sample(&mut self) {
+        loop {
+            let (bank, target, button, name) = match &mut self.normalise_task {
+                Some(task) => match task.pending.pop_front() {
+                    Some((button, name)) => (task.status.bank, task.status.target, button, name),
+                    None => {
+                        task.status.current = None;
+                        task.status.finished = true;
+                        return;
+                    }
+                },
+                None => return,
+            };
+
+            let result = match self.get_path_for_sample(PathBuf::from(&name)).await {
+                Ok(path) => match &mut self.audio_handler {
+                    Some(handler) => {
+                        handler.calculate_gain_thread(path, bank, button, target as f64, None)
+                    }
+                    None => Err(anyhow!("Sampler is not available")),
+                },
+                Err(error) => Err(error),
+            };
+
+            let task = match &mut self.normalise_task {
+                Some(task) => task,
+                None => return,
+            };
+
+            match result {
+                Ok(()) => {
+                    task.status.current = Some(NormalisingSample { button, name });
+                    return;
+                }
+                Err(error) => {
+                    warn!("Unable to Normalise {}: {}", name, error);
+                    let old_gain = self
+                        .profile
+                        .get_sample_bank_tracks(bank)
+                        .into_iter()
+                        .find(|(track_button, file, _)| *track_button == button && *file == name)
+                        .map_or(1., |(_, _, gain)| gain);
+
+                    task.status.results.push(NormalisedSample {
+                        button,
+                        name,
+                        old_gain,
+                        new_gain: None,
+                        error: Some(error.to_string()),
+                    });
+                }
+            }
+
+            // A bank full of missing files shouldn't hold anything up
+            self.preempt(&mut None).await;
+        }
+    }
+
+    // Adds a sample to its button once it's been analysed, unless the button has been cleared (or
+    // another profile loaded) since it was added, in which case it's no longer wanted.
+    async fn add_analysed_sample(&mut self, result: CalculationResult) -> Result<()> {
+        let target = SampleAnalysis {
+            bank: result.bank,
+            button: result.button,
+            file: result.file.clone(),
+        };
+        if self.sample_analysis.as_ref() != Some(&target) {
+            debug!(
+                "Discarding the analysis of {:?}, it's no longer wanted",
+                result.file
+            );
+            return Ok(());
+        }
+        self.sample_analysis = None;
+
+        if let Err(error) = result.result {
+            // We need to somehow push this to the user (via DaemonStatus probably)..
+            self.last_sample_error = Some(error.to_string());
+            return Ok(());
+        }
+
+        // A converted sample is played from its conversion, but we remember where it came from
+        let mut source = None;
+        if result.converted.is_some() {
+            let samples = self.settings.get_samples_directory().await;
+            let original = result.file.strip_prefix(&samples).unwrap_or(&result.file);
+            source = Some(original.to_string_lossy().to_string());
+        }
+
+        let file = result.converted.as_ref().unwrap_or(&result.file);
+        let filename = file.file_name().unwrap();
+        let filename = filename.to_string_lossy().to_string();
+
+        debug!("Calculated Gain: {}", result.gain);
+
+        let track = self
+            .profile
+            .add_sample_file(result.bank, result.button, filename);
+        track.normalized_gain = result.gain;
+        track.source = source;
+        track.duration_ms = result.duration.map(|d| d.as_millis() as u64);
+
+        // A single region marks the useful part of the sample, anything more than
+        // that is ambiguous, so we leave the positions for the user to choose.
+        let regions: Vec<&AudioMarker> = result.markers.iter().filter(|m| m.is_region()).collect();
+        if let [region] = regions.as_slice() {
+            if let Some(end_pct) = region.end_pct {
+                debug!("Trimming sample to region {:?}", region);
+                track.start_position = region.start_pct as f32;
+                track.end_position = end_pct as f32;
+            }
+        }
+
+        track.markers = result
+            .markers
+            .iter()
+            .map(|marker| TrackMarker {
+                label: marker.label.clone().unwrap_or_default(),
+                start_position: marker.start_pct as f32,
+                end_position: marker.end_pct.map(|end| end as f32),
+            })
+            .collect();
+
+        // Get a head start on decoding the sample for playback..
+        if let Some(audio_handler) = &mut self.audio_handler {
+            audio_handler.cache_sample(&result.file);
+        }
+        self.load_colour_map().await
+    }
+
+    /// Stops analysing a sample which is waiting to be added, if it's going to a button which
+    /// matches the filter.
+    fn cancel_sample_analysis(&mut self, filter: impl Fn(&SampleAnalysis) -> bool) {
+        if !self.sample_analysis.as_ref().is_some_and(filter) {
+            return;
+        }
+
+        if let Some(analysis) = self.sample_analysis.take() {
+            debug!("Cancelling the analysis of {:?}", analysis.file);
+        }
+        if let Some(audio_handler) = &mut self.audio_handler {
+            audio_handler.cancel_calculation();
+        }
+    }
+
+    fn store_normalised_gain(&mut self, result: CalculationResult) {
+        let task = match &mut self.normalise_task {
+            Some(task) => task,
+            None => return,
+        };
+
+        let current = match task.status.current.take() {
+            Some(current) => current,
+            None => return,
+        };
+
+        let bank = task.status.bank;
+        let old_gain = self
+            .profile
+            .get_sample_bank_tracks(bank)
+            .into_iter()
+            .find(|(button, file, _)| *button == current.button && *file == current.name)
+            .map(|(_, _, gain)| gain);
+
+        let (old_gain, new_gain, error) = match (result.result, old_gain) {
+            (Err(error), old_gain) => (old_gain.unwrap_or(1.), None, Some(error.to_string())),
+            (Ok(()), None) => (1., None, Some(String::from("Sample was removed"))),
+            (Ok(()), Some(old_gain)) => {
+                debug!(
+                    "Normalised {}: {} -> {}",
+                    current.name, old_gain, result.gain
+                );
+                let duration = result.duration.map(|d| d.as_millis() as u64);
+                self.profile.set_sample_gain(
+                    bank,
+                    current.button,
+                    &current.name,
+                    result.gain,
+                    duration,
+                );
+                (old_gain, Some(result.gain), None)
+            }
+        };
+
+        task.status.results.push(NormalisedSample {
+            button: current.button,
+            name: current.name,
+            old_gain,
+            new_gain,
+            error,
+        });
+    }
+
+    /// Samples added before their length was stored won't have one, so it's read from the
+    /// file's headers and kept with the track.
+    async fn load_sample_duration(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+        index: usize,
+    ) -> Result<()> {
+        let (name, duration) = self.profile.get_sample_duration(bank, button, index)?;
+        if duration.is_some() {
+            return Ok(());
+        }
+
+        let path = self.get_path_for_sample(PathBuf::from(&name)).await?;
+        let duration = match Player::get_duration(&path) {
+            Some(duration) => duration,
+            None => bail!("Unable to find the length of {}", name),
+        };
+        self.profile
+            .set_sample_duration(bank, button, index, duration.as_millis() as u64)
+    }
+
     async fn get_path_for_sample(&mut self, part: PathBuf) -> Result<PathBuf> {
         let sample_path = self.settings.get_samples_directory().await;
         if let Some(file) = find_file_in_path(sample_path, part) {
@@ -1440,26 +3481,177 @@ impl<'a> Device<'a> {
         Ok(changed)
     }
 
-    async fn load_effect_bank(&mut self, preset: EffectBankPresets) -> Result<()> {
-        // Send the TTS Message..
-        let preset_name = self.profile.get_effect_name(preset);
-        let tts_message = format!("Effects {}, {}", preset as u8 + 1, preset_name);
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+    fn start_sample_ducking(&mut self, bank: SampleBank, button: SampleButtons) -> Result<()> {
+        let Some(config) = self.profile.get_sample_button_ducking(bank, button) else {
+            return Ok(());
+        };
 
-        self.profile.load_effect_bank(preset)?;
-        self.set_pitch_mode()?;
-        self.load_encoder_effects()?;
+        self.drop_overridden_ducks();
+        let profile = &self.profile;
+        let changes = self.ducking.start((bank, button), &config, |channel| {
+            profile.get_channel_volume(channel)
+        });
+        self.write_ducked_volumes(changes)
+    }
 
-        self.apply_effects(self.mic_profile.get_fx_keys(self.profile.use_echo_tempo()))?;
+    /// Lets go of ducks for buttons which have stopped playing, and steps those rising back.
+    fn sync_sample_ducking(&mut self) -> Result<()> {
+        self.drop_overridden_ducks();
 
-        Ok(())
+        let now = Instant::now();
+        let mut changes = vec![];
+        for (bank, button) in self.ducking.holders() {
+            let playing = self
+                .audio_handler
+                .as_ref()
+                .is_some_and(|handler| handler.is_sample_playing(bank, button));
+            if !playing {
+                changes.extend(self.ducking.stop((bank, button), now));
+            }
+        }
+        changes.extend(self.ducking.tick(now));
+        self.write_ducked_volumes(changes)
     }
 
-    async fn set_megaphone(&mut self, enabled: bool) -> Result<()> {
-        // Send the TTS Message..
-        let tts_message = format!("Megaphone {}", tts_bool_to_state(enabled));
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+    fn drop_overridden_ducks(&mut self) {
+        let profile = &self.profile;
+        for channel in self
+            .ducking
+            .drop_overridden(|channel| profile.get_channel_volume(channel))
+        {
+            debug!("{} was changed while ducked, leaving it there", channel);
+        }
+    }
+
+    /// Sends ducked volumes to the device without touching the profile, so the volume the
+    /// channel goes back to is the one shown.
+    fn write_ducked_volumes(&mut self, changes: Vec<(ChannelName, u8)>) -> Result<()> {
+        for (channel, volume) in changes {
+            debug!("Ducking {} to {}", channel, volume);
+            self.goxlr.set_volume(channel, volume)?;
+
+            // The Full's faders follow the volume, so mustn't be mistaken for a human moving them
+            if !self.is_device_mini() {
+                if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+                    self.fader_targets[fader].set(volume, Instant::now());
+                }
+            }
+
+            if self.device_supports_submixes() && self.profile.is_submix_enabled() {
+                if let Some(mix) = self.profile.get_submix_from_channel(channel) {
+                    if self.profile.submix_linked(mix) {
+                        let ratio = self.profile.get_submix_ratio(mix);
+                        self.goxlr
+                            .set_sub_volume(mix, (volume as f64 * ratio) as u8)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_effect_select(
+        &mut self,
+        button: Buttons,
+        preset: EffectBankPresets,
+    ) -> Result<()> {
+        if let Some(preview) = self.effect_preview {
+            if preview.button == button {
+                return self.end_effect_preview();
+            }
+        }
+        self.load_effect_bank(preset).await
+    }
+
+    async fn start_effect_preview(
+        &mut self,
+        button: Buttons,
+        preset: EffectBankPresets,
+    ) -> Result<()> {
+        if self.effect_preview.is_some()
+            || !self
+                .settings
+                .get_device_effect_preview_on_hold(self.serial())
+                .await
+        {
+            return Ok(());
+        }
+
+        let previous = self.profile.get_active_effect_bank();
+        if previous == preset {
+            return Ok(());
+        }
+
+        let tts_message = format!("Previewing Effects {}", preset as u8 + 1);
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+
+        let was_dirty = self.profile.is_dirty();
+        self.apply_effect_bank(preset)?;
+
+        self.effect_preview = Some(EffectPreview {
+            button,
+            preset,
+            previous,
+            encoders: self.profile.get_encoder_values(),
+            was_dirty,
+        });
+        Ok(())
+    }
+
+    fn end_effect_preview(&mut self) -> Result<()> {
+        let preview = match self.effect_preview.take() {
+            Some(preview) => preview,
+            None => return Ok(()),
+        };
+
+        // If something else has changed the bank during the preview, leave it alone.
+        if self.profile.get_active_effect_bank() != preview.preset {
+            return Ok(());
+        }
+
+        // Throw away any encoder changes made to the previewed bank, then go back..
+        self.profile.set_encoder_values(preview.encoders)?;
+        self.apply_effect_bank(preview.previous)?;
+
+        if !preview.was_dirty {
+            self.profile.clear_dirty();
+        }
+        Ok(())
+    }
+
+    async fn load_effect_bank(&mut self, preset: EffectBankPresets) -> Result<()> {
+        // Send the TTS Message..
+        let preset_name = self.profile.get_effect_name(preset);
+        let tts_message = format!("Effects {}, {}", preset as u8 + 1, preset_name);
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+
+        // A deliberate change of bank keeps whatever's being previewed
+        self.effect_preview = None;
+        self.apply_effect_bank(preset)
+    }
+
+    fn apply_effect_bank(&mut self, preset: EffectBankPresets) -> Result<()> {
+        self.profile.load_effect_bank(preset)?;
+        self.set_pitch_mode()?;
+        self.load_encoder_effects()?;
+
+        self.apply_effects(self.mic_profile.get_fx_keys(self.profile.use_echo_tempo()))?;
+
+        Ok(())
+    }
+
+    async fn set_megaphone(&mut self, enabled: bool) -> Result<()> {
+        // Send the TTS Message..
+        let tts_message = format!("Megaphone {}", tts_bool_to_state(enabled));
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+
+        self.apply_megaphone(enabled)
+    }
 
+    fn apply_megaphone(&mut self, enabled: bool) -> Result<()> {
         self.profile.set_megaphone(enabled);
         self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphoneEnabled]))?;
         Ok(())
@@ -1468,8 +3660,13 @@ impl<'a> Device<'a> {
     async fn set_robot(&mut self, enabled: bool) -> Result<()> {
         // Send the TTS Message..
         let tts_message = format!("Robot {}", tts_bool_to_state(enabled));
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+
+        self.apply_robot(enabled)
+    }
 
+    fn apply_robot(&mut self, enabled: bool) -> Result<()> {
         self.profile.set_robot(enabled);
         self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotEnabled]))?;
         Ok(())
@@ -1478,8 +3675,13 @@ impl<'a> Device<'a> {
     async fn set_hardtune(&mut self, enabled: bool) -> Result<()> {
         // Send the TTS Message..
         let tts_message = format!("Hard tune {}", tts_bool_to_state(enabled));
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+
+        self.apply_hardtune(enabled)
+    }
 
+    fn apply_hardtune(&mut self, enabled: bool) -> Result<()> {
         self.profile.set_hardtune(enabled);
         self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneEnabled]))?;
         self.set_pitch_mode()?;
@@ -1492,10 +3694,38 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    /// Turns off Megaphone, Robot and Hard Tune together, the FX state and the active effect
+    /// bank are left as they are.
+    async fn clear_effect_toggles(&mut self) -> Result<()> {
+        if !self
+            .settings
+            .get_device_clear_effects_on_hold(self.serial())
+            .await
+        {
+            return Ok(());
+        }
+
+        if self.profile.is_megaphone_enabled(true) {
+            self.apply_megaphone(false)?;
+        }
+        if self.profile.is_robot_enabled(true) {
+            self.apply_robot(false)?;
+        }
+        if self.profile.is_hardtune_enabled(true) {
+            self.apply_hardtune(false)?;
+        }
+
+        let tts_message = String::from("Effects cleared");
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
+        Ok(())
+    }
+
     async fn set_effects(&mut self, enabled: bool) -> Result<()> {
         // Send the TTS Message..
         let tts_message = format!("Effects {}", tts_bool_to_state(enabled));
-        let _ = self.global_events.send(TTSMessage(tts_message)).await;
+        self.announce(NotificationCategory::Effects, tts_message)
+            .await;
 
         self.profile.set_effects(enabled);
 
@@ -1510,14 +3740,15 @@ impl<'a> Device<'a> {
 
     fn mic_muted_by_fader(&self) -> bool {
         // Is the mute button even assigned to a fader?
-        if self.profile.is_mic_on_fader() {
-            let fader = self.profile.get_mic_fader();
-            let (muted_to_x, muted_to_all, mute_function) =
-                self.profile.get_mute_button_state(fader);
-
-            return muted_to_all || (muted_to_x && mute_function == MuteFunction::All);
+        match self.profile.get_mic_fader() {
+            Some(fader) => self.fader_muted_to_all(fader),
+            None => false,
         }
-        false
+    }
+
+    fn fader_muted_to_all(&self, fader: FaderName) -> bool {
+        let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
+        muted_to_all || (muted_to_x && mute_function == MuteFunction::All)
     }
 
     fn mic_muted_by_cough(&self) -> bool {
@@ -1527,34 +3758,49 @@ impl<'a> Device<'a> {
         muted_to_all || (muted_to_x && mute_function == MuteFunction::All)
     }
 
+    /// Called when the volume of a fader's channel is set by anything other than the fader, so
+    /// that reports from the fader don't immediately undo it.
+    fn fader_volume_set(&mut self, fader: FaderName, volume: u8) {
+        match self.is_device_mini() {
+            true => self.fader_pickups[fader].set(volume, self.fader_last_seen[fader]),
+            false => self.fader_targets[fader].set(volume, Instant::now()),
+        }
+    }
+
     async fn update_volumes_to(&mut self, volumes: [u8; 4]) -> Result<bool> {
         let mut value_changed = false;
+        let now = Instant::now();
 
         for fader in FaderName::iter() {
             let new_volume = volumes[fader as usize];
+            let mut volume = new_volume;
             if self.is_device_mini() {
                 if new_volume == self.fader_last_seen[fader] {
                     continue;
                 }
-            } else if self.fader_pause_until[fader].paused {
-                let until = self.fader_pause_until[fader].until;
-
-                // Calculate min and max, make sure we don't overflow..
-                let min = match until < 5 {
-                    true => 0,
-                    false => until - 5,
-                };
 
-                let max = match until > 250 {
-                    true => 255,
-                    false => until + 5,
-                };
-
-                // Are we in this range?
-                if !((min)..=(max)).contains(&new_volume) {
+                if let Some(fine_volume) = self.get_fine_fader_volume(fader, new_volume).await {
+                    volume = fine_volume;
+                } else if !self.fader_pickups[fader].check(new_volume) {
+                    self.fader_last_seen[fader] = new_volume;
                     continue;
-                } else {
-                    self.fader_pause_until[fader].paused = false;
+                }
+            } else {
+                match self.fader_targets[fader].check(new_volume, now) {
+                    FaderReport::Commanded => {
+                        self.fader_last_seen[fader] = new_volume;
+                        continue;
+                    }
+                    FaderReport::Idle => {
+                        // Only a change in position is a human moving the fader, otherwise we'd
+                        // undo a target that the fader settled just short of.
+                        if new_volume == self.fader_last_seen[fader] {
+                            continue;
+                        }
+                    }
+                    FaderReport::Override => {
+                        debug!("Fader {} moved away from its target, hardware wins", fader);
+                    }
                 }
             }
             self.fader_last_seen[fader] = new_volume;
@@ -1562,22 +3808,54 @@ impl<'a> Device<'a> {
             let channel = self.profile.get_fader_assignment(fader);
             let old_volume = self.profile.get_channel_volume(channel);
 
-            if new_volume != old_volume {
+            if volume != old_volume {
                 debug!(
                     "Updating {} volume from {} to {} as a human moved the fader",
-                    channel, old_volume, new_volume
+                    channel, old_volume, volume
                 );
 
                 value_changed = true;
-                self.profile.set_channel_volume(channel, new_volume)?;
+                self.profile.set_channel_volume(channel, volume)?;
 
                 // Update the Submix..
-                self.update_submix_for(channel, new_volume)?;
+                self.update_submix_for(channel, volume)?;
             }
         }
         Ok(value_changed)
     }
 
+    async fn is_fine_fader_adjust_enabled(&self) -> bool {
+        self.is_device_mini()
+            && self
+                .settings
+                .get_device_fine_fader_adjust(self.serial())
+                .await
+    }
+
+    /// Returns the volume for a fader under fine adjustment, which starts when the fader is
+    /// moved while its mute button is held, and lasts until the button is released.
+    async fn get_fine_fader_volume(&mut self, fader: FaderName, position: u8) -> Option<u8> {
+        if self.fader_fine[fader].is_none() {
+            let button = fader_mute_button(fader);
+            if !self.last_buttons.contains(button)
+                || self.button_states[button].ignored
+                || !self.is_fine_fader_adjust_enabled().await
+            {
+                return None;
+            }
+
+            // Adjustment is relative to the volume, so there's nothing to pick up
+            debug!("Starting fine adjustment of Fader {}", fader);
+            let channel = self.profile.get_fader_assignment(fader);
+            self.fader_pickups[fader] = FaderPickup::default();
+            self.fader_fine[fader] = Some(FineFader {
+                position: self.fader_last_seen[fader],
+                volume: self.profile.get_channel_volume(channel),
+            });
+        }
+        self.fader_fine[fader].map(|fine| fine.volume_at(position))
+    }
+
     fn update_submix_for(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
         if self.device_supports_submixes() && self.profile.is_submix_enabled() {
             if let Some(mix) = self.profile.get_submix_from_channel(channel) {
@@ -1601,6 +3879,80 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    async fn save_volume_scene(&mut self, name: String) -> Result<()> {
+        if name.is_empty() {
+            bail!("Volume Scene name cannot be empty");
+        }
+
+        let lock_faders = self.faders_locked().await;
+        let mut volumes = self.profile.get_channel_volume_map();
+
+        // A channel muted to all on a full device is sat at 0, we want the volume it'll return to
+        if !self.is_device_mini() && !lock_faders {
+            for fader in FaderName::iter() {
+                if self.fader_muted_to_all(fader) {
+                    let channel = self.profile.get_fader_assignment(fader);
+                    volumes[channel] = self.profile.get_mute_button_previous_volume(fader);
+                }
+            }
+        }
+
+        let mut submix_volumes = EnumMap::default();
+        for channel in SubMixChannelName::iter() {
+            submix_volumes[channel] = self.profile.get_submix_volume(channel);
+        }
+
+        self.profile.set_volume_scene(name, volumes, submix_volumes);
+        Ok(())
+    }
+
+    async fn load_volume_scene(&mut self, name: &str) -> Result<()> {
+        let (volumes, submix_volumes) = self.profile.get_volume_scene(name)?;
+        let lock_faders = self.faders_locked().await;
+        let submix_enabled = self.device_supports_submixes() && self.profile.is_submix_enabled();
+
+        for channel in ChannelName::iter() {
+            let volume = match volumes[channel] {
+                Some(volume) => volume,
+                None => continue,
+            };
+
+            if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+                if !self.is_device_mini() && self.fader_muted_to_all(fader) {
+                    // Leave the channel muted, but make sure unmuting lands on the scene volume.
+                    self.profile.set_mute_previous_volume(fader, volume)?;
+                    if !lock_faders {
+                        continue;
+                    }
+                }
+
+                self.fader_volume_set(fader, volume);
+            }
+
+            self.profile.set_channel_volume(channel, volume)?;
+
+            // With submixes active, the Mic Monitor is held at 100% (see load_submix_settings)
+            if channel == ChannelName::MicMonitor && submix_enabled {
+                continue;
+            }
+            debug!("Setting Mix volume for {} to {}", channel, volume);
+            self.goxlr.set_volume(channel, volume)?;
+        }
+
+        if self.device_supports_submixes() {
+            for (mix, volume) in submix_volumes {
+                if let Some(volume) = volume {
+                    self.profile.set_submix_volume(mix, volume);
+                    if submix_enabled {
+                        debug!("Setting Sub Mix volume for {} to {}", mix, volume);
+                        self.goxlr.set_sub_volume(mix, volume)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn update_encoders_to(&mut self, encoders: [i8; 4]) -> Result<bool> {
         // Ok, this is funky, due to the way pitch works, the encoder 'value' doesn't match
         // the profile value if hardtune is enabled, so we'll pre-emptively calculate pitch here..
@@ -1628,6 +3980,7 @@ impl<'a> Device<'a> {
             );
             value_changed = true;
             self.profile.set_pitch_knob_position(encoders[0])?;
+            self.profile.rebase_effects_intensity(EncoderName::Pitch);
             self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))?;
 
             let user_value = self
@@ -1636,7 +3989,7 @@ impl<'a> Device<'a> {
 
             if !self.is_device_mini() {
                 let message = format!("Pitch {}", user_value);
-                let _ = self.global_events.send(TTSMessage(message)).await;
+                self.announce(NotificationCategory::Encoders, message).await;
             }
         }
 
@@ -1663,7 +4016,7 @@ impl<'a> Device<'a> {
 
                 if !self.is_device_mini() {
                     let message = format!("Gender {}", new_value);
-                    let _ = self.global_events.send(TTSMessage(message)).await;
+                    self.announce(NotificationCategory::Encoders, message).await;
                 }
             }
         }
@@ -1677,6 +4030,7 @@ impl<'a> Device<'a> {
 
             value_changed = true;
             self.profile.set_reverb_value(encoders[2])?;
+            self.profile.rebase_effects_intensity(EncoderName::Reverb);
 
             let new_value = self
                 .mic_profile
@@ -1688,7 +4042,7 @@ impl<'a> Device<'a> {
 
             if !self.is_device_mini() {
                 let message = format!("Reverb {} percent", percent);
-                let _ = self.global_events.send(TTSMessage(message)).await;
+                self.announce(NotificationCategory::Encoders, message).await;
             }
         }
 
@@ -1700,6 +4054,7 @@ impl<'a> Device<'a> {
             );
             value_changed = true;
             self.profile.set_echo_value(encoders[3])?;
+            self.profile.rebase_effects_intensity(EncoderName::Echo);
             self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoAmount]))?;
 
             let mut user_value = self
@@ -1709,100 +4064,422 @@ impl<'a> Device<'a> {
 
             if !self.is_device_mini() {
                 let message = format!("Echo {} percent", user_value);
-                let _ = self.global_events.send(TTSMessage(message)).await;
+                self.announce(NotificationCategory::Encoders, message).await;
             }
         }
 
         Ok(value_changed)
     }
 
-    pub async fn get_mic_level(&mut self) -> Result<f64> {
-        let level = self.goxlr.get_microphone_level()?;
-
-        let db = ((f64::log(level.into(), 10.) * 20.) - 72.2).clamp(-72.2, 0.);
-        Ok(db)
-    }
-
-    pub async fn perform_command(&mut self, command: GoXLRCommand) -> Result<()> {
-        match command {
-            GoXLRCommand::SetShutdownCommands(commands) => {
-                self.settings
-                    .set_device_shutdown_commands(self.serial(), commands)
-                    .await;
-                self.settings.save().await;
-            }
-            GoXLRCommand::SetSleepCommands(commands) => {
-                self.settings
-                    .set_device_sleep_commands(self.serial(), commands)
-                    .await;
-                self.settings.save().await;
+    /// Applies '<name>.txt' from the mic profile directory, the name is from a client so nothing
+    /// outside of there can be read.
+    pub async fn import_eq_curve(&mut self, name: &str, target: EqTarget) -> Result<EqCurveReport> {
+        if target == EqTarget::Headphones {
+            bail!("Headphone EQ is not supported by this device");
+        }
+        let path = self
+            .get_eq_curve_path(name, EQ_CURVE_IMPORT_EXTENSION)
+            .await?;
+        let path = path.as_path();
+
+        // The curve is mapped to where the bands currently are, rather than their defaults.
+        if self.hardware.device_type == DeviceType::Mini {
+            let bands: Vec<MiniEqFrequencies> = MiniEqFrequencies::iter().collect();
+            let frequencies: Vec<f32> = bands
+                .iter()
+                .map(|band| self.mic_profile.get_mini_eq_freq(*band))
+                .collect();
+
+            let (report, gains) = map_eq_curve(path, &frequencies)?;
+            for (band, gain) in bands.into_iter().zip(gains) {
+                self.perform_command(GoXLRCommand::SetEqMiniGain(band, gain))
+                    .await?;
             }
-            GoXLRCommand::SetWakeCommands(commands) => {
-                self.settings
-                    .set_device_wake_commands(self.serial(), commands)
-                    .await;
-                self.settings.save().await;
+            Ok(report)
+        } else {
+            let bands: Vec<EqFrequencies> = EqFrequencies::iter().collect();
+            let frequencies: Vec<f32> = bands
+                .iter()
+                .map(|band| self.mic_profile.get_eq_freq(*band))
+                .collect();
+
+            let (report, gains) = map_eq_curve(path, &frequencies)?;
+            for (band, gain) in bands.into_iter().zip(gains) {
+                self.perform_command(GoXLRCommand::SetEqGain(band, gain))
+                    .await?;
             }
-            GoXLRCommand::SetSamplerPreBufferDuration(duration) => {
-                if duration > 30000 {
-                    bail!("Buffer must be below 30seconds");
-                }
+            Ok(report)
+        }
+    }
 
-                self.settings
-                    .set_device_sampler_pre_buffer(self.serial(), duration)
-                    .await;
-                self.settings.save().await;
+    /// Writes the EQ to '<name>.csv' in the mic profile directory, an existing file is never
+    /// written over.
+    pub async fn export_eq_curve(&self, name: &str, target: EqTarget) -> Result<()> {
+        self.settings.write_policy().check("export an EQ curve")?;
+        if target == EqTarget::Headphones {
+            bail!("Headphone EQ is not supported by this device");
+        }
+        let path = self
+            .get_eq_curve_path(name, EQ_CURVE_EXPORT_EXTENSION)
+            .await?;
+        if path.exists() {
+            bail!("{} already exists", path.to_string_lossy());
+        }
 
-                // Reload the Audio Handler...
-                self.stop_all_samples(false, true).await?;
+        let bands: Vec<(f32, i8)> = if self.hardware.device_type == DeviceType::Mini {
+            MiniEqFrequencies::iter()
+                .map(|band| {
+                    let frequency = self.mic_profile.get_mini_eq_freq(band);
+                    (frequency, self.mic_profile.get_mini_eq_gain(band))
+                })
+                .collect()
+        } else {
+            EqFrequencies::iter()
+                .map(|band| {
+                    let frequency = self.mic_profile.get_eq_freq(band);
+                    (frequency, self.mic_profile.get_eq_gain(band))
+                })
+                .collect()
+        };
+        write_eq_curve(&path, &bands)
+    }
 
-                // Drop the Audio Handler..
-                if let Some(handler) = &mut self.audio_handler {
-                    handler.update_record_buffer(duration)?;
-                }
-            }
+    // The name comes from a client, so it's refused rather than cleaned up, anything which could
+    // lead outside the mic profile directory (separators or '..') is a problem
+    async fn get_eq_curve_path(&self, name: &str, extension: &str) -> Result<PathBuf> {
+        if let Some(problem) = find_name_problem(name) {
+            return Err(CommandError::InvalidName(problem).into());
+        }
+        let directory = self.settings.get_mic_profile_directory().await;
+        Ok(directory.join(format!("{name}.{extension}")))
+    }
 
-            GoXLRCommand::SetFader(fader, channel) => {
-                self.set_fader(fader, channel).await?;
-            }
-            GoXLRCommand::SetFaderMuteFunction(fader, behaviour) => {
-                if self.profile.get_mute_button_behaviour(fader) == behaviour {
-                    // Settings are the same..
-                    return Ok(());
-                }
+    pub async fn export_windows_profile(&mut self, name: &str) -> Result<()> {
+        self.settings.write_policy().check("export a profile")?;
 
-                // Unmute the channel to prevent weirdness, then set new behaviour
-                //self.unmute_fader(fader).await?;
-                self.profile.set_mute_button_behaviour(fader, behaviour);
+        // The name comes from a client, so it's refused rather than cleaned up, anything which
+        // could lead outside the profile directory (separators or '..') is a problem
+        if let Some(problem) = find_name_problem(name) {
+            return Err(CommandError::InvalidName(problem).into());
+        }
 
-                // We'll pass 'None' into this as there's a guaranteed change..
-                self.apply_mute_from_profile(fader, None)?;
+        let path = self
+            .settings
+            .get_profile_directory()
+            .await
+            .join(format!("{name}.goxlr"));
+        if path.exists() {
+            bail!("{} already exists", path.to_string_lossy());
+        }
+        self.profile.export_official(&path)?;
+        info!(
+            "Exported {} for the official app to {}",
+            self.profile.name(),
+            path.to_string_lossy()
+        );
+        Ok(())
+    }
 
-                let channel = self.profile.get_fader_assignment(fader);
-                if BasicInputDevice::can_from(channel) {
-                    let input = BasicInputDevice::from(channel);
-                    self.apply_routing(input).await?;
+    pub fn get_hardware_state(&mut self) -> Result<HardwareStateReport> {
+        Ok(HardwareStateReport {
+            on_connect: self.hardware_on_connect.clone(),
+            current: self.read_hardware_state()?,
+        })
+    }
 
-                    if input == BasicInputDevice::Chat && self.vc_mute_also_mute_cm {
-                        // Reapply the Mic routing in case we need to mute / unmute to Voice Chat
-                        self.apply_routing(BasicInputDevice::Microphone).await?;
+    pub fn resolve_control(&self, query: ControlQuery) -> ResolvedControl {
+        let is_mini = self.is_device_mini();
+        let available = match query {
+            ControlQuery::EffectButton(_) | ControlQuery::SamplePad(_, _) => !is_mini,
+            _ => true,
+        };
+
+        let (control, button) = match query {
+            ControlQuery::ChannelFader(channel) => {
+                match self.profile.get_fader_from_channel(channel) {
+                    Some(fader) => (
+                        Some(PhysicalControl::Fader(fader)),
+                        fader_mute_button(fader),
+                    ),
+                    None => {
+                        return ResolvedControl {
+                            control: None,
+                            available,
+                            volume: None,
+                            lit: false,
+                            muted: None,
+                        };
                     }
                 }
             }
+            ControlQuery::EffectButton(effect) => {
+                let button = effect_button(effect);
+                (
+                    Some(PhysicalControl::Button(usb_to_standard_button(button))),
+                    button,
+                )
+            }
+            ControlQuery::SamplePad(_, pad) => {
+                let button = sample_button_to_button(pad);
+                (
+                    Some(PhysicalControl::Button(usb_to_standard_button(button))),
+                    button,
+                )
+            }
+            ControlQuery::CoughButton => (
+                Some(PhysicalControl::Button(Button::Cough)),
+                Buttons::MicrophoneMute,
+            ),
+            ControlQuery::BleepButton => {
+                (Some(PhysicalControl::Button(Button::Bleep)), Buttons::Bleep)
+            }
+        };
 
-            GoXLRCommand::SetVolume(channel, volume) => {
-                debug!("Setting Mix volume for {} to {}", channel, volume);
-                self.goxlr.set_volume(channel, volume)?;
-                self.profile.set_channel_volume(channel, volume)?;
+        // The pads only show the active bank, so another bank's pad is never lit
+        let showing = match query {
+            ControlQuery::SamplePad(bank, _) => self.profile.get_active_sample_bank() == bank,
+            _ => true,
+        };
+        let state = self.create_button_states()[button as usize];
+        let lit =
+            available && showing && matches!(state, ButtonStates::Colour1 | ButtonStates::Flashing);
+
+        let (volume, muted) = match (query, control) {
+            (ControlQuery::ChannelFader(channel), Some(PhysicalControl::Fader(fader))) => (
+                Some(self.profile.get_channel_volume(channel)),
+                Some(self.profile.get_fader_mute_state(fader) == ChannelState::Muted),
+            ),
+            (ControlQuery::CoughButton, _) => (None, Some(self.mic_muted_by_cough())),
+            _ => (None, None),
+        };
 
-                // Update the Submix when volume changes via IPC
+        ResolvedControl {
+            control,
+            available,
+            volume,
+            lit,
+            muted,
+        }
+    }
+
+    fn read_hardware_state(&mut self) -> Result<HardwareReadback> {
+        let state = self.goxlr.get_button_states()?;
+        let mic_level = self.goxlr.get_microphone_level()?;
+
+        let mut fader_volumes = EnumMap::default();
+        for fader in FaderName::iter() {
+            fader_volumes[fader] = state.volumes[fader as usize];
+        }
+
+        Ok(HardwareReadback {
+            fader_volumes,
+            encoders: state.encoders,
+            buttons_pressed: state.pressed.iter().map(usb_to_standard_button).collect(),
+            mic_level,
+        })
+    }
+
+    // Fader assignments can't be read back, so this assumes the device still has the ones from
+    // the profile (which will be true unless something else has changed them).
+    fn log_hardware_differences(&self, readback: &HardwareReadback) {
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            let profile_volume = self.profile.get_channel_volume(channel);
+            let hardware_volume = readback.fader_volumes[fader];
+            if profile_volume != hardware_volume {
+                info!(
+                    "Fader {} ({}) is at {} on the device, the profile has {}",
+                    fader, channel, hardware_volume, profile_volume
+                );
+            }
+        }
+
+        if self.is_device_mini() {
+            return;
+        }
+
+        let encoders = [
+            ("Pitch", self.profile.get_pitch_knob_position()),
+            ("Gender", self.profile.get_gender_value()),
+            ("Reverb", self.profile.get_reverb_value()),
+            ("Echo", self.profile.get_echo_value()),
+        ];
+        for ((name, profile_value), hardware_value) in encoders.iter().zip(readback.encoders) {
+            if *profile_value != hardware_value {
+                info!(
+                    "{} is at {} on the device, the profile has {}",
+                    name, hardware_value, profile_value
+                );
+            }
+        }
+    }
+
+    fn adopt_hardware_volumes(&mut self, readback: &HardwareReadback) -> Result<()> {
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            let volume = readback.fader_volumes[fader];
+            if self.profile.get_channel_volume(channel) != volume {
+                debug!("Adopting {} volume of {} from the device", channel, volume);
+                self.profile.set_channel_volume(channel, volume)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn get_mic_level(&mut self) -> Result<f64> {
+        let level = self.goxlr.get_microphone_level()?;
+
+        let db = ((f64::log(level.into(), 10.) * 20.) - 72.2).clamp(-72.2, 0.);
+        Ok(db)
+    }
+
+    pub async fn perform_command(&mut self, command: GoXLRCommand) -> Result<()> {
+        let name = get_command_name(&command);
+        self.last_command = Some(name.clone());
+        #[cfg(feature = "telemetry")]
+        let features = required_features(&command);
+        let started = Instant::now();
+        let result = self.run_command(command).await;
+        self.check_consistency().await;
+
+        self.polling.activity(Instant::now());
+        self.update_poll_interval(Instant::now());
+
+        let elapsed = started.elapsed();
+        self.command_timings.record(elapsed);
+        debug!("Command {} took {:?}", name, elapsed);
+        if elapsed > SLOW_OPERATION_THRESHOLD {
+            warn!(
+                "Command {} took {:?}, inputs were not handled meanwhile",
+                name, elapsed
+            );
+        }
+
+        // Refused and failed commands are left out, they're no sign of the device being used
+        if result.is_ok() {
+            self.session.commands_processed += 1;
+            self.session.last_command_at = Some(Local::now().timestamp());
+            if let Some(privacy) = &mut self.privacy {
+                privacy.last_activity = Instant::now();
+            }
+
+            #[cfg(feature = "telemetry")]
+            {
+                let device_type = self.hardware.device_type.clone();
+                telemetry::record_command(self.settings, device_type, features).await;
+            }
+        }
+        result
+    }
+
+    async fn run_command(&mut self, command: GoXLRCommand) -> Result<()> {
+        if let Some(feature) = self.missing_feature(&command) {
+            return Err(CommandError::NotSupported(feature).into());
+        }
+
+        if let Some(action) = get_file_write_action(&command) {
+            self.settings.write_policy().check(action)?;
+        }
+
+        if self.privacy_locked() && self.unmutes_mic(&command) {
+            return Err(CommandError::PrivacyLocked.into());
+        }
+
+        match command {
+            GoXLRCommand::SetShutdownCommands(commands) => {
+                self.settings
+                    .set_device_shutdown_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSleepCommands(commands) => {
+                self.settings
+                    .set_device_sleep_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetWakeCommands(commands) => {
+                self.settings
+                    .set_device_wake_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSamplerPreBufferDuration(duration) => {
+                if duration > 30000 {
+                    bail!("Buffer must be below 30seconds");
+                }
+
+                self.settings
+                    .set_device_sampler_pre_buffer(self.serial(), duration)
+                    .await;
+                self.settings.save().await;
+                self.rebuild_audio_handler().await?;
+            }
+
+            GoXLRCommand::SetFader(fader, channel) => {
+                self.mute_function_reset = None;
+                self.set_fader(fader, channel).await?;
+            }
+            GoXLRCommand::SetFaderMuteFunction(fader, behaviour) => {
+                if self.profile.get_mute_button_behaviour(fader) == behaviour {
+                    // Settings are the same..
+                    return Ok(());
+                }
+
+                let channel = self.profile.get_fader_assignment(fader);
+                if !behaviour.is_valid_for_channel(channel) {
+                    let valid: Vec<String> = BasicMuteFunction::valid_for_channel(channel)
+                        .iter()
+                        .map(|function| function.to_string())
+                        .collect();
+                    bail!(
+                        "Mute Function {} is not valid for channel {}, valid options: {}",
+                        behaviour,
+                        channel,
+                        valid.join(", ")
+                    );
+                }
+
+                // Unmute the channel to prevent weirdness, then set new behaviour
+                //self.unmute_fader(fader).await?;
+                self.profile.set_mute_button_behaviour(fader, behaviour);
+
+                // We'll pass 'None' into this as there's a guaranteed change..
+                self.apply_mute_from_profile(fader, None)?;
+
+                let channel = self.profile.get_fader_assignment(fader);
+                if BasicInputDevice::can_from(channel) {
+                    let input = BasicInputDevice::from(channel);
+                    self.apply_routing(input).await?;
+
+                    if input == BasicInputDevice::Chat && !self.vc_mute_outputs.is_empty() {
+                        // Reapply the Mic routing in case we need to mute / unmute to Voice Chat
+                        self.apply_routing(BasicInputDevice::Microphone).await?;
+                    }
+                }
+            }
+
+            GoXLRCommand::SetVolume(channel, volume) => {
+                debug!("Setting Mix volume for {} to {}", channel, volume);
+                self.goxlr.set_volume(channel, volume)?;
+                self.profile.set_channel_volume(channel, volume)?;
+
+                // Update the Submix when volume changes via IPC
                 self.update_submix_for(channel, volume)?;
 
                 if let Some(fader) = self.profile.get_fader_from_channel(channel) {
-                    self.fader_pause_until[fader].paused = true;
-                    self.fader_pause_until[fader].until = volume;
+                    self.fader_volume_set(fader, volume);
                 }
             }
+            GoXLRCommand::SaveVolumeScene(name) => {
+                self.save_volume_scene(name).await?;
+            }
+            GoXLRCommand::LoadVolumeScene(name) => {
+                self.load_volume_scene(&name).await?;
+            }
+            GoXLRCommand::DeleteVolumeScene(name) => {
+                self.profile.delete_volume_scene(&name)?;
+            }
 
             GoXLRCommand::SetCoughMuteFunction(mute_function) => {
                 if self.profile.get_chat_mute_button_behaviour() == mute_function {
@@ -1828,21 +4505,62 @@ impl<'a> Device<'a> {
                 self.apply_mic_params(HashSet::from([MicrophoneParamKey::BleepLevel]))?;
             }
             GoXLRCommand::SetMicrophoneType(mic_type) => {
+                self.check_phantom_confirmation(mic_type, false).await?;
+                let previous = self.mic_profile.mic_type();
+                self.mic_profile.set_mic_type(mic_type)?;
+                self.apply_mic_type_change(previous).await?;
+            }
+            GoXLRCommand::ConfirmMicrophoneType(mic_type) => {
+                let previous = self.mic_profile.mic_type();
                 self.mic_profile.set_mic_type(mic_type)?;
-                self.apply_mic_gain()?;
+                self.apply_mic_type_change(previous).await?;
             }
             GoXLRCommand::SetMicrophoneGain(mic_type, gain) => {
+                // This changes the Mic type too, so needs the same confirmation as above, which
+                // the client can give by sending ConfirmMicrophoneType first.
+                self.check_phantom_confirmation(mic_type, false).await?;
+                let previous = self.mic_profile.mic_type();
                 self.mic_profile.set_mic_type(mic_type)?;
                 self.mic_profile.set_mic_gain(mic_type, gain)?;
-                self.apply_mic_gain()?;
+                self.apply_mic_type_change(previous).await?;
             }
             GoXLRCommand::SetRouter(input, output, enabled) => {
                 debug!("Setting Routing: {:?} {:?} {}", input, output, enabled);
-                self.profile.set_routing(input, output, enabled)?;
+                if output == BasicOutputDevice::Sampler && self.is_steam_no_music().await {
+                    // The profile's VOD column isn't used in this mode, Samples can still be
+                    // kept off the recording though, which is a device setting.
+                    if input != BasicInputDevice::Samples {
+                        bail!("The VOD mix follows the Broadcast Mix in Stream No Music mode");
+                    }
+                    self.settings
+                        .set_device_vod_samples(self.serial(), enabled)
+                        .await;
+                    self.settings.save().await;
+                } else {
+                    self.profile.set_routing(input, output, enabled)?;
+                }
 
                 // Apply the change..
                 self.apply_routing(input).await?;
             }
+            GoXLRCommand::SetChannelBalance(channel, balance) => {
+                let Some(input) = BasicInputDevice::iter().find(|i| input_channel(*i) == channel)
+                else {
+                    bail!("{} isn't an input, so can't be balanced", channel);
+                };
+                self.profile.set_input_balance(input, balance)?;
+                self.apply_routing(input).await?;
+            }
+            GoXLRCommand::SetInputLowCut(input, frequency) => {
+                // See DeviceCapabilities::input_low_cut, this can only be applied to recordings.
+                self.profile.set_input_low_cut(input, frequency)?;
+                if let Some(frequency) = frequency {
+                    info!(
+                        "Low Cut of {}Hz set on {:?}, this only applies to Sampler recordings",
+                        frequency, input
+                    );
+                }
+            }
 
             GoXLRCommand::SetElementDisplayMode(element, display) => match element {
                 DisplayModeComponents::NoiseGate => {
@@ -1877,6 +4595,11 @@ impl<'a> Device<'a> {
                 let param = self.mic_profile.set_eq_freq(freq, value)?;
                 self.apply_effects(LinkedHashSet::from_iter([param]))?;
             }
+            GoXLRCommand::SetHeadphoneEqGain(frequency, gain) => {
+                // Only reached on devices with DeviceCapabilities::headphone_eq, no firmware has
+                // the filter keys yet so there's nothing to send, but the gains are kept.
+                self.profile.set_headphone_eq_gain(frequency, gain)?;
+            }
             GoXLRCommand::SetGateThreshold(value) => {
                 self.mic_profile.set_gate_threshold(value)?;
                 self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateThreshold]))?;
@@ -1938,46 +4661,35 @@ impl<'a> Device<'a> {
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::DeEsser]))?;
             }
 
+            adjustment @ (GoXLRCommand::AdjustGateThreshold(_)
+            | GoXLRCommand::AdjustCompressorThreshold(_)
+            | GoXLRCommand::AdjustCompressorMakeupGain(_)
+            | GoXLRCommand::AdjustDeEsser(_)
+            | GoXLRCommand::AdjustBleepVolume(_)) => {
+                let command = self.resolve_adjustment(adjustment);
+                Box::pin(self.run_command(command)).await?;
+            }
+
             // Colouring..
             GoXLRCommand::SetAnimationMode(mode) => {
-                if !self.device_supports_animations() {
-                    bail!("Animations not supported on this firmware.");
-                }
-
-                if mode == goxlr_types::AnimationMode::Ripple && self.is_device_mini() {
-                    bail!("Ripple Mode not supported on the GoXLR Mini");
-                }
-
                 self.profile.set_animation_mode(mode)?;
                 self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationMod1(value) => {
-                if !self.device_supports_animations() {
-                    bail!("Animations not supported on this firmware.");
-                }
-
                 self.profile.set_animation_mod1(value)?;
                 self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationMod2(value) => {
-                if !self.device_supports_animations() {
-                    bail!("Animations not supported on this firmware.");
-                }
-
                 self.profile.set_animation_mod2(value)?;
                 self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationWaterfall(direction) => {
-                if !self.device_supports_animations() {
-                    bail!("Animations not supported on this firmware.");
-                }
-
                 self.profile.set_animation_waterfall(direction)?;
                 self.load_animation(false).await?;
             }
 
             GoXLRCommand::SetGlobalColour(colour) => {
-                self.profile.set_global_colour(colour)?;
+                self.profile.set_global_colour(parse_colour(&colour)?)?;
                 self.load_colour_map().await?;
                 self.update_button_states()?;
                 self.set_all_fader_display_from_profile()?;
@@ -1988,6 +4700,7 @@ impl<'a> Device<'a> {
             }
             GoXLRCommand::SetFaderColours(fader, top, bottom) => {
                 // Need to get the fader colour map, and set values..
+                let (top, bottom) = (parse_colour(&top)?, parse_colour(&bottom)?);
                 self.profile.set_fader_colours(fader, top, bottom)?;
                 self.load_colour_map().await?;
             }
@@ -1995,6 +4708,7 @@ impl<'a> Device<'a> {
                 // I considered this as part of SetFaderColours, but spamming a new colour map
                 // for every fader change seemed excessive, this allows us to set them all before
                 // reloading.
+                let (top, bottom) = (parse_colour(&top)?, parse_colour(&bottom)?);
                 for fader in FaderName::iter() {
                     self.profile
                         .set_fader_colours(fader, top.to_owned(), bottom.to_owned())?;
@@ -2008,6 +4722,8 @@ impl<'a> Device<'a> {
                 }
             }
             GoXLRCommand::SetButtonColours(target, colour, colour2) => {
+                let colour = parse_colour(&colour)?;
+                let colour2 = colour2.map(|colour| parse_colour(&colour)).transpose()?;
                 self.profile
                     .set_button_colours(target, colour, colour2.as_ref())?;
 
@@ -2022,6 +4738,8 @@ impl<'a> Device<'a> {
                 self.update_button_states()?;
             }
             GoXLRCommand::SetButtonGroupColours(target, colour, colour_2) => {
+                let colour = parse_colour(&colour)?;
+                let colour_2 = colour_2.map(|colour| parse_colour(&colour)).transpose()?;
                 self.profile
                     .set_group_button_colours(target, colour, colour_2)?;
 
@@ -2034,16 +4752,23 @@ impl<'a> Device<'a> {
                 self.update_button_states()?;
             }
             GoXLRCommand::SetSimpleColour(target, colour) => {
-                self.profile.set_simple_colours(target, colour)?;
+                self.profile
+                    .set_simple_colours(target, parse_colour(&colour)?)?;
                 self.load_colour_map().await?;
                 self.update_button_states()?;
             }
             GoXLRCommand::SetEncoderColour(target, colour, colour_2, colour_3) => {
+                let colour = parse_colour(&colour)?;
+                let colour_2 = parse_colour(&colour_2)?;
+                let colour_3 = parse_colour(&colour_3)?;
                 self.profile
                     .set_encoder_colours(target, colour, colour_2, colour_3)?;
                 self.load_colour_map().await?;
             }
             GoXLRCommand::SetSampleColour(target, colour, colour_2, colour_3) => {
+                let colour = parse_colour(&colour)?;
+                let colour_2 = parse_colour(&colour_2)?;
+                let colour_3 = parse_colour(&colour_3)?;
                 self.profile
                     .set_sampler_colours(target, colour, colour_2, colour_3)?;
                 self.profile.sync_sample_if_active(target)?;
@@ -2054,6 +4779,12 @@ impl<'a> Device<'a> {
                 self.load_colour_map().await?;
                 self.update_button_states()?;
             }
+            GoXLRCommand::SetReactiveLighting(config) => {
+                self.set_reactive_lighting(config).await?;
+            }
+            GoXLRCommand::SetHighContrastMode(config) => {
+                self.set_high_contrast(config).await?;
+            }
 
             // Effects
             GoXLRCommand::LoadEffectPreset(name) => {
@@ -2070,6 +4801,7 @@ impl<'a> Device<'a> {
             }
 
             GoXLRCommand::RenameActivePreset(name) => {
+                let name = normalise_name(&name)?;
                 let current_bank = self
                     .profile
                     .profile()
@@ -2098,6 +4830,77 @@ impl<'a> Device<'a> {
                 self.profile.write_preset(name, &preset_directory)?;
             }
 
+            GoXLRCommand::LoadEffectSection(section, name) => {
+                let preset_directory = self.settings.get_presets_directory().await;
+                self.profile
+                    .load_effect_section(section, name, &preset_directory)?;
+
+                // Only the effect keys (and encoder) for this section need sending..
+                match section {
+                    EffectSection::Reverb => {
+                        self.profile.rebase_effects_intensity(EncoderName::Reverb);
+                        let value = self.profile.get_reverb_value();
+                        self.goxlr.set_encoder_value(EncoderName::Reverb, value)?;
+                        self.apply_effects(self.mic_profile.get_reverb_keyset())?;
+                    }
+                    EffectSection::Echo => {
+                        self.profile.rebase_effects_intensity(EncoderName::Echo);
+                        let value = self.profile.get_echo_value();
+                        self.goxlr.set_encoder_value(EncoderName::Echo, value)?;
+                        self.apply_effects(
+                            self.mic_profile
+                                .get_echo_keyset(self.profile.use_echo_tempo()),
+                        )?;
+                    }
+                    EffectSection::Pitch => {
+                        self.set_pitch_mode()?;
+                        self.profile.rebase_effects_intensity(EncoderName::Pitch);
+                        let value = self.profile.get_pitch_encoder_position();
+                        self.goxlr.set_encoder_value(EncoderName::Pitch, value)?;
+                        self.apply_effects(self.mic_profile.get_pitch_keyset())?;
+                    }
+                    EffectSection::Gender => {
+                        let value = self.profile.get_gender_value();
+                        self.goxlr.set_encoder_value(EncoderName::Gender, value)?;
+                        self.apply_effects(self.mic_profile.get_gender_keyset())?;
+                    }
+                    EffectSection::Megaphone => {
+                        self.apply_effects(self.mic_profile.get_megaphone_keyset())?;
+                    }
+                    EffectSection::Robot => {
+                        self.apply_effects(self.mic_profile.get_robot_keyset())?;
+                    }
+                    EffectSection::HardTune => {
+                        self.apply_effects(self.mic_profile.get_hardtune_keyset())?;
+                    }
+                }
+            }
+
+            GoXLRCommand::SaveEffectSection(section, name) => {
+                let preset_directory = self.settings.get_presets_directory().await;
+                self.profile
+                    .write_effect_section(section, name, &preset_directory)?;
+            }
+            GoXLRCommand::SetEffectsIntensity(intensity) => {
+                self.profile.set_effects_intensity(intensity)?;
+
+                let reverb = self.profile.get_reverb_value();
+                let echo = self.profile.get_echo_value();
+                let pitch = self.profile.get_pitch_encoder_position();
+                self.goxlr.set_encoder_value(EncoderName::Reverb, reverb)?;
+                self.goxlr.set_encoder_value(EncoderName::Echo, echo)?;
+                self.goxlr.set_encoder_value(EncoderName::Pitch, pitch)?;
+
+                self.apply_effects(LinkedHashSet::from_iter([
+                    EffectKey::ReverbAmount,
+                    EffectKey::EchoAmount,
+                    EffectKey::PitchAmount,
+                ]))?;
+
+                let message = format!("Effects Intensity {} percent", intensity);
+                self.announce(NotificationCategory::Effects, message).await;
+            }
+
             // Reverb
             GoXLRCommand::SetReverbStyle(style) => {
                 self.profile.set_reverb_style(style)?;
@@ -2107,6 +4910,9 @@ impl<'a> Device<'a> {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_percentage_amount(amount)?;
+                self.profile.share_encoder_value(EncoderName::Reverb)?;
+
+                self.profile.rebase_effects_intensity(EncoderName::Reverb);
 
                 let encoder_value = self.profile.get_reverb_value();
                 self.goxlr
@@ -2186,6 +4992,8 @@ impl<'a> Device<'a> {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_percentage_value(value)?;
+                self.profile.share_encoder_value(EncoderName::Echo)?;
+                self.profile.rebase_effects_intensity(EncoderName::Echo);
 
                 let encoder_value = self.profile.get_echo_value();
                 self.goxlr
@@ -2260,6 +5068,8 @@ impl<'a> Device<'a> {
                 self.profile
                     .get_active_pitch_profile_mut()
                     .set_knob_position(value, hard_tune_enabled)?;
+                self.profile.share_encoder_value(EncoderName::Pitch)?;
+                self.profile.rebase_effects_intensity(EncoderName::Pitch);
 
                 let value = self.profile.get_pitch_encoder_position();
                 self.goxlr.set_encoder_value(EncoderName::Pitch, value)?;
@@ -2282,10 +5092,15 @@ impl<'a> Device<'a> {
                 self.profile
                     .get_active_gender_profile_mut()
                     .set_amount(value)?;
+                self.profile.share_encoder_value(EncoderName::Gender)?;
                 let value = self.profile.get_gender_value();
                 self.goxlr.set_encoder_value(EncoderName::Gender, value)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::GenderAmount]))?;
             }
+            GoXLRCommand::SetEncoderGlobalMode(encoder, global) => {
+                // The active preset's value is kept either way, so the device doesn't change
+                self.profile.set_encoder_global(encoder, global)?;
+            }
 
             GoXLRCommand::SetMegaphoneStyle(value) => {
                 self.profile.set_megaphone_style(value)?;
@@ -2447,11 +5262,47 @@ impl<'a> Device<'a> {
             GoXLRCommand::SetSamplerOrder(bank, button, order) => {
                 self.profile.set_sampler_play_order(bank, button, order);
             }
+            GoXLRCommand::SetSampleButtonGuard(bank, button, guarded) => {
+                self.profile
+                    .set_sample_button_guarded(bank, button, guarded);
+            }
+            GoXLRCommand::SetSampleDucking(bank, button, ducking) => {
+                if let Some(config) = &ducking {
+                    if config.channels.is_empty() {
+                        bail!("At least one channel must be ducked");
+                    }
+                    if config.attenuation > 100 {
+                        bail!("Ducking attenuation must be between 0 and 100");
+                    }
+                    for channel in &config.channels {
+                        // The Mic Monitor is held at 100% while submixes are in use
+                        if matches!(channel, ChannelName::Sample | ChannelName::MicMonitor) {
+                            bail!("The {} channel can't be ducked", channel);
+                        }
+                    }
+                }
+                self.profile
+                    .set_sample_button_ducking(bank, button, ducking);
+            }
             GoXLRCommand::AddSample(bank, button, filename) => {
                 let path = self
                     .get_path_for_sample(PathBuf::from(filename.clone()))
                     .await?;
 
+                let convert_to = match self
+                    .settings
+                    .get_device_convert_samples(self.serial())
+                    .await
+                {
+                    true => Some(
+                        self.settings
+                            .get_samples_directory()
+                            .await
+                            .join(CONVERTED_SAMPLES_DIRECTORY),
+                    ),
+                    false => None,
+                };
+
                 // If we have an audio handler, try to calcuate the Gain..
                 if let Some(audio_handler) = &mut self.audio_handler {
                     if audio_handler.is_calculating() {
@@ -2460,20 +5311,46 @@ impl<'a> Device<'a> {
 
                     // V2 Here, this technically still blocks in it's current state, however, it
                     // doesn't have to anymore.
-                    audio_handler.calculate_gain_thread(path, bank, button)?;
+                    audio_handler.calculate_gain_thread(
+                        path.clone(),
+                        bank,
+                        button,
+                        DEFAULT_LOUDNESS_TARGET,
+                        convert_to,
+                    )?;
+                    self.sample_analysis = Some(SampleAnalysis {
+                        bank,
+                        button,
+                        file: path,
+                    });
                 }
 
                 // Update the lighting..
                 self.load_colour_map().await?;
             }
             GoXLRCommand::SetSampleStartPercent(bank, button, index, percent) => {
+                // The length is only used to check the sample isn't made too short
+                if let Err(e) = self.load_sample_duration(bank, button, index).await {
+                    debug!("{}", e);
+                }
                 self.profile
                     .set_sample_start_pct(bank, button, index, percent)?;
             }
             GoXLRCommand::SetSampleStopPercent(bank, button, index, percent) => {
+                if let Err(e) = self.load_sample_duration(bank, button, index).await {
+                    debug!("{}", e);
+                }
                 self.profile
                     .set_sample_stop_pct(bank, button, index, percent)?;
             }
+            GoXLRCommand::SetSampleStartMs(bank, button, index, ms) => {
+                self.load_sample_duration(bank, button, index).await?;
+                self.profile.set_sample_start_ms(bank, button, index, ms)?;
+            }
+            GoXLRCommand::SetSampleStopMs(bank, button, index, ms) => {
+                self.load_sample_duration(bank, button, index).await?;
+                self.profile.set_sample_stop_ms(bank, button, index, ms)?;
+            }
             GoXLRCommand::RemoveSampleByIndex(bank, button, index) => {
                 let remaining = self
                     .profile
@@ -2502,11 +5379,77 @@ impl<'a> Device<'a> {
                 self.stop_sample_playback(bank, button).await?;
                 self.update_button_states()?;
             }
+            GoXLRCommand::NormaliseBank(bank, target) => {
+                match &self.audio_handler {
+                    Some(audio_handler) => {
+                        if audio_handler.is_calculating() {
+                            bail!("Gain Calculation already in progress..");
+                        }
+                    }
+                    None => bail!("Sampler is not available"),
+                }
+
+                if !(-70. ..=0.).contains(&target) {
+                    bail!("Target loudness should be between -70 and 0 LUFS");
+                }
+
+                let pending: VecDeque<(SampleButtons, String)> = self
+                    .profile
+                    .get_sample_bank_tracks(bank)
+                    .into_iter()
+                    .map(|(button, file, _)| (button, file))
+                    .collect();
+
+                if pending.is_empty() {
+                    bail!("There are no samples in Bank {:?}", bank);
+                }
+
+                self.normalise_task = Some(NormaliseTask {
+                    status: BankNormalisation {
+                        bank,
+                        target,
+                        total: pending.len(),
+                        current: None,
+                        results: vec![],
+                        finished: false,
+                        cancelled: false,
+                    },
+                    pending,
+                });
+                self.normalise_next_sample().await;
+            }
+            GoXLRCommand::CancelNormaliseBank() => {
+                let task = match &mut self.normalise_task {
+                    Some(task) if !task.status.finished => task,
+                    _ => bail!("No Bank Normalisation in progress"),
+                };
+
+                // Anything already normalised keeps its new gain
+                task.pending.clear();
+                task.status.current = None;
+                task.status.finished = true;
+                task.status.cancelled = true;
+
+                if let Some(audio_handler) = &mut self.audio_handler {
+                    audio_handler.cancel_calculation();
+                }
+            }
 
             GoXLRCommand::SetScribbleIcon(fader, icon) => {
                 self.profile.set_scribble_icon(fader, icon);
                 self.apply_scribble(fader).await?;
             }
+            GoXLRCommand::SetScribbleGlyph(fader, glyph) => {
+                let glyph = glyph.trim();
+                if glyph.is_empty() {
+                    self.profile.set_scribble_glyph(fader, None);
+                } else {
+                    check_glyph(glyph)?;
+                    self.profile
+                        .set_scribble_glyph(fader, Some(glyph.to_string()));
+                }
+                self.apply_scribble(fader).await?;
+            }
             GoXLRCommand::SetScribbleText(fader, text) => {
                 self.profile.set_scribble_text(fader, text);
                 self.apply_scribble(fader).await?;
@@ -2515,6 +5458,10 @@ impl<'a> Device<'a> {
                 self.profile.set_scribble_number(fader, number);
                 self.apply_scribble(fader).await?;
             }
+            GoXLRCommand::SetScribbleNumberMode(fader, mode) => {
+                self.profile.set_scribble_number_mode(fader, mode);
+                self.apply_scribble(fader).await?;
+            }
             GoXLRCommand::SetScribbleInvert(fader, inverted) => {
                 self.profile.set_scribble_inverted(fader, inverted);
                 self.apply_scribble(fader).await?;
@@ -2522,9 +5469,11 @@ impl<'a> Device<'a> {
 
             // Profiles
             GoXLRCommand::NewProfile(profile_name) => {
+                let profile_name = normalise_name(&profile_name)?;
                 self.stop_all_samples(true, true).await?;
+                self.cancel_sample_analysis(|_| true);
                 let profile_directory = self.settings.get_profile_directory().await;
-                let volumes = self.profile.get_current_state();
+                let volumes = self.get_current_state().await?;
 
                 // Do a new file verification check..
                 ProfileAdapter::can_create_new_file(profile_name.clone(), &profile_directory)?;
@@ -2543,9 +5492,16 @@ impl<'a> Device<'a> {
                     .await;
                 self.settings.save().await;
             }
-            GoXLRCommand::LoadProfile(profile_name, save_change) => {
+            GoXLRCommand::LoadProfile(profile_name, save_change, force) => {
+                // Let the client decide whether unsaved changes should be thrown away.
+                if !force && self.profile.is_dirty() && profile_name != self.profile.name() {
+                    return Err(CommandError::UnsavedChanges.into());
+                }
+
+                // In read-only mode the profile is still loaded, it just won't stick.
+                let save_change = save_change && !self.settings.write_policy().is_read_only();
                 self.stop_all_samples(true, true).await?;
-                let volumes = self.profile.get_current_state();
+                let volumes = self.get_current_state().await?;
 
                 // Grab the needed Paths..
                 let profile_path = self.settings.get_profile_directory().await;
@@ -2564,6 +5520,7 @@ impl<'a> Device<'a> {
                             });
                             debug!("Backup Complete");
                         }
+                        self.cancel_sample_analysis(|_| true);
                         self.profile = profile;
                     }
                     Err(e) => {
@@ -2576,6 +5533,7 @@ impl<'a> Device<'a> {
                         match ProfileAdapter::from_named(profile_name, &backup_path) {
                             Ok(profile) => {
                                 info!("Backup Profile Loaded");
+                                self.cancel_sample_analysis(|_| true);
                                 self.profile = profile;
 
                                 debug!("Overwriting existing corrupt profile..");
@@ -2589,6 +5547,9 @@ impl<'a> Device<'a> {
                 };
 
                 self.apply_profile(Some(volumes)).await?;
+
+                // Carrying the volumes over shouldn't count as a change to the new profile.
+                self.profile.clear_dirty();
                 if save_change {
                     self.settings
                         .set_device_profile_name(self.serial(), self.profile.name())
@@ -2613,9 +5574,14 @@ impl<'a> Device<'a> {
             GoXLRCommand::SaveProfile() => {
                 let profile_directory = self.settings.get_profile_directory().await;
                 self.profile.save(&profile_directory, true)?;
+
+                // The previewed bank has now been saved, so releasing the button shouldn't revert it
+                self.effect_preview = None;
             }
             GoXLRCommand::SaveProfileAs(profile_name) => {
+                let profile_name = normalise_name(&profile_name)?;
                 let path = self.settings.get_profile_directory().await;
+                self.effect_preview = None;
 
                 // Do a new file verification check..
                 ProfileAdapter::can_create_new_file(profile_name.clone(), &path)?;
@@ -2638,11 +5604,42 @@ impl<'a> Device<'a> {
                 self.profile.delete_profile(name.clone(), &profiles)?;
                 self.profile.delete_profile(name.clone(), &backups)?;
             }
+            GoXLRCommand::RenameProfile(name, new_name) => {
+                let new_name = normalise_name(&new_name)?;
+                let profiles = self.settings.get_profile_directory().await;
+                let backups = self.settings.get_backup_directory().await;
+
+                if !ProfileAdapter::is_listed(&name, &profiles) {
+                    bail!("Profile {} does not exist", name);
+                }
+                ProfileAdapter::rename_profile(&name, &new_name, &profiles)?;
+
+                // A stale backup shouldn't stop the rename, it'll be rewritten on next load
+                ProfileAdapter::rename_profile(&name, &new_name, &backups).unwrap_or_else(|e| {
+                    warn!("Unable to rename the Profile Backup: {}", e);
+                });
+
+                if self.profile.name() == name {
+                    self.profile.set_name(new_name.clone());
+                    self.settings
+                        .set_device_profile_name(self.serial(), &new_name)
+                        .await;
+                    self.settings.save().await;
+                }
+            }
+            GoXLRCommand::RebaseProfile(base_name) => {
+                let profile_directory = self.settings.get_profile_directory().await;
+                self.profile.rebase(base_name, &profile_directory)?;
+            }
+            GoXLRCommand::ClearProfileBase() => {
+                self.profile.clear_base()?;
+            }
             GoXLRCommand::ReloadSettings() => {
                 // This is a simple command that will reload the current profile settings
                 self.apply_profile(None).await?;
             }
             GoXLRCommand::NewMicProfile(mic_profile_name) => {
+                let mic_profile_name = normalise_name(&mic_profile_name)?;
                 let mic_profile_directory = self.settings.get_mic_profile_directory().await;
 
                 // Verify we can create this file..
@@ -2667,6 +5664,8 @@ impl<'a> Device<'a> {
                 self.settings.save().await;
             }
             GoXLRCommand::LoadMicProfile(name, persist) => {
+                let persist = persist && !self.settings.write_policy().is_read_only();
+
                 // Grab the needed Paths..
                 let path = self.settings.get_mic_profile_directory().await;
                 let backup = self.settings.get_backup_directory().await;
@@ -2716,11 +5715,33 @@ impl<'a> Device<'a> {
                     self.settings.save().await;
                 }
             }
+            GoXLRCommand::LoadMicProfileSection(name, section) => {
+                let path = self.settings.get_mic_profile_directory().await;
+                let source = MicProfileAdapter::from_named(name, &path)?;
+
+                let previous = self.mic_profile.mic_type();
+                if section == MicProfileSection::Microphone {
+                    self.check_phantom_confirmation(source.mic_type(), false)
+                        .await?;
+                }
+                self.mic_profile.copy_section(section, &source)?;
+
+                if section == MicProfileSection::Microphone {
+                    self.apply_mic_type_change(previous).await?;
+                } else {
+                    let params = self.mic_profile.get_section_params(section);
+                    if !params.is_empty() {
+                        self.apply_mic_params(params)?;
+                    }
+                    self.apply_effects(self.mic_profile.get_section_effects(section))?;
+                }
+            }
             GoXLRCommand::SaveMicProfile() => {
                 let mic_profile_directory = self.settings.get_mic_profile_directory().await;
                 self.mic_profile.save(&mic_profile_directory, true)?;
             }
             GoXLRCommand::SaveMicProfileAs(name) => {
+                let name = normalise_name(&name)?;
                 let path = self.settings.get_mic_profile_directory().await;
                 MicProfileAdapter::can_create_new_file(name.clone(), &path)?;
 
@@ -2752,14 +5773,17 @@ impl<'a> Device<'a> {
             }
 
             GoXLRCommand::SetVCMuteAlsoMuteCM(value) => {
-                self.vc_mute_also_mute_cm = value;
-                self.settings
-                    .set_device_vc_mute_also_mute_cm(self.serial(), value)
-                    .await;
-                self.settings.save().await;
+                let chat_mic = BasicOutputDevice::ChatMic;
+                let mut outputs = self.vc_mute_outputs.clone();
+                outputs.retain(|output| *output != chat_mic);
+                if value {
+                    outputs.push(chat_mic);
+                }
+                self.set_vc_mute_outputs(outputs).await?;
+            }
 
-                // Re-run the Microphone Routing to update if needed..
-                self.apply_routing(BasicInputDevice::Microphone).await?;
+            GoXLRCommand::SetVCMuteAdditionalOutputs(outputs) => {
+                self.set_vc_mute_outputs(outputs).await?;
             }
 
             GoXLRCommand::SetMonitorWithFx(value) => {
@@ -2781,36 +5805,112 @@ impl<'a> Device<'a> {
                 let current = self.settings.get_device_lock_faders(self.serial()).await;
 
                 if current != value {
+                    let was_locked = self.faders_locked().await;
                     self.settings
                         .set_device_lock_faders(self.serial(), value)
                         .await;
 
                     self.settings.save().await;
-
-                    if value {
-                        self.lock_faders()?;
-                    } else {
-                        self.unlock_faders()?;
-                    }
-                    self.load_colour_map().await?;
+                    self.reconcile_fader_lock(was_locked).await?;
                 }
             }
+            GoXLRCommand::SetSilentFaders(value) => {
+                let current = self.settings.get_device_silent_faders(self.serial()).await;
 
-            GoXLRCommand::SetVodMode(value) => {
-                let serial = self.serial();
-
-                // Get the current mode..
-                let current = self.settings.get_device_vod_mode(serial).await;
                 if current != value {
-                    self.settings.set_device_vod_mode(serial, value).await;
+                    let was_locked = self.faders_locked().await;
+                    self.settings
+                        .set_device_silent_faders(self.serial(), value)
+                        .await;
 
-                    // We need to reapply all routing to reconfigure as needed
-                    for input in BasicInputDevice::iter() {
-                        self.apply_routing(input).await?;
-                    }
+                    self.settings.save().await;
+                    self.reconcile_fader_lock(was_locked).await?;
                 }
             }
 
+            GoXLRCommand::SetSampleClearTimeout(value) => {
+                self.settings
+                    .set_device_sample_clear_timeout(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetPhantomConfirmationRequired(value) => {
+                self.settings
+                    .set_device_phantom_confirmation_required(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetPrivacyMode(config) => {
+                self.set_privacy_mode(config).await?;
+            }
+            GoXLRCommand::SetHeadsetMode(enabled) => {
+                self.set_headset_mode(enabled).await?;
+            }
+            GoXLRCommand::SetHeadsetSidetone(sidetone) => {
+                self.set_headset_sidetone(sidetone).await?;
+            }
+            GoXLRCommand::SetFineFaderAdjust(value) => {
+                self.settings
+                    .set_device_fine_fader_adjust(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetPollInterval(interval) => {
+                check_poll_interval(interval)?;
+                self.settings
+                    .set_device_poll_interval(self.serial(), interval)
+                    .await;
+                self.settings.save().await;
+                self.polling.set_configured(interval, Instant::now());
+            }
+            GoXLRCommand::SetStallTimeout(timeout) => {
+                check_stall_timeout(timeout)?;
+                self.settings
+                    .set_device_stall_timeout(self.serial(), timeout)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSampleConversion(value) => {
+                self.settings
+                    .set_device_convert_samples(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetAdoptHardwareState(value) => {
+                self.settings
+                    .set_device_adopt_hardware_state(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetEffectPreviewOnHold(value) => {
+                self.settings
+                    .set_device_effect_preview_on_hold(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetClearEffectsOnHold(value) => {
+                self.settings
+                    .set_device_clear_effects_on_hold(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSwitchMonitorOnUnplug(value) => {
+                self.settings
+                    .set_device_switch_monitor_on_unplug(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSaveOnShutdown(value) => {
+                self.settings
+                    .set_device_save_on_shutdown(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+            }
+
+            GoXLRCommand::SetVodMode(value) => {
+                self.apply_mode_change(ModeChange::Vod(value)).await?;
+            }
+
             GoXLRCommand::SetActiveEffectPreset(preset) => {
                 self.load_effect_bank(preset).await?;
                 self.update_button_states()?;
@@ -2840,6 +5940,8 @@ impl<'a> Device<'a> {
                 MuteState::MutedToX => self.mute_fader_to_x(fader).await?,
                 MuteState::MutedToAll => self.mute_fader_to_all(fader, true).await?,
             },
+            GoXLRCommand::PrivacyLock() => self.set_privacy_locked(true).await?,
+            GoXLRCommand::PrivacyUnlock() => self.set_privacy_locked(false).await?,
             GoXLRCommand::SetCoughMuteState(state) => {
                 // This is more complicated because the 'state' of the mute can come from
                 // various different locations, so what we're going to do is simply update
@@ -2867,19 +5969,7 @@ impl<'a> Device<'a> {
                 self.update_button_states()?;
             }
             GoXLRCommand::SetSubMixEnabled(enabled) => {
-                let headphones = goxlr_types::OutputDevice::Headphones;
-                if self.profile.is_submix_enabled() != enabled {
-                    if !enabled {
-                        // Submixes are being disabled, we need to revert the monitor..
-                        self.profile.set_monitor_mix(headphones)?;
-                        for device in BasicInputDevice::iter() {
-                            self.apply_routing(device).await?;
-                        }
-                    }
-
-                    self.profile.set_submix_enabled(enabled)?;
-                    self.load_submix_settings(true)?;
-                }
+                self.apply_mode_change(ModeChange::SubMix(enabled)).await?;
             }
             GoXLRCommand::SetSubMixVolume(channel, volume) => {
                 self.apply_submix_volume(channel, volume)?;
@@ -2887,40 +5977,124 @@ impl<'a> Device<'a> {
             GoXLRCommand::SetSubMixLinked(channel, linked) => {
                 self.link_submix_channel(channel, linked)?;
             }
+            GoXLRCommand::SetSubMixDefaultLinked(channel, linked) => {
+                let Some(mix) = self.profile.get_submix_from_channel(channel) else {
+                    bail!("{} doesn't have a Submix", channel);
+                };
+                self.settings
+                    .set_device_submix_default_linked(self.serial(), mix, linked)
+                    .await;
+                self.settings.save().await;
+                self.apply_submix_link_defaults().await?;
+            }
+            GoXLRCommand::SetSubMixLinkAll(linked) => {
+                for mix in SubMixChannelName::iter() {
+                    if self.profile.is_channel_linked(mix) == linked {
+                        // Nothing changes, but the profile now has its own setting
+                        self.profile.set_submix_linked(mix, linked)?;
+                    } else {
+                        self.link_submix_channel(submix_to_channel_name(mix), linked)?;
+                    }
+                }
+            }
             GoXLRCommand::SetSubMixOutputMix(device, mix) => {
+                let heard = self
+                    .profile
+                    .get_submix_channel(BasicOutputDevice::Headphones);
                 self.profile.set_mix_output(device, mix)?;
-                self.load_submix_settings(false)?;
+
+                let changed = heard
+                    != self
+                        .profile
+                        .get_submix_channel(BasicOutputDevice::Headphones);
+                self.load_submix_settings(changed)?;
             }
             GoXLRCommand::SetMonitorMix(device) => {
-                self.profile.set_monitor_mix(device)?;
+                // A monitor picked by hand shouldn't be undone when the Headphones come back
+                self.monitor_switched_on_unplug = false;
+                self.set_monitor_mix(device).await?;
+            }
+        }
+        Ok(())
+    }
 
-                // Might be a cleaner way to do this, we only need to handle 1 output..
-                for device in BasicInputDevice::iter() {
-                    self.apply_routing(device).await?;
-                }
+    async fn set_monitor_mix(&mut self, device: BasicOutputDevice) -> Result<()> {
+        let heard = self
+            .profile
+            .get_submix_channel(BasicOutputDevice::Headphones);
+        self.profile.set_monitor_mix(device)?;
 
-                // Make sure to switch Headphones from A to B if needed.
-                self.load_submix_settings(false)?;
-            }
+        // Might be a cleaner way to do this, we only need to handle 1 output..
+        for device in BasicInputDevice::iter() {
+            self.apply_routing(device).await?;
         }
+
+        // Make sure to switch Headphones from A to B if needed, when the mix being heard has
+        // changed its volumes are sent again so the monitor matches what the output gets.
+        let changed = heard
+            != self
+                .profile
+                .get_submix_channel(BasicOutputDevice::Headphones);
+        self.load_submix_settings(changed)?;
         Ok(())
     }
 
     fn update_button_states(&mut self) -> Result<()> {
+        // Identify has full control of the lighting until it's done.
+        if self.identify.is_some() {
+            return Ok(());
+        }
+
         let button_states = self.create_button_states();
-        self.goxlr.set_button_states(button_states)?;
+        self.write_button_states(button_states)?;
         Ok(())
     }
 
     fn create_button_states(&self) -> [ButtonStates; 24] {
         let mut result = [ButtonStates::DimmedColour1; 24];
 
+        if let Some(test) = &self.button_test {
+            for (button, lit) in test.lit {
+                if lit.is_some() {
+                    result[button as usize] = ButtonStates::Colour1;
+                }
+            }
+            return result;
+        }
+
         for button in Buttons::iter() {
             result[button as usize] = self.profile.get_button_colour_state(button);
         }
 
         // Replace the Cough Button button data with correct data.
         result[Buttons::MicrophoneMute as usize] = self.profile.get_mute_chat_button_colour_state();
+
+        // Whatever the off style, inactive buttons show the dimmed colour from get_colour_map
+        if self.high_contrast.is_some() {
+            let inactive = self.high_contrast_inactive_state();
+            for state in result.iter_mut() {
+                if matches!(
+                    state,
+                    ButtonStates::DimmedColour1
+                        | ButtonStates::Colour2
+                        | ButtonStates::DimmedColour2
+                ) {
+                    *state = inactive;
+                }
+            }
+        }
+
+        if self.privacy_locked() {
+            for button in self.mic_mute_buttons() {
+                result[button as usize] = ButtonStates::Colour1;
+            }
+        }
+
+        for (button, flash) in self.guard_flashes {
+            if flash.is_some() {
+                result[sample_button_to_button(button) as usize] = ButtonStates::Flashing;
+            }
+        }
         result
     }
 
@@ -2930,16 +6104,25 @@ impl<'a> Device<'a> {
         input: BasicInputDevice,
         router: EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
-        let (left_input, right_input) = InputDevice::from_basic(&input);
+        let row = self.get_routing_row(input, router);
+        self.write_routing_row(input, row)
+    }
+
+    fn get_routing_row(
+        &self,
+        input: BasicInputDevice,
+        router: EnumMap<BasicOutputDevice, bool>,
+    ) -> RoutingRow {
         let mut left = [0; 22];
         let mut right = [0; 22];
 
+        let (left_level, right_level) = balance_levels(self.profile.get_input_balance(input));
         for output in BasicOutputDevice::iter() {
             if router[output] {
                 let (left_output, right_output) = OutputDevice::from_basic(&output);
 
-                left[left_output.position()] = 0x20;
-                right[right_output.position()] = 0x20;
+                left[left_output.position()] = left_level;
+                right[right_output.position()] = right_level;
             }
         }
 
@@ -2966,33 +6149,31 @@ impl<'a> Device<'a> {
             }
         }
 
+        (left, right)
+    }
+
+    fn write_routing_row(&mut self, input: BasicInputDevice, row: RoutingRow) -> Result<()> {
+        let (left_input, right_input) = InputDevice::from_basic(&input);
+        let (left, right) = row;
+
         self.goxlr.set_routing(left_input, left)?;
         self.goxlr.set_routing(right_input, right)?;
 
         Ok(())
     }
 
-    async fn apply_transient_routing(
+    fn apply_transient_routing(
         &self,
         input: BasicInputDevice,
+        stream_no_music: bool,
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
         // Not all channels are routable, so map the inputs to channels before checking..
-        let channel_name = match input {
-            BasicInputDevice::Microphone => ChannelName::Mic,
-            BasicInputDevice::Chat => ChannelName::Chat,
-            BasicInputDevice::Music => ChannelName::Music,
-            BasicInputDevice::Game => ChannelName::Game,
-            BasicInputDevice::Console => ChannelName::Console,
-            BasicInputDevice::LineIn => ChannelName::LineIn,
-            BasicInputDevice::System => ChannelName::System,
-            BasicInputDevice::Samples => ChannelName::Sample,
-        };
+        let channel_name = input_channel(input);
 
         for fader in FaderName::iter() {
             if self.profile.get_fader_assignment(fader) == channel_name {
-                self.apply_transient_fader_routing(channel_name, fader, router)
-                    .await?;
+                self.apply_transient_fader_routing(channel_name, fader, stream_no_music, router)?;
             }
         }
 
@@ -3000,16 +6181,17 @@ impl<'a> Device<'a> {
         // to ensure that if we're handling the mic, we handle it here.
         if channel_name == ChannelName::Mic {
             self.apply_transient_chat_mic_mute(router)?;
-            self.apply_transient_cough_routing(router).await?;
+            self.apply_transient_cough_routing(stream_no_music, router)?;
         }
 
         Ok(())
     }
 
-    async fn apply_transient_fader_routing(
+    fn apply_transient_fader_routing(
         &self,
         channel_name: ChannelName,
         fader: FaderName,
+        stream_no_music: bool,
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
         let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
@@ -3018,13 +6200,14 @@ impl<'a> Device<'a> {
             muted_to_x,
             muted_to_all,
             mute_function,
+            stream_no_music,
             router,
         )
-        .await
     }
 
-    async fn apply_transient_cough_routing(
+    fn apply_transient_cough_routing(
         &self,
+        stream_no_music: bool,
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
         // Same deal, pull out the current state, make needed changes.
@@ -3036,39 +6219,55 @@ impl<'a> Device<'a> {
             muted_to_x,
             muted_to_all,
             mute_function,
+            stream_no_music,
             router,
         )
-        .await
     }
 
     fn apply_transient_chat_mic_mute(
         &self,
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
-        // Kinda annoying, try to locate the fader with the Chat Mic Assigned..
-        for fader in FaderName::iter() {
-            if self.profile.get_fader_assignment(fader) == ChannelName::Chat {
-                // Get the Mute State..
-                let (muted_to_x, muted_to_all, mute_function) =
-                    self.profile.get_mute_button_state(fader);
-                if muted_to_all || (muted_to_x && mute_function == MuteFunction::All) {
-                    let mute_to_chat = self.vc_mute_also_mute_cm;
-                    if mute_to_chat {
-                        router[BasicOutputDevice::ChatMic] = false;
-                    }
-                }
+        // This only ever removes outputs, so it combines with the Mic's own mutes
+        if self.voice_chat_muted_to_all() {
+            for output in &self.vc_mute_outputs {
+                router[*output] = false;
             }
         }
 
         Ok(())
     }
 
-    async fn apply_transient_channel_routing(
+    // Voice Chat can only be muted by the mute button of a fader it's assigned to
+    fn voice_chat_muted_to_all(&self) -> bool {
+        FaderName::iter().any(|fader| {
+            self.profile.get_fader_assignment(fader) == ChannelName::Chat
+                && self.fader_muted_to_all(fader)
+        })
+    }
+
+    async fn set_vc_mute_outputs(&mut self, outputs: Vec<BasicOutputDevice>) -> Result<()> {
+        // Stored in the usual output order, without duplicates
+        let outputs: Vec<_> = BasicOutputDevice::iter()
+            .filter(|output| outputs.contains(output))
+            .collect();
+        self.settings
+            .set_device_vc_mute_outputs(self.serial(), outputs.clone())
+            .await;
+        self.settings.save().await;
+        self.vc_mute_outputs = outputs;
+
+        // Re-run the Microphone Routing to update if needed..
+        self.apply_routing(BasicInputDevice::Microphone).await
+    }
+
+    fn apply_transient_channel_routing(
         &self,
         channel_name: ChannelName,
         muted_to_x: bool,
         muted_to_all: bool,
         mute_function: MuteFunction,
+        stream_no_music: bool,
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
         if !muted_to_x || muted_to_all || mute_function == MuteFunction::All {
@@ -3090,7 +6289,7 @@ impl<'a> Device<'a> {
                 router[BasicOutputDevice::BroadcastMix] = false;
 
                 // If we're a mini, with VOD Mode 'Stream No Music', disable this route to VOD.
-                if self.is_steam_no_music().await {
+                if stream_no_music {
                     router[BasicOutputDevice::Sampler] = false;
                 }
             }
@@ -3103,6 +6302,27 @@ impl<'a> Device<'a> {
     }
 
     async fn apply_routing(&mut self, input: BasicInputDevice) -> Result<()> {
+        let router = self.get_applied_routing(input).await?;
+        self.apply_channel_routing(input, router)?;
+
+        Ok(())
+    }
+
+    /// The routing for an input once everything outside the profile's routing table (the
+    /// monitor, mutes, Stream No Music, etc.) has been taken into account.
+    async fn get_applied_routing(
+        &self,
+        input: BasicInputDevice,
+    ) -> Result<EnumMap<BasicOutputDevice, bool>> {
+        let layout = self.get_mix_layout().await;
+        self.get_layout_routing(input, &layout).await
+    }
+
+    async fn get_layout_routing(
+        &self,
+        input: BasicInputDevice,
+        layout: &MixLayout,
+    ) -> Result<EnumMap<BasicOutputDevice, bool>> {
         // Load the routing for this channel from the profile..
         let mut router = self.profile.get_router(input);
 
@@ -3120,10 +6340,11 @@ impl<'a> Device<'a> {
             }
         }
 
-        if self.is_steam_no_music().await {
+        if layout.stream_no_music {
             // Ok, so we need to sync the Mix channel to the Sample (VOD) Channel, unless Music
-            if input == BasicInputDevice::Music {
-                // Force Music -> Sample to Off
+            // (or Samples, if they've been excluded)
+            if self.is_excluded_from_vod(input).await {
+                // Force the route to Sample Off
                 router[BasicOutputDevice::Sampler] = false;
             } else {
                 // Sync the Mix and Sampler (VOD) channels
@@ -3131,18 +6352,72 @@ impl<'a> Device<'a> {
             }
         }
 
-        self.apply_transient_routing(input, &mut router).await?;
+        self.apply_transient_routing(input, layout.stream_no_music, &mut router)?;
         debug!("Applying Routing to {:?}:", input);
         debug!("{:?}", router);
 
-        let monitor = self.profile.get_monitoring_mix();
-        if monitor != BasicOutputDevice::Headphones {
-            router[BasicOutputDevice::Headphones] = router[monitor];
+        if layout.monitor != BasicOutputDevice::Headphones {
+            router[BasicOutputDevice::Headphones] = router[layout.monitor];
         }
 
-        self.apply_channel_routing(input, router)?;
+        Ok(router)
+    }
 
-        Ok(())
+    /// What can be heard on each output, from the same routing apply_routing sends to the device
+    /// so that the two can't disagree.
+    async fn get_output_activity(&self) -> Result<EnumMap<BasicOutputDevice, OutputActivity>> {
+        let mut routing: EnumMap<BasicInputDevice, EnumMap<BasicOutputDevice, bool>> =
+            EnumMap::default();
+        for input in BasicInputDevice::iter() {
+            routing[input] = self.get_applied_routing(input).await?;
+        }
+
+        let mut activity: EnumMap<BasicOutputDevice, OutputActivity> = EnumMap::default();
+        for output in BasicOutputDevice::iter() {
+            let inputs: Vec<BasicInputDevice> = BasicInputDevice::iter()
+                .filter(|input| routing[*input][output])
+                .collect();
+
+            let muted = |input: BasicInputDevice| match input_channel(input) {
+                ChannelName::Mic => self.get_mic_channel_state() == Muted,
+                channel => self.profile.get_channel_mute_state(channel) == Muted,
+            };
+            let audible = inputs
+                .iter()
+                .any(|input| !muted(*input) && self.get_heard_volume(*input, output) > 0);
+
+            let output_volume = match output {
+                BasicOutputDevice::Headphones => Some(ChannelName::Headphones),
+                BasicOutputDevice::LineOut => Some(ChannelName::LineOut),
+                _ => None,
+            }
+            .map(|channel| self.profile.get_channel_volume(channel));
+
+            activity[output] = OutputActivity {
+                all_muted: !inputs.is_empty() && inputs.iter().all(|input| muted(*input)),
+                likely_silent: !audible || output_volume == Some(0),
+                inputs,
+            };
+        }
+        Ok(activity)
+    }
+
+    // The volume an input is sent to an output at, before the output's own volume
+    fn get_heard_volume(&self, input: BasicInputDevice, output: BasicOutputDevice) -> u8 {
+        let channel = input_channel(input);
+
+        // The Mic is monitored in the headphones at its own level
+        if channel == ChannelName::Mic && output == BasicOutputDevice::Headphones {
+            return self.profile.get_channel_volume(ChannelName::MicMonitor);
+        }
+
+        let submixes = self.device_supports_submixes() && self.profile.is_submix_enabled();
+        if submixes && self.profile.get_submix_channel(output) == Mix::B {
+            if let Some(submix) = self.profile.get_submix_from_channel(channel) {
+                return self.profile.get_submix_volume(submix);
+            }
+        }
+        self.profile.get_channel_volume(channel)
     }
 
     fn apply_mute_from_profile(
@@ -3162,13 +6437,13 @@ impl<'a> Device<'a> {
                         "Setting Channel {} to Muted (change from previous)",
                         channel
                     );
-                    self.goxlr.set_channel_state(channel, Muted)?;
+                    self.write_channel_state(channel, Muted)?;
                 } else {
                     debug!("Fader {} is Already Muted, doing nothing.", fader);
                 }
             } else {
                 debug!("Setting Channel {} to Muted (no previous)", channel);
-                self.goxlr.set_channel_state(channel, Muted)?;
+                self.write_channel_state(channel, Muted)?;
             }
 
             return Ok(());
@@ -3178,13 +6453,13 @@ impl<'a> Device<'a> {
         if let Some(current) = current {
             if current != Unmuted {
                 debug!("Channel {} set to Unmuted (change from previous)", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.write_channel_state(channel, Unmuted)?;
             } else {
                 debug!("Channel {} already Unmuted, doing nothing.", fader);
             }
         } else {
             debug!("Channel {} set to Unmuted (no previous)", channel);
-            self.goxlr.set_channel_state(channel, Unmuted)?;
+            self.write_channel_state(channel, Unmuted)?;
         }
 
         Ok(())
@@ -3192,44 +6467,52 @@ impl<'a> Device<'a> {
 
     fn apply_cough_from_profile(&mut self) -> Result<()> {
         // As above, but applies the cough profile.
-        let (mute_toggle, muted_to_x, muted_to_all, mute_function) =
-            self.profile.get_mute_chat_button_state();
+        if self.release_held_cough() {
+            return Ok(());
+        }
+
+        let state = self.get_mic_channel_state();
+        debug!("Setting Mic to {:?}", state);
+        self.write_channel_state(ChannelName::Mic, state)?;
+        Ok(())
+    }
 
-        // Firstly, if toggle is to hold and anything is muted, clear it.
+    /// If the cough button is set to hold and anything is muted, it can't still be held, so
+    /// clear it. Returns whether anything was cleared.
+    fn release_held_cough(&mut self) -> bool {
+        let (mute_toggle, muted_to_x, _, _) = self.profile.get_mute_chat_button_state();
         if !mute_toggle && muted_to_x {
             self.profile.set_mute_chat_button_on(false);
             self.profile.set_mute_chat_button_blink(false);
-            return Ok(());
+            return true;
         }
+        false
+    }
 
-        let muted_by_fader = if self.profile.is_mic_on_fader() {
-            // We need to check this fader's mute button..
-            let fader = self.profile.get_mic_fader();
-            let (muted_to_x, muted_to_all, mute_function) =
-                self.profile.get_mute_button_state(fader);
-            muted_to_all || (muted_to_x && mute_function == MuteFunction::All)
-        } else {
-            false
-        };
+    /// The profile's mute state for each channel, with the Mic's cough button taken into account.
+    fn get_expected_mute_states(&self) -> EnumMap<ChannelName, ChannelState> {
+        let mut mute_state = self.profile.get_current_state().mute_state;
+        mute_state[ChannelName::Mic] = self.get_mic_channel_state();
+        mute_state
+    }
+
+    /// The state of the Mic channel, from the cough button and the mic's fader.
+    fn get_mic_channel_state(&self) -> ChannelState {
+        if self.privacy_locked() {
+            return Muted;
+        }
 
-        if muted_to_all || (muted_to_x && mute_function == MuteFunction::All) || muted_by_fader {
-            debug!("Setting Mic to Muted");
-            self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+        if self.mic_muted_by_cough() || self.mic_muted_by_fader() {
+            Muted
         } else {
-            debug!("Setting Mic to Unmuted");
-            self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+            Unmuted
         }
-        Ok(())
     }
 
     async fn set_fader(&mut self, fader: FaderName, new_channel: ChannelName) -> Result<()> {
         // A couple of things need to happen when a fader change occurs depending on scenario..
         if new_channel == self.profile.get_fader_assignment(fader) {
             // We don't need to do anything at all in theory, set the fader anyway..
-            if new_channel == ChannelName::Mic {
-                self.profile.set_mic_fader(fader)?;
-            }
-
             // Submix firmware bug mitigation:
             if self.needs_submix_correction(new_channel) {
                 return Ok(());
@@ -3243,83 +6526,99 @@ impl<'a> Device<'a> {
         let existing_channel = self.profile.get_fader_assignment(fader);
 
         // Go over the faders, see if the new channel is already bound..
-        let mut fader_to_switch: Option<FaderName> = None;
-        for fader_name in FaderName::iter() {
-            if fader_name != fader && self.profile.get_fader_assignment(fader_name) == new_channel {
-                fader_to_switch = Some(fader_name);
+        let fader_to_switch = FaderName::iter().find(|fader_name| {
+            *fader_name != fader && self.profile.get_fader_assignment(*fader_name) == new_channel
+        });
+
+        match fader_to_switch {
+            None => {
+                // Whatever is on the fader already is going away, per windows behaviour we need
+                // to ensure any mute behaviour is restored as it can no longer be tracked.
+                self.unmute_fader(fader).await?;
+
+                // Now set the new fader, the profile keeps track of where the Mic ends up..
+                self.profile.set_fader_assignment(fader, new_channel);
+
+                // The mute behaviour stays with the fader, so it may not make sense for the new
+                // channel
+                let behaviour = self.profile.get_mute_button_behaviour(fader);
+                if !behaviour.is_valid_for_channel(new_channel) {
+                    warn!(
+                        "Mute Function {} is not valid for {}, resetting Fader {} to All",
+                        behaviour, new_channel, fader
+                    );
+                    self.profile
+                        .set_mute_button_behaviour(fader, BasicMuteFunction::All);
+                    self.mute_function_reset = Some((fader, BasicMuteFunction::All));
+                }
             }
-        }
 
-        if fader_to_switch.is_none() {
-            // Whatever is on the fader already is going away, per windows behaviour we need to
-            // ensure any mute behaviour is restored as it can no longer be tracked.
-            self.unmute_fader(fader).await?;
+            // The faders and mute settings are switched in the config, but nothing else changes
+            Some(fader_to_switch) => self.profile.switch_fader_assignment(fader, fader_to_switch),
+        }
 
-            // Check to see if we are dispatching of the mic channel, if so set the id.
-            if existing_channel == ChannelName::Mic {
-                self.profile.clear_mic_fader();
+        // The lighting and scribbles for the new assignment are prepared before anything is
+        // sent, then sent together, so a fader never shows one channel's scribble with another
+        // channel's lighting.
+        let colour_map = match self.identify {
+            None => Some(self.get_colour_map().await),
+            Some(_) => None,
+        };
+        let mut scribbles = vec![];
+        if !self.is_device_mini() {
+            for changed in iter::once(fader).chain(fader_to_switch) {
+                if let Some(scribble) = self.render_scribble(changed).await {
+                    scribbles.push((changed, scribble));
+                }
             }
+        }
 
-            // Now set the new fader..
-            self.profile.set_fader_assignment(fader, new_channel);
-            self.goxlr.set_fader(fader, new_channel)?;
+        // Now switch the faders on the GoXLR..
+        self.goxlr.set_fader(fader, new_channel)?;
+        match fader_to_switch {
+            Some(fader_to_switch) => self.goxlr.set_fader(fader_to_switch, existing_channel)?,
 
             // Due to motorised faders, the internal 'old' channel may be incorrectly set,
             // despite our config here being valid. So we'll force update the old channel.
-            self.goxlr.set_volume(
+            None => self.goxlr.set_volume(
                 existing_channel,
                 self.profile.get_channel_volume(existing_channel),
-            )?;
-
-            // Make sure the new channel comes in with the correct volume..
-            if self.needs_submix_correction(new_channel) {
-                let volume = self.profile.get_channel_volume(new_channel);
-                self.goxlr.set_volume(new_channel, volume)?;
-            }
-
-            // Remember to update the button states after change..
-            self.update_button_states()?;
-
-            return Ok(());
-        }
-
-        // This will always be set here..
-        let fader_to_switch = fader_to_switch.unwrap();
-
-        // So we need to switch the faders and mute settings, but nothing else actually changes,
-        // we'll simply switch the faders and mute buttons in the config, then apply to the
-        // GoXLR.
-        self.profile.switch_fader_assignment(fader, fader_to_switch);
-
-        // Are either of the moves being done by the mic channel?
-        if new_channel == ChannelName::Mic {
-            self.profile.set_mic_fader(fader)?;
+            )?,
         }
 
-        if existing_channel == ChannelName::Mic {
-            self.profile.set_mic_fader(fader_to_switch)?;
-        }
-
-        // Now switch the faders on the GoXLR..
-        self.goxlr.set_fader(fader, new_channel)?;
-        self.goxlr.set_fader(fader_to_switch, existing_channel)?;
-
         // If the channel being moved is either Headphone or Line Out, reset the volume..
         if self.needs_submix_correction(new_channel) {
             let volume = self.profile.get_channel_volume(new_channel);
             self.goxlr.set_volume(new_channel, volume)?;
         }
-        if self.needs_submix_correction(existing_channel) {
-            let volume = self.profile.get_channel_volume(existing_channel);
-            self.goxlr.set_volume(existing_channel, volume)?;
+        self.fader_volume_set(fader, self.profile.get_channel_volume(new_channel));
+
+        if let Some(fader_to_switch) = fader_to_switch {
+            if self.needs_submix_correction(existing_channel) {
+                let volume = self.profile.get_channel_volume(existing_channel);
+                self.goxlr.set_volume(existing_channel, volume)?;
+            }
+            self.fader_volume_set(
+                fader_to_switch,
+                self.profile.get_channel_volume(existing_channel),
+            );
+        }
+
+        if let Some(colour_map) = colour_map {
+            self.write_colour_map(colour_map)?;
         }
 
-        if !self.is_device_mini() {
-            self.apply_scribble(fader).await?;
-            self.apply_scribble(fader_to_switch).await?;
+        // The display style is switched along with the faders
+        if let Some(fader_to_switch) = fader_to_switch {
+            self.set_fader_display_from_profile(fader)?;
+            self.set_fader_display_from_profile(fader_to_switch)?;
+        }
+
+        for (changed, scribble) in scribbles {
+            self.write_scribble(changed, scribble)?;
         }
 
-        // Finally update the button colours..
+        // Finally update the button states..
         self.update_button_states()?;
 
         Ok(())
@@ -3329,10 +6628,17 @@ impl<'a> Device<'a> {
         FaderStatus {
             channel: self.profile().get_fader_assignment(fader),
             mute_type: self.profile().get_mute_button_behaviour(fader),
-            scribble: self
-                .profile()
-                .get_scribble_ipc(fader, self.is_device_mini()),
+            scribble: self.profile().get_scribble_ipc(
+                fader,
+                self.is_device_mini(),
+                self.scribbles[fader].rendered.clone(),
+                self.scribbles[fader]
+                    .error
+                    .as_ref()
+                    .map(|error| error.message.clone()),
+            ),
             mute_state: self.profile.get_ipc_mute_state(fader),
+            awaiting_pickup: self.fader_pickups[fader].direction(),
         }
     }
 
@@ -3353,16 +6659,71 @@ impl<'a> Device<'a> {
     }
 
     async fn load_colour_map(&mut self) -> Result<()> {
+        // This will be reloaded once the identify has finished
+        if self.identify.is_some() {
+            return Ok(());
+        }
+
+        let colour_map = self.get_colour_map().await;
+        self.write_colour_map(colour_map)
+    }
+
+    async fn get_colour_map(&self) -> [u8; 520] {
         // The new colour format occurred on different firmware versions depending on device,
         // so do the check here.
-        let lock_faders = self.settings.get_device_lock_faders(self.serial()).await;
+        let lock_faders = self.faders_locked().await;
 
         let blank_mute = self.is_device_mini() || lock_faders;
 
         let use_1_3_40_format = self.device_supports_animations();
-        let colour_map = self.profile.get_colour_map(use_1_3_40_format, blank_mute);
+        let mut colour_map = self.profile.get_colour_map(use_1_3_40_format, blank_mute);
+
+        // Each button's first colour is scaled to its active and inactive levels, anything
+        // reactive or locked below is drawn over the top as before
+        if let (false, Some(config)) = (self.lighting_overridden(), &self.high_contrast) {
+            for button in Button::iter() {
+                let levels = high_contrast_levels(config, button);
+                let target = standard_to_colour_target(button);
+                let active = target.position(0, use_1_3_40_format);
+                let inactive = target.position(1, use_1_3_40_format);
+
+                let colour: [u8; 4] = colour_map[active..active + 4].try_into().unwrap();
+                colour_map[active..active + 4]
+                    .copy_from_slice(&scale_colour(colour, levels.active));
+                colour_map[inactive..inactive + 4]
+                    .copy_from_slice(&scale_colour(colour, levels.inactive));
+            }
+        }
 
-        if use_1_3_40_format {
+        if let Some(reactive) = &self.reactive {
+            if let (false, Some(colour)) = (self.lighting_overridden(), reactive.colour) {
+                for zone in &reactive.config.zones {
+                    let target = match *zone {
+                        ReactiveZone::Simple(target) => standard_to_profile_simple_colour(target),
+                        ReactiveZone::Button(button) => standard_to_colour_target(button),
+                    };
+                    for i in 0..target.get_colour_count() {
+                        let position = target.position(i, use_1_3_40_format);
+                        colour_map[position..position + 4].copy_from_slice(&colour);
+                    }
+                }
+            }
+        }
+
+        if self.privacy_locked() && !self.lighting_overridden() {
+            for button in self.mic_mute_buttons() {
+                let target = standard_to_colour_target(usb_to_standard_button(button));
+                for i in 0..target.get_colour_count() {
+                    let position = target.position(i, use_1_3_40_format);
+                    colour_map[position..position + 4].copy_from_slice(&PRIVACY_COLOUR);
+                }
+            }
+        }
+        colour_map
+    }
+
+    fn write_colour_map(&mut self, colour_map: [u8; 520]) -> Result<()> {
+        if self.device_supports_animations() {
             self.goxlr.set_button_colours_1_3_40(colour_map)?;
         } else {
             let mut map: [u8; 328] = [0; 328];
@@ -3370,28 +6731,148 @@ impl<'a> Device<'a> {
             self.goxlr.set_button_colours(map)?;
         }
 
+        self.sent.colour_map = Some(colour_map);
         Ok(())
     }
 
-    async fn load_animation(&mut self, map_set: bool) -> Result<()> {
-        let enabled = self.profile.get_animation_mode() != goxlr_types::AnimationMode::None;
+    fn write_button_states(&mut self, button_states: [ButtonStates; 24]) -> Result<()> {
+        self.goxlr.set_button_states(button_states)?;
+        self.sent.button_states = Some(button_states);
+        Ok(())
+    }
 
-        // This one is kinda weird, we go from profile -> types -> usb..
-        let mode = match self.profile.get_animation_mode() {
-            goxlr_types::AnimationMode::RetroRainbow => AnimationMode::RetroRainbow,
-            goxlr_types::AnimationMode::RainbowDark => AnimationMode::RainbowDark,
-            goxlr_types::AnimationMode::RainbowBright => AnimationMode::RainbowBright,
-            goxlr_types::AnimationMode::Simple => AnimationMode::Simple,
-            goxlr_types::AnimationMode::Ripple => AnimationMode::Ripple,
-            goxlr_types::AnimationMode::None => AnimationMode::None,
+    fn write_channel_state(&mut self, channel: ChannelName, state: ChannelState) -> Result<()> {
+        // Every path which unmutes the Mic comes through here, so this is where the lock holds
+        let state = match channel == ChannelName::Mic && self.privacy_locked() {
+            true => Muted,
+            false => state,
         };
+        self.goxlr.set_channel_state(channel, state)?;
+        self.sent.mute_state[channel] = Some(state);
+        Ok(())
+    }
 
-        let mod1 = self.profile.get_animation_mod1();
-        let mod2 = self.profile.get_animation_mod2();
-        let waterfall = match self.profile.get_animation_waterfall() {
-            WaterfallDirection::Down => WaterFallDir::Down,
-            WaterfallDirection::Up => WaterFallDir::Up,
-            WaterfallDirection::Off => WaterFallDir::Off,
+    /// Warns about any mute state or lighting on the device which has drifted from the profile,
+    /// runs after every command and button press when enabled (see CONSISTENCY_CHECKS).
+    async fn check_consistency(&self) {
+        if !CONSISTENCY_CHECKS.load(Ordering::Relaxed) {
+            return;
+        }
+
+        for problem in self.find_inconsistencies().await {
+            warn!("Consistency Check on {}: {}", self.serial(), problem);
+        }
+    }
+
+    /// Compares what was last written to the device with what the profile says it should be.
+    /// While Identify or the Button Test are running they own the lighting, so only the mute
+    /// states are compared.
+    async fn find_inconsistencies(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        let expected = self.get_expected_mute_states();
+        for (channel, sent) in self.sent.mute_state {
+            if let Some(sent) = sent {
+                if sent != expected[channel] {
+                    problems.push(format!(
+                        "Channel {} is {:?} on the device, but should be {:?}",
+                        channel, sent, expected[channel]
+                    ));
+                }
+            }
+        }
+
+        if self.identify.is_some() || self.button_test.is_some() {
+            return problems;
+        }
+
+        if let Some(sent) = self.sent.button_states {
+            let expected = self.create_button_states();
+            for button in Buttons::iter() {
+                let index = button as usize;
+                if sent[index] != expected[index] {
+                    problems.push(format!(
+                        "Button {:?} is {:?} on the device, but should be {:?}",
+                        button, sent[index], expected[index]
+                    ));
+                }
+            }
+        }
+
+        if let Some(sent) = self.sent.colour_map {
+            let expected = self.get_colour_map().await;
+            if sent != expected {
+                // The whole map is large, so just name the parts of it which differ
+                let format = self.device_supports_animations();
+                let targets: Vec<String> = ColourTargets::iter()
+                    .filter(|target| {
+                        (0..target.get_colour_count()).any(|index| {
+                            let position = target.position(index, format);
+                            sent[position..position + 4] != expected[position..position + 4]
+                        })
+                    })
+                    .map(|target| format!("{:?}", target))
+                    .collect();
+                problems.push(format!(
+                    "Colour Map is stale, differing in: {}",
+                    targets.join(", ")
+                ));
+            }
+        }
+        problems
+    }
+
+    fn get_lighting_status(&self) -> Lighting {
+        let is_mini = self.is_device_mini();
+        let mut lighting = self
+            .profile
+            .get_lighting_ipc(is_mini, self.device_supports_animations());
+
+        if let Some(reactive) = &self.reactive {
+            lighting.reactive = ReactiveLighting {
+                config: Some(reactive.config.clone()),
+                active: !reactive.suspended,
+                level: reactive.level,
+            };
+        }
+        lighting.high_contrast = self.high_contrast.clone();
+        lighting
+    }
+
+    fn get_device_test_status(&self) -> DeviceTestStatus {
+        let mut buttons_registered: EnumMap<Button, bool> = Default::default();
+        if let Some(test) = &self.button_test {
+            for button in test.registered {
+                buttons_registered[usb_to_standard_button(button)] = true;
+            }
+        }
+
+        DeviceTestStatus {
+            identifying: self.identify.is_some(),
+            button_test: self.button_test.is_some(),
+            buttons_registered,
+        }
+    }
+
+    async fn load_animation(&mut self, map_set: bool) -> Result<()> {
+        let enabled = self.profile.get_animation_mode() != goxlr_types::AnimationMode::None;
+
+        // This one is kinda weird, we go from profile -> types -> usb..
+        let mode = match self.profile.get_animation_mode() {
+            goxlr_types::AnimationMode::RetroRainbow => AnimationMode::RetroRainbow,
+            goxlr_types::AnimationMode::RainbowDark => AnimationMode::RainbowDark,
+            goxlr_types::AnimationMode::RainbowBright => AnimationMode::RainbowBright,
+            goxlr_types::AnimationMode::Simple => AnimationMode::Simple,
+            goxlr_types::AnimationMode::Ripple => AnimationMode::Ripple,
+            goxlr_types::AnimationMode::None => AnimationMode::None,
+        };
+
+        let mod1 = self.profile.get_animation_mod1();
+        let mod2 = self.profile.get_animation_mod2();
+        let waterfall = match self.profile.get_animation_waterfall() {
+            WaterfallDirection::Down => WaterFallDir::Down,
+            WaterfallDirection::Up => WaterFallDir::Up,
+            WaterfallDirection::Off => WaterFallDir::Off,
         };
 
         self.goxlr
@@ -3405,114 +6886,222 @@ impl<'a> Device<'a> {
             self.load_colour_map().await?;
         }
 
+        // High Contrast's inactive state depends on the animation
+        if self.high_contrast.is_some() {
+            self.update_button_states()?;
+        }
+
         Ok(())
     }
 
-    async fn apply_profile(&mut self, current: Option<CurrentState>) -> Result<()> {
-        // Set volumes first, applying mute may modify stuff..
+    /// A snapshot of what's been written to the device, so a profile load can skip anything
+    /// which isn't changing.
+    async fn get_current_state(&self) -> Result<CurrentState> {
+        let mut state = self.profile.get_current_state();
+        state.mute_state[ChannelName::Mic] = self.get_mic_channel_state();
+        state.volumes = self.get_load_volumes();
+        state.submix_volumes = Some(self.get_submix_load_volumes(self.profile.is_submix_enabled()));
+        state.output_mixes = Some(self.get_output_mixes(self.profile.is_submix_enabled()));
+        state.voice_fx = self.get_voice_fx();
+
+        let mut routing = EnumMap::default();
+        for input in BasicInputDevice::iter() {
+            let router = self.get_applied_routing(input).await?;
+            routing[input] = self.get_routing_row(input, router);
+        }
+        state.routing = Some(routing);
+
+        // While identifying, the lighting on the device isn't the profile's
+        if self.identify.is_none() {
+            state.colour_map = Some(self.get_colour_map().await);
+            state.button_states = Some(self.create_button_states());
+        }
+        Ok(state)
+    }
+
+    async fn apply_profile(&mut self, mut current: Option<CurrentState>) -> Result<()> {
+        // Any preview belongs to the previous profile
+        self.effect_preview = None;
+
+        // With a previous state, only what differs from it is written. Routing goes first so
+        // audio never passes through a half loaded path, and the lighting is sent in one go at
+        // the end so it doesn't flash through the defaults. Between each step, anything
+        // interactive is let in (see preempt).
         debug!("Applying Profile..");
 
+        // The cough button affects the routing, so settle it first
+        self.release_held_cough();
+
+        debug!("Applying Routing..");
+        let previous_routing = current.as_ref().and_then(|current| current.routing);
+        for input in BasicInputDevice::iter() {
+            let router = self.get_applied_routing(input).await?;
+            let row = self.get_routing_row(input, router);
+            if previous_routing.is_some_and(|previous| previous[input] == row) {
+                debug!("Routing for {:?} unchanged, ignoring", input);
+                continue;
+            }
+            self.write_routing_row(input, row)?;
+        }
+        self.preempt(&mut current).await;
+
         debug!("Setting Faders..");
-        let mut mic_assigned_to_fader = false;
-        //
-        // // Prepare the faders, and configure channel mute states
         for fader in FaderName::iter() {
             let assignment = self.profile.get_fader_assignment(fader);
-
-            if let Some(current) = &current {
-                if current.faders[fader] != assignment {
-                    debug!("Setting Fader {} to {:?}", fader, assignment);
-                    self.goxlr.set_fader(fader, assignment)?;
-                } else {
-                    debug!("Fader Already Assigned, ignoring");
-                }
-            } else {
-                debug!("Setting Fader {} to {:?}", fader, assignment);
-                self.goxlr.set_fader(fader, assignment)?;
+            if current
+                .as_ref()
+                .is_some_and(|current| current.faders[fader] == assignment)
+            {
+                debug!("Fader Already Assigned, ignoring");
+                continue;
             }
 
-            // Force Mic Fader Assignment
-            if assignment == ChannelName::Mic {
-                mic_assigned_to_fader = true;
-                self.profile.set_mic_fader(fader)?;
+            debug!("Setting Fader {} to {:?}", fader, assignment);
+            self.goxlr.set_fader(fader, assignment)?;
+        }
+        self.preempt(&mut current).await;
+
+        // Each channel is written once here, including the Mic Monitor which the submix
+        // settings would otherwise send again.
+        debug!("Setting Channel Volumes..");
+        let previous_volumes = current.as_ref().map(|current| current.volumes);
+        let volumes = self.get_load_volumes();
+        for channel in self.get_load_volume_order(previous_volumes) {
+            let channel_volume = volumes[channel];
+            if previous_volumes.is_some_and(|volumes| volumes[channel] == channel_volume) {
+                continue;
             }
+
+            debug!("Setting volume for {} to {}", channel, channel_volume);
+            self.goxlr.set_volume(channel, channel_volume)?;
         }
-        if !mic_assigned_to_fader {
-            self.profile.clear_mic_fader();
+
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            self.fader_volume_set(fader, self.profile.get_channel_volume(channel));
         }
+        self.preempt(&mut current).await;
 
         debug!("Setting Mute States..");
         for channel in ChannelName::iter() {
+            let previous = current.as_ref().map(|current| current.mute_state[channel]);
             if channel == ChannelName::Mic {
                 debug!("Applying Microphone Mute State");
-                self.apply_cough_from_profile()?;
-            } else if let Some(fader) = self.profile.get_fader_from_channel(channel) {
-                debug!("Channel {} on Fader, Loading State from Profile", channel);
-                if let Some(current) = &current {
-                    self.apply_mute_from_profile(fader, Some(current.mute_state[channel]))?;
+                let state = self.get_mic_channel_state();
+                if previous == Some(state) {
+                    debug!("Mic is Already {:?}, doing nothing.", state);
                 } else {
-                    self.apply_mute_from_profile(fader, None)?;
+                    debug!("Setting Mic to {:?}", state);
+                    self.write_channel_state(channel, state)?;
                 }
-            } else if let Some(current) = &current {
-                if current.mute_state[channel] != Unmuted {
+            } else if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+                debug!("Channel {} on Fader, Loading State from Profile", channel);
+                self.apply_mute_from_profile(fader, previous)?;
+            } else if let Some(previous) = previous {
+                if previous != Unmuted {
                     debug!("Channel {} not on Fader, but muted. Unmuting..", channel);
-                    self.goxlr.set_channel_state(channel, Unmuted)?;
+                    self.write_channel_state(channel, Unmuted)?;
                 }
             } else {
                 debug!("Unknown Channel state for {}, Unmuting.", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.write_channel_state(channel, Unmuted)?;
             }
         }
-
-        debug!("Setting Channel Volumes..");
-        let volumes = if let Some(current) = &current {
-            self.get_load_volume_order(Some(current.volumes))
-        } else {
-            self.get_load_volume_order(None)
-        };
-
-        for channel in volumes {
-            let channel_volume = self.profile.get_channel_volume(channel);
-
-            debug!("Setting volume for {} to {}", channel, channel_volume);
-            self.goxlr.set_volume(channel, channel_volume)?;
-        }
+        self.preempt(&mut current).await;
 
         debug!("Applying Submixing Settings..");
-        self.load_submix_settings(true)?;
-
-        debug!("Loading Colour Map..");
-        self.load_colour_map().await?;
+        self.apply_submix_link_defaults().await?;
+        if self.device_supports_submixes() {
+            let mixes = self.get_output_mixes(self.profile.is_submix_enabled());
+            if current
+                .as_ref()
+                .is_some_and(|current| current.output_mixes == Some(mixes))
+            {
+                debug!("Output Mixes unchanged, ignoring");
+            } else {
+                self.write_output_mixes()?;
+            }
 
-        if self.device_supports_animations() {
-            // Load any animation settings..
-            self.load_animation(true).await?;
+            let previous = current.as_ref().and_then(|current| current.submix_volumes);
+            let volumes = self.get_submix_load_volumes(self.profile.is_submix_enabled());
+            self.write_submix_volumes(volumes, previous)?;
         }
+        self.preempt(&mut current).await;
 
         debug!("Setting Fader display modes..");
         for fader in FaderName::iter() {
+            let display = (
+                self.profile.is_fader_gradient(fader),
+                self.profile.is_fader_meter(fader),
+            );
+            if current
+                .as_ref()
+                .is_some_and(|current| current.fader_display[fader] == display)
+            {
+                continue;
+            }
+
             debug!("Setting display for {}", fader);
             self.set_fader_display_from_profile(fader)?;
         }
+        self.preempt(&mut current).await;
 
-        if !self.is_device_mini() {
-            for fader in FaderName::iter() {
-                self.apply_scribble(fader).await?;
+        if self.device_supports_animations() {
+            let animation = self.profile.get_animation();
+            if current
+                .as_ref()
+                .is_some_and(|current| current.animation == Some(animation))
+            {
+                debug!("Animation unchanged, ignoring");
+            } else {
+                // Load any animation settings..
+                self.load_animation(true).await?;
             }
+            self.preempt(&mut current).await;
         }
 
-        debug!("Updating button states..");
-        self.update_button_states()?;
+        // The colour map and button states are only held back while identifying, they're
+        // reloaded once it's done.
+        if self.identify.is_none() {
+            debug!("Loading Colour Map..");
+            let colour_map = self.get_colour_map().await;
+            if current
+                .as_ref()
+                .is_some_and(|current| current.colour_map == Some(colour_map))
+            {
+                debug!("Colour Map unchanged, ignoring");
+            } else {
+                self.write_colour_map(colour_map)?;
+            }
 
-        debug!("Applying Routing..");
-        // For profile load, we should configure all the input channels from the profile,
-        // this is split so we can do tweaks in places where needed.
-        for input in BasicInputDevice::iter() {
-            self.apply_routing(input).await?;
+            debug!("Updating button states..");
+            let button_states = self.create_button_states();
+            if current
+                .as_ref()
+                .is_some_and(|current| current.button_states == Some(button_states))
+            {
+                debug!("Button States unchanged, ignoring");
+            } else {
+                self.write_button_states(button_states)?;
+            }
+        }
+        self.preempt(&mut current).await;
+
+        // The scribbles are drawn in the background, when what was on the device is known, any
+        // which come out the same aren't sent again.
+        if !self.is_device_mini() {
+            for fader in FaderName::iter() {
+                self.scribbles[fader].pending = true;
+                self.scribbles[fader].keep_unchanged = current.is_some();
+            }
         }
 
         debug!("Applying Voice FX");
-        self.apply_voice_fx()?;
+        let previous = current
+            .as_ref()
+            .and_then(|current| current.voice_fx.as_ref());
+        self.apply_voice_fx(previous)?;
+        self.preempt(&mut current).await;
 
         // Drop this to the end so it doesn't directly interfere with profile loading..
         debug!("Validating Sampler Configuration..");
@@ -3580,13 +7169,17 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    fn get_effect_value(&self, effect: EffectKey) -> i32 {
+        match effect {
+            EffectKey::MicInputMute if self.privacy_locked() => 1,
+            _ => self.mic_profile.get_effect_value(effect, self.profile()),
+        }
+    }
+
     fn apply_effects(&mut self, params: LinkedHashSet<EffectKey>) -> Result<()> {
         let mut vec = Vec::new();
         for effect in params {
-            vec.push((
-                effect,
-                self.mic_profile.get_effect_value(effect, self.profile()),
-            ));
+            vec.push((effect, self.get_effect_value(effect)));
         }
 
         for effect in &vec {
@@ -3597,29 +7190,188 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
-    fn apply_voice_fx(&mut self) -> Result<()> {
+    fn get_voice_fx(&self) -> Option<VoiceFxState> {
         if self.is_device_mini() {
             // Voice FX aren't present on the mini.
-            return Ok(());
+            return None;
         }
 
         // Grab all keys that aren't common between devices
         let fx_keys = self.mic_profile.get_fx_keys(self.profile.use_echo_tempo());
+        let effects = fx_keys
+            .into_iter()
+            .map(|key| (key, self.get_effect_value(key)))
+            .collect();
+
+        let pitch_mode = (
+            self.profile.get_pitch_mode(),
+            self.profile.get_pitch_resolution(),
+        );
+        let encoders = EnumMap::from_fn(|encoder| match encoder {
+            EncoderName::Pitch => self.profile.get_pitch_encoder_position(),
+            EncoderName::Gender => self.profile.get_gender_value(),
+            EncoderName::Reverb => self.profile.get_reverb_value(),
+            EncoderName::Echo => self.profile.get_echo_value(),
+        });
+
+        Some(VoiceFxState {
+            effects,
+            pitch_mode,
+            encoders,
+        })
+    }
 
-        // Setup to send these keys..
-        let mut send_keys = LinkedHashSet::new();
-        send_keys.extend(fx_keys);
+    fn apply_voice_fx(&mut self, previous: Option<&VoiceFxState>) -> Result<()> {
+        let Some(voice_fx) = self.get_voice_fx() else {
+            return Ok(());
+        };
+        if previous == Some(&voice_fx) {
+            debug!("Voice FX unchanged, ignoring");
+            return Ok(());
+        }
 
-        // Apply these settings..
-        self.apply_effects(send_keys)?;
+        for (key, value) in &voice_fx.effects {
+            debug!("Setting {:?} to {}", key, value);
+        }
+        self.goxlr.set_effect_values(&voice_fx.effects)?;
 
         // Apply any Pitch / Encoder related Effects
-        self.set_pitch_mode()?;
-        self.load_encoder_effects()?;
+        let (mode, resolution) = voice_fx.pitch_mode;
+        self.goxlr
+            .set_encoder_mode(EncoderName::Pitch, mode, resolution)?;
+        for (encoder, value) in voice_fx.encoders {
+            self.goxlr.set_encoder_value(encoder, value)?;
+        }
+        Ok(())
+    }
+
+    /// Turns a relative adjustment into the Set command for the resulting value.
+    fn resolve_adjustment(&self, command: GoXLRCommand) -> GoXLRCommand {
+        let profile = &self.mic_profile;
+        match command {
+            GoXLRCommand::AdjustGateThreshold(delta) => GoXLRCommand::SetGateThreshold(
+                adjust_value(profile.gate_threshold(), delta, GATE_THRESHOLD_RANGE),
+            ),
+            GoXLRCommand::AdjustCompressorThreshold(delta) => {
+                GoXLRCommand::SetCompressorThreshold(adjust_value(
+                    profile.compressor_threshold(),
+                    delta,
+                    COMPRESSOR_THRESHOLD_RANGE,
+                ))
+            }
+            GoXLRCommand::AdjustCompressorMakeupGain(delta) => {
+                GoXLRCommand::SetCompressorMakeupGain(adjust_value(
+                    profile.compressor_makeup(),
+                    delta,
+                    COMPRESSOR_MAKEUP_RANGE,
+                ))
+            }
+            GoXLRCommand::AdjustDeEsser(delta) => {
+                let current = profile.get_deesser() as i8;
+                GoXLRCommand::SetDeeser(adjust_value(current, delta, DEESSER_RANGE) as u8)
+            }
+            GoXLRCommand::AdjustBleepVolume(delta) => GoXLRCommand::SetSwearButtonVolume(
+                adjust_value(profile.bleep_level(), delta, BLEEP_VOLUME_RANGE),
+            ),
+            command => command,
+        }
+    }
 
+    /// After a command has been run, returns anything the client should be told beyond it
+    /// succeeding (the value an adjustment ended up at, or a Mute Function which was reset).
+    pub fn take_command_response(&mut self, command: &GoXLRCommand) -> Option<DaemonResponse> {
+        let adjusted = match command {
+            GoXLRCommand::AdjustGateThreshold(_) => self.mic_profile.gate_threshold(),
+            GoXLRCommand::AdjustCompressorThreshold(_) => self.mic_profile.compressor_threshold(),
+            GoXLRCommand::AdjustCompressorMakeupGain(_) => self.mic_profile.compressor_makeup(),
+            GoXLRCommand::AdjustDeEsser(_) => self.mic_profile.get_deesser() as i8,
+            GoXLRCommand::AdjustBleepVolume(_) => self.mic_profile.bleep_level(),
+            GoXLRCommand::SetFader(..) => {
+                let (fader, function) = self.mute_function_reset.take()?;
+                return Some(DaemonResponse::MuteFunctionReset(fader, function));
+            }
+            _ => return None,
+        };
+        Some(DaemonResponse::AdjustedValue(adjusted))
+    }
+
+    /// The channels a command would change, for telling the holders of channel claims. This has
+    /// to be checked before the command is run, as it may move the channel off its fader.
+    pub fn channels_changed_by(&self, command: &GoXLRCommand) -> Vec<ChannelName> {
+        match command {
+            GoXLRCommand::SetVolume(channel, _)
+            | GoXLRCommand::SetChannelBalance(channel, _)
+            | GoXLRCommand::SetSubMixVolume(channel, _)
+            | GoXLRCommand::SetSubMixLinked(channel, _) => vec![*channel],
+            GoXLRCommand::SetFader(fader, channel) => {
+                let previous = self.profile.get_fader_assignment(*fader);
+                vec![previous, *channel]
+            }
+            GoXLRCommand::SetFaderMuteState(fader, _)
+            | GoXLRCommand::SetFaderMuteFunction(fader, _) => {
+                vec![self.profile.get_fader_assignment(*fader)]
+            }
+            GoXLRCommand::SetRouter(input, _, _) => vec![input_channel(*input)],
+            _ => vec![],
+        }
+    }
+
+    /// Some microphones (ribbons especially) can be damaged by 48V, so if the user has asked for
+    /// it, enabling phantom power has to be explicitly confirmed.
+    async fn check_phantom_confirmation(
+        &self,
+        mic_type: MicrophoneType,
+        confirmed: bool,
+    ) -> Result<()> {
+        let enabling =
+            mic_type.has_phantom_power() && !self.mic_profile.mic_type().has_phantom_power();
+
+        if enabling && !confirmed {
+            let serial = self.serial();
+            if self
+                .settings
+                .get_device_phantom_confirmation_required(serial)
+                .await
+            {
+                return Err(CommandError::PhantomPowerConfirmation.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the Mic type and gain, if this switches phantom power the Mic is briefly muted
+    /// to avoid the pop while the voltage settles (see update_phantom_settle).
+    async fn apply_mic_type_change(&mut self, previous: MicrophoneType) -> Result<()> {
+        let phantom = self.mic_profile.mic_type().has_phantom_power();
+        if phantom == previous.has_phantom_power() {
+            return self.apply_mic_gain();
+        }
+
+        self.write_channel_state(ChannelName::Mic, Muted)?;
+        self.apply_mic_gain()?;
+        self.phantom_settle = Some(Instant::now() + PHANTOM_SETTLE_TIME);
+
+        let message = match phantom {
+            true => String::from("Phantom power on"),
+            false => String::from("Phantom power off"),
+        };
+        self.announce(NotificationCategory::Microphone, message)
+            .await;
         Ok(())
     }
 
+    /// Once phantom power has settled, the Mic's mute state goes back to the profile's.
+    fn update_phantom_settle(&mut self) -> Result<bool> {
+        match self.phantom_settle {
+            Some(settled) if Instant::now() >= settled => {
+                self.phantom_settle = None;
+                self.write_channel_state(ChannelName::Mic, self.get_mic_channel_state())?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn apply_mic_gain(&mut self) -> Result<()> {
         let mic_type = self.mic_profile.mic_type();
         let gain = self.mic_profile.mic_gains()[mic_type];
@@ -3670,14 +7422,140 @@ impl<'a> Device<'a> {
     }
 
     async fn apply_scribble(&mut self, fader: FaderName) -> Result<()> {
+        match self.render_scribble(fader).await {
+            Some((image, number))
+                if self.scribbles[fader].keep_unchanged
+                    && self.scribbles[fader].image == Some(image) =>
+            {
+                debug!("Scribble for Fader {} unchanged, ignoring", fader);
+                let state = &mut self.scribbles[fader];
+                state.rendered = number;
+                state.pending = false;
+                state.keep_unchanged = false;
+                Ok(())
+            }
+            Some(scribble) => self.write_scribble(fader, scribble),
+            None => Ok(()),
+        }
+    }
+
+    // The image for a fader's scribble, along with the number drawn on it. If it can't be drawn,
+    // the device keeps showing what it already has, and the error is reported in the status.
+    async fn render_scribble(&mut self, fader: FaderName) -> Option<([u8; 1024], Option<String>)> {
         let icon_path = self.settings.get_icons_directory().await;
 
-        let scribble = self.profile.get_scribble_image(fader, &icon_path);
-        self.goxlr.set_fader_scribble(fader, scribble)?;
+        let number = self.get_scribble_number(fader);
+        let error = match self
+            .profile
+            .get_scribble_image(fader, &icon_path, number.clone())
+        {
+            Ok(image) => return Some((image, number)),
+            Err(error) => format!("{:#}", error),
+        };
+
+        let state = &self.scribbles[fader];
+        if state.error.as_ref().map(|error| &error.message) != Some(&error) {
+            warn!(
+                "[{}] Unable to draw the scribble for Fader {:?}: {}",
+                self.serial(),
+                fader,
+                error
+            );
+        }
+
+        let state = &mut self.scribbles[fader];
+        state.pending = false;
+        state.error = Some(ScribbleError {
+            number,
+            message: error,
+        });
+        None
+    }
 
+    fn write_scribble(
+        &mut self,
+        fader: FaderName,
+        (image, number): ([u8; 1024], Option<String>),
+    ) -> Result<()> {
+        self.goxlr.set_fader_scribble(fader, image)?;
+
+        self.scribbles[fader] = ScribbleState {
+            rendered: number,
+            uploaded: Some(Instant::now()),
+            image: Some(image),
+            keep_unchanged: false,
+            pending: false,
+            error: None,
+        };
         Ok(())
     }
 
+    fn get_scribble_number(&self, fader: FaderName) -> Option<String> {
+        let volume = || {
+            let channel = self.profile.get_fader_assignment(fader);
+            self.profile.get_channel_volume(channel)
+        };
+
+        match self.profile.get_scribble_number_mode(fader) {
+            ScribbleNumberMode::Static(text) => (!text.is_empty()).then_some(text),
+            ScribbleNumberMode::VolumePercent => {
+                let percent = (volume() as f64 / 255. * 100.).round();
+                Some(format!("{}%", percent))
+            }
+            ScribbleNumberMode::VolumeDb => Some(volume_to_db(volume())),
+            ScribbleNumberMode::ActivePreset => {
+                let preset = self.profile.get_active_effect_bank();
+                Some(format!("{}", preset as u8 + 1))
+            }
+        }
+    }
+
+    // Draws the next pending scribble, and redraws any showing a live value which has changed
+    // since they were last drawn.
+    async fn update_scribbles(&mut self) -> Result<bool> {
+        if self.is_device_mini() {
+            return Ok(false);
+        }
+
+        if let Some(fader) = FaderName::iter().find(|fader| self.scribbles[*fader].pending) {
+            self.apply_scribble(fader).await?;
+            return Ok(true);
+        }
+
+        let mut updated = false;
+        for fader in FaderName::iter() {
+            if !self.scribble_number_changed(fader) {
+                continue;
+            }
+
+            if let Some(uploaded) = self.scribbles[fader].uploaded {
+                if uploaded.elapsed() < SCRIBBLE_NUMBER_INTERVAL {
+                    continue;
+                }
+            }
+
+            self.apply_scribble(fader).await?;
+            updated = true;
+        }
+        Ok(updated)
+    }
+
+    // Whether a scribble showing a live value needs redrawing, a number which couldn't be drawn
+    // isn't tried again until it changes.
+    fn scribble_number_changed(&self, fader: FaderName) -> bool {
+        if let ScribbleNumberMode::Static(_) = self.profile.get_scribble_number_mode(fader) {
+            return false;
+        }
+
+        let number = self.get_scribble_number(fader);
+        let state = &self.scribbles[fader];
+        number != state.rendered
+            && !state
+                .error
+                .as_ref()
+                .is_some_and(|error| error.number == number)
+    }
+
     fn set_pitch_mode(&mut self) -> Result<()> {
         if self.is_device_mini() {
             // Not a Full GoXLR, nothing to do.
@@ -3698,82 +7576,241 @@ impl<'a> Device<'a> {
             return Ok(());
         }
 
-        let mut mix_a: [u8; 4] = [0x0c; 4];
-        let mut mix_b: [u8; 4] = [0x0c; 4];
-
-        let mut index = 0;
         let submix_enabled = self.profile.is_submix_enabled();
+        self.write_output_mixes()?;
 
-        // This is kinda awkward, but we'll run with it..
-        for device in BasicOutputDevice::iter() {
-            if device == BasicOutputDevice::Headphones {
-                // We need to make sure the monitor is on the right side..
-                if submix_enabled {
-                    let mix = self.profile.get_submix_channel(device);
-                    self.goxlr.set_monitored_mix(mix)?;
-                } else {
-                    self.goxlr.set_monitored_mix(Mix::A)?;
-                }
-
-                // Monitor Mix handled, move to the next channel
-                continue;
-            }
-            if submix_enabled {
-                // We need to place this on the correct mix..
-                match self.profile.get_submix_channel(device) {
-                    Mix::A => mix_a[index] = (device as u8) * 2,
-                    Mix::B => mix_b[index] = (device as u8) * 2,
-                }
-            } else {
-                // Force this channel to A..
-                mix_a[index] = (device as u8) * 2;
-            }
-            index += 1;
+        if submix_enabled && apply_volumes {
+            let volumes = self.get_submix_load_volumes(submix_enabled);
+            self.write_submix_volumes(volumes, None)?;
         }
 
-        let submix = [mix_a, mix_b].concat();
+        let volume = self.get_mic_monitor_volume(submix_enabled);
+        self.goxlr.set_volume(ChannelName::MicMonitor, volume)?;
 
-        // This should always be successful, in theory :D
-        self.goxlr.set_channel_mixes(submix.try_into().unwrap())?;
+        Ok(())
+    }
 
-        if submix_enabled && apply_volumes {
-            for channel in ChannelName::iter() {
-                self.load_submix_volume(channel)?;
+    fn write_output_mixes(&mut self) -> Result<()> {
+        let mixes = self.get_output_mixes(self.profile.is_submix_enabled());
+
+        // We need to make sure the monitor is on the right side..
+        self.goxlr
+            .set_monitored_mix(mixes[BasicOutputDevice::Headphones])?;
+        self.write_channel_mixes(&mixes)
+    }
+
+    /// The mix each output is on, the Headphones' being the monitored mix.
+    fn get_output_mixes(&self, submix_enabled: bool) -> EnumMap<BasicOutputDevice, Mix> {
+        let mut mixes: EnumMap<BasicOutputDevice, Mix> = EnumMap::default();
+        if submix_enabled {
+            for device in BasicOutputDevice::iter() {
+                mixes[device] = self.profile.get_submix_channel(device);
+            }
+        }
+        mixes
+    }
+
+    fn write_channel_mixes(&mut self, mixes: &EnumMap<BasicOutputDevice, Mix>) -> Result<()> {
+        let mut mix_a: [u8; 4] = [0x0c; 4];
+        let mut mix_b: [u8; 4] = [0x0c; 4];
+
+        // The monitored mix is sent on its own, so the Headphones aren't placed here
+        let devices = BasicOutputDevice::iter().filter(|d| *d != BasicOutputDevice::Headphones);
+        for (index, device) in devices.enumerate() {
+            match mixes[device] {
+                Mix::A => mix_a[index] = (device as u8) * 2,
+                Mix::B => mix_b[index] = (device as u8) * 2,
             }
         }
 
-        // If submixes are enabled, the Mic Monitor should be at 100% as monitoring
-        // is supposed to be handled by the mix.
+        let submix = [mix_a, mix_b].concat();
+
+        // This should always be successful, in theory :D
+        self.goxlr.set_channel_mixes(submix.try_into().unwrap())?;
+        Ok(())
+    }
+
+    // If submixes are enabled, the Mic Monitor should be at 100% as monitoring
+    // is supposed to be handled by the mix.
+    fn get_mic_monitor_volume(&self, submix_enabled: bool) -> u8 {
         if submix_enabled {
-            self.goxlr.set_volume(ChannelName::MicMonitor, 255)?;
+            255
         } else {
-            let volume = self.profile.get_channel_volume(ChannelName::MicMonitor);
-            self.goxlr.set_volume(ChannelName::MicMonitor, volume)?;
+            self.profile.get_channel_volume(ChannelName::MicMonitor)
         }
+    }
 
-        Ok(())
+    /// The volume each channel is given on the device, the Mic Monitor being held at 100% while
+    /// submixes are enabled.
+    fn get_load_volumes(&self) -> EnumMap<ChannelName, u8> {
+        let mut volumes = self.profile.get_channel_volume_map();
+        if self.device_supports_submixes() {
+            let submix_enabled = self.profile.is_submix_enabled();
+            volumes[ChannelName::MicMonitor] = self.get_mic_monitor_volume(submix_enabled);
+        }
+        volumes
+    }
+
+    fn get_submix_load_volumes(
+        &self,
+        submix_enabled: bool,
+    ) -> EnumMap<SubMixChannelName, Option<u8>> {
+        let mut volumes: EnumMap<SubMixChannelName, Option<u8>> = EnumMap::default();
+        if submix_enabled {
+            for channel in ChannelName::iter() {
+                if let Some((mix, volume)) = self.get_submix_load_volume(channel) {
+                    volumes[mix] = Some(volume);
+                }
+            }
+        }
+        volumes
     }
 
-    fn load_submix_volume(&mut self, channel: ChannelName) -> Result<()> {
+    fn get_submix_load_volume(&self, channel: ChannelName) -> Option<(SubMixChannelName, u8)> {
         if let Some(mix) = self.profile.get_submix_from_channel(channel) {
             let volume = if self.profile.is_channel_linked(mix) {
                 let volume = self.profile.get_channel_volume(channel);
                 let ratio = self.profile.get_submix_ratio(mix);
 
-                let calced = (volume as f64 * ratio) as u8;
+                (volume as f64 * ratio) as u8
+            } else {
+                self.profile.get_submix_volume(mix)
+            };
+
+            return Some((mix, volume));
+        }
+        None
+    }
 
-                if self.profile.get_submix_volume(mix) != calced {
+    fn write_submix_volumes(
+        &mut self,
+        volumes: EnumMap<SubMixChannelName, Option<u8>>,
+        previous: Option<EnumMap<SubMixChannelName, Option<u8>>>,
+    ) -> Result<()> {
+        // The volumes are gathered up first, then flushed together so the mixes don't visibly
+        // stagger, and so a mix is never written more than once in a pass. Mixes the device
+        // already has at the same volume aren't written at all.
+        for (mix, volume) in volumes {
+            if let Some(volume) = volume {
+                if self.profile.get_submix_volume(mix) != volume {
                     warn!("Channel {} Sub Volume not synced, fixing..", mix);
-                    self.profile.set_submix_volume(mix, calced);
+                    self.profile.set_submix_volume(mix, volume);
                 }
 
-                calced
-            } else {
-                self.profile.get_submix_volume(mix)
-            };
+                if previous.is_some_and(|previous| previous[mix] == Some(volume)) {
+                    continue;
+                }
 
-            debug!("Setting Sub Mix volume for {} to {}", mix, volume);
-            self.goxlr.set_sub_volume(mix, volume)?;
+                debug!("Setting Sub Mix volume for {} to {}", mix, volume);
+                self.goxlr.set_sub_volume(mix, volume)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_mix_layout(&self) -> MixLayout {
+        MixLayout {
+            stream_no_music: self.is_steam_no_music().await,
+            submix_enabled: self.profile.is_submix_enabled(),
+            monitor: self.profile.get_monitoring_mix(),
+        }
+    }
+
+    async fn get_mix_state(&self, layout: &MixLayout) -> Result<MixState> {
+        let mut routing: EnumMap<BasicInputDevice, EnumMap<BasicOutputDevice, bool>> =
+            EnumMap::default();
+        for input in BasicInputDevice::iter() {
+            routing[input] = self.get_layout_routing(input, layout).await?;
+        }
+
+        if !self.device_supports_submixes() {
+            return Ok(MixState {
+                routing,
+                mixes: None,
+                submix_volumes: EnumMap::default(),
+                mic_monitor: None,
+            });
+        }
+
+        let submix_enabled = layout.submix_enabled;
+        Ok(MixState {
+            routing,
+            mixes: Some(self.get_output_mixes(submix_enabled)),
+            submix_volumes: self.get_submix_load_volumes(submix_enabled),
+            mic_monitor: Some(self.get_mic_monitor_volume(submix_enabled)),
+        })
+    }
+
+    /// The states before and after a mode change, both worked out from how things are now.
+    async fn plan_mode_change(&self, change: ModeChange) -> Result<(MixState, MixState)> {
+        let layout = self.get_mix_layout().await;
+        let target = layout.with(change, self.is_device_mini());
+
+        let before = self.get_mix_state(&layout).await?;
+        let after = self.get_mix_state(&target).await?;
+        Ok((before, after))
+    }
+
+    pub async fn preview_mode_change(&self, change: ModeChange) -> Result<ModeChangePreview> {
+        if let Some(feature) = self.missing_feature(&mode_change_command(change)) {
+            return Err(CommandError::NotSupported(feature).into());
+        }
+
+        let (before, after) = self.plan_mode_change(change).await?;
+        Ok(diff_mix_state(&before, &after))
+    }
+
+    async fn apply_mode_change(&mut self, change: ModeChange) -> Result<()> {
+        // This is planned before anything changes, so it's exactly what a preview would show
+        let (before, after) = self.plan_mode_change(change).await?;
+
+        match change {
+            ModeChange::Vod(mode) => {
+                let serial = self.serial();
+                if self.settings.get_device_vod_mode(serial).await == mode {
+                    return Ok(());
+                }
+                self.settings.set_device_vod_mode(serial, mode).await;
+            }
+            ModeChange::SubMix(enabled) => {
+                if self.profile.is_submix_enabled() == enabled {
+                    return Ok(());
+                }
+                if !enabled {
+                    // Submixes are being disabled, we need to revert the monitor..
+                    self.profile
+                        .set_monitor_mix(BasicOutputDevice::Headphones)?;
+                }
+                self.profile.set_submix_enabled(enabled)?;
+            }
+        }
+
+        self.write_mix_state(&before, &after)?;
+        self.apply_submix_link_defaults().await
+    }
+
+    // Sends only what differs between the two states, in a single pass.
+    fn write_mix_state(&mut self, before: &MixState, after: &MixState) -> Result<()> {
+        for input in BasicInputDevice::iter() {
+            if before.routing[input] != after.routing[input] {
+                self.apply_channel_routing(input, after.routing[input])?;
+            }
+        }
+
+        if let (Some(from), Some(to)) = (&before.mixes, &after.mixes) {
+            let headphones = BasicOutputDevice::Headphones;
+            if from[headphones] != to[headphones] {
+                self.goxlr.set_monitored_mix(to[headphones])?;
+            }
+            if BasicOutputDevice::iter().any(|d| d != headphones && from[d] != to[d]) {
+                self.write_channel_mixes(to)?;
+            }
+        }
+
+        self.write_submix_volumes(after.submix_volumes, Some(before.submix_volumes))?;
+
+        if let Some(volume) = after.mic_monitor.filter(|v| before.mic_monitor != Some(*v)) {
+            self.goxlr.set_volume(ChannelName::MicMonitor, volume)?;
         }
         Ok(())
     }
@@ -3788,8 +7825,7 @@ impl<'a> Device<'a> {
                 if self.profile.get_channel_volume(channel) != linked_volume {
                     // Setup the latch..
                     if let Some(fader) = self.profile.get_fader_from_channel(channel) {
-                        self.fader_pause_until[fader].paused = true;
-                        self.fader_pause_until[fader].until = linked_volume;
+                        self.fader_volume_set(fader, linked_volume);
                     }
                     self.profile.set_channel_volume(channel, linked_volume)?;
                     self.goxlr.set_volume(channel, linked_volume)?;
@@ -3805,6 +7841,28 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    // Channels the profile doesn't have a link setting for follow the device's defaults, once
+    // one is applied it's part of the profile, so later changes to the default don't affect it.
+    async fn apply_submix_link_defaults(&mut self) -> Result<()> {
+        if !self.device_supports_submixes() || !self.profile.is_submix_enabled() {
+            return Ok(());
+        }
+
+        let defaults = self
+            .settings
+            .get_device_submix_default_linked(self.serial())
+            .await;
+        for (mix, linked) in defaults {
+            if self.profile.is_submix_link_set(mix) || self.profile.is_channel_linked(mix) == linked
+            {
+                continue;
+            }
+            debug!("Applying default Submix link for {}: {}", mix, linked);
+            self.link_submix_channel(submix_to_channel_name(mix), linked)?;
+        }
+        Ok(())
+    }
+
     fn link_submix_channel(&mut self, channel: ChannelName, linked: bool) -> Result<()> {
         if let Some(mix) = self.profile.get_submix_from_channel(channel) {
             if !linked {
@@ -3833,6 +7891,15 @@ impl<'a> Device<'a> {
         self.hardware.device_type == DeviceType::Mini
     }
 
+    // The first thing a command needs that this device doesn't have, if any
+    fn missing_feature(&self, command: &GoXLRCommand) -> Option<DeviceFeature> {
+        let capabilities = &self.hardware.capabilities;
+        required_features(command)
+            .iter()
+            .copied()
+            .find(|feature| !capabilities.has(*feature))
+    }
+
     fn needs_submix_correction(&self, channel: ChannelName) -> bool {
         // Don't need correction if device doesn't support sub mixes!
         if !self.device_supports_submixes() {
@@ -3888,6 +7955,92 @@ impl<'a> Device<'a> {
         self.hardware.device_type == DeviceType::Mini
             && self.settings.get_device_vod_mode(self.serial()).await == VodMode::StreamNoMusic
     }
+
+    /// While the VOD mix follows the Broadcast Mix, whether an input is kept off it anyway.
+    async fn is_excluded_from_vod(&self, input: BasicInputDevice) -> bool {
+        match input {
+            BasicInputDevice::Music => true,
+            BasicInputDevice::Samples => !self.settings.get_device_vod_samples(self.serial()).await,
+            _ => false,
+        }
+    }
+
+    /// The profile's routing, except where the VOD mix is being driven by the Broadcast Mix, in
+    /// which case we report what's actually being sent to it.
+    async fn get_router_status(
+        &self,
+    ) -> EnumMap<BasicInputDevice, EnumMap<BasicOutputDevice, bool>> {
+        let mut router = self.profile.create_router();
+        if self.is_steam_no_music().await {
+            for (input, outputs) in router.iter_mut() {
+                outputs[BasicOutputDevice::Sampler] = !self.is_excluded_from_vod(input).await
+                    && outputs[BasicOutputDevice::BroadcastMix];
+            }
+        }
+        router
+    }
+
+    async fn get_vod_output(&self) -> Option<VodOutput> {
+        if self.hardware.device_type != DeviceType::Mini {
+            return None;
+        }
+
+        let routable = !self.is_steam_no_music().await;
+        let routable_inputs = match routable {
+            true => BasicInputDevice::iter().collect(),
+            false => vec![BasicInputDevice::Samples],
+        };
+
+        Some(VodOutput {
+            output: BasicOutputDevice::Sampler,
+            label: String::from("VOD"),
+            routable,
+            routable_inputs,
+        })
+    }
+}
+
+// The variant name of a command, without the (potentially large) values it carries.
+// Where between the base (0) and peak (1) colours a Mic level (in dB) falls
+fn reactive_position(level: f64, sensitivity: u8) -> f64 {
+    let peak = -(sensitivity as f64) * 60. / 100.;
+    ((level - peak) / REACTIVE_RANGE + 1.).clamp(0., 1.)
+}
+
+fn blend_colours(base: [u8; 4], peak: [u8; 4], position: f64) -> [u8; 4] {
+    std::array::from_fn(|i| {
+        let (base, peak) = (base[i] as f64, peak[i] as f64);
+        (base + (peak - base) * position).round() as u8
+    })
+}
+
+// The colour map holds colours as BGRA
+fn colour_map_bytes(colour: &str) -> Result<[u8; 4]> {
+    let hex = parse_colour(colour)?;
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    Ok([channel(4)?, channel(2)?, channel(0)?, 255])
+}
+
+fn get_command_name(command: &GoXLRCommand) -> String {
+    let debug = format!("{:?}", command);
+    match debug.split_once(['(', ' ']) {
+        Some((name, _)) => name.to_string(),
+        None => debug,
+    }
+}
+
+fn adjust_value(value: i8, delta: i8, range: RangeInclusive<i8>) -> i8 {
+    value
+        .saturating_add(delta)
+        .clamp(*range.start(), *range.end())
+}
+
+// The volume as a gain relative to full, which is how the faders are scaled.
+fn volume_to_db(volume: u8) -> String {
+    if volume == 0 {
+        return String::from("-inf");
+    }
+    format!("{:.1}dB", 20. * (volume as f64 / 255.).log10())
 }
 
 fn tts_bool_to_state(bool: bool) -> String {
@@ -3906,3 +8059,3492 @@ fn tts_target(target: MuteFunction) -> String {
         MuteFunction::ToLineOut => " to Line Out".to_string(),
     }
 }
+
+fn fader_mute_button(fader: FaderName) -> Buttons {
+    match fader {
+        FaderName::A => Buttons::Fader1Mute,
+        FaderName::B => Buttons::Fader2Mute,
+        FaderName::C => Buttons::Fader3Mute,
+        FaderName::D => Buttons::Fader4Mute,
+    }
+}
+
+fn sample_button_to_button(button: SampleButtons) -> Buttons {
+    match button {
+        SampleButtons::TopLeft => Buttons::SamplerTopLeft,
+        SampleButtons::TopRight => Buttons::SamplerTopRight,
+        SampleButtons::BottomLeft => Buttons::SamplerBottomLeft,
+        SampleButtons::BottomRight => Buttons::SamplerBottomRight,
+    }
+}
+
+// The routing value for a full strength route
+const ROUTE_LEVEL: u8 = 0x20;
+
+/// The left and right routing values for an input's balance, moving towards one side turns the
+/// other side down, so the centre is unchanged.
+fn balance_levels(balance: i8) -> (u8, u8) {
+    let level = |towards_other_side: i8| {
+        let kept = (BALANCE_LIMIT - towards_other_side.clamp(0, BALANCE_LIMIT)) as u16;
+        let limit = BALANCE_LIMIT as u16;
+        ((ROUTE_LEVEL as u16 * kept + limit / 2) / limit) as u8
+    };
+    (level(balance), level(balance.saturating_neg()))
+}
+
+fn effect_button(effect: EffectName) -> Buttons {
+    match effect {
+        EffectName::Megaphone => Buttons::EffectMegaphone,
+        EffectName::Robot => Buttons::EffectRobot,
+        EffectName::HardTune => Buttons::EffectHardTune,
+        EffectName::Fx => Buttons::EffectFx,
+    }
+}
+
+fn fader_for_mute_button(button: Buttons) -> Option<FaderName> {
+    FaderName::iter().find(|fader| fader_mute_button(*fader) == button)
+}
+
+fn high_contrast_group(button: Button) -> HighContrastGroup {
+    match button {
+        Button::Fader1Mute | Button::Fader2Mute | Button::Fader3Mute | Button::Fader4Mute => {
+            HighContrastGroup::FaderMute
+        }
+        Button::Cough => HighContrastGroup::Cough,
+        Button::Bleep => HighContrastGroup::Bleep,
+        Button::EffectSelect1
+        | Button::EffectSelect2
+        | Button::EffectSelect3
+        | Button::EffectSelect4
+        | Button::EffectSelect5
+        | Button::EffectSelect6 => HighContrastGroup::EffectSelector,
+        Button::EffectFx
+        | Button::EffectMegaphone
+        | Button::EffectRobot
+        | Button::EffectHardTune => HighContrastGroup::EffectTypes,
+        Button::SamplerSelectA | Button::SamplerSelectB | Button::SamplerSelectC => {
+            HighContrastGroup::SamplerSelector
+        }
+        Button::SamplerTopLeft
+        | Button::SamplerTopRight
+        | Button::SamplerBottomLeft
+        | Button::SamplerBottomRight
+        | Button::SamplerClear => HighContrastGroup::Sampler,
+    }
+}
+
+fn high_contrast_levels(config: &HighContrastConfig, button: Button) -> HighContrastLevels {
+    let group = high_contrast_group(button);
+    config.groups.get(&group).copied().unwrap_or(config.levels)
+}
+
+fn check_high_contrast(config: &HighContrastConfig) -> Result<()> {
+    let levels = iter::once(&config.levels).chain(config.groups.values());
+    for level in levels {
+        if level.active > 100 || level.inactive > 100 {
+            bail!("High Contrast levels must be between 0 and 100");
+        }
+    }
+    Ok(())
+}
+
+// Scales a colour (as sent, BGRA) to a percentage of its brightness
+fn scale_colour(colour: [u8; 4], percent: u8) -> [u8; 4] {
+    let scale = |value: u8| (value as u16 * percent as u16 / 100) as u8;
+    [
+        scale(colour[0]),
+        scale(colour[1]),
+        scale(colour[2]),
+        colour[3],
+    ]
+}
+
+// The channel whose fader, mute button and volume control an input
+fn input_channel(input: BasicInputDevice) -> ChannelName {
+    match input {
+        BasicInputDevice::Microphone => ChannelName::Mic,
+        BasicInputDevice::Chat => ChannelName::Chat,
+        BasicInputDevice::Music => ChannelName::Music,
+        BasicInputDevice::Game => ChannelName::Game,
+        BasicInputDevice::Console => ChannelName::Console,
+        BasicInputDevice::LineIn => ChannelName::LineIn,
+        BasicInputDevice::System => ChannelName::System,
+        BasicInputDevice::Samples => ChannelName::Sample,
+    }
+}
+
+/// Describes the action of any command which creates, modifies or removes files (other than the
+/// settings, which are handled by the SettingsHandle), these are refused in read-only mode.
+fn get_file_write_action(command: &GoXLRCommand) -> Option<&'static str> {
+    match command {
+        GoXLRCommand::SaveActivePreset() => Some("save the preset"),
+        GoXLRCommand::SaveEffectSection(_, _) => Some("save the effect"),
+        GoXLRCommand::NewProfile(_) => Some("create a profile"),
+        GoXLRCommand::SaveProfile() | GoXLRCommand::SaveProfileAs(_) => Some("save the profile"),
+        GoXLRCommand::DeleteProfile(_) => Some("delete a profile"),
+        GoXLRCommand::RenameProfile(_, _) => Some("rename a profile"),
+        GoXLRCommand::NewMicProfile(_) => Some("create a mic profile"),
+        GoXLRCommand::SaveMicProfile() | GoXLRCommand::SaveMicProfileAs(_) => {
+            Some("save the mic profile")
+        }
+        GoXLRCommand::DeleteMicProfile(_) => Some("delete a mic profile"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use goxlr_ipc::FirmwareUpdateState::*;
+    use goxlr_ipc::{
+        DuckConfig, EncoderState, MixChange, NameProblem, PrivacyConfig, RoutingChange, Scribble,
+    };
+    use goxlr_profile_loader::components::mixer::FullChannelList;
+    use goxlr_profile_loader::profile::Profile;
+    use goxlr_profile_loader::{BaseSection, Faders};
+    use goxlr_types::colours::InvalidColour;
+    use goxlr_types::{
+        ButtonColourOffStyle, EchoStyle, EqFrequencies, Mix, PitchStyle, ProfileSection,
+        ReverbStyle, SimpleColourTargets,
+    };
+    use goxlr_usb::commands::Command;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::files::get_effect_section_extension;
+    use crate::mock_device::{
+        full, mini, settings, writable_settings, Inputs, MockGoXLR, Requests,
+    };
+    use crate::polling::IDLE_AFTER;
+    use crate::settings::{ReadOnlyError, WritePolicy};
+
+    const SUBMIX_FIRMWARE: VersionNumber = VersionNumber(1, 2, Some(0), Some(46));
+    const FULL_FIRMWARE: VersionNumber = VersionNumber(1, 4, Some(2), Some(107));
+
+    async fn run(device: &mut Device<'_>, command: GoXLRCommand) {
+        device.perform_command(command).await.unwrap();
+    }
+
+    // A device on a MockGoXLR, with read-only settings in a new directory (which is removed when
+    // the returned TempDir is dropped, so keep it for the length of the test)
+    async fn new_device(hardware: HardwareStatus) -> (Device<'static>, Requests, TempDir) {
+        let root = tempfile::tempdir().unwrap();
+        let settings = Box::leak(Box::new(settings(root.path()).await));
+        let (events, _) = mpsc::channel(64);
+        let (goxlr, requests) = MockGoXLR::new();
+        let device = Device::new(goxlr, hardware, settings, events)
+            .await
+            .unwrap();
+        (device, requests, root)
+    }
+
+    // The mixes sent to the monitor, and how many submix volumes were sent, since last checked
+    fn take_monitor_writes(requests: &Requests) -> (Vec<u8>, usize) {
+        let mut requests = requests.lock().unwrap();
+        let monitored = requests
+            .iter()
+            .filter(|(command, _)| *command == Command::SetMonitoredMix)
+            .map(|(_, body)| body[0])
+            .collect();
+        let volumes = requests
+            .iter()
+            .filter(|(command, _)| matches!(command, Command::SetSubChannelVolume(_)))
+            .count();
+        requests.clear();
+        (monitored, volumes)
+    }
+
+    async fn monitored_mix(device: &Device<'_>) -> Option<Mix> {
+        let status = device.status().await;
+        status.levels.submix.map(|submix| submix.monitored_mix)
+    }
+
+    #[tokio::test]
+    async fn monitor_follows_the_monitored_outputs_mix() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        let stream = BasicOutputDevice::BroadcastMix;
+        device
+            .perform_command(GoXLRCommand::SetSubMixEnabled(true))
+            .await
+            .unwrap();
+        device
+            .perform_command(GoXLRCommand::SetSubMixOutputMix(stream, Mix::B))
+            .await
+            .unwrap();
+        take_monitor_writes(&requests);
+
+        // Monitoring the Stream moves the Headphones to Mix B, with its volumes
+        device
+            .perform_command(GoXLRCommand::SetMonitorMix(stream))
+            .await
+            .unwrap();
+        let submixes = SubMixChannelName::iter().count();
+        assert_eq!(
+            take_monitor_writes(&requests),
+            (vec![Mix::B as u8], submixes)
+        );
+        assert_eq!(monitored_mix(&device).await, Some(Mix::B));
+
+        // As does moving the Stream while it's being monitored
+        device
+            .perform_command(GoXLRCommand::SetSubMixOutputMix(stream, Mix::A))
+            .await
+            .unwrap();
+        assert_eq!(
+            take_monitor_writes(&requests),
+            (vec![Mix::A as u8], submixes)
+        );
+        assert_eq!(monitored_mix(&device).await, Some(Mix::A));
+
+        // The Headphones' own mix is kept for later, and doesn't change what's heard
+        device
+            .perform_command(GoXLRCommand::SetSubMixOutputMix(
+                BasicOutputDevice::Headphones,
+                Mix::B,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(take_monitor_writes(&requests), (vec![Mix::A as u8], 0));
+        assert_eq!(monitored_mix(&device).await, Some(Mix::A));
+
+        device
+            .perform_command(GoXLRCommand::SetMonitorMix(BasicOutputDevice::Headphones))
+            .await
+            .unwrap();
+        assert_eq!(
+            take_monitor_writes(&requests),
+            (vec![Mix::B as u8], submixes)
+        );
+        assert_eq!(monitored_mix(&device).await, Some(Mix::B));
+    }
+
+    async fn submix(device: &Device<'_>, mix: SubMixChannelName) -> (bool, f64) {
+        let status = device.status().await;
+        let submix = &status.levels.submix.unwrap().inputs[mix];
+        (submix.linked, submix.ratio)
+    }
+
+    #[tokio::test]
+    async fn submix_links_follow_the_defaults_until_the_profile_sets_them() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        // Nothing happens until submixes are enabled
+        let music = SubMixChannelName::Music;
+        run(
+            &mut device,
+            GoXLRCommand::SetSubMixDefaultLinked(ChannelName::Music, false),
+        )
+        .await;
+        assert!(device.profile.is_channel_linked(music));
+        assert!(device
+            .perform_command(GoXLRCommand::SetSubMixDefaultLinked(
+                ChannelName::Headphones,
+                false
+            ))
+            .await
+            .is_err());
+
+        run(&mut device, GoXLRCommand::SetSubMixEnabled(true)).await;
+        assert!(!submix(&device, music).await.0);
+        assert!(submix(&device, SubMixChannelName::Chat).await.0);
+
+        // Once applied it belongs to the profile, so a new default doesn't change it
+        run(
+            &mut device,
+            GoXLRCommand::SetSubMixDefaultLinked(ChannelName::Music, true),
+        )
+        .await;
+        assert!(!submix(&device, music).await.0);
+
+        // Re-linking everything keeps the volumes, taking the ratio from where they are
+        run(
+            &mut device,
+            GoXLRCommand::SetVolume(ChannelName::Music, 200),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetSubMixVolume(ChannelName::Music, 100),
+        )
+        .await;
+        requests.lock().unwrap().clear();
+
+        run(&mut device, GoXLRCommand::SetSubMixLinkAll(true)).await;
+        let (linked, ratio) = submix(&device, music).await;
+        assert!(linked);
+        assert_eq!(ratio, 0.5);
+        assert!(SubMixChannelName::iter().all(|mix| device.profile.is_submix_link_set(mix)));
+        assert!(
+            !requests.lock().unwrap().iter().any(|(command, _)| matches!(
+                command,
+                Command::SetSubChannelVolume(_) | Command::SetChannelVolume(_)
+            ))
+        );
+
+        run(&mut device, GoXLRCommand::SetSubMixLinkAll(false)).await;
+        assert!(SubMixChannelName::iter().all(|mix| !device.profile.is_channel_linked(mix)));
+    }
+
+    // Whether the last routing written for the Samples reaches the Sampler (VOD) output
+    fn samples_written_to_vod(requests: &Requests) -> Option<bool> {
+        let (samples, _) = InputDevice::from_basic(&BasicInputDevice::Samples);
+        let (vod, _) = OutputDevice::from_basic(&BasicOutputDevice::Sampler);
+
+        let requests = requests.lock().unwrap();
+        requests
+            .iter()
+            .rev()
+            .find(|(command, _)| *command == Command::SetRouting(samples))
+            .map(|(_, body)| body[vod.position()] != 0)
+    }
+
+    #[tokio::test]
+    async fn scribble_glyphs_replace_the_icon() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let fader = FaderName::A;
+        let sent_scribble = || {
+            let requests = requests.lock().unwrap();
+            let scribbles = requests
+                .iter()
+                .filter(|(command, _)| *command == Command::SetScribble(fader));
+            scribbles.last().map(|(_, body)| body.clone())
+        };
+        let glyph = |icon: Option<Scribble>| icon.and_then(|scribble| scribble.glyph);
+
+        run(&mut device, GoXLRCommand::SetScribbleIcon(fader, None)).await;
+        let blank = sent_scribble().unwrap();
+
+        // The bundled font has the basic characters, so this doesn't depend on the system
+        let command = GoXLRCommand::SetScribbleGlyph(fader, String::from(" A\u{FE0F} "));
+        run(&mut device, command).await;
+        let scribble = device.status().await.fader_status[fader].scribble.clone();
+        assert_eq!(glyph(scribble.clone()), Some(String::from("A\u{FE0F}")));
+        assert_eq!(scribble.unwrap().file_name, None);
+        assert_ne!(sent_scribble().unwrap(), blank);
+
+        // Anything which can't be drawn is refused, leaving the glyph as it was
+        let command = GoXLRCommand::SetScribbleGlyph(fader, String::from("Far Too Long"));
+        assert!(device.perform_command(command).await.is_err());
+        let scribble = device.status().await.fader_status[fader].scribble.clone();
+        assert_eq!(glyph(scribble), Some(String::from("A\u{FE0F}")));
+
+        // Choosing an icon file (or no icon) removes it
+        run(&mut device, GoXLRCommand::SetScribbleIcon(fader, None)).await;
+        let scribble = device.status().await.fader_status[fader].scribble.clone();
+        assert_eq!(glyph(scribble), None);
+        assert_eq!(sent_scribble().unwrap(), blank);
+    }
+
+    #[tokio::test]
+    async fn scribbles_which_cant_be_drawn_leave_the_display_alone() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let settings = device.settings;
+
+        // Something which claims to be a PNG, but isn't
+        let icons = settings.get_icons_directory().await;
+        std::fs::create_dir_all(&icons).unwrap();
+        let mut corrupt = b"\x89PNG\r\n\x1a\n".to_vec();
+        corrupt.extend_from_slice(&[0xAB; 64]);
+        std::fs::write(icons.join("corrupt.png"), corrupt).unwrap();
+
+        let fader = FaderName::A;
+        let sent_scribbles = || {
+            let requests = requests.lock().unwrap();
+            let scribbles = requests
+                .iter()
+                .filter(|(command, _)| *command == Command::SetScribble(fader));
+            scribbles.map(|(_, body)| body.clone()).collect::<Vec<_>>()
+        };
+        let last_error = |device: &Device| {
+            let scribble = device.get_fader_state(fader).scribble;
+            scribble.and_then(|scribble| scribble.last_error)
+        };
+
+        run(
+            &mut device,
+            GoXLRCommand::SetScribbleText(fader, "Music".into()),
+        )
+        .await;
+        let drawn = sent_scribbles();
+        assert_eq!(last_error(&device), None);
+
+        // The icon is still set, but nothing is sent in place of the last scribble
+        for (icon, problem) in [
+            ("corrupt.png", "Unable to load"),
+            ("missing.png", "not found"),
+        ] {
+            let command = GoXLRCommand::SetScribbleIcon(fader, Some(icon.to_string()));
+            run(&mut device, command).await;
+            assert_eq!(sent_scribbles(), drawn);
+
+            let error = last_error(&device).unwrap();
+            assert!(error.contains(problem) && error.contains(icon), "{}", error);
+        }
+
+        run(&mut device, GoXLRCommand::SetScribbleIcon(fader, None)).await;
+        assert_eq!(sent_scribbles().len(), drawn.len() + 1);
+        assert_eq!(last_error(&device), None);
+    }
+
+    #[tokio::test]
+    async fn work_is_pending_until_the_device_catches_up() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, _inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let scribbles = |device: &Device| {
+            let work = device.pending_work();
+            let scribbles = work.into_iter().filter_map(|work| match work {
+                PendingWork::Scribble(fader) => Some(fader),
+                _ => None,
+            });
+            scribbles.collect::<Vec<_>>()
+        };
+
+        // Scribbles are drawn one per update
+        let faders = FaderName::iter().count();
+        for fader in FaderName::iter() {
+            device.scribbles[fader].pending = true;
+        }
+        assert_eq!(scribbles(&device), FaderName::iter().collect::<Vec<_>>());
+        device.update_state().await.unwrap();
+        assert_eq!(scribbles(&device).len(), faders - 1);
+        for _ in 1..faders {
+            device.update_state().await.unwrap();
+        }
+        assert!(scribbles(&device).is_empty());
+
+        device.identify().await.unwrap();
+        assert!(device.pending_work().contains(&PendingWork::Identify));
+    }
+
+    #[tokio::test]
+    async fn samples_can_be_kept_off_the_vod_mix() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        let (samples, vod) = (BasicInputDevice::Samples, BasicOutputDevice::Sampler);
+        let stream = BasicOutputDevice::BroadcastMix;
+        run(&mut device, GoXLRCommand::SetRouter(samples, stream, true)).await;
+        run(&mut device, GoXLRCommand::SetRouter(samples, vod, false)).await;
+
+        // Following the Broadcast Mix, Samples land on the VOD mix by default
+        requests.lock().unwrap().clear();
+        run(
+            &mut device,
+            GoXLRCommand::SetVodMode(VodMode::StreamNoMusic),
+        )
+        .await;
+        assert_eq!(samples_written_to_vod(&requests), Some(true));
+
+        // Only the Samples can be taken off it
+        let game = GoXLRCommand::SetRouter(BasicInputDevice::Game, vod, false);
+        assert!(device.perform_command(game).await.is_err());
+        run(&mut device, GoXLRCommand::SetRouter(samples, vod, false)).await;
+        assert_eq!(samples_written_to_vod(&requests), Some(false));
+        assert!(!device.status().await.router[samples][vod]);
+        assert!(device.status().await.router[samples][stream]);
+
+        // Muting the Samples to the Stream, then unmuting, mustn't bring the route back
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Sample),
+        )
+        .await;
+        let to_stream = goxlr_types::MuteFunction::ToStream;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteFunction(FaderName::A, to_stream),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToX),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted),
+        )
+        .await;
+        assert_eq!(samples_written_to_vod(&requests), Some(false));
+
+        // The profile's own route is used again once the VOD mix is routable, and the
+        // exclusion returns with the mode
+        run(&mut device, GoXLRCommand::SetVodMode(VodMode::Routable)).await;
+        assert_eq!(samples_written_to_vod(&requests), Some(false));
+        run(&mut device, GoXLRCommand::SetRouter(samples, vod, true)).await;
+        assert_eq!(samples_written_to_vod(&requests), Some(true));
+
+        run(
+            &mut device,
+            GoXLRCommand::SetVodMode(VodMode::StreamNoMusic),
+        )
+        .await;
+        assert_eq!(samples_written_to_vod(&requests), Some(false));
+        assert!(!device.status().await.router[samples][vod]);
+    }
+
+    #[tokio::test]
+    async fn consistency_check_finds_drift() {
+        let (mut device, _requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+        assert_eq!(device.find_inconsistencies().await, Vec::<String>::new());
+
+        // Commands keep the device in step with the profile
+        let all = goxlr_types::MuteFunction::All;
+        run(&mut device, GoXLRCommand::SetCoughMuteFunction(all)).await;
+        run(&mut device, GoXLRCommand::SetCoughIsHold(false)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted),
+        )
+        .await;
+        assert_eq!(device.find_inconsistencies().await, Vec::<String>::new());
+
+        // The profile changing without the device being told
+        device.profile.set_mute_chat_button_on(true);
+        let problems = device.find_inconsistencies().await;
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("Channel Mic is Unmuted"));
+        assert!(problems[1].starts_with("Button MicrophoneMute"));
+
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+        )
+        .await;
+        assert_eq!(device.find_inconsistencies().await, Vec::<String>::new());
+
+        // A colour map which wasn't updated
+        let target = ColourTargets::Scribble1;
+        let position = target.position(0, device.device_supports_animations());
+        if let Some(sent) = &mut device.sent.colour_map {
+            sent[position] = sent[position].wrapping_add(1);
+        }
+        assert_eq!(
+            device.find_inconsistencies().await,
+            vec![String::from("Colour Map is stale, differing in: Scribble1")]
+        );
+
+        // Identify owns the lighting while it runs, so that's not reported
+        device.identify().await.unwrap();
+        assert_eq!(device.find_inconsistencies().await, Vec::<String>::new());
+    }
+
+    #[tokio::test]
+    async fn monitor_stays_on_mix_a_without_submixes() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        let stream = BasicOutputDevice::BroadcastMix;
+        device
+            .perform_command(GoXLRCommand::SetSubMixEnabled(false))
+            .await
+            .unwrap();
+        device
+            .perform_command(GoXLRCommand::SetSubMixOutputMix(stream, Mix::B))
+            .await
+            .unwrap();
+        take_monitor_writes(&requests);
+
+        device
+            .perform_command(GoXLRCommand::SetMonitorMix(stream))
+            .await
+            .unwrap();
+        assert_eq!(take_monitor_writes(&requests), (vec![Mix::A as u8], 0));
+        assert_eq!(monitored_mix(&device).await, None);
+    }
+
+    // The routing rows, mix layouts, monitored mixes, submix volumes and Mic Monitor volumes
+    // sent since last checked
+    fn take_mode_writes(requests: &Requests) -> [usize; 5] {
+        let mut requests = requests.lock().unwrap();
+        let count = |matches: &dyn Fn(&Command) -> bool| {
+            requests
+                .iter()
+                .filter(|(command, _)| matches(command))
+                .count()
+        };
+        let writes = [
+            count(&|command| matches!(command, Command::SetRouting(_))),
+            count(&|command| *command == Command::SetChannelMixes),
+            count(&|command| *command == Command::SetMonitoredMix),
+            count(&|command| matches!(command, Command::SetSubChannelVolume(_))),
+            count(&|command| *command == Command::SetChannelVolume(ChannelName::MicMonitor)),
+        ];
+        requests.clear();
+        writes
+    }
+
+    // The same, for what a preview says will be sent
+    fn previewed_writes(preview: &ModeChangePreview) -> [usize; 5] {
+        let mut inputs: Vec<_> = preview.routing.iter().map(|change| change.input).collect();
+        inputs.dedup();
+
+        let headphones = BasicOutputDevice::Headphones;
+        let monitored = preview
+            .mixes
+            .iter()
+            .filter(|m| m.output == headphones)
+            .count();
+        let mixes = usize::from(preview.mixes.len() > monitored);
+
+        // Each input is routed as a left and right pair
+        [
+            inputs.len() * 2,
+            mixes,
+            monitored,
+            preview.submix_volumes.len(),
+            preview.volumes.len(),
+        ]
+    }
+
+    async fn change_mode(
+        device: &mut Device<'_>,
+        requests: &Requests,
+        change: ModeChange,
+    ) -> ModeChangePreview {
+        let preview = device.preview_mode_change(change).await.unwrap();
+        requests.lock().unwrap().clear();
+        run(device, mode_change_command(change)).await;
+
+        assert_eq!(take_mode_writes(requests), previewed_writes(&preview));
+        assert!(device.preview_mode_change(change).await.unwrap().is_empty());
+        preview
+    }
+
+    #[tokio::test]
+    async fn submix_changes_send_what_they_preview() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+        run(&mut device, GoXLRCommand::SetSubMixEnabled(false)).await;
+
+        // Enabling them brings in every submix volume
+        let preview = change_mode(&mut device, &requests, ModeChange::SubMix(true)).await;
+        assert_eq!(
+            preview.submix_volumes.len(),
+            SubMixChannelName::iter().count()
+        );
+        assert!(preview.routing.is_empty());
+
+        // Monitoring the Stream on Mix B, Game is only heard through the Stream
+        let (game, stream) = (BasicInputDevice::Game, BasicOutputDevice::BroadcastMix);
+        let headphones = BasicOutputDevice::Headphones;
+        run(&mut device, GoXLRCommand::SetRouter(game, stream, true)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetRouter(game, headphones, false),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetSubMixOutputMix(stream, Mix::B),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SetMonitorMix(stream)).await;
+
+        // Disabling them puts everything back on Mix A, and the Headphones hear themselves
+        let preview = change_mode(&mut device, &requests, ModeChange::SubMix(false)).await;
+        for output in [headphones, stream] {
+            let change = MixChange {
+                output,
+                from: Mix::B,
+                to: Mix::A,
+            };
+            assert!(preview.mixes.contains(&change), "{:?}", preview);
+        }
+        let unheard = RoutingChange {
+            input: game,
+            output: headphones,
+            enabled: false,
+        };
+        assert!(preview.routing.contains(&unheard), "{:?}", preview);
+        assert!(preview.submix_volumes.is_empty());
+        assert_eq!(monitored_mix(&device).await, None);
+    }
+
+    #[tokio::test]
+    async fn vod_changes_send_what_they_preview() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        let (game, vod) = (BasicInputDevice::Game, BasicOutputDevice::Sampler);
+        let stream = BasicOutputDevice::BroadcastMix;
+        run(&mut device, GoXLRCommand::SetRouter(game, stream, true)).await;
+        run(&mut device, GoXLRCommand::SetRouter(game, vod, false)).await;
+
+        // Following the Broadcast Mix only changes the VOD routing
+        let no_music = ModeChange::Vod(VodMode::StreamNoMusic);
+        let preview = change_mode(&mut device, &requests, no_music).await;
+        let routed = RoutingChange {
+            input: game,
+            output: vod,
+            enabled: true,
+        };
+        assert!(preview.routing.contains(&routed), "{:?}", preview);
+        assert!(preview.routing.iter().all(|change| change.output == vod));
+        assert!(preview.mixes.is_empty() && preview.volumes.is_empty());
+
+        let routable = ModeChange::Vod(VodMode::Routable);
+        let preview = change_mode(&mut device, &requests, routable).await;
+        let unrouted = RoutingChange {
+            enabled: false,
+            ..routed
+        };
+        assert!(preview.routing.contains(&unrouted), "{:?}", preview);
+    }
+
+    #[tokio::test]
+    async fn headphone_eq_is_only_kept_where_the_device_has_one() {
+        let band = EqFrequencies::Equalizer1KHz;
+        let gain = GoXLRCommand::SetHeadphoneEqGain(band, 4);
+
+        for hardware in [full(FULL_FIRMWARE), mini(SUBMIX_FIRMWARE)] {
+            let root = tempfile::tempdir().unwrap();
+            let settings = writable_settings(root.path()).await;
+
+            // No device reports one, so it's refused before anything is touched
+            let (events, _events_rx) = mpsc::channel(64);
+            let (goxlr, requests) = MockGoXLR::new();
+            let mut device = Device::new(goxlr, hardware.clone(), &settings, events)
+                .await
+                .unwrap();
+            assert!(!device.status().await.hardware.capabilities.headphone_eq);
+            requests.lock().unwrap().clear();
+
+            let error = device.perform_command(gain.clone()).await.unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<CommandError>(),
+                Some(CommandError::NotSupported(DeviceFeature::HeadphoneEq))
+            ));
+            assert_eq!(device.profile.get_headphone_eq_gain(band), 0);
+            assert!(!device.profile.is_dirty());
+            assert!(requests.lock().unwrap().is_empty());
+            drop(device);
+
+            // Where one is reported, the gains are kept in the profile
+            let mut hardware = hardware;
+            hardware.capabilities.headphone_eq = true;
+            let (events, _events_rx) = mpsc::channel(64);
+            let (goxlr, _requests) = MockGoXLR::new();
+            let mut device = Device::new(goxlr, hardware, &settings, events)
+                .await
+                .unwrap();
+            assert!(device.status().await.hardware.capabilities.headphone_eq);
+
+            run(&mut device, gain.clone()).await;
+            let out_of_range = GoXLRCommand::SetHeadphoneEqGain(band, 10);
+            assert!(device.perform_command(out_of_range).await.is_err());
+            assert_eq!(device.profile.get_headphone_eq_gain(band), 4);
+
+            run(&mut device, GoXLRCommand::SaveProfileAs("EQ".into())).await;
+            run(&mut device, GoXLRCommand::SetHeadphoneEqGain(band, 0)).await;
+            let load = GoXLRCommand::LoadProfile("EQ".into(), false, true);
+            run(&mut device, load).await;
+            assert_eq!(device.profile.get_headphone_eq_gain(band), 4);
+        }
+    }
+
+    #[tokio::test]
+    async fn legacy_firmware_previews_without_submixes() {
+        let legacy = VersionNumber(1, 0, Some(0), Some(0));
+        let (mut device, requests, _root) = new_device(mini(legacy.clone())).await;
+
+        let error = device
+            .preview_mode_change(ModeChange::SubMix(true))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CommandError>(),
+            Some(CommandError::NotSupported(DeviceFeature::Submixes))
+        ));
+
+        // The VOD mode still reworks the routing, but there are no mixes to touch
+        let (game, stream) = (BasicInputDevice::Game, BasicOutputDevice::BroadcastMix);
+        let vod = BasicOutputDevice::Sampler;
+        run(&mut device, GoXLRCommand::SetRouter(game, stream, true)).await;
+        run(&mut device, GoXLRCommand::SetRouter(game, vod, false)).await;
+        let no_music = ModeChange::Vod(VodMode::StreamNoMusic);
+        let preview = change_mode(&mut device, &requests, no_music).await;
+        assert!(!preview.routing.is_empty());
+        assert!(preview.mixes.is_empty() && preview.submix_volumes.is_empty());
+        assert!(preview.volumes.is_empty());
+
+        // The full sized device has no VOD mix to change
+        let (device, _, _root) = new_device(full(legacy)).await;
+        assert!(device
+            .preview_mode_change(no_music)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn firmware_updates_lock_the_device_until_they_finish() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+        assert!(!device.is_locked());
+
+        device.begin_firmware_update().unwrap();
+        for state in [Starting, Erasing, Uploading, Validating, Verifying, Writing] {
+            device.set_firmware_state(state);
+            let status = device.status().await;
+            assert!(status.locked);
+            assert_eq!(status.firmware_update, state);
+
+            // Nothing is sent to the device, and its inputs aren't polled
+            requests.lock().unwrap().clear();
+            assert!(!device.monitor_inputs().await.unwrap());
+            assert!(!device.update_state().await.unwrap());
+            assert!(requests.lock().unwrap().is_empty());
+
+            // A second update is refused
+            let error = device.begin_firmware_update().unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<CommandError>(),
+                Some(&CommandError::FirmwareUpdateBlocked(
+                    FirmwareUpdateBlocker::AlreadyUpdating
+                ))
+            );
+        }
+
+        // Once it's complete the device is unlocked and polled again, with the result kept
+        device.set_firmware_state(Complete);
+        let status = device.status().await;
+        assert!(!status.locked);
+        assert_eq!(status.firmware_update, Complete);
+        device.monitor_inputs().await.unwrap();
+        let polled = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(command, _)| *command == Command::GetButtonStates);
+        assert!(polled);
+    }
+
+    #[tokio::test]
+    async fn failed_firmware_updates_unlock_the_device() {
+        let (mut device, _requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+
+        // The state only moves while an update is running
+        device.set_firmware_state(Erasing);
+        assert!(!device.is_locked());
+        assert_eq!(device.status().await.firmware_update, Idle);
+
+        device.begin_firmware_update().unwrap();
+        device.set_firmware_state(Erasing);
+        device.set_firmware_state(Failed);
+        let status = device.status().await;
+        assert!(!status.locked);
+        assert_eq!(status.firmware_update, Failed);
+
+        // And a failed update can be tried again
+        device.begin_firmware_update().unwrap();
+        assert!(device.is_locked());
+    }
+
+    #[tokio::test]
+    async fn profile_loads_leave_the_scribbles_for_later() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let drawn = || {
+            let mut requests = requests.lock().unwrap();
+            let drawn = requests
+                .iter()
+                .filter(|(command, _)| matches!(command, Command::SetScribble(_)))
+                .count();
+            requests.clear();
+            drawn
+        };
+
+        // The default icons aren't in the test's icon directory, so they'd never be drawn
+        let commands = device.status().await.diagnostics.commands.samples;
+        for fader in FaderName::iter() {
+            run(&mut device, GoXLRCommand::SetScribbleIcon(fader, None)).await;
+        }
+        drawn();
+
+        device.apply_profile(None).await.unwrap();
+        assert_eq!(drawn(), 0);
+        assert!(FaderName::iter().all(|fader| device.scribbles[fader].pending));
+
+        device.update_state().await.unwrap();
+        assert_eq!(drawn(), 1);
+
+        // Each command was timed, and shows up in the diagnostics
+        let faders = FaderName::iter().count() as u32;
+        let diagnostics = device.status().await.diagnostics;
+        assert_eq!(diagnostics.commands.samples, commands + faders);
+        assert!(diagnostics.commands.max_ms >= diagnostics.commands.p50_ms);
+    }
+
+    #[tokio::test]
+    async fn colours_are_checked_before_anything_changes() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let fader_colours = |device: &Device<'_>| {
+            let colours = &device.profile.get_lighting_ipc(false, true).faders[&FaderName::A];
+            let colours = &colours.colours;
+            (colours.colour_one.clone(), colours.colour_two.clone())
+        };
+
+        let named = GoXLRCommand::SetFaderColours(FaderName::A, "red".into(), "#00ff00".into());
+        run(&mut device, named).await;
+        let expected = (String::from("FF0000"), String::from("00FF00"));
+        assert_eq!(fader_colours(&device), expected);
+        requests.lock().unwrap().clear();
+
+        // The top colour is fine, but neither is applied
+        let invalid = GoXLRCommand::SetFaderColours(FaderName::A, "blue".into(), "nope".into());
+        let error = device.perform_command(invalid).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<InvalidColour>(),
+            Some(&InvalidColour(String::from("nope")))
+        );
+        assert_eq!(fader_colours(&device), expected);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn silent_faders_leave_muted_faders_in_place() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let channel = device.profile.get_fader_assignment(FaderName::A);
+        run(&mut device, GoXLRCommand::SetVolume(channel, 200)).await;
+        run(&mut device, GoXLRCommand::SetSilentFaders(true)).await;
+
+        let volume_writes = || {
+            let mut requests = requests.lock().unwrap();
+            let volumes: Vec<u8> = requests
+                .iter()
+                .filter_map(|(command, body)| match command {
+                    Command::SetChannelVolume(written) if *written == channel => Some(body[0]),
+                    _ => None,
+                })
+                .collect();
+            requests.clear();
+            volumes
+        };
+        volume_writes();
+
+        // Muting and unmuting to All doesn't touch the fader
+        let mute = || GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll);
+        let unmute = || GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted);
+        run(&mut device, mute()).await;
+        assert_eq!(volume_writes(), Vec::<u8>::new());
+        assert_eq!(device.profile.get_channel_volume(channel), 200);
+        run(&mut device, unmute()).await;
+        assert_eq!(volume_writes(), Vec::<u8>::new());
+
+        // Lock Faders changes nothing while Silent Mode already holds them
+        run(&mut device, mute()).await;
+        run(&mut device, GoXLRCommand::SetLockFaders(true)).await;
+        run(&mut device, GoXLRCommand::SetLockFaders(false)).await;
+        assert_eq!(volume_writes(), Vec::<u8>::new());
+
+        // Turning it off drops the muted fader once, and turning it back on restores it
+        run(&mut device, GoXLRCommand::SetSilentFaders(false)).await;
+        assert_eq!(volume_writes(), vec![0]);
+        run(&mut device, GoXLRCommand::SetSilentFaders(true)).await;
+        assert_eq!(volume_writes(), vec![200]);
+    }
+
+    #[tokio::test]
+    async fn mini_faders_are_picked_up_at_the_volume() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let pickup = |device: &Device<'_>| device.fader_pickups[FaderName::A].direction();
+
+        let channel = device.profile.get_fader_assignment(FaderName::A);
+        let start = device.profile.get_channel_volume(channel);
+        assert_eq!(poll_fader(&mut device, &inputs, start, false).await, start);
+        poll_fader(&mut device, &inputs, 50, false).await;
+
+        // The fader sits below the new volume, so it's ignored until it's brought up to it
+        run(&mut device, GoXLRCommand::SetVolume(channel, 200)).await;
+        assert_eq!(pickup(&device), Some(PickupDirection::Up));
+        assert_eq!(poll_fader(&mut device, &inputs, 60, false).await, 200);
+        assert_eq!(poll_fader(&mut device, &inputs, 196, false).await, 196);
+        assert_eq!(pickup(&device), None);
+
+        // Or past it, without coming close on the way
+        run(&mut device, GoXLRCommand::SetVolume(channel, 100)).await;
+        assert_eq!(pickup(&device), Some(PickupDirection::Down));
+        assert_eq!(poll_fader(&mut device, &inputs, 150, false).await, 100);
+        assert_eq!(poll_fader(&mut device, &inputs, 80, false).await, 80);
+
+        // The ends of the fader can still be reached
+        run(&mut device, GoXLRCommand::SetVolume(channel, 0)).await;
+        assert_eq!(poll_fader(&mut device, &inputs, 40, false).await, 0);
+        assert_eq!(poll_fader(&mut device, &inputs, 0, false).await, 0);
+        assert_eq!(pickup(&device), None);
+
+        // Setting a volume close to where the fader sits needs no pickup at all
+        run(&mut device, GoXLRCommand::SetVolume(channel, 3)).await;
+        assert_eq!(pickup(&device), None);
+    }
+
+    #[tokio::test]
+    async fn replays_are_never_written_over_anything() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let recorded = settings.get_samples_directory().await.join("Recorded");
+        std::fs::create_dir_all(&recorded).unwrap();
+        std::fs::write(recorded.join("Existing.wav"), "").unwrap();
+
+        let error = device.save_replay_buffer(Some(String::new())).await;
+        assert_eq!(
+            error.unwrap_err().downcast_ref::<CommandError>(),
+            Some(&CommandError::InvalidName(NameProblem::Empty))
+        );
+        for existing in ["Existing", "Existing.wav"] {
+            let error = device.save_replay_buffer(Some(existing.into())).await;
+            let error = error.unwrap_err().to_string();
+            assert!(error.ends_with("already exists"), "{}", error);
+        }
+
+        // Separators are dropped, so these stay in the directory and get as far as the sampler,
+        // which has no pre-buffer here
+        for name in ["Clip", "../Clip", "..\\Clip"] {
+            let error = device.save_replay_buffer(Some(name.into())).await;
+            let error = error.unwrap_err().to_string();
+            assert_eq!(error, "The Sampler Pre-Buffer is disabled", "{}", name);
+        }
+        assert_eq!(std::fs::read_dir(&recorded).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn profiles_are_only_exported_to_the_profile_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let read_only = settings(root.path()).await;
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &read_only, events.clone())
+            .await
+            .unwrap();
+        let error = device.export_windows_profile("Official").await.unwrap_err();
+        assert!(error.downcast_ref::<ReadOnlyError>().is_some());
+
+        let settings = writable_settings(root.path()).await;
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        for name in ["../Official", "..\\Official", "..", "Nested/Official", ""] {
+            let error = device.export_windows_profile(name).await.unwrap_err();
+            assert!(
+                matches!(
+                    error.downcast_ref::<CommandError>(),
+                    Some(CommandError::InvalidName(_))
+                ),
+                "{}",
+                name
+            );
+        }
+        assert!(!root.path().join("Official.goxlr").exists());
+
+        let profiles = settings.get_profile_directory().await;
+        device.export_windows_profile("Official").await.unwrap();
+        assert!(profiles.join("Official.goxlr").is_file());
+
+        let error = device.export_windows_profile("Official").await.unwrap_err();
+        assert!(error.to_string().ends_with("already exists"));
+    }
+
+    #[tokio::test]
+    async fn effect_sections_stay_in_the_presets_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let section = EffectSection::Reverb;
+        let extension = get_effect_section_extension(section);
+        let outside = root.path().join(format!("Outside.{extension}"));
+        for name in ["../Outside", "..\\Outside", "..", "Nested/Outside", ""] {
+            let save = GoXLRCommand::SaveEffectSection(section, name.into());
+            let load = GoXLRCommand::LoadEffectSection(section, name.into());
+            for command in [save, load] {
+                let error = device.perform_command(command).await.unwrap_err();
+                assert!(
+                    matches!(
+                        error.downcast_ref::<CommandError>(),
+                        Some(CommandError::InvalidName(_))
+                    ),
+                    "{}",
+                    name
+                );
+            }
+            assert!(!outside.exists(), "{}", name);
+        }
+
+        let presets = settings.get_presets_directory().await;
+        run(
+            &mut device,
+            GoXLRCommand::SaveEffectSection(section, "Hall".into()),
+        )
+        .await;
+        assert!(presets.join(format!("Hall.{extension}")).is_file());
+        run(
+            &mut device,
+            GoXLRCommand::LoadEffectSection(section, "Hall".into()),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn loaded_and_deleted_names_stay_in_their_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let outside = ["goxlr", "goxlrMicProfile", "preset"].map(|extension| {
+            let path = root.path().join(format!("Outside.{extension}"));
+            std::fs::write(&path, b"").unwrap();
+            path
+        });
+        for name in ["../Outside", "..\\Outside", "Nested/../../Outside"] {
+            let commands = [
+                GoXLRCommand::LoadProfile(name.into(), false, true),
+                GoXLRCommand::LoadProfileColours(name.into()),
+                GoXLRCommand::RebaseProfile(name.into()),
+                GoXLRCommand::DeleteProfile(name.into()),
+                GoXLRCommand::LoadMicProfile(name.into(), false),
+                GoXLRCommand::LoadMicProfileSection(name.into(), MicProfileSection::Equaliser),
+                GoXLRCommand::DeleteMicProfile(name.into()),
+                GoXLRCommand::LoadEffectPreset(name.into()),
+            ];
+            for command in commands {
+                let description = format!("{:?}", command);
+                let error = device.perform_command(command).await.unwrap_err();
+                assert!(
+                    matches!(
+                        error.downcast_ref::<CommandError>(),
+                        Some(CommandError::InvalidName(_))
+                    ),
+                    "{}",
+                    description
+                );
+            }
+        }
+        assert!(outside.iter().all(|path| path.is_file()));
+
+        // Files from before the name rules are found by listing the directory, so still load
+        let profiles = settings.get_profile_directory().await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Legacy".into())).await;
+        std::fs::rename(
+            profiles.join("Legacy.goxlr"),
+            profiles.join(" Legacy.goxlr"),
+        )
+        .unwrap();
+        run(
+            &mut device,
+            GoXLRCommand::LoadProfile(" Legacy".into(), false, true),
+        )
+        .await;
+        assert_eq!(device.profile.name(), " Legacy");
+    }
+
+    #[tokio::test]
+    async fn eq_curves_stay_in_the_mic_profile_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        // A curve next to the mic profiles can't be reached by climbing out of them
+        let curve = "Filter 1: ON PK Fc 1000 Hz Gain 3 dB Q 1.00\n";
+        std::fs::write(root.path().join("Outside.txt"), curve).unwrap();
+        let mic = EqTarget::Microphone;
+        for name in ["../Outside", "..\\Outside", "..", "Nested/Outside", ""] {
+            let imported = device.import_eq_curve(name, mic).await.unwrap_err();
+            let exported = device.export_eq_curve(name, mic).await.unwrap_err();
+            for error in [imported, exported] {
+                assert!(
+                    matches!(
+                        error.downcast_ref::<CommandError>(),
+                        Some(CommandError::InvalidName(_))
+                    ),
+                    "{}",
+                    name
+                );
+            }
+        }
+        assert!(!root.path().join("Outside.csv").exists());
+
+        let mic_profiles = settings.get_mic_profile_directory().await;
+        std::fs::write(mic_profiles.join("Curve.txt"), curve).unwrap();
+        device.import_eq_curve("Curve", mic).await.unwrap();
+        let band = EqFrequencies::Equalizer1KHz;
+        assert_eq!(device.mic_profile.get_eq_gain(band), 3);
+
+        // Exports go next to it, and never over anything already there
+        device.export_eq_curve("Curve", mic).await.unwrap();
+        assert!(mic_profiles.join("Curve.csv").is_file());
+        let error = device.export_eq_curve("Curve", mic).await.unwrap_err();
+        assert!(error.to_string().ends_with("already exists"));
+    }
+
+    #[tokio::test]
+    async fn the_sampler_uses_the_highest_routed_low_cut() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let (line_in, console) = (BasicInputDevice::LineIn, BasicInputDevice::Console);
+        let sampler = BasicOutputDevice::Sampler;
+
+        run(&mut device, GoXLRCommand::SetRouter(line_in, sampler, true)).await;
+        run(&mut device, GoXLRCommand::SetRouter(console, sampler, true)).await;
+        run(&mut device, GoXLRCommand::SetInputLowCut(line_in, Some(80))).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetInputLowCut(console, Some(120)),
+        )
+        .await;
+        let levels = device.status().await.levels;
+        assert_eq!(levels.input_low_cut[line_in], Some(80));
+        assert_eq!(levels.input_low_cut[console], Some(120));
+        assert_eq!(device.profile.get_sampler_low_cut(), Some(120));
+
+        // Inputs which aren't heard by the Sampler don't count
+        run(
+            &mut device,
+            GoXLRCommand::SetRouter(console, sampler, false),
+        )
+        .await;
+        assert_eq!(device.profile.get_sampler_low_cut(), Some(80));
+        run(&mut device, GoXLRCommand::SetInputLowCut(line_in, None)).await;
+        assert_eq!(device.profile.get_sampler_low_cut(), None);
+
+        // Only the Line In and Console have one, and only within range
+        for command in [
+            GoXLRCommand::SetInputLowCut(BasicInputDevice::Microphone, Some(80)),
+            GoXLRCommand::SetInputLowCut(line_in, Some(10)),
+            GoXLRCommand::SetInputLowCut(line_in, Some(251)),
+        ] {
+            assert!(device.perform_command(command).await.is_err());
+        }
+        assert_eq!(device.status().await.levels.input_low_cut[line_in], None);
+    }
+
+    #[tokio::test]
+    async fn the_mini_refuses_a_low_cut() {
+        let (mut device, requests, _root) = new_device(mini(SUBMIX_FIRMWARE)).await;
+        let capabilities = device.status().await.hardware.capabilities;
+        assert!(!capabilities.input_low_cut && !capabilities.full_size);
+        requests.lock().unwrap().clear();
+
+        // With no Sampler there's nothing to apply it to, so it's refused before anything changes
+        let line_in = BasicInputDevice::LineIn;
+        let command = GoXLRCommand::SetInputLowCut(line_in, Some(80));
+        let error = device.perform_command(command).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CommandError>(),
+            Some(CommandError::NotSupported(DeviceFeature::FullSize))
+        ));
+        assert_eq!(device.profile.get_input_low_cut(line_in), None);
+        assert!(device.status().await.levels.input_low_cut[line_in].is_none());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn switching_profiles_only_writes_what_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let (game, headphones) = (BasicInputDevice::Game, BasicOutputDevice::Headphones);
+        let routed = device.profile.get_router(game)[headphones];
+        let music = device.profile.get_channel_volume(ChannelName::Music);
+        run(&mut device, GoXLRCommand::SaveProfileAs("First".into())).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetRouter(game, headphones, !routed),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SetVolume(ChannelName::Music, 42)).await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Second".into())).await;
+        requests.lock().unwrap().clear();
+
+        let load = GoXLRCommand::LoadProfile("First".into(), false, false);
+        run(&mut device, load).await;
+        let requests = requests.lock().unwrap();
+        let count = |matches: &dyn Fn(&Command) -> bool| {
+            requests
+                .iter()
+                .filter(|(command, _)| matches(command))
+                .count()
+        };
+
+        // Only the Game's routing rows, and the Music volume, differ between the profiles
+        let (left, right) = InputDevice::from_basic(&game);
+        let routing: Vec<Command> = requests
+            .iter()
+            .map(|(command, _)| *command)
+            .filter(|command| matches!(command, Command::SetRouting(_)))
+            .collect();
+        assert_eq!(
+            routing,
+            vec![Command::SetRouting(left), Command::SetRouting(right)]
+        );
+
+        let volumes: Vec<(ChannelName, u8)> = requests
+            .iter()
+            .filter_map(|(command, body)| match command {
+                Command::SetChannelVolume(channel) => Some((*channel, body[0])),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(volumes, vec![(ChannelName::Music, music)]);
+
+        // Nothing about the lighting or the faders changed, stopping the samples before the
+        // load may refresh the buttons, but the load itself doesn't send them again
+        assert_eq!(count(&|command| *command == Command::SetColourMap()), 0);
+        assert!(count(&|command| *command == Command::SetButtonStates()) <= 1);
+        assert_eq!(count(&|command| matches!(command, Command::SetFader(_))), 0);
+    }
+
+    #[tokio::test]
+    async fn reloading_the_same_profile_sends_nothing() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, requests, _inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        // Let the scribbles be drawn, so what's on the device is known
+        for _ in FaderName::iter() {
+            device.update_state().await.unwrap();
+        }
+        run(&mut device, GoXLRCommand::SaveProfileAs("First".into())).await;
+        requests.lock().unwrap().clear();
+
+        let load = GoXLRCommand::LoadProfile("First".into(), false, false);
+        run(&mut device, load).await;
+        for _ in FaderName::iter() {
+            device.update_state().await.unwrap();
+        }
+
+        let writes: Vec<Command> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(command, _)| *command)
+            .filter(|command| {
+                !matches!(
+                    command,
+                    Command::GetButtonStates
+                        | Command::GetMicrophoneLevel
+                        | Command::GetHardwareInfo(_)
+                )
+            })
+            .collect();
+        assert_eq!(writes, vec![]);
+    }
+
+    #[tokio::test]
+    async fn profile_loads_write_each_volume_once() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        run(&mut device, GoXLRCommand::SetSubMixEnabled(true)).await;
+        let writes = || {
+            let mut requests = requests.lock().unwrap();
+            let mut volumes: EnumMap<ChannelName, usize> = EnumMap::default();
+            let mut submixes: EnumMap<SubMixChannelName, usize> = EnumMap::default();
+            for (command, _) in requests.drain(..) {
+                match command {
+                    Command::SetChannelVolume(channel) => volumes[channel] += 1,
+                    Command::SetSubChannelVolume(mix) => submixes[mix] += 1,
+                    _ => {}
+                }
+            }
+            (volumes, submixes)
+        };
+        writes();
+
+        // With nothing known about the device, every channel and mix is written, but only once,
+        // the Mic Monitor included
+        device.apply_profile(None).await.unwrap();
+        let (volumes, submixes) = writes();
+        assert!(volumes.values().all(|count| *count == 1));
+        assert!(submixes.values().all(|count| *count <= 1));
+        let mixed = device.get_submix_load_volumes(true);
+        for (mix, volume) in mixed {
+            assert_eq!(submixes[mix], volume.is_some() as usize);
+        }
+
+        // Loading the same settings over themselves writes nothing
+        let current = device.get_current_state().await.unwrap();
+        device.apply_profile(Some(current)).await.unwrap();
+        let (volumes, submixes) = writes();
+        assert!(volumes.values().all(|count| *count == 0));
+        assert!(submixes.values().all(|count| *count == 0));
+    }
+
+    #[tokio::test]
+    async fn buttons_pressed_during_a_profile_load_are_not_held_up() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let music = ChannelName::Music;
+        run(&mut device, GoXLRCommand::SetFader(FaderName::A, music)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteFunction(FaderName::A, BasicMuteFunction::All),
+        )
+        .await;
+
+        // Reloading the settings writes the whole profile
+        requests.lock().unwrap().clear();
+        run(&mut device, GoXLRCommand::ReloadSettings()).await;
+        let load_length = requests.lock().unwrap().len();
+        requests.lock().unwrap().clear();
+
+        // Fader A's mute is tapped half way through the next one
+        let midway = load_length / 2;
+        inputs.lock().unwrap().scripted = VecDeque::from([
+            (midway, EnumSet::only(Buttons::Fader1Mute)),
+            (midway, EnumSet::empty()),
+        ]);
+        run(&mut device, GoXLRCommand::ReloadSettings()).await;
+
+        // The mute goes out no more than a step of the load after the press, rather than at the end
+        let requests = requests.lock().unwrap();
+        let pressed = (midway..requests.len())
+            .find(|&index| requests[index].0 == Command::GetButtonStates)
+            .unwrap();
+        let muted = requests
+            .iter()
+            .position(|(command, body)| {
+                *command == Command::SetChannelState(music) && body[0] == Muted.id()
+            })
+            .unwrap();
+        assert!(muted > pressed);
+        assert!(
+            muted - pressed <= 32,
+            "Muted {} requests after the press",
+            muted - pressed
+        );
+        assert!(muted < load_length);
+
+        // And the rest of the load kept to it
+        assert_eq!(device.sent.mute_state[music], Some(Muted));
+        assert!(device.profile.get_mute_button_state(FaderName::A).1);
+    }
+
+    // Sets what's plugged in, and polls the device so it notices
+    async fn plug_headphones(device: &mut Device<'_>, inputs: &Inputs, plugged: bool) {
+        inputs.lock().unwrap().jacks = Some(JackStates {
+            headphones: plugged,
+            line_out: true,
+            microphone: true,
+        });
+        device.monitor_inputs().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn jacks_are_only_reported_once_known() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        device.monitor_inputs().await.unwrap();
+        let jacks = device.status().await.jacks;
+        assert_eq!(jacks.outputs[BasicOutputDevice::Headphones], None);
+        assert_eq!(jacks.outputs[BasicOutputDevice::LineOut], None);
+        assert_eq!(jacks.microphone, None);
+
+        plug_headphones(&mut device, &inputs, false).await;
+        let jacks = device.status().await.jacks;
+        assert_eq!(jacks.outputs[BasicOutputDevice::Headphones], Some(false));
+        assert_eq!(jacks.outputs[BasicOutputDevice::LineOut], Some(true));
+        assert_eq!(jacks.microphone, Some(true));
+    }
+
+    #[tokio::test]
+    async fn the_monitor_follows_the_headphones_when_enabled() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let monitor = |device: &Device<'_>| device.profile.get_monitoring_mix();
+        run(&mut device, GoXLRCommand::SetSubMixEnabled(true)).await;
+
+        // Off by default, so unplugging changes nothing
+        plug_headphones(&mut device, &inputs, true).await;
+        plug_headphones(&mut device, &inputs, false).await;
+        assert_eq!(monitor(&device), BasicOutputDevice::Headphones);
+
+        plug_headphones(&mut device, &inputs, true).await;
+        run(&mut device, GoXLRCommand::SetSwitchMonitorOnUnplug(true)).await;
+
+        // The monitor moves to the Line Out, and back when the Headphones return
+        plug_headphones(&mut device, &inputs, false).await;
+        assert_eq!(monitor(&device), BasicOutputDevice::LineOut);
+        plug_headphones(&mut device, &inputs, true).await;
+        assert_eq!(monitor(&device), BasicOutputDevice::Headphones);
+
+        // A monitor picked while they were out is left alone
+        plug_headphones(&mut device, &inputs, false).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetMonitorMix(BasicOutputDevice::ChatMic),
+        )
+        .await;
+        plug_headphones(&mut device, &inputs, true).await;
+        assert_eq!(monitor(&device), BasicOutputDevice::ChatMic);
+
+        // As is one picked and then put back on the Line Out
+        run(
+            &mut device,
+            GoXLRCommand::SetMonitorMix(BasicOutputDevice::Headphones),
+        )
+        .await;
+        plug_headphones(&mut device, &inputs, false).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetMonitorMix(BasicOutputDevice::LineOut),
+        )
+        .await;
+        plug_headphones(&mut device, &inputs, true).await;
+        assert_eq!(monitor(&device), BasicOutputDevice::LineOut);
+
+        // Only the Headphones matter, the monitor stays put while other jacks change
+        run(
+            &mut device,
+            GoXLRCommand::SetMonitorMix(BasicOutputDevice::Headphones),
+        )
+        .await;
+        inputs.lock().unwrap().jacks = Some(JackStates {
+            headphones: true,
+            line_out: false,
+            microphone: false,
+        });
+        device.monitor_inputs().await.unwrap();
+        assert_eq!(monitor(&device), BasicOutputDevice::Headphones);
+    }
+
+    #[tokio::test]
+    async fn effects_can_be_copied_through_the_status() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+
+        let (goxlr, _) = MockGoXLR::new();
+        let mut source = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events.clone())
+            .await
+            .unwrap();
+        let (goxlr, _) = MockGoXLR::new();
+        let mut copy = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events.clone())
+            .await
+            .unwrap();
+
+        // Move away from the defaults, so there's something to copy
+        for command in [
+            GoXLRCommand::SetEffectsIntensity(80),
+            GoXLRCommand::SetReverbStyle(ReverbStyle::Chapel),
+            GoXLRCommand::SetReverbDecay(2000),
+            GoXLRCommand::SetEchoStyle(EchoStyle::ClassicSlap),
+            GoXLRCommand::SetEchoDelayLeft(120),
+            GoXLRCommand::SetEchoDelayRight(240),
+            GoXLRCommand::SetHardTuneEnabled(true),
+            GoXLRCommand::SetHardTuneSource(HardTuneSource::Game),
+            GoXLRCommand::SetPitchStyle(PitchStyle::Narrow),
+            GoXLRCommand::SetRobotGain(RobotRange::High, -6),
+            GoXLRCommand::SetRobotWidth(RobotRange::Low, 20),
+            GoXLRCommand::SetMegaphonePostGain(4),
+        ] {
+            run(&mut source, command).await;
+        }
+
+        let effects = source.status().await.effects.unwrap();
+        for command in effects.current.as_commands() {
+            run(&mut copy, command).await;
+        }
+
+        let presets = root.path().join("compare");
+        std::fs::create_dir(&presets).unwrap();
+        let mut written = vec![];
+        for (name, device) in [("Source", &mut source), ("Copy", &mut copy)] {
+            device.profile.write_preset(name.into(), &presets).unwrap();
+            let preset = std::fs::read_to_string(presets.join(format!("{name}.preset")));
+            written.push(preset.unwrap());
+        }
+        assert_eq!(written[0], written[1]);
+
+        // The Mini has no effects, rather than a set of empty ones
+        let (goxlr, _) = MockGoXLR::new();
+        let mini = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        assert!(mini.status().await.effects.is_none());
+    }
+
+    async fn encoder_state(device: &Device<'_>, encoder: EncoderName) -> EncoderState {
+        device.status().await.effects.unwrap().encoders[encoder]
+    }
+
+    #[tokio::test]
+    async fn global_encoders_keep_their_value_across_presets() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        // Give the first two presets different amounts
+        run(&mut device, GoXLRCommand::SetReverbAmount(80)).await;
+        run(&mut device, GoXLRCommand::SetEchoAmount(60)).await;
+        let first_echo = encoder_state(&device, EncoderName::Echo).await.value;
+
+        let preset = EffectBankPresets::Preset2;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(preset)).await;
+        run(&mut device, GoXLRCommand::SetReverbAmount(20)).await;
+        run(&mut device, GoXLRCommand::SetEchoAmount(10)).await;
+        let second_reverb = encoder_state(&device, EncoderName::Reverb).await.value;
+        let second_echo = encoder_state(&device, EncoderName::Echo).await.value;
+        assert_ne!(first_echo, second_echo);
+
+        // Making Reverb global keeps the active preset's value in every preset..
+        let command = GoXLRCommand::SetEncoderGlobalMode(EncoderName::Reverb, true);
+        run(&mut device, command).await;
+        assert!(encoder_state(&device, EncoderName::Reverb).await.is_global);
+        assert!(!encoder_state(&device, EncoderName::Echo).await.is_global);
+
+        let preset = EffectBankPresets::Preset1;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(preset)).await;
+        let reverb = encoder_state(&device, EncoderName::Reverb).await;
+        assert_eq!(reverb.value, second_reverb);
+        assert_eq!(reverb.value, device.profile.get_reverb_value());
+        assert_eq!(
+            encoder_state(&device, EncoderName::Echo).await.value,
+            first_echo
+        );
+
+        // ..and turning it changes it for all of them
+        run(&mut device, GoXLRCommand::SetReverbAmount(50)).await;
+        let shared = encoder_state(&device, EncoderName::Reverb).await.value;
+        let preset = EffectBankPresets::Preset3;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(preset)).await;
+        assert_eq!(
+            encoder_state(&device, EncoderName::Reverb).await.value,
+            shared
+        );
+
+        // Once it's no longer global, each preset goes back to having its own
+        let command = GoXLRCommand::SetEncoderGlobalMode(EncoderName::Reverb, false);
+        run(&mut device, command).await;
+        run(&mut device, GoXLRCommand::SetReverbAmount(90)).await;
+        let preset = EffectBankPresets::Preset2;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(preset)).await;
+        let reverb = encoder_state(&device, EncoderName::Reverb).await;
+        assert!(!reverb.is_global);
+        assert_eq!(reverb.value, shared);
+        assert_eq!(
+            encoder_state(&device, EncoderName::Echo).await.value,
+            second_echo
+        );
+    }
+
+    #[tokio::test]
+    async fn global_pitch_follows_each_presets_style() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let pitch =
+            |device: &Device<'_>| device.profile.get_active_pitch_profile().get_pitch_value();
+        let presets = [EffectBankPresets::Preset1, EffectBankPresets::Preset2];
+
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(presets[1])).await;
+        run(&mut device, GoXLRCommand::SetPitchStyle(PitchStyle::Narrow)).await;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(presets[0])).await;
+        run(&mut device, GoXLRCommand::SetPitchStyle(PitchStyle::Wide)).await;
+        run(&mut device, GoXLRCommand::SetPitchAmount(20)).await;
+
+        let command = GoXLRCommand::SetEncoderGlobalMode(EncoderName::Pitch, true);
+        run(&mut device, command).await;
+
+        // The Narrow preset only goes as far as it can, without pulling the Wide one back
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(presets[1])).await;
+        assert_eq!(pitch(&device), 12);
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(presets[0])).await;
+        assert_eq!(pitch(&device), 20);
+
+        // Anything either can reach is shared exactly
+        run(&mut device, GoXLRCommand::SetPitchAmount(-6)).await;
+        run(&mut device, GoXLRCommand::SetActiveEffectPreset(presets[1])).await;
+        assert_eq!(pitch(&device), -6);
+        let amount = device.status().await.effects.unwrap().current.pitch.amount;
+        assert_eq!(
+            encoder_state(&device, EncoderName::Pitch).await.value,
+            amount
+        );
+    }
+
+    // Positions Fader A and its mute button, polls the device, and returns the fader's volume
+    async fn poll_fader(device: &mut Device<'_>, inputs: &Inputs, position: u8, held: bool) -> u8 {
+        {
+            let mut inputs = inputs.lock().unwrap();
+            inputs.volumes[FaderName::A as usize] = position;
+            inputs.pressed = match held {
+                true => EnumSet::only(Buttons::Fader1Mute),
+                false => EnumSet::empty(),
+            };
+        }
+        device.monitor_inputs().await.unwrap();
+
+        let channel = device.profile.get_fader_assignment(FaderName::A);
+        device.profile.get_channel_volume(channel)
+    }
+
+    // Whether Fader A is muted (to anything), and whether that's to all
+    fn fader_a_muted(device: &Device<'_>) -> (bool, bool) {
+        let (muted_to_x, muted_to_all, _) = device.profile.get_mute_button_state(FaderName::A);
+        (muted_to_x || muted_to_all, muted_to_all)
+    }
+
+    #[tokio::test]
+    async fn fader_is_fine_adjusted_while_its_mute_is_held() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        run(&mut device, GoXLRCommand::SetFineFaderAdjust(true)).await;
+
+        // Pick the fader up, then it's followed as normal
+        assert_eq!(poll_fader(&mut device, &inputs, 255, false).await, 255);
+        assert_eq!(poll_fader(&mut device, &inputs, 200, false).await, 200);
+
+        // Holding mute and moving the fader moves the volume a tenth as far
+        assert_eq!(poll_fader(&mut device, &inputs, 200, true).await, 200);
+        assert_eq!(poll_fader(&mut device, &inputs, 250, true).await, 205);
+        assert_eq!(poll_fader(&mut device, &inputs, 150, true).await, 195);
+
+        // Releasing doesn't mute, and the fader has to be brought back to the volume
+        assert_eq!(poll_fader(&mut device, &inputs, 150, false).await, 195);
+        assert!(!fader_a_muted(&device).0);
+        assert_eq!(poll_fader(&mut device, &inputs, 180, false).await, 195);
+        assert_eq!(poll_fader(&mut device, &inputs, 197, false).await, 197);
+
+        // A press without moving the fader is still a mute
+        assert_eq!(poll_fader(&mut device, &inputs, 197, true).await, 197);
+        assert_eq!(poll_fader(&mut device, &inputs, 197, false).await, 197);
+        assert!(fader_a_muted(&device).0);
+    }
+
+    #[tokio::test]
+    async fn fine_adjustment_holds_mute_until_release() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        run(&mut device, GoXLRCommand::SetMuteHoldDuration(1)).await;
+
+        // Normally a hold mutes to all as soon as it's noticed
+        poll_fader(&mut device, &inputs, 0, true).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        device.update_state().await.unwrap();
+        assert!(fader_a_muted(&device).1);
+        poll_fader(&mut device, &inputs, 0, false).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted),
+        )
+        .await;
+
+        // With fine adjustment, the button may yet be used to adjust, so wait for the release
+        run(&mut device, GoXLRCommand::SetFineFaderAdjust(true)).await;
+        poll_fader(&mut device, &inputs, 0, true).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        device.update_state().await.unwrap();
+        assert!(!fader_a_muted(&device).1);
+        poll_fader(&mut device, &inputs, 0, false).await;
+        assert!(fader_a_muted(&device).1);
+    }
+
+    fn reactive_accent(decay: u16) -> ReactiveConfig {
+        ReactiveConfig {
+            zones: vec![ReactiveZone::Simple(SimpleColourTargets::Accent)],
+            base_colour: String::from("000000"),
+            peak_colour: String::from("FF0000"),
+            sensitivity: 50,
+            decay,
+        }
+    }
+
+    // The Accent's colour in the last colour map written, as it's sent (BGRA)
+    fn accent_colour(device: &Device<'_>) -> [u8; 4] {
+        let position = ColourTargets::LogoX.position(0, device.device_supports_animations());
+        let colour_map = device.sent.colour_map.unwrap();
+        colour_map[position..position + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn reactive_lighting_decays_towards_the_base() {
+        assert_eq!(reactive_position(-30., 50), 1.);
+        assert_eq!(reactive_position(-45., 50), 0.5);
+        assert_eq!(reactive_position(-72.2, 50), 0.);
+
+        let mut reactive = ReactiveState::new(reactive_accent(1000), false).unwrap();
+        assert_eq!(reactive.follow(0., Duration::ZERO), [0, 0, 255, 255]);
+
+        // Silence only brings it down as fast as the decay allows
+        let half = Duration::from_millis(500);
+        assert_eq!(reactive.follow(-72.2, half), [0, 0, 128, 255]);
+        assert_eq!(reactive.follow(-72.2, half), [0, 0, 0, 255]);
+    }
+
+    #[tokio::test]
+    async fn lighting_follows_the_mic_level() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let profile_colours = device.sent.colour_map;
+        let profile_accent = accent_colour(&device);
+        run(
+            &mut device,
+            GoXLRCommand::SetReactiveLighting(Some(reactive_accent(0))),
+        )
+        .await;
+
+        // Loud enough to reach the peak colour
+        inputs.lock().unwrap().mic_level = 4096;
+        device.update_state().await.unwrap();
+        assert_eq!(accent_colour(&device), [0, 0, 255, 255]);
+
+        // Polling is limited, so the drop isn't seen until the interval has passed
+        inputs.lock().unwrap().mic_level = 0;
+        device.update_state().await.unwrap();
+        assert_eq!(accent_colour(&device), [0, 0, 255, 255]);
+        tokio::time::sleep(REACTIVE_POLL_INTERVAL).await;
+        device.update_state().await.unwrap();
+        assert_eq!(accent_colour(&device), [0, 0, 0, 255]);
+
+        // The Button Test suspends it, showing the profile's colours until it ends
+        inputs.lock().unwrap().mic_level = 4096;
+        device.set_button_test(true).unwrap();
+        device.update_state().await.unwrap();
+        assert_eq!(accent_colour(&device), profile_accent);
+        assert!(!device.status().await.lighting.reactive.active);
+
+        device.set_button_test(false).unwrap();
+        device.update_state().await.unwrap();
+        device.update_state().await.unwrap();
+        assert_eq!(accent_colour(&device), [0, 0, 255, 255]);
+        assert!(device.status().await.lighting.reactive.active);
+
+        // Turning it off puts back exactly what the profile had
+        run(&mut device, GoXLRCommand::SetReactiveLighting(None)).await;
+        assert_eq!(device.sent.colour_map, profile_colours);
+        assert!(device.status().await.lighting.reactive.config.is_none());
+    }
+
+    // A button's two colours in the last colour map written, as they're sent (BGRA)
+    fn button_colours(device: &Device<'_>, button: Button) -> [[u8; 4]; 2] {
+        let format = device.device_supports_animations();
+        let target = standard_to_colour_target(button);
+        let colour_map = device.sent.colour_map.unwrap();
+        [0, 1].map(|index| {
+            let position = target.position(index, format);
+            colour_map[position..position + 4].try_into().unwrap()
+        })
+    }
+
+    fn button_state(device: &Device<'_>, button: Buttons) -> ButtonStates {
+        device.sent.button_states.unwrap()[button as usize]
+    }
+
+    #[tokio::test]
+    async fn high_contrast_shows_state_by_brightness() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetAnimationMode(goxlr_types::AnimationMode::None),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetButtonOffStyle(Button::Fader1Mute, ButtonColourOffStyle::Dimmed),
+        )
+        .await;
+        let profile_colours = device.sent.colour_map;
+        let profile_states = device.sent.button_states;
+        let [fader_colour, _] = button_colours(&device, Button::Fader1Mute);
+        let [sampler_colour, _] = button_colours(&device, Button::SamplerSelectA);
+
+        let levels = |active, inactive| HighContrastLevels { active, inactive };
+        let config = HighContrastConfig {
+            levels: levels(100, 10),
+            groups: BTreeMap::from([(HighContrastGroup::SamplerSelector, levels(50, 0))]),
+        };
+        run(&mut device, GoXLRCommand::SetHighContrastMode(Some(config))).await;
+
+        // Inactive buttons show their first colour dimmed, whatever their off style
+        let [active, inactive] = button_colours(&device, Button::Fader1Mute);
+        assert_eq!(active, fader_colour);
+        assert_eq!(inactive, scale_colour(fader_colour, 10));
+        assert_eq!(
+            button_state(&device, Buttons::Fader1Mute),
+            ButtonStates::Colour2
+        );
+
+        // Groups with their own levels use those instead
+        let [active, inactive] = button_colours(&device, Button::SamplerSelectA);
+        assert_eq!(active, scale_colour(sampler_colour, 50));
+        assert_eq!(inactive, [0, 0, 0, sampler_colour[3]]);
+
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToX),
+        )
+        .await;
+        assert_eq!(
+            button_state(&device, Buttons::Fader1Mute),
+            ButtonStates::Colour1
+        );
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted),
+        )
+        .await;
+
+        // The Rainbow animations replace the colours, so the device's dimming is used instead
+        run(
+            &mut device,
+            GoXLRCommand::SetAnimationMode(goxlr_types::AnimationMode::RainbowBright),
+        )
+        .await;
+        assert_eq!(
+            button_state(&device, Buttons::Fader1Mute),
+            ButtonStates::DimmedColour1
+        );
+        run(
+            &mut device,
+            GoXLRCommand::SetAnimationMode(goxlr_types::AnimationMode::None),
+        )
+        .await;
+
+        // The profile is left alone, so turning it off puts back exactly what it had
+        let status = device.status().await;
+        assert!(status.lighting.high_contrast.is_some());
+        assert_eq!(
+            status.lighting.buttons[&Button::Fader1Mute].off_style,
+            ButtonColourOffStyle::Dimmed
+        );
+        run(&mut device, GoXLRCommand::SetHighContrastMode(None)).await;
+        assert_eq!(device.sent.colour_map, profile_colours);
+        assert_eq!(device.sent.button_states, profile_states);
+        assert!(device.status().await.lighting.high_contrast.is_none());
+
+        let config = HighContrastConfig {
+            levels: levels(101, 0),
+            groups: BTreeMap::new(),
+        };
+        let command = GoXLRCommand::SetHighContrastMode(Some(config));
+        assert!(device.perform_command(command).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn profiles_are_saved_and_renamed_with_safe_names() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let profiles = settings.get_profile_directory().await;
+        std::fs::create_dir_all(&profiles).unwrap();
+        std::fs::create_dir_all(settings.get_backup_directory().await).unwrap();
+
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        // Separators and other disallowed characters are dropped from the new name
+        run(
+            &mut device,
+            GoXLRCommand::SaveProfileAs("Stream: Main".into()),
+        )
+        .await;
+        assert_eq!(device.profile().name(), "Stream Main");
+        assert!(profiles.join("Stream Main.goxlr").is_file());
+
+        let error = device
+            .perform_command(GoXLRCommand::SaveProfileAs("Aux".into()))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<CommandError>(),
+            Some(&CommandError::InvalidName(NameProblem::Reserved))
+        );
+
+        // Renaming the active profile follows it, and is remembered for next time
+        run(
+            &mut device,
+            GoXLRCommand::RenameProfile("Stream Main".into(), "Live".into()),
+        )
+        .await;
+        assert_eq!(device.profile().name(), "Live");
+        assert!(profiles.join("Live.goxlr").is_file());
+        assert!(!profiles.join("Stream Main.goxlr").exists());
+        assert_eq!(
+            settings.get_device_profile_name(device.serial()).await,
+            Some(String::from("Live"))
+        );
+
+        // Missing profiles and existing names aren't touched
+        let missing = GoXLRCommand::RenameProfile("Missing".into(), "Other".into());
+        assert!(device.perform_command(missing).await.is_err());
+        run(&mut device, GoXLRCommand::SaveProfileAs("Other".into())).await;
+        let taken = GoXLRCommand::RenameProfile("Live".into(), "Other".into());
+        assert!(device.perform_command(taken).await.is_err());
+        assert!(profiles.join("Live.goxlr").is_file());
+
+        // The profile being renamed has to be in the list, not just somewhere on disk
+        for name in ["../profiles/Other", "./Other"] {
+            let outside = GoXLRCommand::RenameProfile(name.into(), "Moved".into());
+            assert!(device.perform_command(outside).await.is_err(), "{}", name);
+        }
+        assert!(profiles.join("Other.goxlr").is_file());
+        assert!(!profiles.join("Moved.goxlr").exists());
+    }
+
+    // Whether anything sent since last checked would have let the Mic be heard
+    fn mic_unmute_sent(requests: &Requests) -> bool {
+        let mute_key = (EffectKey::MicInputMute as u32).to_le_bytes();
+        let mut requests = requests.lock().unwrap();
+        let sent = requests.iter().any(|(command, body)| match command {
+            Command::SetChannelState(ChannelName::Mic) => body[0] == Unmuted.id(),
+            Command::SetEffectParameters => body
+                .chunks(8)
+                .any(|param| param[0..4] == mute_key && param[4..8] == [0; 4]),
+            _ => false,
+        });
+        requests.clear();
+        sent
+    }
+
+    async fn tap(device: &mut Device<'_>, inputs: &Inputs, button: Buttons) {
+        inputs.lock().unwrap().pressed = EnumSet::only(button);
+        device.monitor_inputs().await.unwrap();
+        inputs.lock().unwrap().pressed = EnumSet::empty();
+        device.monitor_inputs().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn button_test_presses_are_passed_on() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        // Outside of the test, presses are actioned as usual
+        tap(&mut device, &inputs, Buttons::Fader1Mute).await;
+        assert!(device.take_button_events().is_empty());
+
+        device.set_button_test(true).unwrap();
+        let muted = device.profile.get_fader_mute_state(FaderName::B);
+        tap(&mut device, &inputs, Buttons::Fader2Mute).await;
+        tap(&mut device, &inputs, Buttons::Cough).await;
+        let pressed: Vec<Button> = device
+            .take_button_events()
+            .into_iter()
+            .map(|event| {
+                assert_eq!(event.serial, "FULL");
+                event.button
+            })
+            .collect();
+        assert_eq!(pressed, vec![Button::Fader2Mute, Button::Cough]);
+        assert_eq!(device.profile.get_fader_mute_state(FaderName::B), muted);
+        assert!(device.take_button_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn headset_mode_puts_back_what_it_replaced() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let mic = BasicInputDevice::Microphone;
+        let headphones = BasicOutputDevice::Headphones;
+        for command in [
+            GoXLRCommand::SetMicrophoneGain(MicrophoneType::Jack, 12),
+            GoXLRCommand::SetMicrophoneGain(MicrophoneType::Dynamic, 40),
+            GoXLRCommand::SetRouter(mic, headphones, false),
+            GoXLRCommand::SetVolume(ChannelName::MicMonitor, 200),
+            GoXLRCommand::SetFader(FaderName::D, ChannelName::Chat),
+            GoXLRCommand::SetFaderMuteFunction(FaderName::D, BasicMuteFunction::All),
+        ] {
+            run(&mut device, command).await;
+        }
+
+        run(&mut device, GoXLRCommand::SetHeadsetSidetone(40)).await;
+        run(&mut device, GoXLRCommand::SetHeadsetMode(true)).await;
+
+        let status = device.status().await;
+        assert_eq!(status.mic_status.mic_type, MicrophoneType::Jack);
+        assert_eq!(status.mic_status.mic_gains[MicrophoneType::Jack], 30);
+        assert!(status.router[mic][headphones]);
+        assert_eq!(status.levels.volumes[ChannelName::MicMonitor], 102);
+        let chat = status.fader_status[FaderName::D].mute_type;
+        assert_eq!(chat, BasicMuteFunction::ToVoiceChat);
+
+        let snapshot = status.headset.snapshot.unwrap();
+        assert_eq!(snapshot.mic_type, MicrophoneType::Dynamic);
+        assert_eq!(snapshot.jack_gain, 12);
+        assert!(!snapshot.mic_monitor);
+        assert_eq!(snapshot.mic_monitor_volume, 200);
+        assert_eq!(snapshot.chat_mute_function, Some(BasicMuteFunction::All));
+
+        // The sidetone follows changes while it's enabled, and the snapshot is left alone
+        run(&mut device, GoXLRCommand::SetHeadsetSidetone(100)).await;
+        let status = device.status().await;
+        assert_eq!(status.levels.volumes[ChannelName::MicMonitor], 255);
+        assert_eq!(status.headset.sidetone, 100);
+        assert_eq!(status.headset.snapshot, Some(snapshot));
+
+        run(&mut device, GoXLRCommand::SetHeadsetMode(false)).await;
+        let status = device.status().await;
+        assert!(status.headset.snapshot.is_none());
+        assert_eq!(status.mic_status.mic_type, MicrophoneType::Dynamic);
+        assert_eq!(status.mic_status.mic_gains[MicrophoneType::Jack], 12);
+        assert_eq!(status.mic_status.mic_gains[MicrophoneType::Dynamic], 40);
+        assert!(!status.router[mic][headphones]);
+        assert_eq!(status.levels.volumes[ChannelName::MicMonitor], 200);
+        let chat = status.fader_status[FaderName::D].mute_type;
+        assert_eq!(chat, BasicMuteFunction::All);
+
+        let sidetone = GoXLRCommand::SetHeadsetSidetone(101);
+        assert!(device.perform_command(sidetone).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn privacy_mode_keeps_the_mic_muted_until_unlocked() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let config = PrivacyConfig {
+            idle_relock: None,
+            relock_on_screen_lock: true,
+        };
+        settings.set_device_privacy_mode("MINI", Some(config)).await;
+
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        // Locked from the moment the device is connected
+        assert!(device.status().await.privacy.locked);
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Muted));
+        assert!(!mic_unmute_sent(&requests));
+
+        let cough = ColourTargets::MicrophoneMute.position(0, device.device_supports_animations());
+        let colour_map = device.sent.colour_map.unwrap();
+        assert_eq!(colour_map[cough..cough + 4], PRIVACY_COLOUR);
+        let button_states = device.sent.button_states.unwrap();
+        assert_eq!(
+            button_states[Buttons::MicrophoneMute as usize],
+            ButtonStates::Colour1
+        );
+
+        run(&mut device, GoXLRCommand::SetCoughIsHold(false)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic),
+        )
+        .await;
+
+        // Asking for the Mic to be unmuted is refused outright
+        for command in [
+            GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted),
+        ] {
+            let error = device.perform_command(command).await.unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<CommandError>(),
+                Some(&CommandError::PrivacyLocked)
+            );
+        }
+        assert!(device.set_mic_muted(false).await.is_err());
+
+        // Everything else which would unmute it goes ahead, without the Mic being heard
+        tap(&mut device, &inputs, Buttons::MicrophoneMute).await;
+        tap(&mut device, &inputs, Buttons::MicrophoneMute).await;
+        tap(&mut device, &inputs, Buttons::Fader1Mute).await;
+        tap(&mut device, &inputs, Buttons::Fader1Mute).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteFunction(FaderName::A, BasicMuteFunction::All),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll),
+        )
+        .await;
+        tap(&mut device, &inputs, Buttons::Fader1Mute).await;
+        run(&mut device, GoXLRCommand::ReloadSettings()).await;
+        run(
+            &mut device,
+            GoXLRCommand::ConfirmMicrophoneType(MicrophoneType::Condenser),
+        )
+        .await;
+        assert!(!mic_unmute_sent(&requests));
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Muted));
+
+        // Unlocking puts the profile's mute state back
+        run(&mut device, GoXLRCommand::PrivacyUnlock()).await;
+        assert!(!device.status().await.privacy.locked);
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Unmuted));
+
+        device.screen_locked().await.unwrap();
+        assert!(device.status().await.privacy.locked);
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Muted));
+
+        // As does an idle period, when enabled
+        let config = PrivacyConfig {
+            idle_relock: Some(0),
+            relock_on_screen_lock: false,
+        };
+        run(&mut device, GoXLRCommand::SetPrivacyMode(Some(config))).await;
+        run(&mut device, GoXLRCommand::PrivacyUnlock()).await;
+        device.update_state().await.unwrap();
+        assert!(device.status().await.privacy.locked);
+
+        // Turning it off leaves the Mic to the profile
+        run(&mut device, GoXLRCommand::SetPrivacyMode(None)).await;
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Unmuted));
+        assert!(device.status().await.privacy.config.is_none());
+    }
+
+    #[tokio::test]
+    async fn phantom_power_is_confirmed_and_holds_the_mic_muted_while_it_settles() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        settings
+            .set_device_phantom_confirmation_required("MINI", true)
+            .await;
+
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, mini(SUBMIX_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        run(
+            &mut device,
+            GoXLRCommand::SetMicrophoneType(MicrophoneType::Dynamic),
+        )
+        .await;
+
+        let condenser = GoXLRCommand::SetMicrophoneType(MicrophoneType::Condenser);
+        let error = device.perform_command(condenser).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<CommandError>(),
+            Some(&CommandError::PhantomPowerConfirmation)
+        );
+
+        // The command returns straight away, with the Mic muted until the voltage has settled
+        run(
+            &mut device,
+            GoXLRCommand::ConfirmMicrophoneType(MicrophoneType::Condenser),
+        )
+        .await;
+        assert_eq!(device.mic_profile.mic_type(), MicrophoneType::Condenser);
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Muted));
+        assert!(device.pending_work().contains(&PendingWork::PhantomPower));
+
+        device.phantom_settle = Some(Instant::now());
+        device.update_state().await.unwrap();
+        assert_eq!(device.sent.mute_state[ChannelName::Mic], Some(Unmuted));
+        assert!(!device.pending_work().contains(&PendingWork::PhantomPower));
+    }
+
+    #[tokio::test]
+    async fn polling_slows_down_while_idle() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        for interval in [4, 101] {
+            let command = GoXLRCommand::SetPollInterval(interval);
+            assert!(device.perform_command(command).await.is_err());
+        }
+        run(&mut device, GoXLRCommand::SetPollInterval(10)).await;
+
+        let status = device.status().await;
+        assert_eq!(status.settings.poll_interval, 10);
+        assert_eq!(status.diagnostics.poll_interval, 10);
+        assert!(!status.diagnostics.polling_idle);
+        assert_eq!(settings.get_device_poll_interval(device.serial()).await, 10);
+
+        // Nothing has happened since the command, so it backs off..
+        assert!(device.update_poll_interval(Instant::now() + IDLE_AFTER));
+        let diagnostics = device.status().await.diagnostics;
+        assert_eq!(diagnostics.poll_interval, 100);
+        assert!(diagnostics.polling_idle);
+
+        // ..until a button is pressed
+        tap(&mut device, &inputs, Buttons::Bleep).await;
+        let diagnostics = device.status().await.diagnostics;
+        assert_eq!(diagnostics.poll_interval, 10);
+        assert!(!diagnostics.polling_idle);
+    }
+
+    #[tokio::test]
+    async fn guarded_sample_buttons_cant_be_cleared_in_guard_mode() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, mut events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let bank = device.profile.get_active_sample_bank();
+        let button = SampleButtons::TopLeft;
+        device
+            .profile
+            .add_sample_file(bank, button, String::from("Airhorn.wav"));
+        run(
+            &mut device,
+            GoXLRCommand::SetSampleButtonGuard(bank, button, true),
+        )
+        .await;
+
+        let sampler = device.status().await.sampler.unwrap();
+        assert!(sampler.banks[&bank][&button].guarded);
+        assert!(!sampler.banks[&bank][&SampleButtons::TopRight].guarded);
+
+        // Guarded buttons behave normally until guard mode is turned on
+        device.set_guard_mode(true);
+        device.profile.set_sample_clear_active(true);
+        tap(&mut device, &inputs, Buttons::SamplerTopLeft).await;
+
+        assert!(device.profile.current_sample_bank_has_samples(button));
+        assert!(device.profile.is_sample_clear_active());
+        assert!(device.guard_flashes[button].is_some());
+        assert_eq!(
+            device.create_button_states()[Buttons::SamplerTopLeft as usize],
+            ButtonStates::Flashing
+        );
+        match events_rx.try_recv() {
+            Ok(TTSMessage(notification)) => {
+                assert_eq!(notification.category, NotificationCategory::Guard)
+            }
+            _ => panic!("No notification was sent for the guarded press"),
+        }
+
+        device.set_guard_mode(false);
+        tap(&mut device, &inputs, Buttons::SamplerTopLeft).await;
+        assert!(!device.profile.current_sample_bank_has_samples(button));
+        assert!(!device.profile.is_sample_clear_active());
+    }
+
+    fn analysis_result(bank: SampleBank, button: SampleButtons, file: &str) -> CalculationResult {
+        CalculationResult {
+            result: Ok(()),
+            file: PathBuf::from(file),
+            converted: None,
+            bank,
+            button,
+            gain: 1.0,
+            markers: vec![],
+            duration: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn sample_ducking_restores_untouched_channels() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let bank = device.profile.get_active_sample_bank();
+        let button = SampleButtons::TopLeft;
+        let (music, game) = (ChannelName::Music, ChannelName::Game);
+        let config = DuckConfig {
+            channels: vec![music, game],
+            attenuation: 50,
+            release: 0,
+        };
+
+        let itself = DuckConfig {
+            channels: vec![ChannelName::Sample],
+            ..config.clone()
+        };
+        let command = GoXLRCommand::SetSampleDucking(bank, button, Some(itself));
+        assert!(device.perform_command(command).await.is_err());
+
+        let command = GoXLRCommand::SetSampleDucking(bank, button, Some(config.clone()));
+        run(&mut device, command).await;
+        let sampler = device.status().await.sampler.unwrap();
+        assert_eq!(sampler.banks[&bank][&button].ducking, Some(config));
+
+        run(&mut device, GoXLRCommand::SetVolume(music, 200)).await;
+        run(&mut device, GoXLRCommand::SetVolume(game, 100)).await;
+        requests.lock().unwrap().clear();
+
+        let volume_writes = || {
+            let mut requests = requests.lock().unwrap();
+            let volumes: Vec<(ChannelName, u8)> = requests
+                .iter()
+                .filter_map(|(command, body)| match command {
+                    Command::SetChannelVolume(channel) => Some((*channel, body[0])),
+                    _ => None,
+                })
+                .collect();
+            requests.clear();
+            volumes
+        };
+
+        // The profile keeps the volumes to go back to
+        device.start_sample_ducking(bank, button).unwrap();
+        assert_eq!(volume_writes(), vec![(music, 100), (game, 50)]);
+        assert_eq!(device.profile.get_channel_volume(music), 200);
+
+        // Game is moved while ducked, so stays where it was put
+        run(&mut device, GoXLRCommand::SetVolume(game, 80)).await;
+        volume_writes();
+
+        // Without an audio handler nothing is playing, so the duck ends on the next update
+        device.sync_sample_ducking().unwrap();
+        assert_eq!(volume_writes(), vec![(music, 200)]);
+        device.sync_sample_ducking().unwrap();
+        assert!(volume_writes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleared_samples_arent_added_when_their_analysis_finishes() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests, inputs) = MockGoXLR::with_inputs();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        let bank = device.profile.get_active_sample_bank();
+        let button = SampleButtons::TopLeft;
+        let analysis = |file: &str| SampleAnalysis {
+            bank,
+            button,
+            file: PathBuf::from(file),
+        };
+
+        // A slow analysis is still running when its button is cleared..
+        device.sample_analysis = Some(analysis("Huge.wav"));
+        device.profile.set_sample_clear_active(true);
+        tap(&mut device, &inputs, Buttons::SamplerTopLeft).await;
+        assert!(device.sample_analysis.is_none());
+
+        // ..so the result is dropped when it arrives
+        let result = analysis_result(bank, button, "Huge.wav");
+        device.add_analysed_sample(result).await.unwrap();
+        assert!(device.profile.get_sample_bank_tracks(bank).is_empty());
+
+        // Clearing a different button leaves it alone
+        device.sample_analysis = Some(analysis("Huge.wav"));
+        device.profile.set_sample_clear_active(true);
+        tap(&mut device, &inputs, Buttons::SamplerTopRight).await;
+        assert_eq!(device.sample_analysis, Some(analysis("Huge.wav")));
+
+        // Only the result for the file which is expected is added
+        let stale = analysis_result(bank, button, "Older.wav");
+        device.add_analysed_sample(stale).await.unwrap();
+        let result = analysis_result(bank, button, "Huge.wav");
+        device.add_analysed_sample(result).await.unwrap();
+        let tracks = device.profile.get_sample_bank_tracks(bank);
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].1, "Huge.wav");
+    }
+
+    #[tokio::test]
+    async fn sample_analysis_is_dropped_when_the_profile_changes() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let bank = device.profile.get_active_sample_bank();
+        let button = SampleButtons::BottomLeft;
+        device.sample_analysis = Some(SampleAnalysis {
+            bank,
+            button,
+            file: PathBuf::from("Huge.wav"),
+        });
+
+        let command = GoXLRCommand::NewProfile(String::from("Another Show"));
+        run(&mut device, command).await;
+        assert_eq!(device.profile.name(), "Another Show");
+
+        let result = analysis_result(bank, button, "Huge.wav");
+        device.add_analysed_sample(result).await.unwrap();
+        assert!(device.profile.get_sample_bank_tracks(bank).is_empty());
+    }
+
+    #[tokio::test]
+    async fn converted_samples_remember_their_source() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        let settings = device.settings;
+
+        run(&mut device, GoXLRCommand::SetSampleConversion(true)).await;
+        assert!(device.status().await.settings.convert_samples);
+        assert!(settings.get_device_convert_samples(device.serial()).await);
+
+        let samples = settings.get_samples_directory().await;
+        let bank = device.profile.get_active_sample_bank();
+        let button = SampleButtons::TopRight;
+        let source = samples.join("Imports").join("Airhorn.flac");
+        device.sample_analysis = Some(SampleAnalysis {
+            bank,
+            button,
+            file: source.clone(),
+        });
+
+        let mut result = analysis_result(bank, button, "");
+        result.file = source;
+        result.converted = Some(
+            samples
+                .join(CONVERTED_SAMPLES_DIRECTORY)
+                .join("Airhorn-0f.wav"),
+        );
+        device.add_analysed_sample(result).await.unwrap();
+
+        // It's played from the conversion, the original is only remembered
+        let sampler = device.status().await.sampler.unwrap();
+        let sample = &sampler.banks[&bank][&button].samples[0];
+        assert_eq!(sample.name, "Airhorn-0f.wav");
+        let expected = PathBuf::from("Imports").join("Airhorn.flac");
+        assert_eq!(sample.source.as_deref(), expected.to_str());
+
+        // Turning conversion off doesn't touch what's already been converted
+        run(&mut device, GoXLRCommand::SetSampleConversion(false)).await;
+        let sampler = device.status().await.sampler.unwrap();
+        assert_eq!(
+            sampler.banks[&bank][&button].samples[0].name,
+            "Airhorn-0f.wav"
+        );
+    }
+
+    // The Mic's channel state, the cough and fader lighting, and whether it reaches the stream
+    async fn mic_mutes(device: &Device<'_>) -> (ChannelState, MuteState, Option<MuteState>, bool) {
+        let status = device.status().await;
+        let fader = status.cough_button.mic_fader;
+        let assigned =
+            FaderName::iter().find(|f| status.fader_status[*f].channel == ChannelName::Mic);
+        assert_eq!(fader, assigned);
+
+        let routing = device
+            .get_applied_routing(BasicInputDevice::Microphone)
+            .await
+            .unwrap();
+        (
+            device.sent.mute_state[ChannelName::Mic].unwrap(),
+            status.cough_button.state,
+            fader.map(|fader| status.fader_status[fader].mute_state),
+            routing[BasicOutputDevice::BroadcastMix],
+        )
+    }
+
+    // A channel which isn't on any fader
+    fn spare_channel(device: &Device<'_>) -> ChannelName {
+        ChannelName::iter()
+            .find(|channel| {
+                FaderName::iter().all(|f| device.profile.get_fader_assignment(f) != *channel)
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn mute_functions_are_accepted_where_the_channel_can_be_routed() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        for channel in ChannelName::iter() {
+            run(&mut device, GoXLRCommand::SetFader(FaderName::A, channel)).await;
+            for function in BasicMuteFunction::iter() {
+                let set = GoXLRCommand::SetFaderMuteFunction(FaderName::A, function);
+                let accepted = device.perform_command(set).await.is_ok();
+                let routable = match function.output() {
+                    None => true,
+                    Some(output) => {
+                        BasicInputDevice::can_from(channel) && {
+                            let input = BasicInputDevice::from(channel);
+                            let route = GoXLRCommand::SetRouter(input, output, true);
+                            device.perform_command(route).await.is_ok()
+                        }
+                    }
+                };
+                assert_eq!(accepted, routable, "{function} on {channel}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn resetting_a_mute_function_is_reported() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        // Take Chat off the faders, so moving it onto one doesn't switch the mute functions over
+        let on_chat = FaderName::iter()
+            .find(|fader| device.profile.get_fader_assignment(*fader) == ChannelName::Chat)
+            .unwrap();
+        let spare = spare_channel(&device);
+        run(&mut device, GoXLRCommand::SetFader(on_chat, spare)).await;
+
+        let fader = FaderName::iter().find(|fader| *fader != on_chat).unwrap();
+        let to_voice_chat = BasicMuteFunction::ToVoiceChat;
+        let set = GoXLRCommand::SetFaderMuteFunction(fader, to_voice_chat);
+        run(&mut device, set).await;
+
+        // Chat can't be routed to the Chat Mic, so it can't be muted to Voice Chat either
+        let assign = GoXLRCommand::SetFader(fader, ChannelName::Chat);
+        run(&mut device, assign.clone()).await;
+        let all = BasicMuteFunction::All;
+        assert!(matches!(
+            device.take_command_response(&assign),
+            Some(DaemonResponse::MuteFunctionReset(reset, function))
+                if reset == fader && function == all
+        ));
+        assert_eq!(device.profile.get_mute_button_behaviour(fader), all);
+
+        // The reset is only reported by the command which made it
+        run(&mut device, assign.clone()).await;
+        assert!(device.take_command_response(&assign).is_none());
+    }
+
+    #[tokio::test]
+    async fn channels_changed_follow_the_fader_assignments() {
+        let (device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let on_a = device.profile.get_fader_assignment(FaderName::A);
+        let spare = spare_channel(&device);
+        let mute = GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll);
+        assert_eq!(device.channels_changed_by(&mute), vec![on_a]);
+
+        // Moving a channel onto a fader changes the one it replaces as well
+        let assign = GoXLRCommand::SetFader(FaderName::A, spare);
+        assert_eq!(device.channels_changed_by(&assign), vec![on_a, spare]);
+
+        let route = GoXLRCommand::SetRouter(
+            BasicInputDevice::Music,
+            BasicOutputDevice::Headphones,
+            false,
+        );
+        assert_eq!(device.channels_changed_by(&route), vec![ChannelName::Music]);
+        let colours = GoXLRCommand::SetGlobalColour(String::from("FF0000"));
+        assert!(device.channels_changed_by(&colours).is_empty());
+    }
+
+    #[tokio::test]
+    async fn fader_changes_are_sent_together() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let unmuted = GoXLRCommand::SetFaderMuteState(FaderName::B, MuteState::Unmuted);
+        run(&mut device, unmuted).await;
+        let take_commands = || -> Vec<Command> {
+            let mut requests = requests.lock().unwrap();
+            let commands = requests.iter().map(|(command, _)| *command).collect();
+            requests.clear();
+            commands
+        };
+        take_commands();
+
+        // Moving a channel onto a fader redraws its scribble along with the lighting
+        let spare = spare_channel(&device);
+        let existing = device.profile.get_fader_assignment(FaderName::B);
+        run(&mut device, GoXLRCommand::SetFader(FaderName::B, spare)).await;
+        assert_eq!(
+            take_commands(),
+            vec![
+                Command::SetFader(FaderName::B),
+                Command::SetChannelVolume(existing),
+                Command::SetColourMap(),
+                Command::SetScribble(FaderName::B),
+                Command::SetButtonStates(),
+            ]
+        );
+
+        // Switching two faders sends everything which moves with them, still in one go
+        run(&mut device, GoXLRCommand::SetFader(FaderName::A, spare)).await;
+        assert_eq!(
+            take_commands(),
+            vec![
+                Command::SetFader(FaderName::A),
+                Command::SetFader(FaderName::B),
+                Command::SetColourMap(),
+                Command::SetFaderDisplayMode(FaderName::A),
+                Command::SetFaderDisplayMode(FaderName::B),
+                Command::SetScribble(FaderName::A),
+                Command::SetScribble(FaderName::B),
+                Command::SetButtonStates(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cough_and_mic_fader_mutes_combine() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        run(&mut device, GoXLRCommand::SetCoughIsHold(false)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFaderMuteFunction(FaderName::A, BasicMuteFunction::All),
+        )
+        .await;
+
+        let states = [MuteState::Unmuted, MuteState::MutedToAll];
+        for cough in states {
+            for fader in states {
+                // Whichever order they're set in, either one is enough to mute the Mic..
+                for (first, second) in [
+                    (
+                        GoXLRCommand::SetCoughMuteState(cough),
+                        GoXLRCommand::SetFaderMuteState(FaderName::A, fader),
+                    ),
+                    (
+                        GoXLRCommand::SetFaderMuteState(FaderName::A, fader),
+                        GoXLRCommand::SetCoughMuteState(cough),
+                    ),
+                ] {
+                    run(&mut device, first).await;
+                    run(&mut device, second).await;
+
+                    let muted = cough != MuteState::Unmuted || fader != MuteState::Unmuted;
+                    let expected = if muted { Muted } else { Unmuted };
+                    let (state, cough_state, fader_state, _) = mic_mutes(&device).await;
+                    assert_eq!(state, expected, "Cough {:?}, Fader {:?}", cough, fader);
+                    assert_eq!((cough_state, fader_state), (cough, Some(fader)));
+                }
+
+                // ..and releasing one leaves the other in charge
+                run(
+                    &mut device,
+                    GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+                )
+                .await;
+                let expected = if fader == MuteState::Unmuted {
+                    Unmuted
+                } else {
+                    Muted
+                };
+                assert_eq!(mic_mutes(&device).await.0, expected);
+
+                run(&mut device, GoXLRCommand::SetCoughMuteState(cough)).await;
+                let unmute = GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::Unmuted);
+                run(&mut device, unmute).await;
+                let expected = if cough == MuteState::Unmuted {
+                    Unmuted
+                } else {
+                    Muted
+                };
+                assert_eq!(mic_mutes(&device).await.0, expected);
+                run(
+                    &mut device,
+                    GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+                )
+                .await;
+            }
+        }
+
+        // Without a fader, the cough button behaves the same on its own
+        let spare = spare_channel(&device);
+        run(&mut device, GoXLRCommand::SetFader(FaderName::A, spare)).await;
+        for cough in states {
+            run(&mut device, GoXLRCommand::SetCoughMuteState(cough)).await;
+            let expected = if cough == MuteState::Unmuted {
+                Unmuted
+            } else {
+                Muted
+            };
+            assert_eq!(
+                mic_mutes(&device).await,
+                (expected, cough, None, expected == Unmuted)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn the_mic_can_change_faders_while_cough_muted() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        run(&mut device, GoXLRCommand::SetCoughIsHold(false)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic),
+        )
+        .await;
+
+        // Muted to Stream only removes the Mic from the Broadcast Mix..
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteFunction(BasicMuteFunction::ToStream),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToX),
+        )
+        .await;
+        let muted_to_stream = (Unmuted, MuteState::MutedToX);
+        let (state, cough, fader, broadcast) = mic_mutes(&device).await;
+        assert_eq!(
+            ((state, cough), fader, broadcast),
+            (muted_to_stream, Some(MuteState::Unmuted), false)
+        );
+
+        // ..which stays that way as the Mic leaves its fader, and arrives on another
+        let spare = spare_channel(&device);
+        run(&mut device, GoXLRCommand::SetFader(FaderName::A, spare)).await;
+        let (state, cough, fader, broadcast) = mic_mutes(&device).await;
+        assert_eq!(
+            ((state, cough), fader, broadcast),
+            (muted_to_stream, None, false)
+        );
+
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::B, ChannelName::Mic),
+        )
+        .await;
+        let (state, cough, fader, broadcast) = mic_mutes(&device).await;
+        assert_eq!(
+            ((state, cough), fader, broadcast),
+            (muted_to_stream, Some(MuteState::Unmuted), false)
+        );
+        assert_eq!(device.profile.get_mic_fader(), Some(FaderName::B));
+        assert!(device.profile.get_mute_chat_button_on());
+
+        // Muted to All, the Mic stays muted when its muted fader is taken away
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToAll),
+        )
+        .await;
+        let mute = GoXLRCommand::SetFaderMuteState(FaderName::B, MuteState::MutedToAll);
+        run(&mut device, mute).await;
+        let spare = spare_channel(&device);
+        run(&mut device, GoXLRCommand::SetFader(FaderName::B, spare)).await;
+        assert_eq!(
+            mic_mutes(&device).await,
+            (Muted, MuteState::MutedToAll, None, false)
+        );
+        let (muted_to_x, muted_to_all, _) = device.profile.get_mute_button_state(FaderName::B);
+        assert!(!muted_to_x && !muted_to_all);
+        assert!(device.profile.get_mute_chat_button_blink());
+
+        // Swapping faders carries the Mic's fader mute along with it
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::C, ChannelName::Mic),
+        )
+        .await;
+        let mute = GoXLRCommand::SetFaderMuteState(FaderName::C, MuteState::MutedToAll);
+        run(&mut device, mute).await;
+        let moved = device.profile.get_fader_assignment(FaderName::D);
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::D, ChannelName::Mic),
+        )
+        .await;
+        assert_eq!(device.profile.get_fader_assignment(FaderName::C), moved);
+        let state = mic_mutes(&device).await;
+        assert_eq!(
+            state,
+            (
+                Muted,
+                MuteState::MutedToAll,
+                Some(MuteState::MutedToAll),
+                false
+            )
+        );
+
+        // Unmuting the cough button leaves the fader holding the Mic muted, until it's unmuted too
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+        )
+        .await;
+        assert_eq!(mic_mutes(&device).await.0, Muted);
+        let unmute = GoXLRCommand::SetFaderMuteState(FaderName::D, MuteState::Unmuted);
+        run(&mut device, unmute).await;
+        let state = mic_mutes(&device).await;
+        assert_eq!(
+            state,
+            (Unmuted, MuteState::Unmuted, Some(MuteState::Unmuted), true)
+        );
+    }
+
+    #[tokio::test]
+    async fn output_activity_follows_the_applied_routing() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        // Whatever happens, the activity must list exactly what's routed on the device
+        async fn check(device: &Device<'_>) -> EnumMap<BasicOutputDevice, OutputActivity> {
+            let activity = device.status().await.output_activity;
+            for input in BasicInputDevice::iter() {
+                let routing = device.get_applied_routing(input).await.unwrap();
+                for output in BasicOutputDevice::iter() {
+                    assert_eq!(activity[output].inputs.contains(&input), routing[output]);
+                }
+            }
+            activity
+        }
+
+        let activity = check(&device).await;
+        assert!(!activity[BasicOutputDevice::Headphones].likely_silent);
+
+        // Muting the Mic removes it from everything
+        let mute = GoXLRCommand::SetCoughMuteState(MuteState::MutedToAll);
+        run(&mut device, mute).await;
+        let activity = check(&device).await;
+        assert!(activity
+            .values()
+            .all(|output| !output.inputs.contains(&BasicInputDevice::Microphone)));
+
+        // Nothing routed to an output is silent, but not muted..
+        for input in BasicInputDevice::iter() {
+            let command = GoXLRCommand::SetRouter(input, BasicOutputDevice::LineOut, false);
+            run(&mut device, command).await;
+        }
+        let line_out = &check(&device).await[BasicOutputDevice::LineOut];
+        assert!(line_out.inputs.is_empty());
+        assert!(line_out.likely_silent && !line_out.all_muted);
+
+        // ..and neither are the headphones once they're turned all the way down
+        run(
+            &mut device,
+            GoXLRCommand::SetVolume(ChannelName::Headphones, 0),
+        )
+        .await;
+        let headphones = &check(&device).await[BasicOutputDevice::Headphones];
+        assert!(!headphones.inputs.is_empty());
+        assert!(headphones.likely_silent && !headphones.all_muted);
+    }
+
+    #[tokio::test]
+    async fn status_summary_stays_small() {
+        let (mut device, _requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        run(
+            &mut device,
+            GoXLRCommand::SetVolume(ChannelName::Headphones, 180),
+        )
+        .await;
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToAll),
+        )
+        .await;
+
+        let summary = device.get_summary();
+        assert_eq!(summary.serial, device.serial());
+        assert_eq!(summary.device_type, DeviceType::Full);
+        assert_eq!(summary.profile_name, device.profile.name());
+        assert!(summary.mic_muted);
+        assert_eq!(summary.cough_state, MuteState::MutedToAll);
+        assert_eq!(summary.headphones_volume, 180);
+
+        // Pollers ask for this every second or so, it mustn't grow into another status
+        let size = serde_json::to_vec(&summary).unwrap().len();
+        assert!(size < 256, "The summary serialised to {} bytes", size);
+
+        let status = serde_json::to_vec(&device.status().await).unwrap().len();
+        assert!(
+            size * 20 < status,
+            "{} bytes, against {} for the status",
+            size,
+            status
+        );
+    }
+
+    #[tokio::test]
+    async fn controls_resolve_to_the_fader_or_button_responsible() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        for hardware in [full(FULL_FIRMWARE), mini(SUBMIX_FIRMWARE)] {
+            let is_mini = hardware.device_type == DeviceType::Mini;
+            let (events, _events_rx) = mpsc::channel(64);
+            let (goxlr, _) = MockGoXLR::new();
+            let mut device = Device::new(goxlr, hardware, &settings, events)
+                .await
+                .unwrap();
+
+            let fader = FaderName::A;
+            run(
+                &mut device,
+                GoXLRCommand::SetFaderMuteState(fader, MuteState::MutedToAll),
+            )
+            .await;
+            let status = device.status().await;
+
+            let mut on_faders = 0;
+            for channel in ChannelName::iter() {
+                let resolved = device.resolve_control(ControlQuery::ChannelFader(channel));
+                let assigned =
+                    FaderName::iter().find(|f| status.fader_status[*f].channel == channel);
+                assert_eq!(resolved.control, assigned.map(PhysicalControl::Fader));
+                assert!(resolved.available);
+                if let Some(assigned) = assigned {
+                    on_faders += 1;
+                    assert_eq!(resolved.volume, Some(status.levels.volumes[channel]));
+                    assert_eq!(resolved.muted, Some(assigned == fader));
+                } else {
+                    assert_eq!(resolved.volume, None);
+                    assert_eq!(resolved.muted, None);
+                }
+            }
+            assert_eq!(on_faders, 4);
+
+            for effect in EffectName::iter() {
+                let resolved = device.resolve_control(ControlQuery::EffectButton(effect));
+                assert!(matches!(resolved.control, Some(PhysicalControl::Button(_))));
+                assert_eq!(resolved.available, !is_mini);
+                assert!(!is_mini || !resolved.lit);
+            }
+
+            let active = device.profile.get_active_sample_bank();
+            for bank in SampleBank::iter() {
+                for pad in SampleButtons::iter() {
+                    let resolved = device.resolve_control(ControlQuery::SamplePad(bank, pad));
+                    assert!(matches!(resolved.control, Some(PhysicalControl::Button(_))));
+                    assert_eq!(resolved.available, !is_mini);
+                    if is_mini || bank != active {
+                        assert!(!resolved.lit);
+                    }
+                }
+            }
+
+            let cough = device.resolve_control(ControlQuery::CoughButton);
+            assert_eq!(cough.control, Some(PhysicalControl::Button(Button::Cough)));
+            assert!(cough.available);
+            assert_eq!(cough.muted, Some(false));
+
+            let bleep = device.resolve_control(ControlQuery::BleepButton);
+            assert_eq!(bleep.control, Some(PhysicalControl::Button(Button::Bleep)));
+            assert!(bleep.available);
+            assert_eq!(bleep.muted, None);
+        }
+    }
+
+    #[test]
+    fn balance_turns_down_the_other_side() {
+        assert_eq!(balance_levels(0), (0x20, 0x20));
+        assert_eq!(balance_levels(50), (0, 0x20));
+        assert_eq!(balance_levels(-50), (0x20, 0));
+        assert_eq!(balance_levels(25), (0x10, 0x20));
+        assert_eq!(balance_levels(-25), (0x20, 0x10));
+
+        // Small changes still move away from the centre
+        assert_eq!(balance_levels(1), (31, 0x20));
+        assert_eq!(balance_levels(i8::MIN), (0x20, 0));
+
+        let mut previous = balance_levels(-BALANCE_LIMIT);
+        for balance in -BALANCE_LIMIT + 1..=BALANCE_LIMIT {
+            let levels = balance_levels(balance);
+            assert!(levels.0 <= previous.0 && levels.1 >= previous.1);
+            previous = levels;
+        }
+    }
+
+    #[tokio::test]
+    async fn balance_is_applied_to_every_routed_output() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        let line_in = BasicInputDevice::LineIn;
+        let (left_input, right_input) = InputDevice::from_basic(&line_in);
+        let written = |input: InputDevice| {
+            let requests = requests.lock().unwrap();
+            requests
+                .iter()
+                .rev()
+                .find(|(command, _)| *command == Command::SetRouting(input))
+                .map(|(_, body)| body.clone())
+                .unwrap()
+        };
+
+        run(
+            &mut device,
+            GoXLRCommand::SetChannelBalance(ChannelName::LineIn, -25),
+        )
+        .await;
+        let router = device.profile.get_router(line_in);
+        let (left, right) = (written(left_input), written(right_input));
+        for output in BasicOutputDevice::iter() {
+            let (left_output, right_output) = OutputDevice::from_basic(&output);
+            let routed = router[output];
+            assert_eq!(left[left_output.position()], if routed { 0x20 } else { 0 });
+            assert_eq!(
+                right[right_output.position()],
+                if routed { 0x10 } else { 0 }
+            );
+        }
+
+        // Routing changes keep the balance
+        let output = BasicOutputDevice::LineOut;
+        let command = GoXLRCommand::SetRouter(line_in, output, true);
+        run(&mut device, command).await;
+        let (_, right_output) = OutputDevice::from_basic(&output);
+        assert_eq!(written(right_input)[right_output.position()], 0x10);
+
+        let status = device.status().await;
+        assert_eq!(status.levels.input_balance[line_in], -25);
+
+        // Outputs and out of range values can't be balanced
+        let headphones = GoXLRCommand::SetChannelBalance(ChannelName::Headphones, 10);
+        assert!(device.perform_command(headphones).await.is_err());
+        let too_far = GoXLRCommand::SetChannelBalance(ChannelName::LineIn, 51);
+        assert!(device.perform_command(too_far).await.is_err());
+        let status = device.status().await;
+        assert_eq!(status.levels.input_balance[line_in], -25);
+    }
+
+    #[tokio::test]
+    async fn voice_chat_mute_outputs_combine_with_the_cough_button() {
+        let (mut device, _, _root) = new_device(full(FULL_FIRMWARE)).await;
+
+        // Route the Mic everywhere, so every removal can be seen
+        let mic = BasicInputDevice::Microphone;
+        for output in BasicOutputDevice::iter() {
+            run(&mut device, GoXLRCommand::SetRouter(mic, output, true)).await;
+        }
+        let chat = FaderName::D;
+        run(&mut device, GoXLRCommand::SetFader(chat, ChannelName::Chat)).await;
+        let all = BasicMuteFunction::All;
+        run(&mut device, GoXLRCommand::SetFaderMuteFunction(chat, all)).await;
+
+        use BasicOutputDevice::{BroadcastMix, ChatMic, LineOut};
+        let outputs = vec![LineOut, ChatMic, LineOut];
+        run(
+            &mut device,
+            GoXLRCommand::SetVCMuteAdditionalOutputs(outputs),
+        )
+        .await;
+        let status = device.status().await;
+        assert_eq!(status.settings.vc_mute_outputs, vec![ChatMic, LineOut]);
+        assert!(status.settings.vc_mute_also_mute_cm);
+        assert!(!status.settings.vc_mute_outputs_active);
+
+        async fn removed(device: &Device<'_>) -> Vec<BasicOutputDevice> {
+            let routing = device
+                .get_applied_routing(BasicInputDevice::Microphone)
+                .await
+                .unwrap();
+            BasicOutputDevice::iter()
+                .filter(|output| !routing[*output])
+                .collect()
+        }
+
+        let to_stream = BasicMuteFunction::ToStream;
+        run(&mut device, GoXLRCommand::SetCoughMuteFunction(to_stream)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToX),
+        )
+        .await;
+        assert_eq!(removed(&device).await, vec![BroadcastMix]);
+
+        let muted = GoXLRCommand::SetFaderMuteState(chat, MuteState::MutedToAll);
+        run(&mut device, muted.clone()).await;
+        assert_eq!(removed(&device).await, vec![BroadcastMix, ChatMic, LineOut]);
+        assert!(device.status().await.settings.vc_mute_outputs_active);
+
+        // Unmuting either one leaves the other's removals in place
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::Unmuted),
+        )
+        .await;
+        assert_eq!(removed(&device).await, vec![ChatMic, LineOut]);
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToX),
+        )
+        .await;
+        let unmuted = GoXLRCommand::SetFaderMuteState(chat, MuteState::Unmuted);
+        run(&mut device, unmuted.clone()).await;
+        assert_eq!(removed(&device).await, vec![BroadcastMix]);
+        assert!(!device.status().await.settings.vc_mute_outputs_active);
+
+        // The cough button still removes an output the Voice Chat mute also removes
+        let to_chat = BasicMuteFunction::ToVoiceChat;
+        run(&mut device, GoXLRCommand::SetCoughMuteFunction(to_chat)).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetCoughMuteState(MuteState::MutedToX),
+        )
+        .await;
+        run(&mut device, muted).await;
+        assert_eq!(removed(&device).await, vec![ChatMic, LineOut]);
+        run(&mut device, unmuted).await;
+        assert_eq!(removed(&device).await, vec![ChatMic]);
+
+        // The original setting only adds or removes the Chat Mic
+        run(&mut device, GoXLRCommand::SetVCMuteAlsoMuteCM(false)).await;
+        let status = device.status().await;
+        assert_eq!(status.settings.vc_mute_outputs, vec![LineOut]);
+        assert!(!status.settings.vc_mute_also_mute_cm);
+    }
+
+    #[tokio::test]
+    async fn voice_chat_mute_setting_is_migrated_to_outputs() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("settings.json");
+        let legacy = serde_json::json!({
+            "devices": {
+                "OFF": { "chat_mute_mutes_mic_to_chat": false },
+                "ON": { "chat_mute_mutes_mic_to_chat": true },
+            }
+        });
+        std::fs::write(&path, legacy.to_string()).unwrap();
+        let settings = SettingsHandle::load(path, WritePolicy::new(true))
+            .await
+            .unwrap();
+
+        let chat_mic = BasicOutputDevice::ChatMic;
+        assert!(settings.get_device_vc_mute_outputs("OFF").await.is_empty());
+        assert_eq!(
+            settings.get_device_vc_mute_outputs("ON").await,
+            vec![chat_mic]
+        );
+        assert_eq!(
+            settings.get_device_vc_mute_outputs("NEW").await,
+            vec![chat_mic]
+        );
+
+        let line_out = vec![BasicOutputDevice::LineOut];
+        settings
+            .set_device_vc_mute_outputs("OFF", line_out.clone())
+            .await;
+        assert_eq!(settings.get_device_vc_mute_outputs("OFF").await, line_out);
+    }
+
+    #[tokio::test]
+    async fn child_profiles_follow_their_base() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let profiles = settings.get_profile_directory().await;
+
+        // The child only differs from its base in the routing
+        let (game, headphones) = (BasicInputDevice::Game, BasicOutputDevice::Headphones);
+        let routed = device.profile.get_router(game)[headphones];
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Sample),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Base".into())).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetRouter(game, headphones, !routed),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Child".into())).await;
+        run(&mut device, GoXLRCommand::RebaseProfile("Base".into())).await;
+        run(&mut device, GoXLRCommand::SaveProfile()).await;
+
+        // Which is all its file holds, the fader's channel is left to the base
+        let file = std::fs::File::open(profiles.join("Child.goxlr")).unwrap();
+        let saved = Profile::load(file).unwrap();
+        let base = saved.settings().base_profile().unwrap();
+        assert!(base.owns(BaseSection::Routing));
+        assert!(!base.owns(BaseSection::Faders));
+        assert!(matches!(
+            saved.settings().fader(Faders::A).channel(),
+            FullChannelList::Mic
+        ));
+
+        // Changes to the base are picked up when the child is next loaded
+        let load = |name: &str| GoXLRCommand::LoadProfile(name.into(), false, false);
+        run(&mut device, load("Base")).await;
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Chat),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SaveProfile()).await;
+        run(&mut device, load("Child")).await;
+
+        let status = device.status().await;
+        assert_eq!(status.fader_status[FaderName::A].channel, ChannelName::Chat);
+        assert_eq!(device.profile.get_router(game)[headphones], !routed);
+
+        let inheritance = status.profile_inheritance.unwrap();
+        assert_eq!(inheritance.chain, vec![String::from("Base")]);
+        assert_eq!(inheritance.sources[ProfileSection::Routing], "Child");
+        assert_eq!(inheritance.sources[ProfileSection::Faders], "Base");
+    }
+
+    #[tokio::test]
+    async fn profile_bases_cant_form_a_loop() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let profiles = settings.get_profile_directory().await;
+
+        run(&mut device, GoXLRCommand::SaveProfileAs("Base".into())).await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Child".into())).await;
+        run(&mut device, GoXLRCommand::RebaseProfile("Base".into())).await;
+        run(&mut device, GoXLRCommand::SaveProfile()).await;
+
+        let rebase = |name: &str| GoXLRCommand::RebaseProfile(name.into());
+        let error = device.perform_command(rebase("Child")).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("itself"));
+
+        // The child is based on Base, so Base can't be based on the child
+        let load = GoXLRCommand::LoadProfile("Base".into(), false, false);
+        run(&mut device, load).await;
+        let error = device.perform_command(rebase("Child")).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("loop"));
+        assert!(device.status().await.profile_inheritance.is_none());
+
+        // A loop made on disk still loads, with everything from the profile itself
+        std::fs::copy(profiles.join("Child.goxlr"), profiles.join("Base.goxlr")).unwrap();
+        let load = GoXLRCommand::LoadProfile("Child".into(), false, false);
+        run(&mut device, load).await;
+        let inheritance = device.status().await.profile_inheritance.unwrap();
+        assert!(inheritance.sources.values().all(|source| source == "Child"));
+    }
+
+    #[tokio::test]
+    async fn rebasing_keeps_the_current_settings() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _requests) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, full(FULL_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+
+        run(&mut device, GoXLRCommand::SaveProfileAs("Base".into())).await;
+        let colours = GoXLRCommand::SetFaderColours(FaderName::A, "FF0000".into(), "00FF00".into());
+        run(&mut device, colours).await;
+        run(&mut device, GoXLRCommand::SaveProfileAs("Child".into())).await;
+        run(&mut device, GoXLRCommand::RebaseProfile("Base".into())).await;
+
+        // Only the lighting differs from the base, and it's unchanged by the rebase
+        let status = device.status().await;
+        let fader = &status.lighting.faders[&FaderName::A];
+        assert_eq!(fader.colours.colour_one, "FF0000");
+        assert!(status.profile_dirty);
+        let inheritance = status.profile_inheritance.unwrap();
+        for (section, source) in inheritance.sources {
+            let expected = match section {
+                ProfileSection::Lighting => "Child",
+                _ => "Base",
+            };
+            assert_eq!(source, expected, "{:?}", section);
+        }
+
+        // Anything changed after the rebase belongs to the child once it's saved
+        run(
+            &mut device,
+            GoXLRCommand::SetFader(FaderName::A, ChannelName::Sample),
+        )
+        .await;
+        run(&mut device, GoXLRCommand::SaveProfile()).await;
+        let inheritance = device.status().await.profile_inheritance.unwrap();
+        assert_eq!(inheritance.sources[ProfileSection::Faders], "Child");
+        assert_eq!(inheritance.sources[ProfileSection::Effects], "Base");
+
+        run(&mut device, GoXLRCommand::ClearProfileBase()).await;
+        assert!(device.status().await.profile_inheritance.is_none());
+    }
+
+    // Reports positions from fader A (the others stay put), and returns its channel's volume
+    async fn report_fader_a(device: &mut Device<'_>, positions: &[u8]) -> u8 {
+        for position in positions {
+            let volumes = [*position, 0, 0, 0];
+            device.update_volumes_to(volumes).await.unwrap();
+        }
+        let channel = device.profile.get_fader_assignment(FaderName::A);
+        device.profile.get_channel_volume(channel)
+    }
+
+    #[tokio::test]
+    async fn commanded_and_hardware_volumes_take_turns() {
+        let (mut device, requests, _root) = new_device(full(FULL_FIRMWARE)).await;
+        run(&mut device, GoXLRCommand::SetSubMixEnabled(false)).await;
+        requests.lock().unwrap().clear();
+
+        let channel = device.profile.get_fader_assignment(FaderName::A);
+
+        // Reports while the fader travels to a set volume are ignored, even if it overshoots
+        run(&mut device, GoXLRCommand::SetVolume(channel, 200)).await;
+        assert_eq!(
+            report_fader_a(&mut device, &[60, 120, 180, 210, 201]).await,
+            200
+        );
+
+        // Once it's arrived, a human moving it wins
+        assert_eq!(report_fader_a(&mut device, &[150]).await, 150);
+
+        // As does one pushing it back while it travels
+        run(&mut device, GoXLRCommand::SetVolume(channel, 40)).await;
+        assert_eq!(report_fader_a(&mut device, &[120, 100]).await, 40);
+        assert_eq!(report_fader_a(&mut device, &[140]).await, 140);
+
+        // A target at the edge doesn't leave the fader stuck
+        run(&mut device, GoXLRCommand::SetVolume(channel, 255)).await;
+        assert_eq!(report_fader_a(&mut device, &[252, 252]).await, 255);
+        assert_eq!(report_fader_a(&mut device, &[230]).await, 230);
+
+        // Only the commands were written, the hardware already had its own positions
+        let requests = requests.lock().unwrap();
+        let set = |volume: u8| (Command::SetChannelVolume(channel), vec![volume]);
+        assert_eq!(*requests, vec![set(200), set(40), set(255)]);
+    }
+}
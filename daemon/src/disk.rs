@@ -0,0 +1,248 @@
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use goxlr_ipc::{CommandError, LowDiskSpace};
+use log::debug;
+
+use crate::settings::SettingsHandle;
+
+// Left free after a profile or settings write, so a save never fills the disk completely
+const WRITE_HEADROOM: u64 = 1024 * 1024;
+
+// Needed before a sample recording starts, about three minutes of the Sampler's audio
+pub const RECORDING_SPACE: u64 = 64 * 1024 * 1024;
+
+// Directories on a filesystem with less than this free are reported in the status
+pub const LOW_SPACE_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// The filesystem operations used to save files, so tests can make them fail.
+pub trait FileSystem: Sync {
+    /// The space available on the filesystem holding a path, or where it would be created.
+    fn available_space(&self, path: &Path) -> io::Result<u64>;
+
+    /// Creates (or replaces) a file, only returning once the contents are on disk.
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+}
+
+pub struct DiskFileSystem;
+
+impl FileSystem for DiskFileSystem {
+    fn available_space(&self, path: &Path) -> io::Result<u64> {
+        let existing = path
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .ok_or(ErrorKind::NotFound)?;
+        platform_available_space(existing)
+    }
+
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(contents)?;
+        file.sync_all()
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+}
+
+// The block counts are u32 on some platforms, and u64 on others
+#[cfg(target_family = "unix")]
+#[allow(clippy::useless_conversion)]
+fn platform_available_space(path: &Path) -> io::Result<u64> {
+    let stat = nix::sys::statvfs::statvfs(path)?;
+    Ok(u64::from(stat.blocks_available()) * u64::from(stat.fragment_size()))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_available_space(path: &Path) -> io::Result<u64> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let mut available = 0;
+    unsafe { GetDiskFreeSpaceExW(&HSTRING::from(path), Some(&mut available), None, None) }?;
+    Ok(available)
+}
+
+/// Refuses with `CommandError::InsufficientDiskSpace` if there's less than `needed` bytes free
+/// for a path. If the space can't be checked the write is allowed to go ahead, it'll fail on its
+/// own if there really isn't room.
+pub fn check_space(file_system: &dyn FileSystem, path: &Path, needed: u64) -> Result<()> {
+    let available = match file_system.available_space(path) {
+        Ok(available) => available,
+        Err(error) => {
+            debug!("Unable to check the free space for {:?}: {}", path, error);
+            return Ok(());
+        }
+    };
+
+    if available < needed {
+        return Err(CommandError::InsufficientDiskSpace {
+            path: path.to_path_buf(),
+            needed,
+            available,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Writes a file through a temporary file which is renamed over it once complete, so a write
+/// which fails part way through leaves the existing file as it was.
+pub fn write_atomic(file_system: &dyn FileSystem, path: &Path, contents: &[u8]) -> Result<()> {
+    check_space(file_system, path, contents.len() as u64 + WRITE_HEADROOM)?;
+
+    let mut tmp_file_name = path.to_path_buf();
+    tmp_file_name.set_extension("tmp");
+
+    debug!("Writing {:?} through {:?}", path, tmp_file_name);
+    let result = file_system
+        .write_file(&tmp_file_name, contents)
+        .and_then(|()| file_system.rename(&tmp_file_name, path));
+
+    if let Err(error) = result {
+        let _ = file_system.remove_file(&tmp_file_name);
+        return Err(error).with_context(|| format!("Unable to write {}", path.to_string_lossy()));
+    }
+    Ok(())
+}
+
+/// The directories the daemon writes to, which are on a filesystem running out of space.
+pub async fn find_low_space(settings: &SettingsHandle) -> Vec<LowDiskSpace> {
+    let mut directories: Vec<PathBuf> = settings
+        .get_config_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .into_iter()
+        .collect();
+    directories.extend([
+        settings.get_profile_directory().await,
+        settings.get_mic_profile_directory().await,
+        settings.get_samples_directory().await,
+        settings.get_presets_directory().await,
+        settings.get_icons_directory().await,
+        settings.get_log_directory().await,
+        settings.get_backup_directory().await,
+    ]);
+
+    directories
+        .into_iter()
+        .filter_map(|path| {
+            let available = DiskFileSystem.available_space(&path).ok()?;
+            (available < LOW_SPACE_THRESHOLD).then_some(LowDiskSpace { path, available })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::ProfileAdapter;
+    use crate::settings::WritePolicy;
+
+    // Reports a fixed amount of free space, and (if set) runs out of it half way through writes
+    struct TestFileSystem {
+        available: u64,
+        fail_writes: bool,
+    }
+
+    impl FileSystem for TestFileSystem {
+        fn available_space(&self, _path: &Path) -> io::Result<u64> {
+            Ok(self.available)
+        }
+
+        fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+            if self.fail_writes {
+                fs::write(path, &contents[..contents.len() / 2])?;
+                return Err(io::Error::other("No space left on device"));
+            }
+            DiskFileSystem.write_file(path, contents)
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+            DiskFileSystem.rename(from, to)
+        }
+
+        fn remove_file(&self, path: &Path) -> io::Result<()> {
+            DiskFileSystem.remove_file(path)
+        }
+    }
+
+    const FAILING: TestFileSystem = TestFileSystem {
+        available: u64::MAX,
+        fail_writes: true,
+    };
+
+    #[test]
+    fn failed_writes_leave_the_original_file() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("file.json");
+        fs::write(&path, "original").unwrap();
+
+        assert!(write_atomic(&FAILING, &path, b"replacement contents").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert!(!path.with_extension("tmp").exists());
+
+        write_atomic(&DiskFileSystem, &path, b"replacement contents").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "replacement contents");
+    }
+
+    #[test]
+    fn writes_are_refused_without_enough_space() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("file.json");
+        let full = TestFileSystem {
+            available: 1000,
+            fail_writes: false,
+        };
+
+        let error = write_atomic(&full, &path, b"contents").unwrap_err();
+        let expected = CommandError::InsufficientDiskSpace {
+            path: path.clone(),
+            needed: 8 + WRITE_HEADROOM,
+            available: 1000,
+        };
+        assert_eq!(error.downcast_ref::<CommandError>(), Some(&expected));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn profile_saves_survive_a_full_disk() {
+        let root = tempfile::tempdir().unwrap();
+        let mut profile = ProfileAdapter::default();
+        profile.save(root.path(), true).unwrap();
+
+        let path = root.path().join(format!("{}.goxlr", profile.name()));
+        let saved = fs::read(&path).unwrap();
+
+        assert!(profile.save_with(&FAILING, root.path(), true).is_err());
+        assert_eq!(fs::read(&path).unwrap(), saved);
+    }
+
+    #[tokio::test]
+    async fn settings_save_failures_are_reported() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("settings.json");
+        let settings = SettingsHandle::load(path.clone(), WritePolicy::new(false))
+            .await
+            .unwrap();
+        let saved = fs::read(&path).unwrap();
+        assert!(settings.get_save_error().await.is_none());
+
+        settings.save_with(&FAILING).await;
+        assert!(settings.get_save_error().await.is_some());
+        assert_eq!(fs::read(&path).unwrap(), saved);
+
+        // The next save which works clears it
+        settings.save().await;
+        assert!(settings.get_save_error().await.is_none());
+    }
+}
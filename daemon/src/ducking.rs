@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use enum_map::EnumMap;
+use goxlr_ipc::DuckConfig;
+use goxlr_types::{ChannelName, SampleBank, SampleButtons};
+use strum::IntoEnumIterator;
+use tokio::time::Instant;
+
+/*
+ * Sample buttons can duck (lower) other channels while they're playing. Several buttons may hold
+ * a channel down at once, so each channel keeps the volume it had before the first of them
+ * started, and only goes back to it once the last one has stopped. There's no way to fade a
+ * volume on the device, so releases are stepped each time the device's state is updated.
+ *
+ * Ducking never touches the profile, so a channel whose profile volume no longer matches what it
+ * had before the duck has been changed by hand (or a scene, or a mute), and is let go of where
+ * it was put.
+ */
+
+type Holder = (SampleBank, SampleButtons);
+
+#[derive(Debug, Copy, Clone)]
+struct Hold {
+    holder: Holder,
+    attenuation: u8,
+    release: u16,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Release {
+    from: u8,
+    started: Instant,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+struct DuckedChannel {
+    original: u8,
+
+    // The volume last sent to the device
+    level: u8,
+    holds: Vec<Hold>,
+    release: Option<Release>,
+}
+
+impl DuckedChannel {
+    /// Moves the channel to where its strongest hold wants it, if it isn't there already.
+    fn settle(&mut self) -> Option<u8> {
+        let attenuation = self.holds.iter().map(|hold| hold.attenuation).max()?;
+        let target = attenuate(self.original, attenuation);
+        self.set_level(target)
+    }
+
+    fn set_level(&mut self, level: u8) -> Option<u8> {
+        if level == self.level {
+            return None;
+        }
+        self.level = level;
+        Some(level)
+    }
+}
+
+fn attenuate(volume: u8, attenuation: u8) -> u8 {
+    let remaining = 100 - attenuation.min(100) as u16;
+    (volume as u16 * remaining / 100) as u8
+}
+
+#[derive(Debug, Default)]
+pub struct Ducking {
+    channels: EnumMap<ChannelName, Option<DuckedChannel>>,
+}
+
+impl Ducking {
+    /// Ducks the config's channels for the button, returning the volumes to send. The volume
+    /// function gives each channel's volume in the profile.
+    pub fn start(
+        &mut self,
+        holder: Holder,
+        config: &DuckConfig,
+        volume: impl Fn(ChannelName) -> u8,
+    ) -> Vec<(ChannelName, u8)> {
+        let hold = Hold {
+            holder,
+            attenuation: config.attenuation,
+            release: config.release,
+        };
+
+        let mut changes = vec![];
+        for channel in ChannelName::iter().filter(|c| config.channels.contains(c)) {
+            let ducked = self.channels[channel].get_or_insert_with(|| DuckedChannel {
+                original: volume(channel),
+                level: volume(channel),
+                holds: vec![],
+                release: None,
+            });
+
+            ducked.holds.retain(|existing| existing.holder != holder);
+            ducked.holds.push(hold);
+            ducked.release = None;
+            if let Some(level) = ducked.settle() {
+                changes.push((channel, level));
+            }
+        }
+        changes
+    }
+
+    /// Lets go of the button's holds, channels which nothing else holds start to rise back.
+    pub fn stop(&mut self, holder: Holder, now: Instant) -> Vec<(ChannelName, u8)> {
+        let mut changes = vec![];
+        for channel in ChannelName::iter() {
+            let Some(ducked) = &mut self.channels[channel] else {
+                continue;
+            };
+            let Some(index) = ducked.holds.iter().position(|h| h.holder == holder) else {
+                continue;
+            };
+
+            let hold = ducked.holds.remove(index);
+            if !ducked.holds.is_empty() {
+                if let Some(level) = ducked.settle() {
+                    changes.push((channel, level));
+                }
+                continue;
+            }
+
+            ducked.release = Some(Release {
+                from: ducked.level,
+                started: now,
+                duration: Duration::from_millis(hold.release.into()),
+            });
+        }
+
+        changes.extend(self.tick(now));
+        changes
+    }
+
+    /// Steps any channels which are rising back, those which have finished are forgotten.
+    pub fn tick(&mut self, now: Instant) -> Vec<(ChannelName, u8)> {
+        let mut changes = vec![];
+        for channel in ChannelName::iter() {
+            let Some(ducked) = &mut self.channels[channel] else {
+                continue;
+            };
+            let Some(release) = ducked.release else {
+                continue;
+            };
+
+            let elapsed = now.saturating_duration_since(release.started);
+            if elapsed >= release.duration {
+                if let Some(level) = ducked.set_level(ducked.original) {
+                    changes.push((channel, level));
+                }
+                self.channels[channel] = None;
+                continue;
+            }
+
+            let progress = elapsed.as_secs_f64() / release.duration.as_secs_f64();
+            let range = ducked.original as f64 - release.from as f64;
+            let level = (release.from as f64 + range * progress).round() as u8;
+            if let Some(level) = ducked.set_level(level) {
+                changes.push((channel, level));
+            }
+        }
+        changes
+    }
+
+    /// Forgets channels whose profile volume has changed since they were ducked, they're left
+    /// at the new volume rather than being restored.
+    pub fn drop_overridden(&mut self, volume: impl Fn(ChannelName) -> u8) -> Vec<ChannelName> {
+        let mut dropped = vec![];
+        for channel in ChannelName::iter() {
+            if let Some(ducked) = &self.channels[channel] {
+                if ducked.original != volume(channel) {
+                    self.channels[channel] = None;
+                    dropped.push(channel);
+                }
+            }
+        }
+        dropped
+    }
+
+    /// The channels rising back after their last hold was let go of.
+    pub fn releasing(&self) -> Vec<ChannelName> {
+        ChannelName::iter()
+            .filter(|channel| {
+                self.channels[*channel]
+                    .as_ref()
+                    .is_some_and(|ducked| ducked.release.is_some())
+            })
+            .collect()
+    }
+
+    /// The buttons currently holding a channel down.
+    pub fn holders(&self) -> Vec<Holder> {
+        let mut holders: Vec<Holder> = vec![];
+        for ducked in self.channels.values().flatten() {
+            for hold in &ducked.holds {
+                if !holders.contains(&hold.holder) {
+                    holders.push(hold.holder);
+                }
+            }
+        }
+        holders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOP_LEFT: Holder = (SampleBank::A, SampleButtons::TopLeft);
+    const TOP_RIGHT: Holder = (SampleBank::A, SampleButtons::TopRight);
+
+    fn config(channels: &[ChannelName], attenuation: u8, release: u16) -> DuckConfig {
+        DuckConfig {
+            channels: channels.to_vec(),
+            attenuation,
+            release,
+        }
+    }
+
+    fn volume(channel: ChannelName) -> u8 {
+        match channel {
+            ChannelName::Music => 200,
+            _ => 100,
+        }
+    }
+
+    #[test]
+    fn ducks_are_restored_when_the_last_holder_stops() {
+        let (music, game) = (ChannelName::Music, ChannelName::Game);
+        let now = Instant::now();
+        let mut ducking = Ducking::default();
+
+        let changes = ducking.start(TOP_LEFT, &config(&[music], 50, 0), volume);
+        assert_eq!(changes, vec![(music, 100)]);
+
+        // The strongest duck wins, and the original is kept from the first
+        let changes = ducking.start(TOP_RIGHT, &config(&[music, game], 75, 0), volume);
+        assert_eq!(changes, vec![(music, 50), (game, 25)]);
+        assert_eq!(ducking.holders(), vec![TOP_LEFT, TOP_RIGHT]);
+
+        let changes = ducking.stop(TOP_RIGHT, now);
+        assert_eq!(changes, vec![(music, 100), (game, 100)]);
+        assert!(ducking.channels[music].is_some() && ducking.channels[game].is_none());
+
+        assert_eq!(ducking.stop(TOP_LEFT, now), vec![(music, 200)]);
+        assert!(ducking.holders().is_empty() && ducking.channels[music].is_none());
+
+        // Stopping something which holds nothing changes nothing
+        assert!(ducking.stop(TOP_LEFT, now).is_empty());
+    }
+
+    #[test]
+    fn releases_are_stepped() {
+        let music = ChannelName::Music;
+        let now = Instant::now();
+        let mut ducking = Ducking::default();
+
+        ducking.start(TOP_LEFT, &config(&[music], 100, 1000), volume);
+        assert!(ducking.stop(TOP_LEFT, now).is_empty());
+
+        assert_eq!(ducking.releasing(), vec![music]);
+
+        let halfway = now + Duration::from_millis(500);
+        assert_eq!(ducking.tick(halfway), vec![(music, 100)]);
+        assert!(ducking.tick(halfway).is_empty());
+
+        // Starting again mid release ducks back down, without losing the original volume
+        assert_eq!(
+            ducking.start(TOP_LEFT, &config(&[music], 100, 1000), |_| 200),
+            vec![(music, 0)]
+        );
+        ducking.stop(TOP_LEFT, halfway);
+        assert_eq!(
+            ducking.tick(halfway + Duration::from_secs(1)),
+            vec![(music, 200)]
+        );
+        assert!(ducking.channels[music].is_none());
+    }
+
+    #[test]
+    fn changed_volumes_are_let_go() {
+        let (music, game) = (ChannelName::Music, ChannelName::Game);
+        let mut ducking = Ducking::default();
+        ducking.start(TOP_LEFT, &config(&[music, game], 50, 0), volume);
+
+        // Music was moved by hand, so stays where it was put
+        let moved = |channel: ChannelName| match channel {
+            ChannelName::Music => 150,
+            channel => volume(channel),
+        };
+        assert_eq!(ducking.drop_overridden(moved), vec![music]);
+        assert_eq!(ducking.stop(TOP_LEFT, Instant::now()), vec![(game, 100)]);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use goxlr_ipc::{EqCurveFilter, EqCurveFilterResult, EqCurveGain, EqCurveReport};
+
+// Curves are read from, and written to, the mic profile directory with these extensions
+pub const EQ_CURVE_IMPORT_EXTENSION: &str = "txt";
+pub const EQ_CURVE_EXPORT_EXTENSION: &str = "csv";
+
+const MIN_GAIN: i8 = -9;
+const MAX_GAIN: i8 = 9;
+
+// Filters further than this (in octaves) from the nearest band are considered out of range
+const MAX_BAND_DISTANCE: f32 = 1.;
+
+// Peaking and shelf filters can all be approximated by a band gain, pass filters can't.
+const SUPPORTED_FILTERS: [&str; 6] = ["PK", "PEQ", "LS", "LSC", "HS", "HSC"];
+
+/// A filter read from an AutoEQ 'ParametricEQ.txt' file, which look like this:
+///
+/// Preamp: -6.2 dB
+/// Filter 1: ON LSC Fc 105 Hz Gain 2.4 dB Q 0.70
+/// Filter 2: ON PK Fc 200 Hz Gain -3.1 dB Q 0.50
+#[derive(Debug)]
+struct ParametricFilter {
+    enabled: bool,
+    filter_type: String,
+    frequency: f32,
+    gain: f32,
+}
+
+/// Maps the curve in the file onto the device's EQ bands, returning the report alongside the
+/// gain for each band (in the order they were provided).
+pub fn map_eq_curve(path: &Path, bands: &[f32]) -> Result<(EqCurveReport, Vec<i8>)> {
+    let content = fs::read_to_string(path)?;
+    let (preamp, filters) = parse_parametric_eq(&content)?;
+
+    let mut totals = vec![0.; bands.len()];
+    let mut report = EqCurveReport {
+        preamp,
+        ..Default::default()
+    };
+
+    for filter in filters {
+        let result = match get_band_for_filter(&filter, bands) {
+            Ok(index) => {
+                totals[index] += filter.gain;
+                EqCurveFilterResult::Mapped(bands[index])
+            }
+            Err(reason) => EqCurveFilterResult::Dropped(reason),
+        };
+
+        report.filters.push(EqCurveFilter {
+            frequency: filter.frequency,
+            gain: filter.gain,
+            result,
+        });
+    }
+
+    let mut gains = vec![];
+    for (frequency, total) in bands.iter().zip(totals) {
+        let rounded = total.round() as i8;
+        let gain = rounded.clamp(MIN_GAIN, MAX_GAIN);
+
+        gains.push(gain);
+        report.gains.push(EqCurveGain {
+            frequency: *frequency,
+            gain,
+            clamped: gain != rounded,
+        });
+    }
+    Ok((report, gains))
+}
+
+/// Writes the bands out as a CSV, which can be loaded into most EQ tools (including AutoEQ).
+pub fn write_eq_curve(path: &Path, bands: &[(f32, i8)]) -> Result<()> {
+    let mut content = String::from("frequency,gain\n");
+    for (frequency, gain) in bands {
+        content.push_str(&format!("{},{}\n", frequency, gain));
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn parse_parametric_eq(content: &str) -> Result<(Option<f32>, Vec<ParametricFilter>)> {
+    let mut preamp = None;
+    let mut filters = vec![];
+
+    for line in content.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value),
+            None => continue,
+        };
+
+        if key.eq_ignore_ascii_case("Preamp") {
+            preamp = value.split_whitespace().next().and_then(|v| v.parse().ok());
+        } else if key.starts_with("Filter") {
+            match parse_filter(value) {
+                Some(filter) => filters.push(filter),
+                None => bail!("Unable to parse EQ filter: {}", line.trim()),
+            }
+        }
+    }
+
+    if filters.is_empty() {
+        bail!("No filters found, is this a ParametricEQ file?");
+    }
+    Ok((preamp, filters))
+}
+
+fn parse_filter(value: &str) -> Option<ParametricFilter> {
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let get_value = |name: &str| {
+        let position = tokens.iter().position(|token| *token == name)?;
+        tokens.get(position + 1)?.parse::<f32>().ok()
+    };
+
+    Some(ParametricFilter {
+        enabled: *tokens.first()? == "ON",
+        filter_type: tokens.get(1)?.to_uppercase(),
+        frequency: get_value("Fc")?,
+        gain: get_value("Gain").unwrap_or(0.),
+    })
+}
+
+fn get_band_for_filter(filter: &ParametricFilter, bands: &[f32]) -> Result<usize, String> {
+    if !filter.enabled {
+        return Err(String::from("Filter is disabled"));
+    }
+
+    if !SUPPORTED_FILTERS.contains(&filter.filter_type.as_str()) {
+        return Err(format!("Unsupported filter type {}", filter.filter_type));
+    }
+
+    if filter.frequency <= 0. {
+        return Err(String::from("Invalid frequency"));
+    }
+
+    // Distance is measured in octaves, as that's how the bands are spread.
+    let nearest = bands
+        .iter()
+        .map(|band| (filter.frequency / band).log2().abs())
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    match nearest {
+        Some((index, distance)) if distance <= MAX_BAND_DISTANCE => Ok(index),
+        _ => Err(String::from("Outside of the equaliser's range")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goxlr_types::{EqFrequencies, MiniEqFrequencies};
+    use strum::IntoEnumIterator;
+
+    const FILE: &str = "Preamp: -6.2 dB\n\
+         Filter 1: ON LSC Fc 105 Hz Gain 2.4 dB Q 0.70\n\
+         Filter 2: ON PK Fc 140 Hz Gain 1.3 dB Q 0.50\n\
+         Filter 3: ON PK Fc 1200 Hz Gain -3.1 dB Q 1.00\n\
+         Filter 4: ON PK Fc 7000 Hz Gain 7 dB Q 1.00\n\
+         Filter 5: ON PK Fc 9000 Hz Gain 6 dB Q 1.00\n\
+         Filter 6: ON PK Fc 20 Hz Gain -2 dB Q 1.00\n";
+
+    // Where each device's bands sit by default
+    fn full_bands() -> Vec<f32> {
+        EqFrequencies::iter()
+            .map(|band| match band {
+                EqFrequencies::Equalizer31Hz => 31.5,
+                EqFrequencies::Equalizer63Hz => 63.,
+                EqFrequencies::Equalizer125Hz => 125.,
+                EqFrequencies::Equalizer250Hz => 250.,
+                EqFrequencies::Equalizer500Hz => 500.,
+                EqFrequencies::Equalizer1KHz => 1000.,
+                EqFrequencies::Equalizer2KHz => 2000.,
+                EqFrequencies::Equalizer4KHz => 4000.,
+                EqFrequencies::Equalizer8KHz => 8000.,
+                EqFrequencies::Equalizer16KHz => 16000.,
+            })
+            .collect()
+    }
+
+    fn mini_bands() -> Vec<f32> {
+        MiniEqFrequencies::iter()
+            .map(|band| match band {
+                MiniEqFrequencies::Equalizer90Hz => 90.,
+                MiniEqFrequencies::Equalizer250Hz => 250.,
+                MiniEqFrequencies::Equalizer500Hz => 500.,
+                MiniEqFrequencies::Equalizer1KHz => 1000.,
+                MiniEqFrequencies::Equalizer3KHz => 3000.,
+                MiniEqFrequencies::Equalizer8KHz => 8000.,
+            })
+            .collect()
+    }
+
+    fn map(content: &str, bands: &[f32]) -> Result<(EqCurveReport, Vec<i8>)> {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("ParametricEQ.txt");
+        fs::write(&path, content).unwrap();
+        map_eq_curve(&path, bands)
+    }
+
+    #[test]
+    fn filters_are_summed_into_the_nearest_band() {
+        let (report, gains) = map(FILE, &full_bands()).unwrap();
+
+        assert_eq!(report.preamp, Some(-6.2));
+        assert_eq!(gains, vec![-2, 0, 4, 0, 0, -3, 0, 0, MAX_GAIN, 0]);
+        let clamped: Vec<usize> = report
+            .gains
+            .iter()
+            .enumerate()
+            .filter_map(|(index, gain)| gain.clamped.then_some(index))
+            .collect();
+        assert_eq!(clamped, vec![8]);
+        assert!(matches!(
+            report.filters[1].result,
+            EqCurveFilterResult::Mapped(band) if band == 125.
+        ));
+    }
+
+    #[test]
+    fn the_mini_has_fewer_bands_to_map_to() {
+        let (report, gains) = map(FILE, &mini_bands()).unwrap();
+
+        // Both low filters land on the lowest band, and the 20Hz filter is too far below it
+        assert_eq!(gains, vec![4, 0, 0, -3, 0, MAX_GAIN]);
+        assert_eq!(report.gains.len(), 6);
+        assert!(matches!(
+            report.filters[1].result,
+            EqCurveFilterResult::Mapped(band) if band == 90.
+        ));
+        assert!(matches!(
+            &report.filters[5].result,
+            EqCurveFilterResult::Dropped(reason) if reason == "Outside of the equaliser's range"
+        ));
+    }
+
+    #[test]
+    fn unusable_filters_are_dropped() {
+        let content = "Filter 1: OFF PK Fc 100 Hz Gain 3 dB Q 1.00\n\
+             Filter 2: ON HPQ Fc 1000 Hz Q 0.70\n\
+             Filter 3: ON PK Fc 40000 Hz Gain 3 dB Q 1.00\n\
+             Filter 4: ON PK Fc 0 Hz Gain 3 dB Q 1.00\n";
+        let (report, gains) = map(content, &full_bands()).unwrap();
+
+        assert_eq!(report.preamp, None);
+        assert_eq!(gains, vec![0; 10]);
+        let reasons: Vec<String> = report
+            .filters
+            .iter()
+            .map(|filter| match &filter.result {
+                EqCurveFilterResult::Dropped(reason) => reason.clone(),
+                EqCurveFilterResult::Mapped(band) => panic!("Mapped to {}", band),
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "Filter is disabled",
+                "Unsupported filter type HPQ",
+                "Outside of the equaliser's range",
+                "Invalid frequency",
+            ]
+        );
+    }
+
+    #[test]
+    fn files_without_filters_are_refused() {
+        assert!(map("frequency,gain\n100,3\n", &full_bands()).is_err());
+        assert!(map("Filter 1: ON PK Gain 3 dB\n", &mini_bands()).is_err());
+    }
+
+    #[test]
+    fn curves_are_exported_as_csv() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("curve.csv");
+        write_eq_curve(&path, &[(31.5, -2), (1000., 4)]).unwrap();
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "frequency,gain\n31.5,-2\n1000,4\n"
+        );
+    }
+}
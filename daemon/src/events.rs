@@ -2,21 +2,23 @@
 // variety of sources, which affect other parts of the daemon.
 
 use crate::primary_worker::DeviceStateChange;
+use crate::servers::ipc_server::{get_socket_name, SOCKET_PATH_ENV};
+use crate::tray::TrayState;
 use crate::{SettingsHandle, Shutdown};
-use goxlr_ipc::{HttpSettings, PathTypes};
+use goxlr_ipc::{HttpSettings, Notification, PathTypes, ShutdownReason};
 use log::{debug, warn};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 use tokio::{select, signal};
 
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum EventTriggers {
-    TTSMessage(String),
-    Stop(bool),
+    TTSMessage(Notification),
+    Stop(bool, ShutdownReason),
     Sleep(oneshot::Sender<()>),
     Wake(oneshot::Sender<()>),
     Lock,
@@ -25,6 +27,7 @@ pub enum EventTriggers {
     Activate,
     OpenUi,
     DevicesStopped,
+    SetMicMuted(bool),
 }
 
 #[derive(Clone)]
@@ -33,14 +36,18 @@ pub struct DaemonState {
     pub http_settings: HttpSettings,
 
     // TTS Output
-    pub tts_sender: Sender<String>,
+    pub tts_sender: Sender<Notification>,
 
     // Shutdown Handlers
     pub shutdown: Shutdown,
     pub shutdown_blocking: Arc<AtomicBool>,
+    pub shutdown_reason: Arc<Mutex<Option<ShutdownReason>>>,
 
     // Settings Handle..
     pub settings_handle: SettingsHandle,
+
+    // Distilled device state, used by the tray
+    pub tray_state: watch::Receiver<TrayState>,
 }
 
 pub async fn spawn_event_handler(
@@ -66,9 +73,10 @@ pub async fn spawn_event_handler(
                     EventTriggers::TTSMessage(message) => {
                         let _ = state.tts_sender.send(message).await;
                     }
-                    EventTriggers::Stop(avoid_write) => {
+                    EventTriggers::Stop(avoid_write, reason) => {
                         if !triggered_device_stop {
-                            debug!("Shutdown Phase 1 Triggered..");
+                            debug!("Shutdown Phase 1 Triggered ({:?})..", reason);
+                            state.shutdown_reason.lock().unwrap().replace(reason);
                             triggered_device_stop = true;
                             let _ = device_state_tx.send(DeviceStateChange::Shutdown(avoid_write)).await;
                         } else {
@@ -93,8 +101,12 @@ pub async fn spawn_event_handler(
                     EventTriggers::Wake(sender) => {
                         let _ = device_state_tx.send(DeviceStateChange::Wake(sender)).await;
                     }
+                    EventTriggers::SetMicMuted(muted) => {
+                        let _ = device_state_tx.send(DeviceStateChange::SetMicMuted(muted)).await;
+                    }
                     EventTriggers::Lock => {
                         debug!("Received Screen Lock Event..");
+                        let _ = device_state_tx.send(DeviceStateChange::ScreenLocked).await;
                     }
                     EventTriggers::Unlock => {
                         debug!("Received Screen Unlock Event");
@@ -139,6 +151,7 @@ pub async fn spawn_event_handler(
                                     if let Some(command) = args.next() {
                                         let result = Command::new(command)
                                             .current_dir(tmp_dir)
+                                            .env(SOCKET_PATH_ENV, get_socket_name())
                                             .args(args)
                                             .stdout(Stdio::null())
                                             .stderr(Stdio::null())
@@ -170,6 +183,7 @@ pub async fn spawn_event_handler(
                                         debug!("Attempting to Execute: {:?}", params);
                                         let result = Command::new(&params[0])
                                             .current_dir(tmp_dir)
+                                            .env(SOCKET_PATH_ENV, get_socket_name())
                                             .args(&params[1..])
                                             .stdout(Stdio::null())
                                             .stderr(Stdio::null())
@@ -19,12 +19,16 @@ use anyhow::{anyhow, bail, Context, Result};
 // use futures::{SinkExt, StreamExt};
 use log::{debug, info, warn};
 
+use enum_map::EnumMap;
 use glob::glob;
-use goxlr_ipc::PathTypes;
+use goxlr_ipc::{CommandError, NameProblem, PathTypes, RecoveredDefaults};
+use goxlr_types::EffectSection;
 use notify::event::{CreateKind, ModifyKind, RemoveKind, RenameMode};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use strum::IntoEnumIterator;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::watch;
 
 use crate::{SettingsHandle, Shutdown};
 
@@ -42,6 +46,9 @@ pub struct FilePaths {
 #[derive(Debug)]
 pub struct FileManager {
     paths: FilePaths,
+
+    // Lets the file watcher and HTTP server follow the paths when they change
+    paths_tx: watch::Sender<FilePaths>,
 }
 
 impl FileManager {
@@ -49,7 +56,20 @@ impl FileManager {
         let paths = FileManager::get_file_paths_from_settings(settings).await;
         FileManager::create_paths(&paths);
 
-        Self { paths }
+        let (paths_tx, _) = watch::channel(paths.clone());
+        Self { paths, paths_tx }
+    }
+
+    /// Switches to a new set of directories (creating any that don't exist), anything
+    /// subscribed is told about the change.
+    pub fn set_paths(&mut self, paths: FilePaths) {
+        FileManager::create_paths(&paths);
+        self.paths = paths.clone();
+        self.paths_tx.send_replace(paths);
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<FilePaths> {
+        self.paths_tx.subscribe()
     }
 
     pub async fn get_file_paths_from_settings(settings: &SettingsHandle) -> FilePaths {
@@ -134,6 +154,17 @@ impl FileManager {
         self.get_files_from_path(path, extension, false)
     }
 
+    pub fn get_effect_sections(&mut self) -> EnumMap<EffectSection, Vec<String>> {
+        let mut sections = EnumMap::default();
+        for section in EffectSection::iter() {
+            let path = self.paths.presets.clone();
+            let extension = [get_effect_section_extension(section)].to_vec();
+
+            sections[section] = self.get_files_from_path(path, extension, false);
+        }
+        sections
+    }
+
     pub fn get_samples(&mut self) -> BTreeMap<String, String> {
         let base_path = self.paths.samples.clone();
         let extensions = ["wav", "mp3"].to_vec();
@@ -235,8 +266,21 @@ impl FileManager {
     }
 }
 
+// Effect sections live alongside the presets, so each needs its own extension to keep them apart.
+pub fn get_effect_section_extension(section: EffectSection) -> &'static str {
+    match section {
+        EffectSection::Reverb => "reverbPreset",
+        EffectSection::Echo => "echoPreset",
+        EffectSection::Pitch => "pitchPreset",
+        EffectSection::Gender => "genderPreset",
+        EffectSection::Megaphone => "megaphonePreset",
+        EffectSection::Robot => "robotPreset",
+        EffectSection::HardTune => "hardtunePreset",
+    }
+}
+
 pub async fn spawn_file_notification_service(
-    paths: FilePaths,
+    mut paths_rx: watch::Receiver<FilePaths>,
     sender: Sender<PathTypes>,
     mut shutdown_signal: Shutdown,
 ) -> Result<()> {
@@ -250,21 +294,8 @@ pub async fn spawn_file_notification_service(
     let (mut watcher, mut rx) = watcher.unwrap();
 
     // Add the Paths to the Watcher..
-    if let Err(error) = watcher.watch(&paths.profiles, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor Profiles Path: {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.mic_profiles, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor the Microphone Profile Path {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.presets, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor the Presets Path: {:?}", error)
-    }
-    if let Err(error) = watcher.watch(&paths.icons, RecursiveMode::NonRecursive) {
-        warn!("Unable to monitor the Icons Path: {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.samples, RecursiveMode::Recursive) {
-        warn!("Unable to Monitor the Samples Path: {:?}", error);
-    }
+    let mut paths = paths_rx.borrow_and_update().clone();
+    watch_paths(&mut watcher, &paths);
 
     // Wait for any changes..
     loop {
@@ -273,6 +304,12 @@ pub async fn spawn_file_notification_service(
                 debug!("Shutdown Signal Received.");
                 break;
             },
+            Ok(()) = paths_rx.changed() => {
+                debug!("File Paths Changed, updating the Watcher..");
+                unwatch_paths(&mut watcher, &paths);
+                paths = paths_rx.borrow_and_update().clone();
+                watch_paths(&mut watcher, &paths);
+            },
             result = rx.recv() => {
                 if let Some(result) = result {
                     match result {
@@ -336,6 +373,38 @@ pub async fn spawn_file_notification_service(
     Ok(())
 }
 
+fn watch_paths(watcher: &mut RecommendedWatcher, paths: &FilePaths) {
+    if let Err(error) = watcher.watch(&paths.profiles, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor Profiles Path: {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.mic_profiles, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor the Microphone Profile Path {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.presets, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor the Presets Path: {:?}", error)
+    }
+    if let Err(error) = watcher.watch(&paths.icons, RecursiveMode::NonRecursive) {
+        warn!("Unable to monitor the Icons Path: {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.samples, RecursiveMode::Recursive) {
+        warn!("Unable to Monitor the Samples Path: {:?}", error);
+    }
+}
+
+fn unwatch_paths(watcher: &mut RecommendedWatcher, paths: &FilePaths) {
+    let directories = [
+        &paths.profiles,
+        &paths.mic_profiles,
+        &paths.presets,
+        &paths.icons,
+        &paths.samples,
+    ];
+    for directory in directories {
+        // This only fails if the path wasn't being watched, which is fine.
+        let _ = watcher.unwatch(directory);
+    }
+}
+
 fn create_watcher() -> notify::Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
     let (tx, rx) = mpsc::channel(1);
 
@@ -393,8 +462,109 @@ pub fn can_create_new_file(path: PathBuf) -> Result<()> {
     Ok(())
 }
 
+// Long enough for any sensible name, while leaving room in the path for the directory on Windows
+pub const MAX_NAME_LENGTH: usize = 64;
+
+// Characters which can't be used in a file name on at least one platform (as well as control
+// characters), these are removed from new names.
+const DISALLOWED_NAME_CHARACTERS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+// Windows won't create files with these names, whatever the extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Cleans up a name for a new profile, preset or sample, returning the name to use. Disallowed
+/// characters are removed (along with any leading or trailing spaces, and trailing dots which
+/// Windows would drop), then anything which still can't be used is refused.
+pub fn normalise_name(name: &str) -> Result<String> {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !DISALLOWED_NAME_CHARACTERS.contains(c))
+        .collect();
+    let cleaned = cleaned
+        .trim_start()
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace());
+
+    if let Some(problem) = find_name_problem(cleaned) {
+        return Err(CommandError::InvalidName(problem).into());
+    }
+    Ok(cleaned.to_string())
+}
+
+/// Checks a name as it is, existing files which fail this still load, but new ones can't be
+/// created with it.
+pub fn find_name_problem(name: &str) -> Option<NameProblem> {
+    if name.is_empty() {
+        return Some(NameProblem::Empty);
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Some(NameProblem::TooLong(MAX_NAME_LENGTH));
+    }
+
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        return Some(NameProblem::Reserved);
+    }
+
+    let invalid = |c: char| c.is_control() || DISALLOWED_NAME_CHARACTERS.contains(&c);
+    if name.contains(invalid) || name.trim() != name || name.ends_with('.') {
+        return Some(NameProblem::InvalidCharacters);
+    }
+    None
+}
+
+/// Checks a client's name for a file which should already be in the directory. Existing files
+/// are found by listing the directory, so they still work with names which wouldn't be accepted
+/// now, any other name is refused if it could lead outside the directory.
+pub fn check_existing_name(name: &str, extension: &str, directory: &Path) -> Result<()> {
+    if is_file_listed(&format!("{name}.{extension}"), directory) {
+        return Ok(());
+    }
+    if let Some(problem) = find_name_problem(name) {
+        return Err(CommandError::InvalidName(problem).into());
+    }
+    Ok(())
+}
+
+/// Whether a file with this name is directly inside the directory.
+pub fn is_file_listed(file_name: &str, directory: &Path) -> bool {
+    match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_name().to_str() == Some(file_name) && entry.path().is_file()),
+        Err(_) => false,
+    }
+}
+
+/// The names in a file list which wouldn't be accepted now, and why.
+pub fn find_name_problems(names: &[String]) -> BTreeMap<String, NameProblem> {
+    names
+        .iter()
+        .filter_map(|name| find_name_problem(name).map(|problem| (name.clone(), problem)))
+        .collect()
+}
+
 const DEFAULTS_BINARY: &str = "goxlr-defaults";
 pub fn extract_defaults(file_type: PathTypes, path: &Path) -> Result<()> {
+    let result = recover_defaults(file_type, path, None, false)?;
+    for (file, error) in result.failed {
+        warn!("Unable to extract {}: {}", file, error);
+    }
+    Ok(())
+}
+
+/// Runs the defaults extractor, which reports each file it handles on a separate line.
+pub fn recover_defaults(
+    file_type: PathTypes,
+    path: &Path,
+    files: Option<Vec<String>>,
+    overwrite: bool,
+) -> Result<RecoveredDefaults> {
     let binary_name = if cfg!(target_os = "windows") {
         format!("{DEFAULTS_BINARY}.exe")
     } else {
@@ -432,24 +602,92 @@ pub fn extract_defaults(file_type: PathTypes, path: &Path) -> Result<()> {
         _ => bail!("Invalid File Type Specified"),
     };
 
-    let command = Command::new(final_bin)
-        .arg(file_type)
-        .arg(path)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .output();
-
-    match command {
-        Ok(output) => {
-            if !output.status.success() {
-                if let Some(code) = output.status.code() {
-                    bail!("Unable to extract defaults, Error Code: {}", code);
-                }
-            }
+    let mut command = Command::new(final_bin);
+    command.arg(file_type).arg(path);
+    if overwrite {
+        command.arg("--overwrite");
+    }
+    for file in files.unwrap_or_default() {
+        command.arg("--only").arg(file);
+    }
+
+    let output = match command.stderr(Stdio::null()).output() {
+        Ok(output) => output,
+        Err(error) => bail!("Unable to run Default extractor: {}", error),
+    };
+
+    if !output.status.success() {
+        if let Some(code) = output.status.code() {
+            bail!("Unable to extract defaults, Error Code: {}", code);
         }
-        Err(error) => {
-            bail!("Unable to run Default extractor: {}", error);
+    }
+
+    let mut result = RecoveredDefaults::default();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.splitn(3, '\t');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("created"), Some(file), _) => result.created.push(file.to_string()),
+            (Some("skipped"), Some(file), _) => result.skipped.push(file.to_string()),
+            (Some("failed"), Some(file), error) => {
+                let error = error.unwrap_or("Unknown Error").to_string();
+                result.failed.insert(file.to_string(), error);
+            }
+            _ => debug!("Unexpected output from Default extractor: {}", line),
         }
     }
-    Ok(())
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(name: &str) -> Option<NameProblem> {
+        normalise_name(name)
+            .err()
+            .and_then(|e| e.downcast_ref::<CommandError>().cloned())
+            .map(|error| match error {
+                CommandError::InvalidName(problem) => problem,
+                error => panic!("Unexpected error: {}", error),
+            })
+    }
+
+    #[test]
+    fn names_are_cleaned_up() {
+        assert_eq!(normalise_name("My Profile").unwrap(), "My Profile");
+        assert_eq!(
+            normalise_name(" ../Stream\\Main\t").unwrap(),
+            "..StreamMain"
+        );
+        assert_eq!(normalise_name("What? <Now>.").unwrap(), "What Now");
+        assert_eq!(normalise_name("CONSOLE").unwrap(), "CONSOLE");
+    }
+
+    #[test]
+    fn unusable_names_are_refused() {
+        assert_eq!(problem(""), Some(NameProblem::Empty));
+        assert_eq!(problem("/\\:"), Some(NameProblem::Empty));
+        assert_eq!(problem("con"), Some(NameProblem::Reserved));
+        assert_eq!(problem("LPT1.backup"), Some(NameProblem::Reserved));
+
+        let long = "a".repeat(MAX_NAME_LENGTH + 1);
+        assert_eq!(problem(&long), Some(NameProblem::TooLong(MAX_NAME_LENGTH)));
+        assert_eq!(problem(&long[1..]), None);
+    }
+
+    #[test]
+    fn existing_names_are_checked_as_they_are() {
+        let names = vec![
+            String::from("Default"),
+            String::from("Stream: Main"),
+            "b".repeat(140),
+        ];
+        let problems = find_name_problems(&names);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems["Stream: Main"], NameProblem::InvalidCharacters);
+        assert_eq!(
+            problems[&"b".repeat(140)],
+            NameProblem::TooLong(MAX_NAME_LENGTH)
+        );
+    }
 }
@@ -0,0 +1,63 @@
+use anyhow::{bail, Result};
+use goxlr_ipc::{GoXLRCommand, HeadsetSnapshot};
+use goxlr_types::{
+    ChannelName, FaderName, InputDevice, MicrophoneType, MuteFunction, OutputDevice,
+};
+
+// The Jack gain (in dB) headset mode applies, headset mics are much quieter than most
+pub const HEADSET_MIC_GAIN: u16 = 30;
+
+// How loud (as a percentage) the Mic is heard in the Headphones, unless configured
+pub const DEFAULT_SIDETONE: u8 = 30;
+
+pub fn check_sidetone(sidetone: u8) -> Result<()> {
+    if sidetone > 100 {
+        bail!("Headset Sidetone must be a percentage");
+    }
+    Ok(())
+}
+
+pub fn sidetone_volume(sidetone: u8) -> u8 {
+    ((sidetone as u16 * 255) / 100) as u8
+}
+
+/// The commands which set the device up for a headset, Chat's mute function can only be set if
+/// it's on a fader.
+pub fn headset_commands(sidetone: u8, chat_fader: Option<FaderName>) -> Vec<GoXLRCommand> {
+    let mut commands = vec![
+        GoXLRCommand::SetMicrophoneType(MicrophoneType::Jack),
+        GoXLRCommand::SetMicrophoneGain(MicrophoneType::Jack, HEADSET_MIC_GAIN),
+        GoXLRCommand::SetRouter(InputDevice::Microphone, OutputDevice::Headphones, true),
+        GoXLRCommand::SetVolume(ChannelName::MicMonitor, sidetone_volume(sidetone)),
+    ];
+    if let Some(fader) = chat_fader {
+        commands.push(GoXLRCommand::SetFaderMuteFunction(
+            fader,
+            MuteFunction::ToVoiceChat,
+        ));
+    }
+    commands
+}
+
+/// The commands which put back the settings headset mode replaced.
+pub fn restore_commands(
+    snapshot: &HeadsetSnapshot,
+    chat_fader: Option<FaderName>,
+) -> Vec<GoXLRCommand> {
+    let mut commands = vec![
+        // Setting the gain switches to the Jack, so the previous type has to come after it. It
+        // was in use before, so doesn't need confirming again.
+        GoXLRCommand::SetMicrophoneGain(MicrophoneType::Jack, snapshot.jack_gain),
+        GoXLRCommand::ConfirmMicrophoneType(snapshot.mic_type),
+        GoXLRCommand::SetRouter(
+            InputDevice::Microphone,
+            OutputDevice::Headphones,
+            snapshot.mic_monitor,
+        ),
+        GoXLRCommand::SetVolume(ChannelName::MicMonitor, snapshot.mic_monitor_volume),
+    ];
+    if let (Some(fader), Some(function)) = (chat_fader, snapshot.chat_mute_function) {
+        commands.push(GoXLRCommand::SetFaderMuteFunction(fader, function));
+    }
+    commands
+}
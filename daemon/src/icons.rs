@@ -0,0 +1,254 @@
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use goxlr_ipc::IconDetails;
+use goxlr_scribbles::{convert_icon, get_icon_dimensions, get_scribble_png, ScribbleIcon};
+use log::{debug, warn};
+
+use crate::files::create_path;
+
+/// The largest upload accepted, the HTTP server uses this as its limit for request bodies.
+pub const MAX_ICON_SIZE: usize = 1024 * 1024;
+
+// Thumbnails are drawn at the scribble's own resolution, so they show exactly what's displayed
+const THUMBNAIL_WIDTH: u32 = 128;
+const THUMBNAIL_HEIGHT: u32 = 64;
+
+/// Describes the named icons, generating thumbnails for any which are new or have changed since
+/// their thumbnail was drawn. Icons which can't be read are skipped.
+pub fn list_icons(icons: &Path, cache: &Path, names: &[String]) -> Vec<IconDetails> {
+    names
+        .iter()
+        .filter_map(|name| match get_icon_details(icons, cache, name) {
+            Ok(details) => Some(details),
+            Err(e) => {
+                warn!("Unable to read Icon {}: {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Converts an uploaded image and stores it as a PNG (named after the upload), it won't replace
+/// an existing icon.
+pub fn upload_icon(icons: &Path, cache: &Path, name: &str, data: &[u8]) -> Result<IconDetails> {
+    if data.len() > MAX_ICON_SIZE {
+        bail!(
+            "Icon is {} bytes, the limit is {} bytes",
+            data.len(),
+            MAX_ICON_SIZE
+        );
+    }
+
+    let stem = match Path::new(check_file_name(name)?).file_stem() {
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => bail!("Invalid Icon name: {}", name),
+    };
+    let file_name = format!("{}.png", stem);
+    let path = icons.join(&file_name);
+    if path.exists() {
+        bail!("An Icon named {} already exists", file_name);
+    }
+
+    let png = convert_icon(data).context("Unable to convert the Icon")?;
+    fs::write(&path, png).with_context(|| format!("Unable to write {:?}", path))?;
+    get_icon_details(icons, cache, &file_name)
+}
+
+pub fn delete_icon(icons: &Path, cache: &Path, name: &str) -> Result<()> {
+    let path = icons.join(check_file_name(name)?);
+    if !path.is_file() {
+        bail!("Icon {} not found", name);
+    }
+
+    fs::remove_file(&path).with_context(|| format!("Unable to remove {:?}", path))?;
+
+    // The thumbnail may never have been drawn, so there's nothing to do if it's not there.
+    let _ = fs::remove_file(get_thumbnail_path(cache, name));
+    Ok(())
+}
+
+/// Removes the thumbnails of icons which are no longer present, called when the file watcher
+/// reports a change to the icons directory.
+pub fn prune_thumbnails(cache: &Path, names: &[String]) {
+    let entries = match cache.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let thumbnail = entry.file_name().to_string_lossy().to_string();
+        let icon = thumbnail.strip_suffix(".png").unwrap_or(&thumbnail);
+        if !names.iter().any(|name| name == icon) {
+            debug!("Removing unused Thumbnail {}", thumbnail);
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// The thumbnail of a single icon, drawn only if the icon has changed since it was last drawn.
+pub fn get_icon_thumbnail(icons: &Path, cache: &Path, name: &str) -> Result<PathBuf> {
+    let path = icons.join(check_file_name(name)?);
+    if !path.is_file() {
+        bail!("Icon {} not found", name);
+    }
+    draw_thumbnail(cache, name, &path)
+}
+
+fn get_icon_details(icons: &Path, cache: &Path, name: &str) -> Result<IconDetails> {
+    let path = icons.join(check_file_name(name)?);
+    let (width, height) = get_icon_dimensions(&path)?;
+    let thumbnail = draw_thumbnail(cache, name, &path)?;
+
+    Ok(IconDetails {
+        name: name.to_string(),
+        width,
+        height,
+        thumbnail,
+    })
+}
+
+// Names come from clients, so make sure they can't point outside the icons directory
+fn check_file_name(name: &str) -> Result<&str> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(name),
+        _ => bail!("Invalid Icon name: {}", name),
+    }
+}
+
+fn get_thumbnail_path(cache: &Path, name: &str) -> PathBuf {
+    cache.join(format!("{}.png", name))
+}
+
+// Thumbnails are given the modified time of their icon, so any change to the icon (including
+// being replaced by an older file) is seen as a different time
+fn draw_thumbnail(cache: &Path, name: &str, icon: &Path) -> Result<PathBuf> {
+    let thumbnail = get_thumbnail_path(cache, name);
+    let modified = get_modified(icon);
+    if modified.is_some() && get_modified(&thumbnail) == modified {
+        return Ok(thumbnail);
+    }
+
+    debug!("Drawing Thumbnail for {}", name);
+    create_path(cache)?;
+
+    let png = get_scribble_png(
+        Some(ScribbleIcon::File(icon.to_path_buf())),
+        None,
+        None,
+        false,
+        THUMBNAIL_WIDTH,
+        THUMBNAIL_HEIGHT,
+    )?;
+    fs::write(&thumbnail, png).with_context(|| format!("Unable to write {:?}", thumbnail))?;
+    if let Some(modified) = modified {
+        let file = File::options().write(true).open(&thumbnail)?;
+        file.set_modified(modified)?;
+    }
+    Ok(thumbnail)
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).ok()?.modified().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(text: &str, width: u32, height: u32) -> Vec<u8> {
+        get_scribble_png(None, Some(text.to_string()), None, false, width, height).unwrap()
+    }
+
+    #[test]
+    fn uploads_are_converted_and_listed() {
+        let root = tempfile::tempdir().unwrap();
+        let (icons, cache) = (root.path().join("icons"), root.path().join("cache"));
+        fs::create_dir(&icons).unwrap();
+
+        let icon = upload_icon(&icons, &cache, "Stream.gif", &image("Hi", 96, 48)).unwrap();
+        assert_eq!(icon.name, "Stream.png");
+        assert_eq!((icon.width, icon.height), (96, 48));
+        assert!(icons.join("Stream.png").is_file());
+        assert!(icon.thumbnail.starts_with(&cache) && icon.thumbnail.is_file());
+
+        // The same name can't be uploaded twice
+        assert!(upload_icon(&icons, &cache, "Stream.png", &image("Hi", 96, 48)).is_err());
+
+        let names = vec![String::from("Stream.png"), String::from("Missing.png")];
+        assert_eq!(list_icons(&icons, &cache, &names), vec![icon]);
+    }
+
+    #[test]
+    fn bad_uploads_are_refused() {
+        let root = tempfile::tempdir().unwrap();
+        let (icons, cache) = (root.path().join("icons"), root.path().join("cache"));
+        fs::create_dir(&icons).unwrap();
+
+        let png = image("Hi", 32, 32);
+        for name in ["../Escape.png", "Nested/Icon.png", ""] {
+            assert!(upload_icon(&icons, &cache, name, &png).is_err(), "{}", name);
+        }
+        assert!(upload_icon(&icons, &cache, "Text.png", b"Not an image").is_err());
+        assert!(upload_icon(&icons, &cache, "Huge.png", &vec![0; MAX_ICON_SIZE + 1]).is_err());
+
+        // Small files can still be too wide or tall, these are refused before being decoded
+        let error = upload_icon(&icons, &cache, "Wide.png", &image("Hi", 2049, 8)).unwrap_err();
+        assert!(format!("{:#}", error).contains("at most 2048x2048"));
+        assert_eq!(icons.read_dir().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn deleted_icons_lose_their_thumbnails() {
+        let root = tempfile::tempdir().unwrap();
+        let (icons, cache) = (root.path().join("icons"), root.path().join("cache"));
+        fs::create_dir(&icons).unwrap();
+
+        let first = upload_icon(&icons, &cache, "First.png", &image("1", 32, 32)).unwrap();
+        let second = upload_icon(&icons, &cache, "Second.png", &image("2", 32, 32)).unwrap();
+
+        delete_icon(&icons, &cache, "First.png").unwrap();
+        assert!(!icons.join("First.png").exists() && !first.thumbnail.exists());
+        assert!(delete_icon(&icons, &cache, "First.png").is_err());
+        assert!(delete_icon(&icons, &cache, "../icons/Second.png").is_err());
+
+        // Removed outside the daemon, so only the file watcher will notice
+        fs::remove_file(icons.join("Second.png")).unwrap();
+        prune_thumbnails(&cache, &[]);
+        assert!(!second.thumbnail.exists());
+    }
+
+    #[test]
+    fn thumbnails_follow_their_icon() {
+        let root = tempfile::tempdir().unwrap();
+        let (icons, cache) = (root.path().join("icons"), root.path().join("cache"));
+        fs::create_dir(&icons).unwrap();
+
+        let icon = upload_icon(&icons, &cache, "Icon.png", &image("1", 32, 32)).unwrap();
+        let drawn = fs::read(&icon.thumbnail).unwrap();
+
+        // Unchanged icons keep the thumbnail already drawn
+        fs::write(&icon.thumbnail, "Cached").unwrap();
+        let file = File::options().write(true).open(&icon.thumbnail).unwrap();
+        file.set_modified(get_modified(&icons.join("Icon.png")).unwrap())
+            .unwrap();
+        let thumbnail = get_icon_thumbnail(&icons, &cache, "Icon.png").unwrap();
+        assert_eq!(fs::read(&thumbnail).unwrap(), b"Cached");
+
+        // Replacing the icon with an older file is still a change
+        let older = SystemTime::now() - std::time::Duration::from_secs(3600);
+        let file = File::options()
+            .write(true)
+            .open(icons.join("Icon.png"))
+            .unwrap();
+        file.set_modified(older).unwrap();
+        let thumbnail = get_icon_thumbnail(&icons, &cache, "Icon.png").unwrap();
+        assert_eq!(fs::read(&thumbnail).unwrap(), drawn);
+
+        assert!(get_icon_thumbnail(&icons, &cache, "Missing.png").is_err());
+        assert!(get_icon_thumbnail(&icons, &cache, "../icons/Icon.png").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use anyhow::{bail, Context, Result};
+use goxlr_ipc::LogLevel;
+use log::{LevelFilter, Log, Metadata, Record};
+use simplelog::{Config, SharedLogger, WriteLogger};
+
+/*
+ * The daemon's logger sits in front of the terminal and file loggers, deciding what reaches
+ * them from a set of env_logger style directives (`target=level`, separated by commas) which can
+ * be changed while running. It also keeps the most recent lines in memory, so clients can see
+ * them without knowing where the log file is.
+ */
+
+// Some packages are noisy at levels we otherwise want, these are applied first so they can be
+// overridden by the user's own filter when debugging them.
+const DEFAULT_DIRECTIVES: &str = concat!(
+    // The tracing package, when used, will output to INFO from zbus every second..
+    "tracing=off,zbus=off,",
+    // Actix is a little noisy on startup and shutdown..
+    "actix_server::accept=off,actix_server::worker=off,",
+    "actix_server::server=off,actix_server::builder=off,",
+    // Symphonia's header announcements go to INFO, and are only useful in development
+    "symphonia=off",
+);
+
+const TAIL_LINES: usize = 1000;
+
+static LOGGER: OnceLock<DaemonLogger> = OnceLock::new();
+
+struct DaemonLogger {
+    filter: RwLock<Filter>,
+    loggers: Vec<Box<dyn SharedLogger>>,
+    tail: Arc<Mutex<VecDeque<String>>>,
+}
+
+struct Filter {
+    level: LevelFilter,
+    user: Option<Directives>,
+    directives: Directives,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Directives {
+    level: Option<LevelFilter>,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+/// Installs the daemon's logger, passing on what the filter allows to the given loggers (which
+/// should be configured to accept everything). An invalid filter is reported, and ignored.
+pub fn init(
+    config: Config,
+    loggers: Vec<Box<dyn SharedLogger>>,
+    level: LevelFilter,
+    filter: Option<&str>,
+) -> Result<()> {
+    let (user, error) = match filter.map(Directives::from_str).transpose() {
+        Ok(user) => (user, None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let tail = Arc::new(Mutex::new(VecDeque::with_capacity(TAIL_LINES)));
+    let mut loggers = loggers;
+    loggers.push(WriteLogger::new(
+        LevelFilter::Trace,
+        config,
+        TailWriter {
+            lines: tail.clone(),
+            partial: String::new(),
+        },
+    ));
+
+    let logger = DaemonLogger {
+        filter: RwLock::new(Filter::new(level, user)),
+        loggers,
+        tail,
+    };
+    if LOGGER.set(logger).is_err() {
+        bail!("The logger has already been configured");
+    }
+
+    let logger = LOGGER.get().unwrap();
+    log::set_logger(logger).context("Unable to install the logger")?;
+    log::set_max_level(logger.filter.read().unwrap().directives.max_level());
+
+    if let Some(e) = error {
+        log::warn!("Ignoring the saved Log Filter: {}", e);
+    }
+    Ok(())
+}
+
+pub fn set_level(level: LevelFilter) {
+    update(|filter| filter.level = level);
+}
+
+/// Replaces the user's filter directives, leaving the current filter in place if they're
+/// invalid. None removes them.
+pub fn set_filter(filter: Option<&str>) -> Result<()> {
+    let user = filter.map(Directives::from_str).transpose()?;
+    update(|filter| filter.user = user);
+    Ok(())
+}
+
+/// The last lines written to the log, oldest first.
+pub fn tail(lines: usize) -> Vec<String> {
+    match LOGGER.get() {
+        Some(logger) => {
+            let tail = logger.tail.lock().unwrap();
+            let skip = tail.len().saturating_sub(lines);
+            tail.iter().skip(skip).cloned().collect()
+        }
+        None => vec![],
+    }
+}
+
+pub fn level_filter(level: &LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Off => LevelFilter::Off,
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+        LogLevel::Trace => LevelFilter::Trace,
+    }
+}
+
+fn update(change: impl FnOnce(&mut Filter)) {
+    if let Some(logger) = LOGGER.get() {
+        let mut filter = logger.filter.write().unwrap();
+        change(&mut filter);
+        *filter = Filter::new(filter.level, filter.user.take());
+        log::set_max_level(filter.directives.max_level());
+    }
+}
+
+impl Log for DaemonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.read().unwrap().directives.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            for logger in &self.loggers {
+                logger.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        for logger in &self.loggers {
+            logger.flush();
+        }
+    }
+}
+
+impl Filter {
+    // The level and the defaults come first, so anything the user has set takes priority
+    fn new(level: LevelFilter, user: Option<Directives>) -> Self {
+        let mut directives = Directives::from_str(DEFAULT_DIRECTIVES).unwrap();
+        directives.level = Some(level);
+        if let Some(user) = &user {
+            directives.merge(user);
+        }
+
+        Self {
+            level,
+            user,
+            directives,
+        }
+    }
+}
+
+impl Directives {
+    fn merge(&mut self, other: &Directives) {
+        if other.level.is_some() {
+            self.level = other.level;
+        }
+        for (target, level) in &other.targets {
+            self.targets.retain(|(existing, _)| existing != target);
+            self.targets.push((target.clone(), *level));
+        }
+    }
+
+    // The most specific (longest) matching target decides, otherwise the overall level
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level.unwrap_or(LevelFilter::Info))
+    }
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        let targets = self.targets.iter().map(|(_, level)| *level);
+        targets.chain(self.level).max().unwrap_or(LevelFilter::Info)
+    }
+}
+
+impl FromStr for Directives {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut directives = Directives::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let target = target.trim();
+                    if target.is_empty() {
+                        bail!("Missing the target in '{}'", directive);
+                    }
+                    let level = LevelFilter::from_str(level.trim())
+                        .with_context(|| format!("Unknown log level in '{}'", directive))?;
+                    directives.targets.push((target.to_string(), level));
+                }
+
+                // As with env_logger, a level alone sets the overall level, and a target alone
+                // enables everything from it.
+                None => match LevelFilter::from_str(directive) {
+                    Ok(level) => directives.level = Some(level),
+                    Err(_) => directives
+                        .targets
+                        .push((directive.to_string(), LevelFilter::Trace)),
+                },
+            }
+        }
+        Ok(directives)
+    }
+}
+
+// simplelog writes a record in several pieces, so lines are only stored once they're complete
+struct TailWriter {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    partial: String,
+}
+
+impl Write for TailWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.partial.push_str(&String::from_utf8_lossy(buf));
+        while let Some(end) = self.partial.find('\n') {
+            let line = self.partial[..end].trim_end_matches('\r').to_string();
+            self.partial.drain(..=end);
+
+            let mut lines = self.lines.lock().unwrap();
+            if lines.len() == TAIL_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{Level, MetadataBuilder};
+
+    use super::*;
+
+    fn allows(directives: &Directives, target: &str, level: Level) -> bool {
+        let metadata = MetadataBuilder::new().target(target).level(level).build();
+        directives.enabled(&metadata)
+    }
+
+    #[test]
+    fn directives_are_parsed() {
+        let directives =
+            Directives::from_str("warn, goxlr_daemon::device=trace,goxlr_usb").unwrap();
+        assert_eq!(directives.level, Some(LevelFilter::Warn));
+        assert_eq!(
+            directives.targets,
+            vec![
+                (String::from("goxlr_daemon::device"), LevelFilter::Trace),
+                (String::from("goxlr_usb"), LevelFilter::Trace),
+            ]
+        );
+
+        assert!(Directives::from_str("goxlr_usb=loud").is_err());
+        assert!(Directives::from_str("=debug").is_err());
+    }
+
+    #[test]
+    fn most_specific_target_wins() {
+        let directives =
+            Directives::from_str("info,goxlr_daemon=warn,goxlr_daemon::device=trace").unwrap();
+        assert!(allows(&directives, "goxlr_daemon::device", Level::Trace));
+        assert!(!allows(&directives, "goxlr_daemon::profile", Level::Info));
+        assert!(allows(&directives, "goxlr_usb", Level::Info));
+        assert!(!allows(&directives, "goxlr_usb", Level::Debug));
+        assert_eq!(directives.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn user_filter_overrides_the_defaults() {
+        let filter = Filter::new(LevelFilter::Debug, None);
+        assert!(!allows(
+            &filter.directives,
+            "zbus::connection",
+            Level::Error
+        ));
+        assert!(allows(&filter.directives, "goxlr_daemon", Level::Debug));
+
+        let user = Directives::from_str("zbus=debug,goxlr_daemon=info").unwrap();
+        let filter = Filter::new(LevelFilter::Debug, Some(user));
+        assert!(allows(&filter.directives, "zbus::connection", Level::Debug));
+        assert!(!allows(&filter.directives, "goxlr_daemon", Level::Debug));
+        assert!(!allows(&filter.directives, "symphonia", Level::Error));
+    }
+
+    #[test]
+    fn tail_keeps_complete_lines() {
+        let lines = Arc::new(Mutex::new(VecDeque::new()));
+        let mut writer = TailWriter {
+            lines: lines.clone(),
+            partial: String::new(),
+        };
+
+        write!(writer, "12:00:00 [INFO] ").unwrap();
+        writeln!(writer, "First").unwrap();
+        write!(writer, "12:00:01 [INFO] Second").unwrap();
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec![String::from("12:00:00 [INFO] First")]
+        );
+
+        for index in 0..TAIL_LINES {
+            writeln!(writer, " {}", index).unwrap();
+        }
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), TAIL_LINES);
+        assert_eq!(lines.back().unwrap(), &format!(" {}", TAIL_LINES - 1));
+    }
+}
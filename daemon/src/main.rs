@@ -2,6 +2,7 @@
 
 extern crate core;
 
+use std::env;
 use std::fs::create_dir_all;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -15,45 +16,78 @@ use file_rotate::{ContentLimit, FileRotate};
 use json_patch::Patch;
 use lazy_static::lazy_static;
 use log::{debug, error, info, warn};
-use simplelog::{
-    ColorChoice, CombinedLogger, ConfigBuilder, TermLogger, TerminalMode, WriteLogger,
-};
+use simplelog::{ColorChoice, ConfigBuilder, TermLogger, TerminalMode, WriteLogger};
 use sys_locale::get_locale;
 
 use tokio::join;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::{broadcast, mpsc, watch};
 
-use goxlr_ipc::{HttpSettings, LogLevel};
+use goxlr_ipc::{ButtonEvent, ClaimOverride, HttpSettings, LogLevel, Notification, ShutdownReason};
 
 use crate::cli::{Cli, LevelFilter};
 use crate::events::{spawn_event_handler, DaemonState, EventTriggers};
 use crate::files::{spawn_file_notification_service, FileManager};
+use crate::migration::{find_alternate_data, get_alternate_data_warning};
+use crate::mute_sync::{create_mute_sync, spawn_mute_sync_service};
 use crate::platform::perform_preflight;
 use crate::platform::spawn_runtime;
 use crate::primary_worker::spawn_usb_handler;
+use crate::run_state::{begin_run, end_run, install_panic_hook};
+use crate::self_check::perform_self_check;
+use crate::servers::clients::ClientRegistry;
 use crate::servers::http_server::spawn_http_server;
-use crate::servers::ipc_server::{bind_socket, spawn_ipc_server};
-use crate::settings::SettingsHandle;
+use crate::servers::ipc_server::{bind_socket, set_socket_name, spawn_ipc_server, SOCKET_PATH_ENV};
+use crate::servers::web_content::set_ui_path;
+use crate::settings::{SettingsHandle, WritePolicy};
 use crate::shutdown::Shutdown;
+use crate::tray::TrayState;
 use crate::tts::spawn_tts_service;
 
 mod audio;
+mod capabilities;
 mod cli;
 mod device;
+mod disk;
+mod ducking;
+mod eq_curve;
 mod events;
 mod files;
+mod headset;
+mod icons;
+mod logging;
 mod mic_profile;
+#[cfg(feature = "midi")]
+mod midi;
+mod migration;
+#[cfg(test)]
+mod mock_device;
+mod mode_change;
+mod mute_sync;
 mod platform;
+mod polling;
 mod primary_worker;
+mod priority;
 mod profile;
+mod profile_rules;
+mod run_state;
+mod scheduler;
+mod self_check;
 mod servers;
 mod settings;
+mod settle;
 mod shutdown;
+mod simulated;
+mod support_bundle;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+mod timings;
 mod tray;
 mod tts;
+mod watchdog;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const ICON: &[u8] = include_bytes!("../resources/goxlr-utility-large.png");
+const LOG_FILE: &str = "goxlr-daemon.log";
 
 /**
 This is ugly, and I know it's ugly. I need to rework how the Primary Worker is constructed
@@ -69,6 +103,12 @@ static OVERRIDE_SAMPLER_OUTPUT: Mutex<Option<String>> = Mutex::new(None);
 */
 pub static HANDLE_MACOS_AGGREGATES: Mutex<Option<bool>> = Mutex::new(Some(true));
 
+/**
+    Whether devices compare their mute states and lighting with the profile after every command,
+    this is always on in debug builds, and can be enabled from the command line otherwise.
+*/
+pub static CONSISTENCY_CHECKS: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+
 lazy_static! {
     /**
         This is a fetcher of the system locale, used for language and translations of the UI.
@@ -79,19 +119,25 @@ lazy_static! {
         .replace('-', "_");
 }
 
-// This is for global 'JSON Patches', for when something changes.
+// Events pushed out to every connected client, 'JSON Patches' for when something changes, and
+// the announcements made by devices. Claim overrides are only passed on to the claimant.
 #[derive(Debug, Clone)]
-pub struct PatchEvent {
-    pub data: Patch,
+pub enum BroadcastEvent {
+    Patch(Patch),
+    Notification(Notification),
+    ClaimOverridden(ClaimOverride),
+    ButtonEvent(ButtonEvent),
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // If running the utility has an error, make sure log level is debug, and propagate the
     // error up to the user on Windows.
-    if let Err(e) = run_utility().await {
+    if let Err(e) = run_utility(Cli::parse(), Shutdown::new()).await {
+        end_run(ShutdownReason::Error(e.to_string()));
+
         let args: Cli = Cli::parse();
-        let settings = SettingsHandle::load(args.config).await?;
+        let settings = SettingsHandle::load(args.config, WritePolicy::new(args.read_only)).await?;
 
         if settings.get_log_level().await != LogLevel::Debug {
             info!("Setting Log Level to Debug for next run..");
@@ -109,11 +155,11 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn run_utility() -> Result<()> {
-    // We're just going to re-parse the args here, while we've technically done it above,
-    // they get moved into the settings loader, which just causes headaches :D
-    let args: Cli = Cli::parse();
-    let settings = SettingsHandle::load(args.config).await?;
+/// Runs the daemon until the shutdown is triggered (either by a client, the tray, or whoever
+/// created it). Where the IPC socket lives is taken from the args, so more than one daemon can
+/// be run at once.
+async fn run_utility(args: Cli, shutdown: Shutdown) -> Result<()> {
+    let settings = SettingsHandle::load(args.config, WritePolicy::new(args.read_only)).await?;
 
     // Set the MacOS Aggregate management..
     let aggregates = settings.get_macos_handle_aggregates().await;
@@ -126,25 +172,10 @@ async fn run_utility() -> Result<()> {
             bail!("Unable to create log directory: {}", e);
         }
     }
-    let log_file = log_path.join("goxlr-daemon.log");
+    let log_file = log_path.join(LOG_FILE);
 
-    // We need to ignore a couple of packages log output so create a builder.
+    // What's logged is decided by the logging module, so these accept everything.
     let mut config = ConfigBuilder::new();
-
-    // The tracing package, when used, will output to INFO from zbus every second..
-    config.add_filter_ignore_str("tracing");
-
-    // Actix is a little noisy on startup and shutdown, so quiet it a bit :)
-    config.add_filter_ignore_str("actix_server::accept");
-    config.add_filter_ignore_str("actix_server::worker");
-    config.add_filter_ignore_str("actix_server::server");
-    config.add_filter_ignore_str("actix_server::builder");
-    config.add_filter_ignore_str("zbus");
-
-    // I'm generally not interested in the Symphonia header announcements which go to INFO,
-    // it's only useful in a development setting!
-    config.add_filter_ignore_str("symphonia");
-
     let timezone_calculated = config.set_time_offset_to_local().is_ok();
 
     // Create a file rotator, that will compress and rotate files after 5Mb
@@ -168,35 +199,39 @@ async fn run_utility() -> Result<()> {
             LevelFilter::Trace => log::LevelFilter::Trace,
         }
     } else {
-        match settings.get_log_level().await {
-            LogLevel::Off => log::LevelFilter::Off,
-            LogLevel::Error => log::LevelFilter::Error,
-            LogLevel::Warn => log::LevelFilter::Warn,
-            LogLevel::Info => log::LevelFilter::Info,
-            LogLevel::Debug => log::LevelFilter::Debug,
-            LogLevel::Trace => log::LevelFilter::Trace,
-        }
+        logging::level_filter(&settings.get_log_level().await)
     };
 
     // Create the loggers :)
-    CombinedLogger::init(vec![
-        TermLogger::new(
-            log_level,
-            config.build(),
-            TerminalMode::Mixed,
-            ColorChoice::Auto,
-        ),
-        WriteLogger::new(log_level, config.build(), file_rotator),
-    ])
+    let log_filter = settings.get_log_filter().await;
+    logging::init(
+        config.build(),
+        vec![
+            TermLogger::new(
+                log::LevelFilter::Trace,
+                config.build(),
+                TerminalMode::Mixed,
+                ColorChoice::Auto,
+            ),
+            WriteLogger::new(log::LevelFilter::Trace, config.build(), file_rotator),
+        ],
+        log_level,
+        log_filter.as_deref(),
+    )
     .context("Could not configure the logger")?;
 
     // Enable the PANIC logger..
     log_panics::init();
+    install_panic_hook();
 
     if !timezone_calculated {
         warn!("Unable to calculate timezone, using UTC for log timestamps");
     }
 
+    if settings.write_policy().is_read_only() {
+        info!("Running in Read-Only mode, changes will not be saved");
+    }
+
     if cfg!(target_os = "macos") {
         debug!(
             "Configure MacOS Aggregates: {:?}",
@@ -226,6 +261,10 @@ async fn run_utility() -> Result<()> {
         }
     }
 
+    if args.consistency_checks {
+        CONSISTENCY_CHECKS.store(true, Ordering::Relaxed);
+    }
+
     if let Some(device) = args.override_sample_input_device {
         OVERRIDE_SAMPLER_INPUT.lock().unwrap().replace(device);
     }
@@ -234,9 +273,21 @@ async fn run_utility() -> Result<()> {
         OVERRIDE_SAMPLER_OUTPUT.lock().unwrap().replace(device);
     }
 
+    if let Some(path) = args.socket_path.or_else(|| env::var(SOCKET_PATH_ENV).ok()) {
+        debug!("Overriding IPC Socket location: {}", path);
+        set_socket_name(path);
+    }
+
     info!("Starting GoXLR Daemon v{}", VERSION);
     info!("System Locale: {}", *SYSTEM_LOCALE);
 
+    if !args.simulate.is_empty() {
+        warn!(
+            "Simulating {:?}, real devices will be ignored",
+            args.simulate
+        );
+    }
+
     // Before we do anything, perform platform pre-flight to make
     // sure we're allowed to start.
     info!("Performing Platform Preflight...");
@@ -257,12 +308,21 @@ async fn run_utility() -> Result<()> {
         bind_address,
         cors_enabled: args.http_enable_cors,
         port: args.http_port,
+        rate_limit: settings.get_http_rate_limit().await,
     };
 
+    // A UI path given on the command line has to be usable, one from the settings falls back
+    if let Some(path) = args.ui_path {
+        set_ui_path(Some(path))?;
+    } else if let Err(e) = set_ui_path(settings.get_ui_path().await) {
+        warn!("{}, serving the built in Web UI instead", e);
+        set_ui_path(None)?;
+    }
+
     // Create the Global Event Channel..
     let (global_tx, global_rx) = mpsc::channel(32);
 
-    // Create the 'Patch' Sending Channel..
+    // Create the Broadcast Channel, for Patches and Notifications..
     let (broadcast_tx, broadcast_rx) = broadcast::channel(16);
     drop(broadcast_rx);
 
@@ -275,12 +335,18 @@ async fn run_utility() -> Result<()> {
     // Create the HTTP Run Channel..
     let (httpd_tx, httpd_rx) = tokio::sync::oneshot::channel();
 
+    // Changes to the HTTP Rate Limits are passed straight on to the server..
+    let (rate_limit_tx, rate_limit_rx) = watch::channel(http_settings.rate_limit.clone());
+
     // Create the Device shutdown signallers..
     let (device_state_tx, device_state_rx) = mpsc::channel(1);
 
+    // Create the Tray State channel..
+    let (tray_state_tx, tray_state_rx) = watch::channel(TrayState::default());
+
     // Create the Shutdown Signallers..
-    let shutdown = Shutdown::new();
     let shutdown_blocking = Arc::new(AtomicBool::new(false));
+    let shutdown_reason = Arc::new(Mutex::new(None));
 
     // Configure Showing the Tray Icon
     let show_tray = Arc::new(AtomicBool::new(settings.get_show_tray_icon().await));
@@ -288,13 +354,22 @@ async fn run_utility() -> Result<()> {
         show_tray.store(override_tray, Ordering::Relaxed);
     }
 
+    // This has to happen before the File Manager fills any empty directories with defaults
+    let alternate_data = find_alternate_data(&settings).await;
+
     // Configure, and Start the File Manager Service..
     let file_manager = FileManager::new(&settings).await;
-    let file_paths = file_manager.paths().clone();
+    let http_file_paths = file_manager.subscribe();
+
+    // Now the directories have been created, make sure everything we need is usable.
+    let mut startup_warnings = perform_self_check(&settings).await?;
+    if let Some(root) = &alternate_data {
+        startup_warnings.push(get_alternate_data_warning(root));
+    }
 
     let (file_tx, file_rx) = mpsc::channel(20);
     let file_handle = tokio::spawn(spawn_file_notification_service(
-        file_paths.clone(),
+        file_manager.subscribe(),
         file_tx,
         shutdown.clone(),
     ));
@@ -306,6 +381,22 @@ async fn run_utility() -> Result<()> {
         bail!("{}", e);
     }
 
+    // Now we know no other daemon is running here, find out how the last one ended.
+    let previous_run = match settings.write_policy().is_read_only() {
+        true => None,
+        false => settings.get_config_path().parent().and_then(begin_run),
+    };
+
+    let (mute_sync_handle, mute_sync_service) = create_mute_sync();
+
+    // The MIDI Service (if built in) reports how it's doing, and is told when its settings change
+    let (midi_status_tx, midi_status_rx) = watch::channel(None);
+    let (midi_reload_tx, midi_reload_rx) = watch::channel(());
+
+    // Clients of both servers are tracked together, so either can list or disconnect them, the
+    // device handler needs them for the channels they've claimed
+    let clients = ClientRegistry::new();
+
     // Start the USB Device Handler
     let usb_handle = tokio::spawn(spawn_usb_handler(
         usb_rx,
@@ -313,10 +404,20 @@ async fn run_utility() -> Result<()> {
         device_state_rx,
         broadcast_tx.clone(),
         global_tx.clone(),
+        tray_state_tx,
         shutdown.clone(),
         settings.clone(),
         http_settings.clone(),
+        rate_limit_tx,
         file_manager,
+        startup_warnings,
+        previous_run,
+        alternate_data,
+        mute_sync_handle,
+        midi_status_rx,
+        midi_reload_tx,
+        clients.clone(),
+        args.simulate,
     ));
 
     // Launch the IPC Server..
@@ -324,6 +425,8 @@ async fn run_utility() -> Result<()> {
     let communications_handle = tokio::spawn(spawn_ipc_server(
         ipc_socket,
         usb_tx.clone(),
+        broadcast_tx.clone(),
+        clients.clone(),
         shutdown.clone(),
     ));
 
@@ -340,7 +443,9 @@ async fn run_utility() -> Result<()> {
             httpd_tx,
             broadcast_tx.clone(),
             http_settings.clone(),
-            file_paths.clone(),
+            http_file_paths,
+            rate_limit_rx,
+            clients,
         ));
         http_server = httpd_rx.await?;
         if let Err(e) = http_server {
@@ -354,6 +459,36 @@ async fn run_utility() -> Result<()> {
     let tts_handle = tokio::spawn(spawn_tts_service(
         settings.clone(),
         tts_rx,
+        broadcast_tx.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the Mute Sync Service..
+    let mute_sync_handle = tokio::spawn(spawn_mute_sync_service(
+        mute_sync_service,
+        settings.clone(),
+        global_tx.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the MIDI Service..
+    #[cfg(feature = "midi")]
+    let midi_handle = tokio::spawn(midi::spawn_midi_service(
+        settings.clone(),
+        usb_tx.clone(),
+        midi_status_tx,
+        midi_reload_rx,
+        shutdown.clone(),
+    ));
+
+    // Without it, the status stays empty and clients know MIDI isn't available
+    #[cfg(not(feature = "midi"))]
+    drop((midi_status_tx, midi_reload_rx));
+
+    // Start the Usage Statistics Service..
+    #[cfg(feature = "telemetry")]
+    let telemetry_handle = tokio::spawn(telemetry::spawn_telemetry_service(
+        settings.clone(),
         shutdown.clone(),
     ));
 
@@ -364,9 +499,11 @@ async fn run_utility() -> Result<()> {
         show_tray,
         shutdown,
         shutdown_blocking,
+        shutdown_reason: shutdown_reason.clone(),
 
         settings_handle: settings.clone(),
         http_settings: http_settings.clone(),
+        tray_state: tray_state_rx,
     };
 
     // Spawn the general event handler..
@@ -399,6 +536,7 @@ async fn run_utility() -> Result<()> {
             server.stop(false),
             file_handle,
             tts_handle,
+            mute_sync_handle,
             event_handle,
             platform_handle
         );
@@ -408,10 +546,20 @@ async fn run_utility() -> Result<()> {
             communications_handle,
             file_handle,
             tts_handle,
+            mute_sync_handle,
             event_handle,
             platform_handle
         );
     }
+
+    #[cfg(feature = "midi")]
+    let _ = midi_handle.await;
+
+    #[cfg(feature = "telemetry")]
+    let _ = telemetry_handle.await;
+
+    let reason = shutdown_reason.lock().unwrap().take();
+    end_run(reason.unwrap_or(ShutdownReason::UserRequest));
     Ok(())
 }
 
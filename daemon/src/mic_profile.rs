@@ -1,4 +1,5 @@
-use crate::files::can_create_new_file;
+use crate::disk::{write_atomic, DiskFileSystem};
+use crate::files::{can_create_new_file, check_existing_name};
 use crate::profile::ProfileAdapter;
 use anyhow::{anyhow, bail, Context, Result};
 use byteorder::{ByteOrder, LittleEndian};
@@ -8,7 +9,8 @@ use goxlr_profile_loader::components::mute::MuteFunction;
 use goxlr_profile_loader::mic_profile::MicProfileSettings;
 use goxlr_types::{
     CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode, EffectKey,
-    EqFrequencies, GateTimes, MicrophoneParamKey, MicrophoneType, MiniEqFrequencies,
+    EqFrequencies, GateTimes, MicProfileSection, MicrophoneParamKey, MicrophoneType,
+    MiniEqFrequencies,
 };
 use log::warn;
 use ritelinked::LinkedHashSet;
@@ -30,10 +32,14 @@ static GATE_ATTENUATION: [i8; 26] = [
 pub struct MicProfileAdapter {
     name: String,
     profile: MicProfileSettings,
+
+    // Set by anything which changes the profile, cleared when it's written to disk.
+    dirty: bool,
 }
 
 impl MicProfileAdapter {
     pub fn from_named(name: String, directory: &Path) -> Result<Self> {
+        check_existing_name(&name, "goxlrMicProfile", directory)?;
         let path = directory.join(format!("{name}.goxlrMicProfile"));
         if path.is_file() {
             let file = File::open(path).context("Couldn't open mic profile for reading")?;
@@ -64,7 +70,11 @@ impl MicProfileAdapter {
 
     pub fn from_reader<R: Read + Seek>(name: String, reader: R) -> Result<Self> {
         let profile = MicProfileSettings::load(reader)?;
-        Ok(Self { name, profile })
+        Ok(Self {
+            name,
+            profile,
+            dirty: false,
+        })
     }
 
     pub fn can_create_new_file(name: String, directory: &Path) -> Result<()> {
@@ -84,11 +94,19 @@ impl MicProfileAdapter {
             return Err(anyhow!("Profile exists, will not overwrite"));
         }
 
-        self.profile.save(path)?;
+        let mut contents = Vec::new();
+        self.profile.write_to(&mut contents)?;
+        write_atomic(&DiskFileSystem, &path, &contents)?;
+        self.dirty = false;
         Ok(())
     }
 
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
     pub fn delete_profile(&mut self, name: String, directory: &Path) -> Result<()> {
+        check_existing_name(&name, "goxlrMicProfile", directory)?;
         let path = directory.join(format!("{name}.goxlrMicProfile"));
         if path.is_file() {
             remove_file(path)?;
@@ -127,6 +145,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_gate_display_mode(&mut self, display_mode: DisplayMode) {
+        self.dirty = true;
         self.profile
             .ui_setup_mut()
             .set_gate_advanced(display_mode != DisplayMode::Simple);
@@ -141,6 +160,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_compressor_display_mode(&mut self, display_mode: DisplayMode) {
+        self.dirty = true;
         self.profile
             .ui_setup_mut()
             .set_comp_advanced(display_mode != DisplayMode::Simple);
@@ -155,6 +175,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_eq_display_mode(&mut self, display_mode: DisplayMode) {
+        self.dirty = true;
         self.profile
             .ui_setup_mut()
             .set_eq_advanced(display_mode != DisplayMode::Simple);
@@ -169,6 +190,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_eq_fine_display_mode(&mut self, display_mode: DisplayMode) {
+        self.dirty = true;
         self.profile
             .ui_setup_mut()
             .set_eq_fine_tune(display_mode != DisplayMode::Simple);
@@ -239,10 +261,12 @@ impl MicProfileAdapter {
     }
 
     pub fn set_mic_type(&mut self, mic_type: MicrophoneType) -> Result<()> {
+        self.dirty = true;
         self.profile.setup_mut().set_mic_type(mic_type as u8)
     }
 
     pub fn set_mic_gain(&mut self, mic_type: MicrophoneType, gain: u16) -> Result<()> {
+        self.dirty = true;
         match mic_type {
             MicrophoneType::Dynamic => self.profile.setup_mut().set_dynamic_mic_gain(gain)?,
             MicrophoneType::Condenser => self.profile.setup_mut().set_condenser_mic_gain(gain)?,
@@ -252,6 +276,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_eq_gain(&mut self, gain: EqFrequencies, value: i8) -> Result<EffectKey> {
+        self.dirty = true;
         match gain {
             EqFrequencies::Equalizer31Hz => {
                 self.profile.equalizer_mut().set_eq_31h_gain(value)?;
@@ -313,6 +338,7 @@ impl MicProfileAdapter {
     }
 
     pub fn set_eq_freq(&mut self, freq: EqFrequencies, value: f32) -> Result<EffectKey> {
+        self.dirty = true;
         match freq {
             EqFrequencies::Equalizer31Hz => {
                 let max = self.profile.equalizer().eq_63h_freq();
@@ -467,6 +493,7 @@ impl MicProfileAdapter {
         gain: MiniEqFrequencies,
         value: i8,
     ) -> Result<MicrophoneParamKey> {
+        self.dirty = true;
         match gain {
             MiniEqFrequencies::Equalizer90Hz => {
                 self.profile.equalizer_mini_mut().set_eq_90h_gain(value)?;
@@ -512,6 +539,7 @@ impl MicProfileAdapter {
         freq: MiniEqFrequencies,
         value: f32,
     ) -> Result<MicrophoneParamKey> {
+        self.dirty = true;
         match freq {
             MiniEqFrequencies::Equalizer90Hz => {
                 let max = self.profile.equalizer_mini().eq_1k_freq();
@@ -601,51 +629,75 @@ impl MicProfileAdapter {
         }
     }
 
+    pub fn gate_threshold(&self) -> i8 {
+        self.profile.gate().threshold()
+    }
+
     pub fn set_gate_threshold(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         self.profile.gate_mut().set_threshold(value)
     }
 
     pub fn set_gate_attenuation(&mut self, value: u8) -> Result<()> {
+        self.dirty = true;
         self.profile.gate_mut().set_attenuation(value)
     }
 
     pub fn set_gate_attack(&mut self, value: GateTimes) -> Result<()> {
+        self.dirty = true;
         self.profile.gate_mut().set_attack(value as u8)
     }
 
     pub fn set_gate_release(&mut self, value: GateTimes) -> Result<()> {
+        self.dirty = true;
         self.profile.gate_mut().set_release(value as u8)
     }
 
     pub fn set_gate_active(&mut self, value: bool) -> Result<()> {
+        self.dirty = true;
         self.profile.gate_mut().set_enabled(value)
     }
 
+    pub fn compressor_threshold(&self) -> i8 {
+        self.profile.compressor().threshold()
+    }
+
     pub fn set_compressor_threshold(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         self.profile.compressor_mut().set_threshold(value)
     }
 
     pub fn set_compressor_ratio(&mut self, value: CompressorRatio) -> Result<()> {
+        self.dirty = true;
         self.profile.compressor_mut().set_ratio(value as u8)
     }
 
     pub fn set_compressor_attack(&mut self, value: CompressorAttackTime) -> Result<()> {
+        self.dirty = true;
         self.profile.compressor_mut().set_attack(value as u8)
     }
 
     pub fn set_compressor_release(&mut self, value: CompressorReleaseTime) -> Result<()> {
+        self.dirty = true;
         self.profile.compressor_mut().set_release(value as u8)
     }
 
+    pub fn compressor_makeup(&self) -> i8 {
+        self.profile.compressor().makeup()
+    }
+
     pub fn set_compressor_makeup(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         self.profile.compressor_mut().set_makeup_gain(value)
     }
 
     pub fn set_deesser(&mut self, value: u8) -> Result<()> {
+        self.dirty = true;
         self.profile.set_deess(value)
     }
 
     pub fn set_bleep_level(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         self.profile.set_bleep_level(value)
     }
 
@@ -653,6 +705,123 @@ impl MicProfileAdapter {
         self.profile.bleep_level()
     }
 
+    /// Replaces one section of this profile with the same section from another.
+    pub fn copy_section(&mut self, section: MicProfileSection, source: &Self) -> Result<()> {
+        self.dirty = true;
+
+        let source = &source.profile;
+        match section {
+            MicProfileSection::Microphone => *self.profile.setup_mut() = source.setup().clone(),
+            MicProfileSection::Gate => {
+                *self.profile.gate_mut() = source.gate().clone();
+                self.profile.set_gate_mode(source.gate_mode());
+            }
+            MicProfileSection::Compressor => {
+                *self.profile.compressor_mut() = source.compressor().clone();
+                self.profile.set_comp_select(source.comp_select());
+            }
+            MicProfileSection::Equaliser => {
+                *self.profile.equalizer_mut() = source.equalizer().clone();
+                *self.profile.equalizer_mini_mut() = source.equalizer_mini().clone();
+            }
+            MicProfileSection::DeEsser => self.profile.set_deess(source.deess())?,
+            MicProfileSection::Bleep => self.profile.set_bleep_level(source.bleep_level())?,
+        }
+        Ok(())
+    }
+
+    /// The Mic Params which need sending after a section has changed, the Microphone section
+    /// isn't included as the gain is applied separately.
+    pub fn get_section_params(&self, section: MicProfileSection) -> HashSet<MicrophoneParamKey> {
+        use MicrophoneParamKey as Key;
+
+        let keys: &[MicrophoneParamKey] = match section {
+            MicProfileSection::Microphone | MicProfileSection::DeEsser => &[],
+            MicProfileSection::Gate => &[
+                Key::GateThreshold,
+                Key::GateAttack,
+                Key::GateRelease,
+                Key::GateAttenuation,
+            ],
+            MicProfileSection::Compressor => &[
+                Key::CompressorThreshold,
+                Key::CompressorRatio,
+                Key::CompressorAttack,
+                Key::CompressorRelease,
+                Key::CompressorMakeUpGain,
+            ],
+            MicProfileSection::Equaliser => &[
+                Key::Equalizer90HzFrequency,
+                Key::Equalizer90HzGain,
+                Key::Equalizer250HzFrequency,
+                Key::Equalizer250HzGain,
+                Key::Equalizer500HzFrequency,
+                Key::Equalizer500HzGain,
+                Key::Equalizer1KHzFrequency,
+                Key::Equalizer1KHzGain,
+                Key::Equalizer3KHzFrequency,
+                Key::Equalizer3KHzGain,
+                Key::Equalizer8KHzFrequency,
+                Key::Equalizer8KHzGain,
+            ],
+            MicProfileSection::Bleep => &[Key::BleepLevel],
+        };
+        keys.iter().copied().collect()
+    }
+
+    /// The subset of get_mic_keys belonging to a section.
+    pub fn get_section_effects(&self, section: MicProfileSection) -> LinkedHashSet<EffectKey> {
+        use EffectKey as Key;
+
+        let mut keys = LinkedHashSet::new();
+        keys.extend(self.get_mic_keys().into_iter().filter(|key| match section {
+            MicProfileSection::Microphone => false,
+            MicProfileSection::Gate => matches!(
+                key,
+                Key::GateMode
+                    | Key::GateEnabled
+                    | Key::GateThreshold
+                    | Key::GateAttenuation
+                    | Key::GateAttack
+                    | Key::GateRelease
+            ),
+            MicProfileSection::Compressor => matches!(
+                key,
+                Key::CompressorThreshold
+                    | Key::CompressorRatio
+                    | Key::CompressorAttack
+                    | Key::CompressorRelease
+                    | Key::CompressorMakeUpGain
+            ),
+            MicProfileSection::Equaliser => matches!(
+                key,
+                Key::Equalizer31HzFrequency
+                    | Key::Equalizer31HzGain
+                    | Key::Equalizer63HzFrequency
+                    | Key::Equalizer63HzGain
+                    | Key::Equalizer125HzFrequency
+                    | Key::Equalizer125HzGain
+                    | Key::Equalizer250HzFrequency
+                    | Key::Equalizer250HzGain
+                    | Key::Equalizer500HzFrequency
+                    | Key::Equalizer500HzGain
+                    | Key::Equalizer1KHzFrequency
+                    | Key::Equalizer1KHzGain
+                    | Key::Equalizer2KHzFrequency
+                    | Key::Equalizer2KHzGain
+                    | Key::Equalizer4KHzFrequency
+                    | Key::Equalizer4KHzGain
+                    | Key::Equalizer8KHzFrequency
+                    | Key::Equalizer8KHzGain
+                    | Key::Equalizer16KHzFrequency
+                    | Key::Equalizer16KHzGain
+            ),
+            MicProfileSection::DeEsser => *key == Key::DeEsser,
+            MicProfileSection::Bleep => *key == Key::BleepLevel,
+        }));
+        keys
+    }
+
     /// The uber method, fetches the relevant setting from the profile and returns it..
     pub fn get_param_value(&self, param: MicrophoneParamKey) -> [u8; 4] {
         let gains = self.mic_gains();
@@ -764,10 +933,7 @@ impl MicProfileAdapter {
         // This essentially clones some behaviour from the main device to do checks.
         let (_, muted_to_x, muted_to_all, mute_function) = profile.get_mute_chat_button_state();
 
-        let muted_by_fader = if profile.is_mic_on_fader() {
-            // We need to check this fader's mute button..
-            let fader = profile.get_mic_fader();
-
+        let muted_by_fader = if let Some(fader) = profile.get_mic_fader() {
             // Get the faders mute configuration
             let (muted_to_x, muted_to_all, mute_function) = profile.get_mute_button_state(fader);
 
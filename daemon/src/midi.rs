@@ -0,0 +1,403 @@
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use goxlr_ipc::{MidiControl, MidiEvent, MidiMapping, MidiMappingStatus, MidiState, MidiStatus};
+use log::{debug, info, warn};
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use tokio::sync::{oneshot, watch};
+use tokio::time::{interval, sleep, MissedTickBehavior};
+
+use crate::primary_worker::{DeviceCommand, DeviceSender};
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+
+/*
+ * Listens to a MIDI input port, and runs the mapped GoXLRCommand when one of its controls
+ * changes. A fader can send dozens of messages a second, far more than the GoXLR needs, so
+ * messages are collected as they arrive and only the latest value of each control is acted on
+ * every tick.
+ */
+
+const CLIENT_NAME: &str = "GoXLR Utility";
+const TICK: Duration = Duration::from_millis(40);
+
+// The controller may simply not be plugged in yet
+const RETRY_PORT: Duration = Duration::from_secs(5);
+
+/// Messages received since the last tick, filled from midir's own thread.
+#[derive(Debug, Default)]
+struct Pending {
+    values: Vec<(MidiControl, u8)>,
+    last_event: Option<MidiEvent>,
+}
+
+impl Pending {
+    fn push(&mut self, control: MidiControl, value: u8) {
+        match self
+            .values
+            .iter_mut()
+            .find(|(existing, _)| *existing == control)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.values.push((control, value)),
+        }
+        self.last_event = Some(MidiEvent { control, value });
+    }
+
+    fn take(&mut self) -> (Vec<(MidiControl, u8)>, Option<MidiEvent>) {
+        (std::mem::take(&mut self.values), self.last_event.take())
+    }
+}
+
+// Closing the port needs to happen on the thread which opened it, so the connection lives on a
+// thread of its own until this is dropped.
+struct Listener {
+    _stop: std_mpsc::Sender<()>,
+}
+
+enum ListenEnd {
+    Reload,
+    Shutdown,
+}
+
+pub fn get_ports() -> Result<Vec<String>> {
+    let input = MidiInput::new(CLIENT_NAME)?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+pub async fn spawn_midi_service(
+    settings: SettingsHandle,
+    usb_tx: DeviceSender,
+    status_tx: watch::Sender<Option<MidiStatus>>,
+    mut reload: watch::Receiver<()>,
+    mut shutdown: Shutdown,
+) {
+    loop {
+        let config = settings.get_midi().await;
+        let mut status = MidiStatus {
+            enabled: config.enabled,
+            port: config.port.clone(),
+            state: MidiState::Disabled,
+            mappings: config
+                .mappings
+                .into_iter()
+                .map(|mapping| MidiMappingStatus {
+                    mapping,
+                    last_value: None,
+                    last_error: None,
+                })
+                .collect(),
+            last_event: None,
+        };
+
+        let retry = match (config.enabled, config.port) {
+            (false, _) => None,
+            (true, None) => {
+                status.state = MidiState::NotConfigured;
+                None
+            }
+            (true, Some(port)) => {
+                let pending = Arc::new(Mutex::new(Pending::default()));
+                match connect(&port, pending.clone()).await {
+                    Ok(Some(listener)) => {
+                        info!("Listening for MIDI on {}", port);
+                        status.state = MidiState::Connected;
+                        status_tx.send_replace(Some(status.clone()));
+
+                        let end = listen(
+                            &pending,
+                            &mut status,
+                            &usb_tx,
+                            &status_tx,
+                            &mut reload,
+                            &mut shutdown,
+                        )
+                        .await;
+
+                        drop(listener);
+                        match end {
+                            ListenEnd::Reload => continue,
+                            ListenEnd::Shutdown => break,
+                        }
+                    }
+                    Ok(None) => {
+                        debug!("MIDI Port {} not found", port);
+                        status.state = MidiState::PortNotFound;
+                        Some(RETRY_PORT)
+                    }
+                    Err(e) => {
+                        warn!("Unable to listen to MIDI Port {}: {:#}", port, e);
+                        status.state = MidiState::Error(format!("{:#}", e));
+                        Some(RETRY_PORT)
+                    }
+                }
+            }
+        };
+        status_tx.send_replace(Some(status));
+
+        tokio::select! {
+            () = wait_for_retry(retry) => {},
+            Ok(()) = reload.changed() => {},
+            () = shutdown.recv() => break,
+        }
+    }
+    debug!("MIDI Service Stopped");
+}
+
+async fn connect(port: &str, pending: Arc<Mutex<Pending>>) -> Result<Option<Listener>> {
+    let (result_tx, result_rx) = oneshot::channel();
+    let (stop_tx, stop_rx) = std_mpsc::channel::<()>();
+
+    let port = port.to_string();
+    thread::spawn(move || match open_port(&port, pending) {
+        Ok(Some(connection)) => {
+            let _ = result_tx.send(Ok(true));
+
+            // This only returns once the Listener has gone
+            let _ = stop_rx.recv();
+            connection.close();
+        }
+        Ok(None) => {
+            let _ = result_tx.send(Ok(false));
+        }
+        Err(e) => {
+            let _ = result_tx.send(Err(e));
+        }
+    });
+
+    match result_rx.await? {
+        Ok(true) => Ok(Some(Listener { _stop: stop_tx })),
+        Ok(false) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn open_port(name: &str, pending: Arc<Mutex<Pending>>) -> Result<Option<MidiInputConnection<()>>> {
+    let mut input = MidiInput::new(CLIENT_NAME)?;
+    input.ignore(Ignore::All);
+
+    let port = input
+        .ports()
+        .into_iter()
+        .find(|port| input.port_name(port).is_ok_and(|port| port == name));
+    let port = match port {
+        Some(port) => port,
+        None => return Ok(None),
+    };
+
+    let connection = input
+        .connect(
+            &port,
+            CLIENT_NAME,
+            move |_, message, _| {
+                if let Some((control, value)) = parse_message(message) {
+                    pending.lock().unwrap().push(control, value);
+                }
+            },
+            (),
+        )
+        .map_err(|e| anyhow!("{}", e))?;
+    Ok(Some(connection))
+}
+
+async fn listen(
+    pending: &Mutex<Pending>,
+    status: &mut MidiStatus,
+    usb_tx: &DeviceSender,
+    status_tx: &watch::Sender<Option<MidiStatus>>,
+    reload: &mut watch::Receiver<()>,
+    shutdown: &mut Shutdown,
+) -> ListenEnd {
+    let mut ticker = interval(TICK);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let (values, last_event) = pending.lock().unwrap().take();
+                if values.is_empty() {
+                    continue;
+                }
+
+                for (control, value) in values {
+                    let mapping = status
+                        .mappings
+                        .iter_mut()
+                        .find(|mapping| mapping.mapping.control == control);
+
+                    if let Some(mapping) = mapping {
+                        mapping.last_value = Some(value);
+                        mapping.last_error = match run_mapping(&mapping.mapping, value, usb_tx).await {
+                            Ok(()) => None,
+                            Err(e) => {
+                                debug!("MIDI Mapping for {:?} failed: {:#}", control, e);
+                                Some(format!("{:#}", e))
+                            }
+                        };
+                    }
+                }
+                status.last_event = last_event;
+                status_tx.send_replace(Some(status.clone()));
+            }
+            Ok(()) = reload.changed() => return ListenEnd::Reload,
+            () = shutdown.recv() => return ListenEnd::Shutdown,
+        }
+    }
+}
+
+async fn run_mapping(mapping: &MidiMapping, value: u8, usb_tx: &DeviceSender) -> Result<()> {
+    let command = match mapping.command_for(value)? {
+        Some(command) => command,
+        None => return Ok(()),
+    };
+
+    let (tx, rx) = oneshot::channel();
+    let serial = mapping.serial.clone();
+    usb_tx
+        .send(DeviceCommand::RunDeviceCommand(serial, command, None, tx))
+        .await
+        .map_err(|_| anyhow!("The device handler isn't running"))?;
+    rx.await??;
+    Ok(())
+}
+
+/// Reads a Control Change or Note message, anything else (including System messages) is ignored.
+fn parse_message(message: &[u8]) -> Option<(MidiControl, u8)> {
+    let (status, number, value) = match message {
+        [status, number, value] => (*status, *number, *value),
+        _ => return None,
+    };
+    let channel = status & 0x0F;
+
+    match status & 0xF0 {
+        0xB0 => Some((
+            MidiControl::ControlChange {
+                channel,
+                controller: number,
+            },
+            value,
+        )),
+        0x90 => Some((
+            MidiControl::Note {
+                channel,
+                note: number,
+            },
+            value,
+        )),
+
+        // Note Off has a release velocity, but a released note is always 0
+        0x80 => Some((
+            MidiControl::Note {
+                channel,
+                note: number,
+            },
+            0,
+        )),
+        _ => None,
+    }
+}
+
+async fn wait_for_retry(retry: Option<Duration>) {
+    match retry {
+        Some(duration) => sleep(duration).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fader() -> MidiControl {
+        MidiControl::ControlChange {
+            channel: 0,
+            controller: 7,
+        }
+    }
+
+    #[test]
+    fn messages_are_parsed() {
+        assert_eq!(
+            parse_message(&[0xB3, 7, 100]),
+            Some((
+                MidiControl::ControlChange {
+                    channel: 3,
+                    controller: 7
+                },
+                100
+            ))
+        );
+
+        let note = MidiControl::Note {
+            channel: 0,
+            note: 60,
+        };
+        assert_eq!(parse_message(&[0x90, 60, 90]), Some((note, 90)));
+        assert_eq!(parse_message(&[0x80, 60, 64]), Some((note, 0)));
+
+        // Pitch Bend, and a truncated message
+        assert_eq!(parse_message(&[0xE0, 0, 64]), None);
+        assert_eq!(parse_message(&[0xB0, 7]), None);
+    }
+
+    #[test]
+    fn values_are_coalesced_per_control() {
+        let button = MidiControl::Note {
+            channel: 0,
+            note: 36,
+        };
+
+        let mut pending = Pending::default();
+        for value in [10, 20, 30] {
+            pending.push(fader(), value);
+        }
+        pending.push(button, 127);
+        pending.push(fader(), 40);
+
+        let (values, last_event) = pending.take();
+        assert_eq!(values, vec![(fader(), 40), (button, 127)]);
+        assert_eq!(
+            last_event,
+            Some(MidiEvent {
+                control: fader(),
+                value: 40
+            })
+        );
+        assert_eq!(pending.take(), (vec![], None));
+    }
+
+    #[test]
+    fn values_are_scaled_into_the_command() {
+        let mapping = MidiMapping {
+            control: fader(),
+            serial: String::from("S210400000CQK"),
+            command: json!({ "SetVolume": ["Chat", "$value"] }),
+            min: 0,
+            max: 255,
+        };
+        let volume = |value| match mapping.command_for(value).unwrap() {
+            Some(command) => serde_json::to_value(command).unwrap(),
+            None => panic!("No command for {}", value),
+        };
+        assert_eq!(volume(0), json!({ "SetVolume": ["Chat", 0] }));
+        assert_eq!(volume(64), json!({ "SetVolume": ["Chat", 129] }));
+        assert_eq!(volume(127), json!({ "SetVolume": ["Chat", 255] }));
+
+        // Without a value to fill in, only a press runs the command
+        let mapping = MidiMapping {
+            command: json!({ "SetFXEnabled": true }),
+            ..mapping
+        };
+        assert!(mapping.command_for(0).unwrap().is_none());
+        assert!(mapping.command_for(127).unwrap().is_some());
+    }
+}
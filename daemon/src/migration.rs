@@ -0,0 +1,442 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::{self, create_dir_all};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use directories::BaseDirs;
+use glob::glob;
+use goxlr_ipc::{DataMigration, MigrationKind, MigrationProgress, MigrationState, StartupWarning};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc::Sender;
+
+use crate::files::{FileManager, FilePaths};
+use crate::settings::{get_data_paths_in, SettingsHandle};
+
+/// If the profile directory has no profiles (so is about to be filled with the defaults), this
+/// looks for another installation's data in the places it's normally found. This needs to be
+/// called before the FileManager creates the directories.
+pub async fn find_alternate_data(settings: &SettingsHandle) -> Option<PathBuf> {
+    let profiles = settings.get_profile_directory().await;
+    if has_profiles(&profiles) {
+        return None;
+    }
+
+    let alternate = get_alternate_roots(settings).into_iter().find(|root| {
+        let alternate_profiles = get_data_paths_in(root).profiles;
+        alternate_profiles != profiles && has_profiles(&alternate_profiles)
+    });
+
+    if let Some(root) = &alternate {
+        warn!(
+            "No profiles found, but {} contains data from another installation",
+            root.to_string_lossy()
+        );
+    }
+    alternate
+}
+
+pub fn get_alternate_data_warning(root: &Path) -> StartupWarning {
+    StartupWarning {
+        check: String::from("Data Directory"),
+        message: format!(
+            "No profiles were found, but {} contains data from another installation which can \
+            be imported",
+            root.to_string_lossy()
+        ),
+    }
+}
+
+fn get_alternate_roots(settings: &SettingsHandle) -> Vec<PathBuf> {
+    let data_directory = settings.get_data_directory();
+
+    // The default location, in case a custom directory has been configured but never filled
+    let mut roots = vec![data_directory.to_path_buf()];
+
+    // A Flatpak keeps its data inside its sandbox, while a native install uses the home
+    // directory, each can find the other from the home directory.
+    if cfg!(target_os = "linux") {
+        if let (Some(dirs), Some(name)) = (BaseDirs::new(), data_directory.file_name()) {
+            let home = dirs.home_dir();
+            roots.push(home.join(".local/share").join(name));
+
+            let pattern = home.join(".var/app/*/data").join(name);
+            if let Ok(paths) = glob(&pattern.to_string_lossy()) {
+                roots.extend(paths.flatten());
+            }
+        }
+    }
+    roots
+}
+
+fn has_profiles(directory: &Path) -> bool {
+    match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .flatten()
+            .any(|entry| entry.path().extension() == Some(OsStr::new("goxlr"))),
+        Err(_) => false,
+    }
+}
+
+/// Checks a migration is able to go ahead, then starts copying in the background. Further
+/// progress is sent to updates, ending with Complete or Failed.
+pub async fn start_migration(
+    kind: MigrationKind,
+    settings: &SettingsHandle,
+    current: &FilePaths,
+    migration: &mut DataMigration,
+    updates: Sender<MigrationProgress>,
+) -> Result<()> {
+    settings
+        .write_policy()
+        .check("migrate the data directory")?;
+
+    if let Some(previous) = &migration.progress {
+        if matches!(
+            previous.state,
+            MigrationState::Copying | MigrationState::Verifying
+        ) {
+            bail!("A migration is already in progress");
+        }
+    }
+
+    let directories = match &kind {
+        MigrationKind::MoveTo(root) => {
+            if root.exists() && !root.is_dir() {
+                bail!("{} is not a directory", root.to_string_lossy());
+            }
+            get_directory_pairs(current, &get_data_paths_in(root))
+        }
+        MigrationKind::ImportFrom(root) => {
+            if !root.is_dir() {
+                bail!("{} is not a directory", root.to_string_lossy());
+            }
+            get_directory_pairs(&get_data_paths_in(root), current)
+        }
+    };
+
+    // Copying a directory into itself (or one inside it) would never finish
+    for (source, target) in &directories {
+        if target.starts_with(source) || source.starts_with(target) {
+            bail!(
+                "{} and {} overlap",
+                source.to_string_lossy(),
+                target.to_string_lossy()
+            );
+        }
+    }
+
+    let progress = MigrationProgress {
+        kind,
+        state: MigrationState::Copying,
+        files_total: 0,
+        files_copied: 0,
+        files_verified: 0,
+        renamed: vec![],
+    };
+
+    migration.progress = Some(progress.clone());
+    tokio::task::spawn_blocking(move || copy_data(progress, directories, updates));
+    Ok(())
+}
+
+/// Once the copy is complete, a move switches everything over to the new directories.
+pub async fn finish_migration(
+    kind: &MigrationKind,
+    settings: &SettingsHandle,
+    file_manager: &mut FileManager,
+) {
+    if let MigrationKind::MoveTo(root) = kind {
+        settings.set_data_paths(&get_data_paths_in(root)).await;
+        settings.save().await;
+
+        // Leave a copy of the settings with the data, so another install can pick them up
+        let config = settings.get_config_path();
+        if let Some(name) = config.file_name() {
+            if let Err(e) = fs::copy(config, root.join(name)) {
+                warn!(
+                    "Unable to copy the settings to {}: {}",
+                    root.to_string_lossy(),
+                    e
+                );
+            }
+        }
+
+        file_manager.set_paths(FileManager::get_file_paths_from_settings(settings).await);
+        info!("Data directory moved to {}", root.to_string_lossy());
+    }
+}
+
+fn get_directory_pairs(source: &FilePaths, target: &FilePaths) -> Vec<(PathBuf, PathBuf)> {
+    vec![
+        (source.profiles.clone(), target.profiles.clone()),
+        (source.mic_profiles.clone(), target.mic_profiles.clone()),
+        (source.presets.clone(), target.presets.clone()),
+        (source.samples.clone(), target.samples.clone()),
+        (source.icons.clone(), target.icons.clone()),
+    ]
+}
+
+fn copy_data(
+    mut progress: MigrationProgress,
+    directories: Vec<(PathBuf, PathBuf)>,
+    updates: Sender<MigrationProgress>,
+) {
+    progress.state = match copy_and_verify(&mut progress, &directories, &updates) {
+        Ok(()) => {
+            info!(
+                "Data Migration Complete, {} files copied",
+                progress.files_total
+            );
+            MigrationState::Complete
+        }
+        Err(e) => {
+            warn!("Data Migration Failed: {:#}", e);
+            MigrationState::Failed(format!("{:#}", e))
+        }
+    };
+    let _ = updates.blocking_send(progress);
+}
+
+fn copy_and_verify(
+    progress: &mut MigrationProgress,
+    directories: &[(PathBuf, PathBuf)],
+    updates: &Sender<MigrationProgress>,
+) -> Result<()> {
+    let mut files = vec![];
+    for (source, target) in directories {
+        if !source.is_dir() {
+            continue;
+        }
+        for file in list_files(source)? {
+            files.push((source.join(&file), target.join(&file)));
+        }
+    }
+
+    // There may be a lot of samples, so don't send an update for every single file
+    progress.files_total = files.len() as u32;
+    let step = (files.len() / 100).max(1);
+    let _ = updates.blocking_send(progress.clone());
+
+    for (index, (source, target)) in files.iter_mut().enumerate() {
+        if let Some(parent) = target.parent() {
+            create_dir_all(parent)?;
+        }
+
+        // Anything already there is kept, if it's different this is copied beside it instead
+        if target.exists() {
+            if hash_file(source)? == hash_file(target)? {
+                info!(
+                    "Skipping {}, it's already present",
+                    target.to_string_lossy()
+                );
+            } else {
+                *target = get_free_name(target);
+                warn!(
+                    "{} already exists, copying as {}",
+                    source.to_string_lossy(),
+                    target.to_string_lossy()
+                );
+                progress.renamed.push(target.clone());
+            }
+        }
+        if !target.exists() {
+            fs::copy(source.as_path(), target.as_path())
+                .with_context(|| format!("Unable to copy {}", source.to_string_lossy()))?;
+        }
+
+        progress.files_copied = index as u32 + 1;
+        if (index + 1) % step == 0 {
+            let _ = updates.blocking_send(progress.clone());
+        }
+    }
+
+    progress.state = MigrationState::Verifying;
+    let _ = updates.blocking_send(progress.clone());
+    verify(progress, directories, &files, updates)
+}
+
+fn verify(
+    progress: &mut MigrationProgress,
+    directories: &[(PathBuf, PathBuf)],
+    files: &[(PathBuf, PathBuf)],
+    updates: &Sender<MigrationProgress>,
+) -> Result<()> {
+    let step = (files.len() / 100).max(1);
+
+    // The targets may already hold other files, but should never have fewer than the source
+    for (source, target) in directories {
+        if !source.is_dir() {
+            continue;
+        }
+        let expected = list_files(source)?.len();
+        let found = list_files(target)?.len();
+        if found < expected {
+            bail!(
+                "Expected {} files in {}, but found {}",
+                expected,
+                target.to_string_lossy(),
+                found
+            );
+        }
+    }
+
+    for (index, (source, target)) in files.iter().enumerate() {
+        if hash_file(source)? != hash_file(target)? {
+            bail!("{} doesn't match the original", target.to_string_lossy());
+        }
+
+        progress.files_verified = index as u32 + 1;
+        if (index + 1) % step == 0 {
+            let _ = updates.blocking_send(progress.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Every file under the root (including those in sub-directories), relative to the root.
+/// Linked directories are followed, but only the first time they're reached, so a link back up
+/// the tree can't send this round forever.
+fn list_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut visited = HashSet::new();
+    let mut pending = vec![PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        let directory = root.join(&relative);
+        let canonical = fs::canonicalize(&directory)
+            .with_context(|| format!("Unable to read {}", directory.to_string_lossy()))?;
+        if !visited.insert(canonical) {
+            warn!(
+                "Skipping {}, it's already been copied",
+                directory.to_string_lossy()
+            );
+            continue;
+        }
+
+        let entries = fs::read_dir(&directory)
+            .with_context(|| format!("Unable to read {}", directory.to_string_lossy()))?;
+        for entry in entries {
+            let path = relative.join(entry?.file_name());
+            if root.join(&path).is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Finds a name beside the path which isn't taken, 'Name (2).ext', 'Name (3).ext' and so on
+fn get_free_name(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()));
+
+    (2..)
+        .map(|count| {
+            let name = format!("{} ({}){}", stem, count, extension.as_deref().unwrap_or(""));
+            path.with_file_name(name)
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+fn hash_file(path: &Path) -> Result<Vec<u8>> {
+    let content =
+        fs::read(path).with_context(|| format!("Unable to read {}", path.to_string_lossy()))?;
+    Ok(Sha256::digest(content).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn progress() -> MigrationProgress {
+        MigrationProgress {
+            kind: MigrationKind::ImportFrom(PathBuf::new()),
+            state: MigrationState::Copying,
+            files_total: 0,
+            files_copied: 0,
+            files_verified: 0,
+            renamed: vec![],
+        }
+    }
+
+    #[test]
+    fn existing_files_are_never_overwritten() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, target) = (root.path().join("source"), root.path().join("target"));
+        fs::create_dir_all(source.join("Recorded")).unwrap();
+        fs::create_dir_all(target.join("Recorded")).unwrap();
+        fs::write(source.join("Stream.goxlr"), "Imported").unwrap();
+        fs::write(source.join("Recorded/Same.wav"), "Same").unwrap();
+        fs::write(target.join("Stream.goxlr"), "Ours").unwrap();
+        fs::write(target.join("Stream (2).goxlr"), "Also Ours").unwrap();
+        fs::write(target.join("Recorded/Same.wav"), "Same").unwrap();
+
+        let (updates, _updates_rx) = mpsc::channel(64);
+        let mut progress = progress();
+        let directories = [(source, target.clone())];
+        copy_and_verify(&mut progress, &directories, &updates).unwrap();
+
+        // Identical files are left alone, different ones are copied beside what's there
+        assert_eq!(
+            fs::read_to_string(target.join("Stream.goxlr")).unwrap(),
+            "Ours"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("Stream (2).goxlr")).unwrap(),
+            "Also Ours"
+        );
+        let renamed = target.join("Stream (3).goxlr");
+        assert_eq!(fs::read_to_string(&renamed).unwrap(), "Imported");
+        assert_eq!(progress.renamed, vec![renamed]);
+        assert_eq!(list_files(&target).unwrap().len(), 4);
+        assert_eq!(progress.files_verified, 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn linked_directories_are_only_listed_once() {
+        let root = tempfile::tempdir().unwrap();
+        let samples = root.path().join("samples");
+        fs::create_dir_all(samples.join("Recorded")).unwrap();
+        fs::write(samples.join("Recorded/Clip.wav"), "Clip").unwrap();
+        std::os::unix::fs::symlink(&samples, samples.join("Recorded/Loop")).unwrap();
+
+        let files = list_files(&samples).unwrap();
+        assert_eq!(files, vec![PathBuf::from("Recorded/Clip.wav")]);
+    }
+
+    #[test]
+    fn copies_which_differ_fail_verification() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, target) = (root.path().join("source"), root.path().join("target"));
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(source.join("Mic.goxlrMicProfile"), "Original").unwrap();
+        fs::write(target.join("Mic.goxlrMicProfile"), "Truncated").unwrap();
+
+        let (updates, _updates_rx) = mpsc::channel(64);
+        let mut progress = progress();
+        let files = [(
+            source.join("Mic.goxlrMicProfile"),
+            target.join("Mic.goxlrMicProfile"),
+        )];
+        let directories = [(source, target)];
+
+        let error = verify(&mut progress, &directories, &files, &updates).unwrap_err();
+        assert!(error.to_string().ends_with("doesn't match the original"));
+        assert_eq!(progress.files_verified, 0);
+
+        // As does a target missing files which were copied to it
+        fs::remove_file(&files[0].1).unwrap();
+        let error = verify(&mut progress, &directories, &files, &updates).unwrap_err();
+        assert!(error.to_string().starts_with("Expected 1 files"));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use enumset::EnumSet;
+use goxlr_ipc::{ColourWay, HardwareStatus, UsbProductInformation};
+use goxlr_types::{DeviceType, FirmwareVersions, VersionNumber};
+use goxlr_usb::buttonstate::Buttons;
+use goxlr_usb::commands::Command;
+use goxlr_usb::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use goxlr_usb::jackstate::JackStates;
+use serde_json::json;
+use tokio::sync::mpsc::Sender;
+
+use crate::settings::{SettingsHandle, WritePolicy};
+
+/// Everything sent to a MockGoXLR, in order.
+pub type Requests = Arc<Mutex<Vec<(Command, Vec<u8>)>>>;
+
+/// The buttons, fader positions and jacks reported by a MockGoXLR, tests change these to act as
+/// the human in front of the device.
+#[derive(Debug, Default)]
+pub struct MockInputs {
+    pub pressed: EnumSet<Buttons>,
+    pub volumes: [u8; 4],
+    pub jacks: Option<JackStates>,
+    pub mic_level: u16,
+
+    // Changes to the pressed buttons, each made at the first read of the buttons once at least
+    // that many requests have been sent, for pressing a button part way through a command
+    pub scripted: VecDeque<(usize, EnumSet<Buttons>)>,
+}
+
+pub type Inputs = Arc<Mutex<MockInputs>>;
+
+/// Stands in for a GoXLR in tests, recording every request and answering each with zeros (other
+/// than the button states and mic level, which come from its inputs).
+pub struct MockGoXLR {
+    requests: Requests,
+    inputs: Inputs,
+}
+
+impl MockGoXLR {
+    pub fn new() -> (Box<Self>, Requests) {
+        let (mock, requests, _) = Self::with_inputs();
+        (mock, requests)
+    }
+
+    pub fn with_inputs() -> (Box<Self>, Requests, Inputs) {
+        let requests = Requests::default();
+        let inputs = Inputs::default();
+        let mock = Box::new(Self {
+            requests: requests.clone(),
+            inputs: inputs.clone(),
+        });
+        (mock, requests, inputs)
+    }
+}
+
+impl AttachGoXLR for MockGoXLR {
+    fn from_device(
+        _: GoXLRDevice,
+        _: Sender<String>,
+        _: Sender<String>,
+        _: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        bail!("The mock can't be attached to a real device");
+    }
+
+    fn set_unique_identifier(&mut self, _: String) {}
+    fn is_connected(&mut self) -> bool {
+        true
+    }
+    fn stop_polling(&mut self) {}
+    fn set_poll_interval(&mut self, _: Duration) {}
+    fn is_polled(&self) -> bool {
+        false
+    }
+}
+
+impl ExecutableGoXLR for MockGoXLR {
+    fn perform_request(&mut self, command: Command, body: &[u8], _: bool) -> Result<Vec<u8>> {
+        let sent = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push((command, body.to_vec()));
+            requests.len()
+        };
+        let mut response = vec![0; 1024];
+        if command == Command::GetButtonStates {
+            let mut inputs = self.inputs.lock().unwrap();
+            if inputs
+                .scripted
+                .front()
+                .is_some_and(|(after, _)| sent > *after)
+            {
+                if let Some((_, pressed)) = inputs.scripted.pop_front() {
+                    inputs.pressed = pressed;
+                }
+            }
+            let pressed = inputs
+                .pressed
+                .iter()
+                .fold(0u32, |bits, button| bits | (1 << button as u8));
+            response[0..4].copy_from_slice(&pressed.to_le_bytes());
+            response[8..12].copy_from_slice(&inputs.volumes);
+        }
+        if command == Command::GetMicrophoneLevel {
+            let inputs = self.inputs.lock().unwrap();
+            response[0..2].copy_from_slice(&inputs.mic_level.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        bail!("The mock has no descriptor");
+    }
+}
+
+impl GoXLRCommands for MockGoXLR {
+    fn get_jack_states(&mut self) -> Result<Option<JackStates>> {
+        Ok(self.inputs.lock().unwrap().jacks)
+    }
+}
+impl FullGoXLRDevice for MockGoXLR {}
+
+// The settings for each data directory, and where they're put inside root
+const DIRECTORIES: [(&str, &str); 7] = [
+    ("profile_directory", "profiles"),
+    ("mic_profile_directory", "mic-profiles"),
+    ("samples_directory", "samples"),
+    ("presets_directory", "presets"),
+    ("icons_directory", "icons"),
+    ("logs_directory", "logs"),
+    ("backup_directory", "backups"),
+];
+
+/// Read-only settings with all the data directories inside root.
+pub async fn settings(root: &Path) -> SettingsHandle {
+    settings_with_policy(root, WritePolicy::new(true)).await
+}
+
+/// As `settings`, for tests which need files to be written, with the data directories already
+/// created (as they are when the daemon starts).
+pub async fn writable_settings(root: &Path) -> SettingsHandle {
+    for (_, dir) in DIRECTORIES {
+        std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    settings_with_policy(root, WritePolicy::new(false)).await
+}
+
+async fn settings_with_policy(root: &Path, write_policy: WritePolicy) -> SettingsHandle {
+    let mut settings = json!({});
+    for (key, dir) in DIRECTORIES {
+        settings[key] = json!(root.join(dir));
+    }
+
+    let path = root.join("settings.json");
+    std::fs::write(&path, settings.to_string()).unwrap();
+    SettingsHandle::load(path, write_policy).await.unwrap()
+}
+
+pub fn mini(firmware: VersionNumber) -> HardwareStatus {
+    hardware(DeviceType::Mini, "MINI", firmware)
+}
+
+pub fn full(firmware: VersionNumber) -> HardwareStatus {
+    hardware(DeviceType::Full, "FULL", firmware)
+}
+
+fn hardware(device_type: DeviceType, serial: &str, firmware: VersionNumber) -> HardwareStatus {
+    HardwareStatus {
+        versions: FirmwareVersions {
+            firmware,
+            fpga_count: 0,
+            dice: VersionNumber(1, 0, None, None),
+        },
+        serial_number: String::from(serial),
+        manufactured_date: String::new(),
+        device_type,
+        colour_way: ColourWay::Black,
+        usb_device: UsbProductInformation {
+            manufacturer_name: String::new(),
+            product_name: String::new(),
+            version: (0, 0, 0),
+            bus_number: 0,
+            address: 0,
+            identifier: None,
+        },
+        capabilities: Default::default(),
+    }
+}
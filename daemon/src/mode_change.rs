@@ -0,0 +1,229 @@
+use enum_map::EnumMap;
+use goxlr_ipc::{
+    GoXLRCommand, MixChange, ModeChange, ModeChangePreview, RoutingChange, SubMixVolumeChange,
+    VolumeChange,
+};
+use goxlr_types::{ChannelName, InputDevice, Mix, OutputDevice, SubMixChannelName, VodMode};
+use strum::IntoEnumIterator;
+
+/*
+ * Toggling Submixes or the VOD mode reworks the routing and mixes of the whole device. The
+ * device works out everything it sends for a layout (a MixState), and both the preview and the
+ * toggle itself compare the state before the change with the state after it, so they can't
+ * disagree about what changes.
+ */
+
+/// The settings a mode change swaps out, which decide how the routing and mixes are laid out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MixLayout {
+    /// Whether the VOD mix follows the Broadcast Mix, only ever true on the Mini
+    pub stream_no_music: bool,
+    pub submix_enabled: bool,
+    pub monitor: OutputDevice,
+}
+
+impl MixLayout {
+    pub fn with(self, change: ModeChange, is_mini: bool) -> Self {
+        match change {
+            ModeChange::SubMix(enabled) if enabled == self.submix_enabled => self,
+            ModeChange::SubMix(true) => MixLayout {
+                submix_enabled: true,
+                ..self
+            },
+
+            // Without submixes, only the Headphones can be monitored
+            ModeChange::SubMix(false) => MixLayout {
+                submix_enabled: false,
+                monitor: OutputDevice::Headphones,
+                ..self
+            },
+            ModeChange::Vod(mode) => MixLayout {
+                stream_no_music: is_mini && mode == VodMode::StreamNoMusic,
+                ..self
+            },
+        }
+    }
+}
+
+/// What a layout sends to the device, the submix parts are None on firmware without submixes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixState {
+    pub routing: EnumMap<InputDevice, EnumMap<OutputDevice, bool>>,
+
+    /// The Headphones' mix is the monitored mix
+    pub mixes: Option<EnumMap<OutputDevice, Mix>>,
+    pub submix_volumes: EnumMap<SubMixChannelName, Option<u8>>,
+    pub mic_monitor: Option<u8>,
+}
+
+/// The command a mode change is made with, for checking whether the device supports it.
+pub fn mode_change_command(change: ModeChange) -> GoXLRCommand {
+    match change {
+        ModeChange::SubMix(enabled) => GoXLRCommand::SetSubMixEnabled(enabled),
+        ModeChange::Vod(mode) => GoXLRCommand::SetVodMode(mode),
+    }
+}
+
+pub fn diff_mix_state(before: &MixState, after: &MixState) -> ModeChangePreview {
+    let mut preview = ModeChangePreview::default();
+
+    for input in InputDevice::iter() {
+        for output in OutputDevice::iter() {
+            let enabled = after.routing[input][output];
+            if before.routing[input][output] != enabled {
+                preview.routing.push(RoutingChange {
+                    input,
+                    output,
+                    enabled,
+                });
+            }
+        }
+    }
+
+    if let (Some(from), Some(to)) = (&before.mixes, &after.mixes) {
+        for output in OutputDevice::iter() {
+            if from[output] != to[output] {
+                preview.mixes.push(MixChange {
+                    output,
+                    from: from[output],
+                    to: to[output],
+                });
+            }
+        }
+    }
+
+    if let (Some(from), Some(to)) = (before.mic_monitor, after.mic_monitor) {
+        if from != to {
+            preview.volumes.push(VolumeChange {
+                channel: ChannelName::MicMonitor,
+                from,
+                to,
+            });
+        }
+    }
+
+    // Submix volumes are only sent while submixes are in use
+    for channel in SubMixChannelName::iter() {
+        let from = before.submix_volumes[channel];
+        if let Some(to) = after.submix_volumes[channel].filter(|to| from != Some(*to)) {
+            preview
+                .submix_volumes
+                .push(SubMixVolumeChange { channel, from, to });
+        }
+    }
+
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> MixLayout {
+        MixLayout {
+            stream_no_music: false,
+            submix_enabled: true,
+            monitor: OutputDevice::BroadcastMix,
+        }
+    }
+
+    fn state() -> MixState {
+        MixState {
+            routing: EnumMap::default(),
+            mixes: Some(EnumMap::default()),
+            submix_volumes: EnumMap::default(),
+            mic_monitor: Some(255),
+        }
+    }
+
+    #[test]
+    fn layouts_only_change_with_their_mode() {
+        let layout = layout();
+        assert_eq!(layout.with(ModeChange::SubMix(true), true), layout);
+
+        let disabled = layout.with(ModeChange::SubMix(false), true);
+        assert!(!disabled.submix_enabled);
+        assert_eq!(disabled.monitor, OutputDevice::Headphones);
+
+        // Turning them back on doesn't bring the monitor back
+        let enabled = disabled.with(ModeChange::SubMix(true), true);
+        assert_eq!(enabled.monitor, OutputDevice::Headphones);
+        assert_eq!(disabled.with(ModeChange::SubMix(false), true), disabled);
+
+        // The VOD mix only exists on the Mini
+        let vod = ModeChange::Vod(VodMode::StreamNoMusic);
+        assert!(layout.with(vod, true).stream_no_music);
+        assert_eq!(layout.with(vod, false), layout);
+        let routable = layout
+            .with(vod, true)
+            .with(ModeChange::Vod(VodMode::Routable), true);
+        assert_eq!(routable, layout);
+    }
+
+    #[test]
+    fn only_changes_are_listed() {
+        let before = state();
+        assert!(diff_mix_state(&before, &before).is_empty());
+
+        let (mic, stream) = (InputDevice::Microphone, OutputDevice::BroadcastMix);
+        let mut after = state();
+        after.routing[mic][stream] = true;
+        if let Some(mixes) = &mut after.mixes {
+            mixes[OutputDevice::Headphones] = Mix::B;
+        }
+        after.mic_monitor = Some(128);
+        after.submix_volumes[SubMixChannelName::Game] = Some(200);
+
+        let preview = diff_mix_state(&before, &after);
+        assert_eq!(
+            preview.routing,
+            vec![RoutingChange {
+                input: mic,
+                output: stream,
+                enabled: true
+            }]
+        );
+        assert_eq!(
+            preview.mixes,
+            vec![MixChange {
+                output: OutputDevice::Headphones,
+                from: Mix::A,
+                to: Mix::B
+            }]
+        );
+        assert_eq!(
+            preview.volumes,
+            vec![VolumeChange {
+                channel: ChannelName::MicMonitor,
+                from: 255,
+                to: 128
+            }]
+        );
+        assert_eq!(
+            preview.submix_volumes,
+            vec![SubMixVolumeChange {
+                channel: SubMixChannelName::Game,
+                from: None,
+                to: 200
+            }]
+        );
+
+        // Volumes which stop being sent aren't a change, the device keeps what it had
+        assert!(diff_mix_state(&after, &before).submix_volumes.is_empty());
+    }
+
+    #[test]
+    fn legacy_firmware_only_changes_routing() {
+        let legacy = MixState {
+            mixes: None,
+            mic_monitor: None,
+            ..state()
+        };
+        let mut after = legacy.clone();
+        after.routing[InputDevice::Music][OutputDevice::Sampler] = true;
+
+        let preview = diff_mix_state(&legacy, &after);
+        assert_eq!(preview.routing.len(), 1);
+        assert!(preview.mixes.is_empty() && preview.volumes.is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context, Result};
+use goxlr_ipc::{MuteSyncApplication, MuteSyncState};
+use interprocess::local_socket::tokio::{RecvHalf, SendHalf};
+use interprocess::local_socket::traits::tokio::Stream;
+use interprocess::local_socket::{GenericFilePath, ToFsName};
+use log::debug;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::mute_sync::{MuteSyncClient, StateReporter};
+use crate::settings::{MuteSyncSettings, SettingsHandle};
+
+// Discord's local RPC, documented at https://discord.com/developers/docs/topics/rpc
+const RPC_VERSION: u32 = 1;
+const SOCKET_COUNT: u32 = 10;
+const TOKEN_URL: &str = "https://discord.com/api/oauth2/token";
+const SCOPES: [&str; 3] = ["rpc", "rpc.voice.read", "rpc.voice.write"];
+const VOICE_EVENT: &str = "VOICE_SETTINGS_UPDATE";
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+const OP_PING: u32 = 3;
+const OP_PONG: u32 = 4;
+
+pub struct DiscordClient {
+    writer: SendHalf,
+    frames: mpsc::Receiver<Result<(u32, Value)>>,
+    reader: JoinHandle<()>,
+    nonce: u64,
+
+    // A mute change which arrived while waiting for a command's response
+    pending_mute: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+impl MuteSyncClient for DiscordClient {
+    async fn connect(
+        application: MuteSyncApplication,
+        config: &MuteSyncSettings,
+        settings: &SettingsHandle,
+        state: &StateReporter,
+    ) -> Result<Option<Self>> {
+        let stream = match find_socket().await {
+            Some(stream) => stream,
+            None => return Ok(None),
+        };
+        let client_id = config.client_id.as_deref().context("No Client ID Set")?;
+        state.set(MuteSyncState::Connecting);
+
+        let (reader, writer) = stream.split();
+        let (frames_tx, frames) = mpsc::channel(16);
+        let mut client = DiscordClient {
+            writer,
+            frames,
+            reader: tokio::spawn(read_frames(reader, frames_tx)),
+            nonce: 0,
+            pending_mute: None,
+        };
+
+        let handshake = json!({ "v": RPC_VERSION, "client_id": client_id });
+        client.write(OP_HANDSHAKE, &handshake).await?;
+        let ready = client.next_frame().await?;
+        if ready["evt"] != "READY" {
+            bail!("Unexpected handshake response: {}", ready);
+        }
+
+        client
+            .authenticate(application, config, settings, state)
+            .await?;
+        client
+            .command("SUBSCRIBE", json!({}), Some(VOICE_EVENT))
+            .await?;
+        Ok(Some(client))
+    }
+
+    async fn get_muted(&mut self) -> Result<bool> {
+        let data = self.command("GET_VOICE_SETTINGS", json!({}), None).await?;
+        data["mute"]
+            .as_bool()
+            .context("Voice Settings didn't include the mute state")
+    }
+
+    async fn set_muted(&mut self, muted: bool) -> Result<()> {
+        self.command("SET_VOICE_SETTINGS", json!({ "mute": muted }), None)
+            .await?;
+        Ok(())
+    }
+
+    async fn next_mute_change(&mut self) -> Result<bool> {
+        loop {
+            if let Some(muted) = self.pending_mute.take() {
+                return Ok(muted);
+            }
+            let frame = self.next_frame().await?;
+            self.handle_event(&frame);
+        }
+    }
+}
+
+impl DiscordClient {
+    /// Tries the stored token, then refreshing it, and only asks the user to authorise the
+    /// client in Discord if neither of those work.
+    async fn authenticate(
+        &mut self,
+        application: MuteSyncApplication,
+        config: &MuteSyncSettings,
+        settings: &SettingsHandle,
+        state: &StateReporter,
+    ) -> Result<()> {
+        if let Some(token) = &config.access_token {
+            match self.send_token(token).await {
+                Ok(()) => return Ok(()),
+                Err(e) => debug!("Stored Discord token rejected: {}", e),
+            }
+        }
+
+        let mut tokens = None;
+        if let Some(refresh_token) = &config.refresh_token {
+            let grant = [
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ];
+            match request_token(config, &grant).await {
+                Ok(response) => tokens = Some(response),
+                Err(e) => debug!("Unable to refresh the Discord token: {}", e),
+            }
+        }
+
+        let tokens = match tokens {
+            Some(tokens) => tokens,
+            None => {
+                state.set(MuteSyncState::AwaitingAuthorisation);
+                let request = json!({ "client_id": config.client_id, "scopes": SCOPES });
+                let data = self.command("AUTHORIZE", request, None).await?;
+                let code = data["code"]
+                    .as_str()
+                    .context("Discord didn't return an Authorisation Code")?;
+
+                let grant = [("grant_type", "authorization_code"), ("code", code)];
+                request_token(config, &grant).await?
+            }
+        };
+
+        settings
+            .set_mute_sync_tokens(
+                application,
+                Some(tokens.access_token.clone()),
+                tokens.refresh_token,
+            )
+            .await;
+        settings.save().await;
+
+        self.send_token(&tokens.access_token).await
+    }
+
+    async fn send_token(&mut self, token: &str) -> Result<()> {
+        let request = json!({ "access_token": token });
+        self.command("AUTHENTICATE", request, None).await?;
+        Ok(())
+    }
+
+    /// Sends a command and waits for its response, handling any events which arrive first.
+    async fn command(&mut self, command: &str, args: Value, event: Option<&str>) -> Result<Value> {
+        self.nonce += 1;
+        let nonce = self.nonce.to_string();
+
+        let mut request = json!({ "cmd": command, "args": args, "nonce": nonce });
+        if let Some(event) = event {
+            request["evt"] = json!(event);
+        }
+        self.write(OP_FRAME, &request).await?;
+
+        loop {
+            let frame = self.next_frame().await?;
+            if frame["nonce"] != nonce.as_str() {
+                self.handle_event(&frame);
+                continue;
+            }
+
+            if frame["evt"] == "ERROR" {
+                bail!(
+                    "{} failed: {}",
+                    command,
+                    frame["data"]["message"].as_str().unwrap_or("Unknown Error")
+                );
+            }
+            return Ok(frame["data"].clone());
+        }
+    }
+
+    fn handle_event(&mut self, frame: &Value) {
+        if frame["evt"] == VOICE_EVENT {
+            if let Some(muted) = frame["data"]["mute"].as_bool() {
+                self.pending_mute = Some(muted);
+            }
+        }
+    }
+
+    /// The next frame from Discord, pings are answered here rather than passed on.
+    async fn next_frame(&mut self) -> Result<Value> {
+        loop {
+            let (opcode, value) = self
+                .frames
+                .recv()
+                .await
+                .ok_or_else(|| anyhow!("Connection to Discord closed"))??;
+
+            match opcode {
+                OP_FRAME => return Ok(value),
+                OP_PING => self.write(OP_PONG, &value).await?,
+                OP_PONG => {}
+                OP_CLOSE => bail!(
+                    "Discord closed the connection: {}",
+                    value["message"].as_str().unwrap_or("No Reason Given")
+                ),
+                _ => bail!("Unexpected opcode from Discord: {}", opcode),
+            }
+        }
+    }
+
+    async fn write(&mut self, opcode: u32, value: &Value) -> Result<()> {
+        let payload = serde_json::to_vec(value)?;
+        let mut frame = Vec::with_capacity(payload.len() + 8);
+        frame.extend_from_slice(&opcode.to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+
+        self.writer.write_all(&frame).await?;
+        Ok(())
+    }
+}
+
+impl Drop for DiscordClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Reads frames on a separate task, a partially read frame would be lost if the read was
+/// cancelled by a select.
+async fn read_frames(mut reader: RecvHalf, frames: mpsc::Sender<Result<(u32, Value)>>) {
+    loop {
+        let result = read_frame(&mut reader).await;
+        let failed = result.is_err();
+        if frames.send(result).await.is_err() || failed {
+            break;
+        }
+    }
+}
+
+async fn read_frame(reader: &mut RecvHalf) -> Result<(u32, Value)> {
+    let opcode = reader.read_u32_le().await?;
+    let length = reader.read_u32_le().await?;
+
+    let mut payload = vec![0; length as usize];
+    reader.read_exact(&mut payload).await?;
+    Ok((opcode, serde_json::from_slice(&payload)?))
+}
+
+async fn request_token(config: &MuteSyncSettings, grant: &[(&str, &str)]) -> Result<TokenResponse> {
+    let client_id = config.client_id.as_deref().context("No Client ID Set")?;
+    let client_secret = config
+        .client_secret
+        .as_deref()
+        .context("No Client Secret Set")?;
+
+    let mut form = vec![("client_id", client_id), ("client_secret", client_secret)];
+    form.extend_from_slice(grant);
+
+    let response = reqwest::Client::new()
+        .post(TOKEN_URL)
+        .form(&form)
+        .send()
+        .await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        bail!("Token request failed ({}): {}", status, body);
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+async fn find_socket() -> Option<interprocess::local_socket::tokio::Stream> {
+    for path in get_socket_paths() {
+        let name = match path.to_fs_name::<GenericFilePath>() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        if let Ok(stream) = interprocess::local_socket::tokio::Stream::connect(name).await {
+            return Some(stream);
+        }
+    }
+    None
+}
+
+/// Discord listens on the first free of several numbered sockets, Flatpak and Snap installs
+/// put them inside their own runtime directories.
+fn get_socket_paths() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        return (0..SOCKET_COUNT)
+            .map(|index| PathBuf::from(format!(r"\\.\pipe\discord-ipc-{}", index)))
+            .collect();
+    }
+
+    let mut roots: Vec<PathBuf> = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|variable| env::var_os(variable).map(PathBuf::from))
+        .collect();
+    roots.push(PathBuf::from("/tmp"));
+
+    let mut paths = vec![];
+    for root in roots {
+        for directory in [
+            root.clone(),
+            root.join("app/com.discordapp.Discord"),
+            root.join("snap.discord"),
+        ] {
+            for index in 0..SOCKET_COUNT {
+                paths.push(directory.join(format!("discord-ipc-{}", index)));
+            }
+        }
+    }
+    paths
+}
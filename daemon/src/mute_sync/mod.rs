@@ -0,0 +1,445 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use enum_map::{Enum, EnumMap};
+use goxlr_ipc::{
+    DaemonStatus, MuteSyncApplication, MuteSyncDirection, MuteSyncState, MuteSyncStatus,
+};
+use goxlr_types::{ChannelName, MuteFunction, MuteState};
+use log::{debug, info, warn};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+
+use crate::events::EventTriggers;
+use crate::mute_sync::discord::DiscordClient;
+use crate::settings::{MuteSyncSettings, SettingsHandle};
+use crate::shutdown::Shutdown;
+
+mod discord;
+
+// An application which isn't running is checked for quietly, and fairly often
+const RETRY_NOT_RUNNING: Duration = Duration::from_secs(10);
+
+// Something went wrong talking to a running application, give it a while before trying again
+const RETRY_ERROR: Duration = Duration::from_secs(60);
+
+/// The Primary Worker's side of the service, it publishes the GoXLR's voice chat mute state
+/// and reads back how each application's connection is doing.
+pub struct MuteSyncHandle {
+    pub mic_muted: watch::Sender<Option<bool>>,
+    pub states: watch::Receiver<EnumMap<MuteSyncApplication, MuteSyncState>>,
+    pub reload: watch::Sender<()>,
+}
+
+#[derive(Clone)]
+pub struct MuteSyncService {
+    mic_muted: watch::Receiver<Option<bool>>,
+    states: watch::Sender<EnumMap<MuteSyncApplication, MuteSyncState>>,
+    reload: watch::Receiver<()>,
+}
+
+pub fn create_mute_sync() -> (MuteSyncHandle, MuteSyncService) {
+    let (mic_muted_tx, mic_muted_rx) = watch::channel(None);
+    let (states_tx, states_rx) = watch::channel(EnumMap::default());
+    let (reload_tx, reload_rx) = watch::channel(());
+
+    let handle = MuteSyncHandle {
+        mic_muted: mic_muted_tx,
+        states: states_rx,
+        reload: reload_tx,
+    };
+    let service = MuteSyncService {
+        mic_muted: mic_muted_rx,
+        states: states_tx,
+        reload: reload_rx,
+    };
+    (handle, service)
+}
+
+/// Whether the mic can currently be heard in voice chat, this is None until a device is
+/// attached. Only a mute which covers voice chat counts, muting to the stream alone shouldn't
+/// mute someone on a call.
+pub fn is_voice_chat_muted(status: &DaemonStatus) -> Option<bool> {
+    if status.mixers.is_empty() {
+        return None;
+    }
+
+    let muted = |state: MuteState, function: MuteFunction| match state {
+        MuteState::Unmuted => false,
+        MuteState::MutedToAll => true,
+        MuteState::MutedToX => matches!(function, MuteFunction::All | MuteFunction::ToVoiceChat),
+    };
+
+    Some(status.mixers.values().any(|mixer| {
+        let cough = &mixer.cough_button;
+        muted(cough.state, cough.mute_type)
+            || mixer.fader_status.values().any(|fader| {
+                fader.channel == ChannelName::Mic && muted(fader.mute_state, fader.mute_type)
+            })
+    }))
+}
+
+/// Combines each application's settings with its connection state, for the DaemonStatus. The
+/// secret and tokens are never included.
+pub async fn get_mute_sync_status(
+    settings: &SettingsHandle,
+    states: &EnumMap<MuteSyncApplication, MuteSyncState>,
+) -> EnumMap<MuteSyncApplication, MuteSyncStatus> {
+    let mut status: EnumMap<MuteSyncApplication, MuteSyncStatus> = EnumMap::default();
+    for (application, status) in status.iter_mut() {
+        let config = settings.get_mute_sync(application).await;
+        *status = MuteSyncStatus {
+            enabled: config.enabled,
+            direction: config.direction,
+            client_id: config.client_id,
+            state: states[application].clone(),
+        };
+    }
+    status
+}
+
+pub async fn spawn_mute_sync_service(
+    service: MuteSyncService,
+    settings: SettingsHandle,
+    global_tx: Sender<EventTriggers>,
+    shutdown: Shutdown,
+) {
+    let mut tasks = JoinSet::new();
+    for index in 0..MuteSyncApplication::LENGTH {
+        let application = MuteSyncApplication::from_usize(index);
+        let service = service.clone();
+        let settings = settings.clone();
+        let global_tx = global_tx.clone();
+        let shutdown = shutdown.clone();
+
+        match application {
+            MuteSyncApplication::Discord => tasks.spawn(run_application::<DiscordClient>(
+                application,
+                service,
+                settings,
+                global_tx,
+                shutdown,
+            )),
+        };
+    }
+
+    while tasks.join_next().await.is_some() {}
+    debug!("Mute Sync Service Stopped");
+}
+
+/// A connection to an application's local API. Connecting returns None if the application
+/// isn't running, which isn't treated as an error. The state is only set to Connecting once
+/// the application has been found, so retrying doesn't flip it back and forth.
+trait MuteSyncClient: Sized {
+    async fn connect(
+        application: MuteSyncApplication,
+        config: &MuteSyncSettings,
+        settings: &SettingsHandle,
+        state: &StateReporter,
+    ) -> Result<Option<Self>>;
+
+    async fn get_muted(&mut self) -> Result<bool>;
+    async fn set_muted(&mut self, muted: bool) -> Result<()>;
+
+    /// Waits for the application to report its mute state, this must be cancel safe.
+    async fn next_mute_change(&mut self) -> Result<bool>;
+}
+
+struct StateReporter {
+    application: MuteSyncApplication,
+    states: watch::Sender<EnumMap<MuteSyncApplication, MuteSyncState>>,
+}
+
+impl StateReporter {
+    /// Only transitions are logged, so an application which stays closed doesn't fill the log.
+    fn set(&self, state: MuteSyncState) {
+        let application = self.application;
+        self.states.send_if_modified(|states| {
+            if states[application] == state {
+                return false;
+            }
+
+            match &state {
+                MuteSyncState::Error(error) => {
+                    warn!("Mute Sync with {:?} failed: {}", application, error)
+                }
+                MuteSyncState::Connected => info!("Mute Sync connected to {:?}", application),
+                _ => debug!("Mute Sync with {:?} is now {:?}", application, state),
+            }
+            states[application] = state;
+            true
+        });
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Origin {
+    GoXLR,
+    Application,
+}
+
+enum SyncEnd {
+    Reload,
+    Shutdown,
+}
+
+async fn run_application<C: MuteSyncClient>(
+    application: MuteSyncApplication,
+    mut service: MuteSyncService,
+    settings: SettingsHandle,
+    global_tx: Sender<EventTriggers>,
+    mut shutdown: Shutdown,
+) {
+    let state = StateReporter {
+        application,
+        states: service.states.clone(),
+    };
+
+    loop {
+        let config = settings.get_mute_sync(application).await;
+        let retry = if !config.enabled {
+            state.set(MuteSyncState::Disabled);
+            None
+        } else if config.client_id.is_none() || config.client_secret.is_none() {
+            state.set(MuteSyncState::NotConfigured);
+            None
+        } else {
+            let result = tokio::select! {
+                result = C::connect(application, &config, &settings, &state) => result,
+                Ok(()) = service.reload.changed() => continue,
+                () = shutdown.recv() => return,
+            };
+
+            match result {
+                Ok(Some(client)) => {
+                    state.set(MuteSyncState::Connected);
+                    let result = sync(
+                        client,
+                        config.direction,
+                        &mut service,
+                        &global_tx,
+                        &mut shutdown,
+                    )
+                    .await;
+
+                    match result {
+                        Ok(SyncEnd::Reload) => continue,
+                        Ok(SyncEnd::Shutdown) => return,
+
+                        // Most likely the application has been closed, so check again soon
+                        Err(e) => {
+                            state.set(MuteSyncState::Error(format!("{:#}", e)));
+                            Some(RETRY_NOT_RUNNING)
+                        }
+                    }
+                }
+                Ok(None) => {
+                    state.set(MuteSyncState::NotRunning);
+                    Some(RETRY_NOT_RUNNING)
+                }
+                Err(e) => {
+                    state.set(MuteSyncState::Error(format!("{:#}", e)));
+                    Some(RETRY_ERROR)
+                }
+            }
+        };
+
+        tokio::select! {
+            () = wait_for_retry(retry) => {},
+            Ok(()) = service.reload.changed() => {},
+            () = shutdown.recv() => return,
+        }
+    }
+}
+
+/// Keeps the application in step with the GoXLR until the connection ends. Each change made
+/// is remembered along with where it came from, so when it's reflected back from the other
+/// side it's recognised as an echo rather than a new change.
+async fn sync<C: MuteSyncClient>(
+    mut client: C,
+    direction: MuteSyncDirection,
+    service: &mut MuteSyncService,
+    global_tx: &Sender<EventTriggers>,
+    shutdown: &mut Shutdown,
+) -> Result<SyncEnd> {
+    let mut app_muted = client.get_muted().await?;
+    let mut last_sync: Option<(Origin, bool)> = None;
+
+    // The GoXLR wins when first connecting
+    let goxlr_muted = *service.mic_muted.borrow_and_update();
+    if let Some(muted) = goxlr_muted {
+        if muted != app_muted {
+            client.set_muted(muted).await?;
+            last_sync = Some((Origin::GoXLR, muted));
+        }
+    }
+
+    loop {
+        tokio::select! {
+            Ok(()) = service.mic_muted.changed() => {
+                let muted = match *service.mic_muted.borrow_and_update() {
+                    Some(muted) => muted,
+                    None => continue,
+                };
+
+                if last_sync == Some((Origin::Application, muted)) {
+                    last_sync = None;
+                    continue;
+                }
+                if muted != app_muted {
+                    client.set_muted(muted).await?;
+                    last_sync = Some((Origin::GoXLR, muted));
+                }
+            }
+            result = client.next_mute_change() => {
+                let muted = result?;
+                if muted == app_muted {
+                    continue;
+                }
+                app_muted = muted;
+
+                if last_sync == Some((Origin::GoXLR, muted)) {
+                    last_sync = None;
+                    continue;
+                }
+
+                let goxlr_muted = *service.mic_muted.borrow();
+                if direction == MuteSyncDirection::Both && goxlr_muted.is_some_and(|m| m != muted) {
+                    last_sync = Some((Origin::Application, muted));
+                    let _ = global_tx.send(EventTriggers::SetMicMuted(muted)).await;
+                }
+            }
+            Ok(()) = service.reload.changed() => return Ok(SyncEnd::Reload),
+            () = shutdown.recv() => return Ok(SyncEnd::Shutdown),
+        }
+    }
+}
+
+async fn wait_for_retry(retry: Option<Duration>) {
+    match retry {
+        Some(duration) => sleep(duration).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use anyhow::Context;
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
+
+    use super::*;
+
+    // Long enough for a change to go back and forth a good few times, if it was going to
+    const SETTLE: Duration = Duration::from_millis(50);
+
+    // An application which reports every change to its mute back, as Discord does, whether it
+    // was made through the API or by the user.
+    struct EchoingClient {
+        muted: bool,
+        changes: mpsc::UnboundedReceiver<bool>,
+        echo: mpsc::UnboundedSender<bool>,
+        writes: Arc<AtomicUsize>,
+    }
+
+    impl MuteSyncClient for EchoingClient {
+        async fn connect(
+            _: MuteSyncApplication,
+            _: &MuteSyncSettings,
+            _: &SettingsHandle,
+            _: &StateReporter,
+        ) -> Result<Option<Self>> {
+            unimplemented!("Created directly by the tests")
+        }
+
+        async fn get_muted(&mut self) -> Result<bool> {
+            Ok(self.muted)
+        }
+
+        async fn set_muted(&mut self, muted: bool) -> Result<()> {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            self.muted = muted;
+            let _ = self.echo.send(muted);
+            Ok(())
+        }
+
+        async fn next_mute_change(&mut self) -> Result<bool> {
+            let muted = self.changes.recv().await.context("Closed")?;
+            self.muted = muted;
+            Ok(muted)
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_reflected_back_are_not_bounced() {
+        let (handle, mut service) = create_mute_sync();
+        let (global_tx, mut global_rx) = mpsc::channel(16);
+        let (app_tx, changes) = mpsc::unbounded_channel();
+        let app_writes = Arc::new(AtomicUsize::new(0));
+        let client = EchoingClient {
+            muted: false,
+            changes,
+            echo: app_tx.clone(),
+            writes: app_writes.clone(),
+        };
+
+        // The GoXLR applies whatever the application asks for and publishes its new state, as
+        // does a mute pressed on the GoXLR itself
+        let (press_tx, mut presses) = mpsc::unbounded_channel();
+        let goxlr_writes = Arc::new(AtomicUsize::new(0));
+        let writes = goxlr_writes.clone();
+        let mic_muted = handle.mic_muted;
+        mic_muted.send_replace(Some(false));
+        let goxlr = tokio::spawn(async move {
+            loop {
+                let muted = tokio::select! {
+                    Some(event) = global_rx.recv() => match event {
+                        EventTriggers::SetMicMuted(muted) => {
+                            writes.fetch_add(1, Ordering::SeqCst);
+                            muted
+                        }
+                        _ => continue,
+                    },
+                    Some(muted) = presses.recv() => muted,
+                    else => return,
+                };
+                mic_muted.send_replace(Some(muted));
+            }
+        });
+
+        let shutdown = Shutdown::new();
+        let mut sync_shutdown = shutdown.clone();
+        let task = tokio::spawn(async move {
+            let direction = MuteSyncDirection::Both;
+            sync(
+                client,
+                direction,
+                &mut service,
+                &global_tx,
+                &mut sync_shutdown,
+            )
+            .await
+        });
+
+        // Each side mutes and unmutes the other in turn, every change should cross once
+        for round in 1..=3 {
+            press_tx.send(true).unwrap();
+            sleep(SETTLE).await;
+            app_tx.send(false).unwrap();
+            sleep(SETTLE).await;
+
+            assert_eq!(app_writes.load(Ordering::SeqCst), round);
+            assert_eq!(goxlr_writes.load(Ordering::SeqCst), round);
+        }
+
+        shutdown.trigger();
+        let result = timeout(SETTLE, task).await.unwrap().unwrap();
+        assert!(matches!(result, Ok(SyncEnd::Shutdown)));
+        goxlr.abort();
+    }
+}
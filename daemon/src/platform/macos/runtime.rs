@@ -1,5 +1,6 @@
 use anyhow::Result;
 use coreaudio_sys::AudioDeviceID;
+use goxlr_ipc::ShutdownReason;
 use log::{debug, error, warn};
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::HashMap;
@@ -88,7 +89,7 @@ pub async fn run(tx: mpsc::Sender<EventTriggers>, mut stop: Shutdown) -> Result<
             Some(_) = stream.recv() => {
                 // Trigger a Shutdown
                 debug!("TERM Signal Received, Triggering STOP");
-                let _ = tx.send(EventTriggers::Stop(false)).await;
+                let _ = tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await;
             },
 
             _ = stop.recv() => {
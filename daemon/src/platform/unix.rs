@@ -1,6 +1,7 @@
 use crate::events::EventTriggers;
 use crate::DaemonState;
 use anyhow::Result;
+use goxlr_ipc::ShutdownReason;
 use log::debug;
 use tokio::select;
 use tokio::signal::unix::{signal, SignalKind};
@@ -19,7 +20,7 @@ pub async fn spawn_platform_runtime(
         Some(_) = stream.recv() => {
             // Trigger a Shutdown
             debug!("TERM Signal Received, Triggering STOP");
-            let _ = tx.send(EventTriggers::Stop(false)).await;
+            let _ = tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await;
         },
         () = shutdown.recv() => {}
     }
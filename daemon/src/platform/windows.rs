@@ -1,6 +1,7 @@
 use crate::events::EventTriggers;
 use crate::DaemonState;
 use anyhow::{bail, Result};
+use goxlr_ipc::ShutdownReason;
 use lazy_static::lazy_static;
 use log::{debug, error};
 use mslnk::ShellLink;
@@ -85,24 +86,26 @@ pub async fn spawn_platform_runtime(
                     // We're calling 'DevicesStopped' here to force an end to the util, we can't use
                     // the regular Stop because it may attempt to load profiles, which isn't possible
                     // in a situation where the official app is running.
+                    let reason = String::from("The official GoXLR App was started");
+                    state.shutdown_reason.lock().unwrap().replace(ShutdownReason::Error(reason));
                     tx.send(EventTriggers::DevicesStopped).await?;
                     break;
                 }
             },
             Some(_) = ctrl_break.recv() => {
-                tx.send(EventTriggers::Stop(false)).await?;
+                tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await?;
             },
             Some(_) = ctrl_close.recv() => {
                 debug!("Hit Ctrl+Close");
-                tx.send(EventTriggers::Stop(false)).await?;
+                tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await?;
             }
             Some(_) = ctrl_shutdown.recv() => {
                 debug!("Hit Ctrl+Shutdown");
-                tx.send(EventTriggers::Stop(false)).await?;
+                tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await?;
             }
             Some(_) = ctrl_logoff.recv() => {
                 debug!("Hit Ctrl+Logoff");
-                tx.send(EventTriggers::Stop(false)).await?;
+                tx.send(EventTriggers::Stop(false, ShutdownReason::OsSignal)).await?;
             }
             //Some(_) = ctrl_
             () = shutdown.recv() => {
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use tokio::time::Instant;
+
+// How often (in milliseconds) the buttons, faders and encoders are read, unless configured
+pub const DEFAULT_POLL_INTERVAL: u16 = 20;
+const MIN_POLL_INTERVAL: u16 = 5;
+const MAX_POLL_INTERVAL: u16 = 100;
+
+// Once nothing has happened on the device for this long, it's read less often, there's no need
+// to poll fifty times a second overnight.
+pub const IDLE_AFTER: Duration = Duration::from_secs(30);
+const IDLE_POLL_INTERVAL: u16 = 100;
+
+pub fn check_poll_interval(interval: u16) -> Result<()> {
+    if !(MIN_POLL_INTERVAL..=MAX_POLL_INTERVAL).contains(&interval) {
+        bail!(
+            "Poll Interval must be between {}ms and {}ms",
+            MIN_POLL_INTERVAL,
+            MAX_POLL_INTERVAL
+        );
+    }
+    Ok(())
+}
+
+/// Decides how often a device's inputs are polled, backing off to the idle rate when nothing
+/// has changed for a while, and returning to the configured rate as soon as something does.
+#[derive(Debug)]
+pub struct PollRate {
+    configured: u16,
+    current: u16,
+    idle: bool,
+    last_activity: Instant,
+}
+
+impl PollRate {
+    pub fn new(configured: u16, now: Instant) -> Self {
+        Self {
+            configured,
+            current: configured,
+            idle: false,
+            last_activity: now,
+        }
+    }
+
+    pub fn configured(&self) -> u16 {
+        self.configured
+    }
+
+    /// The interval currently being polled at.
+    pub fn current(&self) -> u16 {
+        self.current
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    pub fn set_configured(&mut self, interval: u16, now: Instant) {
+        self.configured = interval;
+        self.activity(now);
+    }
+
+    /// Something changed on the device, or a command was sent to it.
+    pub fn activity(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// Works out the interval to poll at, returning it if it's different to the current one.
+    /// While 'stay_fast' is set (something needs quick responses) the device never goes idle.
+    pub fn update(&mut self, now: Instant, stay_fast: bool) -> Option<u16> {
+        if stay_fast {
+            self.activity(now);
+        }
+
+        self.idle = now.saturating_duration_since(self.last_activity) >= IDLE_AFTER;
+        let interval = match self.idle {
+            true => self.configured.max(IDLE_POLL_INTERVAL),
+            false => self.configured,
+        };
+
+        if interval == self.current {
+            return None;
+        }
+        self.current = interval;
+        Some(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn intervals_are_bounded() {
+        assert!(check_poll_interval(4).is_err());
+        assert!(check_poll_interval(5).is_ok());
+        assert!(check_poll_interval(100).is_ok());
+        assert!(check_poll_interval(101).is_err());
+    }
+
+    #[test]
+    fn backs_off_when_idle_and_returns_on_activity() {
+        let start = Instant::now();
+        let mut rate = PollRate::new(10, start);
+        assert_eq!(rate.update(start, false), None);
+
+        // Occasional activity keeps it fast..
+        for second in [10, 20, 40] {
+            rate.activity(start + SECOND * second);
+        }
+        assert_eq!(rate.update(start + SECOND * 69, false), None);
+        assert!(!rate.is_idle());
+
+        // ..until it's been left alone
+        assert_eq!(rate.update(start + SECOND * 70, false), Some(100));
+        assert!(rate.is_idle());
+        assert_eq!(rate.update(start + SECOND * 600, false), None);
+
+        // The first change snaps it straight back
+        rate.activity(start + SECOND * 601);
+        assert_eq!(rate.update(start + SECOND * 601, false), Some(10));
+        assert_eq!(rate.current(), 10);
+    }
+
+    #[test]
+    fn stays_fast_while_required() {
+        let start = Instant::now();
+        let mut rate = PollRate::new(20, start);
+
+        for second in (0..120).step_by(5) {
+            assert_eq!(rate.update(start + SECOND * second, true), None);
+        }
+        assert!(!rate.is_idle());
+
+        // The idle time starts from when it stopped being required
+        assert_eq!(rate.update(start + SECOND * 140, false), None);
+        assert_eq!(rate.update(start + SECOND * 145, false), Some(100));
+    }
+
+    #[test]
+    fn changing_the_interval_applies_immediately() {
+        let start = Instant::now();
+        let mut rate = PollRate::new(20, start);
+        assert_eq!(rate.update(start + IDLE_AFTER, false), Some(100));
+
+        rate.set_configured(5, start + IDLE_AFTER);
+        assert_eq!(rate.update(start + IDLE_AFTER, false), Some(5));
+        assert_eq!(rate.configured(), 5);
+
+        // Slower than the idle rate is never sped up when idle
+        rate.set_configured(100, start + IDLE_AFTER);
+        assert_eq!(rate.update(start + IDLE_AFTER, false), Some(100));
+        assert_eq!(rate.update(start + IDLE_AFTER * 3, false), None);
+        assert!(rate.is_idle());
+    }
+}
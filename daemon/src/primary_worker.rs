@@ -1,41 +1,127 @@
+use crate::audio::get_audio_devices;
+use crate::cli::SimulatedDevice;
 use crate::device::Device;
+use crate::disk::find_low_space;
 use crate::events::EventTriggers;
-use crate::files::extract_defaults;
-use crate::platform::{get_ui_app_path, has_autostart, set_autostart};
-use crate::{FileManager, PatchEvent, SettingsHandle, Shutdown, SYSTEM_LOCALE, VERSION};
-use anyhow::{anyhow, Result};
+use crate::files::{find_name_problems, recover_defaults};
+use crate::icons::{delete_icon, get_icon_thumbnail, list_icons, prune_thumbnails, upload_icon};
+use crate::logging;
+use crate::migration::{finish_migration, start_migration};
+use crate::mute_sync::{get_mute_sync_status, is_voice_chat_muted, MuteSyncHandle};
+use crate::platform::{display_error, get_ui_app_path, has_autostart, set_autostart};
+use crate::priority::{split_interactive, ClientCommand};
+use crate::profile_rules::{
+    add_profile_rule, apply_profile_rule, find_profile_rule, remove_profile_rule,
+};
+use crate::scheduler::{
+    add_schedule, cancel_schedule, rearm_schedules, record_schedule_result, take_due_schedules,
+};
+use crate::servers::clients::ClientRegistry;
+use crate::servers::ipc_server::get_socket_name;
+use crate::servers::web_content::{get_ui_path, get_ui_version_warning, set_ui_path};
+use crate::settle::{PendingWork, SettleWaiters};
+use crate::simulated::{simulated_hardware, SimulatedGoXLR};
+use crate::support_bundle::{create_support_bundle, ActiveProfiles};
+#[cfg(feature = "telemetry")]
+use crate::telemetry;
+use crate::tray::TrayState;
+use crate::watchdog::{Watchdog, WatchdogAction, WATCHDOG_INTERVAL};
+use crate::{BroadcastEvent, FileManager, SettingsHandle, Shutdown, SYSTEM_LOCALE, VERSION};
+use anyhow::{anyhow, bail, Result};
+use chrono::Local;
 use enum_map::EnumMap;
+use goxlr_audio::cache::SampleCache;
+#[cfg(feature = "telemetry")]
+use goxlr_ipc::TelemetryReport;
 use goxlr_ipc::{
-    Activation, ColourWay, DaemonCommand, DaemonConfig, DaemonStatus, DriverDetails, Files,
-    GoXLRCommand, HardwareStatus, HttpSettings, Locale, PathTypes, Paths, SampleFile,
-    UsbProductInformation,
+    Activation, AudioDevices, ChannelClaim, ColourWay, CommandError, ControlQuery, DaemonCommand,
+    DaemonConfig, DaemonResponse, DaemonStatus, DataMigration, DeviceCapabilities, DeviceSummary,
+    DriverDetails, DriverStatus, EqCurveReport, EqTarget, Files, GoXLRCommand, HardwareStateReport,
+    HardwareStatus, HttpRateLimit, HttpSettings, IconDetails, Locale, LowDiskSpace, MidiStatus,
+    MigrationKind, MigrationState, MixerStatus, ModeChange, ModeChangePreview, MuteSyncApplication,
+    MuteSyncState, PathTypes, Paths, PreviousRun, RecoveredDefaults, ReplayCapture,
+    ResolvedControl, SampleFile, SessionStatus, ShutdownReason, StartupWarning, TelemetryStatus,
+    UsbProductInformation, STATUS_VERSION,
 };
-use goxlr_types::{DeviceType, VersionNumber};
+use goxlr_types::{ChannelName, DeviceType, MuteState, VersionNumber};
 use goxlr_usb::device::base::GoXLRDevice;
-use goxlr_usb::device::{find_devices, from_device, get_version};
+use goxlr_usb::device::{check_driver, find_devices, from_device, get_version, is_goxlr_connected};
+use goxlr_usb::error::DriverError;
 use goxlr_usb::{PID_GOXLR_FULL, PID_GOXLR_MINI};
 use json_patch::diff;
 use log::{debug, error, info, warn};
 use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::time::sleep;
 use xmltree::Element;
 
 const IGNORE_DEVICE_DURATION: Duration = Duration::from_secs(10);
 const APP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 // Adding a third entry has tripped enum_variant_names, I'll probably need to rename
 // RunDeviceCommand, but that'll need to be in a separate commit, for now, suppress.
 #[allow(clippy::enum_variant_names)]
 pub enum DeviceCommand {
     SendDaemonStatus(oneshot::Sender<DaemonStatus>),
+    GetStatusSummary(oneshot::Sender<Vec<DeviceSummary>>),
     RunDaemonCommand(DaemonCommand, oneshot::Sender<Result<()>>),
-    RunDeviceCommand(String, GoXLRCommand, oneshot::Sender<Result<()>>),
+    // The id of the client which sent it, if any (MIDI commands have none)
+    RunDeviceCommand(
+        String,
+        GoXLRCommand,
+        Option<u64>,
+        oneshot::Sender<Result<Option<DaemonResponse>>>,
+    ),
     GetDeviceMicLevel(String, oneshot::Sender<Result<f64>>),
+    IdentifyDevice(String, oneshot::Sender<Result<()>>),
+    Settle(String, u32, oneshot::Sender<Result<()>>),
+    SetButtonTest(String, bool, oneshot::Sender<Result<()>>),
+    GetAudioDevices(oneshot::Sender<AudioDevices>),
+    RecoverDefaults(
+        PathTypes,
+        Option<Vec<String>>,
+        bool,
+        oneshot::Sender<Result<RecoveredDefaults>>,
+    ),
+    ImportEqCurve(
+        String,
+        String,
+        EqTarget,
+        oneshot::Sender<Result<EqCurveReport>>,
+    ),
+    ExportEqCurve(String, String, EqTarget, oneshot::Sender<Result<()>>),
+    ExportWindowsProfile(String, String, oneshot::Sender<Result<()>>),
+    GetHardwareState(String, oneshot::Sender<Result<HardwareStateReport>>),
+    ResolveControl(
+        String,
+        ControlQuery,
+        oneshot::Sender<Result<ResolvedControl>>,
+    ),
+    PreviewModeChange(
+        String,
+        ModeChange,
+        oneshot::Sender<Result<ModeChangePreview>>,
+    ),
+    CreateSupportBundle(bool, oneshot::Sender<Result<PathBuf>>),
+    SaveReplayBuffer(
+        String,
+        Option<String>,
+        oneshot::Sender<Result<ReplayCapture>>,
+    ),
+    ListIcons(oneshot::Sender<Vec<IconDetails>>),
+    GetIconThumbnail(String, oneshot::Sender<Result<PathBuf>>),
+    UploadIcon(String, Vec<u8>, oneshot::Sender<Result<IconDetails>>),
+    DeleteIcon(String, bool, oneshot::Sender<Result<()>>),
+    #[cfg(feature = "telemetry")]
+    GetTelemetryPreview(oneshot::Sender<TelemetryReport>),
 }
 
 #[allow(dead_code)]
@@ -43,6 +129,39 @@ pub enum DeviceStateChange {
     Shutdown(bool),
     Sleep(oneshot::Sender<()>),
     Wake(oneshot::Sender<()>),
+    SetMicMuted(bool),
+    ScreenLocked,
+}
+
+// When the daemon started, and how many times each device has connected since
+struct Session {
+    started: Instant,
+    started_at: i64,
+    connections: HashMap<String, u32>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            started_at: Local::now().timestamp(),
+            connections: HashMap::new(),
+        }
+    }
+
+    fn status(&self) -> SessionStatus {
+        SessionStatus {
+            started_at: self.started_at,
+            uptime_secs: self.started.elapsed().as_secs(),
+        }
+    }
+
+    // Returns how many times the device had already connected (and so has been reconnected)
+    fn device_connected(&mut self, serial: &str) -> u32 {
+        let connections = self.connections.entry(serial.to_owned()).or_default();
+        *connections += 1;
+        *connections - 1
+    }
 }
 
 pub type DeviceSender = Sender<DeviceCommand>;
@@ -51,22 +170,38 @@ pub type DeviceReceiver = Receiver<DeviceCommand>;
 // Fix this later..
 #[allow(clippy::too_many_arguments)]
 pub async fn spawn_usb_handler(
-    mut command_rx: DeviceReceiver,
+    command_rx: DeviceReceiver,
     mut file_rx: Receiver<PathTypes>,
     mut device_state_rx: Receiver<DeviceStateChange>,
-    broadcast_tx: BroadcastSender<PatchEvent>,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
     global_tx: Sender<EventTriggers>,
+    tray_state_tx: watch::Sender<TrayState>,
     mut shutdown: Shutdown,
     settings: SettingsHandle,
-    http_settings: HttpSettings,
+    mut http_settings: HttpSettings,
+    rate_limit_tx: watch::Sender<HttpRateLimit>,
     mut file_manager: FileManager,
+    startup_warnings: Vec<StartupWarning>,
+    previous_run: Option<PreviousRun>,
+    alternate_data: Option<PathBuf>,
+    mut mute_sync: MuteSyncHandle,
+    mut midi_status: watch::Receiver<Option<MidiStatus>>,
+    midi_reload: watch::Sender<()>,
+    clients: Arc<ClientRegistry>,
+    mut simulate: Vec<SimulatedDevice>,
 ) {
+    let simulating = !simulate.is_empty();
     let mut firmware_version = None;
+    let mut session = Session::new();
+
+    // Mutes and volumes are pulled out of the queue so they don't wait behind bulk work
+    let (mut command_rx, interactive) = split_interactive(command_rx);
 
     // We can probably either merge these, or struct them..
     let (disconnect_sender, mut disconnect_receiver) = mpsc::channel(16);
     let (event_sender, mut event_receiver) = mpsc::channel(16);
     let (firmware_sender, mut firmware_receiver) = mpsc::channel(1);
+    let (migration_sender, mut migration_receiver) = mpsc::channel(16);
 
     // Spawn a task in the background to check for the latest firmware versions.
     tokio::spawn(check_firmware_versions(firmware_sender));
@@ -89,15 +224,54 @@ pub async fn spawn_usb_handler(
     let app_sleep = sleep(app_duration);
     tokio::pin!(app_sleep);
 
+    // Timer for running any Scheduled Commands..
+    let schedule_sleep = sleep(SCHEDULE_CHECK_INTERVAL);
+    tokio::pin!(schedule_sleep);
+    rearm_schedules(&settings).await;
+
+    // Timer for checking that every device is still being read..
+    let mut watchdog = Watchdog::default();
+    let watchdog_sleep = sleep(WATCHDOG_INTERVAL);
+    tokio::pin!(watchdog_sleep);
+
+    // Timer for checking the space left where the daemon writes its files..
+    let mut low_disk_space = find_low_space(&settings).await;
+    let disk_sleep = sleep(DISK_CHECK_INTERVAL);
+    tokio::pin!(disk_sleep);
+
     // Get the Driver Type and Details..
     let (interface, version) = get_version();
-    let driver_interface = DriverDetails { interface, version };
+    let driver_check = check_driver();
+    if let Err(error) = &driver_check {
+        error!("Driver Check Failed: {}", error);
+    }
+
+    let driver_interface = DriverDetails {
+        interface,
+        version,
+        status: get_driver_status(&driver_check),
+    };
+
+    // We only want to pop up a driver error once, rather than every time we retry.
+    let mut driver_error_reported = false;
+
+    // Guard Mode is only for the current session, it always starts off
+    let mut guard_mode = false;
 
     // Create the Primary Device List, and 'Ignore' list..
     let mut devices: HashMap<String, Device> = HashMap::new();
     let mut ignore_list = HashMap::new();
 
+    // Clients waiting for a device to finish its outstanding work..
+    let mut settle_waiters = SettleWaiters::default();
+
     let mut files = get_files(&mut file_manager, &settings).await;
+    let mut data_migration = DataMigration {
+        alternate_directory: alternate_data,
+        progress: None,
+    };
+    let mute_sync_states = mute_sync.states.borrow().clone();
+    let midi = midi_status.borrow().clone();
     let mut daemon_status = get_daemon_status(
         &devices,
         &settings,
@@ -106,6 +280,15 @@ pub async fn spawn_usb_handler(
         &firmware_version,
         files.clone(),
         &app_check,
+        &startup_warnings,
+        &previous_run,
+        &data_migration,
+        &mute_sync_states,
+        &midi,
+        &session,
+        guard_mode,
+        &low_disk_space,
+        clients.claims(),
     )
     .await;
 
@@ -133,7 +316,37 @@ pub async fn spawn_usb_handler(
                 change_found = true;
             },
             () = &mut detection_sleep => {
-                if let Some(device) = find_new_device(&daemon_status, &ignore_list) {
+                // Simulated devices are 'found' once, and nothing real is ever looked for.
+                for simulated in simulate.drain(..) {
+                    // Simulating the same device twice would give two devices one serial
+                    if devices.contains_key(&simulated_hardware(simulated).serial_number) {
+                        continue;
+                    }
+                    match load_simulated_device(simulated, global_tx.clone(), &settings).await {
+                        Ok(mut device) => {
+                            device.set_reconnect_count(session.device_connected(device.serial()));
+                            device.set_guard_mode(guard_mode);
+                            device.set_interactive_queue(interactive.clone());
+                            devices.insert(device.serial().to_owned(), device);
+                            change_found = true;
+                        }
+                        Err(e) => error!("Couldn't load the simulated GoXLR: {}", e),
+                    }
+                }
+
+                // If the driver's unusable, we'll never find a device, so check the USB bus..
+                if let Err(error) = &driver_check {
+                    if !simulating && !driver_error_reported && is_goxlr_connected() {
+                        report_driver_error(error);
+                        driver_error_reported = true;
+                    }
+                }
+
+                let found = match simulating {
+                    true => None,
+                    false => find_new_device(&daemon_status, &ignore_list),
+                };
+                if let Some(device) = found {
                     let existing_serials: Vec<String> = get_all_serials(&devices);
                     let bus_number = device.bus_number();
                     let address = device.address();
@@ -144,7 +357,10 @@ pub async fn spawn_usb_handler(
                     }
 
                     match load_device(device, existing_serials, disconnect_sender.clone(), event_sender.clone(), global_tx.clone(), &settings).await {
-                        Ok(device) => {
+                        Ok(mut device) => {
+                            device.set_reconnect_count(session.device_connected(device.serial()));
+                            device.set_guard_mode(guard_mode);
+                            device.set_interactive_queue(interactive.clone());
                             devices.insert(device.serial().to_owned(), device);
                             change_found = true;
                         }
@@ -153,6 +369,12 @@ pub async fn spawn_usb_handler(
                                 "Couldn't load potential GoXLR on bus {} address {}: {}",
                                 bus_number, address, e
                             );
+                            if let Some(error) = e.downcast_ref::<DriverError>() {
+                                if !driver_error_reported {
+                                    report_driver_error(error);
+                                    driver_error_reported = true;
+                                }
+                            }
                             ignore_list
                                 .insert((bus_number, address, device_identifier), Instant::now() + IGNORE_DEVICE_DURATION);
                         }
@@ -161,17 +383,7 @@ pub async fn spawn_usb_handler(
                 detection_sleep.as_mut().reset(tokio::time::Instant::now() + detection_duration);
             },
             () = &mut update_sleep => {
-                for device in devices.values_mut() {
-                    let updated = device.update_state().await;
-
-                    if let Ok(result) = updated {
-                        change_found = result;
-                    }
-
-                    if let Err(error) = updated {
-                        warn!("Error Received from {} while updating state: {}", device.serial(), error);
-                    }
-                }
+                change_found = update_devices(&mut devices).await;
                 update_sleep.as_mut().reset(tokio::time::Instant::now() + update_duration);
             },
             () = &mut app_sleep => {
@@ -180,6 +392,40 @@ pub async fn spawn_usb_handler(
                 }
                 app_sleep.as_mut().reset(tokio::time::Instant::now() + APP_CHECK_INTERVAL);
             },
+            () = &mut schedule_sleep => {
+                change_found = run_due_schedules(&mut devices, &settings).await;
+                schedule_sleep.as_mut().reset(tokio::time::Instant::now() + SCHEDULE_CHECK_INTERVAL);
+            },
+            () = &mut watchdog_sleep => {
+                change_found = check_watchdog(&mut devices, &mut watchdog).await;
+                watchdog_sleep.as_mut().reset(tokio::time::Instant::now() + WATCHDOG_INTERVAL);
+            },
+            () = &mut disk_sleep => {
+                let low_space = find_low_space(&settings).await;
+                let newly_low = low_space
+                    .iter()
+                    .filter(|dir| !low_disk_space.iter().any(|old| old.path == dir.path));
+                for directory in newly_low {
+                    warn!(
+                        "Only {}MB free for {}",
+                        directory.available / (1024 * 1024),
+                        directory.path.to_string_lossy()
+                    );
+                }
+
+                // The exact space changes constantly, only the directories are worth a patch
+                let paths = |list: &[LowDiskSpace]| -> Vec<PathBuf> {
+                    list.iter().map(|dir| dir.path.clone()).collect()
+                };
+                if paths(&low_space) != paths(&low_disk_space) {
+                    change_found = true;
+                }
+                low_disk_space = low_space;
+                disk_sleep.as_mut().reset(tokio::time::Instant::now() + DISK_CHECK_INTERVAL);
+            },
+            () = clients.claims_changed() => {
+                change_found = true;
+            },
             Some(serial) = disconnect_receiver.recv() => {
                 info!("[{}] Device Disconnected", serial);
                 devices.remove(&serial);
@@ -209,7 +455,7 @@ pub async fn spawn_usb_handler(
                         shutdown_triggered = true;
 
                         // Flip through all the devices, send a shutdown signal..
-                        for device in devices.values_mut() {
+                        for device in unlocked_devices(&mut devices) {
                             device.shutdown(avoid_write).await;
                         }
 
@@ -218,14 +464,14 @@ pub async fn spawn_usb_handler(
                     },
                     DeviceStateChange::Sleep(sender) => {
                         debug!("Received Sleep Notification");
-                        for device in devices.values_mut() {
+                        for device in unlocked_devices(&mut devices) {
                             device.sleep().await;
                         }
                         let _ = sender.send(());
                     },
                     DeviceStateChange::Wake(sender) => {
                         debug!("Received Wake Notification");
-                        for device in devices.values_mut() {
+                        for device in unlocked_devices(&mut devices) {
                             device.wake().await;
                         }
                         let _ = sender.send(());
@@ -234,6 +480,22 @@ pub async fn spawn_usb_handler(
                         // allows the UI to update when waking up.
                         change_found = true;
                     }
+                    DeviceStateChange::SetMicMuted(muted) => {
+                        for device in unlocked_devices(&mut devices) {
+                            if let Err(error) = device.set_mic_muted(muted).await {
+                                warn!("Unable to change Mic Mute on {}: {}", device.serial(), error);
+                            }
+                        }
+                        change_found = true;
+                    }
+                    DeviceStateChange::ScreenLocked => {
+                        for device in devices.values_mut() {
+                            if let Err(error) = device.screen_locked().await {
+                                warn!("Unable to lock the Mic on {}: {}", device.serial(), error);
+                            }
+                        }
+                        change_found = true;
+                    }
                 }
 
 
@@ -242,18 +504,40 @@ pub async fn spawn_usb_handler(
                 info!("Shutting down device worker");
                 return;
             },
+            () = interactive.arrived() => {
+                for queued in interactive.take_all() {
+                    change_found |= run_client_command(&mut devices, &clients, &broadcast_tx, queued).await;
+                }
+            }
             Some(command) = command_rx.recv() => {
+                // Anything interactive sent before this goes first, anything sent after it can
+                // only jump it at the preemption points while it's running (see priority.rs)
+                for queued in interactive.take_all() {
+                    run_client_command(&mut devices, &clients, &broadcast_tx, queued).await;
+                }
+                interactive.started();
+
                 match command {
                     DeviceCommand::SendDaemonStatus(sender) => {
+                        // The uptime is otherwise only refreshed alongside other changes
+                        daemon_status.session = session.status();
                         let _ = sender.send(daemon_status.clone());
                     }
 
+                    DeviceCommand::GetStatusSummary(sender) => {
+                        // Built straight from the devices, rather than cloning the whole status
+                        let mut summary: Vec<DeviceSummary> =
+                            devices.values().map(|device| device.get_summary()).collect();
+                        summary.sort_by(|a, b| a.serial.cmp(&b.serial));
+                        let _ = sender.send(summary);
+                    }
+
                     DeviceCommand::RunDaemonCommand(command, sender) => {
                         match command {
                             DaemonCommand::StopDaemon => {
                                 // These should probably be moved upstream somewhere, they're not
                                 // device specific!
-                                let _ = global_tx.send(EventTriggers::Stop(false)).await;
+                                let _ = global_tx.send(EventTriggers::Stop(false, ShutdownReason::UserRequest)).await;
                                 let _ = sender.send(Ok(()));
                             }
                             DaemonCommand::OpenUi => {
@@ -265,28 +549,35 @@ pub async fn spawn_usb_handler(
                                 let _ = sender.send(Ok(()));
                             }
                             DaemonCommand::RecoverDefaults(path_type) => {
-                                let path = match path_type {
-                                    PathTypes::Profiles => settings.get_profile_directory().await,
-                                    PathTypes::Presets => settings.get_presets_directory().await,
-                                    PathTypes::Icons => settings.get_icons_directory().await,
-                                    PathTypes::MicProfiles => settings.get_mic_profile_directory().await,
-                                    _ => {
-                                        let _ = sender.send(Err(anyhow!("Invalid Path type Sent")));
-                                        return;
-                                    }
-                                };
-                                let _ = sender.send(extract_defaults(path_type, &path));
+                                let result = recover_path_defaults(&settings, path_type.clone(), None, false).await;
+                                files = update_files(files, path_type, &mut file_manager, &settings).await;
+                                change_found = true;
+                                let _ = sender.send(result.map(|_| ()));
                             }
                             DaemonCommand::SetAutoStartEnabled(enabled) => {
-                                let _ = sender.send(set_autostart(enabled));
+                                let result = settings
+                                    .write_policy()
+                                    .check("change autostart")
+                                    .and_then(|_| set_autostart(enabled));
+                                let _ = sender.send(result);
                                 change_found = true;
                             }
                             DaemonCommand::SetLogLevel(level) => {
+                                logging::set_level(logging::level_filter(&level));
                                 settings.set_log_level(level).await;
                                 settings.save().await;
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetLogFilter(filter) => {
+                                let result = logging::set_filter(filter.as_deref());
+                                if result.is_ok() {
+                                    settings.set_log_filter(filter).await;
+                                    settings.save().await;
+                                    change_found = true;
+                                }
+                                let _ = sender.send(result);
+                            }
                             DaemonCommand::SetLocale(language) => {
                                 settings.set_selected_locale(language).await;
                                 settings.save().await;
@@ -318,6 +609,14 @@ pub async fn spawn_usb_handler(
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetHttpRateLimit(limit) => {
+                                settings.set_http_rate_limit(limit.clone()).await;
+                                settings.save().await;
+                                http_settings.rate_limit = limit.clone();
+                                let _ = rate_limit_tx.send(limit);
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
                             DaemonCommand::OpenPath(path_type) => {
                                 // There's nothing we can really do if this errors..
                                 let _ = global_tx.send(EventTriggers::Open(path_type)).await;
@@ -336,6 +635,42 @@ pub async fn spawn_usb_handler(
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetSampleCacheEnabled(enabled) => {
+                                settings.set_sample_cache_enabled(enabled).await;
+                                settings.save().await;
+
+                                for device in devices.values_mut() {
+                                    device.load_sample_cache_settings().await;
+                                }
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::SetSampleCacheSize(size) => {
+                                settings.set_sample_cache_size(size).await;
+                                settings.save().await;
+
+                                for device in devices.values_mut() {
+                                    device.load_sample_cache_settings().await;
+                                }
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::ClearSampleCache => {
+                                // All devices share the same cache directory, but track their own
+                                // statistics, so make sure they all get reset.
+                                let mut result = Ok(());
+                                if devices.is_empty() {
+                                    let path = settings.get_sample_cache_directory();
+                                    result = SampleCache::new(path).clear();
+                                }
+                                for device in devices.values_mut() {
+                                    if let Err(e) = device.clear_sample_cache() {
+                                        result = Err(e);
+                                    }
+                                }
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
                             DaemonCommand::SetActivatorPath(path) => {
                                 if let Some(path) = path {
                                     settings.set_activate(Some(path.to_string_lossy().to_string())).await;
@@ -347,6 +682,69 @@ pub async fn spawn_usb_handler(
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetUiPath(path) => {
+                                let result = set_ui_path(path.clone());
+                                if result.is_ok() {
+                                    settings.set_ui_path(path).await;
+                                    settings.save().await;
+                                }
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::MigrateDataDirectory(root) => {
+                                let kind = MigrationKind::MoveTo(root);
+                                let result = start_migration(kind, &settings, file_manager.paths(), &mut data_migration, migration_sender.clone()).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::ImportDataDirectory(root) => {
+                                let kind = MigrationKind::ImportFrom(root);
+                                let result = start_migration(kind, &settings, file_manager.paths(), &mut data_migration, migration_sender.clone()).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::SetMuteSyncEnabled(application, enabled) => {
+                                settings.set_mute_sync_enabled(application, enabled).await;
+                                settings.save().await;
+                                mute_sync.reload.send_replace(());
+
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::SetMuteSyncDirection(application, direction) => {
+                                settings.set_mute_sync_direction(application, direction).await;
+                                settings.save().await;
+                                mute_sync.reload.send_replace(());
+
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::SetMuteSyncClient { application, client_id, client_secret } => {
+                                settings.set_mute_sync_client(application, client_id, client_secret).await;
+                                settings.save().await;
+                                mute_sync.reload.send_replace(());
+
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::SetMidiEnabled(_)
+                            | DaemonCommand::SetMidiPort(_)
+                            | DaemonCommand::SetMidiMapping(_)
+                            | DaemonCommand::RemoveMidiMapping(_) => {
+                                let result = update_midi_settings(&settings, command).await;
+                                if result.is_ok() {
+                                    midi_reload.send_replace(());
+                                }
+
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::SetTelemetryEnabled(_)
+                            | DaemonCommand::SetTelemetryEndpoint(_) => {
+                                let result = update_telemetry_settings(&settings, command).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
                             DaemonCommand::HandleMacOSAggregates(value) => {
                                 settings.set_macos_handle_aggregates(value).await;
                                 settings.save().await;
@@ -354,50 +752,283 @@ pub async fn spawn_usb_handler(
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetGuardMode(enabled) => {
+                                info!("Guard Mode {}", if enabled { "Enabled" } else { "Disabled" });
+                                guard_mode = enabled;
+                                for device in devices.values_mut() {
+                                    device.set_guard_mode(enabled);
+                                }
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::ScheduleCommand { id, when, serial, command } => {
+                                let result = add_schedule(&settings, id, when, serial, command).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::CancelScheduledCommand(id) => {
+                                let result = cancel_schedule(&settings, &id).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::SetProfileRule(rule) => {
+                                let result = add_profile_rule(&settings, rule).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
+                            DaemonCommand::RemoveProfileRule(trigger) => {
+                                let result = remove_profile_rule(&settings, trigger).await;
+                                change_found = true;
+                                let _ = sender.send(result);
+                            }
                         }
                     },
 
-                    DeviceCommand::RunDeviceCommand(serial, command, sender) => {
+                    DeviceCommand::RunDeviceCommand(serial, command, client_id, sender) => {
+                        let command = ClientCommand { serial, command, client_id, sender };
+                        change_found = run_client_command(&mut devices, &clients, &broadcast_tx, command).await;
+                    },
+
+                    DeviceCommand::GetDeviceMicLevel(serial, sender) => {
+                        match unlocked_device(&mut devices, &serial) {
+                            Ok(device) => {
+                                let _ = sender.send(device.get_mic_level().await);
+                            }
+                            Err(error) => {
+                                let _ = sender.send(Err(error));
+                            }
+                        }
+                    }
+
+                    DeviceCommand::IdentifyDevice(serial, sender) => {
+                        match unlocked_device(&mut devices, &serial) {
+                            Ok(device) => {
+                                let _ = sender.send(device.identify().await);
+                                change_found = true;
+                            }
+                            Err(error) => {
+                                let _ = sender.send(Err(error));
+                            }
+                        }
+                    }
+
+                    DeviceCommand::Settle(serial, timeout, sender) => {
+                        settle_waiters.add(serial, timeout, sender, Instant::now());
+                    }
+
+                    DeviceCommand::SetButtonTest(serial, enabled, sender) => {
+                        match unlocked_device(&mut devices, &serial) {
+                            Ok(device) => {
+                                let _ = sender.send(device.set_button_test(enabled));
+                                change_found = true;
+                            }
+                            Err(error) => {
+                                let _ = sender.send(Err(error));
+                            }
+                        }
+                    }
+
+                    DeviceCommand::GetAudioDevices(sender) => {
+                        let _ = sender.send(get_audio_devices());
+                    }
+
+                    DeviceCommand::RecoverDefaults(path_type, names, overwrite, sender) => {
+                        let result = recover_path_defaults(&settings, path_type.clone(), names, overwrite).await;
+                        files = update_files(files, path_type, &mut file_manager, &settings).await;
+                        change_found = true;
+                        let _ = sender.send(result);
+                    }
+
+                    DeviceCommand::ImportEqCurve(serial, name, target, sender) => {
+                        match unlocked_device(&mut devices, &serial) {
+                            Ok(device) => {
+                                let _ = sender.send(device.import_eq_curve(&name, target).await);
+                                change_found = true;
+                            }
+                            Err(error) => {
+                                let _ = sender.send(Err(error));
+                            }
+                        }
+                    }
+
+                    DeviceCommand::ExportEqCurve(serial, name, target, sender) => {
+                        match unlocked_device(&mut devices, &serial) {
+                            Ok(device) => {
+                                let _ = sender.send(device.export_eq_curve(&name, target).await);
+                            }
+                            Err(error) => {
+                                let _ = sender.send(Err(error));
+                            }
+                        }
+                    }
+
+                    DeviceCommand::ExportWindowsProfile(serial, name, sender) => {
                         if let Some(device) = devices.get_mut(&serial) {
-                            let result = match device.perform_command(command.clone()).await {
-                                Ok(result) => {
-                                    Ok(result)
-                                }
-                                Err(error) => {
-                                    warn!("Error Executing: {:?}, {}", command, error);
-                                    Err(error)
-                                }
-                            };
-                            let _ = sender.send(result);
-                            change_found = true;
+                            let _ = sender.send(device.export_windows_profile(&name).await);
                         } else {
                             let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
                         }
-                    },
+                    }
 
-                    DeviceCommand::GetDeviceMicLevel(serial, sender) => {
+                    DeviceCommand::GetHardwareState(serial, sender) => {
+                        if let Some(device) = devices.get_mut(&serial) {
+                            let _ = sender.send(device.get_hardware_state());
+                        } else {
+                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
+                        }
+                    }
+
+                    DeviceCommand::ResolveControl(serial, query, sender) => {
+                        if let Some(device) = devices.get(&serial) {
+                            let _ = sender.send(Ok(device.resolve_control(query)));
+                        } else {
+                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
+                        }
+                    }
+
+                    DeviceCommand::PreviewModeChange(serial, change, sender) => {
+                        if let Some(device) = devices.get(&serial) {
+                            let _ = sender.send(device.preview_mode_change(change).await);
+                        } else {
+                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
+                        }
+                    }
+
+                    DeviceCommand::SaveReplayBuffer(serial, file_name, sender) => {
                         if let Some(device) = devices.get_mut(&serial) {
-                            let _ = sender.send(device.get_mic_level().await);
+                            let _ = sender.send(device.save_replay_buffer(file_name).await);
+                            change_found = true;
                         } else {
                             let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
                         }
                     }
+
+                    DeviceCommand::CreateSupportBundle(include_profiles, sender) => {
+                        let profiles: Vec<ActiveProfiles> = devices
+                            .iter()
+                            .map(|(serial, device)| ActiveProfiles {
+                                serial: serial.clone(),
+                                profile_name: device.profile().name().to_string(),
+                                profile_version: device.profile().get_version(),
+                                profile_utility_version: device
+                                    .profile()
+                                    .get_utility_version()
+                                    .map(String::from),
+                                mic_profile_name: device.mic_profile().name().to_string(),
+                            })
+                            .collect();
+
+                        let result = create_support_bundle(
+                            &settings,
+                            &daemon_status,
+                            &profiles,
+                            include_profiles,
+                        )
+                        .await;
+                        let _ = sender.send(result);
+                    }
+
+                    #[cfg(feature = "telemetry")]
+                    DeviceCommand::GetTelemetryPreview(sender) => {
+                        let counts = settings.get_telemetry().await.counts;
+                        let _ = sender.send(telemetry::create_report(counts));
+                    }
+
+                    DeviceCommand::ListIcons(sender) => {
+                        let icons = settings.get_icons_directory().await;
+                        let cache = settings.get_icon_cache_directory();
+                        let names = files.icons.clone();
+
+                        // Drawing new thumbnails can take a moment, so keep it off this loop
+                        tokio::task::spawn_blocking(move || {
+                            let _ = sender.send(list_icons(&icons, &cache, &names));
+                        });
+                    }
+
+                    DeviceCommand::GetIconThumbnail(name, sender) => {
+                        let icons = settings.get_icons_directory().await;
+                        let cache = settings.get_icon_cache_directory();
+                        tokio::task::spawn_blocking(move || {
+                            let _ = sender.send(get_icon_thumbnail(&icons, &cache, &name));
+                        });
+                    }
+
+                    DeviceCommand::UploadIcon(name, data, sender) => {
+                        let icons = settings.get_icons_directory().await;
+                        let cache = settings.get_icon_cache_directory();
+                        let write_policy = settings.write_policy();
+
+                        // The file watcher will see the new icon, and update the file list
+                        tokio::task::spawn_blocking(move || {
+                            let result = write_policy
+                                .check("upload an icon")
+                                .and_then(|()| upload_icon(&icons, &cache, &name, &data));
+                            let _ = sender.send(result);
+                        });
+                    }
+
+                    DeviceCommand::DeleteIcon(name, force, sender) => {
+                        let result = remove_icon(&settings, &devices, &name, force).await;
+                        files = update_files(files, PathTypes::Icons, &mut file_manager, &settings).await;
+                        change_found = true;
+                        let _ = sender.send(result);
+                    }
                 }
+                interactive.finished();
             },
-            Some(path) = file_rx.recv() => {
-                // Notify devices if Samples have changed..
-                if path == PathTypes::Samples {
-                    for device in devices.values_mut() {
-                        let _ = device.validate_sampler().await;
+            Some(progress) = migration_receiver.recv() => {
+                if progress.state == MigrationState::Complete {
+                    finish_migration(&progress.kind, &settings, &mut file_manager).await;
+
+                    // Everything may have changed, so reload all the file lists
+                    files = get_files(&mut file_manager, &settings).await;
+                    if let MigrationKind::ImportFrom(_) = progress.kind {
+                        data_migration.alternate_directory = None;
                     }
                 }
+                data_migration.progress = Some(progress);
+                change_found = true;
+            }
+            Ok(()) = mute_sync.states.changed() => {
+                change_found = true;
+            }
+            Ok(()) = midi_status.changed() => {
+                change_found = true;
+            }
+            Some(path) = file_rx.recv() => {
+                notify_file_change(&mut devices, &path).await;
 
+                let icons_changed = path == PathTypes::Icons;
                 files = update_files(files, path, &mut file_manager, &settings).await;
+                if icons_changed {
+                    prune_thumbnails(&settings.get_icon_cache_directory(), &files.icons);
+                }
+                change_found = true;
+            }
+        }
+
+        // Let anyone with a claim know about interactive commands which ran inside bulk work
+        for device in devices.values_mut() {
+            for preempted in device.take_preempted() {
+                let serial = device.serial().to_owned();
+                report_claim_overrides(
+                    &clients,
+                    &broadcast_tx,
+                    &serial,
+                    preempted.client_id,
+                    &preempted.channels,
+                    &preempted.command,
+                );
                 change_found = true;
             }
+            for event in device.take_button_events() {
+                let _ = broadcast_tx.send(BroadcastEvent::ButtonEvent(event));
+            }
         }
 
         if change_found {
+            let mute_sync_states = mute_sync.states.borrow().clone();
+            let midi = midi_status.borrow().clone();
             let new_status = get_daemon_status(
                 &devices,
                 &settings,
@@ -406,6 +1037,15 @@ pub async fn spawn_usb_handler(
                 &firmware_version,
                 files.clone(),
                 &app_check,
+                &startup_warnings,
+                &previous_run,
+                &data_migration,
+                &mute_sync_states,
+                &midi,
+                &session,
+                guard_mode,
+                &low_disk_space,
+                clients.claims(),
             )
             .await;
 
@@ -417,15 +1057,124 @@ pub async fn spawn_usb_handler(
 
             // Only send a patch if something has changed..
             if !patch.0.is_empty() {
-                let _ = broadcast_tx.send(PatchEvent { data: patch });
+                let _ = broadcast_tx.send(BroadcastEvent::Patch(patch));
             }
 
             // Send the patch to the tokio broadcaster, for handling by clients..
             daemon_status = new_status;
+
+            let tray_state = TrayState {
+                connected: !daemon_status.mixers.is_empty(),
+                mic_muted: daemon_status
+                    .mixers
+                    .values()
+                    .any(|mixer| mixer.cough_button.state != MuteState::Unmuted),
+                updating: daemon_status.mixers.values().any(|mixer| mixer.locked),
+            };
+            tray_state_tx.send_if_modified(|state| {
+                if *state != tray_state {
+                    *state = tray_state;
+                    return true;
+                }
+                false
+            });
+
+            let voice_chat_muted = is_voice_chat_muted(&daemon_status);
+            mute_sync.mic_muted.send_if_modified(|muted| {
+                if *muted != voice_chat_muted {
+                    *muted = voice_chat_muted;
+                    return true;
+                }
+                false
+            });
         }
+
+        // Anything still queued has to run before a device can be considered settled
+        let queued = command_rx.len() + interactive.count();
+        settle_waiters.check(Instant::now(), |serial| {
+            let mut work = devices.get(serial)?.pending_work();
+            if queued > 0 {
+                work.insert(0, PendingWork::QueuedCommands(queued));
+            }
+            Some(work)
+        });
     }
 }
 
+// The MIDI Service is told to reload once these have been saved
+async fn update_midi_settings(settings: &SettingsHandle, command: DaemonCommand) -> Result<()> {
+    if !cfg!(feature = "midi") {
+        bail!("MIDI support isn't included in this build");
+    }
+
+    match command {
+        DaemonCommand::SetMidiEnabled(enabled) => settings.set_midi_enabled(enabled).await,
+        DaemonCommand::SetMidiPort(port) => settings.set_midi_port(port).await,
+        DaemonCommand::SetMidiMapping(mapping) => {
+            // The command is checked at both ends of the range, so a bad template is refused
+            // now rather than failing each time the control is moved.
+            for value in [0, 127] {
+                if let Err(e) = mapping.command_for(value) {
+                    bail!("Invalid Command for the MIDI Mapping: {}", e);
+                }
+            }
+            settings.set_midi_mapping(mapping).await;
+        }
+        DaemonCommand::RemoveMidiMapping(control) => {
+            if !settings.remove_midi_mapping(control).await {
+                bail!("No MIDI Mapping found for {:?}", control);
+            }
+        }
+        _ => bail!("Not a MIDI command"),
+    }
+    settings.save().await;
+    Ok(())
+}
+
+async fn update_telemetry_settings(
+    settings: &SettingsHandle,
+    command: DaemonCommand,
+) -> Result<()> {
+    if !cfg!(feature = "telemetry") {
+        bail!("Usage statistics aren't included in this build");
+    }
+
+    match command {
+        DaemonCommand::SetTelemetryEnabled(enabled) => {
+            let now = Local::now().timestamp();
+            settings.set_telemetry_enabled(enabled, now).await;
+        }
+        DaemonCommand::SetTelemetryEndpoint(endpoint) => {
+            if let Some(endpoint) = &endpoint {
+                if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                    bail!("The endpoint must be an http or https URL");
+                }
+            }
+            settings.set_telemetry_endpoint(endpoint).await;
+        }
+        _ => bail!("Not a Telemetry command"),
+    }
+    settings.save().await;
+    Ok(())
+}
+
+#[cfg(feature = "telemetry")]
+async fn get_telemetry_status(settings: &SettingsHandle) -> Option<TelemetryStatus> {
+    let telemetry = settings.get_telemetry().await;
+    Some(TelemetryStatus {
+        enabled: telemetry.enabled,
+        endpoint: telemetry.endpoint,
+        last_submitted: telemetry.last_submitted,
+    })
+}
+
+// Without it, the status stays empty and clients know usage statistics aren't available
+#[cfg(not(feature = "telemetry"))]
+async fn get_telemetry_status(_settings: &SettingsHandle) -> Option<TelemetryStatus> {
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn get_daemon_status(
     devices: &HashMap<String, Device<'_>>,
     settings: &SettingsHandle,
@@ -434,8 +1183,18 @@ async fn get_daemon_status(
     firmware_versions: &Option<EnumMap<DeviceType, Option<VersionNumber>>>,
     files: Files,
     app_check: &Option<String>,
+    startup_warnings: &[StartupWarning],
+    previous_run: &Option<PreviousRun>,
+    data_migration: &DataMigration,
+    mute_sync_states: &EnumMap<MuteSyncApplication, MuteSyncState>,
+    midi: &Option<MidiStatus>,
+    session: &Session,
+    guard_mode: bool,
+    low_disk_space: &[LowDiskSpace],
+    claims: Vec<ChannelClaim>,
 ) -> DaemonStatus {
     let mut status = DaemonStatus {
+        status_version: STATUS_VERSION,
         config: DaemonConfig {
             http_settings: http_settings.clone(),
             daemon_version: String::from(VERSION),
@@ -450,6 +1209,7 @@ async fn get_daemon_status(
             tts_enabled: settings.get_tts_enabled().await,
             allow_network_access: settings.get_allow_network_access().await,
             log_level: settings.get_log_level().await,
+            log_filter: settings.get_log_filter().await,
             open_ui_on_launch: settings.get_open_ui_on_launch().await,
             activation: Activation {
                 active_path: settings.get_activate().await,
@@ -457,6 +1217,22 @@ async fn get_daemon_status(
             },
             platform: env::consts::OS.to_string(),
             handle_macos_aggregates: settings.get_macos_handle_aggregates().await,
+            sample_cache_enabled: settings.get_sample_cache_enabled().await,
+            sample_cache_size: settings.get_sample_cache_size().await,
+            read_only: settings.write_policy().is_read_only(),
+            socket_path: get_socket_name().to_string(),
+            startup_warnings: startup_warnings.to_vec(),
+            previous_run: previous_run.clone(),
+            ui_path: get_ui_path(),
+            ui_version_warning: get_ui_version_warning(),
+            data_migration: data_migration.clone(),
+            mute_sync: get_mute_sync_status(settings, mute_sync_states).await,
+            midi: midi.clone(),
+            telemetry: get_telemetry_status(settings).await,
+            profile_rules: settings.get_profile_rules().await,
+            guard_mode,
+            low_disk_space: low_disk_space.to_vec(),
+            settings_save_error: settings.get_save_error().await,
         },
         paths: Paths {
             profile_directory: settings.get_profile_directory().await,
@@ -467,16 +1243,223 @@ async fn get_daemon_status(
             logs_directory: settings.get_log_directory().await,
         },
         files,
+        schedules: settings.get_schedules().await,
+        session: session.status(),
+        claims,
         ..Default::default()
     };
 
+    status.mixers = get_mixer_statuses(devices).await;
+    status
+}
+
+async fn get_mixer_statuses(devices: &HashMap<String, Device<'_>>) -> HashMap<String, MixerStatus> {
+    let mut mixers = HashMap::new();
     for (serial, device) in devices {
-        status
-            .mixers
-            .insert(serial.to_owned(), device.status().await.clone());
+        mixers.insert(serial.to_owned(), device.status().await);
     }
+    mixers
+}
 
-    status
+/// Updates the state of every device, returns true if any of them changed.
+async fn update_devices(devices: &mut HashMap<String, Device<'_>>) -> bool {
+    let mut change_found = false;
+    for device in devices.values_mut() {
+        match device.update_state().await {
+            Ok(changed) => change_found |= changed,
+            Err(error) => {
+                warn!(
+                    "Error Received from {} while updating state: {}",
+                    device.serial(),
+                    error
+                );
+            }
+        }
+    }
+    change_found
+}
+
+/// Resets any device which has stopped being read, by dropping it so it's found and connected
+/// again (as if it had been unplugged). Returns true if anything changed.
+async fn check_watchdog(
+    devices: &mut HashMap<String, Device<'_>>,
+    watchdog: &mut Watchdog,
+) -> bool {
+    let now = tokio::time::Instant::now();
+    let mut change_found = false;
+    let mut stalled = vec![];
+
+    for (serial, device) in devices.iter_mut() {
+        let timeout = device.stall_timeout().await;
+        match watchdog.check(serial, device.heartbeat(), timeout, now) {
+            Some(WatchdogAction::Reset) => stalled.push(serial.clone()),
+            Some(WatchdogAction::Faulted) => {
+                error!(
+                    "[{}] Device has stalled too often, not resetting it",
+                    serial
+                );
+                device.report_stall(false).await;
+            }
+            None => {}
+        }
+
+        let faulted = watchdog.is_faulted(serial);
+        if device.is_faulted() != faulted {
+            device.set_faulted(faulted);
+            change_found = true;
+        }
+    }
+
+    for serial in stalled {
+        if let Some(mut device) = devices.remove(&serial) {
+            warn!("[{}] Device has stalled, resetting", serial);
+            device.report_stall(true).await;
+            change_found = true;
+        }
+    }
+    change_found
+}
+
+/// Runs a client's command on its device, and sends back the result. Returns true if the device
+/// is connected (so its status may have changed).
+async fn run_client_command(
+    devices: &mut HashMap<String, Device<'_>>,
+    clients: &ClientRegistry,
+    broadcast_tx: &BroadcastSender<BroadcastEvent>,
+    command: ClientCommand,
+) -> bool {
+    let ClientCommand {
+        serial,
+        command,
+        client_id,
+        sender,
+    } = command;
+    let connected = devices.contains_key(&serial);
+    let channels = devices
+        .get(&serial)
+        .map(|device| device.channels_changed_by(&command))
+        .unwrap_or_default();
+
+    let result = run_device_command(devices, &serial, command.clone()).await;
+    if result.is_ok() {
+        report_claim_overrides(
+            clients,
+            broadcast_tx,
+            &serial,
+            client_id,
+            &channels,
+            &command,
+        );
+    }
+    let _ = sender.send(result);
+    connected
+}
+
+/// Lets the clients holding claims on these channels know another client changed them.
+fn report_claim_overrides(
+    clients: &ClientRegistry,
+    broadcast_tx: &BroadcastSender<BroadcastEvent>,
+    serial: &str,
+    client_id: Option<u64>,
+    channels: &[ChannelName],
+    command: &GoXLRCommand,
+) {
+    for claim_override in clients.overrides(client_id, serial, channels, command) {
+        debug!(
+            "[{}] {:?} (claimed by {}) was changed by client {}",
+            serial,
+            claim_override.claim.channel,
+            claim_override.claim.claimant,
+            claim_override.overridden_by.id
+        );
+        let _ = broadcast_tx.send(BroadcastEvent::ClaimOverridden(claim_override));
+    }
+}
+
+/// Runs a command on a single device. Clients often send the same command to every connected
+/// device, so a command refused for lacking a feature is logged against the device it was sent
+/// to, to make it clear which one couldn't handle it.
+async fn run_device_command(
+    devices: &mut HashMap<String, Device<'_>>,
+    serial: &str,
+    command: GoXLRCommand,
+) -> Result<Option<DaemonResponse>> {
+    let device = unlocked_device(devices, serial)?;
+    match device.perform_command(command.clone()).await {
+        Ok(()) => Ok(device.take_command_response(&command)),
+        Err(error) => {
+            match error.downcast_ref::<CommandError>() {
+                Some(CommandError::NotSupported(feature)) => warn!(
+                    "[{}] Refused {:?}, the {:?} doesn't have {:?}",
+                    serial,
+                    command,
+                    device.device_type(),
+                    feature
+                ),
+                _ => warn!("[{}] Error Executing: {:?}, {}", serial, command, error),
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Lets the devices know their files have changed on disk.
+async fn notify_file_change(devices: &mut HashMap<String, Device<'_>>, path: &PathTypes) {
+    if *path == PathTypes::Samples {
+        for device in unlocked_devices(devices) {
+            if let Err(error) = device.validate_sampler().await {
+                warn!(
+                    "Unable to validate samples on {}: {}",
+                    device.serial(),
+                    error
+                );
+            }
+        }
+    }
+}
+
+/// Runs any Scheduled Commands which are due, returns true if any were run.
+async fn run_due_schedules(
+    devices: &mut HashMap<String, Device<'_>>,
+    settings: &SettingsHandle,
+) -> bool {
+    let due = take_due_schedules(settings).await;
+    if due.is_empty() {
+        return false;
+    }
+
+    for (id, serial, command) in due {
+        debug!("Running Scheduled Command {}: {:?}", id, command);
+        let result = match unlocked_device(devices, &serial) {
+            Ok(device) => device.perform_command(command).await,
+            Err(error) => Err(error),
+        };
+        record_schedule_result(settings, &id, &result).await;
+    }
+
+    settings.save().await;
+    true
+}
+
+/// The device a command is for, commands are refused while a firmware update has it locked.
+fn unlocked_device<'a, 'b>(
+    devices: &'a mut HashMap<String, Device<'b>>,
+    serial: &str,
+) -> Result<&'a mut Device<'b>> {
+    let device = devices
+        .get_mut(serial)
+        .ok_or_else(|| anyhow!("Device {} is not connected", serial))?;
+    if device.is_locked() {
+        return Err(CommandError::FirmwareUpdateInProgress.into());
+    }
+    Ok(device)
+}
+
+/// Every device which isn't locked by a firmware update.
+fn unlocked_devices<'a, 'b>(
+    devices: &'a mut HashMap<String, Device<'b>>,
+) -> impl Iterator<Item = &'a mut Device<'b>> {
+    devices.values_mut().filter(|device| !device.is_locked())
 }
 
 #[allow(const_item_mutation)]
@@ -537,28 +1520,93 @@ async fn get_sample_files(
 }
 
 async fn get_files(file_manager: &mut FileManager, settings: &SettingsHandle) -> Files {
+    let profiles = file_manager.get_profiles();
     Files {
-        profiles: file_manager.get_profiles(),
+        profile_name_problems: find_name_problems(&profiles),
+        profiles,
         mic_profiles: file_manager.get_mic_profiles(),
         presets: file_manager.get_presets(),
+        effect_sections: file_manager.get_effect_sections(),
         samples: get_sample_files(file_manager, settings).await,
         icons: file_manager.get_icons(),
     }
 }
 
+async fn recover_path_defaults(
+    settings: &SettingsHandle,
+    path_type: PathTypes,
+    files: Option<Vec<String>>,
+    overwrite: bool,
+) -> Result<RecoveredDefaults> {
+    settings.write_policy().check("recover defaults")?;
+
+    let path = match path_type {
+        PathTypes::Profiles => settings.get_profile_directory().await,
+        PathTypes::Presets => settings.get_presets_directory().await,
+        PathTypes::Icons => settings.get_icons_directory().await,
+        PathTypes::MicProfiles => settings.get_mic_profile_directory().await,
+        _ => bail!("Invalid Path type Sent"),
+    };
+
+    let result = recover_defaults(path_type, &path, files, overwrite)?;
+    info!(
+        "Recovered Defaults, {} created, {} skipped, {} failed",
+        result.created.len(),
+        result.skipped.len(),
+        result.failed.len()
+    );
+    Ok(result)
+}
+
+/// Deletes an icon, unless a loaded profile has it on a scribble (in which case it must be
+/// forced, and the scribble will be left blank).
+async fn remove_icon(
+    settings: &SettingsHandle,
+    devices: &HashMap<String, Device<'_>>,
+    name: &str,
+    force: bool,
+) -> Result<()> {
+    settings.write_policy().check("delete an icon")?;
+
+    let faders: Vec<String> = devices
+        .values()
+        .flat_map(|device| {
+            let faders = device.profile().get_faders_using_icon(name);
+            faders
+                .into_iter()
+                .map(|fader| format!("Fader {} on {}", fader, device.serial()))
+        })
+        .collect();
+
+    if !faders.is_empty() {
+        if !force {
+            bail!("{} is used by {}", name, faders.join(", "));
+        }
+        warn!("Deleting {}, which is used by {}", name, faders.join(", "));
+    }
+
+    let icons = settings.get_icons_directory().await;
+    delete_icon(&icons, &settings.get_icon_cache_directory(), name)?;
+    info!("Deleted Icon {}", name);
+    Ok(())
+}
+
 async fn update_files(
     files: Files,
     file_type: PathTypes,
     file_manager: &mut FileManager,
     settings: &SettingsHandle,
 ) -> Files {
+    let profiles = if file_type != PathTypes::Profiles {
+        files.profiles
+    } else {
+        file_manager.get_profiles()
+    };
+
     // Only re-poll for the changed type.
     Files {
-        profiles: if file_type != PathTypes::Profiles {
-            files.profiles
-        } else {
-            file_manager.get_profiles()
-        },
+        profile_name_problems: find_name_problems(&profiles),
+        profiles,
 
         mic_profiles: if file_type != PathTypes::MicProfiles {
             files.mic_profiles
@@ -572,6 +1620,12 @@ async fn update_files(
             file_manager.get_presets()
         },
 
+        effect_sections: if file_type != PathTypes::Presets {
+            files.effect_sections
+        } else {
+            file_manager.get_effect_sections()
+        },
+
         samples: if file_type != PathTypes::Samples {
             files.samples
         } else {
@@ -586,6 +1640,26 @@ async fn update_files(
     }
 }
 
+fn get_driver_status(check: &Result<(), DriverError>) -> DriverStatus {
+    match check {
+        Err(DriverError::NotInstalled { .. }) => DriverStatus::NotInstalled,
+        Err(DriverError::Outdated { required, .. }) => DriverStatus::Outdated {
+            required: required.clone(),
+        },
+
+        // Anything else only occurs when attaching to a device.
+        _ => DriverStatus::Ok,
+    }
+}
+
+fn report_driver_error(error: &DriverError) {
+    let message = format!("Unable to connect to the GoXLR:\r\n\r\n{}", error);
+    error!("{}", error);
+
+    // The dialog blocks until it's dismissed, so keep it away from the worker.
+    tokio::task::spawn_blocking(move || display_error(message));
+}
+
 fn find_new_device(
     current_status: &DaemonStatus,
     devices_to_ignore: &HashMap<(u8, u8, Option<String>), Instant>,
@@ -683,14 +1757,45 @@ async fn load_device(
         device_type,
         colour_way,
         usb_device,
+        capabilities: DeviceCapabilities::default(),
     };
+    let firmware = hardware.versions.firmware.clone();
     let device = Device::new(handled_device, hardware, settings, global_events).await?;
+    finish_loading(device, &firmware, settings).await
+}
+
+async fn load_simulated_device(
+    simulated: SimulatedDevice,
+    global_events: Sender<EventTriggers>,
+    settings: &SettingsHandle,
+) -> Result<Device<'_>> {
+    let hardware = simulated_hardware(simulated);
+    let serial_number = hardware.serial_number.clone();
+    info!("Simulating a GoXLR with the serial {}", serial_number);
+
+    let firmware = hardware.versions.firmware.clone();
+    let device = Device::new(Box::new(SimulatedGoXLR), hardware, settings, global_events).await?;
+    finish_loading(device, &firmware, settings).await
+}
+
+// Records the profiles the device loaded as its defaults, before any profile rule replaces them
+async fn finish_loading<'a>(
+    mut device: Device<'a>,
+    firmware: &VersionNumber,
+    settings: &SettingsHandle,
+) -> Result<Device<'a>> {
+    let serial = device.serial().to_owned();
     settings
-        .set_device_profile_name(&serial_number, device.profile().name())
+        .set_device_profile_name(&serial, device.profile().name())
         .await;
     settings
-        .set_device_mic_profile_name(&serial_number, device.mic_profile().name())
+        .set_device_mic_profile_name(&serial, device.mic_profile().name())
         .await;
+
+    let today = Local::now().date_naive();
+    if let Some(rule) = find_profile_rule(settings, &serial, firmware, today).await {
+        apply_profile_rule(&mut device, rule).await;
+    }
     settings.save().await;
     Ok(device)
 }
@@ -727,3 +1832,168 @@ async fn check_firmware_versions(x: Sender<EnumMap<DeviceType, Option<VersionNum
 
     let _ = x.send(map).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use goxlr_ipc::{DeviceFeature, FirmwareUpdateState};
+    use goxlr_types::{
+        AnimationMode, ChannelName, FaderName, InputDevice, OutputDevice, SampleBank,
+    };
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::mock_device::{full, mini, settings, MockGoXLR};
+
+    const FIRMWARE: VersionNumber = VersionNumber(1, 4, Some(2), Some(107));
+    const MINI_FIRMWARE: VersionNumber = VersionNumber(1, 2, Some(0), Some(46));
+
+    #[tokio::test]
+    async fn locked_devices_refuse_every_command() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, requests) = MockGoXLR::new();
+        let device = Device::new(goxlr, mini(MINI_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        let mut devices = HashMap::from([(String::from("MINI"), device)]);
+
+        let device = devices.get_mut("MINI").unwrap();
+        device.begin_firmware_update().unwrap();
+        device.set_firmware_state(FirmwareUpdateState::Erasing);
+        requests.lock().unwrap().clear();
+
+        let command = || GoXLRCommand::SetVolume(ChannelName::Music, 100);
+        let error = run_device_command(&mut devices, "MINI", command()).await;
+        assert_eq!(
+            error.unwrap_err().downcast_ref::<CommandError>(),
+            Some(&CommandError::FirmwareUpdateInProgress)
+        );
+        assert_eq!(unlocked_devices(&mut devices).count(), 0);
+        assert!(requests.lock().unwrap().is_empty());
+
+        // Once the update's over, commands reach the device again
+        let device = devices.get_mut("MINI").unwrap();
+        device.set_firmware_state(FirmwareUpdateState::Complete);
+        run_device_command(&mut devices, "MINI", command())
+            .await
+            .unwrap();
+        assert!(!requests.lock().unwrap().is_empty());
+    }
+
+    // The paths patched in the status between two snapshots of the mixers
+    fn patched_paths(
+        before: &HashMap<String, MixerStatus>,
+        after: &HashMap<String, MixerStatus>,
+    ) -> Vec<String> {
+        let patch = diff(&json!({ "mixers": before }), &json!({ "mixers": after }));
+        serde_json::to_value(&patch)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|operation| operation["path"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn mixed_devices_only_change_their_own_status() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+
+        let mut devices = HashMap::new();
+        let (goxlr, _) = MockGoXLR::new();
+        let device = Device::new(goxlr, full(FIRMWARE), &settings, events.clone());
+        devices.insert(String::from("FULL"), device.await.unwrap());
+        let (goxlr, mini_requests) = MockGoXLR::new();
+        let device = Device::new(goxlr, mini(MINI_FIRMWARE), &settings, events);
+        devices.insert(String::from("MINI"), device.await.unwrap());
+
+        let statuses = get_mixer_statuses(&devices).await;
+        assert!(statuses["FULL"].hardware.capabilities.full_size);
+        assert!(!statuses["MINI"].hardware.capabilities.full_size);
+
+        // The same commands sent to both, as a client driving every device would
+        let commands = [
+            GoXLRCommand::SetVolume(ChannelName::Music, 100),
+            GoXLRCommand::SetFaderMuteState(FaderName::B, MuteState::MutedToAll),
+            GoXLRCommand::SetRouter(InputDevice::Music, OutputDevice::ChatMic, true),
+            GoXLRCommand::SetGlobalColour(String::from("FF0000")),
+            GoXLRCommand::SetAnimationMode(AnimationMode::RetroRainbow),
+            GoXLRCommand::SetActiveSamplerBank(SampleBank::C),
+            GoXLRCommand::SetFXEnabled(true),
+        ];
+        for command in commands {
+            for serial in ["FULL", "MINI"] {
+                let before = get_mixer_statuses(&devices).await;
+                let result = run_device_command(&mut devices, serial, command.clone()).await;
+                let after = get_mixer_statuses(&devices).await;
+
+                let description = format!("{:?} on {}", command, serial);
+                let full_size = before[serial].hardware.capabilities.full_size;
+                let refused = result.as_ref().err().and_then(|e| e.downcast_ref());
+                match refused {
+                    Some(CommandError::NotSupported(DeviceFeature::FullSize)) => {
+                        assert!(!full_size, "{}", description)
+                    }
+                    _ => assert!(result.is_ok(), "{}", description),
+                }
+
+                let prefix = format!("/mixers/{}/", serial);
+                for path in patched_paths(&before, &after) {
+                    assert!(path.starts_with(&prefix), "{}: {}", description, path);
+                }
+            }
+        }
+
+        // A change to the samples is nothing to do with the Mini
+        let sent = mini_requests.lock().unwrap().len();
+        let before = get_mixer_statuses(&devices).await;
+        notify_file_change(&mut devices, &PathTypes::Samples).await;
+        let after = get_mixer_statuses(&devices).await;
+        assert_eq!(mini_requests.lock().unwrap().len(), sent);
+        for path in patched_paths(&before, &after) {
+            assert!(path.starts_with("/mixers/FULL/"), "{}", path);
+        }
+
+        // Connected devices are never confused for each other
+        let result = run_device_command(&mut devices, "OTHER", GoXLRCommand::SetFXEnabled(true));
+        assert!(result.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn session_counts_reconnects_and_commands() {
+        let mut session = Session::new();
+        assert_eq!(session.device_connected("MINI"), 0);
+        assert_eq!(session.device_connected("FULL"), 0);
+        assert_eq!(session.device_connected("MINI"), 1);
+
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _) = MockGoXLR::new();
+        let mut device = Device::new(goxlr, mini(MINI_FIRMWARE), &settings, events)
+            .await
+            .unwrap();
+        device.set_reconnect_count(session.device_connected(device.serial()));
+        let mut devices = HashMap::from([(String::from("MINI"), device)]);
+
+        // Only the commands which were actually run are counted
+        let refused = GoXLRCommand::SetFXEnabled(true);
+        assert!(run_device_command(&mut devices, "MINI", refused)
+            .await
+            .is_err());
+        let command = GoXLRCommand::SetVolume(ChannelName::Music, 100);
+        assert!(run_device_command(&mut devices, "MINI", command)
+            .await
+            .is_ok());
+
+        let statuses = get_mixer_statuses(&devices).await;
+        let status = &statuses["MINI"].session;
+        assert_eq!(status.reconnect_count, 2);
+        assert_eq!(status.commands_processed, 1);
+        assert!(status.last_command_at.is_some());
+    }
+}
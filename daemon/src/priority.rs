@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use goxlr_ipc::{DaemonResponse, GoXLRCommand};
+use goxlr_types::ChannelName;
+use tokio::sync::{mpsc, oneshot, Notify};
+
+use crate::primary_worker::{DeviceCommand, DeviceReceiver};
+
+/*
+ * Everything sent to a device runs on the one worker, one thing at a time. Most of it is quick,
+ * but loading a profile sends a lot to the device, and a mute pressed or sent part way through
+ * shouldn't have to wait until the load is done to be heard. Commands are split into two classes:
+ * Interactive ones (mutes and volumes) are taken out of the queue as they arrive. Bulk work which
+ * takes a while stops at points where the device is in a consistent state, and runs any
+ * Interactive commands (and button presses) before carrying on.
+ *
+ * Only work which is already running can be jumped this way, an Interactive command sent after
+ * Bulk work which is still waiting in the queue runs after it, so commands take effect in the
+ * order they were sent. To tell the two apart, the queue counts the commands passed on to the
+ * worker, and those the worker has started and finished.
+ */
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Priority {
+    Interactive,
+    Bulk,
+}
+
+impl Priority {
+    pub fn of(command: &GoXLRCommand) -> Self {
+        match command {
+            GoXLRCommand::SetFaderMuteState(..)
+            | GoXLRCommand::SetCoughMuteState(_)
+            | GoXLRCommand::SetVolume(..)
+            | GoXLRCommand::SetSubMixVolume(..) => Priority::Interactive,
+            _ => Priority::Bulk,
+        }
+    }
+}
+
+/// A command from a client, for one of the devices.
+pub struct ClientCommand {
+    pub serial: String,
+    pub command: GoXLRCommand,
+    pub client_id: Option<u64>,
+    pub sender: oneshot::Sender<Result<Option<DaemonResponse>>>,
+}
+
+/// An Interactive command which was run part way through some Bulk work, kept so the worker can
+/// let anyone with a claim on the channels know once the Bulk work is done.
+pub struct PreemptedCommand {
+    pub command: GoXLRCommand,
+    pub client_id: Option<u64>,
+    pub channels: Vec<ChannelName>,
+}
+
+struct QueuedCommand {
+    command: ClientCommand,
+
+    // How many commands had been passed to the worker when this arrived, and whether the worker
+    // had started all of them (so nothing was waiting, and the last may still be running)
+    after: u64,
+    preempts: bool,
+}
+
+#[derive(Default)]
+struct QueueState {
+    commands: VecDeque<QueuedCommand>,
+    forwarded: u64,
+    started: u64,
+    finished: u64,
+}
+
+impl QueueState {
+    // Whether everything sent before this command has at least started
+    fn is_ready(&self, queued: &QueuedCommand) -> bool {
+        queued.preempts || queued.after <= self.finished
+    }
+
+    // Takes the ready commands from the front of the queue, once one has to wait everything
+    // after it waits too, so Interactive commands stay in the order they were sent
+    fn take(&mut self, filter: impl Fn(&ClientCommand) -> bool) -> Vec<ClientCommand> {
+        let mut taken = vec![];
+        let mut remaining = VecDeque::new();
+        let mut waiting = false;
+        while let Some(queued) = self.commands.pop_front() {
+            waiting |= !self.is_ready(&queued);
+            if !waiting && filter(&queued.command) {
+                taken.push(queued.command);
+            } else {
+                remaining.push_back(queued);
+            }
+        }
+        self.commands = remaining;
+        taken
+    }
+}
+
+/// The Interactive commands which have arrived, but not yet been run.
+#[derive(Clone, Default)]
+pub struct InteractiveQueue {
+    state: Arc<Mutex<QueueState>>,
+    arrived: Arc<Notify>,
+}
+
+impl InteractiveQueue {
+    fn push(&self, command: ClientCommand) {
+        let mut state = self.state.lock().unwrap();
+        let queued = QueuedCommand {
+            command,
+            after: state.forwarded,
+            preempts: state.started == state.forwarded,
+        };
+        state.commands.push_back(queued);
+        self.arrived.notify_one();
+    }
+
+    fn forwarded(&self) {
+        self.state.lock().unwrap().forwarded += 1;
+    }
+
+    /// Called by the worker as it takes a command from its queue.
+    pub fn started(&self) {
+        self.state.lock().unwrap().started += 1;
+    }
+
+    /// Called by the worker once it's done with a command from its queue, anything which was
+    /// waiting for it is then ready to run.
+    pub fn finished(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.finished += 1;
+        if state
+            .commands
+            .front()
+            .is_some_and(|queued| state.is_ready(queued))
+        {
+            self.arrived.notify_one();
+        }
+    }
+
+    /// Waits until a command may be ready to run.
+    pub async fn arrived(&self) {
+        self.arrived.notified().await;
+    }
+
+    pub fn take_all(&self) -> Vec<ClientCommand> {
+        self.state.lock().unwrap().take(|_| true)
+    }
+
+    pub fn take_for(&self, serial: &str) -> Vec<ClientCommand> {
+        self.state
+            .lock()
+            .unwrap()
+            .take(|command| command.serial == serial)
+    }
+
+    pub fn count(&self) -> usize {
+        self.state.lock().unwrap().commands.len()
+    }
+}
+
+/// Takes the Interactive commands out of the worker's queue as they're sent, everything else
+/// comes through the returned receiver in the order it was sent.
+pub fn split_interactive(mut receiver: DeviceReceiver) -> (DeviceReceiver, InteractiveQueue) {
+    let queue = InteractiveQueue::default();
+    let (sender, bulk) = mpsc::channel(receiver.max_capacity());
+
+    let interactive = queue.clone();
+    tokio::spawn(async move {
+        while let Some(command) = receiver.recv().await {
+            match command {
+                DeviceCommand::RunDeviceCommand(serial, command, client_id, reply)
+                    if Priority::of(&command) == Priority::Interactive =>
+                {
+                    interactive.push(ClientCommand {
+                        serial,
+                        command,
+                        client_id,
+                        sender: reply,
+                    });
+                }
+                command => {
+                    interactive.forwarded();
+                    if sender.send(command).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    (bulk, queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goxlr_types::{FaderName, MuteState};
+
+    fn run(serial: &str, command: GoXLRCommand) -> DeviceCommand {
+        let (sender, _) = oneshot::channel();
+        DeviceCommand::RunDeviceCommand(serial.into(), command, None, sender)
+    }
+
+    async fn wait_for(interactive: &InteractiveQueue, count: usize) {
+        while interactive.count() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn interactive_commands_skip_running_work() {
+        let (sender, receiver) = mpsc::channel(8);
+        let (mut bulk, interactive) = split_interactive(receiver);
+
+        // The reload is running by the time the mute and volume are sent
+        sender
+            .send(run("S1", GoXLRCommand::ReloadSettings()))
+            .await
+            .unwrap();
+        let received = bulk.recv().await.unwrap();
+        assert!(matches!(
+            received,
+            DeviceCommand::RunDeviceCommand(_, GoXLRCommand::ReloadSettings(), ..)
+        ));
+        interactive.started();
+
+        let mute = GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll);
+        let volume = GoXLRCommand::SetVolume(ChannelName::Music, 10);
+        sender.send(run("S1", mute)).await.unwrap();
+        sender.send(run("S2", volume)).await.unwrap();
+        interactive.arrived().await;
+        wait_for(&interactive, 2).await;
+
+        let taken = interactive.take_for("S2");
+        assert_eq!(taken.len(), 1);
+        assert!(matches!(
+            taken[0].command,
+            GoXLRCommand::SetVolume(ChannelName::Music, 10)
+        ));
+        assert_eq!(interactive.count(), 1);
+        assert_eq!(interactive.take_all()[0].serial, "S1");
+    }
+
+    #[tokio::test]
+    async fn send_order_is_kept_for_waiting_work() {
+        let (sender, receiver) = mpsc::channel(8);
+        let (mut bulk, interactive) = split_interactive(receiver);
+
+        // Both are sent before the worker gets to the reload
+        let mute = GoXLRCommand::SetFaderMuteState(FaderName::A, MuteState::MutedToAll);
+        sender
+            .send(run("S1", GoXLRCommand::ReloadSettings()))
+            .await
+            .unwrap();
+        sender.send(run("S1", mute)).await.unwrap();
+        wait_for(&interactive, 1).await;
+        assert!(interactive.take_all().is_empty());
+
+        // It can't jump the reload, even once that's running
+        bulk.recv().await.unwrap();
+        interactive.started();
+        assert!(interactive.take_for("S1").is_empty());
+
+        // A volume sent now could, but not ahead of the mute
+        let volume = GoXLRCommand::SetVolume(ChannelName::Music, 10);
+        sender.send(run("S1", volume)).await.unwrap();
+        wait_for(&interactive, 2).await;
+        assert!(interactive.take_for("S1").is_empty());
+
+        interactive.finished();
+        interactive.arrived().await;
+        let taken = interactive.take_all();
+        assert!(matches!(
+            taken.as_slice(),
+            [
+                ClientCommand {
+                    command: GoXLRCommand::SetFaderMuteState(..),
+                    ..
+                },
+                ClientCommand {
+                    command: GoXLRCommand::SetVolume(..),
+                    ..
+                },
+            ]
+        ));
+    }
+}
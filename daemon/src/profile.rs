@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::default::Default;
-use std::fs::{remove_file, File};
+use std::fs::{remove_file, rename, File};
 use std::io::{Cursor, Read, Seek};
 use std::path::{Path, PathBuf};
 
@@ -11,16 +11,19 @@ use log::{debug, warn};
 use strum::IntoEnumIterator;
 
 use goxlr_ipc::{
-    ActiveEffects, AnimationLighting, ButtonLighting, CoughButton, Echo, Effects, FaderLighting,
-    Gender, HardTune, Lighting, Megaphone, OneColour, Pitch, Reverb, Robot, Sample,
-    SampleProcessState, Sampler, SamplerButton, SamplerLighting, Scribble, Submix, Submixes,
-    ThreeColours, TwoColours,
+    ActiveEffects, AnimationLighting, ButtonLighting, CommandError, CoughButton, DuckConfig, Echo,
+    Effects, EffectsIntensity, EncoderState, FaderLighting, Gender, HardTune, Lighting, Megaphone,
+    OneColour, Pitch, ProfileInheritance, ReplayCapture, Reverb, Robot, Sample, SampleCacheStatus,
+    SampleMarker, SampleProcessState, Sampler, SamplerButton, SamplerLighting, Scribble,
+    ScribbleNumberMode, Submix, Submixes, ThreeColours, TwoColours,
 };
 use goxlr_profile_loader::components::animation::{AnimationMode, WaterfallDirection};
+use goxlr_profile_loader::components::base_profile::BaseProfile;
 use goxlr_profile_loader::components::colours::{
     Colour, ColourDisplay, ColourMap, ColourOffStyle, ColourState,
 };
 use goxlr_profile_loader::components::echo::{EchoEncoder, EchoStyle};
+use goxlr_profile_loader::components::effects::EffectsIntensity as ProfileEffectsIntensity;
 use goxlr_profile_loader::components::gender::{GenderEncoder, GenderStyle};
 use goxlr_profile_loader::components::hardtune::{HardTuneEffect, HardTuneSource, HardTuneStyle};
 use goxlr_profile_loader::components::megaphone::{MegaphoneEffect, MegaphoneStyle};
@@ -30,48 +33,221 @@ use goxlr_profile_loader::components::mute_chat::{CoughToggle, MuteChat};
 use goxlr_profile_loader::components::pitch::{PitchEncoder, PitchStyle};
 use goxlr_profile_loader::components::reverb::{ReverbEncoder, ReverbStyle};
 use goxlr_profile_loader::components::robot::{RobotEffect, RobotStyle};
-use goxlr_profile_loader::components::sample::{PlayOrder, PlaybackMode, SampleBank, Track};
+use goxlr_profile_loader::components::sample::{
+    PlayOrder, PlaybackMode, SampleBank, SampleDucking, Track,
+};
+use goxlr_profile_loader::components::scribble::ScribbleNumberMode as ProfileNumberMode;
 use goxlr_profile_loader::components::simple::SimpleElements;
 use goxlr_profile_loader::components::submix::mix_routing_tree::Mix;
-use goxlr_profile_loader::profile::{Profile, ProfileSettings};
+use goxlr_profile_loader::components::volume_scenes::VolumeScene;
+use goxlr_profile_loader::microphone::equalizer::validate_gain;
+use goxlr_profile_loader::profile::{Profile, ProfileSettings, SectionSnapshot};
 use goxlr_profile_loader::SampleButtons::{BottomLeft, BottomRight, Clear, TopLeft, TopRight};
-use goxlr_profile_loader::{Faders, Preset, SampleButtons};
-use goxlr_scribbles::get_scribble;
+use goxlr_profile_loader::{BaseSection, Faders, Preset, PresetSection, SampleButtons};
+use goxlr_scribbles::{get_scribble, ScribbleIcon};
 use goxlr_types::{
     Button, ButtonColourGroups, ButtonColourOffStyle as BasicColourOffStyle, ChannelName,
-    EffectBankPresets, EncoderColourTargets, EncoderName, FaderDisplayStyle as BasicColourDisplay,
-    FaderDisplayStyle, FaderName, InputDevice, MuteFunction as BasicMuteFunction, MuteState,
-    OutputDevice, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets,
-    SubMixChannelName, VersionNumber,
+    EffectBankPresets, EffectSection, EncoderColourTargets, EncoderName, EqFrequencies,
+    FaderDisplayStyle as BasicColourDisplay, FaderDisplayStyle, FaderName, InputDevice,
+    MuteFunction as BasicMuteFunction, MuteState, OutputDevice, ProfileSection, SamplePlayOrder,
+    SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets, SubMixChannelName,
+    VersionNumber,
 };
 use goxlr_usb::buttonstate::{ButtonStates, Buttons};
 use goxlr_usb::channelstate::ChannelState;
 use goxlr_usb::colouring::ColourTargets;
 
 use crate::audio::{AudioFile, AudioHandler};
-use crate::device::CurrentState;
-use crate::files::can_create_new_file;
+use crate::device::{CurrentState, ProfileAnimation};
+use crate::disk::{write_atomic, DiskFileSystem, FileSystem};
+use crate::files::{
+    can_create_new_file, check_existing_name, find_name_problem, get_effect_section_extension,
+    is_file_listed,
+};
 
 pub const DEFAULT_PROFILE_NAME: &str = "Default";
 const DEFAULT_PROFILE: &[u8] = include_bytes!("../profiles/Default.goxlr");
 
+// The range of frequencies (in Hz) accepted for an input's Low Cut
+const LOW_CUT_MIN: u8 = 20;
+const LOW_CUT_MAX: u8 = 250;
+
+// How far an input's balance can be moved to either side, at the limit the other side is silent
+pub const BALANCE_LIMIT: i8 = 50;
+
+// The shortest part of a sample which can be selected for playback
+const MIN_SAMPLE_LENGTH_MS: u64 = 50;
+
 #[derive(Debug)]
 pub struct ProfileAdapter {
     name: String,
     profile: Profile,
+
+    // Only present when the profile is based on another, along with the sections taken from the
+    // base as they were loaded (which are checked when saving, to see which are now our own).
+    inheritance: Option<Inheritance>,
+    inherited: EnumMap<BaseSection, Option<SectionSnapshot>>,
+
+    // Set by anything which changes the profile, cleared when it's written to disk.
+    dirty: bool,
+}
+
+/// Where each section of a profile with a base came from. The file of a child profile only holds
+/// the sections it owns, the rest are filled in from the base each time it's loaded.
+#[derive(Debug)]
+struct Inheritance {
+    chain: Vec<String>,
+    sources: EnumMap<BaseSection, String>,
+}
+
+impl Inheritance {
+    fn new(name: &str, base: &BaseProfile, inherited: Option<Inheritance>) -> Self {
+        let mut chain = vec![base.name().to_string()];
+        let mut sources = EnumMap::from_fn(|_| base.name().to_string());
+        if let Some(inherited) = inherited {
+            chain.extend(inherited.chain);
+            sources = inherited.sources;
+        }
+
+        for section in BaseSection::iter() {
+            if base.owns(section) {
+                sources[section] = name.to_string();
+            }
+        }
+        Self { chain, sources }
+    }
 }
 
 impl ProfileAdapter {
     pub fn from_named(name: String, directory: &Path) -> Result<Self> {
-        let path = directory.join(format!("{}.goxlr", name));
+        let profile = load_profile(&name, directory)?;
+        let mut adapter = Self {
+            name,
+            profile,
+            inheritance: None,
+            inherited: EnumMap::default(),
+            dirty: false,
+        };
 
-        if path.is_file() {
-            debug!("Loading Profile From {}", path.to_string_lossy());
-            let file = File::open(path).context("Couldn't open profile for reading")?;
-            return ProfileAdapter::from_reader(name, file);
+        if let Some(base) = adapter.profile.settings().base_profile().cloned() {
+            adapter.apply_base(&base, directory);
         }
+        adapter.sync_mic_fader();
+        Ok(adapter)
+    }
+
+    /// Fills in the sections this profile doesn't own from its base, if the base can't be loaded
+    /// they're left at their defaults.
+    fn apply_base(&mut self, base: &BaseProfile, directory: &Path) {
+        let mut chain = vec![self.name.clone()];
+        match load_composed(base.name(), directory, &mut chain) {
+            Ok((mut parts, inherited)) => {
+                compose(&mut self.profile, base, &mut parts);
+                self.inheritance = Some(Inheritance::new(&self.name, base, inherited));
+            }
+            Err(e) => {
+                warn!(
+                    "Unable to load the base of {}, its other sections are defaults: {}",
+                    self.name, e
+                );
+                let mut inheritance = Inheritance::new(&self.name, base, None);
+                inheritance.sources = EnumMap::from_fn(|_| self.name.clone());
+                self.inheritance = Some(inheritance);
+            }
+        }
+        self.snapshot_inherited();
+    }
 
-        bail!("Profile {} does not exist inside {:?}", name, directory);
+    // Keeps the sections which came from the base as they are now, so changes can be found
+    fn snapshot_inherited(&mut self) {
+        self.inherited = EnumMap::default();
+        let base = match self.profile.settings().base_profile() {
+            Some(base) => base.clone(),
+            None => return,
+        };
+
+        for section in BaseSection::iter().filter(|section| !base.owns(*section)) {
+            match self.profile.snapshot_section(section) {
+                Ok(snapshot) => self.inherited[section] = Some(snapshot),
+                Err(e) => warn!("Unable to check {} for changes: {}", section, e),
+            }
+        }
+    }
+
+    /// Makes this profile a child of another, it keeps the sections which differ from the new
+    /// base and follows it for the rest, so the current settings don't change.
+    pub fn rebase(&mut self, base_name: String, directory: &Path) -> Result<()> {
+        if base_name == self.name {
+            bail!("A profile can't be based on itself");
+        }
+
+        let mut chain = vec![self.name.clone()];
+        let (mut base_profile, inherited) = load_composed(&base_name, directory, &mut chain)
+            .with_context(|| format!("Unable to use {} as a base", base_name))?;
+
+        let mut base = BaseProfile::new(base_name, vec![]);
+        for section in BaseSection::iter() {
+            if !self.profile.section_matches(section, &mut base_profile)? {
+                base.set_owned(section, true);
+            }
+        }
+
+        self.inheritance = Some(Inheritance::new(&self.name, &base, inherited));
+        self.profile.settings_mut().set_base_profile(Some(base));
+        self.snapshot_inherited();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Detaches the profile from its base, it keeps everything it currently has.
+    pub fn clear_base(&mut self) -> Result<()> {
+        if self.inheritance.is_none() {
+            bail!("Profile {} doesn't have a base", self.name);
+        }
+
+        self.profile.settings_mut().set_base_profile(None);
+        self.inheritance = None;
+        self.inherited = EnumMap::default();
+        self.dirty = true;
+        Ok(())
+    }
+
+    // Sections which have been changed since the profile was loaded now belong to it
+    fn claim_changed_sections(&mut self) -> Result<()> {
+        let inheritance = match &mut self.inheritance {
+            Some(inheritance) => inheritance,
+            None => return Ok(()),
+        };
+        let mut base = match self.profile.settings().base_profile() {
+            Some(base) => base.clone(),
+            None => return Ok(()),
+        };
+
+        for section in BaseSection::iter() {
+            let changed = match &self.inherited[section] {
+                Some(loaded) => self.profile.snapshot_section(section)? != *loaded,
+                None => false,
+            };
+            if !base.owns(section) && changed {
+                debug!("{} has changed, it now belongs to {}", section, self.name);
+                base.set_owned(section, true);
+                inheritance.sources[section] = self.name.clone();
+                self.inherited[section] = None;
+            }
+        }
+        self.profile.settings_mut().set_base_profile(Some(base));
+        Ok(())
+    }
+
+    pub fn get_inheritance(&self) -> Option<ProfileInheritance> {
+        self.inheritance
+            .as_ref()
+            .map(|inheritance| ProfileInheritance {
+                chain: inheritance.chain.clone(),
+                sources: EnumMap::from_fn(|section| {
+                    inheritance.sources[standard_to_base_section(section)].clone()
+                }),
+            })
     }
 
     pub fn default() -> Self {
@@ -84,7 +260,15 @@ impl ProfileAdapter {
 
     pub fn from_reader<R: Read + Seek>(name: String, reader: R) -> Result<Self> {
         let profile = Profile::load(reader)?;
-        Ok(Self { name, profile })
+        let mut adapter = Self {
+            name,
+            profile,
+            inheritance: None,
+            inherited: EnumMap::default(),
+            dirty: false,
+        };
+        adapter.sync_mic_fader();
+        Ok(adapter)
     }
 
     pub fn can_create_new_file(name: String, directory: &Path) -> Result<()> {
@@ -93,28 +277,122 @@ impl ProfileAdapter {
     }
 
     pub fn save_as(&mut self, name: String, directory: &Path, overwrite: bool) -> Result<()> {
+        if let Some(inheritance) = &mut self.inheritance {
+            if inheritance.chain.contains(&name) {
+                // Saving over one of our bases would make the profile its own base
+                warn!("{} is a base of {}, saving without a base", name, self.name);
+                self.profile.settings_mut().set_base_profile(None);
+                self.inheritance = None;
+                self.inherited = EnumMap::default();
+            } else {
+                for source in inheritance.sources.values_mut() {
+                    if *source == self.name {
+                        source.clone_from(&name);
+                    }
+                }
+            }
+        }
+
         self.name = name;
         self.save(directory, overwrite)
     }
 
     pub fn save(&mut self, directory: &Path, overwrite: bool) -> Result<()> {
+        self.save_with(&DiskFileSystem, directory, overwrite)
+    }
+
+    pub fn save_with(
+        &mut self,
+        file_system: &dyn FileSystem,
+        directory: &Path,
+        overwrite: bool,
+    ) -> Result<()> {
         let name = &self.name;
         let path = directory.join(format!("{name}.goxlr"));
         if !overwrite && path.is_file() {
             return Err(anyhow!("Profile exists, will not overwrite"));
         }
 
-        self.profile.save(path)?;
+        self.claim_changed_sections()?;
+        let mut contents = Cursor::new(Vec::new());
+        self.profile.write_to(&mut contents)?;
+        write_atomic(file_system, &path, contents.get_ref())?;
+        self.dirty = false;
         Ok(())
     }
 
+    pub fn export_official(&mut self, path: &Path) -> Result<()> {
+        self.profile.export_official(path)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
     pub fn write_preset(&mut self, name: String, directory: &Path) -> Result<()> {
+        // The name comes from the effect bank, which may have been loaded with any name
+        check_existing_name(&name, "preset", directory)?;
         let path = directory.join(format!("{name}.preset"));
         self.profile.save_preset(path)?;
         Ok(())
     }
 
+    pub fn write_effect_section(
+        &self,
+        section: EffectSection,
+        name: String,
+        directory: &Path,
+    ) -> Result<()> {
+        // The name comes from a client, so it's refused rather than cleaned up, anything which
+        // could lead outside the presets directory (separators or '..') is a problem
+        if let Some(problem) = find_name_problem(&name) {
+            return Err(CommandError::InvalidName(problem).into());
+        }
+        let extension = get_effect_section_extension(section);
+        let path = directory.join(format!("{name}.{extension}"));
+        self.profile.settings().write_preset_section(
+            path,
+            standard_to_profile_section(section),
+            name,
+        )
+    }
+
+    pub fn load_effect_section(
+        &mut self,
+        section: EffectSection,
+        name: String,
+        directory: &Path,
+    ) -> Result<()> {
+        if let Some(problem) = find_name_problem(&name) {
+            return Err(CommandError::InvalidName(problem).into());
+        }
+
+        self.dirty = true;
+        let extension = get_effect_section_extension(section);
+        let path = directory.join(format!("{name}.{extension}"));
+        if !path.is_file() {
+            bail!(
+                "{} Preset {} does not exist inside {:?}",
+                section,
+                name,
+                directory
+            );
+        }
+
+        debug!("Loading {} Preset From {}", section, path.to_string_lossy());
+        let file = File::open(path).context("Couldn't open preset for reading")?;
+        self.profile
+            .settings_mut()
+            .load_preset_section(file, standard_to_profile_section(section))?;
+        self.restore_shared_encoder_values()
+    }
+
     pub fn delete_profile(&mut self, name: String, directory: &Path) -> Result<()> {
+        check_existing_name(&name, "goxlr", directory)?;
         let path = directory.join(format!("{name}.goxlr"));
         if path.is_file() {
             remove_file(path)?;
@@ -122,11 +400,52 @@ impl ProfileAdapter {
         Ok(())
     }
 
+    /// Whether a profile with this name is in the directory. Names from clients are looked up
+    /// rather than joined to the directory, so they can't refer to a file anywhere else.
+    pub fn is_listed(name: &str, directory: &Path) -> bool {
+        is_file_listed(&format!("{name}.goxlr"), directory)
+    }
+
+    /// Renames a profile's file, if it's present in this directory.
+    pub fn rename_profile(name: &str, new_name: &str, directory: &Path) -> Result<()> {
+        let path = directory.join(format!("{name}.goxlr"));
+        if Self::is_listed(name, directory) {
+            can_create_new_file(directory.join(format!("{new_name}.goxlr")))?;
+            rename(path, directory.join(format!("{new_name}.goxlr")))?;
+        }
+        Ok(())
+    }
+
+    /// Follows a rename of the loaded profile's file, without saving anything.
+    pub fn set_name(&mut self, name: String) {
+        if let Some(inheritance) = &mut self.inheritance {
+            for source in inheritance.sources.values_mut() {
+                if *source == self.name {
+                    source.clone_from(&name);
+                }
+            }
+        }
+        self.name = name;
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    pub fn is_from_newer_version(&self) -> bool {
+        self.profile.settings().is_from_newer_version()
+    }
+
+    pub fn get_version(&self) -> u8 {
+        self.profile.settings().get_version()
+    }
+
+    pub fn get_utility_version(&self) -> Option<&str> {
+        self.profile.settings().get_utility_version()
+    }
+
     pub fn load_colour_profile(&mut self, new_profile: ProfileAdapter) {
+        self.dirty = true;
         for colour in ColourTargets::iter() {
             let our_map = get_profile_colour_map_mut(self.profile.settings_mut(), colour);
             let new_map = get_profile_colour_map(new_profile.profile.settings(), colour);
@@ -143,10 +462,12 @@ impl ProfileAdapter {
     }
 
     pub fn load_preset(&mut self, name: String, directories: Vec<&Path>) -> Result<()> {
+        self.dirty = true;
         let mut dir_list = "".to_string();
 
         // Loop through the provided directories, and try to find the preset..
         for directory in directories {
+            check_existing_name(&name, "preset", directory)?;
             let path = directory.join(format!("{name}.preset"));
 
             if path.is_file() {
@@ -154,6 +475,10 @@ impl ProfileAdapter {
                 let file = File::open(path).context("Couldn't open preset for reading")?;
 
                 self.profile.settings_mut().load_preset(file)?;
+                self.restore_shared_encoder_values()?;
+
+                // The preset's amounts are its own, so they start at full intensity
+                self.set_stored_effects_intensity(None);
                 return Ok(());
             }
             dir_list = format!("{}, {}", dir_list, directory.to_string_lossy());
@@ -169,9 +494,11 @@ impl ProfileAdapter {
     pub(crate) fn get_current_state(&self) -> CurrentState {
         let mut faders = EnumMap::default();
         let mut mute_state = EnumMap::default();
+        let mut fader_display = EnumMap::default();
 
         for fader in FaderName::iter() {
             faders[fader] = self.get_fader_assignment(fader);
+            fader_display[fader] = (self.is_fader_gradient(fader), self.is_fader_meter(fader));
         }
 
         for channel in ChannelName::iter() {
@@ -182,10 +509,13 @@ impl ProfileAdapter {
             faders,
             mute_state,
             volumes: self.get_channel_volume_map(),
+            fader_display,
+            animation: Some(self.get_animation()),
+            ..Default::default()
         }
     }
 
-    fn get_channel_mute_state(&self, channel: ChannelName) -> ChannelState {
+    pub fn get_channel_mute_state(&self, channel: ChannelName) -> ChannelState {
         // Is this assigned to a fader?
         if let Some(fader) = self.get_fader_from_channel(channel) {
             return self.get_fader_mute_state(fader);
@@ -249,6 +579,7 @@ impl ProfileAdapter {
         output: OutputDevice,
         enabled: bool,
     ) -> Result<()> {
+        self.dirty = true;
         let input_channel = standard_input_to_profile(input);
         let output_channel = standard_output_to_profile(output);
         let monitoring = self.get_monitoring_mix();
@@ -256,8 +587,8 @@ impl ProfileAdapter {
         let value = if enabled { 8192 } else { 0 };
 
         // Before we do anything before we do anything, make sure it's valid..
-        if input == InputDevice::Chat && output == OutputDevice::ChatMic {
-            bail!("Invalid Route: Chat -> Chat Mic");
+        if !input.can_route_to(output) {
+            bail!("Invalid Route: {} -> {}", input, output);
         }
 
         // Before we do anything, are we changing Headphones while they're not the active Monitor?
@@ -287,6 +618,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_monitor_routing(&mut self, input: InputDevice, output: OutputDevice, enabled: bool) {
+        self.dirty = true;
         // This is similar to above, except we don't do the monitor checks as we need to force the
         // routing settings.
         let input_channel = standard_input_to_profile(input);
@@ -297,6 +629,89 @@ impl ProfileAdapter {
         table[input_channel][output_channel] = value;
     }
 
+    pub fn get_input_low_cut(&self, input: InputDevice) -> Option<u8> {
+        let input_channel = standard_input_to_profile(input);
+        self.profile.settings().mixer().low_cut(input_channel)
+    }
+
+    pub fn set_input_low_cut(&mut self, input: InputDevice, frequency: Option<u8>) -> Result<()> {
+        if !matches!(input, InputDevice::LineIn | InputDevice::Console) {
+            bail!("Low Cut is only available on the Line In and Console inputs");
+        }
+        if let Some(frequency) = frequency {
+            if !(LOW_CUT_MIN..=LOW_CUT_MAX).contains(&frequency) {
+                bail!(
+                    "Low Cut must be between {}Hz and {}Hz",
+                    LOW_CUT_MIN,
+                    LOW_CUT_MAX
+                );
+            }
+        }
+
+        self.dirty = true;
+        let input_channel = standard_input_to_profile(input);
+        self.profile
+            .settings_mut()
+            .mixer_mut()
+            .set_low_cut(input_channel, frequency);
+        Ok(())
+    }
+
+    pub fn get_input_balance(&self, input: InputDevice) -> i8 {
+        let input_channel = standard_input_to_profile(input);
+        self.profile
+            .settings()
+            .mixer()
+            .balance(input_channel)
+            .unwrap_or(0)
+    }
+
+    pub fn set_input_balance(&mut self, input: InputDevice, balance: i8) -> Result<()> {
+        if !(-BALANCE_LIMIT..=BALANCE_LIMIT).contains(&balance) {
+            bail!(
+                "Balance must be between -{} and {}",
+                BALANCE_LIMIT,
+                BALANCE_LIMIT
+            );
+        }
+
+        // A centred input is left out of the profile
+        self.dirty = true;
+        let input_channel = standard_input_to_profile(input);
+        self.profile
+            .settings_mut()
+            .mixer_mut()
+            .set_balance(input_channel, (balance != 0).then_some(balance));
+        Ok(())
+    }
+
+    pub fn get_headphone_eq_gain(&self, frequency: EqFrequencies) -> i8 {
+        let mixer = self.profile.settings().mixer();
+        mixer.headphone_eq_gain(frequency as usize).unwrap_or(0)
+    }
+
+    pub fn set_headphone_eq_gain(&mut self, frequency: EqFrequencies, gain: i8) -> Result<()> {
+        validate_gain(gain)?;
+
+        // A flat band is left out of the profile
+        self.dirty = true;
+        self.profile
+            .settings_mut()
+            .mixer_mut()
+            .set_headphone_eq_gain(frequency as usize, (gain != 0).then_some(gain));
+        Ok(())
+    }
+
+    /// The Sampler records a single mix of everything routed to it, so the highest Low Cut of
+    /// the routed inputs is used for the whole recording.
+    pub fn get_sampler_low_cut(&self) -> Option<u8> {
+        [InputDevice::LineIn, InputDevice::Console]
+            .into_iter()
+            .filter(|input| self.get_router(*input)[OutputDevice::Sampler])
+            .filter_map(|input| self.get_input_low_cut(input))
+            .max()
+    }
+
     pub fn get_fader_assignment(&self, fader: FaderName) -> ChannelName {
         let fader = self
             .profile
@@ -306,13 +721,16 @@ impl ProfileAdapter {
     }
 
     pub fn set_fader_assignment(&mut self, fader: FaderName, channel: ChannelName) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .fader_mut(standard_to_profile_fader(fader))
             .set_channel(standard_to_profile_channel(channel));
+        self.sync_mic_fader();
     }
 
     pub fn switch_fader_assignment(&mut self, fader_one: FaderName, fader_two: FaderName) {
+        self.dirty = true;
         let profile_fader_one = standard_to_profile_fader(fader_one);
         let profile_fader_two = standard_to_profile_fader(fader_two);
 
@@ -330,10 +748,12 @@ impl ProfileAdapter {
             .settings_mut()
             .mute_buttons()
             .swap(profile_fader_one, profile_fader_two);
+        self.sync_mic_fader();
     }
 
     // Animation Settings
     pub fn set_animation_mode(&mut self, mode: goxlr_types::AnimationMode) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .animation_mut()
@@ -345,14 +765,25 @@ impl ProfileAdapter {
     }
 
     pub fn set_animation_mod1(&mut self, mod1: u8) -> Result<()> {
+        self.dirty = true;
         self.profile.settings_mut().animation_mut().set_mod1(mod1)
     }
 
+    pub(crate) fn get_animation(&self) -> ProfileAnimation {
+        (
+            self.get_animation_mode(),
+            self.get_animation_mod1(),
+            self.get_animation_mod2(),
+            self.get_animation_waterfall(),
+        )
+    }
+
     pub fn get_animation_mod1(&self) -> u8 {
         self.profile.settings().animation().mod1()
     }
 
     pub fn set_animation_mod2(&mut self, mod2: u8) -> Result<()> {
+        self.dirty = true;
         self.profile.settings_mut().animation_mut().set_mod2(mod2)
     }
 
@@ -364,6 +795,7 @@ impl ProfileAdapter {
         &mut self,
         waterfall: goxlr_types::WaterfallDirection,
     ) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .animation_mut()
@@ -375,6 +807,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_fader_display(&mut self, fader: FaderName, display: BasicColourDisplay) {
+        self.dirty = true;
         let colours = self
             .profile
             .settings_mut()
@@ -390,6 +823,7 @@ impl ProfileAdapter {
         top: String,
         bottom: String,
     ) -> Result<()> {
+        self.dirty = true;
         let colours = self
             .profile
             .settings_mut()
@@ -400,35 +834,71 @@ impl ProfileAdapter {
         Ok(())
     }
 
-    pub fn get_scribble_image(&self, fader: FaderName, path: &Path) -> [u8; 1024] {
+    /// Renders the scribble, with 'number' in the top left (as it may not be the static text).
+    pub fn get_scribble_image(
+        &self,
+        fader: FaderName,
+        path: &Path,
+        number: Option<String>,
+    ) -> Result<[u8; 1024]> {
         let scribble = self
             .profile
             .settings()
             .scribble(standard_to_profile_fader(fader));
 
-        let mut icon_path = None;
-        if let Some(file) = scribble.icon_file() {
-            icon_path = Some(path.join(file));
-        }
+        let icon = match (scribble.icon_glyph(), scribble.icon_file()) {
+            (Some(glyph), _) => Some(ScribbleIcon::Glyph(glyph)),
+            (None, Some(file)) => Some(ScribbleIcon::File(path.join(file))),
+            (None, None) => None,
+        };
 
         get_scribble(
-            icon_path,
+            icon,
             scribble.text_bottom_middle(),
-            scribble.text_top_left(),
+            number,
             scribble.is_style_invert(),
         )
     }
 
+    pub fn get_faders_using_icon(&self, icon: &str) -> Vec<FaderName> {
+        FaderName::iter()
+            .filter(|fader| {
+                let scribble = self
+                    .profile
+                    .settings()
+                    .scribble(standard_to_profile_fader(*fader));
+                scribble.icon_file().as_deref() == Some(icon)
+            })
+            .collect()
+    }
+
     pub fn set_scribble_icon(&mut self, fader: FaderName, icon: Option<String>) {
+        self.dirty = true;
         let scribble = self
             .profile
             .settings_mut()
             .scribble_mut(standard_to_profile_fader(fader));
 
+        // An icon file replaces any glyph, removing the icon removes both
+        scribble.set_icon_glyph(None);
         scribble.set_icon_file(icon);
     }
 
+    pub fn set_scribble_glyph(&mut self, fader: FaderName, glyph: Option<String>) {
+        self.dirty = true;
+        let scribble = self
+            .profile
+            .settings_mut()
+            .scribble_mut(standard_to_profile_fader(fader));
+
+        if glyph.is_some() {
+            scribble.set_icon_file(None);
+        }
+        scribble.set_icon_glyph(glyph);
+    }
+
     pub fn set_scribble_text(&mut self, fader: FaderName, text: String) {
+        self.dirty = true;
         let scribble = self
             .profile
             .settings_mut()
@@ -438,15 +908,53 @@ impl ProfileAdapter {
     }
 
     pub fn set_scribble_number(&mut self, fader: FaderName, text: String) {
+        self.dirty = true;
         let scribble = self
             .profile
             .settings_mut()
             .scribble_mut(standard_to_profile_fader(fader));
 
         scribble.set_text_top_left(text);
+        scribble.set_number_mode(ProfileNumberMode::Static);
+    }
+
+    pub fn get_scribble_number_mode(&self, fader: FaderName) -> ScribbleNumberMode {
+        let scribble = self
+            .profile
+            .settings()
+            .scribble(standard_to_profile_fader(fader));
+
+        match scribble.number_mode() {
+            ProfileNumberMode::Static => {
+                ScribbleNumberMode::Static(scribble.text_top_left().unwrap_or_default())
+            }
+            ProfileNumberMode::VolumePercent => ScribbleNumberMode::VolumePercent,
+            ProfileNumberMode::VolumeDb => ScribbleNumberMode::VolumeDb,
+            ProfileNumberMode::ActivePreset => ScribbleNumberMode::ActivePreset,
+        }
+    }
+
+    pub fn set_scribble_number_mode(&mut self, fader: FaderName, mode: ScribbleNumberMode) {
+        self.dirty = true;
+        let scribble = self
+            .profile
+            .settings_mut()
+            .scribble_mut(standard_to_profile_fader(fader));
+
+        let number_mode = match mode {
+            ScribbleNumberMode::Static(text) => {
+                scribble.set_text_top_left(text);
+                ProfileNumberMode::Static
+            }
+            ScribbleNumberMode::VolumePercent => ProfileNumberMode::VolumePercent,
+            ScribbleNumberMode::VolumeDb => ProfileNumberMode::VolumeDb,
+            ScribbleNumberMode::ActivePreset => ProfileNumberMode::ActivePreset,
+        };
+        scribble.set_number_mode(number_mode);
     }
 
     pub fn set_scribble_inverted(&mut self, fader: FaderName, inverted: bool) {
+        self.dirty = true;
         let scribble = self
             .profile
             .settings_mut()
@@ -475,6 +983,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_channel_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .mixer_mut()
@@ -745,6 +1254,10 @@ impl ProfileAdapter {
             simple: simple_map,
             sampler: sampler_map,
             encoders: encoder_map,
+
+            // Not part of the profile, the device fills this in
+            reactive: Default::default(),
+            high_contrast: None,
         }
     }
 
@@ -854,11 +1367,34 @@ impl ProfileAdapter {
             ),
         };
 
+        // The status shows baselines as percentages, the same as the Reverb and Echo amounts
+        let stored = self.get_effects_intensity();
+        let intensity = EffectsIntensity {
+            intensity: stored.intensity,
+            reverb_baseline: ((stored.reverb as u16 * 100) / 24) as u8,
+            echo_baseline: ((stored.echo as u16 * 100) / 24) as u8,
+            pitch_baseline: stored.pitch,
+        };
+
+        let mut encoders: EnumMap<EncoderName, EncoderState> = EnumMap::default();
+        for encoder in EncoderName::iter() {
+            encoders[encoder] = EncoderState {
+                is_global: self.is_encoder_global(encoder),
+                value: match encoder {
+                    EncoderName::Pitch => self.get_pitch_encoder_position(),
+                    EncoderName::Gender => self.get_gender_value(),
+                    EncoderName::Reverb => self.get_reverb_value(),
+                    EncoderName::Echo => self.get_echo_value(),
+                },
+            };
+        }
+
         let is_enabled = self.is_fx_enabled();
         Some(Effects {
             is_enabled,
             active_preset,
             preset_names,
+            encoders,
             current: ActiveEffects {
                 reverb,
                 echo,
@@ -867,6 +1403,7 @@ impl ProfileAdapter {
                 megaphone,
                 robot,
                 hard_tune,
+                intensity,
             },
         })
     }
@@ -877,6 +1414,8 @@ impl ProfileAdapter {
         audio_handler: &Option<AudioHandler>,
         sampler_prerecord: u16,
         processing_state: SampleProcessState,
+        clear_remaining: Option<u16>,
+        last_replay: Option<ReplayCapture>,
     ) -> Option<Sampler> {
         if is_device_mini {
             return None;
@@ -897,19 +1436,43 @@ impl ProfileAdapter {
 
                 let mut tracks = vec![];
                 for track in sample_bank.get_tracks() {
+                    let duration = track.duration_ms;
                     tracks.push(Sample {
                         name: track.track.clone(),
                         start_pct: track.start_position,
                         stop_pct: track.end_position,
+                        start_ms: sample_pct_to_ms(track.start_position, duration),
+                        stop_ms: sample_pct_to_ms(track.end_position, duration),
+                        duration_ms: duration,
+                        markers: track
+                            .markers
+                            .iter()
+                            .map(|marker| SampleMarker {
+                                label: marker.label.clone(),
+                                start_pct: marker.start_position,
+                                stop_pct: marker.end_position,
+                                start_ms: sample_pct_to_ms(marker.start_position, duration),
+                                stop_ms: marker
+                                    .end_position
+                                    .and_then(|end| sample_pct_to_ms(end, duration)),
+                            })
+                            .collect(),
+                        recording_peak: track.recording_peak,
+                        clipped: track.clipped,
+                        source: track.source.clone(),
                     });
                 }
 
                 let mut is_playing = false;
                 let mut is_recording = false;
+                let mut recording_peak = None;
 
                 if let Some(audio_handler) = audio_handler {
                     is_playing = audio_handler.is_sample_playing(bank, button);
                     is_recording = audio_handler.sample_recording(bank, button);
+                    recording_peak = audio_handler
+                        .get_recording_peak(bank, button)
+                        .map(|peak| (peak * 10.).round() as f32 / 10.);
                 }
 
                 // Create a SamplerButton
@@ -921,6 +1484,9 @@ impl ProfileAdapter {
                     samples: tracks,
                     is_playing,
                     is_recording,
+                    recording_peak,
+                    guarded: sample_bank.is_guarded(),
+                    ducking: sample_bank.get_ducking().map(profile_to_standard_ducking),
                 };
                 buttons.insert(button, sampler_button);
             }
@@ -928,16 +1494,38 @@ impl ProfileAdapter {
             sampler_map.insert(bank, buttons);
         }
 
+        let cache = audio_handler
+            .as_ref()
+            .filter(|handler| handler.is_sample_cache_enabled())
+            .map(|handler| {
+                let stats = handler.get_sample_cache_stats();
+                SampleCacheStatus {
+                    size: stats.size,
+                    entries: stats.entries,
+                    hits: stats.hits,
+                    misses: stats.misses,
+                }
+            });
+
         Some(Sampler {
             processing_state,
             active_bank: self.get_active_sample_bank(),
             clear_active: self.is_sample_clear_active(),
+            clear_remaining,
             record_buffer: sampler_prerecord,
             banks: sampler_map,
+            cache,
+            last_replay,
         })
     }
 
-    pub fn get_scribble_ipc(&self, fader: FaderName, is_mini: bool) -> Option<Scribble> {
+    pub fn get_scribble_ipc(
+        &self,
+        fader: FaderName,
+        is_mini: bool,
+        rendered_number: Option<String>,
+        last_error: Option<String>,
+    ) -> Option<Scribble> {
         if is_mini {
             return None;
         }
@@ -949,9 +1537,13 @@ impl ProfileAdapter {
 
         Some(Scribble {
             file_name: scribble.icon_file(),
+            glyph: scribble.icon_glyph(),
             bottom_text: scribble.text_bottom_middle(),
             left_text: scribble.text_top_left(),
             inverted: scribble.is_style_invert(),
+            number_mode: self.get_scribble_number_mode(fader),
+            rendered_number,
+            last_error,
         })
     }
 
@@ -963,6 +1555,7 @@ impl ProfileAdapter {
     }
 
     fn get_mute_button_mut(&mut self, fader: FaderName) -> &mut MuteButton {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .mute_button_mut(standard_to_profile_fader(fader))
@@ -986,6 +1579,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_mute_button_behaviour(&mut self, fader: FaderName, behaviour: BasicMuteFunction) {
+        self.dirty = true;
         let mute_config = self.get_mute_button_mut(fader);
         mute_config.set_mute_function(standard_to_profile_mute_function(behaviour));
     }
@@ -1007,16 +1601,19 @@ impl ProfileAdapter {
     }
 
     pub fn set_mute_previous_volume(&mut self, fader: FaderName, volume: u8) -> Result<()> {
+        self.dirty = true;
         self.get_mute_button_mut(fader).set_previous_volume(volume)
     }
 
     pub fn set_mute_button_on(&mut self, fader: FaderName, on: bool) {
+        self.dirty = true;
         self.get_mute_button_mut(fader)
             .colour_map_mut()
             .set_state_on(on)
     }
 
     pub fn set_mute_button_blink(&mut self, fader: FaderName, on: bool) {
+        self.dirty = true;
         self.get_mute_button_mut(fader)
             .colour_map_mut()
             .set_blink_on(on)
@@ -1028,6 +1625,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_chat_mute_button_mut(&mut self) -> &mut MuteChat {
+        self.dirty = true;
         self.profile.settings_mut().mute_chat_mut()
     }
 
@@ -1037,11 +1635,13 @@ impl ProfileAdapter {
     }
 
     pub fn set_chat_mute_button_behaviour(&mut self, behaviour: BasicMuteFunction) {
+        self.dirty = true;
         let mute_config = self.get_chat_mute_button_mut();
         mute_config.set_cough_mute_source(standard_to_profile_mute_function(behaviour));
     }
 
     pub fn set_chat_mute_button_is_held(&mut self, is_hold: bool) {
+        self.dirty = true;
         let mute_config = self.get_chat_mute_button_mut();
         if is_hold {
             mute_config.set_cough_behaviour(CoughToggle::Hold);
@@ -1068,6 +1668,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_mute_chat_button_on(&mut self, on: bool) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .mute_chat_mut()
@@ -1075,6 +1676,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_mute_chat_button_blink(&mut self, on: bool) {
+        self.dirty = true;
         self.profile.settings_mut().mute_chat_mut().set_blink_on(on);
     }
 
@@ -1124,6 +1726,7 @@ impl ProfileAdapter {
                 *self.profile.settings().mute_chat().cough_mute_source(),
             ),
             state: mic_state,
+            mic_fader: self.get_mic_fader(),
         }
     }
 
@@ -1140,16 +1743,26 @@ impl ProfileAdapter {
         let routing = self.profile.settings().mix_routing();
         let mut inputs: EnumMap<SubMixChannelName, Submix> = Default::default();
 
+        // The Headphones are always assigned the mix of whatever's being monitored
+        let monitored_mix =
+            profile_to_standard_mix(routing.get_assignment(OutputChannels::Headphones));
+
         for channel in SubMixChannelName::iter() {
             let input_channel = submix_standard_to_profile_input(channel);
             let ratio = match mixes.linking_tree().is_linked(input_channel) {
                 true => mixes.linking_tree().get_ratio(input_channel),
                 false => 1.0_f64,
             };
+            let volume = mixes.volume_table()[input_channel];
+            let monitored_volume = match monitored_mix {
+                goxlr_types::Mix::A => self.get_channel_volume(submix_to_channel_name(channel)),
+                goxlr_types::Mix::B => volume,
+            };
             inputs[channel] = Submix {
-                volume: mixes.volume_table()[input_channel],
+                volume,
                 linked: mixes.linking_tree().is_linked(input_channel),
                 ratio,
+                monitored_volume,
             };
         }
 
@@ -1175,30 +1788,36 @@ impl ProfileAdapter {
             }
         }
 
-        Some(Submixes { inputs, outputs })
+        Some(Submixes {
+            inputs,
+            outputs,
+            monitored_mix,
+        })
     }
 
     /** Fader Stuff */
-    pub fn is_mic_on_fader(&self) -> bool {
-        self.profile.settings().mute_chat().mic_fader_id() != 4
-    }
-
-    pub fn get_mic_fader(&self) -> FaderName {
-        self.fader_from_id(self.profile.settings().mute_chat().mic_fader_id())
+    /// The fader the Mic channel is on, if any. This is kept in step with the fader assignments,
+    /// so the cough button and the Mic's fader always agree on who shares the Mic's mute state.
+    pub fn get_mic_fader(&self) -> Option<FaderName> {
+        match self.profile.settings().mute_chat().mic_fader_id() {
+            4 => None,
+            id => Some(self.fader_from_id(id)),
+        }
     }
 
-    pub fn set_mic_fader(&mut self, fader: FaderName) -> Result<()> {
-        self.profile
-            .settings_mut()
-            .mute_chat_mut()
-            .set_mic_fader_id(fader as u8)
-    }
+    // Profiles don't always get this right, so it's worked out from the assignments instead
+    fn sync_mic_fader(&mut self) {
+        let mic_fader =
+            FaderName::iter().find(|f| self.get_fader_assignment(*f) == ChannelName::Mic);
 
-    pub fn clear_mic_fader(&mut self) {
-        self.profile
-            .settings_mut()
-            .mute_chat_mut()
-            .clear_mic_fader_id();
+        let mute_chat = self.profile.settings_mut().mute_chat_mut();
+        match mic_fader {
+            Some(fader) => {
+                // FaderName is always in range, so this can't fail
+                let _ = mute_chat.set_mic_fader_id(fader as u8);
+            }
+            None => mute_chat.clear_mic_fader_id(),
+        }
     }
 
     // TODO: This can probably be cleaned with EnumIter
@@ -1229,6 +1848,7 @@ impl ProfileAdapter {
 
     /** Bleep Button **/
     pub fn set_swear_button_on(&mut self, on: bool) {
+        self.dirty = true;
         // Get the colour map for the bleep button..
         self.profile
             .settings_mut()
@@ -1238,12 +1858,13 @@ impl ProfileAdapter {
     }
 
     /** Effects Bank Behaviours **/
-    pub fn get_active_effect_bank(&mut self) -> EffectBankPresets {
+    pub fn get_active_effect_bank(&self) -> EffectBankPresets {
         let current = self.profile.settings().context().selected_effects();
         profile_to_standard_preset(current)
     }
 
     pub fn load_effect_bank(&mut self, preset: EffectBankPresets) -> Result<()> {
+        self.dirty = true;
         let preset = standard_to_profile_preset(preset);
         let current = self.profile.settings().context().selected_effects();
 
@@ -1304,6 +1925,102 @@ impl ProfileAdapter {
             .colour_map_mut()
             .set_state_on(true);
 
+        // Global encoders keep their value, rather than taking the new preset's
+        for encoder in EncoderName::iter() {
+            if self.is_encoder_global(encoder) {
+                self.copy_encoder_value(encoder, current, preset)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether an encoder's value is shared by every effect preset, rather than each preset
+    /// having its own.
+    pub fn is_encoder_global(&self, encoder: EncoderName) -> bool {
+        let settings = self.profile.settings();
+        match encoder {
+            EncoderName::Pitch => settings.pitch_encoder().is_global_knob(),
+            EncoderName::Gender => settings.gender_encoder().is_global_knob(),
+            EncoderName::Reverb => settings.reverb_encoder().is_global_knob(),
+            EncoderName::Echo => settings.echo_encoder().is_global_knob(),
+        }
+    }
+
+    pub fn set_encoder_global(&mut self, encoder: EncoderName, global: bool) -> Result<()> {
+        self.dirty = true;
+        let settings = self.profile.settings_mut();
+        match encoder {
+            EncoderName::Pitch => settings.pitch_encoder_mut().set_global_knob(global),
+            EncoderName::Gender => settings.gender_encoder_mut().set_global_knob(global),
+            EncoderName::Reverb => settings.reverb_encoder_mut().set_global_knob(global),
+            EncoderName::Echo => settings.echo_encoder_mut().set_global_knob(global),
+        }
+
+        // The active preset's value becomes the shared one
+        self.share_encoder_value(encoder)
+    }
+
+    /// Copies the active preset's value of a global encoder to every other preset, so the
+    /// stored presets agree with what's in use (and with what the official app will load).
+    pub fn share_encoder_value(&mut self, encoder: EncoderName) -> Result<()> {
+        if !self.is_encoder_global(encoder) {
+            return Ok(());
+        }
+
+        let current = self.profile.settings().context().selected_effects();
+        for preset in Preset::iter().filter(|preset| *preset != current) {
+            self.copy_encoder_value(encoder, current, preset)?;
+        }
+        Ok(())
+    }
+
+    /// Puts the shared values of global encoders back after a preset has been loaded over the
+    /// active one, every other preset still holds them.
+    fn restore_shared_encoder_values(&mut self) -> Result<()> {
+        let current = self.profile.settings().context().selected_effects();
+        let Some(other) = Preset::iter().find(|preset| *preset != current) else {
+            return Ok(());
+        };
+
+        for encoder in EncoderName::iter() {
+            if self.is_encoder_global(encoder) {
+                self.copy_encoder_value(encoder, other, current)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn copy_encoder_value(&mut self, encoder: EncoderName, from: Preset, to: Preset) -> Result<()> {
+        let settings = self.profile.settings_mut();
+        match encoder {
+            EncoderName::Pitch => {
+                // This copies the pitch itself, which Narrow presets can only go half as far with,
+                // so a Wide preset which is already further in the same direction is left alone
+                let source = settings.pitch_encoder().get_preset(from);
+                let (value, limit) = (source.get_pitch_value(), pitch_limit(source.style()));
+                let target = settings.pitch_encoder_mut().get_preset_mut(to);
+                if target.get_pitch_value().clamp(-limit, limit) != value {
+                    let limit = pitch_limit(target.style());
+                    target.set_pitch_value(value.clamp(-limit, limit));
+                }
+            }
+            EncoderName::Gender => {
+                let value = settings.gender_encoder().get_preset(from).knob_position();
+                let target = settings.gender_encoder_mut().get_preset_mut(to);
+                target.set_knob_position(value)?;
+            }
+            EncoderName::Reverb => {
+                let value = settings.reverb_encoder().get_preset(from).knob_position();
+                let target = settings.reverb_encoder_mut().get_preset_mut(to);
+                target.set_knob_position(value)?;
+            }
+            EncoderName::Echo => {
+                let value = settings.echo_encoder().get_preset(from).knob_position();
+                let target = settings.echo_encoder_mut().get_preset_mut(to);
+                target.set_knob_position(value)?;
+            }
+        }
         Ok(())
     }
 
@@ -1313,6 +2030,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_megaphone(&mut self, enabled: bool) {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
 
         self.profile
@@ -1328,6 +2046,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_robot(&mut self, enable: bool) {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1342,6 +2061,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_hardtune(&mut self, enabled: bool) {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
 
         self.profile
@@ -1357,6 +2077,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_effects(&mut self, enabled: bool) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .simple_element_mut(SimpleElements::FxClear)
@@ -1380,19 +2101,22 @@ impl ProfileAdapter {
     }
 
     pub fn set_pitch_knob_position(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         let hardtune_enabled = self.is_hardtune_enabled(true);
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
             .pitch_encoder_mut()
             .get_preset_mut(current)
-            .set_knob_position(value, hardtune_enabled)
+            .set_knob_position(value, hardtune_enabled)?;
+        self.share_encoder_value(EncoderName::Pitch)
     }
 
     pub fn set_pitch_style(&mut self, style: goxlr_types::PitchStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_pitch_profile_mut()
             .set_style(standard_to_profile_pitch_style(style));
-        Ok(())
+        self.share_encoder_value(EncoderName::Pitch)
     }
 
     pub fn get_pitch_mode(&self) -> u8 {
@@ -1411,6 +2135,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_pitch_profile_mut(&mut self) -> &mut PitchEncoder {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1428,15 +2153,18 @@ impl ProfileAdapter {
     }
 
     pub fn set_gender_value(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
             .gender_encoder_mut()
             .get_preset_mut(current)
-            .set_knob_position(value)
+            .set_knob_position(value)?;
+        self.share_encoder_value(EncoderName::Gender)
     }
 
     pub fn set_gender_style(&mut self, style: goxlr_types::GenderStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_gender_profile_mut()
             .set_style(standard_to_profile_gender_style(style));
         Ok(())
@@ -1448,6 +2176,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_gender_profile_mut(&mut self) -> &mut GenderEncoder {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1465,15 +2194,18 @@ impl ProfileAdapter {
     }
 
     pub fn set_reverb_value(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
             .reverb_encoder_mut()
             .get_preset_mut(current)
-            .set_knob_position(value)
+            .set_knob_position(value)?;
+        self.share_encoder_value(EncoderName::Reverb)
     }
 
     pub fn set_reverb_style(&mut self, style: goxlr_types::ReverbStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_reverb_profile_mut()
             .set_style(standard_to_profile_reverb_style(style))?;
         Ok(())
@@ -1485,6 +2217,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_reverb_profile_mut(&mut self) -> &mut ReverbEncoder {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1506,15 +2239,122 @@ impl ProfileAdapter {
     }
 
     pub fn set_echo_value(&mut self, value: i8) -> Result<()> {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
             .echo_encoder_mut()
             .get_preset_mut(current)
-            .set_knob_position(value)
+            .set_knob_position(value)?;
+        self.share_encoder_value(EncoderName::Echo)
+    }
+
+    /// The stored Pitch, Gender, Reverb and Echo values of the active effect bank, these are
+    /// raw so they can be put back as they were regardless of the Hard Tune state.
+    pub fn get_encoder_values(&self) -> [i8; 4] {
+        [
+            self.get_active_pitch_profile().get_pitch_value(),
+            self.get_gender_value(),
+            self.get_reverb_value(),
+            self.get_echo_value(),
+        ]
+    }
+
+    pub fn set_encoder_values(&mut self, values: [i8; 4]) -> Result<()> {
+        self.get_active_pitch_profile_mut()
+            .set_pitch_value(values[0]);
+        self.share_encoder_value(EncoderName::Pitch)?;
+        self.set_gender_value(values[1])?;
+        self.set_reverb_value(values[2])?;
+        self.set_echo_value(values[3])?;
+        Ok(())
+    }
+
+    /// The active bank's effects intensity, if none is stored the current amounts are the
+    /// baselines.
+    pub fn get_effects_intensity(&self) -> ProfileEffectsIntensity {
+        let current = self.profile.settings().context().selected_effects();
+        match self.profile.settings().effects(current).intensity() {
+            Some(intensity) => intensity,
+            None => ProfileEffectsIntensity {
+                intensity: 100,
+                reverb: self.get_reverb_value(),
+                echo: self.get_echo_value(),
+                pitch: self.get_pitch_knob_position(),
+            },
+        }
+    }
+
+    /// Scales the Reverb, Echo and Pitch amounts of the active bank to a percentage of their
+    /// baselines.
+    pub fn set_effects_intensity(&mut self, intensity: u8) -> Result<()> {
+        if intensity > 100 {
+            bail!("Effects Intensity must be a percentage");
+        }
+
+        self.dirty = true;
+        let baseline = self.get_effects_intensity();
+        let scale = |value: i8| (value as f32 * intensity as f32 / 100.).round() as i8;
+
+        self.set_reverb_value(scale(baseline.reverb))?;
+        self.set_echo_value(scale(baseline.echo))?;
+
+        let hardtune_enabled = self.is_hardtune_enabled(true);
+        let range = self.get_active_pitch_profile().knob_range(hardtune_enabled);
+        let pitch = scale(baseline.pitch).clamp(*range.start(), *range.end());
+        self.set_pitch_knob_position(pitch)?;
+
+        // At full intensity the amounts are the baselines, so there's nothing to keep
+        let stored = (intensity != 100).then_some(ProfileEffectsIntensity {
+            intensity,
+            ..baseline
+        });
+        self.set_stored_effects_intensity(stored);
+        Ok(())
+    }
+
+    /// Called when an amount has been changed directly, moves that effect's baseline so the
+    /// current intensity produces the new amount.
+    pub fn rebase_effects_intensity(&mut self, encoder: EncoderName) {
+        let current = self.profile.settings().context().selected_effects();
+        let mut stored = match self.profile.settings().effects(current).intensity() {
+            Some(stored) => stored,
+            None => return,
+        };
+
+        let intensity = stored.intensity;
+        let rebase = |value: i8, min: i8, max: i8| {
+            if intensity == 0 {
+                return value;
+            }
+            let value = (value as f32 * 100. / intensity as f32).round();
+            value.clamp(min as f32, max as f32) as i8
+        };
+
+        match encoder {
+            EncoderName::Reverb => stored.reverb = rebase(self.get_reverb_value(), 0, 24),
+            EncoderName::Echo => stored.echo = rebase(self.get_echo_value(), 0, 24),
+            EncoderName::Pitch => {
+                let hardtune_enabled = self.is_hardtune_enabled(true);
+                let range = self.get_active_pitch_profile().knob_range(hardtune_enabled);
+                stored.pitch = rebase(self.get_pitch_knob_position(), *range.start(), *range.end());
+            }
+            EncoderName::Gender => return,
+        }
+        self.set_stored_effects_intensity(Some(stored));
+    }
+
+    fn set_stored_effects_intensity(&mut self, intensity: Option<ProfileEffectsIntensity>) {
+        self.dirty = true;
+        let current = self.profile.settings().context().selected_effects();
+        self.profile
+            .settings_mut()
+            .effects_mut(current)
+            .set_intensity(intensity);
     }
 
     pub fn set_echo_style(&mut self, style: goxlr_types::EchoStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_echo_profile_mut()
             .set_style(standard_to_profile_echo_style(style))?;
         Ok(())
@@ -1526,6 +2366,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_echo_profile_mut(&mut self) -> &mut EchoEncoder {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1534,6 +2375,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_megaphone_style(&mut self, style: goxlr_types::MegaphoneStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_megaphone_profile_mut()
             .set_style(standard_to_profile_megaphone_style(style))?;
         Ok(())
@@ -1548,6 +2390,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_megaphone_profile_mut(&mut self) -> &mut MegaphoneEffect {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1556,6 +2399,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_robot_style(&mut self, style: goxlr_types::RobotStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_robot_profile_mut()
             .set_style(standard_to_profile_robot_style(style))?;
         Ok(())
@@ -1567,6 +2411,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_robot_profile_mut(&mut self) -> &mut RobotEffect {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1575,12 +2420,14 @@ impl ProfileAdapter {
     }
 
     pub fn set_hardtune_style(&mut self, style: goxlr_types::HardTuneStyle) -> Result<()> {
+        self.dirty = true;
         self.get_active_hardtune_profile_mut()
             .set_style(standard_to_profile_hard_tune_style(style))?;
         Ok(())
     }
 
     pub fn set_hardtune_source(&mut self, source: goxlr_types::HardTuneSource) -> Result<()> {
+        self.dirty = true;
         self.get_active_hardtune_profile_mut()
             .set_source(standard_to_profile_hard_tune_source(source));
         Ok(())
@@ -1599,6 +2446,7 @@ impl ProfileAdapter {
     }
 
     pub fn get_active_hardtune_profile_mut(&mut self) -> &mut HardTuneEffect {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_effects();
         self.profile
             .settings_mut()
@@ -1671,6 +2519,7 @@ impl ProfileAdapter {
 
     /** Sampler Related **/
     pub fn load_sample_bank(&mut self, bank: goxlr_types::SampleBank) -> Result<()> {
+        self.dirty = true;
         let bank = standard_to_profile_sample_bank(bank);
         let current = self.profile.settings().context().selected_sample();
 
@@ -1717,6 +2566,7 @@ impl ProfileAdapter {
     }
 
     pub fn sync_sample_if_active(&mut self, target: SamplerColourTargets) -> Result<()> {
+        self.dirty = true;
         let current = self.profile.settings().context().selected_sample();
         let bank = standard_sample_colour_to_profile_bank(target);
 
@@ -1727,6 +2577,7 @@ impl ProfileAdapter {
     }
 
     pub fn sync_sample_colours(&mut self, bank: SampleBank) -> Result<()> {
+        self.dirty = true;
         // When loading a bank, the colour settings from the SampleBank button get migrated
         // across to the sample buttons, which are then used to display (it's a little convoluted!)
         let colour_map = get_profile_colour_map(
@@ -1807,6 +2658,7 @@ impl ProfileAdapter {
         bank: goxlr_types::SampleBank,
         button: goxlr_types::SampleButtons,
     ) -> Result<AudioFile> {
+        self.dirty = true;
         let track = self
             .profile
             .settings_mut()
@@ -1856,6 +2708,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_sample_button_state(&mut self, button: goxlr_types::SampleButtons, state: bool) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -1868,6 +2721,7 @@ impl ProfileAdapter {
         bank: goxlr_types::SampleBank,
         button: goxlr_types::SampleButtons,
     ) -> &mut Vec<Track> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -1875,7 +2729,52 @@ impl ProfileAdapter {
             .get_tracks_mut()
     }
 
+    /// Lists the button, file and gain of every track in the bank.
+    pub fn get_sample_bank_tracks(
+        &self,
+        bank: goxlr_types::SampleBank,
+    ) -> Vec<(goxlr_types::SampleButtons, String, f64)> {
+        let mut tracks = vec![];
+        for button in goxlr_types::SampleButtons::iter() {
+            let stack = self
+                .profile
+                .settings()
+                .sample_button(standard_to_profile_sample_button(button))
+                .get_stack(standard_to_profile_sample_bank(bank));
+
+            for track in stack.get_tracks() {
+                tracks.push((button, track.track().to_string(), track.normalized_gain()));
+            }
+        }
+        tracks
+    }
+
+    /// Sets the gain (and length, if it was found) of any track on the button using the file,
+    /// returning false if there aren't any (for example, if it was removed while the gain was
+    /// being calculated).
+    pub fn set_sample_gain(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        file: &str,
+        gain: f64,
+        duration_ms: Option<u64>,
+    ) -> bool {
+        let mut found = false;
+        for track in self.get_sample_bank(bank, button) {
+            if track.track() == file {
+                track.normalized_gain = gain;
+                if duration_ms.is_some() {
+                    track.duration_ms = duration_ms;
+                }
+                found = true;
+            }
+        }
+        found
+    }
+
     pub fn set_sample_button_blink(&mut self, button: goxlr_types::SampleButtons, state: bool) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -1892,6 +2791,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_sample_clear_active(&mut self, active: bool) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(Clear)
@@ -1900,6 +2800,7 @@ impl ProfileAdapter {
     }
 
     pub fn clear_all_samples(&mut self, button: goxlr_types::SampleButtons) {
+        self.dirty = true;
         let bank = self.profile.settings().context().selected_sample();
 
         self.profile
@@ -1916,6 +2817,7 @@ impl ProfileAdapter {
         colour_one: String,
         colour_two: Option<&String>,
     ) -> Result<()> {
+        self.dirty = true;
         let colour_target = standard_to_colour_target(target);
         let colours = get_profile_colour_map_mut(self.profile.settings_mut(), colour_target);
 
@@ -1931,6 +2833,7 @@ impl ProfileAdapter {
         target: SimpleColourTargets,
         colour_one: String,
     ) -> Result<()> {
+        self.dirty = true;
         if target == SimpleColourTargets::Global {
             // The 'Global' Colour as defined in the GoXLR App is a 'special' case, where it will
             // set every target to the same colour. (along with a couple of other tweaks).
@@ -1961,6 +2864,7 @@ impl ProfileAdapter {
         colour_two: String,
         colour_three: String,
     ) -> Result<()> {
+        self.dirty = true;
         let colour_target = standard_to_profile_encoder_colour(target);
         let colours = get_profile_colour_map_mut(self.profile.settings_mut(), colour_target);
 
@@ -1978,6 +2882,7 @@ impl ProfileAdapter {
         colour_two: String,
         colour_three: String,
     ) -> Result<()> {
+        self.dirty = true;
         let colour_target = standard_to_sample_colour(target);
         let colours = get_profile_colour_map_mut(self.profile.settings_mut(), colour_target);
 
@@ -1993,6 +2898,7 @@ impl ProfileAdapter {
         target: SamplerColourTargets,
         off_style: BasicColourOffStyle,
     ) {
+        self.dirty = true;
         let colour_target = standard_to_sample_colour(target);
         get_profile_colour_map_mut(self.profile.settings_mut(), colour_target)
             .set_off_style(standard_to_profile_colour_off_style(off_style))
@@ -2004,6 +2910,7 @@ impl ProfileAdapter {
         button: goxlr_types::SampleButtons,
         mode: SamplePlaybackMode,
     ) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -2017,6 +2924,7 @@ impl ProfileAdapter {
         button: goxlr_types::SampleButtons,
         order: SamplePlayOrder,
     ) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -2024,18 +2932,85 @@ impl ProfileAdapter {
             .set_play_order(Some(standard_to_profile_sample_playback_order(order)));
     }
 
+    pub fn set_sample_button_guarded(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        guarded: bool,
+    ) {
+        self.dirty = true;
+        self.profile
+            .settings_mut()
+            .sample_button_mut(standard_to_profile_sample_button(button))
+            .get_stack_mut(standard_to_profile_sample_bank(bank))
+            .set_guarded(guarded);
+    }
+
+    pub fn set_sample_button_ducking(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        ducking: Option<DuckConfig>,
+    ) {
+        self.dirty = true;
+        let ducking = ducking.map(|config| SampleDucking {
+            channels: config
+                .channels
+                .into_iter()
+                .map(standard_to_profile_channel)
+                .collect(),
+            attenuation: config.attenuation,
+            release: config.release,
+        });
+
+        self.profile
+            .settings_mut()
+            .sample_button_mut(standard_to_profile_sample_button(button))
+            .get_stack_mut(standard_to_profile_sample_bank(bank))
+            .set_ducking(ducking);
+    }
+
+    pub fn get_sample_button_ducking(
+        &self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+    ) -> Option<DuckConfig> {
+        self.profile
+            .settings()
+            .sample_button(standard_to_profile_sample_button(button))
+            .get_stack(standard_to_profile_sample_bank(bank))
+            .get_ducking()
+            .map(profile_to_standard_ducking)
+    }
+
+    /// Whether the button is guarded on the active bank
+    pub fn is_sample_button_guarded(&self, button: goxlr_types::SampleButtons) -> bool {
+        let bank = self.profile.settings().context().selected_sample();
+        self.profile
+            .settings()
+            .sample_button(standard_to_profile_sample_button(button))
+            .get_stack(bank)
+            .is_guarded()
+    }
+
     pub fn add_sample_file(
         &mut self,
         bank: goxlr_types::SampleBank,
         button: goxlr_types::SampleButtons,
         file: String,
     ) -> &mut Track {
+        self.dirty = true;
         // Create a new 'Track' (Oddly, positions are a percentage :D)..
         let track = Track {
             track: file,
             start_position: 0.0,
             end_position: 100.0,
             normalized_gain: 1.0,
+            markers: vec![],
+            recording_peak: None,
+            clipped: false,
+            duration_ms: None,
+            source: None,
         };
 
         // Add this to the list, then return the track..
@@ -2053,13 +3028,9 @@ impl ProfileAdapter {
         index: usize,
         percent: f32,
     ) -> Result<()> {
-        let track = self
-            .profile
-            .settings_mut()
-            .sample_button_mut(standard_to_profile_sample_button(button))
-            .get_stack_mut(standard_to_profile_sample_bank(bank))
-            .get_track_by_index_mut(index)?;
-
+        self.dirty = true;
+        let track = self.get_sample_track_mut(bank, button, index)?;
+        check_sample_range(track, percent, track.end_position())?;
         track.set_start_position(percent)?;
         Ok(())
     }
@@ -2071,15 +3042,84 @@ impl ProfileAdapter {
         index: usize,
         percent: f32,
     ) -> Result<()> {
-        let track = self
-            .profile
+        self.dirty = true;
+        let track = self.get_sample_track_mut(bank, button, index)?;
+        check_sample_range(track, track.start_position(), percent)?;
+        track.set_end_position(percent)?;
+        Ok(())
+    }
+
+    pub fn set_sample_start_ms(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+        ms: u64,
+    ) -> Result<()> {
+        let track = self.get_sample_track(bank, button, index)?;
+        let percent = ms_to_sample_pct(track, ms)?;
+        self.set_sample_start_pct(bank, button, index, percent)
+    }
+
+    pub fn set_sample_stop_ms(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+        ms: u64,
+    ) -> Result<()> {
+        let track = self.get_sample_track(bank, button, index)?;
+        let percent = ms_to_sample_pct(track, ms)?;
+        self.set_sample_stop_pct(bank, button, index, percent)
+    }
+
+    /// The file name and (if it's known) the length of a track.
+    pub fn get_sample_duration(
+        &self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+    ) -> Result<(String, Option<u64>)> {
+        let track = self.get_sample_track(bank, button, index)?;
+        Ok((track.track().to_string(), track.duration_ms))
+    }
+
+    pub fn set_sample_duration(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+        duration_ms: u64,
+    ) -> Result<()> {
+        self.dirty = true;
+        self.get_sample_track_mut(bank, button, index)?.duration_ms = Some(duration_ms);
+        Ok(())
+    }
+
+    fn get_sample_track(
+        &self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+    ) -> Result<&Track> {
+        self.profile
+            .settings()
+            .sample_button(standard_to_profile_sample_button(button))
+            .get_stack(standard_to_profile_sample_bank(bank))
+            .get_track_by_index(index)
+    }
+
+    fn get_sample_track_mut(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+    ) -> Result<&mut Track> {
+        self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
             .get_stack_mut(standard_to_profile_sample_bank(bank))
-            .get_track_by_index_mut(index)?;
-
-        track.set_end_position(percent)?;
-        Ok(())
+            .get_track_by_index_mut(index)
     }
 
     pub fn remove_sample_file_by_index(
@@ -2088,6 +3128,7 @@ impl ProfileAdapter {
         button: goxlr_types::SampleButtons,
         index: usize,
     ) -> Result<usize> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
@@ -2103,6 +3144,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_button_off_style(&mut self, target: Button, off_style: BasicColourOffStyle) {
+        self.dirty = true;
         let colour_target = standard_to_colour_target(target);
         get_profile_colour_map_mut(self.profile.settings_mut(), colour_target)
             .set_off_style(standard_to_profile_colour_off_style(off_style))
@@ -2147,6 +3189,14 @@ impl ProfileAdapter {
             .is_linked(submix_standard_to_profile_input(channel))
     }
 
+    /// Whether the profile has its own setting for the channel's link, rather than the default.
+    pub fn is_submix_link_set(&self, channel: SubMixChannelName) -> bool {
+        self.profile
+            .settings()
+            .submixes()
+            .is_link_set(submix_standard_to_profile_input(channel))
+    }
+
     pub fn get_submix_ratio(&self, channel: SubMixChannelName) -> f64 {
         self.profile
             .settings()
@@ -2156,6 +3206,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_submix_volume(&mut self, channel: SubMixChannelName, volume: u8) {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .submixes_mut()
@@ -2163,6 +3214,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_submix_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .submixes_mut()
@@ -2170,6 +3222,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_submix_linked(&mut self, channel: SubMixChannelName, linked: bool) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .submixes_mut()
@@ -2177,6 +3230,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_submix_link_ratio(&mut self, channel: SubMixChannelName, ratio: f64) -> Result<()> {
+        self.dirty = true;
         self.profile
             .settings_mut()
             .submixes_mut()
@@ -2184,9 +3238,21 @@ impl ProfileAdapter {
     }
 
     pub fn set_mix_output(&mut self, channel: OutputDevice, mix: goxlr_types::Mix) -> Result<()> {
+        self.dirty = true;
         let profile_mix = standard_to_profile_mix(mix);
         let device = standard_output_to_profile(channel);
 
+        // While something else is monitored the Headphones are following its mix, like routing
+        // changes this is kept for when the Headphones are monitored again.
+        if channel == OutputDevice::Headphones && self.get_monitoring_mix() != channel {
+            self.profile
+                .settings_mut()
+                .submixes_mut()
+                .monitor_tree_mut()
+                .set_headphone_mix(profile_mix);
+            return Ok(());
+        }
+
         // Do we also need to change the mic assignment?
         if self.get_monitoring_mix() == channel && channel != OutputDevice::Headphones {
             // Move the headphone mix across too..
@@ -2206,6 +3272,65 @@ impl ProfileAdapter {
 
     //////////////////// END SUBMIXES ////////////////////
 
+    pub fn get_volume_scene_names(&self) -> Vec<String> {
+        self.profile.settings().volume_scenes().scene_names()
+    }
+
+    pub fn set_volume_scene(
+        &mut self,
+        name: String,
+        volumes: EnumMap<ChannelName, u8>,
+        submix_volumes: EnumMap<SubMixChannelName, u8>,
+    ) {
+        self.dirty = true;
+        let mut scene = VolumeScene::new(name);
+        for (channel, volume) in volumes {
+            scene.set_volume(standard_to_profile_channel(channel), volume);
+        }
+        for (channel, volume) in submix_volumes {
+            scene.set_submix_volume(submix_standard_to_profile_input(channel), volume);
+        }
+        self.profile
+            .settings_mut()
+            .volume_scenes_mut()
+            .set_scene(scene);
+    }
+
+    /// Returns the volumes stored in a scene, channels missing from the scene are None.
+    #[allow(clippy::type_complexity)]
+    pub fn get_volume_scene(
+        &self,
+        name: &str,
+    ) -> Result<(
+        EnumMap<ChannelName, Option<u8>>,
+        EnumMap<SubMixChannelName, Option<u8>>,
+    )> {
+        let scene = match self.profile.settings().volume_scenes().get_scene(name) {
+            Some(scene) => scene,
+            None => bail!("Volume Scene {} not found", name),
+        };
+
+        let mut volumes = EnumMap::default();
+        for channel in ChannelName::iter() {
+            volumes[channel] = scene.volume(standard_to_profile_channel(channel));
+        }
+
+        let mut submix_volumes = EnumMap::default();
+        for channel in SubMixChannelName::iter() {
+            submix_volumes[channel] =
+                scene.submix_volume(submix_standard_to_profile_input(channel));
+        }
+        Ok((volumes, submix_volumes))
+    }
+
+    pub fn delete_volume_scene(&mut self, name: &str) -> Result<()> {
+        self.dirty = true;
+        self.profile
+            .settings_mut()
+            .volume_scenes_mut()
+            .remove_scene(name)
+    }
+
     // TODO: We can probably do better with grouping these so they can be reused.
     pub fn set_group_button_colours(
         &mut self,
@@ -2213,6 +3338,7 @@ impl ProfileAdapter {
         colour_one: String,
         colour_two: Option<String>,
     ) -> Result<()> {
+        self.dirty = true;
         match group {
             ButtonColourGroups::FaderMute => {
                 self.set_button_colours(
@@ -2288,6 +3414,7 @@ impl ProfileAdapter {
         target: ButtonColourGroups,
         off_style: BasicColourOffStyle,
     ) -> Result<()> {
+        self.dirty = true;
         match target {
             ButtonColourGroups::FaderMute => {
                 self.set_button_off_style(Button::Fader1Mute, off_style);
@@ -2314,6 +3441,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_global_colour(&mut self, colour: String) -> Result<()> {
+        self.dirty = true;
         // A list of colour targets which require colour1 changed, rather than 0.
         let fade_meters = vec![
             ColourTargets::FadeMeter1,
@@ -2403,6 +3531,7 @@ impl ProfileAdapter {
     }
 
     pub fn set_monitor_mix(&mut self, device: OutputDevice) -> Result<()> {
+        self.dirty = true;
         // Ok, this is convoluted, but firstly, what are we mixing to?
         let output = self
             .profile
@@ -2546,10 +3675,45 @@ impl ProfileAdapter {
     }
 
     pub fn profile_mut(&mut self) -> &mut Profile {
+        self.dirty = true;
         &mut self.profile
     }
 }
 
+/// Positions are kept as percentages (as the official app does) so they still line up if the
+/// file is re-analysed, the length is only needed to check the range isn't too short.
+fn check_sample_range(track: &Track, start: f32, stop: f32) -> Result<()> {
+    if start >= stop {
+        bail!("The sample's start must be before its end");
+    }
+    if let Some(duration) = track.duration_ms {
+        let length = ((stop - start) as f64 / 100. * duration as f64).round() as u64;
+        if length < MIN_SAMPLE_LENGTH_MS {
+            bail!(
+                "The sample must be at least {}ms long, this would be {}ms",
+                MIN_SAMPLE_LENGTH_MS,
+                length
+            );
+        }
+    }
+    Ok(())
+}
+
+fn ms_to_sample_pct(track: &Track, ms: u64) -> Result<f32> {
+    let duration = match track.duration_ms {
+        Some(duration) if duration > 0 => duration,
+        _ => bail!("The length of {} isn't known", track.track()),
+    };
+    if ms > duration {
+        bail!("{}ms is past the end of the sample ({}ms)", ms, duration);
+    }
+    Ok((ms as f64 / duration as f64 * 100.) as f32)
+}
+
+fn sample_pct_to_ms(percent: f32, duration_ms: Option<u64>) -> Option<u64> {
+    duration_ms.map(|duration| (percent as f64 / 100. * duration as f64).round() as u64)
+}
+
 fn profile_to_standard_input(value: InputChannels) -> InputDevice {
     match value {
         InputChannels::Mic => InputDevice::Microphone,
@@ -2667,6 +3831,18 @@ fn profile_to_standard_channel(value: FullChannelList) -> ChannelName {
     }
 }
 
+fn profile_to_standard_ducking(ducking: &SampleDucking) -> DuckConfig {
+    DuckConfig {
+        channels: ducking
+            .channels
+            .iter()
+            .map(|channel| profile_to_standard_channel(*channel))
+            .collect(),
+        attenuation: ducking.attenuation,
+        release: ducking.release,
+    }
+}
+
 fn standard_to_profile_channel(value: ChannelName) -> FullChannelList {
     match value {
         ChannelName::Mic => FullChannelList::Mic,
@@ -2776,6 +3952,14 @@ fn profile_to_standard_preset(value: Preset) -> EffectBankPresets {
     }
 }
 
+// How far (in semitones) the stored pitch can go in each style
+fn pitch_limit(style: &PitchStyle) -> i8 {
+    match style {
+        PitchStyle::Narrow => 12,
+        PitchStyle::Wide => 24,
+    }
+}
+
 fn standard_to_profile_preset(value: EffectBankPresets) -> Preset {
     match value {
         EffectBankPresets::Preset1 => Preset::Preset1,
@@ -2787,6 +3971,79 @@ fn standard_to_profile_preset(value: EffectBankPresets) -> Preset {
     }
 }
 
+fn load_profile(name: &str, directory: &Path) -> Result<Profile> {
+    check_existing_name(name, "goxlr", directory)?;
+    let path = directory.join(format!("{}.goxlr", name));
+    if !path.is_file() {
+        bail!("Profile {} does not exist inside {:?}", name, directory);
+    }
+
+    debug!("Loading Profile From {}", path.to_string_lossy());
+    let file = File::open(path).context("Couldn't open profile for reading")?;
+    Profile::load(file)
+}
+
+/// Loads a profile with everything it inherits applied. The chain holds the profiles being
+/// loaded which led to this one, so a profile which is eventually its own base can be refused.
+fn load_composed(
+    name: &str,
+    directory: &Path,
+    chain: &mut Vec<String>,
+) -> Result<(Profile, Option<Inheritance>)> {
+    if chain.iter().any(|loading| loading == name) {
+        bail!(
+            "Profile bases form a loop: {} -> {}",
+            chain.join(" -> "),
+            name
+        );
+    }
+
+    let mut profile = load_profile(name, directory)?;
+    let base = match profile.settings().base_profile() {
+        Some(base) => base.clone(),
+        None => return Ok((profile, None)),
+    };
+
+    chain.push(name.to_string());
+    let loaded = load_composed(base.name(), directory, chain);
+    chain.pop();
+
+    let (mut base_profile, inherited) = loaded?;
+    compose(&mut profile, &base, &mut base_profile);
+    Ok((profile, Some(Inheritance::new(name, &base, inherited))))
+}
+
+// Moves every section the profile doesn't own in from its base
+fn compose(profile: &mut Profile, base: &BaseProfile, base_profile: &mut Profile) {
+    for section in BaseSection::iter() {
+        if !base.owns(section) {
+            profile.swap_section(section, base_profile);
+        }
+    }
+}
+
+fn standard_to_profile_section(value: EffectSection) -> PresetSection {
+    match value {
+        EffectSection::Reverb => PresetSection::Reverb,
+        EffectSection::Echo => PresetSection::Echo,
+        EffectSection::Pitch => PresetSection::Pitch,
+        EffectSection::Gender => PresetSection::Gender,
+        EffectSection::Megaphone => PresetSection::Megaphone,
+        EffectSection::Robot => PresetSection::Robot,
+        EffectSection::HardTune => PresetSection::HardTune,
+    }
+}
+
+fn standard_to_base_section(value: ProfileSection) -> BaseSection {
+    match value {
+        ProfileSection::Lighting => BaseSection::Lighting,
+        ProfileSection::Routing => BaseSection::Routing,
+        ProfileSection::Faders => BaseSection::Faders,
+        ProfileSection::Effects => BaseSection::Effects,
+        ProfileSection::Sampler => BaseSection::Sampler,
+    }
+}
+
 fn standard_to_profile_fader(value: FaderName) -> Faders {
     match value {
         FaderName::A => Faders::A,
@@ -3255,6 +4512,19 @@ fn submix_standard_to_profile_input(source: SubMixChannelName) -> InputChannels
     }
 }
 
+pub fn submix_to_channel_name(source: SubMixChannelName) -> ChannelName {
+    match source {
+        SubMixChannelName::Mic => ChannelName::Mic,
+        SubMixChannelName::LineIn => ChannelName::LineIn,
+        SubMixChannelName::Console => ChannelName::Console,
+        SubMixChannelName::System => ChannelName::System,
+        SubMixChannelName::Game => ChannelName::Game,
+        SubMixChannelName::Chat => ChannelName::Chat,
+        SubMixChannelName::Sample => ChannelName::Sample,
+        SubMixChannelName::Music => ChannelName::Music,
+    }
+}
+
 pub fn channel_name_to_submix(source: ChannelName) -> Option<SubMixChannelName> {
     match source {
         ChannelName::Mic => Some(SubMixChannelName::Mic),
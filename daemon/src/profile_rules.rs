@@ -0,0 +1,188 @@
+use crate::device::Device;
+use crate::SettingsHandle;
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use goxlr_ipc::{GoXLRCommand, ProfileRule, ProfileTrigger};
+use goxlr_types::VersionNumber;
+use log::{info, warn};
+
+/*
+ * Profile rules load a profile (or mic profile) as a device connects, in place of its defaults.
+ * To tell which triggers apply, the date and firmware of each device's last connection are
+ * kept in its settings. A device's first connection doesn't count as a firmware change.
+ */
+
+pub async fn add_profile_rule(settings: &SettingsHandle, rule: ProfileRule) -> Result<()> {
+    let missing = |name: &Option<String>| !name.as_ref().is_some_and(|name| !name.is_empty());
+    if missing(&rule.profile) && missing(&rule.mic_profile) {
+        bail!("A profile rule needs a profile or a mic profile to load");
+    }
+
+    info!("Setting the {:?} profile rule", rule.trigger);
+    settings.set_profile_rule(rule).await;
+    settings.save().await;
+    Ok(())
+}
+
+pub async fn remove_profile_rule(settings: &SettingsHandle, trigger: ProfileTrigger) -> Result<()> {
+    if !settings.remove_profile_rule(trigger).await {
+        bail!("No profile rule found for {:?}", trigger);
+    }
+    settings.save().await;
+    Ok(())
+}
+
+/// Finds the rule to apply for this connection, and records the connection for next time.
+pub async fn find_profile_rule(
+    settings: &SettingsHandle,
+    serial: &str,
+    firmware: &VersionNumber,
+    today: NaiveDate,
+) -> Option<ProfileRule> {
+    let today = today.format("%Y-%m-%d").to_string();
+    let (last_date, last_firmware) = settings.get_device_last_connect(serial).await;
+    settings
+        .set_device_last_connect(serial, today.clone(), firmware.clone())
+        .await;
+
+    let triggered = |trigger: ProfileTrigger| match trigger {
+        ProfileTrigger::FirmwareChanged => {
+            last_firmware.as_ref().is_some_and(|last| last != firmware)
+        }
+        ProfileTrigger::FirstConnectOfDay => last_date.as_ref() != Some(&today),
+        ProfileTrigger::OnConnect => true,
+    };
+
+    // These come back in order of precedence
+    let rules = settings.get_profile_rules().await;
+    rules.into_iter().find(|rule| triggered(rule.trigger))
+}
+
+/// Loads the rule's profiles without making them the device's defaults, a profile which can't
+/// be loaded leaves the device on the one it already has.
+pub async fn apply_profile_rule(device: &mut Device<'_>, rule: ProfileRule) {
+    info!(
+        "[{}] Applying the {:?} profile rule",
+        device.serial(),
+        rule.trigger
+    );
+
+    if let Some(profile) = rule.profile.filter(|name| !name.is_empty()) {
+        let command = GoXLRCommand::LoadProfile(profile.clone(), false, false);
+        if let Err(e) = device.perform_command(command).await {
+            warn!("Unable to load the profile {}: {}", profile, e);
+        }
+    }
+
+    if let Some(mic_profile) = rule.mic_profile.filter(|name| !name.is_empty()) {
+        let command = GoXLRCommand::LoadMicProfile(mic_profile.clone(), false);
+        if let Err(e) = device.perform_command(command).await {
+            warn!("Unable to load the mic profile {}: {}", mic_profile, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_device::settings;
+
+    const FIRMWARE: VersionNumber = VersionNumber(1, 4, Some(2), Some(107));
+    const UPDATED: VersionNumber = VersionNumber(1, 5, Some(0), Some(120));
+
+    fn rule(trigger: ProfileTrigger, profile: &str) -> ProfileRule {
+        ProfileRule {
+            trigger,
+            profile: Some(profile.to_owned()),
+            mic_profile: None,
+        }
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    async fn connect(
+        settings: &SettingsHandle,
+        firmware: &VersionNumber,
+        day: NaiveDate,
+    ) -> String {
+        find_profile_rule(settings, "S1", firmware, day)
+            .await
+            .and_then(|rule| rule.profile)
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn rules_follow_the_date_and_firmware() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        assert!(find_profile_rule(&settings, "S1", &FIRMWARE, day(1))
+            .await
+            .is_none());
+
+        for (trigger, profile) in [
+            (ProfileTrigger::OnConnect, "Default"),
+            (ProfileTrigger::FirmwareChanged, "Calibration"),
+            (ProfileTrigger::FirstConnectOfDay, "Morning"),
+        ] {
+            add_profile_rule(&settings, rule(trigger, profile))
+                .await
+                .unwrap();
+        }
+
+        // Connecting before never counts as a firmware change, only the first of the day
+        let settings = &settings;
+        assert_eq!(connect(settings, &FIRMWARE, day(2)).await, "Morning");
+        assert_eq!(connect(settings, &FIRMWARE, day(2)).await, "Default");
+
+        // A firmware change wins over the first connection of the day, and happens once
+        assert_eq!(connect(settings, &UPDATED, day(3)).await, "Calibration");
+        assert_eq!(connect(settings, &UPDATED, day(3)).await, "Default");
+
+        // Going back is still a change
+        assert_eq!(connect(settings, &FIRMWARE, day(3)).await, "Calibration");
+        assert_eq!(connect(settings, &FIRMWARE, day(4)).await, "Morning");
+
+        // Other devices are tracked separately
+        let other = find_profile_rule(settings, "S2", &UPDATED, day(4)).await;
+        assert_eq!(other.unwrap().trigger, ProfileTrigger::FirstConnectOfDay);
+    }
+
+    #[tokio::test]
+    async fn rules_are_replaced_by_trigger() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+
+        let empty = ProfileRule {
+            trigger: ProfileTrigger::OnConnect,
+            profile: Some(String::new()),
+            mic_profile: None,
+        };
+        assert!(add_profile_rule(&settings, empty).await.is_err());
+
+        let trigger = ProfileTrigger::FirstConnectOfDay;
+        add_profile_rule(&settings, rule(ProfileTrigger::OnConnect, "Default"))
+            .await
+            .unwrap();
+        add_profile_rule(&settings, rule(trigger, "Morning"))
+            .await
+            .unwrap();
+        add_profile_rule(&settings, rule(trigger, "Evening"))
+            .await
+            .unwrap();
+
+        let rules = settings.get_profile_rules().await;
+        assert_eq!(
+            rules,
+            vec![
+                rule(trigger, "Evening"),
+                rule(ProfileTrigger::OnConnect, "Default")
+            ]
+        );
+
+        remove_profile_rule(&settings, trigger).await.unwrap();
+        assert!(remove_profile_rule(&settings, trigger).await.is_err());
+        assert_eq!(settings.get_profile_rules().await.len(), 1);
+    }
+}
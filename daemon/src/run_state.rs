@@ -0,0 +1,143 @@
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use goxlr_ipc::{PreviousRun, PreviousRunOutcome, ShutdownReason};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+// Kept alongside settings.json, rather than in the log directory where it may be rotated away
+const RUN_STATE_FILE: &str = "last-run.json";
+
+// Only set once this run has been recorded, so nothing is written before then
+static RUN_STATE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Written when the daemon starts, and updated when it stops or panics. If a run ends without
+/// a stop reason being written, it didn't get the chance to shut down cleanly.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RunState {
+    started: u64,
+    stopped: Option<u64>,
+    reason: Option<ShutdownReason>,
+    panic: Option<PanicRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PanicRecord {
+    time: u64,
+    message: String,
+    backtrace: String,
+}
+
+/// Reads how the previous run ended, then marks this one as started.
+pub fn begin_run(directory: &Path) -> Option<PreviousRun> {
+    let path = directory.join(RUN_STATE_FILE);
+
+    let previous = match fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str::<RunState>(&content) {
+            Ok(state) => Some(classify(state)),
+            Err(e) => {
+                warn!("Unable to parse {}: {}", path.to_string_lossy(), e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+
+    if let Some(previous) = &previous {
+        match &previous.outcome {
+            PreviousRunOutcome::Stopped(reason) => info!("Previous run stopped: {:?}", reason),
+            PreviousRunOutcome::Crashed => match &previous.panic {
+                Some(panic) => warn!("Previous run crashed: {}", panic),
+                None => warn!("Previous run ended without shutting down"),
+            },
+        }
+    }
+
+    let state = RunState {
+        started: now(),
+        ..Default::default()
+    };
+    if let Err(e) = write_state(&path, &state) {
+        warn!("Unable to record run state: {}", e);
+    }
+    let _ = RUN_STATE_PATH.set(path);
+
+    previous
+}
+
+/// Records why the daemon is stopping.
+pub fn end_run(reason: ShutdownReason) {
+    update_state(|state| {
+        state.stopped = Some(now());
+        state.reason = Some(reason);
+    });
+}
+
+/// Adds to the existing panic hook (set by log_panics), so the first panic of a run is recorded.
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let message = info.to_string().replace('\n', " ");
+        update_state(|state| {
+            if state.panic.is_none() {
+                state.panic = Some(PanicRecord {
+                    time: now(),
+                    message,
+                    backtrace: Backtrace::force_capture().to_string(),
+                });
+            }
+        });
+        previous(info);
+    }));
+}
+
+fn classify(state: RunState) -> PreviousRun {
+    let ended = match (state.stopped, &state.panic) {
+        (Some(stopped), _) => Some(stopped),
+        (None, Some(panic)) => Some(panic.time),
+        (None, None) => None,
+    };
+
+    PreviousRun {
+        outcome: match state.reason {
+            Some(reason) => PreviousRunOutcome::Stopped(reason),
+            None => PreviousRunOutcome::Crashed,
+        },
+        duration_secs: ended.map(|ended| ended.saturating_sub(state.started)),
+        panic: state.panic.map(|panic| panic.message),
+    }
+}
+
+fn update_state(update: impl FnOnce(&mut RunState)) {
+    let path = match RUN_STATE_PATH.get() {
+        Some(path) => path,
+        None => return,
+    };
+
+    let mut state = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    update(&mut state);
+
+    if let Err(e) = write_state(path, &state) {
+        warn!("Unable to record run state: {}", e);
+    }
+}
+
+fn write_state(path: &Path, state: &RunState) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,159 @@
+use crate::SettingsHandle;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Days, Local, NaiveTime};
+use goxlr_ipc::{GoXLRCommand, Schedule, ScheduledCommand};
+use log::{debug, info, warn};
+
+/// Adds (or replaces) a scheduled command, the command itself isn't checked until it runs, at
+/// which point it goes through the same path as any other command.
+pub async fn add_schedule(
+    settings: &SettingsHandle,
+    id: String,
+    when: Schedule,
+    serial: String,
+    command: GoXLRCommand,
+) -> Result<()> {
+    if id.is_empty() {
+        bail!("Scheduled commands require an ID");
+    }
+
+    if let Schedule::DailyAt(time) = &when {
+        if time.hour > 23 || time.minute > 59 {
+            bail!("Invalid Time: {:02}:{:02}", time.hour, time.minute);
+        }
+    }
+
+    let next_run = match get_next_run(&when, Local::now()) {
+        Some(next_run) => next_run,
+        None => bail!("Unable to determine when the command should run"),
+    };
+
+    info!("Scheduling {:?} on {} ({:?})", command, serial, when);
+    let schedule = ScheduledCommand {
+        serial,
+        schedule: when,
+        command,
+        next_run: Some(next_run),
+        last_run: None,
+        last_error: None,
+    };
+    settings.set_schedule(id, schedule).await;
+    settings.save().await;
+    Ok(())
+}
+
+pub async fn cancel_schedule(settings: &SettingsHandle, id: &str) -> Result<()> {
+    if !settings.remove_schedule(id).await {
+        bail!("Scheduled Command {} not found", id);
+    }
+    settings.save().await;
+    Ok(())
+}
+
+/// Called when the daemon starts, anything which should have run while we weren't around is
+/// not run late, one-shot schedules are dropped and repeating ones wait for their next run.
+pub async fn rearm_schedules(settings: &SettingsHandle) {
+    let now = Local::now();
+    let mut changed = false;
+
+    for (id, mut schedule) in settings.get_schedules().await {
+        match schedule.next_run {
+            Some(next_run) if next_run <= now.timestamp() => {}
+            _ => continue,
+        }
+
+        if is_repeating(&schedule.schedule) {
+            debug!("Skipping missed run of Scheduled Command {}", id);
+            schedule.next_run = get_next_run(&schedule.schedule, now);
+            settings.set_schedule(id, schedule).await;
+        } else {
+            info!("Scheduled Command {} was missed, removing", id);
+            settings.remove_schedule(&id).await;
+        }
+        changed = true;
+    }
+
+    if changed {
+        settings.save().await;
+    }
+}
+
+/// Returns the ID, serial and command of any schedules which are due, and moves them on to
+/// their next run.
+pub async fn take_due_schedules(settings: &SettingsHandle) -> Vec<(String, String, GoXLRCommand)> {
+    let now = Local::now();
+    let mut due = vec![];
+
+    for (id, mut schedule) in settings.get_schedules().await {
+        match schedule.next_run {
+            Some(next_run) if next_run <= now.timestamp() => {}
+            _ => continue,
+        }
+
+        schedule.last_run = Some(now.timestamp());
+        schedule.next_run = match is_repeating(&schedule.schedule) {
+            true => get_next_run(&schedule.schedule, now),
+            false => None,
+        };
+
+        due.push((
+            id.clone(),
+            schedule.serial.clone(),
+            schedule.command.clone(),
+        ));
+        settings.set_schedule(id, schedule).await;
+    }
+    due
+}
+
+/// One-shot schedules are removed once they've run successfully, a failed one is kept so that
+/// the error can be seen until the schedule is cancelled.
+pub async fn record_schedule_result(settings: &SettingsHandle, id: &str, result: &Result<()>) {
+    let mut schedule = match settings.get_schedules().await.remove(id) {
+        Some(schedule) => schedule,
+        None => return,
+    };
+
+    match result {
+        Ok(()) => {
+            if !is_repeating(&schedule.schedule) {
+                settings.remove_schedule(id).await;
+                return;
+            }
+            schedule.last_error = None;
+        }
+        Err(error) => {
+            warn!("Scheduled Command {} failed: {}", id, error);
+            schedule.last_error = Some(error.to_string());
+        }
+    }
+    settings.set_schedule(id.to_string(), schedule).await;
+}
+
+fn is_repeating(schedule: &Schedule) -> bool {
+    match schedule {
+        Schedule::In(_) => false,
+        Schedule::DailyAt(_) => true,
+    }
+}
+
+/// Works out the next time (as a unix timestamp) after 'now' that the schedule should run.
+fn get_next_run(schedule: &Schedule, now: DateTime<Local>) -> Option<i64> {
+    match schedule {
+        Schedule::In(duration) => Some(now.timestamp() + duration.as_secs() as i64),
+        Schedule::DailyAt(time) => {
+            let time = NaiveTime::from_hms_opt(time.hour as u32, time.minute as u32, 0)?;
+
+            // The time may not exist on a day the clocks go forward, so look a little further.
+            for days in 0..=2 {
+                let date = now.date_naive().checked_add_days(Days::new(days))?;
+                if let Some(run) = date.and_time(time).and_local_timezone(Local).earliest() {
+                    if run > now {
+                        return Some(run.timestamp());
+                    }
+                }
+            }
+            None
+        }
+    }
+}
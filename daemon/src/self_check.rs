@@ -0,0 +1,292 @@
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use goxlr_ipc::StartupWarning;
+use goxlr_usb::device::{check_usb_access, UsbAccess};
+use log::{debug, error, info, warn};
+
+use crate::servers::ipc_server::get_socket_path;
+use crate::SettingsHandle;
+
+// The places udev rules are normally installed to
+const UDEV_RULE_DIRECTORIES: [&str; 3] = [
+    "/etc/udev/rules.d",
+    "/usr/lib/udev/rules.d",
+    "/lib/udev/rules.d",
+];
+
+// Created (and immediately removed) to check a directory is writable, the process ID is added
+// so this can't collide with a file left by someone else in a shared directory.
+const WRITE_TEST_FILE: &str = ".goxlr-write-test";
+
+/// A problem found by one of the checks, fatal problems will stop the daemon from starting.
+#[derive(Debug)]
+pub struct CheckIssue {
+    pub check: String,
+    pub message: String,
+    pub fatal: bool,
+}
+
+impl CheckIssue {
+    fn new(check: &str, message: String, fatal: bool) -> Self {
+        Self {
+            check: check.to_string(),
+            message,
+            fatal,
+        }
+    }
+}
+
+/// Checks that everything the daemon needs is present and usable before any of the workers are
+/// started. If anything fatal is found, the error contains a report of every problem, otherwise
+/// the non-fatal problems are returned to be shown to the user.
+pub async fn perform_self_check(settings: &SettingsHandle) -> Result<Vec<StartupWarning>> {
+    info!("Performing Startup Self Check...");
+    let mut issues = vec![];
+
+    // In read-only mode nothing is written, so it doesn't matter if we can't.
+    if !settings.write_policy().is_read_only() {
+        if let Some(config) = settings.get_config_path().parent() {
+            issues.extend(check_directory("Configuration Directory", config, true));
+        }
+
+        let directories = [
+            ("Profile Directory", settings.get_profile_directory().await),
+            (
+                "Mic Profile Directory",
+                settings.get_mic_profile_directory().await,
+            ),
+            ("Samples Directory", settings.get_samples_directory().await),
+            ("Presets Directory", settings.get_presets_directory().await),
+            ("Icons Directory", settings.get_icons_directory().await),
+        ];
+        for (check, path) in directories {
+            issues.extend(check_directory(check, &path, false));
+        }
+    }
+
+    if let Some(socket_path) = get_socket_path() {
+        issues.extend(check_socket_path(socket_path));
+    }
+
+    let udev_directories: Vec<PathBuf> = match cfg!(target_os = "linux") {
+        true => UDEV_RULE_DIRECTORIES.iter().map(PathBuf::from).collect(),
+        false => vec![],
+    };
+    issues.extend(check_usb(check_usb_access(), &udev_directories));
+
+    if issues.is_empty() {
+        debug!("Self Check Passed");
+        return Ok(vec![]);
+    }
+
+    for issue in &issues {
+        if issue.fatal {
+            error!("Self Check - {}: {}", issue.check, issue.message);
+        } else {
+            warn!("Self Check - {}: {}", issue.check, issue.message);
+        }
+    }
+
+    if issues.iter().any(|issue| issue.fatal) {
+        bail!("{}", get_report(&issues));
+    }
+
+    Ok(issues
+        .into_iter()
+        .map(|issue| StartupWarning {
+            check: issue.check,
+            message: issue.message,
+        })
+        .collect())
+}
+
+/// Produces a single message describing every problem, to be shown to the user.
+pub fn get_report(issues: &[CheckIssue]) -> String {
+    let mut report = String::from("The following problems were found:\r\n");
+    for issue in issues {
+        report.push_str(&format!("\r\n{}: {}", issue.check, issue.message));
+    }
+    report
+}
+
+pub fn check_directory(check: &str, path: &Path, fatal: bool) -> Option<CheckIssue> {
+    if !path.exists() {
+        let message = format!("{} does not exist", path.to_string_lossy());
+        return Some(CheckIssue::new(check, message, fatal));
+    }
+
+    if !path.is_dir() {
+        let message = format!("{} is not a directory", path.to_string_lossy());
+        return Some(CheckIssue::new(check, message, fatal));
+    }
+
+    if let Err(e) = check_writable(path) {
+        let message = match e.kind() {
+            ErrorKind::PermissionDenied => format!("{} is not writable", path.to_string_lossy()),
+            _ => format!("Unable to write to {}: {}", path.to_string_lossy(), e),
+        };
+        return Some(CheckIssue::new(check, message, fatal));
+    }
+    None
+}
+
+/// The socket is created when the IPC server starts, so we check that it can be, and that one
+/// left behind by another user won't be in the way.
+pub fn check_socket_path(path: &Path) -> Option<CheckIssue> {
+    let check = "IPC Socket";
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = check_writable(parent) {
+            let message = format!(
+                "Unable to create {} in {}: {}",
+                path.to_string_lossy(),
+                parent.to_string_lossy(),
+                e
+            );
+            return Some(CheckIssue::new(check, message, true));
+        }
+    }
+
+    #[cfg(unix)]
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        use std::os::unix::fs::MetadataExt;
+
+        if metadata.uid() != nix::unistd::Uid::effective().as_raw() {
+            let message = format!(
+                "{} belongs to another user, another GoXLR Utility may be running as them",
+                path.to_string_lossy()
+            );
+            return Some(CheckIssue::new(check, message, true));
+        }
+    }
+    None
+}
+
+/// Checks whether an attached GoXLR can be used, if access was denied the udev directories
+/// (if any) are checked to see whether the rules have been installed.
+pub fn check_usb(access: UsbAccess, udev_directories: &[PathBuf]) -> Option<CheckIssue> {
+    let check = "USB Access";
+
+    match access {
+        UsbAccess::NotConnected => {
+            debug!("No GoXLR Devices Found");
+            None
+        }
+        UsbAccess::Available => None,
+        UsbAccess::PermissionDenied => {
+            let mut message = String::from("A GoXLR was found, but access to it was denied.");
+            if !udev_directories.is_empty() {
+                if has_udev_rule(udev_directories) {
+                    message.push_str(" The udev rules are installed, try reconnecting the GoXLR.");
+                } else {
+                    message.push_str(
+                        " The udev rules don't appear to be installed, please consult the \
+                        'Permissions' section of the README.",
+                    );
+                }
+            }
+            Some(CheckIssue::new(check, message, false))
+        }
+        UsbAccess::Failed(e) => {
+            let message = format!("Unable to check USB Devices: {}", e);
+            Some(CheckIssue::new(check, message, false))
+        }
+    }
+}
+
+pub fn has_udev_rule(directories: &[PathBuf]) -> bool {
+    directories.iter().any(|directory| {
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return false,
+        };
+
+        entries.flatten().any(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            name.contains("goxlr") && name.ends_with(".rules")
+        })
+    })
+}
+
+fn check_writable(path: &Path) -> std::io::Result<()> {
+    let file = path.join(format!("{}-{}", WRITE_TEST_FILE, std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&file)?;
+    fs::remove_file(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_must_exist_and_be_writable() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(check_directory("Test", root.path(), true).is_none());
+
+        // Nothing is left behind by the write test
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+
+        let missing = check_directory("Test", &root.path().join("missing"), true).unwrap();
+        assert!(missing.message.ends_with("does not exist"));
+        assert!(missing.fatal);
+
+        let file = root.path().join("file");
+        fs::write(&file, "").unwrap();
+        let not_directory = check_directory("Test", &file, false).unwrap();
+        assert!(not_directory.message.ends_with("is not a directory"));
+        assert!(!not_directory.fatal);
+    }
+
+    #[test]
+    fn sockets_need_a_writable_directory() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(check_socket_path(&root.path().join("goxlr.socket")).is_none());
+
+        let issue = check_socket_path(&root.path().join("missing/goxlr.socket")).unwrap();
+        assert_eq!(issue.check, "IPC Socket");
+        assert!(issue.fatal);
+    }
+
+    #[test]
+    fn denied_usb_access_looks_for_the_udev_rules() {
+        assert!(check_usb(UsbAccess::NotConnected, &[]).is_none());
+        assert!(check_usb(UsbAccess::Available, &[]).is_none());
+
+        let rules = tempfile::tempdir().unwrap();
+        let directories = vec![rules.path().join("missing"), rules.path().to_path_buf()];
+        let missing = check_usb(UsbAccess::PermissionDenied, &directories).unwrap();
+        assert!(missing.message.contains("don't appear to be installed"));
+        assert!(!missing.fatal);
+
+        fs::write(rules.path().join("50-GoXLR.rules"), "").unwrap();
+        assert!(has_udev_rule(&directories));
+        let installed = check_usb(UsbAccess::PermissionDenied, &directories).unwrap();
+        assert!(installed.message.contains("try reconnecting"));
+
+        // Without anywhere to look (not Linux), only the denial is reported
+        let elsewhere = check_usb(UsbAccess::PermissionDenied, &[]).unwrap();
+        assert!(elsewhere.message.ends_with("access to it was denied."));
+
+        let failed = check_usb(UsbAccess::Failed(String::from("Busy")), &[]).unwrap();
+        assert_eq!(failed.message, "Unable to check USB Devices: Busy");
+    }
+
+    #[test]
+    fn every_issue_is_reported() {
+        let issues = [
+            CheckIssue::new("First", String::from("One"), true),
+            CheckIssue::new("Second", String::from("Two"), false),
+        ];
+        assert_eq!(
+            get_report(&issues),
+            "The following problems were found:\r\n\r\nFirst: One\r\nSecond: Two"
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use chrono::Local;
+use goxlr_ipc::{
+    ChannelClaim, ClaimOverride, ClientTransport, ConnectedClient, DaemonRequest, DaemonResponse,
+    GoXLRCommand,
+};
+use goxlr_types::ChannelName;
+use log::info;
+use tokio::sync::Notify;
+
+use crate::BroadcastEvent;
+
+// HTTP clients never connect, so they're listed until they've been quiet for this long
+const HTTP_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Everything currently talking to the daemon, shared between the IPC and HTTP servers so that
+/// any client can list (or disconnect) the others. It also holds the channels clients have
+/// claimed, which go with them when they disconnect.
+#[derive(Default)]
+pub struct ClientRegistry {
+    next_id: AtomicU64,
+    clients: Mutex<HashMap<u64, Client>>,
+    claims: Mutex<Vec<ChannelClaim>>,
+
+    // Woken whenever a claim is added or removed, so the status can be updated
+    claims_changed: Notify,
+}
+
+struct Client {
+    details: ConnectedClient,
+    last_seen: Instant,
+    disconnect: Arc<Notify>,
+}
+
+impl ClientRegistry {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registers an IPC or websocket connection, which is removed when the session is dropped.
+    pub fn connect(
+        self: &Arc<Self>,
+        transport: ClientTransport,
+        address: Option<String>,
+    ) -> ClientSession {
+        let (id, disconnect) = self.insert(transport, address);
+        info!("Client {} connected over {:?}", id, transport);
+
+        ClientSession {
+            registry: self.clone(),
+            id,
+            disconnect,
+            connected: true,
+        }
+    }
+
+    /// HTTP clients are known by their address, every request from the same address is counted
+    /// against the same client.
+    pub fn http(self: &Arc<Self>, peer: Option<IpAddr>) -> ClientSession {
+        let address = peer.map(|peer| peer.to_string());
+
+        let existing = {
+            let mut clients = self.clients.lock().unwrap();
+            prune_http(&mut clients);
+            clients
+                .iter()
+                .find(|(_, client)| {
+                    client.details.transport == ClientTransport::Http
+                        && client.details.address == address
+                })
+                .map(|(id, client)| (*id, client.disconnect.clone()))
+        };
+
+        let (id, disconnect) = match existing {
+            Some(existing) => existing,
+            None => self.insert(ClientTransport::Http, address),
+        };
+
+        ClientSession {
+            registry: self.clone(),
+            id,
+            disconnect,
+            connected: false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<ConnectedClient> {
+        let mut clients = self.clients.lock().unwrap();
+        prune_http(&mut clients);
+
+        let mut list: Vec<ConnectedClient> = clients
+            .values()
+            .map(|client| client.details.clone())
+            .collect();
+        list.sort_by_key(|client| client.id);
+        list
+    }
+
+    /// Asks a connection to close, it's removed from the registry once it has.
+    pub fn disconnect(&self, id: u64) -> Result<()> {
+        let clients = self.clients.lock().unwrap();
+        let Some(client) = clients.get(&id) else {
+            bail!("There is no client with the id {}", id);
+        };
+
+        if client.details.transport == ClientTransport::Http {
+            bail!("HTTP clients don't hold a connection open, so can't be disconnected");
+        }
+
+        info!("Disconnecting client {}", id);
+        client.disconnect.notify_one();
+        Ok(())
+    }
+
+    /// Claims a channel for a client, or renames its existing claim. A channel can only be
+    /// claimed by one client at a time.
+    pub fn claim(
+        &self,
+        client_id: u64,
+        serial: &str,
+        channel: ChannelName,
+        claimant: String,
+    ) -> Result<()> {
+        let transport = self
+            .clients
+            .lock()
+            .unwrap()
+            .get(&client_id)
+            .map(|client| client.details.transport);
+        if transport == Some(ClientTransport::Http) {
+            bail!("HTTP clients don't stay connected, so can't claim channels");
+        }
+
+        let mut claims = self.claims.lock().unwrap();
+        let existing = claims
+            .iter_mut()
+            .find(|claim| claim.serial == serial && claim.channel == channel);
+
+        match existing {
+            Some(claim) if claim.client_id != client_id => {
+                bail!("{:?} is already claimed by {}", channel, claim.claimant);
+            }
+            Some(claim) => claim.claimant = claimant,
+            None => {
+                info!("Client {} claimed {:?} on {}", client_id, channel, serial);
+                claims.push(ChannelClaim {
+                    serial: serial.to_owned(),
+                    channel,
+                    claimant,
+                    client_id,
+                    claimed_at: Local::now().timestamp(),
+                });
+            }
+        }
+        self.claims_changed.notify_one();
+        Ok(())
+    }
+
+    pub fn release(&self, client_id: u64, serial: &str, channel: ChannelName) -> Result<()> {
+        let mut claims = self.claims.lock().unwrap();
+        let Some(index) = claims
+            .iter()
+            .position(|claim| claim.serial == serial && claim.channel == channel)
+        else {
+            bail!("{:?} isn't claimed", channel);
+        };
+
+        if claims[index].client_id != client_id {
+            bail!("{:?} is claimed by {}", channel, claims[index].claimant);
+        }
+
+        claims.remove(index);
+        self.claims_changed.notify_one();
+        Ok(())
+    }
+
+    pub fn claims(&self) -> Vec<ChannelClaim> {
+        self.claims.lock().unwrap().clone()
+    }
+
+    /// Completes once a claim has been added or removed since the last call.
+    pub async fn claims_changed(&self) {
+        self.claims_changed.notified().await;
+    }
+
+    /// Finds the claims a command from this client overrides, given the channels it changed.
+    /// Commands which didn't come from a client (such as MIDI) aren't reported.
+    pub fn overrides(
+        &self,
+        client_id: Option<u64>,
+        serial: &str,
+        channels: &[ChannelName],
+        command: &GoXLRCommand,
+    ) -> Vec<ClaimOverride> {
+        let Some(client_id) = client_id else {
+            return vec![];
+        };
+        let Some(overridden_by) = self
+            .clients
+            .lock()
+            .unwrap()
+            .get(&client_id)
+            .map(|client| client.details.clone())
+        else {
+            return vec![];
+        };
+
+        self.claims
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|claim| claim.serial == serial && claim.client_id != client_id)
+            .filter(|claim| channels.contains(&claim.channel))
+            .map(|claim| ClaimOverride {
+                claim: claim.clone(),
+                overridden_by: overridden_by.clone(),
+                command: command.clone(),
+            })
+            .collect()
+    }
+
+    fn insert(&self, transport: ClientTransport, address: Option<String>) -> (u64, Arc<Notify>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let disconnect = Arc::new(Notify::new());
+
+        let client = Client {
+            details: ConnectedClient {
+                id,
+                transport,
+                address,
+                connected_at: Local::now().timestamp(),
+                requests: 0,
+                commands: 0,
+                protocol: None,
+            },
+            last_seen: Instant::now(),
+            disconnect: disconnect.clone(),
+        };
+        self.clients.lock().unwrap().insert(id, client);
+        (id, disconnect)
+    }
+
+    fn remove(&self, id: u64) {
+        if self.clients.lock().unwrap().remove(&id).is_some() {
+            info!("Client {} disconnected", id);
+        }
+
+        let mut claims = self.claims.lock().unwrap();
+        let count = claims.len();
+        claims.retain(|claim| claim.client_id != id);
+        if claims.len() != count {
+            self.claims_changed.notify_one();
+        }
+    }
+}
+
+/// What (if anything) a client should be sent for an event on the broadcast bus.
+pub fn event_for_client(
+    event: BroadcastEvent,
+    client_id: u64,
+    notifications_only: bool,
+) -> Option<DaemonResponse> {
+    match event {
+        BroadcastEvent::Patch(_) if notifications_only => None,
+        BroadcastEvent::Patch(patch) => Some(DaemonResponse::Patch(patch)),
+        BroadcastEvent::Notification(notification) => {
+            Some(DaemonResponse::Notification(notification))
+        }
+        BroadcastEvent::ButtonEvent(_) if notifications_only => None,
+        BroadcastEvent::ButtonEvent(event) => Some(DaemonResponse::ButtonEvent(event)),
+        BroadcastEvent::ClaimOverridden(claim_override) => (claim_override.claim.client_id
+            == client_id)
+            .then_some(DaemonResponse::ClaimOverridden(claim_override)),
+    }
+}
+
+fn prune_http(clients: &mut HashMap<u64, Client>) {
+    clients.retain(|_, client| {
+        client.details.transport != ClientTransport::Http
+            || client.last_seen.elapsed() < HTTP_IDLE_TIMEOUT
+    });
+}
+
+/// A single client's view of the registry, used to count its requests and to find out when it's
+/// been told to disconnect.
+pub struct ClientSession {
+    registry: Arc<ClientRegistry>,
+    id: u64,
+    disconnect: Arc<Notify>,
+
+    // Connections leave the registry when they close, HTTP clients only once they're idle
+    connected: bool,
+}
+
+impl ClientSession {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn registry(&self) -> &ClientRegistry {
+        &self.registry
+    }
+
+    pub fn record(&self, request: &DaemonRequest) {
+        let mut clients = self.registry.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(&self.id) {
+            client.details.requests += 1;
+            if matches!(
+                request,
+                DaemonRequest::Command(..) | DaemonRequest::Daemon(..)
+            ) {
+                client.details.commands += 1;
+            }
+            client.last_seen = Instant::now();
+        }
+    }
+
+    pub fn set_protocol(&self, protocol: u32) {
+        let mut clients = self.registry.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(&self.id) {
+            client.details.protocol = Some(protocol);
+        }
+    }
+
+    /// The protocol the client said it speaks, if it has.
+    pub fn protocol(&self) -> Option<u32> {
+        let clients = self.registry.clients.lock().unwrap();
+        clients
+            .get(&self.id)
+            .and_then(|client| client.details.protocol)
+    }
+
+    /// Completes once DisconnectClient has been called for this session.
+    pub async fn disconnected(&self) {
+        self.disconnect.notified().await;
+    }
+
+    /// As above, for when the wait needs to outlive a borrow of the session.
+    pub fn disconnect_signal(&self) -> Arc<Notify> {
+        self.disconnect.clone()
+    }
+}
+
+impl Drop for ClientSession {
+    fn drop(&mut self) {
+        if self.connected {
+            self.registry.remove(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goxlr_ipc::{ButtonEvent, DaemonCommand};
+    use goxlr_types::Button;
+    use std::net::Ipv4Addr;
+
+    fn transports(registry: &ClientRegistry) -> Vec<ClientTransport> {
+        registry
+            .list()
+            .iter()
+            .map(|client| client.transport)
+            .collect()
+    }
+
+    #[test]
+    fn sessions_are_removed_when_they_close() {
+        let registry = ClientRegistry::new();
+        let ipc = registry.connect(ClientTransport::Ipc, None);
+        let websocket = registry.connect(
+            ClientTransport::WebSocket,
+            Some(String::from("192.168.0.2")),
+        );
+        assert_eq!(
+            transports(&registry),
+            vec![ClientTransport::Ipc, ClientTransport::WebSocket]
+        );
+        assert_ne!(ipc.id(), websocket.id());
+
+        drop(ipc);
+        let clients = registry.list();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].id, websocket.id());
+        assert_eq!(clients[0].address.as_deref(), Some("192.168.0.2"));
+
+        drop(websocket);
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn requests_and_commands_are_counted() {
+        let registry = ClientRegistry::new();
+        let session = registry.connect(ClientTransport::Ipc, None);
+        session.record(&DaemonRequest::GetStatus);
+        session.record(&DaemonRequest::Daemon(DaemonCommand::StopDaemon));
+
+        let client = &registry.list()[0];
+        assert_eq!(client.requests, 2);
+        assert_eq!(client.commands, 1);
+    }
+
+    #[test]
+    fn http_clients_are_known_by_address() {
+        let registry = ClientRegistry::new();
+        let peer = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)));
+
+        let first = registry.http(peer);
+        first.record(&DaemonRequest::GetStatus);
+        drop(first);
+
+        // Still listed after the request, and the next one is counted against the same client
+        let second = registry.http(peer);
+        second.record(&DaemonRequest::GetStatus);
+        let other = registry.http(Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+
+        let clients = registry.list();
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[0].id, second.id());
+        assert_eq!(clients[0].requests, 2);
+        assert_eq!(clients[1].id, other.id());
+
+        assert!(registry.disconnect(second.id()).is_err());
+    }
+
+    #[tokio::test]
+    async fn claims_are_released_when_the_client_disconnects() {
+        let registry = ClientRegistry::new();
+        let media = registry.connect(ClientTransport::Ipc, None);
+        let other = registry.connect(ClientTransport::WebSocket, None);
+
+        let label = String::from("MediaSync");
+        registry
+            .claim(media.id(), "S1", ChannelName::Music, label.clone())
+            .unwrap();
+        assert!(registry
+            .claim(other.id(), "S1", ChannelName::Music, String::from("Other"))
+            .is_err());
+        assert!(registry
+            .release(other.id(), "S1", ChannelName::Music)
+            .is_err());
+
+        // The same channel on another device is free to claim
+        registry
+            .claim(other.id(), "S2", ChannelName::Music, String::from("Other"))
+            .unwrap();
+
+        // HTTP clients would never let theirs go
+        let http = registry.http(None);
+        assert!(registry
+            .claim(http.id(), "S1", ChannelName::Game, label)
+            .is_err());
+
+        let claims = registry.claims();
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[0].client_id, media.id());
+        assert_eq!(claims[0].claimant, "MediaSync");
+
+        // Take the notification for the claims made so far, the next is for the disconnect
+        registry.claims_changed().await;
+        drop(media);
+        tokio::time::timeout(Duration::from_secs(1), registry.claims_changed())
+            .await
+            .expect("The claims weren't updated when the client left");
+
+        let claims = registry.claims();
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].client_id, other.id());
+    }
+
+    #[test]
+    fn overrides_are_only_sent_to_the_claimant() {
+        let registry = ClientRegistry::new();
+        let media = registry.connect(ClientTransport::Ipc, None);
+        let web_ui = registry.connect(ClientTransport::WebSocket, None);
+        let watcher = registry.connect(ClientTransport::Ipc, None);
+        registry
+            .claim(
+                media.id(),
+                "S1",
+                ChannelName::Music,
+                String::from("MediaSync"),
+            )
+            .unwrap();
+
+        let music = [ChannelName::Music];
+        let command = GoXLRCommand::SetVolume(ChannelName::Music, 10);
+
+        // The claimant changing its own channel, changes to other channels or devices, and
+        // commands from MIDI aren't overrides
+        assert!(registry
+            .overrides(Some(media.id()), "S1", &music, &command)
+            .is_empty());
+        assert!(registry
+            .overrides(Some(web_ui.id()), "S1", &[ChannelName::Game], &command)
+            .is_empty());
+        assert!(registry
+            .overrides(Some(web_ui.id()), "S2", &music, &command)
+            .is_empty());
+        assert!(registry.overrides(None, "S1", &music, &command).is_empty());
+
+        let mut overrides = registry.overrides(Some(web_ui.id()), "S1", &music, &command);
+        assert_eq!(overrides.len(), 1);
+        let claim_override = overrides.remove(0);
+        assert_eq!(claim_override.claim.claimant, "MediaSync");
+        assert_eq!(claim_override.overridden_by.id, web_ui.id());
+
+        let event = BroadcastEvent::ClaimOverridden(claim_override);
+        for (client, expected) in [(&media, true), (&web_ui, false), (&watcher, false)] {
+            let response = event_for_client(event.clone(), client.id(), true);
+            assert_eq!(
+                matches!(response, Some(DaemonResponse::ClaimOverridden(_))),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn button_events_go_to_every_subscriber() {
+        let event = BroadcastEvent::ButtonEvent(ButtonEvent {
+            serial: String::from("S1"),
+            button: Button::Cough,
+        });
+
+        // Clients which only asked for notifications don't get them
+        for (notifications_only, expected) in [(false, true), (true, false)] {
+            let response = event_for_client(event.clone(), 1, notifications_only);
+            assert_eq!(
+                matches!(response, Some(DaemonResponse::ButtonEvent(_))),
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn connections_can_be_disconnected() {
+        let registry = ClientRegistry::new();
+        let session = registry.connect(ClientTransport::WebSocket, None);
+        assert!(registry.disconnect(session.id() + 1).is_err());
+
+        registry.disconnect(session.id()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), session.disconnected())
+            .await
+            .expect("The session wasn't told to disconnect");
+
+        // It's only removed once the connection has actually gone
+        assert_eq!(registry.list().len(), 1);
+        drop(session);
+        assert!(registry.list().is_empty());
+    }
+}
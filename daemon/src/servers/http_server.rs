@@ -1,22 +1,23 @@
 use std::collections::HashMap;
 use std::fs;
+use std::net::IpAddr;
 use std::ops::DerefMut;
 use std::path::{Component, PathBuf};
+use std::sync::Arc;
 
 use actix::{
-    Actor, ActorContext, AsyncContext, ContextFutureSpawner, Handler, Message, StreamHandler,
-    WrapFuture,
+    Actor, ActorContext, ActorFutureExt, AsyncContext, ContextFutureSpawner, Handler, Message,
+    StreamHandler, WrapFuture,
 };
 use actix_cors::Cors;
 use actix_web::dev::ServerHandle;
-use actix_web::http::header::ContentType;
+use actix_web::http::header::{ContentType, RETRY_AFTER};
 use actix_web::middleware::Condition;
 use actix_web::web::Data;
-use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer};
+use actix_web::{delete, get, post, web, App, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
 use actix_web_actors::ws::{CloseCode, CloseReason};
 use anyhow::{anyhow, Result};
-use include_dir::{include_dir, Dir};
 use jsonpath_rust::JsonPathQuery;
 use log::{debug, error, info, warn};
 use mime_guess::mime::IMAGE_PNG;
@@ -24,24 +25,32 @@ use mime_guess::MimeGuess;
 use serde_json::Value;
 use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio::sync::oneshot::Sender;
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, watch, Mutex};
 
 use crate::files::{find_file_in_path, FilePaths};
-use crate::PatchEvent;
+use crate::icons::MAX_ICON_SIZE;
+use crate::BroadcastEvent;
 use goxlr_ipc::{
-    DaemonRequest, DaemonResponse, DaemonStatus, HttpSettings, WebsocketRequest, WebsocketResponse,
+    unsupported_request, ClientTransport, DaemonRequest, DaemonResponse, DaemonStatus,
+    HttpRateLimit, HttpSettings, WebsocketRequest, WebsocketResponse, PROTOCOL_VERSION,
+    STATUS_VERSION,
 };
-use goxlr_scribbles::get_scribble_png;
+use goxlr_scribbles::{get_scribble_png, ScribbleIcon};
 use goxlr_types::FaderName;
 
-use crate::primary_worker::DeviceSender;
+use crate::primary_worker::{DeviceCommand, DeviceSender};
+use crate::servers::clients::{event_for_client, ClientRegistry, ClientSession};
+use crate::servers::rate_limit::{RateLimited, RateLimiter, RequestKind};
 use crate::servers::server_packet::handle_packet;
-
-const WEB_CONTENT: Dir = include_dir!("./daemon/web-content/");
+use crate::servers::web_content::get_ui_file;
+use crate::VERSION;
 
 struct Websocket {
     usb_tx: DeviceSender,
-    broadcast_tx: BroadcastSender<PatchEvent>,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
+    rate_limiter: Data<RateLimiter>,
+    peer: Option<IpAddr>,
+    client: Arc<ClientSession>,
 }
 
 impl Actor for Websocket {
@@ -50,17 +59,22 @@ impl Actor for Websocket {
     fn started(&mut self, ctx: &mut Self::Context) {
         let address = ctx.address();
         let mut broadcast_rx = self.broadcast_tx.subscribe();
+        let client_id = self.client.id();
 
         // Create a future that simply monitors the global broadcast bus, and pushes any changes
         // out to the WebSocket.
         let future = Box::pin(async move {
             loop {
                 if let Ok(event) = broadcast_rx.recv().await {
+                    let Some(data) = event_for_client(event, client_id, false) else {
+                        continue;
+                    };
+
                     // We've received a message, attempt to trigger the WsMessage Handle..
-                    if let Err(error) = address.clone().try_send(WsResponse(WebsocketResponse {
-                        id: u64::MAX,
-                        data: DaemonResponse::Patch(event.data),
-                    })) {
+                    if let Err(error) = address
+                        .clone()
+                        .try_send(WsResponse(WebsocketResponse { id: u64::MAX, data }))
+                    {
                         error!(
                             "Error Occurred when sending message to websocket: {:?}",
                             error
@@ -74,6 +88,19 @@ impl Actor for Websocket {
 
         let future = future.into_actor(self);
         ctx.spawn(future);
+
+        // Close the connection if another client asks for it to be disconnected
+        let disconnect = self.client.disconnect_signal();
+        let future = async move { disconnect.notified().await }
+            .into_actor(self)
+            .map(|_, _, ctx: &mut Self::Context| {
+                ctx.close(Some(CloseReason {
+                    code: CloseCode::Normal,
+                    description: Some(String::from("Disconnected by another client")),
+                }));
+                ctx.stop();
+            });
+        ctx.spawn(future);
     }
 }
 
@@ -99,46 +126,175 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Websocket {
                 match serde_json::from_slice::<WebsocketRequest>(text.as_ref()) {
                     Ok(request) => {
                         let recipient = ctx.address().recipient();
+                        let kind = RequestKind::of(&request.data);
+                        let in_flight = match self.rate_limiter.check(self.peer, kind) {
+                            Ok(in_flight) => in_flight,
+                            Err(limited) => {
+                                recipient.do_send(WsResponse(WebsocketResponse {
+                                    id: request.id,
+                                    data: DaemonResponse::Error(limited.to_string()),
+                                }));
+                                return;
+                            }
+                        };
+
                         let mut usb_tx = self.usb_tx.clone();
-                        let future = async move {
-                            let request_id = request.id;
-                            let result = handle_packet(request.data, &mut usb_tx).await;
-                            match result {
-                                Ok(resp) => match resp {
-                                    DaemonResponse::Ok => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::Ok,
-                                        }));
-                                    }
-                                    DaemonResponse::Error(error) => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::Error(error),
-                                        }));
+                        let client = self.client.clone();
+                        let future =
+                            async move {
+                                let _in_flight = in_flight;
+                                let request_id = request.id;
+                                let result =
+                                    handle_packet(request.data, &mut usb_tx, &client).await;
+                                match result {
+                                    Ok(resp) => {
+                                        match resp {
+                                            DaemonResponse::Ok => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Ok,
+                                                }));
+                                            }
+                                            DaemonResponse::Error(error) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Error(error),
+                                                }));
+                                            }
+                                            DaemonResponse::Status(status) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Status(status),
+                                                }));
+                                            }
+                                            DaemonResponse::StatusSchema(schema) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::StatusSchema(schema),
+                                                }));
+                                            }
+                                            DaemonResponse::MicLevel(level) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::MicLevel(level),
+                                                }))
+                                            }
+                                            DaemonResponse::AudioDevices(devices) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::AudioDevices(devices),
+                                                })),
+                                            DaemonResponse::CommandError(error) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::CommandError(error),
+                                                })),
+                                            DaemonResponse::EqCurveImport(report) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::EqCurveImport(report),
+                                                })),
+                                            DaemonResponse::RecoveredDefaults(result) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::RecoveredDefaults(result),
+                                                })),
+                                            DaemonResponse::HardwareState(report) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::HardwareState(report),
+                                                })),
+                                            DaemonResponse::SupportBundle(path) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::SupportBundle(path),
+                                                })),
+                                            DaemonResponse::AdjustedValue(value) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::AdjustedValue(value),
+                                                })),
+                                            DaemonResponse::MuteFunctionReset(fader, function) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::MuteFunctionReset(
+                                                        fader, function,
+                                                    ),
+                                                }))
+                                            }
+                                            DaemonResponse::ReplaySaved(capture) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::ReplaySaved(capture),
+                                                })),
+                                            DaemonResponse::LogLines(lines) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::LogLines(lines),
+                                                }))
+                                            }
+                                            DaemonResponse::Icons(icons) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Icons(icons),
+                                                }))
+                                            }
+                                            DaemonResponse::Icon(icon) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Icon(icon),
+                                                }))
+                                            }
+                                            DaemonResponse::MidiPorts(ports) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::MidiPorts(ports),
+                                                }))
+                                            }
+                                            DaemonResponse::ConnectedClients(clients) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::ConnectedClients(clients),
+                                                })),
+                                            DaemonResponse::StatusSummary(summary) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::StatusSummary(summary),
+                                                })),
+                                            DaemonResponse::ResolvedControl(resolved) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::ResolvedControl(resolved),
+                                                })),
+                                            DaemonResponse::TelemetryPreview(report) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::TelemetryPreview(report),
+                                                })),
+                                            DaemonResponse::Hello(protocol) => {
+                                                recipient.do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::Hello(protocol),
+                                                }))
+                                            }
+                                            DaemonResponse::ModeChangePreview(preview) => recipient
+                                                .do_send(WsResponse(WebsocketResponse {
+                                                    id: request_id,
+                                                    data: DaemonResponse::ModeChangePreview(
+                                                        preview,
+                                                    ),
+                                                })),
+                                            _ => {}
+                                        }
                                     }
-                                    DaemonResponse::Status(status) => {
+                                    Err(error) => {
                                         recipient.do_send(WsResponse(WebsocketResponse {
                                             id: request_id,
-                                            data: DaemonResponse::Status(status),
+                                            data: DaemonResponse::Error(error.to_string()),
                                         }));
                                     }
-                                    DaemonResponse::MicLevel(level) => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::MicLevel(level),
-                                        }))
-                                    }
-                                    _ => {}
-                                },
-                                Err(error) => {
-                                    recipient.do_send(WsResponse(WebsocketResponse {
-                                        id: request_id,
-                                        data: DaemonResponse::Error(error.to_string()),
-                                    }));
                                 }
-                            }
-                        };
+                            };
                         future.into_actor(self).spawn(ctx);
                     }
                     Err(error) => {
@@ -153,10 +309,13 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Websocket {
                         match request {
                             Ok(value) => {
                                 if let Some(request_id) = value["id"].as_u64() {
+                                    let protocol = self.client.protocol();
+                                    let error =
+                                        unsupported_request(&value["data"], protocol, &error);
                                     let recipient = ctx.address().recipient();
                                     recipient.do_send(WsResponse(WebsocketResponse {
                                         id: request_id,
-                                        data: DaemonResponse::Error(error.to_string()),
+                                        data: DaemonResponse::Error(error),
                                     }));
                                 } else {
                                     warn!("id missing, Cannot continue. Closing connection");
@@ -198,17 +357,23 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Websocket {
 
 struct AppData {
     usb_tx: DeviceSender,
-    broadcast_tx: BroadcastSender<PatchEvent>,
-    file_paths: FilePaths,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
+    file_paths: watch::Receiver<FilePaths>,
+    clients: Arc<ClientRegistry>,
 }
 
 pub async fn spawn_http_server(
     usb_tx: DeviceSender,
     handle_tx: Sender<Result<Option<ServerHandle>>>,
-    broadcast_tx: tokio::sync::broadcast::Sender<PatchEvent>,
+    broadcast_tx: tokio::sync::broadcast::Sender<BroadcastEvent>,
     settings: HttpSettings,
-    file_paths: FilePaths,
+    file_paths: watch::Receiver<FilePaths>,
+    rate_limit: watch::Receiver<HttpRateLimit>,
+    clients: Arc<ClientRegistry>,
 ) {
+    // Shared between the workers, so the limits apply to the server as a whole
+    let rate_limiter = Data::new(RateLimiter::new(rate_limit));
+
     let server = HttpServer::new(move || {
         let cors = Cors::default()
             .allowed_origin_fn(|origin, _req_head| {
@@ -224,12 +389,20 @@ pub async fn spawn_http_server(
                 broadcast_tx: broadcast_tx.clone(),
                 usb_tx: usb_tx.clone(),
                 file_paths: file_paths.clone(),
+                clients: clients.clone(),
             })))
+            .app_data(rate_limiter.clone())
+            .app_data(web::PayloadConfig::new(MAX_ICON_SIZE))
             .service(execute_command)
             .service(get_devices)
             .service(get_sample)
             .service(get_scribble)
             .service(get_path)
+            .service(get_version)
+            .service(get_icons)
+            .service(upload_icon)
+            .service(delete_icon)
+            .service(get_icon_thumbnail)
             .service(websocket)
             .default_service(web::to(default))
     })
@@ -270,15 +443,25 @@ pub async fn spawn_http_server(
 #[get("/api/websocket")]
 async fn websocket(
     usb_mutex: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
     req: HttpRequest,
     stream: web::Payload,
 ) -> Result<HttpResponse, actix_web::Error> {
     let data = usb_mutex.lock().await;
 
+    // Opening the connection isn't limited, but each request sent over it is
+    let peer = get_peer(&req);
+    let client = data.clients.connect(
+        ClientTransport::WebSocket,
+        peer.map(|peer| peer.to_string()),
+    );
     ws::start(
         Websocket {
             usb_tx: data.usb_tx.clone(),
             broadcast_tx: data.broadcast_tx.clone(),
+            rate_limiter,
+            peer,
+            client: Arc::new(client),
         },
         &req,
         stream,
@@ -289,33 +472,69 @@ async fn websocket(
 // news everybody! So do we.. :)
 #[post("/api/command")]
 async fn execute_command(
-    request: web::Json<DaemonRequest>,
+    request: web::Json<Value>,
     app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
 ) -> HttpResponse {
+    // Parsed here, rather than by actix, so a request from a newer client gets a proper reply
+    let request: DaemonRequest = match serde_json::from_value(request.0.clone()) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            let guard = app_data.lock().await;
+            let protocol = guard.clients.http(get_peer(&req)).protocol();
+            let error = unsupported_request(&request.0, protocol, &error);
+            return HttpResponse::Ok().json(DaemonResponse::Error(error));
+        }
+    };
+
+    let kind = RequestKind::of(&request);
+    let _in_flight = match rate_limiter.check(get_peer(&req), kind) {
+        Ok(in_flight) => in_flight,
+        Err(limited) => return too_many_requests(limited),
+    };
+
     let mut guard = app_data.lock().await;
     let sender = guard.deref_mut();
+    let client = sender.clients.http(get_peer(&req));
 
     // Errors propagate weirdly in the javascript world, so send all as OK, and handle there.
-    match handle_packet(request.0, &mut sender.usb_tx).await {
+    match handle_packet(request, &mut sender.usb_tx, &client).await {
         Ok(result) => HttpResponse::Ok().json(result),
         Err(error) => HttpResponse::Ok().json(DaemonResponse::Error(error.to_string())),
     }
 }
 
 #[get("/api/get-devices")]
-async fn get_devices(app_data: Data<Mutex<AppData>>) -> HttpResponse {
-    if let Ok(response) = get_status(app_data).await {
+async fn get_devices(
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if let Err(limited) = rate_limiter.check(get_peer(&req), RequestKind::Status) {
+        return too_many_requests(limited);
+    }
+
+    if let Ok(response) = get_status(app_data, &req).await {
         return HttpResponse::Ok().json(&response);
     }
     HttpResponse::InternalServerError().finish()
 }
 
 #[get("/api/path")]
-async fn get_path(app_data: Data<Mutex<AppData>>, req: HttpRequest) -> HttpResponse {
+async fn get_path(
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    if let Err(limited) = rate_limiter.check(get_peer(&req), RequestKind::Status) {
+        return too_many_requests(limited);
+    }
+
     let params = web::Query::<HashMap<String, String>>::from_query(req.query_string());
     if let Ok(params) = params {
         if let Some(path) = params.get("path") {
-            if let Ok(status) = get_status(app_data).await {
+            if let Ok(status) = get_status(app_data, &req).await {
                 if let Ok(value) = serde_json::to_value(status) {
                     if let Ok(result) = value.path(path) {
                         return HttpResponse::Ok().json(result);
@@ -338,6 +557,102 @@ async fn get_path(app_data: Data<Mutex<AppData>>, req: HttpRequest) -> HttpRespo
     HttpResponse::InternalServerError().finish()
 }
 
+/// Lets a UI check it's talking to a daemon it understands before it connects.
+#[get("/api/version")]
+async fn get_version() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "daemon_version": VERSION,
+        "status_version": STATUS_VERSION,
+        "protocol_version": PROTOCOL_VERSION,
+    }))
+}
+
+#[get("/api/icons")]
+async fn get_icons(
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    run_icon_request(DaemonRequest::ListIcons, app_data, rate_limiter, req).await
+}
+
+// The body is the image itself, which saves the UI from having to encode it
+#[post("/api/icons/{name}")]
+async fn upload_icon(
+    name: web::Path<String>,
+    body: web::Bytes,
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let request = DaemonRequest::UploadIcon(name.into_inner(), body.to_vec());
+    run_icon_request(request, app_data, rate_limiter, req).await
+}
+
+#[delete("/api/icons/{name}")]
+async fn delete_icon(
+    name: web::Path<String>,
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let params = web::Query::<HashMap<String, String>>::from_query(req.query_string());
+    let force = match params {
+        Ok(params) => params.get("force").is_some_and(|force| force == "true"),
+        Err(_) => false,
+    };
+
+    let request = DaemonRequest::DeleteIcon {
+        name: name.into_inner(),
+        force,
+    };
+    run_icon_request(request, app_data, rate_limiter, req).await
+}
+
+async fn run_icon_request(
+    request: DaemonRequest,
+    app_data: Data<Mutex<AppData>>,
+    rate_limiter: Data<RateLimiter>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let _in_flight = match rate_limiter.check(get_peer(&req), RequestKind::of(&request)) {
+        Ok(in_flight) => in_flight,
+        Err(limited) => return too_many_requests(limited),
+    };
+
+    let mut guard = app_data.lock().await;
+    let sender = guard.deref_mut();
+    let client = sender.clients.http(get_peer(&req));
+
+    // As with commands, errors are sent as OK for the UI to handle
+    match handle_packet(request, &mut sender.usb_tx, &client).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(error) => HttpResponse::Ok().json(DaemonResponse::Error(error.to_string())),
+    }
+}
+
+#[get("/files/icons/{name}/thumbnail.png")]
+async fn get_icon_thumbnail(
+    name: web::Path<String>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    // Only this icon is looked at, and its thumbnail is only redrawn if the icon has changed
+    let (tx, rx) = oneshot::channel();
+    let usb_tx = app_data.lock().await.usb_tx.clone();
+    let request = DeviceCommand::GetIconThumbnail(name.into_inner(), tx);
+    if usb_tx.send(request).await.is_ok() {
+        if let Ok(Ok(thumbnail)) = rx.await {
+            if let Ok(png) = fs::read(thumbnail) {
+                let mut builder = HttpResponse::Ok();
+                builder.insert_header(ContentType(IMAGE_PNG));
+                return builder.body(png);
+            }
+        }
+    }
+
+    HttpResponse::NotFound().finish()
+}
+
 #[get("/files/scribble/{serial}/{fader}.png")]
 async fn get_scribble(
     path: web::Path<(String, FaderName)>,
@@ -368,22 +683,26 @@ async fn get_scribble(
     // Now we need to grab the DaemonResponse to get the layout of the scribble..
     let mut guard = app_data.lock().await;
     let sender = guard.deref_mut();
+    let client = sender.clients.http(get_peer(&req));
     let request = DaemonRequest::GetStatus;
 
-    if let Ok(DaemonResponse::Status(status)) = handle_packet(request, &mut sender.usb_tx).await {
+    if let Ok(DaemonResponse::Status(status)) =
+        handle_packet(request, &mut sender.usb_tx, &client).await
+    {
         let scribble_path = status.paths.icons_directory;
 
         if let Some(mixer) = status.mixers.get(serial) {
             // Locate the Scribble..
             if let Some(scribble) = &mixer.fader_status[fader].scribble {
-                let mut icon_path = None;
-                if let Some(file) = &scribble.file_name {
-                    icon_path = Some(scribble_path.join(file));
-                }
+                let icon = match (&scribble.glyph, &scribble.file_name) {
+                    (Some(glyph), _) => Some(ScribbleIcon::Glyph(glyph.clone())),
+                    (None, Some(file)) => Some(ScribbleIcon::File(scribble_path.join(file))),
+                    (None, None) => None,
+                };
 
                 // We have access to the Scribble package, so generate and throw out..
                 let png = get_scribble_png(
-                    icon_path,
+                    icon,
                     scribble.bottom_text.clone(),
                     scribble.left_text.clone(),
                     scribble.inverted,
@@ -392,10 +711,21 @@ async fn get_scribble(
                 );
                 debug!("Creating Image {}x{}", final_width, final_height);
 
+                let png = match png {
+                    Ok(png) => png,
+                    Err(e) => {
+                        warn!(
+                            "Unable to draw the scribble for {} {}: {:#}",
+                            serial, fader, e
+                        );
+                        return HttpResponse::InternalServerError().body(format!("{:#}", e));
+                    }
+                };
+
                 let mime_type = ContentType(IMAGE_PNG);
                 let mut builder = HttpResponse::Ok();
                 builder.insert_header(mime_type);
-                return builder.body(png.unwrap());
+                return builder.body(png);
             }
         }
     }
@@ -411,7 +741,7 @@ async fn get_sample(sample: web::Path<String>, app_data: Data<Mutex<AppData>>) -
     // Get the Base Samples Path..
     let mut guard = app_data.lock().await;
     let sender = guard.deref_mut();
-    let sample_path = sender.file_paths.samples.clone();
+    let sample_path = sender.file_paths.borrow().samples.clone();
     drop(guard);
 
     let sample = sample.into_inner();
@@ -442,27 +772,210 @@ async fn default(req: HttpRequest) -> HttpResponse {
         req.path()
     };
     let path_part = &path[1..path.len()];
-    let file = WEB_CONTENT.get_file(path_part);
+    let file = get_ui_file(path_part);
     if let Some(file) = file {
         let mime_type = MimeGuess::from_path(path).first_or_octet_stream();
         let mut builder = HttpResponse::Ok();
         builder.insert_header(ContentType(mime_type));
-        builder.body(file.contents())
+        builder.body(file)
     } else {
         HttpResponse::NotFound().finish()
     }
 }
 
-async fn get_status(app_data: Data<Mutex<AppData>>) -> Result<DaemonStatus> {
+fn get_peer(req: &HttpRequest) -> Option<IpAddr> {
+    req.peer_addr().map(|address| address.ip())
+}
+
+fn too_many_requests(limited: RateLimited) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header((RETRY_AFTER, limited.retry_after))
+        .json(DaemonResponse::Error(limited.to_string()))
+}
+
+async fn get_status(app_data: Data<Mutex<AppData>>, req: &HttpRequest) -> Result<DaemonStatus> {
     // Unwrap the Mutex Guard..
     let mut guard = app_data.lock().await;
     let sender = guard.deref_mut();
+    let client = sender.clients.http(get_peer(req));
 
     let request = DaemonRequest::GetStatus;
 
-    let result = handle_packet(request, &mut sender.usb_tx).await?;
+    let result = handle_packet(request, &mut sender.usb_tx, &client).await?;
     match result {
         DaemonResponse::Status(status) => Ok(status),
         _ => Err(anyhow!("Unexpected Daemon Status Result: {:?}", result)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::path::Path;
+
+    use actix_web::dev::{Service, ServiceResponse};
+    use actix_web::http::StatusCode;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use goxlr_ipc::GoXLRCommand;
+    use goxlr_types::{ChannelName, FaderName};
+    use tokio::sync::{broadcast, mpsc};
+
+    use super::*;
+    use crate::settings::get_data_paths_in;
+
+    const REMOTE: &str = "192.168.0.20:50000";
+
+    fn limits(commands_per_second: u32) -> watch::Receiver<HttpRateLimit> {
+        let (_, rx) = watch::channel(HttpRateLimit {
+            commands_per_second,
+            status_per_second: 1,
+            max_in_flight: 0,
+        });
+        rx
+    }
+
+    async fn app(
+        limits: watch::Receiver<HttpRateLimit>,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>
+    {
+        app_with_clients(limits, ClientRegistry::new()).await
+    }
+
+    async fn app_with_clients(
+        limits: watch::Receiver<HttpRateLimit>,
+        clients: Arc<ClientRegistry>,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>
+    {
+        // Nothing's listening on the device channel, so commands fail quickly (but not with 429)
+        let (usb_tx, _) = mpsc::channel(1);
+        let (broadcast_tx, _) = broadcast::channel(1);
+        let (_, file_paths) = watch::channel(get_data_paths_in(Path::new("/tmp")));
+
+        init_service(
+            App::new()
+                .app_data(Data::new(Mutex::new(AppData {
+                    usb_tx,
+                    broadcast_tx,
+                    file_paths,
+                    clients,
+                })))
+                .app_data(Data::new(RateLimiter::new(limits)))
+                .service(execute_command),
+        )
+        .await
+    }
+
+    fn command(peer: &str) -> actix_http::Request {
+        let command = GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic);
+        TestRequest::post()
+            .uri("/api/command")
+            .peer_addr(peer.parse::<SocketAddr>().unwrap())
+            .set_json(DaemonRequest::Command(String::from("S1"), command))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn remote_commands_are_limited() {
+        let app = app(limits(2)).await;
+
+        for _ in 0..2 {
+            let response = call_service(&app, command(REMOTE)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = call_service(&app, command(REMOTE)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        // Other addresses have their own allowance
+        let response = call_service(&app, command("192.168.0.21:50000")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn local_clients_are_never_limited() {
+        let app = app(limits(1)).await;
+
+        for peer in ["127.0.0.1:50000", "[::1]:50000", "[::ffff:127.0.0.1]:50000"] {
+            for _ in 0..10 {
+                let response = call_service(&app, command(peer)).await;
+                assert_eq!(response.status(), StatusCode::OK, "{} was limited", peer);
+            }
+        }
+    }
+
+    #[actix_web::test]
+    async fn status_requests_use_their_own_limit() {
+        let app = app(limits(1)).await;
+        call_service(&app, command(REMOTE)).await;
+        let response = call_service(&app, command(REMOTE)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let ping = || {
+            TestRequest::post()
+                .uri("/api/command")
+                .peer_addr(REMOTE.parse::<SocketAddr>().unwrap())
+                .set_json(DaemonRequest::Ping)
+                .to_request()
+        };
+        let response = call_service(&app, ping()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = call_service(&app, ping()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn in_flight_commands_are_capped() {
+        let (_tx, rx) = watch::channel(HttpRateLimit {
+            commands_per_second: 0,
+            status_per_second: 0,
+            max_in_flight: 1,
+        });
+        let limiter = RateLimiter::new(rx);
+        let first: IpAddr = "10.0.0.1".parse().unwrap();
+        let second: IpAddr = "10.0.0.2".parse().unwrap();
+
+        let in_flight = limiter.check(Some(first), RequestKind::Command).unwrap();
+        assert!(limiter.check(Some(second), RequestKind::Command).is_err());
+        assert!(limiter.check(Some(second), RequestKind::Status).is_ok());
+
+        drop(in_flight);
+        assert!(limiter.check(Some(second), RequestKind::Command).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn http_clients_are_listed_and_cant_be_disconnected() {
+        let clients = ClientRegistry::new();
+        let app = app_with_clients(limits(0), clients.clone()).await;
+        let socket = clients.connect(ClientTransport::WebSocket, None);
+
+        call_service(&app, command(REMOTE)).await;
+        let request = TestRequest::post()
+            .uri("/api/command")
+            .peer_addr(REMOTE.parse::<SocketAddr>().unwrap())
+            .set_json(DaemonRequest::GetConnectedClients)
+            .to_request();
+        let response: DaemonResponse =
+            actix_web::test::read_body_json(call_service(&app, request).await).await;
+
+        let DaemonResponse::ConnectedClients(list) = response else {
+            panic!("Unexpected response: {:?}", response);
+        };
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].transport, ClientTransport::Http);
+        assert_eq!(list[1].address.as_deref(), Some("192.168.0.20"));
+        assert_eq!((list[1].requests, list[1].commands), (2, 1));
+
+        // The websocket can be closed from over HTTP, but the HTTP client can't be
+        for (id, expected) in [(list[1].id, false), (socket.id(), true)] {
+            let request = TestRequest::post()
+                .uri("/api/command")
+                .peer_addr(REMOTE.parse::<SocketAddr>().unwrap())
+                .set_json(DaemonRequest::DisconnectClient(id))
+                .to_request();
+            let response: DaemonResponse =
+                actix_web::test::read_body_json(call_service(&app, request).await).await;
+            assert_eq!(matches!(response, DaemonResponse::Ok), expected);
+        }
+    }
+}
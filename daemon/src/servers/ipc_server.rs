@@ -1,32 +1,70 @@
 use anyhow::{bail, Result};
 use goxlr_ipc::clients::ipc::ipc_socket::Socket;
-use goxlr_ipc::{DaemonRequest, DaemonResponse};
+use goxlr_ipc::{unsupported_request, ClientTransport, DaemonRequest, DaemonResponse};
 use interprocess::local_socket::tokio::prelude::{LocalSocketListener, LocalSocketStream};
 use interprocess::local_socket::traits::tokio::{Listener, Stream};
 use interprocess::local_socket::{
     GenericFilePath, GenericNamespaced, ListenerOptions, ToFsName, ToNsName,
 };
 use log::{debug, info, warn};
+use serde_json::Value;
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
 
 use crate::primary_worker::DeviceSender;
+use crate::servers::clients::{event_for_client, ClientRegistry, ClientSession};
 use crate::servers::server_packet::handle_packet;
-use crate::Shutdown;
+use crate::{BroadcastEvent, Shutdown};
 
 static SOCKET_PATH: &str = "/tmp/goxlr.socket";
 static NAMED_PIPE: &str = "@goxlr.socket";
 
+/// Overrides the socket location, this is also set for anything launched on Activate so that
+/// it'll talk to this daemon.
+pub static SOCKET_PATH_ENV: &str = "GOXLR_SOCKET_PATH";
+
+// Set once at startup, when the socket location has been overridden
+static SOCKET_NAME: OnceLock<String> = OnceLock::new();
+
+/// Overrides where the socket (or on Windows, the named pipe) is created, this must be called
+/// before the socket is bound, and only the first call has any effect.
+pub fn set_socket_name(name: String) {
+    let _ = SOCKET_NAME.set(name);
+}
+
+/// The name of the socket file or named pipe that clients should connect to.
+pub fn get_socket_name() -> &'static str {
+    match SOCKET_NAME.get() {
+        Some(name) => name,
+        None => match cfg!(windows) {
+            true => NAMED_PIPE,
+            false => SOCKET_PATH,
+        },
+    }
+}
+
+/// The file the IPC socket will be created at, if the platform uses one.
+pub fn get_socket_path() -> Option<&'static Path> {
+    match cfg!(windows) {
+        true => None,
+        false => Some(Path::new(get_socket_name())),
+    }
+}
+
 async fn ipc_tidy() -> Result<()> {
     // We only need a possible cleanup if we're using file based sockets, this has changed
     // substantially with the latest interprocess crate, so we're OS based now..
+    let socket_name = get_socket_name();
     let socket_type = if cfg!(windows) {
-        NAMED_PIPE.to_ns_name::<GenericNamespaced>()?
+        socket_name.to_ns_name::<GenericNamespaced>()?
     } else {
-        if !Path::new(SOCKET_PATH).exists() {
+        if !Path::new(socket_name).exists() {
             return Ok(());
         }
-        SOCKET_PATH.to_fs_name::<GenericFilePath>()?
+        socket_name.to_fs_name::<GenericFilePath>()?
     };
 
     let connection = LocalSocketStream::connect(socket_type).await;
@@ -37,7 +75,7 @@ async fn ipc_tidy() -> Result<()> {
             }
             false => {
                 debug!("Connection Failed. Socket File is stale, removing..");
-                fs::remove_file(SOCKET_PATH)?;
+                fs::remove_file(socket_name)?;
             }
         }
         return Ok(());
@@ -55,35 +93,41 @@ async fn ipc_tidy() -> Result<()> {
             }
             false => {
                 debug!("Unable to send messages, removing socket..");
-                fs::remove_file(SOCKET_PATH)?;
+                fs::remove_file(socket_name)?;
             }
         }
         return Ok(());
     }
 
     // If we get here, there's an active GoXLR Daemon running!
-    bail!("The GoXLR Daemon is already running.");
+    bail!(
+        "Another GoXLR Daemon is already running on {}, use --socket-path to start a separate \
+        instance.",
+        socket_name
+    );
 }
 
 pub async fn bind_socket() -> Result<LocalSocketListener> {
     ipc_tidy().await?;
 
     let name = if cfg!(windows) {
-        NAMED_PIPE.to_ns_name::<GenericNamespaced>()?
+        get_socket_name().to_ns_name::<GenericNamespaced>()?
     } else {
-        SOCKET_PATH.to_fs_name::<GenericFilePath>()?
+        get_socket_name().to_fs_name::<GenericFilePath>()?
     };
 
     let opts = ListenerOptions::new().name(name.clone());
     let listener = opts.create_tokio()?;
 
-    info!("Bound IPC Socket @ {:?}", name);
+    info!("Bound IPC Socket @ {}", get_socket_name());
     Ok(listener)
 }
 
 pub async fn spawn_ipc_server(
     listener: LocalSocketListener,
     usb_tx: DeviceSender,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
+    clients: Arc<ClientRegistry>,
     mut shutdown_signal: Shutdown,
 ) {
     debug!("Running IPC Server..");
@@ -92,13 +136,17 @@ pub async fn spawn_ipc_server(
             Ok(connection) = listener.accept() => {
                 let socket = Socket::new(connection);
                 let usb_tx = usb_tx.clone();
+                let broadcast_tx = broadcast_tx.clone();
+
+                // The socket can't tell us who's on the other end, but it'll always be local
+                let client = clients.connect(ClientTransport::Ipc, None);
                 tokio::spawn(async move {
-                    handle_connection(socket, usb_tx).await;
+                    handle_connection(socket, usb_tx, broadcast_tx, client).await;
                 });
             }
             () = shutdown_signal.recv() => {
                 if !cfg!(windows) {
-                    let _ = fs::remove_file(SOCKET_PATH);
+                    let _ = fs::remove_file(get_socket_name());
                 }
                 return;
             }
@@ -107,12 +155,34 @@ pub async fn spawn_ipc_server(
 }
 
 async fn handle_connection(
-    mut socket: Socket<DaemonRequest, DaemonResponse>,
+    mut socket: Socket<Value, DaemonResponse>,
     mut usb_tx: DeviceSender,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
+    client: ClientSession,
 ) {
-    while let Some(msg) = socket.read().await {
+    loop {
+        let msg = tokio::select! {
+            msg = socket.read() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            () = client.disconnected() => break,
+        };
+
+        // Requests are parsed here rather than by the socket, so that one from a newer client
+        // gets told what couldn't be handled
+        let msg = msg.map(|value| {
+            serde_json::from_value::<DaemonRequest>(value.clone())
+                .map_err(|e| unsupported_request(&value, client.protocol(), &e))
+        });
+
         match msg {
-            Ok(msg) => match handle_packet(msg, &mut usb_tx).await {
+            Ok(Ok(request @ DaemonRequest::Subscribe { notifications_only })) => {
+                client.record(&request);
+                handle_subscription(&mut socket, &broadcast_tx, &client, notifications_only).await;
+                return;
+            }
+            Ok(Ok(msg)) => match handle_packet(msg, &mut usb_tx, &client).await {
                 Ok(response) => {
                     if let Err(e) = socket.send(response).await {
                         warn!("Couldn't reply to {:?}: {}", socket.address(), e);
@@ -126,6 +196,13 @@ async fn handle_connection(
                     }
                 }
             },
+            Ok(Err(error)) => {
+                warn!("{:?}: {}", socket.address(), error);
+                if let Err(e) = socket.send(DaemonResponse::Error(error)).await {
+                    warn!("Could not reply to {:?}: {}", socket.address(), e);
+                    return;
+                }
+            }
             Err(e) => {
                 warn!("Invalid message from {:?}: {}", socket.address(), e);
                 if let Err(e) = socket.send(DaemonResponse::Error(e.to_string())).await {
@@ -137,3 +214,48 @@ async fn handle_connection(
     }
     debug!("Disconnected {:?}", socket.address());
 }
+
+// Once subscribed, the connection only carries events, it's closed when the client goes away.
+async fn handle_subscription(
+    socket: &mut Socket<Value, DaemonResponse>,
+    broadcast_tx: &BroadcastSender<BroadcastEvent>,
+    client: &ClientSession,
+    notifications_only: bool,
+) {
+    let mut broadcast_rx = broadcast_tx.subscribe();
+    if socket.send(DaemonResponse::Ok).await.is_err() {
+        return;
+    }
+
+    debug!("{:?} Subscribed to Events", socket.address());
+    loop {
+        tokio::select! {
+            event = broadcast_rx.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("{:?} missed {} events", socket.address(), count);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let Some(response) = event_for_client(event, client.id(), notifications_only)
+                else {
+                    continue;
+                };
+
+                if socket.send(response).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.read() => {
+                // Nothing more is expected from the client, we're just waiting for it to leave.
+                if message.is_none() {
+                    break;
+                }
+            }
+            () = client.disconnected() => break,
+        }
+    }
+    debug!("Disconnected {:?}", socket.address());
+}
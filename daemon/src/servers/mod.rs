@@ -1,3 +1,6 @@
+pub(crate) mod clients;
 pub(crate) mod http_server;
 pub(crate) mod ipc_server;
+pub(crate) mod rate_limit;
 pub(crate) mod server_packet;
+pub(crate) mod web_content;
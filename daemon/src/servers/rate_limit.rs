@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use goxlr_ipc::{DaemonRequest, HttpRateLimit};
+use log::warn;
+use tokio::sync::watch;
+
+// Refused clients are only logged once in this period, no matter how many requests they send
+const LOG_WINDOW: Duration = Duration::from_secs(60);
+
+// Clients which haven't been seen for a while are forgotten, once there are enough to bother
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const PRUNE_THRESHOLD: usize = 256;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RequestKind {
+    /// Answered by the daemon from what it already knows
+    Status,
+
+    /// Needs a trip to the device (or the daemon to do some work)
+    Command,
+}
+
+impl RequestKind {
+    pub fn of(request: &DaemonRequest) -> Self {
+        match request {
+            DaemonRequest::Ping
+            | DaemonRequest::Hello(_)
+            | DaemonRequest::GetStatus
+            | DaemonRequest::GetStatusSummary
+            | DaemonRequest::GetStatusSchema
+            | DaemonRequest::GetConnectedClients
+            | DaemonRequest::ResolveControl(..)
+            | DaemonRequest::PreviewModeChange(..)
+            | DaemonRequest::Settle(..)
+            | DaemonRequest::GetTelemetryPreview
+            | DaemonRequest::TailLog(_) => RequestKind::Status,
+            _ => RequestKind::Command,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimited {
+    /// Whole seconds, as sent in the Retry-After header
+    pub retry_after: u64,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Too Many Requests, retry in {}s", self.retry_after)
+    }
+}
+
+/// Held while a command is being handled, so it counts towards the in-flight limit.
+pub struct InFlight(Option<Arc<AtomicU32>>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some(count) = &self.0 {
+            count.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Token buckets for each remote address (one for status requests, one for commands), and a
+/// count of the commands currently waiting on the devices. Limits can change while running.
+pub struct RateLimiter {
+    limits: watch::Receiver<HttpRateLimit>,
+    clients: Mutex<HashMap<IpAddr, Client>>,
+    in_flight: Arc<AtomicU32>,
+    in_flight_warned: Mutex<Option<Instant>>,
+}
+
+struct Client {
+    status: Bucket,
+    commands: Bucket,
+    last_seen: Instant,
+    warned: Option<Instant>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(limits: watch::Receiver<HttpRateLimit>) -> Self {
+        Self {
+            limits,
+            clients: Mutex::new(HashMap::new()),
+            in_flight: Arc::new(AtomicU32::new(0)),
+            in_flight_warned: Mutex::new(None),
+        }
+    }
+
+    /// Checks whether a request from this address can go ahead, the returned InFlight should be
+    /// kept until the request has been handled. Local clients (and those whose address isn't
+    /// known) are never limited.
+    pub fn check(&self, peer: Option<IpAddr>, kind: RequestKind) -> Result<InFlight, RateLimited> {
+        let peer = match peer {
+            Some(peer) if !is_local(peer) => peer,
+            _ => return Ok(InFlight(None)),
+        };
+
+        let limits = self.limits.borrow().clone();
+        let now = Instant::now();
+
+        {
+            let mut clients = self.clients.lock().unwrap();
+            if clients.len() > PRUNE_THRESHOLD {
+                clients.retain(|_, client| now - client.last_seen < IDLE_TIMEOUT);
+            }
+
+            let client = clients.entry(peer).or_insert_with(|| Client {
+                status: Bucket::full(limits.status_per_second, now),
+                commands: Bucket::full(limits.commands_per_second, now),
+                last_seen: now,
+                warned: None,
+            });
+            client.last_seen = now;
+
+            let (bucket, rate) = match kind {
+                RequestKind::Status => (&mut client.status, limits.status_per_second),
+                RequestKind::Command => (&mut client.commands, limits.commands_per_second),
+            };
+
+            if let Err(limited) = bucket.take(rate, now) {
+                if should_warn(&mut client.warned, now) {
+                    warn!(
+                        "{} is sending {:?} requests too quickly, some are being refused",
+                        peer, kind
+                    );
+                }
+                return Err(limited);
+            }
+        }
+
+        if kind == RequestKind::Status || limits.max_in_flight == 0 {
+            return Ok(InFlight(None));
+        }
+
+        let max = limits.max_in_flight;
+        let result = self
+            .in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < max).then_some(count + 1)
+            });
+
+        match result {
+            Ok(_) => Ok(InFlight(Some(self.in_flight.clone()))),
+            Err(_) => {
+                if should_warn(&mut self.in_flight_warned.lock().unwrap(), now) {
+                    warn!(
+                        "{} HTTP commands are already waiting on the devices, some are being \
+                        refused",
+                        max
+                    );
+                }
+                Err(RateLimited { retry_after: 1 })
+            }
+        }
+    }
+}
+
+impl Bucket {
+    // A bucket holds up to a second's worth of requests
+    fn full(rate: u32, now: Instant) -> Self {
+        Self {
+            tokens: rate as f64,
+            updated: now,
+        }
+    }
+
+    fn take(&mut self, rate: u32, now: Instant) -> Result<(), RateLimited> {
+        if rate == 0 {
+            return Ok(());
+        }
+
+        let rate = rate as f64;
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let wait = (1.0 - self.tokens) / rate;
+        Err(RateLimited {
+            retry_after: (wait.ceil() as u64).max(1),
+        })
+    }
+}
+
+fn should_warn(warned: &mut Option<Instant>, now: Instant) -> bool {
+    if warned.is_some_and(|warned| now - warned < LOG_WINDOW) {
+        return false;
+    }
+    *warned = Some(now);
+    true
+}
+
+fn is_local(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => address.is_loopback(),
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => address.is_loopback(),
+            None => address.is_loopback(),
+        },
+    }
+}
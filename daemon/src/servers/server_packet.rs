@@ -1,14 +1,26 @@
+use crate::logging;
+#[cfg(feature = "midi")]
+use crate::midi;
 use crate::primary_worker::{DeviceCommand, DeviceSender};
-use anyhow::{anyhow, Context, Result};
-use goxlr_ipc::{DaemonRequest, DaemonResponse};
+use crate::servers::clients::ClientSession;
+use anyhow::{anyhow, bail, Context, Result};
+use goxlr_ipc::{status_schema, CommandError, DaemonRequest, DaemonResponse, PROTOCOL_VERSION};
+use log::debug;
 use tokio::sync::oneshot;
 
 pub async fn handle_packet(
     request: DaemonRequest,
     usb_tx: &mut DeviceSender,
+    client: &ClientSession,
 ) -> Result<DaemonResponse> {
+    client.record(&request);
+
     match request {
         DaemonRequest::Ping => Ok(DaemonResponse::Ok),
+        DaemonRequest::Hello(protocol) => {
+            client.set_protocol(protocol);
+            Ok(DaemonResponse::Hello(PROTOCOL_VERSION))
+        }
         DaemonRequest::GetStatus => {
             let (tx, rx) = oneshot::channel();
             usb_tx
@@ -20,7 +32,69 @@ pub async fn handle_packet(
                 "Could not execute the command on the device task",
             )?))
         }
+        DaemonRequest::GetStatusSummary => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::GetStatusSummary(tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            Ok(DaemonResponse::StatusSummary(rx.await.context(
+                "Could not execute the command on the device task",
+            )?))
+        }
+        DaemonRequest::GetStatusSchema => Ok(DaemonResponse::StatusSchema(status_schema())),
+        DaemonRequest::TailLog(lines) => Ok(DaemonResponse::LogLines(logging::tail(lines))),
+
+        #[cfg(feature = "midi")]
+        DaemonRequest::GetMidiPorts => {
+            let ports = tokio::task::spawn_blocking(midi::get_ports).await??;
+            Ok(DaemonResponse::MidiPorts(ports))
+        }
+        #[cfg(not(feature = "midi"))]
+        DaemonRequest::GetMidiPorts => bail!("MIDI support isn't included in this build"),
+
+        #[cfg(feature = "telemetry")]
+        DaemonRequest::GetTelemetryPreview => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::GetTelemetryPreview(tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            Ok(DaemonResponse::TelemetryPreview(rx.await.context(
+                "Could not execute the command on the device task",
+            )?))
+        }
+        #[cfg(not(feature = "telemetry"))]
+        DaemonRequest::GetTelemetryPreview => {
+            bail!("Usage statistics aren't included in this build")
+        }
+
+        DaemonRequest::GetConnectedClients => {
+            Ok(DaemonResponse::ConnectedClients(client.registry().list()))
+        }
+        DaemonRequest::DisconnectClient(id) => {
+            client.registry().disconnect(id)?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::ClaimChannel(serial, channel, claimant) => {
+            client
+                .registry()
+                .claim(client.id(), &serial, channel, claimant)?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::ReleaseChannel(serial, channel) => {
+            client.registry().release(client.id(), &serial, channel)?;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::Subscribe { .. } => {
+            // Handled by the IPC server, which holds the connection open for the events.
+            bail!("Subscriptions are only available via the IPC socket");
+        }
         DaemonRequest::Daemon(command) => {
+            debug!("Client {} sent {:?}", client.id(), command);
             let (tx, rx) = oneshot::channel();
             usb_tx
                 .send(DeviceCommand::RunDaemonCommand(command, tx))
@@ -50,9 +124,116 @@ pub async fn handle_packet(
         }
 
         DaemonRequest::Command(serial, command) => {
+            debug!("Client {} sent {:?} to {}", client.id(), command, serial);
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::RunDeviceCommand(
+                    serial,
+                    command,
+                    Some(client.id()),
+                    tx,
+                ))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let result = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")?;
+
+            match result {
+                Ok(None) => Ok(DaemonResponse::Ok),
+                Ok(Some(response)) => Ok(response),
+                Err(e) => match e.downcast_ref::<CommandError>() {
+                    Some(error) => Ok(DaemonResponse::CommandError(error.clone())),
+                    None => Err(e),
+                },
+            }
+        }
+
+        DaemonRequest::IdentifyDevice(serial) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::IdentifyDevice(serial, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::TestButtons(serial, enabled) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::SetButtonTest(serial, enabled, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::GetAudioDevices => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::GetAudioDevices(tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            Ok(DaemonResponse::AudioDevices(rx.await.context(
+                "Could not execute the command on the device task",
+            )?))
+        }
+
+        DaemonRequest::RecoverDefaults {
+            path_type,
+            files,
+            overwrite,
+        } => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::RecoverDefaults(
+                    path_type, files, overwrite, tx,
+                ))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            let result = rx
+                .await
+                .context("Could not execute the command on the device task")??;
+            Ok(DaemonResponse::RecoveredDefaults(result))
+        }
+
+        DaemonRequest::ImportEqCurve(serial, path, target) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::ImportEqCurve(serial, path, target, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let report = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::EqCurveImport(report))
+        }
+
+        DaemonRequest::ExportEqCurve(serial, path, target) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::ExportEqCurve(serial, path, target, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::ExportWindowsProfile(serial, name) => {
             let (tx, rx) = oneshot::channel();
             usb_tx
-                .send(DeviceCommand::RunDeviceCommand(serial, command, tx))
+                .send(DeviceCommand::ExportWindowsProfile(serial, name, tx))
                 .await
                 .map_err(|e| anyhow!(e.to_string()))
                 .context("Could not communicate with the GoXLR device")?;
@@ -60,5 +241,119 @@ pub async fn handle_packet(
                 .context("Could not execute the command on the GoXLR device")??;
             Ok(DaemonResponse::Ok)
         }
+
+        DaemonRequest::GetHardwareState(serial) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::GetHardwareState(serial, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let report = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::HardwareState(report))
+        }
+
+        DaemonRequest::ResolveControl(serial, query) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::ResolveControl(serial, query, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let resolved = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::ResolvedControl(resolved))
+        }
+
+        DaemonRequest::PreviewModeChange(serial, change) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::PreviewModeChange(serial, change, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let preview = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::ModeChangePreview(preview))
+        }
+
+        DaemonRequest::Settle(serial, timeout) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::Settle(serial, timeout, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::SaveReplayBuffer(serial, file_name) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::SaveReplayBuffer(serial, file_name, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let capture = rx
+                .await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::ReplaySaved(capture))
+        }
+
+        DaemonRequest::CreateSupportBundle { include_profiles } => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::CreateSupportBundle(include_profiles, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            let path = rx
+                .await
+                .context("Could not execute the command on the device task")??;
+            Ok(DaemonResponse::SupportBundle(path))
+        }
+
+        DaemonRequest::ListIcons => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::ListIcons(tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            Ok(DaemonResponse::Icons(rx.await.context(
+                "Could not execute the command on the device task",
+            )?))
+        }
+
+        DaemonRequest::UploadIcon(name, data) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::UploadIcon(name, data, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            let icon = rx
+                .await
+                .context("Could not execute the command on the device task")??;
+            Ok(DaemonResponse::Icon(icon))
+        }
+
+        DaemonRequest::DeleteIcon { name, force } => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::DeleteIcon(name, force, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            rx.await
+                .context("Could not execute the command on the device task")??;
+            Ok(DaemonResponse::Ok)
+        }
     }
 }
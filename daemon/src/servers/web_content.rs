@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use anyhow::{bail, Result};
+use goxlr_ipc::STATUS_VERSION;
+use include_dir::{include_dir, Dir};
+use log::{debug, info, warn};
+use serde::Deserialize;
+
+const WEB_CONTENT: Dir = include_dir!("./daemon/web-content/");
+
+// Shipped with a UI build, saying which version it is and which status layout it expects
+const MANIFEST_FILE: &str = "version.json";
+
+// How many status versions apart the UI and daemon can be before the UI is flagged
+const COMPATIBILITY_WINDOW: u32 = 2;
+
+// Swapped out when the path changes, so the HTTP server itself never needs restarting
+static UI_CONTENT: RwLock<UiContent> = RwLock::new(UiContent {
+    path: None,
+    warning: None,
+});
+
+struct UiContent {
+    path: Option<PathBuf>,
+    warning: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UiManifest {
+    version: String,
+    status_version: Option<u32>,
+}
+
+/// Sets where the Web UI is served from, None serves the copy built into the daemon. A
+/// directory is only accepted if it contains an index.html.
+pub fn set_ui_path(path: Option<PathBuf>) -> Result<()> {
+    let manifest = match &path {
+        Some(path) => {
+            if !path.join("index.html").is_file() {
+                bail!("{} does not contain an index.html", path.to_string_lossy());
+            }
+            info!("Serving the Web UI from {}", path.to_string_lossy());
+            fs::read(path.join(MANIFEST_FILE)).ok()
+        }
+        None => WEB_CONTENT
+            .get_file(MANIFEST_FILE)
+            .map(|file| file.contents().to_vec()),
+    };
+
+    let warning = match manifest {
+        Some(manifest) => match serde_json::from_slice::<UiManifest>(&manifest) {
+            Ok(manifest) => check_manifest(&manifest),
+            Err(e) => {
+                warn!("Unable to parse the Web UI's {}: {}", MANIFEST_FILE, e);
+                None
+            }
+        },
+        None => {
+            debug!(
+                "Web UI has no {}, unable to check its version",
+                MANIFEST_FILE
+            );
+            None
+        }
+    };
+
+    let mut content = UI_CONTENT.write().unwrap();
+    *content = UiContent { path, warning };
+    Ok(())
+}
+
+pub fn get_ui_path() -> Option<PathBuf> {
+    UI_CONTENT.read().unwrap().path.clone()
+}
+
+pub fn get_ui_version_warning() -> Option<String> {
+    UI_CONTENT.read().unwrap().warning.clone()
+}
+
+/// Fetches a file from the Web UI, the path is relative to its root.
+pub fn get_ui_file(path: &str) -> Option<Vec<u8>> {
+    let root = get_ui_path();
+    match root {
+        Some(root) => {
+            // Don't allow anything outside the UI directory to be read
+            let path = Path::new(path);
+            if !path
+                .components()
+                .all(|part| matches!(part, Component::Normal(_)))
+            {
+                return None;
+            }
+            fs::read(root.join(path)).ok()
+        }
+        None => WEB_CONTENT
+            .get_file(path)
+            .map(|file| file.contents().to_vec()),
+    }
+}
+
+fn check_manifest(manifest: &UiManifest) -> Option<String> {
+    info!("Web UI Version: {}", manifest.version);
+
+    let status_version = manifest.status_version?;
+    if status_version.abs_diff(STATUS_VERSION) <= COMPATIBILITY_WINDOW {
+        return None;
+    }
+
+    let warning = format!(
+        "The Web UI ({}) was built for status version {}, but the daemon is using {}, parts of \
+        it may not work",
+        manifest.version, status_version, STATUS_VERSION
+    );
+    warn!("{}", warning);
+    Some(warning)
+}
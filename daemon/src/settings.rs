@@ -1,13 +1,23 @@
+use crate::disk::{write_atomic, DiskFileSystem, FileSystem};
+use crate::files::FilePaths;
+use crate::headset::DEFAULT_SIDETONE;
 use crate::mic_profile::DEFAULT_MIC_PROFILE_NAME;
+use crate::polling::DEFAULT_POLL_INTERVAL;
 use crate::profile::DEFAULT_PROFILE_NAME;
+use crate::watchdog::DEFAULT_STALL_TIMEOUT;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
-use goxlr_ipc::{GoXLRCommand, LogLevel};
-use goxlr_types::VodMode;
+use goxlr_ipc::{
+    GoXLRCommand, HeadsetSnapshot, HighContrastConfig, HttpRateLimit, LogLevel, MidiControl,
+    MidiMapping, MuteSyncApplication, MuteSyncDirection, PrivacyConfig, ProfileRule,
+    ProfileTrigger, ReactiveConfig, ScheduledCommand, TelemetryCounts,
+};
 use goxlr_types::VodMode::Routable;
-use log::{debug, error, info, warn};
+use goxlr_types::{OutputDevice, SubMixChannelName, VersionNumber, VodMode};
+use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::fs;
 use std::fs::{create_dir_all, File};
 use std::io::ErrorKind;
@@ -20,8 +30,50 @@ pub struct SettingsHandle {
     path: PathBuf,
     data_dir: PathBuf,
     settings: Arc<RwLock<Settings>>,
+    write_policy: WritePolicy,
+
+    // Why the last save failed, cleared by one that works
+    save_error: Arc<RwLock<Option<String>>>,
+}
+
+/// Decides whether the daemon is allowed to persist anything, anything which writes to disk
+/// (other than logs and caches) should ask this first.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WritePolicy {
+    read_only: bool,
+}
+
+impl WritePolicy {
+    pub fn new(read_only: bool) -> Self {
+        Self { read_only }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Returns a ReadOnlyError describing the attempted action if writes aren't permitted.
+    pub fn check(&self, action: &str) -> Result<()> {
+        if self.read_only {
+            return Err(ReadOnlyError(action.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Returned when something attempts to write while the daemon is in read-only mode, this can be
+/// picked out of an anyhow::Error with downcast_ref.
+#[derive(Debug)]
+pub struct ReadOnlyError(pub String);
+
+impl Display for ReadOnlyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unable to {}, the daemon is in read-only mode", self.0)
+    }
 }
 
+impl std::error::Error for ReadOnlyError {}
+
 enum Paths {
     Profiles,
     MicProfiles,
@@ -30,6 +82,8 @@ enum Paths {
     Icons,
     Logs,
     Backups,
+    SampleCache,
+    IconCache,
 }
 
 impl AsRef<Path> for Paths {
@@ -42,12 +96,26 @@ impl AsRef<Path> for Paths {
             Paths::Icons => Path::new("icons"),
             Paths::Logs => Path::new("logs"),
             Paths::Backups => Path::new("backups"),
+            Paths::SampleCache => Path::new("sample-cache"),
+            Paths::IconCache => Path::new("icon-cache"),
         }
     }
 }
 
+/// The data directories as they're laid out inside a root, this matches the default layout.
+pub fn get_data_paths_in(root: &Path) -> FilePaths {
+    FilePaths {
+        profiles: root.join(Paths::Profiles),
+        mic_profiles: root.join(Paths::MicProfiles),
+        presets: root.join(Paths::Presets),
+        icons: root.join(Paths::Icons),
+        samples: root.join(Paths::Samples),
+        backups: root.join(Paths::Backups),
+    }
+}
+
 impl SettingsHandle {
-    pub async fn load(path: PathBuf) -> Result<SettingsHandle> {
+    pub async fn load(path: PathBuf, write_policy: WritePolicy) -> Result<SettingsHandle> {
         // This is only used for defaults
         let proj_dirs = ProjectDirs::from("org", "GoXLR-on-Linux", "GoXLR-Utility")
             .context("Couldn't find project directories")?;
@@ -61,6 +129,7 @@ impl SettingsHandle {
                 selected_locale: None,
                 tts_enabled: Some(false),
                 allow_network_access: Some(false),
+                http_rate_limit: None,
                 macos_handle_aggregates: None,
                 profile_directory: None,
                 mic_profile_directory: None,
@@ -70,10 +139,19 @@ impl SettingsHandle {
                 logs_directory: None,
                 backup_directory: None,
                 log_level: Some(LogLevel::Debug),
+                log_filter: None,
                 open_ui_on_launch: None,
                 activate: None,
+                ui_path: None,
+                sample_cache_enabled: Some(false),
+                sample_cache_size: Some(512),
                 devices: Some(Default::default()),
                 sample_gain: Some(Default::default()),
+                schedules: Some(Default::default()),
+                mute_sync: Some(Default::default()),
+                midi: Some(Default::default()),
+                telemetry: Some(Default::default()),
+                profile_rules: Some(Default::default()),
             }
         });
 
@@ -145,34 +223,98 @@ impl SettingsHandle {
             settings.macos_handle_aggregates = Some(true);
         }
 
+        if settings.sample_cache_enabled.is_none() {
+            settings.sample_cache_enabled = Some(false);
+        }
+
+        if settings.sample_cache_size.is_none() {
+            settings.sample_cache_size = Some(512);
+        }
+
         if settings.devices.is_none() {
             settings.devices = Some(Default::default());
         }
 
+        if settings.schedules.is_none() {
+            settings.schedules = Some(Default::default());
+        }
+
+        if settings.mute_sync.is_none() {
+            settings.mute_sync = Some(Default::default());
+        }
+
+        if settings.profile_rules.is_none() {
+            settings.profile_rules = Some(Default::default());
+        }
+
         let handle = SettingsHandle {
             path,
             data_dir: data_dir.to_path_buf(),
             settings: Arc::new(RwLock::new(settings)),
+            write_policy,
+            save_error: Arc::new(RwLock::new(None)),
         };
         handle.save().await;
         Ok(handle)
     }
 
     pub async fn save(&self) {
+        self.save_with(&DiskFileSystem).await;
+    }
+
+    pub async fn save_with(&self, file_system: &dyn FileSystem) {
+        if self.write_policy.is_read_only() {
+            debug!("Read-only mode, not saving settings");
+            return;
+        }
+
         let settings = self.settings.write().await;
-        if let Err(e) = settings.write(&self.path) {
+        let result = settings.write(file_system, &self.path);
+        if let Err(e) = &result {
             error!(
-                "Couldn't save settings to {}: {}",
+                "Couldn't save settings to {}: {:#}",
                 self.path.to_string_lossy(),
                 e
             );
         }
+        *self.save_error.write().await = result.err().map(|e| format!("{e:#}"));
+    }
+
+    pub async fn get_save_error(&self) -> Option<String> {
+        self.save_error.read().await.clone()
+    }
+
+    pub fn get_config_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write_policy(&self) -> WritePolicy {
+        self.write_policy
     }
 
     fn get_default_path(&self, suffix: Paths) -> PathBuf {
         self.data_dir.join(suffix)
     }
 
+    pub fn get_data_directory(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Points the profile, mic profile, presets, samples and icons directories at new locations,
+    /// anything at its default location is stored as unset.
+    pub async fn set_data_paths(&self, paths: &FilePaths) {
+        let configured = |path: &PathBuf, default: Paths| {
+            (path != &self.get_default_path(default)).then(|| path.clone())
+        };
+
+        let mut settings = self.settings.write().await;
+        settings.profile_directory = configured(&paths.profiles, Paths::Profiles);
+        settings.mic_profile_directory = configured(&paths.mic_profiles, Paths::MicProfiles);
+        settings.presets_directory = configured(&paths.presets, Paths::Presets);
+        settings.samples_directory = configured(&paths.samples, Paths::Samples);
+        settings.icons_directory = configured(&paths.icons, Paths::Icons);
+    }
+
     pub async fn get_show_tray_icon(&self) -> bool {
         let settings = self.settings.read().await;
         settings.show_tray_icon.unwrap()
@@ -222,6 +364,16 @@ impl SettingsHandle {
         settings.allow_network_access = Some(enabled);
     }
 
+    pub async fn get_http_rate_limit(&self) -> HttpRateLimit {
+        let settings = self.settings.read().await;
+        settings.http_rate_limit.clone().unwrap_or_default()
+    }
+
+    pub async fn set_http_rate_limit(&self, limit: HttpRateLimit) {
+        let mut settings = self.settings.write().await;
+        settings.http_rate_limit = Some(limit);
+    }
+
     pub async fn set_macos_handle_aggregates(&self, enabled: bool) {
         let mut settings = self.settings.write().await;
         settings.macos_handle_aggregates = Some(enabled);
@@ -295,6 +447,34 @@ impl SettingsHandle {
         }
     }
 
+    pub fn get_sample_cache_directory(&self) -> PathBuf {
+        self.get_default_path(Paths::SampleCache)
+    }
+
+    pub fn get_icon_cache_directory(&self) -> PathBuf {
+        self.get_default_path(Paths::IconCache)
+    }
+
+    pub async fn get_sample_cache_enabled(&self) -> bool {
+        let settings = self.settings.read().await;
+        settings.sample_cache_enabled.unwrap_or(false)
+    }
+
+    pub async fn set_sample_cache_enabled(&self, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        settings.sample_cache_enabled = Some(enabled);
+    }
+
+    pub async fn get_sample_cache_size(&self) -> u32 {
+        let settings = self.settings.read().await;
+        settings.sample_cache_size.unwrap_or(512)
+    }
+
+    pub async fn set_sample_cache_size(&self, size: u32) {
+        let mut settings = self.settings.write().await;
+        settings.sample_cache_size = Some(size);
+    }
+
     pub async fn set_log_level(&self, level: LogLevel) {
         let mut settings = self.settings.write().await;
         settings.log_level = Some(level);
@@ -305,6 +485,16 @@ impl SettingsHandle {
         settings.log_level.clone().unwrap_or(LogLevel::Info)
     }
 
+    pub async fn set_log_filter(&self, filter: Option<String>) {
+        let mut settings = self.settings.write().await;
+        settings.log_filter = filter;
+    }
+
+    pub async fn get_log_filter(&self) -> Option<String> {
+        let settings = self.settings.read().await;
+        settings.log_filter.clone()
+    }
+
     pub async fn get_open_ui_on_launch(&self) -> bool {
         let settings = self.settings.read().await;
         settings.open_ui_on_launch.unwrap_or(false)
@@ -325,6 +515,22 @@ impl SettingsHandle {
         settings.activate = activate;
     }
 
+    pub async fn get_ui_path(&self) -> Option<PathBuf> {
+        let settings = self.settings.read().await;
+        settings.ui_path.clone()
+    }
+
+    pub async fn set_ui_path(&self, path: Option<PathBuf>) {
+        let mut settings = self.settings.write().await;
+        settings.ui_path = path;
+    }
+
+    /// Every device settings are kept for, whether it's connected or not.
+    pub async fn get_device_serials(&self) -> Vec<String> {
+        let settings = self.settings.read().await;
+        settings.devices.as_ref().unwrap().keys().cloned().collect()
+    }
+
     pub async fn get_device_profile_name(&self, device_serial: &str) -> Option<String> {
         let settings = self.settings.read().await;
         settings
@@ -419,20 +625,37 @@ impl SettingsHandle {
         500
     }
 
-    // I absolutely hate this naming.. O_O
-    pub async fn get_device_chat_mute_mutes_mic_to_chat(&self, device_serial: &str) -> bool {
+    /// The outputs the Mic is removed from while Voice Chat is muted to All, settings from
+    /// before this was configurable only had the Chat Mic as an option.
+    pub async fn get_device_vc_mute_outputs(&self, device_serial: &str) -> Vec<OutputDevice> {
+        let settings = self.settings.read().await;
+        let device = settings.devices.as_ref().unwrap().get(device_serial);
+        if let Some(outputs) = device.and_then(|d| d.chat_mute_mutes_mic_outputs.clone()) {
+            return outputs;
+        }
+
+        match device
+            .and_then(|d| d.chat_mute_mutes_mic_to_chat)
+            .unwrap_or(true)
+        {
+            true => vec![OutputDevice::ChatMic],
+            false => vec![],
+        }
+    }
+
+    pub async fn get_device_silent_faders(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
         let value = settings
             .devices
             .as_ref()
             .unwrap()
             .get(device_serial)
-            .map(|d| d.chat_mute_mutes_mic_to_chat.unwrap_or(true));
+            .map(|d| d.silent_faders.unwrap_or(false));
 
         if let Some(value) = value {
             return value;
         }
-        true
+        false
     }
 
     pub async fn get_device_lock_faders(&self, device_serial: &str) -> bool {
@@ -450,179 +673,842 @@ impl SettingsHandle {
         true
     }
 
-    pub async fn get_enable_monitor_with_fx(&self, device_serial: &str) -> bool {
+    pub async fn get_device_sample_clear_timeout(&self, device_serial: &str) -> u16 {
         let settings = self.settings.read().await;
         let value = settings
             .devices
             .as_ref()
             .unwrap()
             .get(device_serial)
-            .map(|d| d.enable_monitor_with_fx.unwrap_or(false));
+            .map(|d| d.sample_clear_timeout.unwrap_or(10));
+
         if let Some(value) = value {
             return value;
         }
-        false
+        10
     }
 
-    pub async fn get_device_vod_mode(&self, device_serial: &str) -> VodMode {
+    pub async fn get_device_phantom_confirmation_required(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
         let value = settings
             .devices
             .as_ref()
             .unwrap()
             .get(device_serial)
-            .map(|d| d.vod_mode.unwrap_or(Routable));
+            .map(|d| d.phantom_confirmation_required.unwrap_or(false));
 
         if let Some(value) = value {
             return value;
         }
-        Routable
+        false
     }
 
-    pub async fn get_sampler_reset_on_clear(&self, device_serial: &str) -> bool {
+    pub async fn get_device_adopt_hardware_state(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
-        settings
+        let value = settings
             .devices
             .as_ref()
             .unwrap()
             .get(device_serial)
-            .map(|d| d.sampler_reset_on_clear.unwrap_or(true))
-            .unwrap_or(true)
-    }
+            .map(|d| d.adopt_hardware_state.unwrap_or(false));
 
-    pub async fn get_sample_gain_percent(&self, name: String) -> u8 {
-        let settings = self.settings.read().await;
-        if let Some(gain) = &settings.sample_gain {
-            if let Some(percent) = gain.get(&*name) {
-                return *percent;
-            }
-            return 100;
+        if let Some(value) = value {
+            return value;
         }
-        100
+        false
     }
 
-    /// This exists so we don't have to repeatedly lock / unlock the struct to get individual
-    /// gain values. We can simply clone off the list, and let it be handled elsewhere.
-    pub async fn get_sample_gain_list(&self) -> HashMap<String, u8> {
+    pub async fn get_device_effect_preview_on_hold(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
-        if let Some(gain) = &settings.sample_gain {
-            return gain.clone();
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.effect_preview_on_hold.unwrap_or(false));
+
+        if let Some(value) = value {
+            return value;
         }
-        HashMap::default()
+        false
     }
 
-    pub async fn set_device_profile_name(&self, device_serial: &str, profile_name: &str) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_clear_effects_on_hold(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        profile_name.clone_into(&mut entry.profile);
+            .get(device_serial)
+            .map(|d| d.clear_effects_on_hold.unwrap_or(true))
+            .unwrap_or(true)
     }
 
-    pub async fn set_device_mic_profile_name(&self, device_serial: &str, mic_profile_name: &str) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_switch_monitor_on_unplug(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        mic_profile_name.clone_into(&mut entry.mic_profile);
+            .get(device_serial)
+            .map(|d| d.switch_monitor_on_unplug.unwrap_or(false))
+            .unwrap_or(false)
     }
 
-    pub async fn set_device_shutdown_commands(
-        &self,
-        device_serial: &str,
-        commands: Vec<GoXLRCommand>,
-    ) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_save_on_shutdown(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        let value = settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        commands.clone_into(&mut entry.shutdown_commands);
+            .get(device_serial)
+            .map(|d| d.save_on_shutdown.unwrap_or(false));
+
+        if let Some(value) = value {
+            return value;
+        }
+        false
     }
 
-    pub async fn set_device_sleep_commands(
-        &self,
-        device_serial: &str,
-        commands: Vec<GoXLRCommand>,
-    ) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_fine_fader_adjust(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        commands.clone_into(&mut entry.sleep_commands);
+            .get(device_serial)
+            .map(|d| d.fine_fader_adjust.unwrap_or(false))
+            .unwrap_or(false)
     }
 
-    pub async fn set_device_wake_commands(&self, device_serial: &str, commands: Vec<GoXLRCommand>) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_convert_samples(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        commands.clone_into(&mut entry.wake_commands);
+            .get(device_serial)
+            .map(|d| d.convert_samples.unwrap_or(false))
+            .unwrap_or(false)
     }
 
-    pub async fn set_device_sampler_pre_buffer(&self, device_serial: &str, duration: u16) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_poll_interval(&self, device_serial: &str) -> u16 {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        entry.sampler_pre_buffer = Some(duration);
+            .get(device_serial)
+            .and_then(|d| d.poll_interval)
+            .unwrap_or(DEFAULT_POLL_INTERVAL)
     }
 
-    pub async fn set_device_mute_hold_duration(&self, device_serial: &str, duration: u16) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_stall_timeout(&self, device_serial: &str) -> u16 {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        entry.hold_delay = Some(duration);
+            .get(device_serial)
+            .and_then(|d| d.stall_timeout)
+            .unwrap_or(DEFAULT_STALL_TIMEOUT)
     }
 
-    pub async fn set_device_vc_mute_also_mute_cm(&self, device_serial: &str, setting: bool) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_reactive_lighting(
+        &self,
+        device_serial: &str,
+    ) -> Option<ReactiveConfig> {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        entry.chat_mute_mutes_mic_to_chat = Some(setting);
+            .get(device_serial)
+            .and_then(|d| d.reactive_lighting.clone())
     }
 
-    pub async fn set_device_lock_faders(&self, device_serial: &str, setting: bool) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_high_contrast(
+        &self,
+        device_serial: &str,
+    ) -> Option<HighContrastConfig> {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
             .unwrap()
-            .entry(device_serial.to_owned())
-            .or_insert_with(DeviceSettings::default);
-        entry.lock_faders = Some(setting);
+            .get(device_serial)
+            .and_then(|d| d.high_contrast.clone())
     }
 
-    pub async fn set_enable_monitor_with_fx(&self, device_serial: &str, setting: bool) {
-        let mut settings = self.settings.write().await;
-        let entry = settings
+    pub async fn get_device_privacy_mode(&self, device_serial: &str) -> Option<PrivacyConfig> {
+        let settings = self.settings.read().await;
+        settings
             .devices
-            .as_mut()
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .and_then(|d| d.privacy_mode.clone())
+    }
+
+    pub async fn get_device_headset_mode(&self, device_serial: &str) -> Option<HeadsetSnapshot> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .and_then(|d| d.headset_mode.clone())
+    }
+
+    pub async fn get_device_headset_sidetone(&self, device_serial: &str) -> u8 {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .and_then(|d| d.headset_sidetone)
+            .unwrap_or(DEFAULT_SIDETONE)
+    }
+
+    pub async fn get_device_submix_default_linked(
+        &self,
+        device_serial: &str,
+    ) -> HashMap<SubMixChannelName, bool> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.submix_default_linked.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_enable_monitor_with_fx(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.enable_monitor_with_fx.unwrap_or(false));
+        if let Some(value) = value {
+            return value;
+        }
+        false
+    }
+
+    pub async fn get_device_vod_mode(&self, device_serial: &str) -> VodMode {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.vod_mode.unwrap_or(Routable));
+
+        if let Some(value) = value {
+            return value;
+        }
+        Routable
+    }
+
+    pub async fn get_device_vod_samples(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.vod_samples.unwrap_or(true))
+            .unwrap_or(true)
+    }
+
+    pub async fn get_sampler_reset_on_clear(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.sampler_reset_on_clear.unwrap_or(true))
+            .unwrap_or(true)
+    }
+
+    pub async fn get_sample_gain_percent(&self, name: String) -> u8 {
+        let settings = self.settings.read().await;
+        if let Some(gain) = &settings.sample_gain {
+            if let Some(percent) = gain.get(&*name) {
+                return *percent;
+            }
+            return 100;
+        }
+        100
+    }
+
+    /// This exists so we don't have to repeatedly lock / unlock the struct to get individual
+    /// gain values. We can simply clone off the list, and let it be handled elsewhere.
+    pub async fn get_sample_gain_list(&self) -> HashMap<String, u8> {
+        let settings = self.settings.read().await;
+        if let Some(gain) = &settings.sample_gain {
+            return gain.clone();
+        }
+        HashMap::default()
+    }
+
+    pub async fn get_schedules(&self) -> HashMap<String, ScheduledCommand> {
+        let settings = self.settings.read().await;
+        settings.schedules.clone().unwrap_or_default()
+    }
+
+    pub async fn set_schedule(&self, id: String, schedule: ScheduledCommand) {
+        let mut settings = self.settings.write().await;
+        settings
+            .schedules
+            .get_or_insert_with(Default::default)
+            .insert(id, schedule);
+    }
+
+    pub async fn remove_schedule(&self, id: &str) -> bool {
+        let mut settings = self.settings.write().await;
+        match &mut settings.schedules {
+            Some(schedules) => schedules.remove(id).is_some(),
+            None => false,
+        }
+    }
+
+    pub async fn get_mute_sync(&self, application: MuteSyncApplication) -> MuteSyncSettings {
+        let settings = self.settings.read().await;
+        settings
+            .mute_sync
+            .as_ref()
+            .and_then(|mute_sync| mute_sync.get(&application))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn set_mute_sync_enabled(&self, application: MuteSyncApplication, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        settings.mute_sync_mut(application).enabled = enabled;
+    }
+
+    pub async fn set_mute_sync_direction(
+        &self,
+        application: MuteSyncApplication,
+        direction: MuteSyncDirection,
+    ) {
+        let mut settings = self.settings.write().await;
+        settings.mute_sync_mut(application).direction = direction;
+    }
+
+    /// Sets the client details, any tokens were issued to the previous client so are dropped.
+    pub async fn set_mute_sync_client(
+        &self,
+        application: MuteSyncApplication,
+        client_id: String,
+        client_secret: String,
+    ) {
+        let mut settings = self.settings.write().await;
+        let mute_sync = settings.mute_sync_mut(application);
+        mute_sync.client_id = Some(client_id);
+        mute_sync.client_secret = Some(client_secret);
+        mute_sync.access_token = None;
+        mute_sync.refresh_token = None;
+    }
+
+    pub async fn set_mute_sync_tokens(
+        &self,
+        application: MuteSyncApplication,
+        access_token: Option<String>,
+        refresh_token: Option<String>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let mute_sync = settings.mute_sync_mut(application);
+        mute_sync.access_token = access_token;
+        mute_sync.refresh_token = refresh_token;
+    }
+
+    #[cfg(feature = "midi")]
+    pub async fn get_midi(&self) -> MidiSettings {
+        let settings = self.settings.read().await;
+        settings.midi.clone().unwrap_or_default()
+    }
+
+    pub async fn set_midi_enabled(&self, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        settings.midi_mut().enabled = enabled;
+    }
+
+    pub async fn set_midi_port(&self, port: Option<String>) {
+        let mut settings = self.settings.write().await;
+        settings.midi_mut().port = port;
+    }
+
+    pub async fn set_midi_mapping(&self, mapping: MidiMapping) {
+        let mut settings = self.settings.write().await;
+        let mappings = &mut settings.midi_mut().mappings;
+        mappings.retain(|existing| existing.control != mapping.control);
+        mappings.push(mapping);
+    }
+
+    /// Returns false if the control wasn't mapped.
+    pub async fn remove_midi_mapping(&self, control: MidiControl) -> bool {
+        let mut settings = self.settings.write().await;
+        let mappings = &mut settings.midi_mut().mappings;
+        let count = mappings.len();
+        mappings.retain(|existing| existing.control != control);
+        mappings.len() != count
+    }
+
+    /// The profile rules, in order of precedence.
+    pub async fn get_profile_rules(&self) -> Vec<ProfileRule> {
+        let settings = self.settings.read().await;
+        let mut rules = settings.profile_rules.clone().unwrap_or_default();
+        rules.sort_by_key(|rule| rule.trigger);
+        rules
+    }
+
+    pub async fn set_profile_rule(&self, rule: ProfileRule) {
+        let mut settings = self.settings.write().await;
+        let rules = settings.profile_rules.get_or_insert_with(Default::default);
+        rules.retain(|existing| existing.trigger != rule.trigger);
+        rules.push(rule);
+    }
+
+    /// Returns false if there was no rule for the trigger.
+    pub async fn remove_profile_rule(&self, trigger: ProfileTrigger) -> bool {
+        let mut settings = self.settings.write().await;
+        let rules = settings.profile_rules.get_or_insert_with(Default::default);
+        let count = rules.len();
+        rules.retain(|existing| existing.trigger != trigger);
+        rules.len() != count
+    }
+
+    /// The date (as YYYY-MM-DD) and firmware the device had when it last connected.
+    pub async fn get_device_last_connect(
+        &self,
+        device_serial: &str,
+    ) -> (Option<String>, Option<VersionNumber>) {
+        let settings = self.settings.read().await;
+        match settings.devices.as_ref().unwrap().get(device_serial) {
+            Some(device) => (
+                device.last_connect_date.clone(),
+                device.last_firmware.clone(),
+            ),
+            None => (None, None),
+        }
+    }
+
+    pub async fn set_device_last_connect(
+        &self,
+        device_serial: &str,
+        date: String,
+        firmware: VersionNumber,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.last_connect_date = Some(date);
+        entry.last_firmware = Some(firmware);
+    }
+
+    #[cfg(feature = "telemetry")]
+    pub async fn get_telemetry(&self) -> TelemetrySettings {
+        let settings = self.settings.read().await;
+        settings.telemetry.clone().unwrap_or_default()
+    }
+
+    /// Anything counted is discarded when disabled, enabling starts counting afresh from now.
+    pub async fn set_telemetry_enabled(&self, enabled: bool, now: i64) {
+        let mut settings = self.settings.write().await;
+        let telemetry = settings.telemetry_mut();
+        if enabled != telemetry.enabled {
+            telemetry.period_start = enabled.then_some(now);
+            telemetry.counts = Default::default();
+        }
+        telemetry.enabled = enabled;
+    }
+
+    pub async fn set_telemetry_endpoint(&self, endpoint: Option<String>) {
+        let mut settings = self.settings.write().await;
+        settings.telemetry_mut().endpoint = endpoint;
+    }
+
+    /// Counts are only changed in memory, they're written with the next save.
+    #[cfg(feature = "telemetry")]
+    pub async fn update_telemetry_counts(&self, update: impl FnOnce(&mut TelemetryCounts)) {
+        // This is called for every command, so while telemetry is off (as it is by default) it
+        // shouldn't hold up everything else waiting on the settings
+        let settings = self.settings.read().await;
+        let enabled = settings.telemetry.as_ref().is_some_and(|t| t.enabled);
+        drop(settings);
+        if !enabled {
+            return;
+        }
+
+        // It may have been disabled in between, so this is checked again below
+        let mut settings = self.settings.write().await;
+        let telemetry = settings.telemetry_mut();
+        if telemetry.enabled {
+            update(&mut telemetry.counts);
+        }
+    }
+
+    /// Takes the counts to be sent, and starts a new period from now.
+    #[cfg(feature = "telemetry")]
+    pub async fn take_telemetry_counts(&self, now: i64) -> TelemetryCounts {
+        let mut settings = self.settings.write().await;
+        let telemetry = settings.telemetry_mut();
+        telemetry.period_start = Some(now);
+        telemetry.last_submitted = Some(now);
+        std::mem::take(&mut telemetry.counts)
+    }
+
+    pub async fn set_device_profile_name(&self, device_serial: &str, profile_name: &str) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        profile_name.clone_into(&mut entry.profile);
+    }
+
+    pub async fn set_device_mic_profile_name(&self, device_serial: &str, mic_profile_name: &str) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        mic_profile_name.clone_into(&mut entry.mic_profile);
+    }
+
+    pub async fn set_device_shutdown_commands(
+        &self,
+        device_serial: &str,
+        commands: Vec<GoXLRCommand>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        commands.clone_into(&mut entry.shutdown_commands);
+    }
+
+    pub async fn set_device_sleep_commands(
+        &self,
+        device_serial: &str,
+        commands: Vec<GoXLRCommand>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        commands.clone_into(&mut entry.sleep_commands);
+    }
+
+    pub async fn set_device_wake_commands(&self, device_serial: &str, commands: Vec<GoXLRCommand>) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        commands.clone_into(&mut entry.wake_commands);
+    }
+
+    pub async fn set_device_sampler_pre_buffer(&self, device_serial: &str, duration: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_pre_buffer = Some(duration);
+    }
+
+    pub async fn set_device_mute_hold_duration(&self, device_serial: &str, duration: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.hold_delay = Some(duration);
+    }
+
+    pub async fn set_device_vc_mute_outputs(
+        &self,
+        device_serial: &str,
+        outputs: Vec<OutputDevice>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.chat_mute_mutes_mic_to_chat = None;
+        entry.chat_mute_mutes_mic_outputs = Some(outputs);
+    }
+
+    pub async fn set_device_silent_faders(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.silent_faders = Some(setting);
+    }
+
+    pub async fn set_device_lock_faders(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.lock_faders = Some(setting);
+    }
+
+    pub async fn set_device_sample_clear_timeout(&self, device_serial: &str, timeout: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sample_clear_timeout = Some(timeout);
+    }
+
+    pub async fn set_device_adopt_hardware_state(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.adopt_hardware_state = Some(setting);
+    }
+
+    pub async fn set_device_phantom_confirmation_required(
+        &self,
+        device_serial: &str,
+        setting: bool,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.phantom_confirmation_required = Some(setting);
+    }
+
+    pub async fn set_device_effect_preview_on_hold(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.effect_preview_on_hold = Some(setting);
+    }
+
+    pub async fn set_device_clear_effects_on_hold(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.clear_effects_on_hold = Some(setting);
+    }
+
+    pub async fn set_device_switch_monitor_on_unplug(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.switch_monitor_on_unplug = Some(setting);
+    }
+
+    pub async fn set_device_save_on_shutdown(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.save_on_shutdown = Some(setting);
+    }
+
+    pub async fn set_device_fine_fader_adjust(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.fine_fader_adjust = Some(setting);
+    }
+
+    pub async fn set_device_convert_samples(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.convert_samples = Some(setting);
+    }
+
+    pub async fn set_device_poll_interval(&self, device_serial: &str, interval: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.poll_interval = Some(interval);
+    }
+
+    pub async fn set_device_stall_timeout(&self, device_serial: &str, timeout: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.stall_timeout = Some(timeout);
+    }
+
+    pub async fn set_device_reactive_lighting(
+        &self,
+        device_serial: &str,
+        config: Option<ReactiveConfig>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.reactive_lighting = config;
+    }
+
+    pub async fn set_device_high_contrast(
+        &self,
+        device_serial: &str,
+        config: Option<HighContrastConfig>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.high_contrast = config;
+    }
+
+    pub async fn set_device_privacy_mode(
+        &self,
+        device_serial: &str,
+        config: Option<PrivacyConfig>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.privacy_mode = config;
+    }
+
+    pub async fn set_device_headset_mode(
+        &self,
+        device_serial: &str,
+        snapshot: Option<HeadsetSnapshot>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.headset_mode = snapshot;
+    }
+
+    pub async fn set_device_headset_sidetone(&self, device_serial: &str, sidetone: u8) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.headset_sidetone = Some(sidetone);
+    }
+
+    pub async fn set_device_submix_default_linked(
+        &self,
+        device_serial: &str,
+        channel: SubMixChannelName,
+        linked: bool,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.submix_default_linked.insert(channel, linked);
+    }
+
+    pub async fn set_enable_monitor_with_fx(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
             .unwrap()
             .entry(device_serial.to_owned())
             .or_insert_with(DeviceSettings::default);
@@ -640,6 +1526,17 @@ impl SettingsHandle {
         entry.vod_mode = Some(setting);
     }
 
+    pub async fn set_device_vod_samples(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.vod_samples = Some(setting);
+    }
+
     pub async fn set_sampler_reset_on_clear(&self, device_serial: &str, setting: bool) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -668,6 +1565,7 @@ pub struct Settings {
     selected_locale: Option<String>,
     tts_enabled: Option<bool>,
     allow_network_access: Option<bool>,
+    http_rate_limit: Option<HttpRateLimit>,
     macos_handle_aggregates: Option<bool>,
     profile_directory: Option<PathBuf>,
     mic_profile_directory: Option<PathBuf>,
@@ -677,13 +1575,74 @@ pub struct Settings {
     logs_directory: Option<PathBuf>,
     backup_directory: Option<PathBuf>,
     log_level: Option<LogLevel>,
+    log_filter: Option<String>,
     open_ui_on_launch: Option<bool>,
     activate: Option<String>,
+    ui_path: Option<PathBuf>,
+    sample_cache_enabled: Option<bool>,
+    sample_cache_size: Option<u32>,
     devices: Option<HashMap<String, DeviceSettings>>,
     sample_gain: Option<HashMap<String, u8>>,
+    schedules: Option<HashMap<String, ScheduledCommand>>,
+    mute_sync: Option<HashMap<MuteSyncApplication, MuteSyncSettings>>,
+    midi: Option<MidiSettings>,
+    telemetry: Option<TelemetrySettings>,
+    profile_rules: Option<Vec<ProfileRule>>,
+}
+
+/// How an application's mute is kept in step with the GoXLR, the tokens are those issued when
+/// the application last authorised us.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MuteSyncSettings {
+    pub enabled: bool,
+    pub direction: MuteSyncDirection,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+}
+
+/// Which MIDI port is listened to, and what its controls do. These are kept even when the
+/// daemon is built without MIDI support.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiSettings {
+    pub enabled: bool,
+    pub port: Option<String>,
+    pub mappings: Vec<MidiMapping>,
+}
+
+/// Whether anonymous usage statistics are collected, and what's been counted since they were
+/// last sent. These are kept even when the daemon is built without them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+
+    /// When counting began, the statistics are sent once this is a week old
+    pub period_start: Option<i64>,
+    pub last_submitted: Option<i64>,
+    pub counts: TelemetryCounts,
 }
 
 impl Settings {
+    fn midi_mut(&mut self) -> &mut MidiSettings {
+        self.midi.get_or_insert_with(Default::default)
+    }
+
+    fn telemetry_mut(&mut self) -> &mut TelemetrySettings {
+        self.telemetry.get_or_insert_with(Default::default)
+    }
+
+    fn mute_sync_mut(&mut self, application: MuteSyncApplication) -> &mut MuteSyncSettings {
+        self.mute_sync
+            .get_or_insert_with(Default::default)
+            .entry(application)
+            .or_default()
+    }
+
     pub fn read(path: &Path) -> Result<Option<Settings>> {
         match File::open(path) {
             Ok(reader) => {
@@ -713,7 +1672,7 @@ impl Settings {
         }
     }
 
-    pub fn write(&self, path: &Path) -> Result<()> {
+    pub fn write(&self, file_system: &dyn FileSystem, path: &Path) -> Result<()> {
         debug!("Saving Settings");
         if let Some(parent) = path.parent() {
             if let Err(e) = create_dir_all(parent) {
@@ -726,28 +1685,7 @@ impl Settings {
             }
         }
 
-        let mut tmp_file_name = path.to_path_buf();
-        tmp_file_name.set_extension("tmp");
-        if tmp_file_name.exists() {
-            debug!("Temporary file already exists? Removing.");
-            fs::remove_file(&tmp_file_name)?;
-        }
-
-        debug!("Creating Temporary Save File: {:?}", tmp_file_name);
-        let temp_file = File::create(&tmp_file_name)?;
-        serde_json::to_writer_pretty(&temp_file, self)?;
-        temp_file.sync_all()?;
-        drop(temp_file);
-
-        debug!("Save Complete and synced, renaming to {:?}", path);
-        if path.exists() {
-            debug!("Target exists, removing..");
-            fs::remove_file(path).unwrap_or_else(|e| {
-                warn!("Error Removing File: {}", e);
-            });
-        }
-        debug!("Renaming {:?} to {:?}", tmp_file_name, path);
-        fs::rename(tmp_file_name, path)?;
+        write_atomic(file_system, path, &serde_json::to_vec_pretty(self)?)?;
 
         debug!("Settings Saved.");
         Ok(())
@@ -763,21 +1701,86 @@ struct DeviceSettings {
     hold_delay: Option<u16>,
     sampler_pre_buffer: Option<u16>,
 
-    // 'Voice Chat Mute All Also Mutes Mic to Chat Mic' O_O
+    // 'Voice Chat Mute All Also Mutes Mic to Chat Mic' O_O, only read to migrate it to the
+    // outputs below
     chat_mute_mutes_mic_to_chat: Option<bool>,
 
+    // The outputs the Mic is removed from while Voice Chat is muted to All
+    chat_mute_mutes_mic_outputs: Option<Vec<OutputDevice>>,
+
     // Disables the Movement of the Faders when Muting to All (full device only)
     lock_faders: Option<bool>,
 
+    // Avoids moving the Faders unless the volume can't be set any other way (full device only)
+    silent_faders: Option<bool>,
+
     // Enable Monitoring when FX are Enabled
     enable_monitor_with_fx: Option<bool>,
 
     // Clear Sample Settings when Clearing Button
     sampler_reset_on_clear: Option<bool>,
 
+    // Seconds before an armed Sample Clear disarms itself (0 leaves it armed)
+    sample_clear_timeout: Option<u16>,
+
     // VoD 'Mode'
     vod_mode: Option<VodMode>,
 
+    // Whether Samples reach the VOD mix while it follows the Broadcast Mix (Stream No Music)
+    vod_samples: Option<bool>,
+
+    // Holding an Effect Select button previews the bank until it's released
+    effect_preview_on_hold: Option<bool>,
+
+    // Holding the FX button turns off Megaphone, Robot and Hard Tune
+    clear_effects_on_hold: Option<bool>,
+
+    // Monitor the Line Out while the Headphones are unplugged
+    switch_monitor_on_unplug: Option<bool>,
+
+    // Write any unsaved profile changes when the daemon stops
+    save_on_shutdown: Option<bool>,
+
+    // Keep the volumes the faders are at when the device connects, rather than the profile's
+    adopt_hardware_state: Option<bool>,
+
+    // Switching to a Condenser Mic (and enabling 48V) must be confirmed by the client
+    phantom_confirmation_required: Option<bool>,
+
+    // On the Mini, moving a fader while holding its mute button adjusts the volume finely
+    fine_fader_adjust: Option<bool>,
+
+    // How often (in milliseconds) the device is read for changes while it's in use
+    poll_interval: Option<u16>,
+
+    // How long (in seconds) the device can go unread before the watchdog resets it
+    stall_timeout: Option<u16>,
+
+    // Convert samples to 48kHz 16bit WAVs when they're added to a button
+    convert_samples: Option<bool>,
+
+    // Lighting which follows the Mic level, None when it's off
+    reactive_lighting: Option<ReactiveConfig>,
+
+    // Button lighting which shows state by brightness, None when it's off
+    high_contrast: Option<HighContrastConfig>,
+
+    // Holds the Mic muted until a client unlocks it, None when it's off
+    privacy_mode: Option<PrivacyConfig>,
+
+    // The settings replaced by headset mode, None when it's off
+    headset_mode: Option<HeadsetSnapshot>,
+
+    // How loud (as a percentage) the Mic is heard in the Headphones in headset mode
+    headset_sidetone: Option<u8>,
+
+    // Whether channels start linked when submixes are enabled, for profiles which don't say
+    submix_default_linked: HashMap<SubMixChannelName, bool>,
+
+    // When the device last connected, for the profile rules
+    last_connect_date: Option<String>,
+    last_firmware: Option<VersionNumber>,
+
     // 'Shutdown' commands..
     shutdown_commands: Vec<GoXLRCommand>,
     sleep_commands: Vec<GoXLRCommand>,
@@ -793,11 +1796,33 @@ impl Default for DeviceSettings {
             hold_delay: Some(500),
             sampler_pre_buffer: None,
             chat_mute_mutes_mic_to_chat: Some(true),
+            chat_mute_mutes_mic_outputs: None,
             lock_faders: Some(false),
+            silent_faders: Some(false),
             enable_monitor_with_fx: Some(false),
             sampler_reset_on_clear: Some(true),
+            sample_clear_timeout: Some(10),
 
             vod_mode: Some(Routable),
+            vod_samples: Some(true),
+            effect_preview_on_hold: Some(false),
+            clear_effects_on_hold: Some(true),
+            switch_monitor_on_unplug: Some(false),
+            save_on_shutdown: Some(false),
+            adopt_hardware_state: Some(false),
+            phantom_confirmation_required: Some(false),
+            fine_fader_adjust: Some(false),
+            poll_interval: Some(DEFAULT_POLL_INTERVAL),
+            stall_timeout: Some(DEFAULT_STALL_TIMEOUT),
+            convert_samples: Some(false),
+            reactive_lighting: None,
+            high_contrast: None,
+            privacy_mode: None,
+            headset_mode: None,
+            headset_sidetone: None,
+            submix_default_linked: HashMap::new(),
+            last_connect_date: None,
+            last_firmware: None,
 
             shutdown_commands: vec![],
             sleep_commands: vec![],
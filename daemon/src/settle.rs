@@ -0,0 +1,169 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use goxlr_types::{ChannelName, FaderName, SampleButtons};
+use tokio::sync::oneshot;
+
+/*
+ * Commands return as soon as they've been run, but some leave work behind which the device
+ * finishes over the following updates (scribbles are drawn one at a time, faders take a moment
+ * to move, and so on). A Settle request waits until there's none left for the device, and no
+ * commands are queued behind it, so a script can tell when everything it sent has reached the
+ * hardware.
+ */
+
+// Waiting any longer than this is almost certainly a mistake
+const MAX_SETTLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Work which carries on after the command that started it has returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingWork {
+    QueuedCommands(usize),
+    Scribble(FaderName),
+    FaderMoving(FaderName),
+    DuckRelease(ChannelName),
+    SampleAnalysis,
+    Normalising,
+    GuardFlash(SampleButtons),
+    Identify,
+    PhantomPower,
+}
+
+impl Display for PendingWork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingWork::QueuedCommands(count) => write!(f, "{} queued commands", count),
+            PendingWork::Scribble(fader) => write!(f, "drawing the scribble on Fader {}", fader),
+            PendingWork::FaderMoving(fader) => write!(f, "moving Fader {}", fader),
+            PendingWork::DuckRelease(channel) => write!(f, "restoring the {} volume", channel),
+            PendingWork::SampleAnalysis => write!(f, "analysing a sample"),
+            PendingWork::Normalising => write!(f, "normalising samples"),
+            PendingWork::GuardFlash(button) => write!(f, "flashing sample button {}", button),
+            PendingWork::Identify => write!(f, "identifying the device"),
+            PendingWork::PhantomPower => write!(f, "waiting for phantom power to settle"),
+        }
+    }
+}
+
+struct SettleWaiter {
+    serial: String,
+    timeout: Duration,
+    started: Instant,
+    sender: oneshot::Sender<Result<()>>,
+}
+
+#[derive(Default)]
+pub struct SettleWaiters {
+    waiters: Vec<SettleWaiter>,
+}
+
+impl SettleWaiters {
+    pub fn add(
+        &mut self,
+        serial: String,
+        timeout: u32,
+        sender: oneshot::Sender<Result<()>>,
+        now: Instant,
+    ) {
+        let timeout = Duration::from_millis(timeout.into());
+        if timeout > MAX_SETTLE_TIMEOUT {
+            let error = anyhow!(
+                "The Settle timeout can't be longer than {}ms",
+                MAX_SETTLE_TIMEOUT.as_millis()
+            );
+            let _ = sender.send(Err(error));
+            return;
+        }
+
+        self.waiters.push(SettleWaiter {
+            serial,
+            timeout,
+            started: now,
+            sender,
+        });
+    }
+
+    /// Answers anyone waiting on a device which has settled, gone, or taken too long. The
+    /// pending function gives the work left for a device, or None when it's not connected.
+    pub fn check(&mut self, now: Instant, pending: impl Fn(&str) -> Option<Vec<PendingWork>>) {
+        let mut waiting = vec![];
+        for waiter in self.waiters.drain(..) {
+            let result = match pending(&waiter.serial) {
+                None => Err(anyhow!("Device {} is not connected", waiter.serial)),
+                Some(work) if work.is_empty() => Ok(()),
+                Some(work) if now.duration_since(waiter.started) >= waiter.timeout => {
+                    let work: Vec<String> = work.iter().map(|work| work.to_string()).collect();
+                    Err(anyhow!(
+                        "Device {} didn't settle within {}ms, still {}",
+                        waiter.serial,
+                        waiter.timeout.as_millis(),
+                        work.join(", ")
+                    ))
+                }
+                Some(_) => {
+                    waiting.push(waiter);
+                    continue;
+                }
+            };
+            let _ = waiter.sender.send(result);
+        }
+        self.waiters = waiting;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waiters_are_answered_once_settled() {
+        let now = Instant::now();
+        let mut waiters = SettleWaiters::default();
+        let (tx, mut rx) = oneshot::channel();
+        waiters.add(String::from("S1"), 1000, tx, now);
+
+        let scribble = vec![PendingWork::Scribble(FaderName::A)];
+        waiters.check(now, |_| Some(scribble.clone()));
+        assert!(rx.try_recv().is_err());
+
+        waiters.check(now + Duration::from_millis(50), |_| Some(vec![]));
+        assert!(rx.try_recv().unwrap().is_ok());
+        assert!(waiters.waiters.is_empty());
+    }
+
+    #[test]
+    fn timeouts_describe_what_was_pending() {
+        let now = Instant::now();
+        let mut waiters = SettleWaiters::default();
+        let (tx, mut rx) = oneshot::channel();
+        waiters.add(String::from("S1"), 100, tx, now);
+
+        let pending = vec![
+            PendingWork::QueuedCommands(2),
+            PendingWork::FaderMoving(FaderName::B),
+        ];
+        waiters.check(now + Duration::from_millis(100), |_| Some(pending.clone()));
+        let error = rx.try_recv().unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Device S1 didn't settle within 100ms, still 2 queued commands, moving Fader B"
+        );
+    }
+
+    #[test]
+    fn waiters_need_a_device_and_a_sensible_timeout() {
+        let now = Instant::now();
+        let mut waiters = SettleWaiters::default();
+
+        let (tx, mut rx) = oneshot::channel();
+        waiters.add(String::from("S1"), 120000, tx, now);
+        assert!(rx.try_recv().unwrap().is_err());
+
+        let (tx, mut rx) = oneshot::channel();
+        waiters.add(String::from("S2"), 1000, tx, now);
+        waiters.check(now, |serial| (serial == "S1").then(Vec::new));
+        let error = rx.try_recv().unwrap().unwrap_err();
+        assert!(error.to_string().contains("not connected"));
+    }
+}
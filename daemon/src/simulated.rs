@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use goxlr_ipc::{ColourWay, HardwareStatus, UsbProductInformation};
+use goxlr_types::{DeviceType, FirmwareVersions, VersionNumber};
+use goxlr_usb::commands::Command;
+use goxlr_usb::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use tokio::sync::mpsc::Sender;
+
+use crate::cli::SimulatedDevice;
+
+/*
+ * A GoXLR which only exists in memory, used when the daemon is started with --simulate so that
+ * clients (and the integration tests) can be driven without any hardware attached. Everything
+ * sent to it is accepted, and every request is answered with zeros, so nobody is ever pressing
+ * a button or moving a fader.
+ */
+
+// Recent enough that nothing is refused for being out of date
+const FULL_FIRMWARE: VersionNumber = VersionNumber(1, 4, Some(2), Some(107));
+const MINI_FIRMWARE: VersionNumber = VersionNumber(1, 2, Some(0), Some(46));
+
+pub struct SimulatedGoXLR;
+
+impl AttachGoXLR for SimulatedGoXLR {
+    fn from_device(
+        _: GoXLRDevice,
+        _: Sender<String>,
+        _: Sender<String>,
+        _: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        bail!("A simulated GoXLR can't be attached to a real device");
+    }
+
+    fn set_unique_identifier(&mut self, _: String) {}
+    fn is_connected(&mut self) -> bool {
+        true
+    }
+    fn stop_polling(&mut self) {}
+    fn set_poll_interval(&mut self, _: Duration) {}
+    fn is_polled(&self) -> bool {
+        false
+    }
+}
+
+impl ExecutableGoXLR for SimulatedGoXLR {
+    fn perform_request(&mut self, _: Command, _: &[u8], _: bool) -> Result<Vec<u8>> {
+        Ok(vec![0; 1024])
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        bail!("A simulated GoXLR has no descriptor");
+    }
+}
+
+impl GoXLRCommands for SimulatedGoXLR {}
+impl FullGoXLRDevice for SimulatedGoXLR {}
+
+fn simulated_serial(device: SimulatedDevice) -> &'static str {
+    match device {
+        SimulatedDevice::Full => "SIMULATED-FULL",
+        SimulatedDevice::Mini => "SIMULATED-MINI",
+    }
+}
+
+pub fn simulated_hardware(device: SimulatedDevice) -> HardwareStatus {
+    let (device_type, firmware, product_name) = match device {
+        SimulatedDevice::Full => (DeviceType::Full, FULL_FIRMWARE, "GoXLR"),
+        SimulatedDevice::Mini => (DeviceType::Mini, MINI_FIRMWARE, "GoXLR Mini"),
+    };
+
+    HardwareStatus {
+        versions: FirmwareVersions {
+            firmware,
+            fpga_count: 0,
+            dice: VersionNumber(1, 0, None, None),
+        },
+        serial_number: String::from(simulated_serial(device)),
+        manufactured_date: String::new(),
+        device_type,
+        colour_way: ColourWay::Black,
+        usb_device: UsbProductInformation {
+            manufacturer_name: String::from("Simulated"),
+            product_name: String::from(product_name),
+            version: (0, 0, 0),
+            bus_number: 0,
+            address: 0,
+            identifier: None,
+        },
+        capabilities: Default::default(),
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use directories::BaseDirs;
+use goxlr_ipc::DaemonStatus;
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::{SettingsHandle, LOG_FILE};
+
+// Any settings key containing one of these has its value replaced entirely
+const SENSITIVE_KEYS: [&str; 3] = ["token", "password", "secret"];
+const REDACTED: &str = "<redacted>";
+const REDACTED_USER: &str = "<user>";
+
+/// The profiles a device currently has loaded, as listed in the bundle.
+#[derive(Debug, Serialize)]
+pub struct ActiveProfiles {
+    pub serial: String,
+    pub profile_name: String,
+    pub profile_version: u8,
+    pub profile_utility_version: Option<String>,
+    pub mic_profile_name: String,
+}
+
+/// Builds a zip containing what's normally asked for in a bug report in the log directory,
+/// returning where it was written.
+pub async fn create_support_bundle(
+    settings: &SettingsHandle,
+    status: &DaemonStatus,
+    profiles: &[ActiveProfiles],
+    include_profiles: bool,
+) -> Result<PathBuf> {
+    settings.write_policy().check("create a support bundle")?;
+
+    // Requests can come from the web UI, so the bundle only ever goes in the log directory
+    let log_directory = settings.get_log_directory().await;
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+    let output = log_directory.join(format!("goxlr-support-{}.zip", timestamp));
+
+    let file = File::create(&output)
+        .with_context(|| format!("Unable to create {}", output.to_string_lossy()))?;
+    // The settings also keep devices which aren't connected, so their serials go as well
+    let mut serials: Vec<String> = status.mixers.keys().cloned().collect();
+    for serial in settings.get_device_serials().await {
+        if !serials.contains(&serial) {
+            serials.push(serial);
+        }
+    }
+    let mut bundle = Bundle {
+        writer: ZipWriter::new(file),
+        redactor: Redactor::new(serials),
+        added: HashSet::new(),
+    };
+
+    // The current log, and the most recent one before it (older logs are compressed)
+    for name in [LOG_FILE.to_string(), format!("{}.1", LOG_FILE)] {
+        let path = log_directory.join(&name);
+        if path.is_file() {
+            let content = String::from_utf8_lossy(&fs::read(&path)?).to_string();
+            bundle.add_text(&format!("logs/{}", name), &content)?;
+        }
+    }
+
+    let settings_path = settings.get_config_path();
+    match fs::read_to_string(settings_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(value) => bundle.add_json("settings.json", value)?,
+            Err(_) => bundle.add_text("settings.json", &content)?,
+        },
+        Err(e) => warn!("Unable to read {}: {}", settings_path.to_string_lossy(), e),
+    }
+
+    bundle.add_json("health.json", serde_json::to_value(&status.config)?)?;
+    bundle.add_json("status.json", serde_json::to_value(status)?)?;
+    bundle.add_json("profiles.json", serde_json::to_value(profiles)?)?;
+
+    if include_profiles {
+        let profile_directory = settings.get_profile_directory().await;
+        let mic_profile_directory = settings.get_mic_profile_directory().await;
+
+        for active in profiles {
+            let files = [
+                (&profile_directory, format!("{}.goxlr", active.profile_name)),
+                (
+                    &mic_profile_directory,
+                    format!("{}.goxlrMicProfile", active.mic_profile_name),
+                ),
+            ];
+            for (directory, name) in files {
+                let path = directory.join(&name);
+                match fs::read(&path) {
+                    Ok(content) => bundle.add_file(&format!("profiles/{}", name), &content)?,
+                    Err(e) => warn!("Unable to read {}: {}", path.to_string_lossy(), e),
+                }
+            }
+        }
+    }
+
+    bundle.writer.finish()?;
+    info!("Support Bundle written to {}", output.to_string_lossy());
+    Ok(output)
+}
+
+struct Bundle {
+    writer: ZipWriter<File>,
+    redactor: Redactor,
+    added: HashSet<String>,
+}
+
+impl Bundle {
+    fn add_file(&mut self, name: &str, content: &[u8]) -> Result<()> {
+        // Profiles may appear more than once if several devices share them
+        if !self.added.insert(name.to_string()) {
+            return Ok(());
+        }
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.writer.start_file(name, options)?;
+        self.writer.write_all(content)?;
+        Ok(())
+    }
+
+    fn add_text(&mut self, name: &str, content: &str) -> Result<()> {
+        let content = self.redactor.text(content);
+        self.add_file(name, content.as_bytes())
+    }
+
+    fn add_json(&mut self, name: &str, mut value: Value) -> Result<()> {
+        self.redactor.json(&mut value);
+        self.add_file(name, serde_json::to_string_pretty(&value)?.as_bytes())
+    }
+}
+
+/// Removes things which could identify the user, their home directory (and name, where it
+/// appears in a path), device serials and the values of anything that looks like a credential.
+struct Redactor {
+    home: Option<String>,
+    user: Option<String>,
+    serials: Vec<String>,
+}
+
+impl Redactor {
+    fn new(serials: Vec<String>) -> Self {
+        let home = BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_string_lossy().to_string())
+            .filter(|home| Path::new(home).parent().is_some());
+        let user = env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .ok()
+            .filter(|user| !user.is_empty());
+
+        Self {
+            home,
+            user,
+            serials,
+        }
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if let Some(home) = &self.home {
+            text = text.replace(home, "~");
+        }
+        if let Some(user) = &self.user {
+            for separator in ['/', '\\'] {
+                let find = format!("{separator}{user}{separator}");
+                let replace = format!("{separator}{REDACTED_USER}{separator}");
+                text = text.replace(&find, &replace);
+            }
+        }
+        for (index, serial) in self.serials.iter().enumerate() {
+            text = text.replace(serial, &format!("<serial {}>", index + 1));
+        }
+        text
+    }
+
+    fn json(&self, value: &mut Value) {
+        match value {
+            Value::String(string) => *string = self.text(string),
+            Value::Array(values) => values.iter_mut().for_each(|value| self.json(value)),
+            Value::Object(map) => {
+                // Serials are also used as keys (the mixers in the status, for one)
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        let lower = key.to_lowercase();
+                        if SENSITIVE_KEYS
+                            .iter()
+                            .any(|sensitive| lower.contains(sensitive))
+                        {
+                            value = Value::String(REDACTED.to_string());
+                        } else {
+                            self.json(&mut value);
+                        }
+                        (self.text(&key), value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::mock_device::writable_settings;
+    use serde_json::json;
+    use zip::ZipArchive;
+
+    fn redactor() -> Redactor {
+        Redactor {
+            home: Some(String::from("/home/frank")),
+            user: Some(String::from("frank")),
+            serials: vec![String::from("S210500771CQK")],
+        }
+    }
+
+    #[test]
+    fn logs_are_redacted() {
+        let log = "Loading /home/frank/.config/profile.goxlr for S210500771CQK\n\
+                   Found C:\\Users\\frank\\Documents\\GoXLR\n";
+        let redacted = redactor().text(log);
+
+        assert!(!redacted.contains("frank"));
+        assert!(!redacted.contains("S210500771CQK"));
+        assert!(redacted.contains("~/.config/profile.goxlr for <serial 1>"));
+        assert!(redacted.contains("\\<user>\\Documents"));
+    }
+
+    #[test]
+    fn json_is_redacted() {
+        let mut status = json!({
+            "mixers": {
+                "S210500771CQK": {
+                    "hardware": { "serial_number": "S210500771CQK" },
+                    "profile_path": "/home/frank/profiles"
+                }
+            },
+            "activation": { "api_token": "abc123", "AuthSecret": ["xyz"] }
+        });
+        redactor().json(&mut status);
+
+        let written = status.to_string();
+        assert!(!written.contains("S210500771CQK"));
+        assert!(!written.contains("frank"));
+        assert!(!written.contains("abc123") && !written.contains("xyz"));
+
+        let mixer = &status["mixers"]["<serial 1>"];
+        assert_eq!(mixer["hardware"]["serial_number"], "<serial 1>");
+        assert_eq!(mixer["profile_path"], "~/profiles");
+        assert_eq!(status["activation"]["AuthSecret"], REDACTED);
+    }
+
+    #[tokio::test]
+    async fn disconnected_devices_are_redacted() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = writable_settings(root.path()).await;
+        settings
+            .set_device_profile_name("S210500771CQK", "Default")
+            .await;
+        settings.save().await;
+
+        // Nothing is connected, the serial is only in the settings
+        let status = DaemonStatus::default();
+        let bundle = create_support_bundle(&settings, &status, &[], false)
+            .await
+            .unwrap();
+
+        let mut archive = ZipArchive::new(File::open(bundle).unwrap()).unwrap();
+        let mut written = String::new();
+        archive
+            .by_name("settings.json")
+            .unwrap()
+            .read_to_string(&mut written)
+            .unwrap();
+        assert!(!written.contains("S210500771CQK"));
+        assert!(written.contains("<serial 1>"));
+    }
+}
@@ -0,0 +1,188 @@
+use std::env;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Local;
+use goxlr_ipc::{DeviceFeature, TelemetryCounts, TelemetryReport};
+use goxlr_types::DeviceType;
+use log::{debug, info};
+use reqwest::header::CONTENT_TYPE;
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use crate::VERSION;
+
+/*
+ * Anonymous usage statistics, which are only collected once enabled. Commands which worked are
+ * counted by the type of device they ran on, and by the features the capability checks say they
+ * need, so nothing about what was set (or which device it was set on) is kept. Once a week the
+ * counts are sent to the configured endpoint and a new week starts, whether or not they could
+ * be sent.
+ */
+
+const SUBMIT_INTERVAL: i64 = 7 * 24 * 60 * 60;
+
+// How often the counts are saved, and checked to see whether they're due to be sent
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Counts a command which ran, given the features it needed (from `required_features`).
+pub async fn record_command(
+    settings: &SettingsHandle,
+    device_type: DeviceType,
+    features: &[DeviceFeature],
+) {
+    settings
+        .update_telemetry_counts(|counts| {
+            counts.commands[device_type] += 1;
+            for feature in features {
+                counts.features[*feature] += 1;
+            }
+        })
+        .await;
+}
+
+pub fn create_report(counts: TelemetryCounts) -> TelemetryReport {
+    TelemetryReport {
+        daemon_version: VERSION.to_string(),
+        os_family: env::consts::OS.to_string(),
+        counts,
+    }
+}
+
+fn is_due(period_start: Option<i64>, now: i64) -> bool {
+    period_start.is_some_and(|start| now - start >= SUBMIT_INTERVAL)
+}
+
+pub async fn spawn_telemetry_service(settings: SettingsHandle, mut shutdown: Shutdown) {
+    let mut ticker = interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let telemetry = settings.get_telemetry().await;
+                if !telemetry.enabled {
+                    continue;
+                }
+
+                let now = Local::now().timestamp();
+                if is_due(telemetry.period_start, now) {
+                    let report = create_report(settings.take_telemetry_counts(now).await);
+                    match telemetry.endpoint {
+                        Some(endpoint) => submit(&endpoint, &report).await,
+                        None => debug!("No usage statistics endpoint set, discarding counts"),
+                    }
+                }
+                settings.save().await;
+            }
+            () = shutdown.recv() => break,
+        }
+    }
+}
+
+// Failures aren't retried, the counts for that week are simply dropped
+async fn submit(endpoint: &str, report: &TelemetryReport) {
+    match send_report(endpoint, report).await {
+        Ok(()) => info!("Usage statistics sent"),
+        Err(e) => debug!("Unable to send usage statistics: {}", e),
+    }
+}
+
+async fn send_report(endpoint: &str, report: &TelemetryReport) -> Result<()> {
+    reqwest::Client::builder()
+        .timeout(SUBMIT_TIMEOUT)
+        .build()?
+        .post(endpoint)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(report)?)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use goxlr_ipc::GoXLRCommand;
+    use goxlr_types::VersionNumber;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::device::Device;
+    use crate::mock_device::{full, settings, MockGoXLR};
+
+    #[tokio::test]
+    async fn commands_are_only_counted_while_enabled() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        let submixes = [DeviceFeature::Submixes];
+
+        record_command(&settings, DeviceType::Mini, &submixes).await;
+        assert_eq!(settings.get_telemetry().await.counts, Default::default());
+
+        settings.set_telemetry_enabled(true, 1000).await;
+        record_command(&settings, DeviceType::Mini, &submixes).await;
+        record_command(&settings, DeviceType::Full, &[]).await;
+        let telemetry = settings.get_telemetry().await;
+        assert_eq!(telemetry.period_start, Some(1000));
+        assert_eq!(telemetry.counts.commands[DeviceType::Mini], 1);
+        assert_eq!(telemetry.counts.commands[DeviceType::Full], 1);
+        assert_eq!(telemetry.counts.features[DeviceFeature::Submixes], 1);
+        assert_eq!(telemetry.counts.features[DeviceFeature::FullSize], 0);
+
+        // Disabling throws away anything which hasn't been sent
+        settings.set_telemetry_enabled(false, 2000).await;
+        let telemetry = settings.get_telemetry().await;
+        assert_eq!(telemetry.counts, Default::default());
+        assert_eq!(telemetry.period_start, None);
+    }
+
+    #[tokio::test]
+    async fn reports_only_hold_counts() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = settings(root.path()).await;
+        settings.set_telemetry_enabled(true, 1000).await;
+
+        let (events, _events_rx) = mpsc::channel(64);
+        let (goxlr, _) = MockGoXLR::new();
+        let firmware = VersionNumber(1, 4, Some(2), Some(107));
+        let mut device = Device::new(goxlr, full(firmware), &settings, events)
+            .await
+            .unwrap();
+
+        let name = String::from("My Profile");
+        for command in [
+            GoXLRCommand::SetSubMixEnabled(true),
+            GoXLRCommand::SetFXEnabled(true),
+            GoXLRCommand::SaveProfileAs(name),
+        ] {
+            let _ = device.perform_command(command).await;
+        }
+
+        // Saving is refused in read-only mode, so isn't counted
+        let report = create_report(settings.take_telemetry_counts(5000).await);
+        let expected = json!({
+            "daemon_version": VERSION,
+            "os_family": env::consts::OS,
+            "counts": {
+                "commands": { "Unknown": 0, "Full": 2, "Mini": 0 },
+                "features": { "FullSize": 1, "Animations": 0, "Submixes": 1 },
+            }
+        });
+        assert_eq!(serde_json::to_value(&report).unwrap(), expected);
+
+        let telemetry = settings.get_telemetry().await;
+        assert_eq!(telemetry.counts, Default::default());
+        assert_eq!(telemetry.last_submitted, Some(5000));
+    }
+
+    #[test]
+    fn reports_are_due_weekly() {
+        assert!(!is_due(None, SUBMIT_INTERVAL * 2));
+        assert!(!is_due(Some(1000), 1000 + SUBMIT_INTERVAL - 1));
+        assert!(is_due(Some(1000), 1000 + SUBMIT_INTERVAL));
+    }
+}
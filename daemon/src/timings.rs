@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use goxlr_ipc::TimingStats;
+
+// Only the most recent samples are kept, so the figures reflect how things are behaving now
+const MAX_SAMPLES: usize = 500;
+
+/// Keeps track of how long an operation has recently taken.
+#[derive(Debug, Default)]
+pub struct Timings {
+    samples: VecDeque<Duration>,
+}
+
+impl Timings {
+    pub fn record(&mut self, elapsed: Duration) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(elapsed);
+    }
+
+    pub fn stats(&self) -> TimingStats {
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort();
+
+        let percentile = |percentile: f64| match sorted.is_empty() {
+            true => 0.,
+            false => {
+                let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+                to_millis(sorted[index])
+            }
+        };
+
+        TimingStats {
+            samples: sorted.len() as u32,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            p99_ms: percentile(0.99),
+            max_ms: percentile(1.),
+        }
+    }
+}
+
+// Rounded to a tenth of a millisecond, finer than that is just noise.
+fn to_millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 10000.).round() / 10.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_come_from_the_recent_samples() {
+        let mut timings = Timings::default();
+        assert_eq!(timings.stats().samples, 0);
+        assert_eq!(timings.stats().max_ms, 0.);
+
+        for millis in (1..=100).rev() {
+            timings.record(Duration::from_millis(millis));
+        }
+        let stats = timings.stats();
+        assert_eq!(stats.samples, 100);
+        assert_eq!(stats.p50_ms, 51.);
+        assert_eq!(stats.p95_ms, 95.);
+        assert_eq!(stats.p99_ms, 99.);
+        assert_eq!(stats.max_ms, 100.);
+
+        // Older samples make way for new ones
+        for _ in 0..MAX_SAMPLES {
+            timings.record(Duration::from_micros(1234));
+        }
+        let stats = timings.stats();
+        assert_eq!(stats.samples as usize, MAX_SAMPLES);
+        assert_eq!(stats.max_ms, 1.2);
+    }
+}
@@ -1,7 +1,12 @@
 use crate::events::EventTriggers;
-use crate::{DaemonState, ICON};
+use crate::tray::state::{
+    get_mute_label, get_png, get_tray_icons, is_mute_enabled, TrayIcon, TrayStateDebouncer,
+};
+use crate::tray::TrayState;
+use crate::DaemonState;
 use anyhow::Result;
 use goxlr_ipc::PathTypes::{Icons, Logs, MicProfiles, Presets, Profiles, Samples};
+use goxlr_ipc::ShutdownReason;
 use ksni::menu::{StandardItem, SubMenu};
 use ksni::{Category, MenuItem, Status, ToolTip, Tray};
 use log::{debug, warn};
@@ -16,61 +21,84 @@ pub fn handle_tray(state: DaemonState, tx: mpsc::Sender<EventTriggers>) -> Resul
         return Ok(());
     }
 
-    // Before we spawn the tray, we're going to extract our icon to a temporary location
-    // so that it can be immediately used. Depending on pixmaps seems to cause issues under
+    // Before we spawn the tray, we're going to extract our icons to a temporary location
+    // so that they can be immediately used. Depending on pixmaps seems to cause issues under
     // gnome, where occasionally the icon wont correctly spawn.
 
-    // We'll dump the icon here :)
+    // We'll dump the icons here :)
     let tmp_file_dir = PathBuf::from("/tmp/goxlr-utility/");
-
-    // Extract the icon to a temporary directory, and pass its path..
-    let tmp_file_path = tmp_file_dir.join("goxlr-utility-icon.png");
     if !tmp_file_dir.exists() {
         fs::create_dir_all(&tmp_file_dir)?;
     }
 
-    // Rather than random shenanigans, we'll simply try to remove any existing files and
-    // recycle whatever is there if we can't. These should evaluate in order, so if the
-    // file is absent, or the file was successfully removed, we can write to it.
-    if !tmp_file_path.exists() || fs::remove_file(&tmp_file_path).is_ok() {
-        fs::write(&tmp_file_path, ICON)?;
-    } else {
-        warn!("Unable to remove existing icon, using whatever is already there..");
+    // Extract each of the icon variants to the temporary directory..
+    let mut icon_paths = vec![];
+    for (icon, image) in get_tray_icons() {
+        let tmp_file_path = tmp_file_dir.join(format!("{}.png", icon.name()));
+
+        // Rather than random shenanigans, we'll simply try to remove any existing files and
+        // recycle whatever is there if we can't. These should evaluate in order, so if the
+        // file is absent, or the file was successfully removed, we can write to it.
+        if !tmp_file_path.exists() || fs::remove_file(&tmp_file_path).is_ok() {
+            fs::write(&tmp_file_path, get_png(&image))?;
+        } else {
+            warn!("Unable to remove existing icon, using whatever is already there..");
+        }
+        icon_paths.push(tmp_file_path);
     }
 
+    let mut debouncer = TrayStateDebouncer::new(state.tray_state.clone());
+    let tray = GoXLRTray::new(tx, &tmp_file_dir, debouncer.current());
+
     // Attempt to immediately update the environment..
-    let handle = ksni::spawn(GoXLRTray::new(tx, &tmp_file_path));
+    let handle = ksni::spawn(tray);
     let handle = match handle {
         Ok(handle) => handle,
         Err(e) => {
             // There's no harm in running without a tray icon, in some cases this may actually
             // be preferable (for example, when running under the CLI), so we just warn, tidy
             // up, and consider our work here done.
-            fs::remove_file(&tmp_file_path)?;
+            remove_icons(&icon_paths)?;
             warn!("Unable to Spawn the Tray Handler: {}", e);
             return Ok(());
         }
     };
 
     while !state.shutdown_blocking.load(Ordering::Relaxed) {
+        if let Some(tray_state) = debouncer.poll() {
+            debug!("Updating Tray State: {:?}", tray_state);
+            handle.update(|tray: &mut GoXLRTray| tray.state = tray_state);
+        }
         thread::sleep(Duration::from_millis(100));
     }
 
     debug!("Shutting Down Tray Handler..");
     let _ = handle.shutdown();
-    fs::remove_file(&tmp_file_path)?;
+    remove_icons(&icon_paths)?;
+    Ok(())
+}
+
+fn remove_icons(paths: &[PathBuf]) -> Result<()> {
+    for path in paths {
+        fs::remove_file(path)?;
+    }
     Ok(())
 }
 
 struct GoXLRTray {
     tx: mpsc::Sender<EventTriggers>,
-    icon: PathBuf,
+    icon_dir: PathBuf,
+    state: TrayState,
 }
 
 impl GoXLRTray {
-    fn new(tx: mpsc::Sender<EventTriggers>, icon: &Path) -> Self {
-        let icon = icon.to_path_buf();
-        Self { tx, icon }
+    fn new(tx: mpsc::Sender<EventTriggers>, icon_dir: &Path, state: TrayState) -> Self {
+        let icon_dir = icon_dir.to_path_buf();
+        Self {
+            tx,
+            icon_dir,
+            state,
+        }
     }
 }
 
@@ -96,18 +124,11 @@ impl Tray for GoXLRTray {
     }
 
     fn icon_theme_path(&self) -> String {
-        if let Some(parent) = self.icon.parent() {
-            return parent.to_string_lossy().to_string();
-        }
-
-        String::from("")
+        self.icon_dir.to_string_lossy().to_string()
     }
 
     fn icon_name(&self) -> String {
-        if let Some(file) = self.icon.file_stem() {
-            return file.to_string_lossy().to_string();
-        }
-        "goxlr-utility".to_string()
+        TrayIcon::from_state(self.state).name().to_string()
     }
 
     fn tool_tip(&self) -> ToolTip {
@@ -128,6 +149,16 @@ impl Tray for GoXLRTray {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: String::from(get_mute_label(self.state)),
+                enabled: is_mute_enabled(self.state),
+                activate: Box::new(|this: &mut GoXLRTray| {
+                    let muted = !this.state.mic_muted;
+                    let _ = this.tx.try_send(EventTriggers::SetMicMuted(muted));
+                }),
+                ..Default::default()
+            }
+            .into(),
             MenuItem::Separator,
             SubMenu {
                 label: String::from("Open Path"),
@@ -190,7 +221,9 @@ impl Tray for GoXLRTray {
             StandardItem {
                 label: String::from("Quit"),
                 activate: Box::new(|this: &mut GoXLRTray| {
-                    let _ = this.tx.try_send(EventTriggers::Stop(false));
+                    let _ = this
+                        .tx
+                        .try_send(EventTriggers::Stop(false, ShutdownReason::UserRequest));
                 }),
                 ..Default::default()
             }
@@ -14,7 +14,7 @@ use cocoa::base::nil;
 use cocoa::foundation::{NSAutoreleasePool, NSData};
 use cocoa_foundation::base::id;
 use cocoa_foundation::foundation::{NSPoint, NSSize, NSString, NSTimeInterval};
-use enum_map::Enum;
+use enum_map::{Enum, EnumMap};
 use log::{debug, warn};
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, YES};
@@ -24,15 +24,17 @@ use tokio::select;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::oneshot;
 
-use goxlr_ipc::PathTypes;
+use goxlr_ipc::{PathTypes, ShutdownReason};
 
 use crate::events::EventTriggers::Open;
 use crate::events::{DaemonState, EventTriggers};
 use crate::tray::macos::TrayOption::{
     Configure, OpenPathIcons, OpenPathLogs, OpenPathMicProfiles, OpenPathPresets, OpenPathProfiles,
-    OpenPathSamples, Quit,
+    OpenPathSamples, Quit, ToggleMicMute,
+};
+use crate::tray::state::{
+    get_mute_label, get_png, get_tray_icons, is_mute_enabled, TrayIcon, TrayStateDebouncer,
 };
-use crate::ICON;
 
 // MacOS is similar to Windows, except it expects the App loop to exist on the main thread..
 pub fn handle_tray(state: DaemonState, tx: Sender<EventTriggers>) -> anyhow::Result<()> {
@@ -80,7 +82,11 @@ async fn run_tray(mut p: RunParams) {
                     OpenPathSamples => tx.try_send(Open(PathTypes::Samples)),
                     OpenPathIcons => tx.try_send(Open(PathTypes::Icons)),
                     OpenPathLogs => tx.try_send(Open(PathTypes::Logs)),
-                    Quit => tx.try_send(EventTriggers::Stop(false))
+                    ToggleMicMute => {
+                        let muted = !p.state.tray_state.borrow().mic_muted;
+                        tx.try_send(EventTriggers::SetMicMuted(muted))
+                    }
+                    Quit => tx.try_send(EventTriggers::Stop(false, ShutdownReason::UserRequest))
                 };
             },
             () = p.state.shutdown.recv() => {
@@ -121,6 +127,7 @@ unsafe fn stop_ns_application() {
 #[derive(Display, Debug, Enum, EnumIter, Eq, PartialEq)]
 enum TrayOption {
     Configure,
+    ToggleMicMute,
     OpenPathProfiles,
     OpenPathMicProfiles,
     OpenPathPresets,
@@ -132,6 +139,14 @@ enum TrayOption {
 
 struct App {}
 
+// Everything the main loop timer needs to update the tray..
+struct TrayUpdater {
+    debouncer: TrayStateDebouncer,
+    button: id,
+    mute_item: id,
+    images: EnumMap<TrayIcon, id>,
+}
+
 struct AppParams {
     sender: Sender<TrayOption>,
     show_tray: Arc<AtomicBool>,
@@ -161,29 +176,38 @@ impl App {
             app
         };
 
-        let status = if p.show_tray.load(Ordering::Relaxed) {
-            debug!("Spawning Tray..");
-            unsafe {
-                let status = NSStatusBar::systemStatusBar(nil)
-                    .statusItemWithLength_(-1.)
-                    .autorelease();
-
-                let button = status.button();
-                let icon = ICON;
+        let debouncer = TrayStateDebouncer::new(p.state.tray_state.clone());
+        let tray_state = debouncer.current();
 
+        // Pre-render all of the icon variants, so we can simply swap between them..
+        let icons = get_tray_icons();
+        let images: EnumMap<TrayIcon, id> = EnumMap::from_fn(|icon| {
+            let png = get_png(&icons[icon]);
+            unsafe {
                 let nsdata = NSData::dataWithBytes_length_(
                     nil,
-                    icon.as_ptr() as *const std::os::raw::c_void,
-                    icon.len() as u64,
+                    png.as_ptr() as *const std::os::raw::c_void,
+                    png.len() as u64,
                 );
 
                 let nsimage = NSImage::initWithData_(NSImage::alloc(nil), nsdata);
                 let new_size = NSSize::new(18.0, 18.0);
-
-                button.setImage_(nsimage);
                 let () = msg_send![nsimage, setSize: new_size];
-                let () = msg_send![button, setImagePosition: 2];
                 let () = msg_send![nsimage, setTemplate: false];
+                nsimage
+            }
+        });
+
+        let status = if p.show_tray.load(Ordering::Relaxed) {
+            debug!("Spawning Tray..");
+            unsafe {
+                let status = NSStatusBar::systemStatusBar(nil)
+                    .statusItemWithLength_(-1.)
+                    .autorelease();
+
+                let button = status.button();
+                button.setImage_(images[TrayIcon::from_state(tray_state)]);
+                let () = msg_send![button, setImagePosition: 2];
 
                 Some(status)
             }
@@ -200,6 +224,7 @@ impl App {
 
             // Create the Main Tray Labels..
             let configure = App::get_label("Configure GoXLR", Configure, p.sender.clone());
+            let mute = App::get_label(get_mute_label(tray_state), ToggleMicMute, p.sender.clone());
             let quit = App::get_label("Quit", Quit, p.sender.clone());
 
             // Create SubMenu Items..
@@ -235,15 +260,46 @@ impl App {
                 // Create the Tray Labels..
                 debug!("Generating Main Menu..");
                 menu.addItem_(configure);
+                menu.addItem_(mute);
                 menu.addItem_(App::get_separator());
                 menu.addItem_(sub_menu);
                 menu.addItem_(App::get_separator());
                 menu.addItem_(quit);
+
+                // Items are enabled / disabled manually depending on device state
+                let () = msg_send![menu, setAutoenablesItems: false];
+                let () = msg_send![mute, setEnabled: is_mute_enabled(tray_state)];
             }
 
             unsafe {
                 status.setMenu_(menu);
             }
+
+            // Create a timer on the main loop, which will keep the icon and menu in sync with
+            // the device state..
+            unsafe {
+                let updater: id = msg_send![App::make_tray_updater_class(), alloc];
+                let () = msg_send![updater, init];
+
+                let boxed = Box::new(TrayUpdater {
+                    debouncer,
+                    button: status.button(),
+                    mute_item: mute,
+                    images,
+                });
+                let ptr = Box::into_raw(boxed);
+                let ptr = ptr as *mut c_void as usize;
+                (*updater).set_ivar("UPDATER", ptr);
+
+                let interval: f64 = 0.1;
+                let _: id = msg_send![class!(NSTimer),
+                    scheduledTimerWithTimeInterval:interval
+                    target:updater
+                    selector:sel!(tick:)
+                    userInfo:nil
+                    repeats:YES
+                ];
+            }
         }
 
         unsafe {
@@ -369,6 +425,45 @@ impl App {
         })
     }
 
+    fn make_tray_updater_class() -> &'static Class {
+        let class_name = "TrayUpdater";
+        Class::get(class_name).unwrap_or_else(|| {
+            let superclass = class!(NSObject);
+            let mut decl = ClassDecl::new(class_name, superclass).unwrap();
+
+            extern "C" fn tick(this: &Object, _: Sel, _: id) {
+                let mut updater: Box<TrayUpdater> = unsafe {
+                    let pointer_value: usize = *this.get_ivar("UPDATER");
+                    let pointer = pointer_value as *mut c_void;
+                    let pointer = pointer as *mut TrayUpdater;
+                    Box::from_raw(pointer)
+                };
+
+                if let Some(tray_state) = updater.debouncer.poll() {
+                    debug!("Updating Tray State: {:?}", tray_state);
+                    unsafe {
+                        let image = updater.images[TrayIcon::from_state(tray_state)];
+                        updater.button.setImage_(image);
+
+                        let label = get_mute_label(tray_state);
+                        let title = NSString::alloc(nil).init_str(label).autorelease();
+                        let () = msg_send![updater.mute_item, setTitle: title];
+                        let enabled = is_mute_enabled(tray_state);
+                        let () = msg_send![updater.mute_item, setEnabled: enabled];
+                    }
+                }
+                mem::forget(updater);
+            }
+
+            unsafe {
+                decl.add_method(sel!(tick:), tick as extern "C" fn(&Object, Sel, id));
+                decl.add_ivar::<usize>("UPDATER");
+            }
+
+            decl.register()
+        })
+    }
+
     fn make_shutdown_hook_class() -> &'static Class {
         let class_name = "PowerHandler";
         Class::get(class_name).unwrap_or_else(|| {
@@ -392,7 +487,7 @@ impl App {
                 };
 
                 // This is pretty similar to Windows, we loop until we're ready to die..
-                let _ = sender.try_send(EventTriggers::Stop(false));
+                let _ = sender.try_send(EventTriggers::Stop(false, ShutdownReason::OsSignal));
 
                 // Now wait for the daemon to actually stop..
                 loop {
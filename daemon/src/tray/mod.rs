@@ -3,6 +3,19 @@ use crate::DaemonState;
 use anyhow::Result;
 use tokio::sync::mpsc;
 
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+mod state;
+
+/// A distilled view of the daemon's state, containing only what the tray needs to render itself.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TrayState {
+    pub connected: bool,
+    pub mic_muted: bool,
+
+    /// A firmware update is running on one of the devices, so it can't be muted from the tray
+    pub updating: bool,
+}
+
 #[cfg(target_os = "linux")]
 mod linux;
 
@@ -0,0 +1,154 @@
+use crate::tray::TrayState;
+use crate::ICON;
+use enum_map::{Enum, EnumMap};
+use image::{ImageFormat, Rgba, RgbaImage};
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+use strum::EnumIter;
+use tokio::sync::watch::Receiver;
+
+// How long the state needs to remain unchanged before we push it to the tray.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(250);
+
+// The dot drawn over the icon while the mic is muted, or a firmware update is running
+const MUTED_COLOUR: Rgba<u8> = Rgba([220, 30, 30, 255]);
+const UPDATING_COLOUR: Rgba<u8> = Rgba([240, 160, 20, 255]);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Enum, EnumIter)]
+pub enum TrayIcon {
+    Default,
+    MicMuted,
+    Updating,
+    Disconnected,
+}
+
+impl TrayIcon {
+    pub fn from_state(state: TrayState) -> Self {
+        if !state.connected {
+            TrayIcon::Disconnected
+        } else if state.updating {
+            TrayIcon::Updating
+        } else if state.mic_muted {
+            TrayIcon::MicMuted
+        } else {
+            TrayIcon::Default
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrayIcon::Default => "goxlr-utility-icon",
+            TrayIcon::MicMuted => "goxlr-utility-icon-muted",
+            TrayIcon::Updating => "goxlr-utility-icon-updating",
+            TrayIcon::Disconnected => "goxlr-utility-icon-disconnected",
+        }
+    }
+}
+
+pub fn get_mute_label(state: TrayState) -> &'static str {
+    if state.updating {
+        "Updating Firmware.."
+    } else if state.mic_muted {
+        "Unmute Mic"
+    } else {
+        "Mute Mic"
+    }
+}
+
+/// Muting from the tray needs a device to mute, which isn't in the middle of a firmware update.
+pub fn is_mute_enabled(state: TrayState) -> bool {
+    state.connected && !state.updating
+}
+
+/// Renders all the icon variations from the embedded icon, this is only done once on startup.
+pub fn get_tray_icons() -> EnumMap<TrayIcon, RgbaImage> {
+    let base = image::load_from_memory(ICON)
+        .expect("Failed to load Icon")
+        .into_rgba8();
+
+    EnumMap::from_fn(|icon| match icon {
+        TrayIcon::Default => base.clone(),
+        TrayIcon::MicMuted => with_overlay(&base, MUTED_COLOUR),
+        TrayIcon::Updating => with_overlay(&base, UPDATING_COLOUR),
+        TrayIcon::Disconnected => as_greyscale(&base),
+    })
+}
+
+pub fn get_png(image: &RgbaImage) -> Vec<u8> {
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .expect("Failed to encode Icon");
+    png.into_inner()
+}
+
+fn with_overlay(base: &RgbaImage, colour: Rgba<u8>) -> RgbaImage {
+    let mut image = base.clone();
+    let (width, height) = image.dimensions();
+
+    // A red dot in the bottom right corner, roughly a third of the size of the icon..
+    let radius = (width.min(height) / 6) as i64;
+    let centre_x = width as i64 - radius - 1;
+    let centre_y = height as i64 - radius - 1;
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let dx = x as i64 - centre_x;
+        let dy = y as i64 - centre_y;
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = colour;
+        }
+    }
+    image
+}
+
+fn as_greyscale(base: &RgbaImage) -> RgbaImage {
+    let mut image = base.clone();
+    for pixel in image.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let luma = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) as u8;
+
+        // Fade it out a little as well, so it reads as 'inactive'
+        *pixel = Rgba([luma, luma, luma, a / 2]);
+    }
+    image
+}
+
+/// Watches the tray state, only reporting a change once it has settled, so that rapidly
+/// toggling a mute button doesn't hammer the desktop's tray implementation.
+pub struct TrayStateDebouncer {
+    receiver: Receiver<TrayState>,
+    applied: Option<TrayState>,
+    pending: Option<(TrayState, Instant)>,
+}
+
+impl TrayStateDebouncer {
+    pub fn new(receiver: Receiver<TrayState>) -> Self {
+        Self {
+            receiver,
+            applied: None,
+            pending: None,
+        }
+    }
+
+    pub fn current(&self) -> TrayState {
+        *self.receiver.borrow()
+    }
+
+    pub fn poll(&mut self) -> Option<TrayState> {
+        if self.receiver.has_changed().unwrap_or(false) {
+            let state = *self.receiver.borrow_and_update();
+            self.pending = Some((state, Instant::now()));
+        }
+
+        if let Some((state, since)) = self.pending {
+            if since.elapsed() >= DEBOUNCE_DURATION {
+                self.pending = None;
+                if self.applied != Some(state) {
+                    self.applied = Some(state);
+                    return Some(state);
+                }
+            }
+        }
+        None
+    }
+}
@@ -6,11 +6,13 @@ use std::time::Duration;
 use std::{mem, ptr};
 
 use anyhow::{bail, Result};
+use enum_map::EnumMap;
+use image::RgbaImage;
 use lazy_static::lazy_static;
 use log::{debug, error, warn};
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
-use windows::core::w;
+use windows::core::{w, HSTRING, PCWSTR};
 use windows::Win32::Foundation::{
     GetLastError, FALSE, HINSTANCE, HWND, LPARAM, LRESULT, POINT, WPARAM,
 };
@@ -21,24 +23,29 @@ use windows::Win32::System::RemoteDesktop::{
 };
 use windows::Win32::System::Shutdown::{ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy};
 use windows::Win32::UI::Shell::{
-    Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
-    NOTIFY_ICON_DATA_FLAGS, NOTIFY_ICON_MESSAGE,
+    Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY,
+    NOTIFYICONDATAW, NOTIFY_ICON_DATA_FLAGS, NOTIFY_ICON_MESSAGE,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     AppendMenuW, CreateIcon, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyWindow,
-    DispatchMessageW, GetMessageW, GetWindowLongPtrW, RegisterClassW, RegisterWindowMessageW,
-    SetMenuInfo, SetTimer, SetWindowLongPtrW, TranslateMessage, CREATESTRUCTW, CS_HREDRAW,
-    CS_VREDRAW, CW_USEDEFAULT, GWLP_USERDATA, HICON, HMENU, MENUINFO, MF_POPUP, MF_SEPARATOR,
-    MF_STRING, MIM_APPLYTOSUBMENUS, MIM_STYLE, MNS_NOTIFYBYPOS, WINDOW_EX_STYLE, WINDOW_STYLE,
-    WM_CREATE, WM_NCDESTROY, WM_USER, WNDCLASSW,
+    DispatchMessageW, GetMessageW, GetWindowLongPtrW, ModifyMenuW, RegisterClassW,
+    RegisterWindowMessageW, SetMenuInfo, SetTimer, SetWindowLongPtrW, TranslateMessage,
+    CREATESTRUCTW, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, GWLP_USERDATA, HICON, HMENU, MENUINFO,
+    MF_BYCOMMAND, MF_GRAYED, MF_POPUP, MF_SEPARATOR, MF_STRING, MIM_APPLYTOSUBMENUS, MIM_STYLE,
+    MNS_NOTIFYBYPOS, WINDOW_EX_STYLE, WINDOW_STYLE, WM_CREATE, WM_NCDESTROY, WM_USER, WNDCLASSW,
 };
 
-use goxlr_ipc::PathTypes;
+use goxlr_ipc::{PathTypes, ShutdownReason};
 
 use crate::events::EventTriggers::Open;
 use crate::events::{DaemonState, EventTriggers};
+use crate::tray::state::{
+    get_mute_label, get_tray_icons, is_mute_enabled, TrayIcon, TrayStateDebouncer,
+};
+use crate::tray::TrayState;
 
 const EVENT_MESSAGE: u32 = WM_USER + 1;
+const MUTE_MENU_ID: u32 = 5;
 
 lazy_static! {
     static ref RESPAWN: u32 = unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) };
@@ -71,6 +78,7 @@ fn create_window(state: DaemonState, tx: Sender<EventTriggers>) -> Result<()> {
         debug!("Creating Main Menu..");
         let hmenu = CreatePopupMenu()?;
         AppendMenuW(hmenu, MF_STRING, 0, w!("Configure GoXLR"))?;
+        AppendMenuW(hmenu, MF_STRING, MUTE_MENU_ID as usize, w!("Mute Mic"))?;
         AppendMenuW(hmenu, MF_SEPARATOR, 1, None)?;
         AppendMenuW(hmenu, MF_POPUP, sub.0 as usize, w!("Open Path"))?;
         AppendMenuW(hmenu, MF_SEPARATOR, 3, None)?;
@@ -176,9 +184,10 @@ fn create_hwnd(proc: Rc<Box<dyn WindowProc>>) -> Result<HWND> {
     Ok(hwnd)
 }
 
-fn load_icon() -> Result<HICON> {
+fn load_icon(image: &RgbaImage) -> Result<HICON> {
     debug!("Loading Tray Icon");
-    let (rgba, width, height) = get_icon_from_global();
+    let (width, height) = image.dimensions();
+    let rgba = image.as_raw();
 
     let count = rgba.len() / 4;
     let mut alpha_mask = Vec::with_capacity(count);
@@ -206,21 +215,32 @@ struct GoXLRWindowProc {
     global_tx: Sender<EventTriggers>,
     menu: HMENU,
 
+    icons: EnumMap<TrayIcon, RgbaImage>,
+    debouncer: TrayStateDebouncer,
+    tray_state: TrayState,
+
     shutdown_triggered: bool,
 }
 
 impl GoXLRWindowProc {
     pub fn new(state: DaemonState, tx: Sender<EventTriggers>, menu: HMENU) -> Self {
+        let debouncer = TrayStateDebouncer::new(state.tray_state.clone());
+        let tray_state = debouncer.current();
+
         Self {
             state,
             global_tx: tx,
             menu,
+            icons: get_tray_icons(),
+            debouncer,
+            tray_state,
             shutdown_triggered: false,
         }
     }
 
     fn create_tray(&self, hwnd: HWND) -> Option<NOTIFYICONDATAW> {
-        if let Ok(icon) = load_icon() {
+        let image = &self.icons[TrayIcon::from_state(self.tray_state)];
+        if let Ok(icon) = load_icon(image) {
             debug!("Generating Tray Item");
 
             let mut tray_item = get_notification_struct(hwnd);
@@ -266,6 +286,40 @@ impl GoXLRWindowProc {
         }
     }
 
+    fn update_tray_state(&mut self, hwnd: HWND) {
+        if let Some(tray_state) = self.debouncer.poll() {
+            debug!("Updating Tray State: {:?}", tray_state);
+            self.tray_state = tray_state;
+
+            self.update_menu();
+            if self.state.show_tray.load(Ordering::Relaxed) {
+                self.spawn_tray(hwnd, NIM_MODIFY);
+            }
+        }
+    }
+
+    fn update_menu(&self) {
+        let mut flags = MF_BYCOMMAND | MF_STRING;
+        if !is_mute_enabled(self.tray_state) {
+            flags |= MF_GRAYED;
+        }
+
+        let label = HSTRING::from(get_mute_label(self.tray_state));
+        unsafe {
+            if ModifyMenuW(
+                self.menu,
+                MUTE_MENU_ID,
+                flags,
+                MUTE_MENU_ID as usize,
+                PCWSTR(label.as_ptr()),
+            )
+            .is_err()
+            {
+                warn!("Unable to update Mute Menu Item");
+            }
+        }
+    }
+
     fn create_menu(&self) {
         debug!("Creating Menu");
         let m = MENUINFO {
@@ -304,6 +358,11 @@ impl WindowProc for GoXLRWindowProc {
                 // Window has spawned, Create our Menu :)
                 self.create_icon(hwnd);
                 self.create_menu();
+                self.update_menu();
+            }
+            WM_TIMER => {
+                // This fires regularly from the main loop, so use it to check for state changes.
+                self.update_tray_state(hwnd);
             }
             // Menu Related Commands..
             WM_MENUCOMMAND => unsafe {
@@ -315,7 +374,13 @@ impl WindowProc for GoXLRWindowProc {
                 let _ = match menu_id {
                     // Main Menu
                     0 => self.global_tx.try_send(EventTriggers::Activate),
-                    4 => self.global_tx.try_send(EventTriggers::Stop(true)),
+                    4 => self
+                        .global_tx
+                        .try_send(EventTriggers::Stop(true, ShutdownReason::UserRequest)),
+                    MUTE_MENU_ID => {
+                        let muted = !self.tray_state.mic_muted;
+                        self.global_tx.try_send(EventTriggers::SetMicMuted(muted))
+                    }
 
                     // Open Paths Menu
                     10 => self.global_tx.try_send(Open(PathTypes::Profiles)),
@@ -378,7 +443,9 @@ impl WindowProc for GoXLRWindowProc {
             WM_CLOSE => {
                 // If something tries to close this hidden window, it's a good bet that it wants
                 // us to shutdown, start the shutdown, but don't close the Window.
-                let _ = self.global_tx.try_send(EventTriggers::Stop(false));
+                let _ = self
+                    .global_tx
+                    .try_send(EventTriggers::Stop(false, ShutdownReason::OsSignal));
                 return Some(LRESULT(1));
             }
 
@@ -428,12 +495,16 @@ impl WindowProc for GoXLRWindowProc {
                 if !self.shutdown_triggered {
                     if !critical {
                         debug!("Attempting Shutdown..");
-                        let _ = self.global_tx.try_send(EventTriggers::Stop(false));
+                        let _ = self
+                            .global_tx
+                            .try_send(EventTriggers::Stop(false, ShutdownReason::OsSignal));
                     } else {
                         // If we receive an ENDSESSION_CRITICAL we should avoid writing to the
                         // disk at all cost as we are no longer in control of when we exit.
                         debug!("Critical Shutdown Received, safely shutting down..");
-                        let _ = self.global_tx.try_send(EventTriggers::Stop(true));
+                        let _ = self
+                            .global_tx
+                            .try_send(EventTriggers::Stop(true, ShutdownReason::OsSignal));
                     }
                     self.shutdown_triggered = true;
                 } else {
@@ -535,15 +606,6 @@ fn get_notification_struct(hwnd: HWND) -> NOTIFYICONDATAW {
     }
 }
 
-pub fn get_icon_from_global() -> (Vec<u8>, u32, u32) {
-    let image = image::load_from_memory(crate::ICON)
-        .expect("Failed to load Icon")
-        .into_rgba8();
-    let (width, height) = image.dimensions();
-    let rgba = image.into_raw();
-    (rgba, width, height)
-}
-
 unsafe extern "system" fn raw_window_proc(
     hwnd: HWND,
     msg: u32,
@@ -1,8 +1,11 @@
 use crate::settings::SettingsHandle;
 use crate::shutdown::Shutdown;
+use crate::BroadcastEvent;
 use anyhow::Result;
+use goxlr_ipc::{Notification, NotificationCategory};
 use log::{debug, info, warn};
 use std::time::Duration;
+use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio::sync::mpsc::Receiver;
 use tokio::time;
 
@@ -12,18 +15,23 @@ use tts::Tts;
 #[allow(clippy::upper_case_acronyms)]
 pub(crate) struct TTS {
     settings: SettingsHandle,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
     tts: Option<Tts>,
 }
 
 impl TTS {
-    pub fn new(settings: SettingsHandle) -> Result<TTS> {
+    pub fn new(
+        settings: SettingsHandle,
+        broadcast_tx: BroadcastSender<BroadcastEvent>,
+    ) -> Result<TTS> {
         Ok(Self {
             settings,
+            broadcast_tx,
             tts: None,
         })
     }
 
-    pub async fn listen(&mut self, mut rx: Receiver<String>, mut shutdown: Shutdown) {
+    pub async fn listen(&mut self, mut rx: Receiver<Notification>, mut shutdown: Shutdown) {
         let mut ticker = time::interval(Duration::from_secs(5));
 
         loop {
@@ -35,9 +43,20 @@ impl TTS {
                     info!("Shutting down TTS Service");
                     return;
                 },
-                Some(message) = rx.recv() => {
-                    debug!("Received TTS Message: {}", message);
-                    self.speak_tts(message).await;
+                Some(notification) = rx.recv() => {
+                    debug!("Received TTS Message: {}", notification.text);
+
+                    // Clients get the text even if it's not going to be spoken, so it can be
+                    // displayed instead. It's fine if nobody is listening.
+                    let text = notification.text.clone();
+                    let category = notification.category;
+                    let _ = self.broadcast_tx.send(BroadcastEvent::Notification(notification));
+
+                    // Guard mode is on because something is being recorded, which would pick
+                    // up the speech
+                    if category != NotificationCategory::Guard {
+                        self.speak_tts(text).await;
+                    }
                 },
             }
         }
@@ -113,9 +132,14 @@ impl TTS {
     }
 }
 
-pub async fn spawn_tts_service(settings: SettingsHandle, rx: Receiver<String>, shutdown: Shutdown) {
+pub async fn spawn_tts_service(
+    settings: SettingsHandle,
+    rx: Receiver<Notification>,
+    broadcast_tx: BroadcastSender<BroadcastEvent>,
+    shutdown: Shutdown,
+) {
     info!("Starting TTS Service..");
-    let tts = TTS::new(settings);
+    let tts = TTS::new(settings, broadcast_tx);
     if tts.is_err() {
         warn!("Unable to Start TTS Service");
         return;
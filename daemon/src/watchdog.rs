@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use tokio::time::Instant;
+
+// How long (in seconds) a polled device can go without being read before it's reset, unless
+// configured. The slowest poll interval is 100ms, so this is plenty of missed reads.
+pub const DEFAULT_STALL_TIMEOUT: u16 = 5;
+const MIN_STALL_TIMEOUT: u16 = 2;
+const MAX_STALL_TIMEOUT: u16 = 60;
+
+// How often every device is checked
+pub const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+
+// A device which needs resetting this many times in the period isn't going to be fixed by
+// another reset, so it's left alone until the earlier resets have aged out.
+const MAX_RESETS: usize = 3;
+const RESET_PERIOD: Duration = Duration::from_secs(600);
+
+pub fn check_stall_timeout(timeout: u16) -> Result<()> {
+    if !(MIN_STALL_TIMEOUT..=MAX_STALL_TIMEOUT).contains(&timeout) {
+        bail!(
+            "Stall Timeout must be between {} and {} seconds",
+            MIN_STALL_TIMEOUT,
+            MAX_STALL_TIMEOUT
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// The device has stopped responding, and should be dropped and connected again.
+    Reset,
+
+    /// The device has stopped responding again, but has been reset too often recently.
+    Faulted,
+}
+
+/// Keeps track of the devices which have stopped being read, and how often they've been reset.
+/// This is keyed by serial, so it survives the device being dropped and connected again.
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    resets: HashMap<String, Vec<Instant>>,
+    faulted: HashSet<String>,
+}
+
+impl Watchdog {
+    /// Checks a device's last heartbeat, returning what should be done about it, if anything.
+    /// Devices which aren't expected to heartbeat (they're not polled) have no heartbeat.
+    pub fn check(
+        &mut self,
+        serial: &str,
+        heartbeat: Option<Instant>,
+        timeout: Duration,
+        now: Instant,
+    ) -> Option<WatchdogAction> {
+        let stalled = heartbeat.is_some_and(|beat| now.saturating_duration_since(beat) >= timeout);
+        if !stalled {
+            self.faulted.remove(serial);
+            return None;
+        }
+
+        let resets = self.resets.entry(serial.to_owned()).or_default();
+        resets.retain(|reset| now.saturating_duration_since(*reset) < RESET_PERIOD);
+        if resets.len() >= MAX_RESETS {
+            // This is only reported once, rather than every check
+            return match self.faulted.insert(serial.to_owned()) {
+                true => Some(WatchdogAction::Faulted),
+                false => None,
+            };
+        }
+
+        resets.push(now);
+        Some(WatchdogAction::Reset)
+    }
+
+    pub fn is_faulted(&self, serial: &str) -> bool {
+        self.faulted.contains(serial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    // Stands in for a device which is polled every 20ms until it stalls
+    struct Heartbeats {
+        connected: Instant,
+        stalls_at: Option<Instant>,
+    }
+
+    impl Heartbeats {
+        fn new(connected: Instant) -> Self {
+            Self {
+                connected,
+                stalls_at: None,
+            }
+        }
+
+        fn last(&self, now: Instant) -> Option<Instant> {
+            let now = self.stalls_at.map_or(now, |stall| stall.min(now));
+            let polls = now.saturating_duration_since(self.connected).as_millis() / 20;
+            Some(self.connected + Duration::from_millis(polls as u64 * 20))
+        }
+    }
+
+    #[test]
+    fn timeouts_are_bounded() {
+        assert!(check_stall_timeout(1).is_err());
+        assert!(check_stall_timeout(2).is_ok());
+        assert!(check_stall_timeout(60).is_ok());
+        assert!(check_stall_timeout(61).is_err());
+    }
+
+    #[test]
+    fn stalled_devices_are_reset() {
+        let start = Instant::now();
+        let mut watchdog = Watchdog::default();
+        let mut device = Heartbeats::new(start);
+
+        // A device being read is left alone, however long it's been connected..
+        for second in 0..60 {
+            let now = start + SECOND * second;
+            assert_eq!(watchdog.check("S1", device.last(now), TIMEOUT, now), None);
+        }
+
+        // ..until it stops being read for long enough
+        device.stalls_at = Some(start + SECOND * 60);
+        for second in 60..65 {
+            let now = start + SECOND * second;
+            assert_eq!(watchdog.check("S1", device.last(now), TIMEOUT, now), None);
+        }
+        let now = start + SECOND * 65;
+        let action = watchdog.check("S1", device.last(now), TIMEOUT, now);
+        assert_eq!(action, Some(WatchdogAction::Reset));
+        assert!(!watchdog.is_faulted("S1"));
+
+        // Devices which aren't polled never stall
+        let now = start + SECOND * 600;
+        assert_eq!(watchdog.check("S2", None, TIMEOUT, now), None);
+    }
+
+    #[test]
+    fn repeated_stalls_back_off() {
+        let start = Instant::now();
+        let mut watchdog = Watchdog::default();
+
+        // Each reset connects a new device, which soon stalls again
+        let mut connected = start;
+        for _ in 0..MAX_RESETS {
+            let device = Heartbeats {
+                connected,
+                stalls_at: Some(connected + SECOND),
+            };
+            let now = connected + SECOND * 6;
+            let action = watchdog.check("S1", device.last(now), TIMEOUT, now);
+            assert_eq!(action, Some(WatchdogAction::Reset));
+            connected = now + SECOND;
+        }
+
+        // The next stall marks it as faulted, once, rather than resetting it again
+        let device = Heartbeats {
+            connected,
+            stalls_at: Some(connected + SECOND),
+        };
+        let now = connected + SECOND * 6;
+        let action = watchdog.check("S1", device.last(now), TIMEOUT, now);
+        assert_eq!(action, Some(WatchdogAction::Faulted));
+        assert!(watchdog.is_faulted("S1"));
+        for second in 7..60 {
+            let now = connected + SECOND * second;
+            assert_eq!(watchdog.check("S1", device.last(now), TIMEOUT, now), None);
+        }
+        assert!(watchdog.is_faulted("S1"));
+
+        // Other devices are unaffected
+        let other = Heartbeats::new(start);
+        let action = watchdog.check("S2", other.last(now), TIMEOUT, now);
+        assert_eq!(action, None);
+        assert!(!watchdog.is_faulted("S2"));
+
+        // If it recovers by itself, it's no longer faulted..
+        let recovered = Heartbeats::new(now);
+        let later = now + SECOND;
+        assert_eq!(
+            watchdog.check("S1", recovered.last(later), TIMEOUT, later),
+            None
+        );
+        assert!(!watchdog.is_faulted("S1"));
+
+        // ..and once the earlier resets are old enough, it can be reset again
+        let later = start + RESET_PERIOD + SECOND * 30;
+        let action = watchdog.check("S1", device.last(later), TIMEOUT, later);
+        assert_eq!(action, Some(WatchdogAction::Reset));
+    }
+}
@@ -0,0 +1,419 @@
+/*
+ * These start the real daemon against a simulated GoXLR, and drive it over IPC exactly as a
+ * client would, so anything which breaks the IPC surface (rather than a single device command)
+ * is caught here. Each test gets its own daemon, with its config, data and socket in a temporary
+ * directory, so they can run side by side without any hardware (or a running daemon) around.
+ */
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use goxlr_ipc::client::Client;
+use goxlr_ipc::clients::ipc::ipc_client::IPCClient;
+use goxlr_ipc::clients::ipc::ipc_socket::Socket;
+use goxlr_ipc::{
+    CommandError, DaemonCommand, DaemonRequest, DaemonResponse, DeviceFeature, GoXLRCommand,
+    MixerStatus,
+};
+use goxlr_types::{ChannelName, DeviceType, InputDevice, OutputDevice};
+use interprocess::local_socket::tokio::prelude::LocalSocketStream;
+use interprocess::local_socket::traits::tokio::Stream;
+use interprocess::local_socket::{GenericFilePath, ToFsName};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout};
+
+const SERIAL: &str = "SIMULATED-FULL";
+const MINI_SERIAL: &str = "SIMULATED-MINI";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A daemon running in its own directory, killed (if it's still running) when dropped.
+struct TestDaemon {
+    root: TempDir,
+    child: Child,
+    serials: Vec<&'static str>,
+}
+
+impl TestDaemon {
+    async fn start() -> Self {
+        Self::simulating(&[("full", SERIAL)]).await
+    }
+
+    /// Starts a daemon simulating each device (as given to --simulate), with the serial it has.
+    async fn simulating(devices: &[(&str, &'static str)]) -> Self {
+        let root = tempfile::tempdir().unwrap();
+
+        // Everything the daemon would normally write to the user's directories goes in here
+        let mut settings = json!({ "show_tray_icon": false });
+        for (key, dir) in [
+            ("profile_directory", "profiles"),
+            ("mic_profile_directory", "mic-profiles"),
+            ("samples_directory", "samples"),
+            ("presets_directory", "presets"),
+            ("icons_directory", "icons"),
+            ("logs_directory", "logs"),
+            ("backup_directory", "backups"),
+        ] {
+            settings[key] = json!(root.path().join(dir));
+        }
+        let config = root.path().join("settings.json");
+        std::fs::write(&config, settings.to_string()).unwrap();
+
+        let mut command = Command::new(env!("CARGO_BIN_EXE_goxlr-daemon"));
+        command
+            .arg("--config")
+            .arg(&config)
+            .arg("--socket-path")
+            .arg(socket_path(root.path()));
+        for (device, _) in devices {
+            command.args(["--simulate", device]);
+        }
+        let child = command
+            .args(["--http-disable", "--force-root"])
+            .args(["--log-level", "warn"])
+            .stdout(Stdio::null())
+            .stdin(Stdio::null())
+            .spawn()
+            .expect("Unable to start the daemon");
+
+        let mut daemon = Self {
+            root,
+            child,
+            serials: devices.iter().map(|(_, serial)| *serial).collect(),
+        };
+        daemon.wait_for_device().await;
+        daemon
+    }
+
+    fn socket_path(&self) -> PathBuf {
+        socket_path(self.root.path())
+    }
+
+    async fn connect(&self) -> Result<IPCClient> {
+        let path = self.socket_path();
+        let name = path.as_path().to_fs_name::<GenericFilePath>()?;
+        let connection = LocalSocketStream::connect(name).await?;
+        Ok(IPCClient::new(
+            Socket::<DaemonResponse, DaemonRequest>::new(connection),
+        ))
+    }
+
+    // The socket is bound before any device is found, so wait for the socket and every device
+    async fn wait_for_device(&mut self) {
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_TIMEOUT {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                panic!("The daemon exited during startup: {}", status);
+            }
+
+            if let Ok(mut client) = self.connect().await {
+                if client.poll_status().await.is_ok()
+                    && self
+                        .serials
+                        .iter()
+                        .all(|serial| client.status().mixers.contains_key(*serial))
+                {
+                    return;
+                }
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        panic!(
+            "The simulated GoXLR didn't appear within {:?}",
+            STARTUP_TIMEOUT
+        );
+    }
+
+    async fn wait_for_exit(&mut self) -> Option<ExitStatus> {
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_TIMEOUT {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                return Some(status);
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        None
+    }
+}
+
+impl Drop for TestDaemon {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn socket_path(root: &Path) -> PathBuf {
+    root.join("goxlr.socket")
+}
+
+fn mixer(client: &IPCClient) -> &MixerStatus {
+    &client.status().mixers[SERIAL]
+}
+
+/// Reads events until a patch changes the path to the value, giving up after EVENT_TIMEOUT.
+async fn wait_for_patch(client: &mut IPCClient, path: &str, value: Value) -> Result<()> {
+    patches_until(client, path, value).await.map(|_| ())
+}
+
+/// As wait_for_patch, returning the paths of every patch operation read along the way.
+async fn patches_until(client: &mut IPCClient, path: &str, value: Value) -> Result<Vec<String>> {
+    let wait = async {
+        let mut paths = vec![];
+        loop {
+            match client.next_event().await? {
+                DaemonResponse::Patch(patch) => {
+                    let operations = serde_json::to_value(&patch)?;
+                    let mut found = false;
+                    for operation in operations.as_array().into_iter().flatten() {
+                        found |= operation["path"] == path && operation["value"] == value;
+                        paths.extend(operation["path"].as_str().map(String::from));
+                    }
+                    if found {
+                        return Ok(paths);
+                    }
+                }
+                DaemonResponse::Notification(_) => {}
+                response => bail!("Unexpected event: {:?}", response),
+            }
+        }
+    };
+    timeout(EVENT_TIMEOUT, wait)
+        .await
+        .with_context(|| format!("No patch to {} was received", path))?
+}
+
+#[tokio::test]
+async fn status_describes_the_simulated_device() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.connect().await.unwrap();
+    client.poll_status().await.unwrap();
+
+    let status = client.status();
+    assert_eq!(status.config.daemon_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(status.mixers.len(), 1);
+    assert!(status.session.started_at > 0);
+
+    let mixer = mixer(&client);
+    assert_eq!(mixer.hardware.serial_number, SERIAL);
+    assert_eq!(mixer.hardware.device_type, DeviceType::Full);
+    assert!(mixer.hardware.capabilities.full_size);
+    assert!(mixer.sampler.is_some());
+    assert!(mixer.effects.is_some());
+
+    // The default profile is created in (and loaded from) the directory it was given
+    let profiles = &client.status().paths.profile_directory;
+    assert!(profiles.starts_with(daemon.root.path()));
+    assert!(client.status().files.profiles.contains(&mixer.profile_name));
+    assert!(!mixer.profile_dirty);
+}
+
+#[tokio::test]
+async fn simulated_devices_run_alongside_each_other() {
+    let devices = [("full", SERIAL), ("mini", MINI_SERIAL)];
+    let daemon = TestDaemon::simulating(&devices).await;
+    let mut client = daemon.connect().await.unwrap();
+    client.poll_status().await.unwrap();
+    assert_eq!(client.status().mixers.len(), 2);
+
+    // Each is reported as it should be, commands for any device work on both, and those for the
+    // full sized device only work on one
+    for (serial, device_type) in [(SERIAL, DeviceType::Full), (MINI_SERIAL, DeviceType::Mini)] {
+        let full_size = device_type == DeviceType::Full;
+        let mixer = &client.status().mixers[serial];
+        assert_eq!(mixer.hardware.device_type, device_type);
+        assert_eq!(mixer.hardware.capabilities.full_size, full_size);
+        assert!(mixer.hardware.capabilities.animations);
+        assert!(mixer.hardware.capabilities.submixes);
+
+        let volume = GoXLRCommand::SetVolume(ChannelName::Music, 100);
+        client.command(serial, volume).await.unwrap();
+        client.poll_status().await.unwrap();
+        let mixer = &client.status().mixers[serial];
+        assert_eq!(mixer.levels.volumes[ChannelName::Music], 100);
+
+        let fx = GoXLRCommand::SetFXEnabled(true);
+        let result = client.command(serial, fx).await;
+        if full_size {
+            result.unwrap();
+        } else {
+            let error = result.unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<CommandError>(),
+                Some(&CommandError::NotSupported(DeviceFeature::FullSize))
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn patches_only_touch_the_device_which_changed() {
+    let devices = [("full", SERIAL), ("mini", MINI_SERIAL)];
+    let daemon = TestDaemon::simulating(&devices).await;
+    let mut client = daemon.connect().await.unwrap();
+    for serial in [SERIAL, MINI_SERIAL] {
+        client.settle(serial, 10000).await.unwrap();
+    }
+
+    let mut subscriber = daemon.connect().await.unwrap();
+    subscriber.subscribe(false).await.unwrap();
+
+    for (volume, serial) in [(101, SERIAL), (102, MINI_SERIAL), (103, SERIAL)] {
+        let command = GoXLRCommand::SetVolume(ChannelName::Music, volume);
+        client.command(serial, command).await.unwrap();
+
+        let path = format!("/mixers/{}/levels/volumes/Music", serial);
+        let paths = patches_until(&mut subscriber, &path, json!(volume))
+            .await
+            .unwrap();
+        let own = format!("/mixers/{}/", serial);
+        for path in paths.iter().filter(|path| path.starts_with("/mixers/")) {
+            assert!(path.starts_with(&own), "{} changed {}", serial, path);
+        }
+    }
+}
+
+#[tokio::test]
+async fn volume_changes_are_broadcast() {
+    let daemon = TestDaemon::start().await;
+    let mut subscriber = daemon.connect().await.unwrap();
+    subscriber.subscribe(false).await.unwrap();
+
+    let mut client = daemon.connect().await.unwrap();
+    client.poll_status().await.unwrap();
+    let volume = match mixer(&client).levels.volumes[ChannelName::Music] {
+        123 => 124,
+        _ => 123,
+    };
+
+    let command = GoXLRCommand::SetVolume(ChannelName::Music, volume);
+    client.command(SERIAL, command).await.unwrap();
+    client.poll_status().await.unwrap();
+    assert_eq!(mixer(&client).levels.volumes[ChannelName::Music], volume);
+
+    let path = format!("/mixers/{}/levels/volumes/Music", SERIAL);
+    wait_for_patch(&mut subscriber, &path, json!(volume))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn profiles_survive_a_save_and_load() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.connect().await.unwrap();
+    client.poll_status().await.unwrap();
+
+    // Flip a route, so the saved profile differs from the default whatever that has
+    let routed = mixer(&client).router[InputDevice::Music][OutputDevice::Headphones];
+    let route =
+        |enabled| GoXLRCommand::SetRouter(InputDevice::Music, OutputDevice::Headphones, enabled);
+
+    client.command(SERIAL, route(!routed)).await.unwrap();
+    let save = GoXLRCommand::SaveProfileAs(String::from("Round Trip"));
+    client.command(SERIAL, save).await.unwrap();
+    client.poll_status().await.unwrap();
+    assert_eq!(mixer(&client).profile_name, "Round Trip");
+    assert!(!mixer(&client).profile_dirty);
+
+    let file = client
+        .status()
+        .paths
+        .profile_directory
+        .join("Round Trip.goxlr");
+    assert!(file.exists(), "{:?} wasn't written", file);
+
+    // Change it back without saving, loading the profile should put it back again
+    client.command(SERIAL, route(routed)).await.unwrap();
+    client.poll_status().await.unwrap();
+    assert!(mixer(&client).profile_dirty);
+
+    let load = GoXLRCommand::LoadProfile(String::from("Round Trip"), false, true);
+    client.command(SERIAL, load).await.unwrap();
+    client.poll_status().await.unwrap();
+    let mixer = mixer(&client);
+    assert_eq!(
+        mixer.router[InputDevice::Music][OutputDevice::Headphones],
+        !routed
+    );
+    assert_eq!(mixer.profile_name, "Round Trip");
+    assert!(!mixer.profile_dirty);
+}
+
+#[tokio::test]
+async fn bad_requests_are_refused() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.connect().await.unwrap();
+
+    // An unknown serial
+    let command = GoXLRCommand::SetVolume(ChannelName::Music, 100);
+    let error = client.command("NOT-A-GOXLR", command).await.unwrap_err();
+    assert!(error.to_string().contains("not connected"), "{}", error);
+
+    // An out of range value, which mustn't be stored
+    client.poll_status().await.unwrap();
+    let buffer = |client: &IPCClient| mixer(client).sampler.as_ref().unwrap().record_buffer;
+    let before = buffer(&client);
+    let command = GoXLRCommand::SetSamplerPreBufferDuration(60000);
+    assert!(client.command(SERIAL, command).await.is_err());
+    client.poll_status().await.unwrap();
+    assert_eq!(buffer(&client), before);
+
+    // A profile which doesn't exist leaves the current one loaded
+    let profile = mixer(&client).profile_name.clone();
+    let load = GoXLRCommand::LoadProfile(String::from("Does Not Exist"), false, true);
+    assert!(client.command(SERIAL, load).await.is_err());
+    client.poll_status().await.unwrap();
+    assert_eq!(mixer(&client).profile_name, profile);
+
+    // None of that should have upset the connection
+    let command = GoXLRCommand::SetVolume(ChannelName::Music, 100);
+    client.command(SERIAL, command).await.unwrap();
+}
+
+#[tokio::test]
+async fn settle_waits_for_outstanding_work() {
+    let daemon = TestDaemon::start().await;
+    let mut client = daemon.connect().await.unwrap();
+
+    // Nothing has been asked of the device, so there's nothing to wait for
+    client.settle(SERIAL, 1000).await.unwrap();
+
+    // Identifying carries on for a few seconds after the request returns
+    let started = Instant::now();
+    let identify = DaemonRequest::IdentifyDevice(SERIAL.to_owned());
+    client.send(identify).await.unwrap();
+
+    let error = client.settle(SERIAL, 100).await.unwrap_err();
+    assert!(error.to_string().contains("identifying"), "{}", error);
+
+    client.settle(SERIAL, 10000).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(3));
+
+    let error = client.settle("NOT-A-GOXLR", 1000).await.unwrap_err();
+    assert!(error.to_string().contains("not connected"), "{}", error);
+}
+
+#[tokio::test]
+async fn daemon_stops_when_asked() {
+    let mut daemon = TestDaemon::start().await;
+    let mut client = daemon.connect().await.unwrap();
+
+    let stop = DaemonRequest::Daemon(DaemonCommand::StopDaemon);
+    client.send(stop).await.unwrap();
+
+    let status = daemon.wait_for_exit().await;
+    assert!(
+        matches!(status, Some(status) if status.success()),
+        "The daemon didn't stop cleanly: {:?}",
+        status
+    );
+
+    // Nobody else should be able to find a daemon which has gone
+    assert!(!daemon.socket_path().exists());
+    assert!(daemon.connect().await.is_err());
+}
@@ -16,8 +16,6 @@ const ICONS: Dir = include_dir!("./defaults/resources/icons");
 enum Errors {
     PathNotExist,
     PathNotDir,
-    ErrorRemovingFile,
-    ErrorWritingFile,
 }
 
 fn main() -> Result<(), Errors> {
@@ -38,23 +36,38 @@ fn main() -> Result<(), Errors> {
         Type::Icons => ICONS,
     };
 
-    // Iterate through the embedded files..
+    // If specific files were requested, make sure they're all ones we know about.
+    for name in &args.only {
+        if files.get_file(name).is_none() {
+            println!("failed\t{}\tNot a default file", name);
+        }
+    }
+
+    // Iterate through the embedded files, each result is written to stdout as a tab separated
+    // line, so the daemon can report back what happened.
     for file in files.files() {
+        let name = file.path().to_string_lossy();
+        if !args.only.is_empty() && !args.only.iter().any(|only| *only == name) {
+            continue;
+        }
+
         let file_path = args.file_path.join(file.path());
 
         if file_path.exists() {
             if !args.overwrite {
+                println!("skipped\t{}", name);
                 continue;
             } else if let Err(e) = fs::remove_file(&file_path) {
-                eprintln!("Error Removing File: {}", e);
-                return Err(Errors::ErrorRemovingFile);
+                println!("failed\t{}\tError Removing File: {}", name, e);
+                continue;
             }
         }
 
         if let Err(e) = fs::write(&file_path, file.contents()) {
-            eprintln!("Error Writing File: {}", e);
-            return Err(Errors::ErrorWritingFile);
+            println!("failed\t{}\tError Writing File: {}", name, e);
+            continue;
         }
+        println!("created\t{}", name);
     }
 
     Ok(())
@@ -72,6 +85,10 @@ struct Cli {
     /// Whether to Overwrite existing files
     #[clap(long)]
     pub overwrite: bool,
+
+    /// Only extract the named files (may be specified multiple times)
+    #[clap(long)]
+    pub only: Vec<String>,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
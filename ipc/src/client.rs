@@ -1,12 +1,75 @@
-use crate::{DaemonRequest, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    AudioDevices, ConnectedClient, ControlQuery, DaemonRequest, DaemonResponse, DaemonStatus,
+    DeviceSummary, EqCurveReport, EqTarget, GoXLRCommand, HardwareStateReport, HttpSettings,
+    IconDetails, ModeChange, ModeChangePreview, PathTypes, RecoveredDefaults, ReplayCapture,
+    ResolvedControl, TelemetryReport,
+};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 #[async_trait]
 pub trait Client {
     async fn send(&mut self, request: DaemonRequest) -> Result<()>;
+    /// Tells the daemon which protocol this client speaks, returning the daemon's, or None for
+    /// a daemon from before protocols were versioned.
+    async fn hello(&mut self) -> Result<Option<u32>>;
     async fn poll_status(&mut self) -> Result<()>;
     async fn command(&mut self, serial: &str, command: GoXLRCommand) -> Result<()>;
+
+    /// Runs one of the Adjust commands, returning the value it resulted in.
+    async fn adjust(&mut self, serial: &str, command: GoXLRCommand) -> Result<i8>;
+    async fn get_audio_devices(&mut self) -> Result<AudioDevices>;
+    async fn import_eq_curve(
+        &mut self,
+        serial: &str,
+        name: &str,
+        target: EqTarget,
+    ) -> Result<EqCurveReport>;
+    async fn recover_defaults(
+        &mut self,
+        path_type: PathTypes,
+        files: Option<Vec<String>>,
+        overwrite: bool,
+    ) -> Result<RecoveredDefaults>;
+    async fn get_hardware_state(&mut self, serial: &str) -> Result<HardwareStateReport>;
+    async fn create_support_bundle(&mut self, include_profiles: bool) -> Result<PathBuf>;
+    async fn save_replay_buffer(
+        &mut self,
+        serial: &str,
+        file_name: Option<String>,
+    ) -> Result<ReplayCapture>;
+
+    /// The most recent lines from the daemon's log, oldest first.
+    async fn tail_log(&mut self, lines: usize) -> Result<Vec<String>>;
+    async fn list_icons(&mut self) -> Result<Vec<IconDetails>>;
+    async fn upload_icon(&mut self, name: &str, data: Vec<u8>) -> Result<IconDetails>;
+
+    /// Removes an icon, refusing if a loaded profile uses it unless forced.
+    async fn delete_icon(&mut self, name: &str, force: bool) -> Result<()>;
+    async fn get_midi_ports(&mut self) -> Result<Vec<String>>;
+    async fn get_connected_clients(&mut self) -> Result<Vec<ConnectedClient>>;
+    async fn get_status_summary(&mut self) -> Result<Vec<DeviceSummary>>;
+    async fn resolve_control(
+        &mut self,
+        serial: &str,
+        query: ControlQuery,
+    ) -> Result<ResolvedControl>;
+    async fn get_telemetry_preview(&mut self) -> Result<TelemetryReport>;
+    async fn preview_mode_change(
+        &mut self,
+        serial: &str,
+        change: ModeChange,
+    ) -> Result<ModeChangePreview>;
+
+    /// Waits for the device to finish everything it was asked to do, for up to the timeout in
+    /// milliseconds.
+    async fn settle(&mut self, serial: &str, timeout: u32) -> Result<()>;
+
+    /// Asks the daemon to push events to this client, once subscribed no further requests can
+    /// be made, and events are read with next_event.
+    async fn subscribe(&mut self, notifications_only: bool) -> Result<()>;
+    async fn next_event(&mut self) -> Result<DaemonResponse>;
     fn status(&self) -> &DaemonStatus;
     fn http_status(&self) -> &HttpSettings;
 }
@@ -1,8 +1,14 @@
 use crate::client::Client;
 use crate::clients::ipc::ipc_socket::Socket;
-use crate::{DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    AudioDevices, ConnectedClient, ControlQuery, DaemonRequest, DaemonResponse, DaemonStatus,
+    DeviceSummary, EqCurveReport, EqTarget, GoXLRCommand, HardwareStateReport, HttpSettings,
+    IconDetails, ModeChange, ModeChangePreview, PathTypes, RecoveredDefaults, ReplayCapture,
+    ResolvedControl, TelemetryReport, PROTOCOL_VERSION,
+};
 use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct IPCClient {
@@ -19,23 +25,24 @@ impl IPCClient {
             http_settings: Default::default(),
         }
     }
-}
 
-#[async_trait]
-impl Client for IPCClient {
-    async fn send(&mut self, request: DaemonRequest) -> Result<()> {
+    async fn request(&mut self, request: DaemonRequest) -> Result<DaemonResponse> {
         self.socket
             .send(request)
             .await
             .context("Failed to send a command to the GoXLR daemon process")?;
-        let result = self
-            .socket
+        self.socket
             .read()
             .await
             .context("Failed to retrieve the command result from the GoXLR daemon process")?
-            .context("Failed to parse the command result from the GoXLR daemon process")?;
+            .context("Failed to parse the command result from the GoXLR daemon process")
+    }
+}
 
-        match result {
+#[async_trait]
+impl Client for IPCClient {
+    async fn send(&mut self, request: DaemonRequest) -> Result<()> {
+        match self.request(request).await? {
             DaemonResponse::Status(status) => {
                 self.status = status.clone();
                 self.http_settings = status.config.http_settings;
@@ -43,12 +50,83 @@ impl Client for IPCClient {
             }
             DaemonResponse::Ok => Ok(()),
             DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            DaemonResponse::Hello(_protocol) => {
+                bail!("Received Hello as response, shouldn't happen!")
+            }
             DaemonResponse::MicLevel(_level) => {
                 bail!("Received Mic Level as Response, shouldn't happen!");
             }
+            DaemonResponse::StatusSchema(_schema) => {
+                bail!("Received Status Schema as response, shouldn't happen!")
+            }
             DaemonResponse::Patch(_patch) => {
                 Err(anyhow!("Received Patch as response, shouldn't happen!"))
             }
+            DaemonResponse::AudioDevices(_devices) => {
+                bail!("Received Audio Devices as response, shouldn't happen!")
+            }
+            DaemonResponse::CommandError(error) => Err(error.into()),
+            DaemonResponse::EqCurveImport(_report) => {
+                bail!("Received EQ Curve Report as response, shouldn't happen!")
+            }
+            DaemonResponse::RecoveredDefaults(_result) => {
+                bail!("Received Recovered Defaults as response, shouldn't happen!")
+            }
+            DaemonResponse::Notification(_notification) => {
+                bail!("Received Notification as response, shouldn't happen!")
+            }
+            DaemonResponse::HardwareState(_report) => {
+                bail!("Received Hardware State as response, shouldn't happen!")
+            }
+            DaemonResponse::SupportBundle(_path) => {
+                bail!("Received Support Bundle as response, shouldn't happen!")
+            }
+            DaemonResponse::AdjustedValue(_value) => Ok(()),
+            DaemonResponse::MuteFunctionReset(_fader, _function) => Ok(()),
+            DaemonResponse::ReplaySaved(_capture) => Ok(()),
+            DaemonResponse::LogLines(_lines) => {
+                bail!("Received Log Lines as response, shouldn't happen!")
+            }
+            DaemonResponse::Icons(_icons) => {
+                bail!("Received Icon List as response, shouldn't happen!")
+            }
+            DaemonResponse::Icon(_icon) => {
+                bail!("Received Icon as response, shouldn't happen!")
+            }
+            DaemonResponse::MidiPorts(_ports) => {
+                bail!("Received MIDI Ports as response, shouldn't happen!")
+            }
+            DaemonResponse::ConnectedClients(_clients) => {
+                bail!("Received Connected Clients as response, shouldn't happen!")
+            }
+            DaemonResponse::StatusSummary(_summary) => {
+                bail!("Received Status Summary as response, shouldn't happen!")
+            }
+            DaemonResponse::ResolvedControl(_resolved) => {
+                bail!("Received Resolved Control as response, shouldn't happen!")
+            }
+            DaemonResponse::TelemetryPreview(_report) => {
+                bail!("Received Telemetry Preview as response, shouldn't happen!")
+            }
+            DaemonResponse::ModeChangePreview(_preview) => {
+                bail!("Received Mode Change Preview as response, shouldn't happen!")
+            }
+            DaemonResponse::ClaimOverridden(_claim) => {
+                bail!("Received Claim Override as response, shouldn't happen!")
+            }
+            DaemonResponse::ButtonEvent(_event) => {
+                bail!("Received Button Event as response, shouldn't happen!")
+            }
+        }
+    }
+
+    async fn hello(&mut self) -> Result<Option<u32>> {
+        match self.request(DaemonRequest::Hello(PROTOCOL_VERSION)).await? {
+            DaemonResponse::Hello(protocol) => Ok(Some(protocol)),
+
+            // Older daemons can't parse the request, but still handle everything they know
+            DaemonResponse::Error(_) => Ok(None),
+            _ => bail!("Unexpected response when greeting the daemon"),
         }
     }
 
@@ -61,6 +139,207 @@ impl Client for IPCClient {
             .await
     }
 
+    async fn adjust(&mut self, serial: &str, command: GoXLRCommand) -> Result<i8> {
+        let request = DaemonRequest::Command(serial.to_string(), command);
+        match self.request(request).await? {
+            DaemonResponse::AdjustedValue(value) => Ok(value),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            DaemonResponse::CommandError(error) => Err(error.into()),
+            _ => bail!("Unexpected response when adjusting a value"),
+        }
+    }
+
+    async fn get_audio_devices(&mut self) -> Result<AudioDevices> {
+        match self.request(DaemonRequest::GetAudioDevices).await? {
+            DaemonResponse::AudioDevices(devices) => Ok(devices),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when fetching Audio Devices"),
+        }
+    }
+
+    async fn import_eq_curve(
+        &mut self,
+        serial: &str,
+        name: &str,
+        target: EqTarget,
+    ) -> Result<EqCurveReport> {
+        let request = DaemonRequest::ImportEqCurve(serial.to_string(), name.to_string(), target);
+        match self.request(request).await? {
+            DaemonResponse::EqCurveImport(report) => Ok(report),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when importing EQ Curve"),
+        }
+    }
+
+    async fn recover_defaults(
+        &mut self,
+        path_type: PathTypes,
+        files: Option<Vec<String>>,
+        overwrite: bool,
+    ) -> Result<RecoveredDefaults> {
+        let request = DaemonRequest::RecoverDefaults {
+            path_type,
+            files,
+            overwrite,
+        };
+        match self.request(request).await? {
+            DaemonResponse::RecoveredDefaults(result) => Ok(result),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when recovering Defaults"),
+        }
+    }
+
+    async fn get_hardware_state(&mut self, serial: &str) -> Result<HardwareStateReport> {
+        let request = DaemonRequest::GetHardwareState(serial.to_string());
+        match self.request(request).await? {
+            DaemonResponse::HardwareState(report) => Ok(report),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when reading the Hardware State"),
+        }
+    }
+
+    async fn create_support_bundle(&mut self, include_profiles: bool) -> Result<PathBuf> {
+        let request = DaemonRequest::CreateSupportBundle { include_profiles };
+        match self.request(request).await? {
+            DaemonResponse::SupportBundle(path) => Ok(path),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when creating the Support Bundle"),
+        }
+    }
+
+    async fn save_replay_buffer(
+        &mut self,
+        serial: &str,
+        file_name: Option<String>,
+    ) -> Result<ReplayCapture> {
+        let request = DaemonRequest::SaveReplayBuffer(serial.to_string(), file_name);
+        match self.request(request).await? {
+            DaemonResponse::ReplaySaved(capture) => Ok(capture),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when saving the Replay Buffer"),
+        }
+    }
+
+    async fn tail_log(&mut self, lines: usize) -> Result<Vec<String>> {
+        match self.request(DaemonRequest::TailLog(lines)).await? {
+            DaemonResponse::LogLines(lines) => Ok(lines),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when reading the Log"),
+        }
+    }
+
+    async fn list_icons(&mut self) -> Result<Vec<IconDetails>> {
+        match self.request(DaemonRequest::ListIcons).await? {
+            DaemonResponse::Icons(icons) => Ok(icons),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when listing the Icons"),
+        }
+    }
+
+    async fn upload_icon(&mut self, name: &str, data: Vec<u8>) -> Result<IconDetails> {
+        let request = DaemonRequest::UploadIcon(name.to_string(), data);
+        match self.request(request).await? {
+            DaemonResponse::Icon(icon) => Ok(icon),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when uploading the Icon"),
+        }
+    }
+
+    async fn delete_icon(&mut self, name: &str, force: bool) -> Result<()> {
+        let request = DaemonRequest::DeleteIcon {
+            name: name.to_string(),
+            force,
+        };
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when deleting the Icon"),
+        }
+    }
+
+    async fn get_midi_ports(&mut self) -> Result<Vec<String>> {
+        match self.request(DaemonRequest::GetMidiPorts).await? {
+            DaemonResponse::MidiPorts(ports) => Ok(ports),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when listing the MIDI Ports"),
+        }
+    }
+
+    async fn get_connected_clients(&mut self) -> Result<Vec<ConnectedClient>> {
+        match self.request(DaemonRequest::GetConnectedClients).await? {
+            DaemonResponse::ConnectedClients(clients) => Ok(clients),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when listing the Connected Clients"),
+        }
+    }
+
+    async fn get_status_summary(&mut self) -> Result<Vec<DeviceSummary>> {
+        match self.request(DaemonRequest::GetStatusSummary).await? {
+            DaemonResponse::StatusSummary(summary) => Ok(summary),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when fetching the Status Summary"),
+        }
+    }
+
+    async fn resolve_control(
+        &mut self,
+        serial: &str,
+        query: ControlQuery,
+    ) -> Result<ResolvedControl> {
+        let request = DaemonRequest::ResolveControl(serial.to_string(), query);
+        match self.request(request).await? {
+            DaemonResponse::ResolvedControl(resolved) => Ok(resolved),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when resolving the Control"),
+        }
+    }
+
+    async fn get_telemetry_preview(&mut self) -> Result<TelemetryReport> {
+        match self.request(DaemonRequest::GetTelemetryPreview).await? {
+            DaemonResponse::TelemetryPreview(report) => Ok(report),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when fetching the Telemetry Preview"),
+        }
+    }
+
+    async fn preview_mode_change(
+        &mut self,
+        serial: &str,
+        change: ModeChange,
+    ) -> Result<ModeChangePreview> {
+        let request = DaemonRequest::PreviewModeChange(serial.to_string(), change);
+        match self.request(request).await? {
+            DaemonResponse::ModeChangePreview(preview) => Ok(preview),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when previewing the Mode Change"),
+        }
+    }
+
+    async fn settle(&mut self, serial: &str, timeout: u32) -> Result<()> {
+        let request = DaemonRequest::Settle(serial.to_string(), timeout);
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when waiting for the device to settle"),
+        }
+    }
+
+    async fn subscribe(&mut self, notifications_only: bool) -> Result<()> {
+        let request = DaemonRequest::Subscribe { notifications_only };
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => bail!("Unexpected response when subscribing to events"),
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<DaemonResponse> {
+        match self.socket.read().await {
+            Some(event) => event.context("Failed to parse an event from the GoXLR daemon process"),
+            None => bail!("The GoXLR daemon process closed the connection"),
+        }
+    }
+
     fn status(&self) -> &DaemonStatus {
         &self.status
     }
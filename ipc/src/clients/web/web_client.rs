@@ -1,7 +1,13 @@
 use crate::client::Client;
-use crate::{DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    AudioDevices, ConnectedClient, ControlQuery, DaemonRequest, DaemonResponse, DaemonStatus,
+    DeviceSummary, EqCurveReport, EqTarget, GoXLRCommand, HardwareStateReport, HttpSettings,
+    IconDetails, ModeChange, ModeChangePreview, PathTypes, RecoveredDefaults, ReplayCapture,
+    ResolvedControl, TelemetryReport, PROTOCOL_VERSION,
+};
 use anyhow::bail;
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct WebClient {
@@ -18,18 +24,22 @@ impl WebClient {
             http_settings: Default::default(),
         }
     }
-}
 
-#[async_trait]
-impl Client for WebClient {
-    async fn send(&mut self, request: DaemonRequest) -> anyhow::Result<()> {
-        let resp = reqwest::Client::new()
+    async fn request(&self, request: DaemonRequest) -> anyhow::Result<DaemonResponse> {
+        Ok(reqwest::Client::new()
             .post(&self.url)
             .json(&request)
             .send()
             .await?
             .json::<DaemonResponse>()
-            .await?;
+            .await?)
+    }
+}
+
+#[async_trait]
+impl Client for WebClient {
+    async fn send(&mut self, request: DaemonRequest) -> anyhow::Result<()> {
+        let resp = self.request(request).await?;
 
         // Should probably abstract this part, it's common between clients..
         match resp {
@@ -40,12 +50,83 @@ impl Client for WebClient {
             }
             DaemonResponse::Ok => Ok(()),
             DaemonResponse::Error(error) => bail!("{}", error),
+            DaemonResponse::Hello(_protocol) => {
+                bail!("Received Hello as response, shouldn't happen!")
+            }
             DaemonResponse::MicLevel(_level) => {
                 bail!("Received Mic Level as response, shouldn't happen!")
             }
+            DaemonResponse::StatusSchema(_schema) => {
+                bail!("Received Status Schema as response, shouldn't happen!")
+            }
             DaemonResponse::Patch(_patch) => {
                 bail!("Received Patch as response, shouldn't happen!")
             }
+            DaemonResponse::AudioDevices(_devices) => {
+                bail!("Received Audio Devices as response, shouldn't happen!")
+            }
+            DaemonResponse::CommandError(error) => Err(error.into()),
+            DaemonResponse::EqCurveImport(_report) => {
+                bail!("Received EQ Curve Report as response, shouldn't happen!")
+            }
+            DaemonResponse::RecoveredDefaults(_result) => {
+                bail!("Received Recovered Defaults as response, shouldn't happen!")
+            }
+            DaemonResponse::Notification(_notification) => {
+                bail!("Received Notification as response, shouldn't happen!")
+            }
+            DaemonResponse::HardwareState(_report) => {
+                bail!("Received Hardware State as response, shouldn't happen!")
+            }
+            DaemonResponse::SupportBundle(_path) => {
+                bail!("Received Support Bundle as response, shouldn't happen!")
+            }
+            DaemonResponse::AdjustedValue(_value) => Ok(()),
+            DaemonResponse::MuteFunctionReset(_fader, _function) => Ok(()),
+            DaemonResponse::ReplaySaved(_capture) => Ok(()),
+            DaemonResponse::LogLines(_lines) => {
+                bail!("Received Log Lines as response, shouldn't happen!")
+            }
+            DaemonResponse::Icons(_icons) => {
+                bail!("Received Icon List as response, shouldn't happen!")
+            }
+            DaemonResponse::Icon(_icon) => {
+                bail!("Received Icon as response, shouldn't happen!")
+            }
+            DaemonResponse::MidiPorts(_ports) => {
+                bail!("Received MIDI Ports as response, shouldn't happen!")
+            }
+            DaemonResponse::ConnectedClients(_clients) => {
+                bail!("Received Connected Clients as response, shouldn't happen!")
+            }
+            DaemonResponse::StatusSummary(_summary) => {
+                bail!("Received Status Summary as response, shouldn't happen!")
+            }
+            DaemonResponse::ResolvedControl(_resolved) => {
+                bail!("Received Resolved Control as response, shouldn't happen!")
+            }
+            DaemonResponse::TelemetryPreview(_report) => {
+                bail!("Received Telemetry Preview as response, shouldn't happen!")
+            }
+            DaemonResponse::ModeChangePreview(_preview) => {
+                bail!("Received Mode Change Preview as response, shouldn't happen!")
+            }
+            DaemonResponse::ClaimOverridden(_claim) => {
+                bail!("Received Claim Override as response, shouldn't happen!")
+            }
+            DaemonResponse::ButtonEvent(_event) => {
+                bail!("Received Button Event as response, shouldn't happen!")
+            }
+        }
+    }
+
+    async fn hello(&mut self) -> anyhow::Result<Option<u32>> {
+        match self.request(DaemonRequest::Hello(PROTOCOL_VERSION)).await? {
+            DaemonResponse::Hello(protocol) => Ok(Some(protocol)),
+
+            // Older daemons can't parse the request, but still handle everything they know
+            DaemonResponse::Error(_) => Ok(None),
+            _ => bail!("Unexpected response when greeting the daemon"),
         }
     }
 
@@ -58,6 +139,199 @@ impl Client for WebClient {
             .await
     }
 
+    async fn adjust(&mut self, serial: &str, command: GoXLRCommand) -> anyhow::Result<i8> {
+        let request = DaemonRequest::Command(serial.to_string(), command);
+        match self.request(request).await? {
+            DaemonResponse::AdjustedValue(value) => Ok(value),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            DaemonResponse::CommandError(error) => Err(error.into()),
+            _ => bail!("Unexpected response when adjusting a value"),
+        }
+    }
+
+    async fn get_audio_devices(&mut self) -> anyhow::Result<AudioDevices> {
+        match self.request(DaemonRequest::GetAudioDevices).await? {
+            DaemonResponse::AudioDevices(devices) => Ok(devices),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when fetching Audio Devices"),
+        }
+    }
+
+    async fn import_eq_curve(
+        &mut self,
+        serial: &str,
+        name: &str,
+        target: EqTarget,
+    ) -> anyhow::Result<EqCurveReport> {
+        let request = DaemonRequest::ImportEqCurve(serial.to_string(), name.to_string(), target);
+        match self.request(request).await? {
+            DaemonResponse::EqCurveImport(report) => Ok(report),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when importing EQ Curve"),
+        }
+    }
+
+    async fn recover_defaults(
+        &mut self,
+        path_type: PathTypes,
+        files: Option<Vec<String>>,
+        overwrite: bool,
+    ) -> anyhow::Result<RecoveredDefaults> {
+        let request = DaemonRequest::RecoverDefaults {
+            path_type,
+            files,
+            overwrite,
+        };
+        match self.request(request).await? {
+            DaemonResponse::RecoveredDefaults(result) => Ok(result),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when recovering Defaults"),
+        }
+    }
+
+    async fn get_hardware_state(&mut self, serial: &str) -> anyhow::Result<HardwareStateReport> {
+        let request = DaemonRequest::GetHardwareState(serial.to_string());
+        match self.request(request).await? {
+            DaemonResponse::HardwareState(report) => Ok(report),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when reading the Hardware State"),
+        }
+    }
+
+    async fn create_support_bundle(&mut self, include_profiles: bool) -> anyhow::Result<PathBuf> {
+        let request = DaemonRequest::CreateSupportBundle { include_profiles };
+        match self.request(request).await? {
+            DaemonResponse::SupportBundle(path) => Ok(path),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when creating the Support Bundle"),
+        }
+    }
+
+    async fn save_replay_buffer(
+        &mut self,
+        serial: &str,
+        file_name: Option<String>,
+    ) -> anyhow::Result<ReplayCapture> {
+        let request = DaemonRequest::SaveReplayBuffer(serial.to_string(), file_name);
+        match self.request(request).await? {
+            DaemonResponse::ReplaySaved(capture) => Ok(capture),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when saving the Replay Buffer"),
+        }
+    }
+
+    async fn tail_log(&mut self, lines: usize) -> anyhow::Result<Vec<String>> {
+        match self.request(DaemonRequest::TailLog(lines)).await? {
+            DaemonResponse::LogLines(lines) => Ok(lines),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when reading the Log"),
+        }
+    }
+
+    async fn list_icons(&mut self) -> anyhow::Result<Vec<IconDetails>> {
+        match self.request(DaemonRequest::ListIcons).await? {
+            DaemonResponse::Icons(icons) => Ok(icons),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when listing the Icons"),
+        }
+    }
+
+    async fn upload_icon(&mut self, name: &str, data: Vec<u8>) -> anyhow::Result<IconDetails> {
+        let request = DaemonRequest::UploadIcon(name.to_string(), data);
+        match self.request(request).await? {
+            DaemonResponse::Icon(icon) => Ok(icon),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when uploading the Icon"),
+        }
+    }
+
+    async fn delete_icon(&mut self, name: &str, force: bool) -> anyhow::Result<()> {
+        let request = DaemonRequest::DeleteIcon {
+            name: name.to_string(),
+            force,
+        };
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when deleting the Icon"),
+        }
+    }
+
+    async fn get_midi_ports(&mut self) -> anyhow::Result<Vec<String>> {
+        match self.request(DaemonRequest::GetMidiPorts).await? {
+            DaemonResponse::MidiPorts(ports) => Ok(ports),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when listing the MIDI Ports"),
+        }
+    }
+
+    async fn get_connected_clients(&mut self) -> anyhow::Result<Vec<ConnectedClient>> {
+        match self.request(DaemonRequest::GetConnectedClients).await? {
+            DaemonResponse::ConnectedClients(clients) => Ok(clients),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when listing the Connected Clients"),
+        }
+    }
+
+    async fn get_status_summary(&mut self) -> anyhow::Result<Vec<DeviceSummary>> {
+        match self.request(DaemonRequest::GetStatusSummary).await? {
+            DaemonResponse::StatusSummary(summary) => Ok(summary),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when fetching the Status Summary"),
+        }
+    }
+
+    async fn resolve_control(
+        &mut self,
+        serial: &str,
+        query: ControlQuery,
+    ) -> anyhow::Result<ResolvedControl> {
+        let request = DaemonRequest::ResolveControl(serial.to_string(), query);
+        match self.request(request).await? {
+            DaemonResponse::ResolvedControl(resolved) => Ok(resolved),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when resolving the Control"),
+        }
+    }
+
+    async fn get_telemetry_preview(&mut self) -> anyhow::Result<TelemetryReport> {
+        match self.request(DaemonRequest::GetTelemetryPreview).await? {
+            DaemonResponse::TelemetryPreview(report) => Ok(report),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when fetching the Telemetry Preview"),
+        }
+    }
+
+    async fn preview_mode_change(
+        &mut self,
+        serial: &str,
+        change: ModeChange,
+    ) -> anyhow::Result<ModeChangePreview> {
+        let request = DaemonRequest::PreviewModeChange(serial.to_string(), change);
+        match self.request(request).await? {
+            DaemonResponse::ModeChangePreview(preview) => Ok(preview),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when previewing the Mode Change"),
+        }
+    }
+
+    async fn settle(&mut self, serial: &str, timeout: u32) -> anyhow::Result<()> {
+        let request = DaemonRequest::Settle(serial.to_string(), timeout);
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response when waiting for the device to settle"),
+        }
+    }
+
+    async fn subscribe(&mut self, _notifications_only: bool) -> anyhow::Result<()> {
+        bail!("Subscribing to events is not supported over HTTP, use the websocket instead")
+    }
+
+    async fn next_event(&mut self) -> anyhow::Result<DaemonResponse> {
+        bail!("Subscribing to events is not supported over HTTP, use the websocket instead")
+    }
+
     fn status(&self) -> &DaemonStatus {
         &self.status
     }
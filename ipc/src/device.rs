@@ -1,33 +1,57 @@
-use crate::{ColourWay, GoXLRCommand, LogLevel};
+use crate::schema::EnumMapSchema;
+use crate::{
+    ChannelClaim, ColourWay, DeviceFeature, DuckConfig, GoXLRCommand, HeadsetSnapshot,
+    HighContrastConfig, LogLevel, MidiControl, MidiMapping, MuteSyncApplication, MuteSyncDirection,
+    NameProblem, PrivacyConfig, ProfileRule, ReactiveConfig, Schedule,
+};
 use enum_map::EnumMap;
 use goxlr_types::MuteState::Unmuted;
 use goxlr_types::{
     AnimationMode, Button, ButtonColourOffStyle, ChannelName, CompressorAttackTime,
     CompressorRatio, CompressorReleaseTime, DeviceType, DisplayMode, DriverInterface, EchoStyle,
-    EffectBankPresets, EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName,
-    FirmwareVersions, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle, InputDevice,
-    MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState, OutputDevice,
-    PitchStyle, ReverbStyle, RobotStyle, SampleBank, SampleButtons, SamplePlayOrder,
-    SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets, SubMixChannelName,
-    VersionNumber, VodMode, WaterfallDirection,
+    EffectBankPresets, EffectSection, EncoderColourTargets, EncoderName, EqFrequencies,
+    FaderDisplayStyle, FaderName, FirmwareVersions, GateTimes, GenderStyle, HardTuneSource,
+    HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix,
+    MuteFunction, MuteState, OutputDevice, PitchStyle, ProfileSection, ReverbStyle, RobotRange,
+    RobotStyle, SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode,
+    SamplerColourTargets, SimpleColourTargets, SubMixChannelName, VersionNumber, VodMode,
+    WaterfallDirection,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct DaemonStatus {
+    pub status_version: u32,
     pub config: DaemonConfig,
     pub mixers: HashMap<String, MixerStatus>,
     pub paths: Paths,
     pub files: Files,
+    pub schedules: HashMap<String, ScheduledCommand>,
+    pub session: SessionStatus,
+
+    /// Channels which clients have claimed, across every device
+    pub claims: Vec<ChannelClaim>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// How long the daemon has been running, times are Unix timestamps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SessionStatus {
+    pub started_at: i64,
+
+    /// Only brought up to date when something else in the status changes (or the status is
+    /// requested), rather than causing a patch every second.
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct DaemonConfig {
     pub http_settings: HttpSettings,
     pub daemon_version: String,
     pub driver_interface: DriverDetails,
+    #[schemars(with = "Option<EnumMapSchema<DeviceType, Option<VersionNumber>>>")]
     pub latest_firmware: Option<EnumMap<DeviceType, Option<VersionNumber>>>,
     pub locale: Locale,
     pub activation: Activation,
@@ -36,55 +60,427 @@ pub struct DaemonConfig {
     pub tts_enabled: Option<bool>,
     pub allow_network_access: bool,
     pub log_level: LogLevel,
+
+    /// Extra log filter directives, applied on top of the log level (see SetLogFilter)
+    pub log_filter: Option<String>,
     pub open_ui_on_launch: bool,
     pub platform: String,
     pub handle_macos_aggregates: bool,
+    pub sample_cache_enabled: bool,
+    pub sample_cache_size: u32,
+    pub read_only: bool,
+    pub socket_path: String,
+    pub startup_warnings: Vec<StartupWarning>,
+    pub previous_run: Option<PreviousRun>,
+    pub ui_path: Option<PathBuf>,
+    pub ui_version_warning: Option<String>,
+    pub data_migration: DataMigration,
+    #[schemars(with = "EnumMapSchema<MuteSyncApplication, MuteSyncStatus>")]
+    pub mute_sync: EnumMap<MuteSyncApplication, MuteSyncStatus>,
+
+    /// Not present if the daemon was built without MIDI support
+    pub midi: Option<MidiStatus>,
+
+    /// Not present if the daemon was built without usage statistics
+    pub telemetry: Option<TelemetryStatus>,
+
+    /// Profiles loaded as devices connect, in order of precedence
+    pub profile_rules: Vec<ProfileRule>,
+
+    /// While active, guarded sample buttons can't be played, recorded to or cleared
+    pub guard_mode: bool,
+
+    /// Directories the daemon writes to which are running out of space, rechecked every minute
+    pub low_disk_space: Vec<LowDiskSpace>,
+
+    /// Why the settings couldn't be saved, cleared by the next save which works
+    pub settings_save_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MuteSyncStatus {
+    pub enabled: bool,
+    pub direction: MuteSyncDirection,
+    pub client_id: Option<String>,
+    pub state: MuteSyncState,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MuteSyncState {
+    #[default]
+    Disabled,
+
+    /// Enabled, but the client details haven't been set
+    NotConfigured,
+
+    /// The application couldn't be found, this is retried periodically
+    NotRunning,
+    Connecting,
+
+    /// Waiting for the user to accept the request in the application
+    AwaitingAuthorisation,
+    Connected,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+
+    /// When the statistics were last sent (or an attempt was made), as a unix timestamp
+    pub last_submitted: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MidiStatus {
+    pub enabled: bool,
+    pub port: Option<String>,
+    pub state: MidiState,
+    pub mappings: Vec<MidiMappingStatus>,
+
+    /// The most recent message received from a control, whether or not it's mapped
+    pub last_event: Option<MidiEvent>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MidiState {
+    #[default]
+    Disabled,
+
+    /// Enabled, but no port has been chosen
+    NotConfigured,
+
+    /// The port couldn't be found, this is retried periodically
+    PortNotFound,
+    Connected,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MidiMappingStatus {
+    pub mapping: MidiMapping,
+    pub last_value: Option<u8>,
+
+    /// Why the last command failed, cleared when one succeeds
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MidiEvent {
+    pub control: MidiControl,
+    pub value: u8,
+}
+
+/// Moving the daemon's data between directories, see DaemonCommand::MigrateDataDirectory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DataMigration {
+    /// Another installation's data directory, found at startup while ours had no profiles
+    pub alternate_directory: Option<PathBuf>,
+
+    /// The most recent migration, if there's been one since the daemon started
+    pub progress: Option<MigrationProgress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MigrationProgress {
+    pub kind: MigrationKind,
+    pub state: MigrationState,
+    pub files_total: u32,
+    pub files_copied: u32,
+    pub files_verified: u32,
+
+    /// Files which were already in the target with different content, these were copied
+    /// alongside them under a new name (as they're found in the target)
+    pub renamed: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum MigrationKind {
+    /// The current data is being copied to a new directory, which will then be used
+    MoveTo(PathBuf),
+
+    /// Another directory's data is being copied into the current directories
+    ImportFrom(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MigrationState {
+    Copying,
+    Verifying,
+    Complete,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LowDiskSpace {
+    pub path: PathBuf,
+
+    /// In bytes
+    pub available: u64,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// A problem found while the daemon was starting which didn't stop it from running, but will
+/// likely cause something not to work.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StartupWarning {
+    pub check: String,
+    pub message: String,
+}
+
+/// How the daemon's previous run came to an end, this is None if there's no record of one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PreviousRun {
+    pub outcome: PreviousRunOutcome,
+
+    /// How long the run lasted, unknown if it was killed without a chance to record anything
+    pub duration_secs: Option<u64>,
+
+    /// The first panic during the run (with its location), if there was one
+    pub panic: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum PreviousRunOutcome {
+    Stopped(ShutdownReason),
+    Crashed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum ShutdownReason {
+    UserRequest,
+    OsSignal,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct DriverDetails {
     pub interface: DriverInterface,
     pub version: VersionNumber,
+    pub status: DriverStatus,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub enum DriverStatus {
+    #[default]
+    Ok,
+    NotInstalled,
+    Outdated {
+        required: VersionNumber,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Locale {
     pub user_locale: Option<String>,
     pub system_locale: String,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Activation {
     pub active_path: Option<String>,
     pub app_path: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct HttpSettings {
     pub enabled: bool,
     pub bind_address: String,
     pub cors_enabled: bool,
     pub port: u16,
+    pub rate_limit: HttpRateLimit,
+}
+
+/// Limits on how quickly clients of the HTTP API can send requests, a limit of 0 turns it off.
+/// Clients on the local machine are never limited, they could just as easily use the IPC socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HttpRateLimit {
+    /// Commands each address may send per second, any unused allowance carries over for up to a
+    /// second, so short bursts are fine.
+    pub commands_per_second: u32,
+
+    /// Status requests each address may make per second
+    pub status_per_second: u32,
+
+    /// Commands from all addresses which can be waiting on the devices at once
+    pub max_in_flight: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for HttpRateLimit {
+    fn default() -> Self {
+        Self {
+            commands_per_second: 20,
+            status_per_second: 50,
+
+            // The same size as the queue to the device worker
+            max_in_flight: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MixerStatus {
     pub hardware: HardwareStatus,
     pub shutdown_commands: Vec<GoXLRCommand>,
     pub sleep_commands: Vec<GoXLRCommand>,
     pub wake_commands: Vec<GoXLRCommand>,
+    #[schemars(with = "EnumMapSchema<FaderName, FaderStatus>")]
     pub fader_status: EnumMap<FaderName, FaderStatus>,
     pub mic_status: MicSettings,
     pub levels: Levels,
+    #[schemars(with = "EnumMapSchema<InputDevice, EnumMapSchema<OutputDevice, bool>>")]
     pub router: EnumMap<InputDevice, EnumMap<OutputDevice, bool>>,
+    #[schemars(with = "EnumMapSchema<OutputDevice, OutputActivity>")]
+    pub output_activity: EnumMap<OutputDevice, OutputActivity>,
+    pub vod_output: Option<VodOutput>,
     pub cough_button: CoughButton,
     pub lighting: Lighting,
     pub effects: Option<Effects>,
     pub sampler: Option<Sampler>,
     pub settings: Settings,
+    #[schemars(with = "EnumMapSchema<Button, bool>")]
     pub button_down: EnumMap<Button, bool>,
+    pub device_test: DeviceTestStatus,
+
+    /// Set while a firmware update is running, commands for the device are refused (with
+    /// CommandError::FirmwareUpdateInProgress) and it isn't polled until the update has finished
+    pub locked: bool,
+    pub firmware_update: FirmwareUpdateState,
     pub profile_name: String,
+    pub profile_from_newer_version: bool,
+    pub profile_dirty: bool,
+    pub profile_inheritance: Option<ProfileInheritance>,
+    pub jacks: JackStatus,
     pub mic_profile_name: String,
+    pub mic_profile_dirty: bool,
+    pub volume_scenes: Vec<String>,
+    pub diagnostics: Diagnostics,
+    pub session: DeviceSession,
+    pub privacy: PrivacyStatus,
+    pub headset: HeadsetStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PrivacyStatus {
+    /// None when Privacy Mode is off
+    pub config: Option<PrivacyConfig>,
+
+    /// While locked the Microphone is muted, whatever the profile says
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HeadsetStatus {
+    /// The settings headset mode replaced, None when it's off
+    pub snapshot: Option<HeadsetSnapshot>,
+
+    /// The Mic Monitor level (as a percentage) headset mode applies
+    pub sidetone: u8,
+}
+
+/// The device's connection and activity during this run of the daemon, times are Unix timestamps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceSession {
+    pub connected_at: i64,
+
+    /// How many times the device has been disconnected and connected again
+    pub reconnect_count: u32,
+    pub commands_processed: u64,
+    pub last_command_at: Option<i64>,
+}
+
+/// Present when the profile is based on another profile.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileInheritance {
+    /// The base profile first, followed by its base (if it has one) and so on
+    pub chain: Vec<String>,
+
+    /// The profile each section was taken from, if the base couldn't be loaded this will be the
+    /// profile itself for every section.
+    #[schemars(with = "EnumMapSchema<ProfileSection, String>")]
+    pub sources: EnumMap<ProfileSection, String>,
+}
+
+/// Whether something is plugged into each jack, these are None unless the device reports it (see
+/// DeviceCapabilities::jack_detection). Outputs without a physical jack are always None.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct JackStatus {
+    #[schemars(with = "EnumMapSchema<OutputDevice, Option<bool>>")]
+    pub outputs: EnumMap<OutputDevice, Option<bool>>,
+    pub microphone: Option<bool>,
+}
+
+/// How long the device has recently been taking to handle commands, and to respond to changes
+/// on the hardware (button presses and fader movements).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Diagnostics {
+    pub commands: TimingStats,
+    pub input_polls: TimingStats,
+
+    /// How often (in milliseconds) the device is currently being read for changes, this drops
+    /// to a slower rate while the device is idle.
+    pub poll_interval: u16,
+    pub polling_idle: bool,
+
+    /// Set when the device keeps stopping responding, it's no longer reset automatically until
+    /// it's been a while since the last reset.
+    pub faulted: bool,
+
+    /// None when the device has no sampler.
+    pub sampler_output: Option<AudioOutputStatus>,
+}
+
+/// The audio device Samples are played to, which is followed as it's unplugged and reconnected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AudioOutputStatus {
+    /// None while the device can't be found
+    pub device: Option<String>,
+    pub times_lost: u32,
+    pub last_change: Option<String>,
+
+    /// True while the sound server itself can't be reached (Linux only), nothing will play or
+    /// record until it's back.
+    pub server_lost: bool,
+    pub server_interruptions: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TimingStats {
+    pub samples: u32,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// On the Mini, the Sampler output carries a second stream mix for VOD recordings, this tells
+/// clients which column of the router that is, and whether it can currently be changed.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VodOutput {
+    pub output: OutputDevice,
+    pub label: String,
+
+    // In StreamNoMusic mode, the VOD mix follows the Broadcast Mix (minus Music)
+    pub routable: bool,
+
+    /// The inputs whose route to this output can be set, while it follows the Broadcast Mix
+    /// only Samples can be (to keep them off the recording).
+    pub routable_inputs: Vec<InputDevice>,
+}
+
+/// Whether anything can be heard on an output, worked out from the routing the device is actually
+/// using (after mutes, the monitor mix, VOD mode, etc.) rather than the profile's routing table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OutputActivity {
+    /// The inputs currently sent to this output
+    pub inputs: Vec<InputDevice>,
+
+    /// Every input sent to this output is muted (false when nothing is sent to it)
+    pub all_muted: bool,
+
+    /// Nothing is sent here, or everything sent here is muted or has no volume, or the output's
+    /// own volume is zero
+    pub likely_silent: bool,
 }
 
 impl MixerStatus {
@@ -101,7 +497,7 @@ impl MixerStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HardwareStatus {
     pub versions: FirmwareVersions,
     pub serial_number: String,
@@ -109,21 +505,128 @@ pub struct HardwareStatus {
     pub device_type: DeviceType,
     pub colour_way: ColourWay,
     pub usb_device: UsbProductInformation,
+    pub capabilities: DeviceCapabilities,
+}
+
+/// Features which may or may not be available depending on the device, UIs should use these to
+/// decide whether to present the related settings.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceCapabilities {
+    /// The Sampler, Effects, Encoders and Scribbles, only found on the full sized GoXLR.
+    pub full_size: bool,
+
+    /// An EQ on the Headphone output. No GoXLR firmware currently exposes any output side filter
+    /// keys (the only EQ keys in the effect space belong to the microphone chain), so this is
+    /// false for every device the daemon finds, and `SetHeadphoneEqGain` is refused. Where it's
+    /// available, the gains are kept in the profile.
+    pub headphone_eq: bool,
+
+    /// A Low Cut on the Line In and Console inputs. Like the Headphone EQ there are no filter
+    /// keys for any non-mic input, so this is always false. `SetInputLowCut` is still accepted on
+    /// devices with a Sampler, but the filter is only applied to what the Sampler records.
+    pub input_low_cut: bool,
+
+    /// Whether the device reports what's plugged into its jacks. The button state poll is the
+    /// only status the firmware sends, and it has no jack sense, so this is always false.
+    pub jack_detection: bool,
+
+    /// Lighting Animations, available from firmware 1.3.40 on the Full and 1.1.8 on the Mini.
+    pub animations: bool,
+
+    /// Submixes and Mix Monitoring, available from firmware 1.4.2.107 on the Full and 1.2.0.46
+    /// on the Mini.
+    pub submixes: bool,
+}
+
+impl DeviceCapabilities {
+    /// Whether the device has a feature, commands needing one it doesn't have are refused with
+    /// `CommandError::NotSupported`.
+    pub fn has(&self, feature: DeviceFeature) -> bool {
+        match feature {
+            DeviceFeature::FullSize => self.full_size,
+            DeviceFeature::Animations => self.animations,
+            DeviceFeature::Submixes => self.submixes,
+            DeviceFeature::HeadphoneEq => self.headphone_eq,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceTestStatus {
+    pub identifying: bool,
+    pub button_test: bool,
+
+    /// Buttons which have been pressed since the Button Test started
+    #[schemars(with = "EnumMapSchema<Button, bool>")]
+    pub buttons_registered: EnumMap<Button, bool>,
+}
+
+/// Where a device is in a firmware update.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum FirmwareUpdateState {
+    #[default]
+    Idle,
+
+    /// Putting the device into its update mode
+    Starting,
+
+    /// Clearing the device's update partition
+    Erasing,
+
+    /// Sending the firmware to the update partition
+    Uploading,
+
+    /// The device checking what it was sent
+    Validating,
+
+    /// The device verifying the new firmware before it's used
+    Verifying,
+
+    /// The device writing the new firmware to its active memory
+    Writing,
+
+    /// The firmware has been written, and the device is restarting with it
+    Complete,
+    Failed,
+}
+
+impl FirmwareUpdateState {
+    /// Whether an update is running, the device is locked until it reaches Complete or Failed.
+    pub fn is_running(&self) -> bool {
+        !matches!(
+            self,
+            FirmwareUpdateState::Idle | FirmwareUpdateState::Complete | FirmwareUpdateState::Failed
+        )
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FaderStatus {
     pub channel: ChannelName,
     pub mute_type: MuteFunction,
     pub scribble: Option<Scribble>,
     pub mute_state: MuteState,
+
+    /// On the Mini, set while the fader is being ignored until it's moved to the channel's
+    /// volume, with the direction it needs to be moved in.
+    pub awaiting_pickup: Option<PickupDirection>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum PickupDirection {
+    Up,
+    Down,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, JsonSchema)]
 pub struct CoughButton {
     pub is_toggle: bool,
     pub mute_type: MuteFunction,
     pub state: MuteState,
+
+    /// The fader the Mic channel is on, whose mute button shares the Mic's mute state with the
+    /// cough button. None when the Mic isn't on a fader.
+    pub mic_fader: Option<FaderName>,
 }
 
 impl Default for FaderStatus {
@@ -133,13 +636,16 @@ impl Default for FaderStatus {
             mute_type: MuteFunction::All,
             scribble: None,
             mute_state: Unmuted,
+            awaiting_pickup: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MicSettings {
     pub mic_type: MicrophoneType,
+    pub phantom_power_active: bool,
+    #[schemars(with = "EnumMapSchema<MicrophoneType, u16>")]
     pub mic_gains: EnumMap<MicrophoneType, u16>,
 
     pub equaliser: Equaliser,
@@ -148,42 +654,60 @@ pub struct MicSettings {
     pub compressor: Compressor,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Levels {
     pub submix_supported: bool,
     pub output_monitor: OutputDevice,
+    #[schemars(with = "EnumMapSchema<ChannelName, u8>")]
     pub volumes: EnumMap<ChannelName, u8>,
     pub submix: Option<Submixes>,
     pub bleep: i8,
     pub deess: u8,
+
+    /// The Low Cut (in Hz) of each input, only applied to Sampler recordings and replays.
+    #[schemars(with = "EnumMapSchema<InputDevice, Option<u8>>")]
+    pub input_low_cut: EnumMap<InputDevice, Option<u8>>,
+
+    /// The Left / Right balance of each input, applied to every output it's routed to. The
+    /// device only has 32 steps per side, so nearby values may sound the same.
+    #[schemars(with = "EnumMapSchema<InputDevice, i8>")]
+    pub input_balance: EnumMap<InputDevice, i8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Submixes {
+    #[schemars(with = "EnumMapSchema<SubMixChannelName, Submix>")]
     pub inputs: EnumMap<SubMixChannelName, Submix>,
+    #[schemars(with = "EnumMapSchema<OutputDevice, Mix>")]
     pub outputs: EnumMap<OutputDevice, Mix>,
+
+    /// The mix heard on the Headphones, which follows the output being monitored
+    pub monitored_mix: Mix,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Submix {
     pub volume: u8,
     pub linked: bool,
     pub ratio: f64,
+
+    /// The volume this channel is heard at on the Headphones, from the monitored mix
+    pub monitored_volume: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Equaliser {
     pub gain: HashMap<EqFrequencies, i8>,
     pub frequency: HashMap<EqFrequencies, f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EqualiserMini {
     pub gain: HashMap<MiniEqFrequencies, i8>,
     pub frequency: HashMap<MiniEqFrequencies, f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NoiseGate {
     pub threshold: i8,
     pub attack: GateTimes,
@@ -192,7 +716,7 @@ pub struct NoiseGate {
     pub attenuation: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Compressor {
     pub threshold: i8,
     pub ratio: CompressorRatio,
@@ -201,7 +725,7 @@ pub struct Compressor {
     pub makeup_gain: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Lighting {
     pub animation: AnimationLighting,
     pub faders: HashMap<FaderName, FaderLighting>,
@@ -209,9 +733,24 @@ pub struct Lighting {
     pub simple: HashMap<SimpleColourTargets, OneColour>,
     pub sampler: HashMap<SamplerColourTargets, SamplerLighting>,
     pub encoders: HashMap<EncoderColourTargets, ThreeColours>,
+    pub reactive: ReactiveLighting,
+
+    /// None when High Contrast mode is off
+    pub high_contrast: Option<HighContrastConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ReactiveLighting {
+    pub config: Option<ReactiveConfig>,
+
+    /// False while the lighting is paused for an Identify or the Button Test
+    pub active: bool,
+
+    /// The Mic level (in whole dB) the lighting is following, published a few times a second
+    pub level: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AnimationLighting {
     pub supported: bool,
     pub mode: AnimationMode,
@@ -220,51 +759,66 @@ pub struct AnimationLighting {
     pub waterfall_direction: WaterfallDirection,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ButtonLighting {
     pub off_style: ButtonColourOffStyle,
     pub colours: TwoColours,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SamplerLighting {
     pub off_style: ButtonColourOffStyle,
     pub colours: ThreeColours,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FaderLighting {
     pub style: FaderDisplayStyle,
     pub colours: TwoColours,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OneColour {
     pub colour_one: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TwoColours {
     pub colour_one: String,
     pub colour_two: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ThreeColours {
     pub colour_one: String,
     pub colour_two: String,
     pub colour_three: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Effects {
     pub is_enabled: bool,
     pub active_preset: EffectBankPresets,
     pub preset_names: HashMap<EffectBankPresets, String>,
     pub current: ActiveEffects,
+
+    #[schemars(with = "EnumMapSchema<EncoderName, EncoderState>")]
+    pub encoders: EnumMap<EncoderName, EncoderState>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EncoderState {
+    /// Set when the encoder's value is shared by every preset, rather than loaded with each
+    /// one (see GoXLRCommand::SetEncoderGlobalMode).
+    pub is_global: bool,
+
+    /// The value the encoder is set to, whichever preset it came from.
+    pub value: i8,
+}
+
+/// The active preset, values (other than the raw encoder positions) are in the units accepted by
+/// the matching GoXLRCommands, so the preset can be copied with `as_commands`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ActiveEffects {
     pub reverb: Reverb,
     pub echo: Echo,
@@ -273,9 +827,119 @@ pub struct ActiveEffects {
     pub megaphone: Megaphone,
     pub robot: Robot,
     pub hard_tune: HardTune,
+    pub intensity: EffectsIntensity,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ActiveEffects {
+    /// The commands which would recreate this preset on the active preset of another device.
+    ///
+    /// Order matters here, a style replaces the rest of its effect's values, the intensity
+    /// rescales the amounts, and the pitch range depends on whether Hard Tune is enabled, so
+    /// each of those is sent before the values which depend on it. Only the echo timing used by
+    /// its style (tempo, or Classic Slap's delays) can be set.
+    pub fn as_commands(&self) -> Vec<GoXLRCommand> {
+        let (reverb, echo, pitch) = (&self.reverb, &self.echo, &self.pitch);
+        let (megaphone, robot, hard_tune) = (&self.megaphone, &self.robot, &self.hard_tune);
+
+        let mut commands = vec![
+            GoXLRCommand::SetEffectsIntensity(self.intensity.intensity),
+            GoXLRCommand::SetReverbStyle(reverb.style),
+            GoXLRCommand::SetReverbAmount(reverb.amount),
+            GoXLRCommand::SetReverbDecay(reverb.decay),
+            GoXLRCommand::SetReverbEarlyLevel(reverb.early_level),
+            GoXLRCommand::SetReverbTailLevel(reverb.tail_level),
+            GoXLRCommand::SetReverbPreDelay(reverb.pre_delay),
+            GoXLRCommand::SetReverbLowColour(reverb.lo_colour),
+            GoXLRCommand::SetReverbHighColour(reverb.hi_colour),
+            GoXLRCommand::SetReverbHighFactor(reverb.hi_factor),
+            GoXLRCommand::SetReverbDiffuse(reverb.diffuse),
+            GoXLRCommand::SetReverbModSpeed(reverb.mod_speed),
+            GoXLRCommand::SetReverbModDepth(reverb.mod_depth),
+            GoXLRCommand::SetEchoStyle(echo.style),
+            GoXLRCommand::SetEchoAmount(echo.amount),
+            GoXLRCommand::SetEchoFeedback(echo.feedback),
+        ];
+
+        // Classic Slap has its own delays, the other styles follow the tempo
+        match echo.style {
+            EchoStyle::ClassicSlap => commands.extend([
+                GoXLRCommand::SetEchoDelayLeft(echo.delay_left),
+                GoXLRCommand::SetEchoDelayRight(echo.delay_right),
+            ]),
+            _ => commands.push(GoXLRCommand::SetEchoTempo(echo.tempo)),
+        }
+
+        commands.extend([
+            GoXLRCommand::SetEchoFeedbackLeft(echo.feedback_left),
+            GoXLRCommand::SetEchoFeedbackRight(echo.feedback_right),
+            GoXLRCommand::SetEchoFeedbackXFBLtoR(echo.feedback_xfb_l_to_r),
+            GoXLRCommand::SetEchoFeedbackXFBRtoL(echo.feedback_xfb_r_to_l),
+            GoXLRCommand::SetHardTuneEnabled(hard_tune.is_enabled),
+            GoXLRCommand::SetHardTuneStyle(hard_tune.style),
+            GoXLRCommand::SetHardTuneAmount(hard_tune.amount),
+            GoXLRCommand::SetHardTuneRate(hard_tune.rate),
+            GoXLRCommand::SetHardTuneWindow(hard_tune.window),
+            GoXLRCommand::SetHardTuneSource(hard_tune.source),
+            GoXLRCommand::SetPitchStyle(pitch.style),
+            GoXLRCommand::SetPitchAmount(pitch.amount),
+            GoXLRCommand::SetPitchCharacter(pitch.character),
+            GoXLRCommand::SetGenderStyle(self.gender.style),
+            GoXLRCommand::SetGenderAmount(self.gender.amount),
+            GoXLRCommand::SetMegaphoneEnabled(megaphone.is_enabled),
+            GoXLRCommand::SetMegaphoneStyle(megaphone.style),
+            GoXLRCommand::SetMegaphoneAmount(megaphone.amount),
+            GoXLRCommand::SetMegaphonePostGain(megaphone.post_gain),
+            GoXLRCommand::SetRobotEnabled(robot.is_enabled),
+            GoXLRCommand::SetRobotStyle(robot.style),
+        ]);
+
+        let bands = [
+            (
+                RobotRange::Low,
+                robot.low_gain,
+                robot.low_freq,
+                robot.low_width,
+            ),
+            (
+                RobotRange::Medium,
+                robot.mid_gain,
+                robot.mid_freq,
+                robot.mid_width,
+            ),
+            (
+                RobotRange::High,
+                robot.high_gain,
+                robot.high_freq,
+                robot.high_width,
+            ),
+        ];
+        for (range, gain, freq, width) in bands {
+            commands.push(GoXLRCommand::SetRobotGain(range, gain));
+            commands.push(GoXLRCommand::SetRobotFreq(range, freq));
+            commands.push(GoXLRCommand::SetRobotWidth(range, width));
+        }
+
+        commands.extend([
+            GoXLRCommand::SetRobotWaveform(robot.waveform),
+            GoXLRCommand::SetRobotPulseWidth(robot.pulse_width),
+            GoXLRCommand::SetRobotThreshold(robot.threshold),
+            GoXLRCommand::SetRobotDryMix(robot.dry_mix),
+        ]);
+        commands
+    }
+}
+
+/// The Reverb, Echo and Pitch amounts are scaled from these baselines by the intensity, the
+/// baselines use the same units as each effect's amount.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EffectsIntensity {
+    pub intensity: u8,
+    pub reverb_baseline: u8,
+    pub echo_baseline: u8,
+    pub pitch_baseline: i8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Reverb {
     pub style: ReverbStyle,
     pub amount: u8,
@@ -292,7 +956,7 @@ pub struct Reverb {
     pub raw_encoder: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Echo {
     pub style: EchoStyle,
     pub amount: u8,
@@ -307,7 +971,7 @@ pub struct Echo {
     pub raw_encoder: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Pitch {
     pub style: PitchStyle,
     pub amount: i8,
@@ -315,14 +979,14 @@ pub struct Pitch {
     pub raw_encoder: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Gender {
     pub style: GenderStyle,
     pub amount: i8,
     pub raw_encoder: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Megaphone {
     pub is_enabled: bool,
     pub style: MegaphoneStyle,
@@ -330,7 +994,7 @@ pub struct Megaphone {
     pub post_gain: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Robot {
     pub is_enabled: bool,
     pub style: RobotStyle,
@@ -349,7 +1013,7 @@ pub struct Robot {
     pub dry_mix: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HardTune {
     pub is_enabled: bool,
     pub style: HardTuneStyle,
@@ -359,49 +1023,157 @@ pub struct HardTune {
     pub source: HardTuneSource,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Sampler {
     pub processing_state: SampleProcessState,
     pub active_bank: SampleBank,
     pub clear_active: bool,
+
+    // Seconds until an armed Sample Clear disarms itself, if it will
+    pub clear_remaining: Option<u16>,
     pub record_buffer: u16,
     pub banks: HashMap<SampleBank, HashMap<SampleButtons, SamplerButton>>,
+    pub cache: Option<SampleCacheStatus>,
+    pub last_replay: Option<ReplayCapture>,
+}
+
+/// A capture of the Sampler's pre-buffer, saved without assigning it to a button.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayCapture {
+    pub path: PathBuf,
+    pub duration_ms: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SampleCacheStatus {
+    pub size: u64,
+    pub entries: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SampleProcessState {
     pub progress: Option<u8>,
     pub last_error: Option<String>,
+    pub normalisation: Option<BankNormalisation>,
+}
+
+/// The progress of a NormaliseBank, this remains once finished so the results can be seen.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BankNormalisation {
+    pub bank: SampleBank,
+    pub target: f32,
+    pub total: usize,
+    pub current: Option<NormalisingSample>,
+    pub results: Vec<NormalisedSample>,
+    pub finished: bool,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NormalisingSample {
+    pub button: SampleButtons,
+    pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NormalisedSample {
+    pub button: SampleButtons,
+    pub name: String,
+    pub old_gain: f64,
+
+    // Not present if the sample couldn't be analysed (error will be set)
+    pub new_gain: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SamplerButton {
     pub function: SamplePlaybackMode,
     pub order: SamplePlayOrder,
     pub samples: Vec<Sample>,
     pub is_playing: bool,
     pub is_recording: bool,
+    pub recording_peak: Option<f32>,
+
+    /// Presses are ignored while the daemon's guard mode is active
+    pub guarded: bool,
+    pub ducking: Option<DuckConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Sample {
     pub name: String,
     pub start_pct: f32,
     pub stop_pct: f32,
+
+    // These are only present once the length of the file is known
+    pub start_ms: Option<u64>,
+    pub stop_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+
+    pub markers: Vec<SampleMarker>,
+    pub recording_peak: Option<f32>,
+    pub clipped: bool,
+
+    /// The file this sample was converted from, if it was converted when it was added
+    pub source: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SampleMarker {
+    pub label: String,
+    pub start_pct: f32,
+    pub stop_pct: Option<f32>,
+    pub start_ms: Option<u64>,
+    pub stop_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Settings {
     pub display: Display,
     pub mute_hold_duration: u16,
+
+    /// Whether the Chat Mic is one of the vc_mute_outputs
     pub vc_mute_also_mute_cm: bool,
+
+    /// The outputs the Microphone is removed from while Voice Chat is muted to All
+    pub vc_mute_outputs: Vec<OutputDevice>,
+
+    /// Whether Voice Chat is muted to All, so the Microphone is currently removed from them
+    pub vc_mute_outputs_active: bool,
     pub enable_monitor_with_fx: bool,
     pub reset_sampler_on_clear: bool,
+    pub sample_clear_timeout: u16,
     pub lock_faders: bool,
+    pub silent_faders: bool,
     pub vod_mode: VodMode,
+    pub effect_preview_on_hold: bool,
+    pub clear_effects_on_hold: bool,
+    pub switch_monitor_on_unplug: bool,
+    pub save_on_shutdown: bool,
+    pub adopt_hardware_state: bool,
+    pub phantom_confirmation_required: bool,
+
+    /// Mini only, holding a fader's mute button while moving the fader adjusts the volume finely
+    pub fine_fader_adjust: bool,
+
+    /// How often (in milliseconds) the device is read for changes while it's being used
+    pub poll_interval: u16,
+
+    /// How long (in seconds) the device can go without being read before it's reset
+    pub stall_timeout: u16,
+
+    /// Samples are converted to 48kHz 16bit WAVs when they're added, so they play smoothly
+    pub convert_samples: bool,
+
+    /// Whether each channel starts linked when submixes are enabled, if the profile doesn't say
+    #[schemars(with = "EnumMapSchema<SubMixChannelName, Option<bool>>")]
+    pub submix_default_linked: EnumMap<SubMixChannelName, Option<bool>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Display {
     pub gate: DisplayMode,
     pub compressor: DisplayMode,
@@ -409,7 +1181,7 @@ pub struct Display {
     pub equaliser_fine: DisplayMode,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Paths {
     pub profile_directory: PathBuf,
     pub mic_profile_directory: PathBuf,
@@ -419,30 +1191,63 @@ pub struct Paths {
     pub logs_directory: PathBuf,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Files {
     pub profiles: Vec<String>,
+
+    /// Profiles with names which wouldn't be accepted for a new file, they still load but
+    /// should be renamed (with RenameProfile)
+    pub profile_name_problems: BTreeMap<String, NameProblem>,
     pub mic_profiles: Vec<String>,
     pub presets: Vec<String>,
+    #[schemars(with = "EnumMapSchema<EffectSection, Vec<String>>")]
+    pub effect_sections: EnumMap<EffectSection, Vec<String>>,
     pub samples: BTreeMap<String, SampleFile>,
     pub icons: Vec<String>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SampleFile {
     pub name: String,
     pub gain_pct: u8,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Scribble {
     pub file_name: Option<String>,
+
+    // Drawn instead of the file, when set
+    pub glyph: Option<String>,
     pub bottom_text: Option<String>,
     pub left_text: Option<String>,
     pub inverted: bool,
+    pub number_mode: ScribbleNumberMode,
+
+    // The text last drawn in the top left, which in the dynamic modes isn't left_text
+    pub rendered_number: Option<String>,
+
+    /// Why the scribble couldn't be drawn (such as an icon which failed to load), the device is
+    /// left showing what it had before
+    pub last_error: Option<String>,
+}
+
+/// What to show in a scribble's top left 'number' field, anything other than Static is kept up
+/// to date by the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum ScribbleNumberMode {
+    Static(String),
+    VolumePercent,
+    VolumeDb,
+    ActivePreset,
+}
+
+impl Default for ScribbleNumberMode {
+    fn default() -> Self {
+        ScribbleNumberMode::Static(String::new())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UsbProductInformation {
     pub manufacturer_name: String,
     pub product_name: String,
@@ -451,3 +1256,15 @@ pub struct UsbProductInformation {
     pub address: u8,
     pub identifier: Option<String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduledCommand {
+    pub serial: String,
+    pub schedule: Schedule,
+    pub command: GoXLRCommand,
+
+    // Unix timestamps, next_run is None once a one-shot schedule has fired
+    pub next_run: Option<i64>,
+    pub last_run: Option<i64>,
+    pub last_error: Option<String>,
+}
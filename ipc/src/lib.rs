@@ -1,42 +1,285 @@
+use enum_map::{Enum, EnumMap};
 use json_patch::Patch;
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub mod client;
 pub mod clients;
 mod device;
+mod protocol;
+mod schema;
 
+use crate::schema::EnumMapSchema;
 pub use device::*;
 use goxlr_types::{
     AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
-    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode,
-    DisplayModeComponents, EchoStyle, EffectBankPresets, EncoderColourTargets, EqFrequencies,
-    FaderDisplayStyle, FaderName, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle,
-    InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState,
+    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DeviceType, DisplayMode,
+    DisplayModeComponents, EchoStyle, EffectBankPresets, EffectName, EffectSection,
+    EncoderColourTargets, EncoderName, EqFrequencies, FaderDisplayStyle, FaderName, GateTimes,
+    GenderStyle, HardTuneSource, HardTuneStyle, HighContrastGroup, InputDevice, MegaphoneStyle,
+    MicProfileSection, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState,
     OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle, SampleBank, SampleButtons,
-    SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets, VodMode,
-    WaterfallDirection,
+    SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets,
+    SubMixChannelName, VodMode, WaterfallDirection,
 };
+pub use protocol::*;
+pub use schema::*;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum DaemonRequest {
     Ping,
+
+    /// Tells the daemon which protocol (see PROTOCOL_VERSION) the client speaks, it replies with
+    /// its own. Daemons from before protocol versions were introduced reply with an Error.
+    Hello(u32),
     GetStatus,
+
+    /// A small summary of each device, for clients which poll frequently (such as status bars)
+    /// and only need to show the mic and profile. This is a stable surface, fields are only ever
+    /// added to it, never removed or changed.
+    GetStatusSummary,
+    GetStatusSchema,
     Daemon(DaemonCommand),
     GetMicLevel(String),
     Command(String, GoXLRCommand),
+    IdentifyDevice(String),
+    TestButtons(String, bool),
+    GetAudioDevices,
+    /// Applies an AutoEQ 'ParametricEQ' file, by its name (without the .txt) in the mic profile
+    /// directory, to the EQ.
+    ImportEqCurve(String, String, EqTarget),
+
+    /// Writes the EQ to a CSV file in the mic profile directory, under the given name. An
+    /// existing file is never written over.
+    ExportEqCurve(String, String, EqTarget),
+
+    /// Writes the active profile to a .goxlr file in the profile directory, under the given name,
+    /// which the official app can load. Anything the utility adds to profiles is left out.
+    ExportWindowsProfile(String, String),
+
+    /// Reads back what the device can report about its current state, for debugging.
+    GetHardwareState(String),
+
+    /// Restores the default files of a type, either all of them or only those named, existing
+    /// files are only replaced if overwrite is set.
+    RecoverDefaults {
+        path_type: PathTypes,
+        #[serde(default)]
+        files: Option<Vec<String>>,
+        #[serde(default)]
+        overwrite: bool,
+    },
+
+    /// Writes a zip of the logs, settings and status for attaching to a bug report into the log
+    /// directory, and returns where it was written.
+    CreateSupportBundle {
+        #[serde(default)]
+        include_profiles: bool,
+    },
+
+    /// Writes the Sampler's pre-buffer (the last few seconds of audio) to the Recorded samples
+    /// directory, with an optional file name. Not available on the Mini.
+    SaveReplayBuffer(String, Option<String>),
+
+    /// Returns up to this many of the most recent lines written to the daemon's log.
+    TailLog(usize),
+
+    /// Lists the icons in the icons directory, with a thumbnail of how each looks on a scribble.
+    ListIcons,
+
+    /// Stores an image (by file name, and its contents) in the icons directory, converted to
+    /// greyscale. Icons which already exist aren't replaced.
+    UploadIcon(String, Vec<u8>),
+
+    /// Removes an icon, unless a loaded profile is using it and force isn't set.
+    DeleteIcon {
+        name: String,
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// The names of the MIDI input ports which can be listened to (see SetMidiPort).
+    GetMidiPorts,
+
+    /// The clients currently connected over IPC or a websocket, and HTTP clients which have made
+    /// a request recently.
+    GetConnectedClients,
+
+    /// Closes an IPC or websocket connection by its id (from GetConnectedClients). HTTP clients
+    /// don't hold a connection open, so can't be disconnected.
+    DisconnectClient(u64),
+
+    /// Turns the connection into a stream of events (patches and notifications) which lasts
+    /// until the client disconnects. Only supported over the IPC socket, websocket clients
+    /// receive these events without asking.
+    Subscribe {
+        #[serde(default)]
+        notifications_only: bool,
+    },
+
+    /// Finds the physical control (a fader or button) which is responsible for a channel or
+    /// function on a device, along with its current state.
+    ResolveControl(String, ControlQuery),
+
+    /// The usage statistics which will be sent next, exactly as they'll be sent. Only available
+    /// if the daemon was built with the 'telemetry' feature.
+    GetTelemetryPreview,
+
+    /// Marks a channel on a device (by serial) as managed by this client, with a label to show
+    /// users. Claims are advisory, anyone can still change the channel, but the claimant is sent
+    /// a ClaimOverridden event when another client does. They're released when the client
+    /// disconnects.
+    ClaimChannel(String, ChannelName, String),
+    ReleaseChannel(String, ChannelName),
+
+    /// What a device (by serial) would send to the hardware if Submixes were toggled, or the
+    /// VOD mode changed, without changing anything.
+    PreviewModeChange(String, ModeChange),
+
+    /// Waits (for up to the timeout, in milliseconds) until a device (by serial) has finished
+    /// everything it was asked to do before, including work carried on after the commands
+    /// returned, such as scribbles being drawn and faders moving. An error lists what was still
+    /// pending when the timeout was reached.
+    Settle(String, u32),
+}
+
+/// A change which reworks the routing and mixes of the whole device.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum ModeChange {
+    SubMix(bool),
+    Vod(VodMode),
+}
+
+/// Everything a ModeChange alters on the device, only values which change are listed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ModeChangePreview {
+    pub routing: Vec<RoutingChange>,
+
+    /// Which mix each output is assigned to, the Headphones' entry is the monitored mix
+    pub mixes: Vec<MixChange>,
+    pub volumes: Vec<VolumeChange>,
+    pub submix_volumes: Vec<SubMixVolumeChange>,
+}
+
+impl ModeChangePreview {
+    pub fn is_empty(&self) -> bool {
+        self.routing.is_empty()
+            && self.mixes.is_empty()
+            && self.volumes.is_empty()
+            && self.submix_volumes.is_empty()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct RoutingChange {
+    pub input: InputDevice,
+    pub output: OutputDevice,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct MixChange {
+    pub output: OutputDevice,
+    pub from: Mix,
+    pub to: Mix,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct VolumeChange {
+    pub channel: ChannelName,
+    pub from: u8,
+    pub to: u8,
+}
+
+/// A submix volume being sent, from is None when submixes weren't in use before.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SubMixVolumeChange {
+    pub channel: SubMixChannelName,
+    pub from: Option<u8>,
+    pub to: u8,
+}
+
+/// A channel or function, to find the physical control responsible for it.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum ControlQuery {
+    ChannelFader(ChannelName),
+    EffectButton(EffectName),
+    SamplePad(SampleBank, SampleButtons),
+    CoughButton,
+    BleepButton,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum PhysicalControl {
+    Fader(FaderName),
+    Button(Button),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ResolvedControl {
+    /// None when nothing is responsible, such as a channel which isn't on a fader
+    pub control: Option<PhysicalControl>,
+
+    /// Whether this device type has the control, the Mini has no effect or sampler buttons
+    pub available: bool,
+
+    /// The channel's volume, for faders
+    pub volume: Option<u8>,
+
+    /// Whether the button (or the fader's mute button) is lit, sample pads are only lit while
+    /// their bank is active
+    pub lit: bool,
+
+    /// Whether the control currently has its channel muted, for faders and the cough button
+    pub muted: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonResponse {
     Ok,
     Error(String),
+    Hello(u32),
     MicLevel(f64),
     Status(DaemonStatus),
+    StatusSchema(RootSchema),
     Patch(Patch),
+    AudioDevices(AudioDevices),
+    CommandError(CommandError),
+    EqCurveImport(EqCurveReport),
+    RecoveredDefaults(RecoveredDefaults),
+    Notification(Notification),
+    HardwareState(HardwareStateReport),
+    SupportBundle(PathBuf),
+    AdjustedValue(i8),
+    ReplaySaved(ReplayCapture),
+
+    /// A SetFader moved a channel onto a fader whose Mute Function doesn't work for it, so the
+    /// fader's Mute Function was reset to the one given.
+    MuteFunctionReset(FaderName, MuteFunction),
+    LogLines(Vec<String>),
+    Icons(Vec<IconDetails>),
+    Icon(IconDetails),
+    MidiPorts(Vec<String>),
+    ConnectedClients(Vec<ConnectedClient>),
+    StatusSummary(Vec<DeviceSummary>),
+    ResolvedControl(ResolvedControl),
+    TelemetryPreview(TelemetryReport),
+    ModeChangePreview(ModeChangePreview),
+
+    /// Only sent to the client holding the claim, as an event alongside patches and
+    /// notifications.
+    ClaimOverridden(ClaimOverride),
+
+    /// A button pressed during a Button Test, sent as an event alongside patches.
+    ButtonEvent(ButtonEvent),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WebsocketRequest {
     pub id: u64,
     pub data: DaemonRequest,
@@ -48,13 +291,351 @@ pub struct WebsocketResponse {
     pub data: DaemonResponse,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+/// Errors which a client may want to act on (for example, by asking the user to confirm), rather
+/// than simply display.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum CommandError {
+    /// A firmware update is running on the device, nothing else can be done with it until the
+    /// update has finished (see MixerStatus::locked)
+    FirmwareUpdateInProgress,
+
+    /// A firmware update can't start while the device is busy with something it would break
+    FirmwareUpdateBlocked(FirmwareUpdateBlocker),
+
+    UnsavedChanges,
+    PhantomPowerConfirmation,
+
+    /// The command can't be applied to this device, it was refused before anything was changed
+    NotSupported(DeviceFeature),
+
+    /// The name given for a new file (or a rename) can't be used
+    InvalidName(NameProblem),
+
+    /// Privacy Mode is holding the Microphone muted, it must be unlocked with PrivacyUnlock
+    PrivacyLocked,
+
+    /// There isn't enough free space to write a file, nothing was written (sizes are in bytes)
+    InsufficientDiskSpace {
+        path: PathBuf,
+        needed: u64,
+        available: u64,
+    },
+}
+
+/// Why a name can't be used for a file. New names have path separators, control characters and
+/// anything Windows doesn't allow removed before they're checked, existing files may still
+/// contain them.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum NameProblem {
+    /// Nothing is left once the disallowed characters are removed
+    Empty,
+
+    /// Longer than the maximum number of characters (which is given)
+    TooLong(usize),
+
+    /// A name Windows reserves for devices (CON, PRN, AUX, NUL, COM1 to 9 and LPT1 to 9)
+    Reserved,
+
+    /// Contains characters which aren't allowed in new names
+    InvalidCharacters,
+}
+
+/// Something a command needs from the device, which not every GoXLR has.
+#[derive(Debug, Copy, Clone, Enum, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum DeviceFeature {
+    /// Hardware only found on the full sized GoXLR (the Sampler, Effects, Encoders and Scribbles)
+    FullSize,
+
+    /// Lighting Animations, see DeviceCapabilities::animations
+    Animations,
+
+    /// Submixes and Mix Monitoring, see DeviceCapabilities::submixes
+    Submixes,
+
+    /// An EQ on the Headphone output, see DeviceCapabilities::headphone_eq
+    HeadphoneEq,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::FirmwareUpdateInProgress => {
+                write!(f, "A firmware update is in progress on this device")
+            }
+            CommandError::FirmwareUpdateBlocked(FirmwareUpdateBlocker::Recording) => {
+                write!(
+                    f,
+                    "The firmware can't be updated while a sample is recording"
+                )
+            }
+            CommandError::FirmwareUpdateBlocked(FirmwareUpdateBlocker::BatchRunning) => {
+                write!(f, "The firmware can't be updated until the device is idle")
+            }
+            CommandError::FirmwareUpdateBlocked(FirmwareUpdateBlocker::AlreadyUpdating) => {
+                write!(f, "A firmware update is already running on this device")
+            }
+            CommandError::UnsavedChanges => write!(f, "The current profile has unsaved changes"),
+            CommandError::PhantomPowerConfirmation => write!(
+                f,
+                "Switching to a Condenser microphone enables 48V phantom power"
+            ),
+            CommandError::NotSupported(DeviceFeature::FullSize) => {
+                write!(f, "Not supported on GoXLR Mini")
+            }
+            CommandError::NotSupported(DeviceFeature::Animations) => {
+                write!(f, "Animations are not supported by this device's firmware")
+            }
+            CommandError::NotSupported(DeviceFeature::Submixes) => {
+                write!(f, "Submixes are not supported by this device's firmware")
+            }
+            CommandError::NotSupported(DeviceFeature::HeadphoneEq) => {
+                write!(f, "Headphone EQ is not supported by this device")
+            }
+            CommandError::InvalidName(NameProblem::Empty) => write!(f, "The name is empty"),
+            CommandError::InvalidName(NameProblem::TooLong(max)) => {
+                write!(f, "The name is longer than {} characters", max)
+            }
+            CommandError::InvalidName(NameProblem::Reserved) => {
+                write!(f, "The name is reserved by Windows")
+            }
+            CommandError::InvalidName(NameProblem::InvalidCharacters) => {
+                write!(
+                    f,
+                    "The name contains characters which aren't allowed in file names"
+                )
+            }
+            CommandError::PrivacyLocked => {
+                write!(f, "The Microphone is locked muted by Privacy Mode")
+            }
+            CommandError::InsufficientDiskSpace {
+                path,
+                needed,
+                available,
+            } => {
+                let megabytes = |bytes: &u64| *bytes as f64 / (1024.0 * 1024.0);
+                write!(
+                    f,
+                    "Not enough disk space to write {}, {:.1}MB is needed but only {:.1}MB is free",
+                    path.to_string_lossy(),
+                    megabytes(needed),
+                    megabytes(available)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// What stopped a firmware update from starting.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum FirmwareUpdateBlocker {
+    /// A sample is being recorded
+    Recording,
+
+    /// Work which carries on over several updates is running (such as a sample's gain being
+    /// calculated)
+    BatchRunning,
+
+    /// An update is already running on the device
+    AlreadyUpdating,
+}
+
+/// Everything which can be read back from a device, the rest of its state (routing, colours,
+/// effects, fader assignments) can only be written to it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HardwareReadback {
+    #[schemars(with = "EnumMapSchema<FaderName, u8>")]
+    pub fader_volumes: EnumMap<FaderName, u8>,
+
+    // Pitch, Gender, Reverb and Echo, in that order (not present on the Mini)
+    pub encoders: [i8; 4],
+    pub buttons_pressed: Vec<Button>,
+    pub mic_level: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HardwareStateReport {
+    // What was read when the device connected, before the profile was applied
+    pub on_connect: Option<HardwareReadback>,
+    pub current: HardwareReadback,
+}
+
+/// An announcement from a device (the text which would be spoken by TTS), these are sent
+/// regardless of whether TTS is enabled so they can be shown on screen instead.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Notification {
+    pub serial: String,
+    pub text: String,
+    pub category: NotificationCategory,
+}
+
+/// A button pressed on a device while its Button Test is running, the press isn't actioned.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct ButtonEvent {
+    pub serial: String,
+    pub button: Button,
+}
+
+/// What caused an announcement, allowing clients to choose which they're interested in.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum NotificationCategory {
+    Mute,
+    Sampler,
+    Effects,
+    Encoders,
+    Microphone,
+
+    /// A guarded sample button was pressed, these are never spoken
+    Guard,
+
+    /// The device stopped responding, and was reset (or is being left alone)
+    Device,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum EqTarget {
+    Microphone,
+    Headphones,
+}
+
+/// Describes what happened to each filter of an imported EQ curve, and the gains which were
+/// applied as a result.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EqCurveReport {
+    /// The curve's preamp, this isn't applied, but may be useful to know.
+    pub preamp: Option<f32>,
+    pub filters: Vec<EqCurveFilter>,
+    pub gains: Vec<EqCurveGain>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EqCurveFilter {
+    pub frequency: f32,
+    pub gain: f32,
+    pub result: EqCurveFilterResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum EqCurveFilterResult {
+    /// The frequency of the band this filter's gain was added to
+    Mapped(f32),
+    Dropped(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EqCurveGain {
+    pub frequency: f32,
+    pub gain: i8,
+
+    // Set if the combined gain was outside the range the device supports
+    pub clamped: bool,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum ClientTransport {
+    Ipc,
+    WebSocket,
+    Http,
+}
+
+/// The state of a device most often shown by status bars, see GetStatusSummary.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct DeviceSummary {
+    pub serial: String,
+
+    /// A name given to the device by the user, devices can't currently be named so this is
+    /// always None
+    #[serde(default)]
+    pub nickname: Option<String>,
+    pub device_type: DeviceType,
+    pub profile_name: String,
+
+    /// Whether the Microphone is muted to everything, by the cough button, its fader's mute
+    /// button, or the privacy lock
+    pub mic_muted: bool,
+    pub cough_state: MuteState,
+    pub fx_enabled: bool,
+    pub mic_volume: u8,
+    pub headphones_volume: u8,
+}
+
+/// Something talking to the daemon, times are Unix timestamps.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct ConnectedClient {
+    pub id: u64,
+    pub transport: ClientTransport,
+
+    /// The remote address, where one is known (IPC clients are always local)
+    pub address: Option<String>,
+    pub connected_at: i64,
+
+    /// Every request made, and of those, how many were commands
+    pub requests: u64,
+    pub commands: u64,
+
+    /// The protocol the client gave in its Hello, if it sent one
+    #[serde(default)]
+    pub protocol: Option<u32>,
+}
+
+/// A channel which a client has said it's managing, see ClaimChannel.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct ChannelClaim {
+    pub serial: String,
+    pub channel: ChannelName,
+    pub claimant: String,
+
+    /// The id of the claiming client, from GetConnectedClients
+    pub client_id: u64,
+    pub claimed_at: i64,
+}
+
+/// A claimed channel was changed by a command from a different client.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimOverride {
+    pub claim: ChannelClaim,
+    pub overridden_by: ConnectedClient,
+    pub command: GoXLRCommand,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct RecoveredDefaults {
+    pub created: Vec<String>,
+    pub skipped: Vec<String>,
+
+    /// File names, and why they couldn't be restored
+    pub failed: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct IconDetails {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+
+    /// A PNG of the icon as it would be drawn on a scribble strip, regenerated when the icon
+    /// changes. Also served over HTTP at /files/icons/{name}/thumbnail.png
+    pub thumbnail: PathBuf,
+}
+
+/// The audio devices known to the platform audio backend, alongside the devices the sampler
+/// would currently use for recording and playback.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub struct AudioDevices {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub current_sampler_input: Option<String>,
+    pub current_sampler_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
 pub enum ColourWay {
     Black,
     White,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
 pub enum PathTypes {
     Profiles,
     MicProfiles,
@@ -65,7 +646,7 @@ pub enum PathTypes {
     Backups,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
 pub enum LogLevel {
     Off,
     Error,
@@ -76,29 +657,328 @@ pub enum LogLevel {
     Trace,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Applications whose microphone mute can be kept in step with the GoXLR's.
+#[derive(Debug, Copy, Clone, Hash, Enum, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum MuteSyncApplication {
+    Discord,
+}
+
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum MuteSyncDirection {
+    /// The application is muted and unmuted along with the GoXLR's microphone
+    #[default]
+    ToApplication,
+
+    /// As above, and muting in the application also mutes the GoXLR's microphone
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum DaemonCommand {
     OpenUi,
     Activate,
     StopDaemon,
     OpenPath(PathTypes),
     SetLogLevel(LogLevel),
+
+    /// Log filter directives in the style of env_logger's RUST_LOG (for example,
+    /// `goxlr_daemon::device=trace,goxlr_usb=debug`), applied on top of the log level without a
+    /// restart. None removes them.
+    SetLogFilter(Option<String>),
     SetShowTrayIcon(bool),
     SetLocale(Option<String>),
     SetTTSEnabled(bool),
     SetAutoStartEnabled(bool),
     SetAllowNetworkAccess(bool),
+    SetHttpRateLimit(HttpRateLimit),
     SetUiLaunchOnLoad(bool),
     RecoverDefaults(PathTypes),
     SetActivatorPath(Option<PathBuf>),
 
+    /// Serves the Web UI from a directory (which must contain an index.html) rather than the
+    /// copy built into the daemon, None switches back to the built in copy.
+    SetUiPath(Option<PathBuf>),
+
+    /// Copies the profiles, mic profiles, presets, samples, icons and settings into a new
+    /// directory, checks the copies, then switches the daemon over to it. This runs in the
+    /// background, with its progress reported in the status.
+    MigrateDataDirectory(PathBuf),
+
+    /// Copies another installation's data (such as the alternate directory reported in the
+    /// status) into the current directories, in the same way as a migration.
+    ImportDataDirectory(PathBuf),
+
+    // Keeping an application's mute in step with the GoXLR
+    SetMuteSyncEnabled(MuteSyncApplication, bool),
+    SetMuteSyncDirection(MuteSyncApplication, MuteSyncDirection),
+
+    /// The application's client details, for Discord these are from an application created in
+    /// the Developer Portal. Changing these will require authorising again.
+    SetMuteSyncClient {
+        application: MuteSyncApplication,
+        client_id: String,
+        client_secret: String,
+    },
+
     SetSampleGainPct(String, u8),
     ApplySampleChange,
 
+    SetSampleCacheEnabled(bool),
+    SetSampleCacheSize(u32),
+    ClearSampleCache,
+
+    /// Stops guarded sample buttons from being pressed, for when a recording or stream starts.
+    /// This isn't saved, the daemon always starts with it off.
+    SetGuardMode(bool),
+
     HandleMacOSAggregates(bool),
+
+    // Driving commands from a MIDI controller, only available if the daemon was built with the
+    // 'midi' feature (in which case DaemonConfig.midi is set)
+    SetMidiEnabled(bool),
+    SetMidiPort(Option<String>),
+
+    /// Adds a mapping, replacing any existing mapping for the same control.
+    SetMidiMapping(MidiMapping),
+    RemoveMidiMapping(MidiControl),
+
+    // Anonymous usage statistics, only available if the daemon was built with the 'telemetry'
+    // feature (in which case DaemonConfig.telemetry is set). Disabling them discards anything
+    // collected but not yet sent.
+    SetTelemetryEnabled(bool),
+
+    /// Where the statistics are sent, nothing is sent while this isn't set.
+    SetTelemetryEndpoint(Option<String>),
+
+    /// Adds a profile rule, replacing any existing rule for the same trigger.
+    SetProfileRule(ProfileRule),
+    RemoveProfileRule(ProfileTrigger),
+
+    // Scheduled Commands
+    ScheduleCommand {
+        id: String,
+        when: Schedule,
+        serial: String,
+        command: GoXLRCommand,
+    },
+    CancelScheduledCommand(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Anonymous usage statistics, sent at most once a week while enabled. Only counts are kept,
+/// never serials, names or paths.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TelemetryReport {
+    pub daemon_version: String,
+
+    /// The operating system (linux, macos or windows)
+    pub os_family: String,
+    pub counts: TelemetryCounts,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct TelemetryCounts {
+    /// Commands run on each type of device
+    #[schemars(with = "EnumMapSchema<DeviceType, u64>")]
+    pub commands: EnumMap<DeviceType, u64>,
+
+    /// Commands which used each feature, as classified for the device capability checks, a
+    /// command can count towards more than one
+    #[schemars(with = "EnumMapSchema<DeviceFeature, u64>")]
+    pub features: EnumMap<DeviceFeature, u64>,
+}
+
+/// A control on a MIDI device, channels are numbered from 0 to 15.
+#[derive(Debug, Copy, Clone, Hash, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
+pub enum MidiControl {
+    ControlChange {
+        channel: u8,
+        controller: u8,
+    },
+
+    /// Pressing sends the velocity as the value, releasing sends 0
+    Note {
+        channel: u8,
+        note: u8,
+    },
+}
+
+/// Runs a command on a device when a MIDI control changes. The command is a GoXLRCommand as
+/// JSON, in which the string "$value" is replaced by the control's value (0 to 127) scaled to
+/// between min and max, for example {"SetSubMixVolume": ["Chat", "$value"]}. A command without
+/// "$value" runs each time the control is pressed (its value goes above 0).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct MidiMapping {
+    pub control: MidiControl,
+    pub serial: String,
+    pub command: Value,
+    #[serde(default)]
+    pub min: i32,
+    #[serde(default = "default_midi_max")]
+    pub max: i32,
+}
+
+fn default_midi_max() -> i32 {
+    127
+}
+
+impl MidiMapping {
+    /// Builds the command for a value, or None if the mapping shouldn't run for it.
+    pub fn command_for(&self, value: u8) -> serde_json::Result<Option<GoXLRCommand>> {
+        let range = (self.max - self.min) as f64;
+        let scaled = (self.min as f64 + range * value.min(127) as f64 / 127.).round() as i64;
+
+        let mut command = self.command.clone();
+        if !replace_midi_value(&mut command, scaled) && value == 0 {
+            return Ok(None);
+        }
+        serde_json::from_value(command).map(Some)
+    }
+}
+
+fn replace_midi_value(value: &mut Value, replacement: i64) -> bool {
+    match value {
+        Value::String(text) if text == "$value" => {
+            *value = Value::from(replacement);
+            true
+        }
+        Value::Array(values) => replace_midi_values(values.iter_mut(), replacement),
+        Value::Object(values) => replace_midi_values(values.values_mut(), replacement),
+        _ => false,
+    }
+}
+
+// Every placeholder is replaced, so this can't stop at the first one found
+fn replace_midi_values<'a>(values: impl Iterator<Item = &'a mut Value>, replacement: i64) -> bool {
+    let mut found = false;
+    for value in values {
+        found |= replace_midi_value(value, replacement);
+    }
+    found
+}
+
+/// When a profile rule is applied as a device connects. Where more than one matches, the first
+/// listed here wins.
+#[derive(
+    Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, JsonSchema,
+)]
+pub enum ProfileTrigger {
+    /// The device's firmware is different to the last time it connected
+    FirmwareChanged,
+
+    /// The device hasn't connected yet today (local time)
+    FirstConnectOfDay,
+    OnConnect,
+}
+
+/// Loads a profile and / or mic profile as a device connects, in place of those it would load
+/// otherwise. These aren't made the device's defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ProfileRule {
+    pub trigger: ProfileTrigger,
+    pub profile: Option<String>,
+    pub mic_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum Schedule {
+    /// Runs once, after the duration has passed
+    In(Duration),
+
+    /// Runs every day, at the given local time
+    DailyAt(ScheduleTime),
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleTime {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+/// Lighting which follows the level of the Microphone, fading the zones from the base colour
+/// towards the peak colour as the level rises. Colours are RGB hex strings, as elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ReactiveConfig {
+    pub zones: Vec<ReactiveZone>,
+    pub base_colour: String,
+    pub peak_colour: String,
+
+    /// From 0 to 100, at 0 the peak colour is only reached at 0dB, and at 100 it's reached at
+    /// -60dB. The base colour is shown at 30dB below that.
+    pub sensitivity: u8,
+
+    /// How long (in milliseconds) the lighting takes to fall from the peak colour back to the
+    /// base when the level drops, 0 follows the level immediately.
+    pub decay: u16,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum ReactiveZone {
+    Simple(SimpleColourTargets),
+    Button(Button),
+}
+
+/// Lowers the volume of other channels while a sample button is playing, and puts them back once
+/// it stops.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct DuckConfig {
+    pub channels: Vec<ChannelName>,
+
+    /// How much of the channels' volume is taken away, from 0 to 100 percent
+    pub attenuation: u8,
+
+    /// How long (in milliseconds) the channels take to rise back once playback stops, 0 puts
+    /// them back immediately.
+    pub release: u16,
+}
+
+/// Lights buttons so their state can be told apart by brightness alone, whatever the profile's
+/// colours and off styles. Active buttons show their first colour at the active level, inactive
+/// ones the same colour at the inactive level. The profile itself isn't changed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct HighContrastConfig {
+    pub levels: HighContrastLevels,
+
+    /// Groups of buttons which use their own levels in place of the above
+    #[serde(default)]
+    pub groups: BTreeMap<HighContrastGroup, HighContrastLevels>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct HighContrastLevels {
+    /// The brightness of active buttons, from 0 to 100 percent of their colour
+    pub active: u8,
+
+    /// The brightness of inactive buttons, from 0 to 100 percent of their colour
+    pub inactive: u8,
+}
+
+/// Keeps the Microphone muted on the device until a client unlocks it. It's locked whenever the
+/// device is connected, and when the mode is enabled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PrivacyConfig {
+    /// Lock again after this many seconds without a command or button press
+    pub idle_relock: Option<u32>,
+
+    /// Lock again when the screen is locked (where the platform reports it)
+    pub relock_on_screen_lock: bool,
+}
+
+/// The settings replaced when headset mode was enabled, which are put back when it's disabled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct HeadsetSnapshot {
+    pub mic_type: MicrophoneType,
+    pub jack_gain: u16,
+
+    /// Whether the Mic was routed to the Headphones, and the level it was heard at
+    pub mic_monitor: bool,
+    pub mic_monitor_volume: u8,
+
+    /// The mute function of the fader Chat was on, None if it wasn't on one
+    pub chat_mute_function: Option<MuteFunction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum GoXLRCommand {
     SetShutdownCommands(Vec<GoXLRCommand>),
     SetSleepCommands(Vec<GoXLRCommand>),
@@ -109,9 +989,24 @@ pub enum GoXLRCommand {
     SetFaderMuteFunction(FaderName, MuteFunction),
 
     SetVolume(ChannelName, u8),
+
+    // Volume Scenes
+    SaveVolumeScene(String),
+    LoadVolumeScene(String),
+    DeleteVolumeScene(String),
+
     SetMicrophoneType(MicrophoneType),
+
+    /// Switches the Mic type like SetMicrophoneType, confirming that enabling 48V phantom power
+    /// is intended (when the device requires it to be confirmed).
+    ConfirmMicrophoneType(MicrophoneType),
     SetMicrophoneGain(MicrophoneType, u16),
     SetRouter(InputDevice, OutputDevice, bool),
+    SetInputLowCut(InputDevice, Option<u8>),
+
+    /// Moves an input channel towards the Left (negative) or Right (positive) of every output
+    /// it's routed to, from -50 to 50.
+    SetChannelBalance(ChannelName, i8),
 
     // Cough Button
     SetCoughMuteFunction(MuteFunction),
@@ -125,6 +1020,7 @@ pub enum GoXLRCommand {
     SetEqMiniFreq(MiniEqFrequencies, f32),
     SetEqGain(EqFrequencies, i8),
     SetEqFreq(EqFrequencies, f32),
+    SetHeadphoneEqGain(EqFrequencies, i8),
 
     // Gate Settings
     SetGateThreshold(i8),
@@ -146,6 +1042,14 @@ pub enum GoXLRCommand {
     // DeEss
     SetDeeser(u8),
 
+    // Relative changes to the values above, clamped to their valid range. The resulting value
+    // is returned in an AdjustedValue response.
+    AdjustGateThreshold(i8),
+    AdjustCompressorThreshold(i8),
+    AdjustCompressorMakeupGain(i8),
+    AdjustDeEsser(i8),
+    AdjustBleepVolume(i8),
+
     // Colour Related Settings..
     SetAnimationMode(AnimationMode),
     SetAnimationMod1(u8),
@@ -169,10 +1073,19 @@ pub enum GoXLRCommand {
     SetSampleColour(SamplerColourTargets, String, String, String),
     SetSampleOffStyle(SamplerColourTargets, ButtonColourOffStyle),
 
+    SetReactiveLighting(Option<ReactiveConfig>),
+
+    /// Overrides the buttons' off styles so their state is shown by brightness, None returns to
+    /// the profile's lighting.
+    SetHighContrastMode(Option<HighContrastConfig>),
+
     // Effect Related Settings..
     LoadEffectPreset(String),
     RenameActivePreset(String),
     SaveActivePreset(),
+    LoadEffectSection(EffectSection, String),
+    SaveEffectSection(EffectSection, String),
+    SetEffectsIntensity(u8),
 
     // Reverb
     SetReverbStyle(ReverbStyle),
@@ -209,6 +1122,10 @@ pub enum GoXLRCommand {
     SetGenderStyle(GenderStyle),
     SetGenderAmount(i8),
 
+    /// When enabled, the encoder's value is shared by every effect preset, so it stays where it
+    /// is when the preset changes.
+    SetEncoderGlobalMode(EncoderName, bool),
+
     // Megaphone
     SetMegaphoneStyle(MegaphoneStyle),
     SetMegaphoneAmount(u8),
@@ -235,31 +1152,53 @@ pub enum GoXLRCommand {
     ClearSampleProcessError(),
     SetSamplerFunction(SampleBank, SampleButtons, SamplePlaybackMode),
     SetSamplerOrder(SampleBank, SampleButtons, SamplePlayOrder),
+
+    /// Guarded buttons do nothing (other than flash) while guard mode (see
+    /// DaemonCommand::SetGuardMode) is active.
+    SetSampleButtonGuard(SampleBank, SampleButtons, bool),
+
+    /// Ducks other channels while the button is playing, None stops it ducking.
+    SetSampleDucking(SampleBank, SampleButtons, Option<DuckConfig>),
     AddSample(SampleBank, SampleButtons, String),
     SetSampleStartPercent(SampleBank, SampleButtons, usize, f32),
     SetSampleStopPercent(SampleBank, SampleButtons, usize, f32),
+    SetSampleStartMs(SampleBank, SampleButtons, usize, u64),
+    SetSampleStopMs(SampleBank, SampleButtons, usize, u64),
     RemoveSampleByIndex(SampleBank, SampleButtons, usize),
     PlaySampleByIndex(SampleBank, SampleButtons, usize),
     PlayNextSample(SampleBank, SampleButtons),
     StopSamplePlayback(SampleBank, SampleButtons),
+    NormaliseBank(SampleBank, f32),
+    CancelNormaliseBank(),
 
     // Scribbles
     SetScribbleIcon(FaderName, Option<String>),
+
+    /// Draws the character(s) (such as an emoji) in place of an icon file, empty to remove it
+    SetScribbleGlyph(FaderName, String),
     SetScribbleText(FaderName, String),
     SetScribbleNumber(FaderName, String),
+    SetScribbleNumberMode(FaderName, ScribbleNumberMode),
     SetScribbleInvert(FaderName, bool),
 
     // Profile Handling..
     NewProfile(String),
-    LoadProfile(String, bool),
+    // Name, Persist, Force (discards any unsaved changes to the current profile)
+    LoadProfile(String, bool, #[serde(default)] bool),
     LoadProfileColours(String),
     SaveProfile(),
     SaveProfileAs(String),
     DeleteProfile(String),
+    RenameProfile(String, String),
+    // Base Profile Name, sections which differ from the base are kept by this profile
+    RebaseProfile(String),
+    ClearProfileBase(),
     ReloadSettings(),
 
     NewMicProfile(String),
     LoadMicProfile(String, bool),
+    // Name, Section (the rest of the active mic profile is left alone)
+    LoadMicProfileSection(String, MicProfileSection),
     SaveMicProfile(),
     SaveMicProfileAs(String),
     DeleteMicProfile(String),
@@ -267,10 +1206,40 @@ pub enum GoXLRCommand {
     // General Settings
     SetMuteHoldDuration(u16),
     SetVCMuteAlsoMuteCM(bool),
+
+    /// The outputs the Microphone is removed from while Voice Chat is muted to All, this
+    /// replaces SetVCMuteAlsoMuteCM (which adds or removes the Chat Mic from the list).
+    SetVCMuteAdditionalOutputs(Vec<OutputDevice>),
     SetMonitorWithFx(bool),
     SetSamplerResetOnClear(bool),
+    SetSampleClearTimeout(u16),
     SetLockFaders(bool),
+    SetSilentFaders(bool),
     SetVodMode(VodMode),
+    SetEffectPreviewOnHold(bool),
+    SetClearEffectsOnHold(bool),
+    SetSwitchMonitorOnUnplug(bool),
+    SetSaveOnShutdown(bool),
+    SetAdoptHardwareState(bool),
+    SetPhantomConfirmationRequired(bool),
+    SetFineFaderAdjust(bool),
+    SetPollInterval(u16),
+
+    /// How long (in seconds) the device can go without being read before it's considered
+    /// stalled, and is reset.
+    SetStallTimeout(u16),
+
+    /// Samples added while this is on are converted to 48kHz 16bit WAVs (in the Converted
+    /// directory of the samples) before being analysed, the original file is left untouched.
+    SetSampleConversion(bool),
+    SetPrivacyMode(Option<PrivacyConfig>),
+
+    /// Sets the device up for a headset (a Jack Mic, heard in the Headphones, with Chat muting
+    /// to Voice Chat), or puts back the settings it replaced.
+    SetHeadsetMode(bool),
+
+    /// How loud (as a percentage) the Mic is heard in the Headphones in headset mode.
+    SetHeadsetSidetone(u8),
 
     // These control the current GoXLR 'State'..
     SetActiveEffectPreset(EffectBankPresets),
@@ -281,6 +1250,8 @@ pub enum GoXLRCommand {
     SetFXEnabled(bool),
     SetFaderMuteState(FaderName, MuteState),
     SetCoughMuteState(MuteState),
+    PrivacyLock(),
+    PrivacyUnlock(),
 
     // Submix Commands
     SetSubMixEnabled(bool),
@@ -288,6 +1259,13 @@ pub enum GoXLRCommand {
     SetSubMixLinked(ChannelName, bool),
     SetSubMixOutputMix(OutputDevice, Mix),
 
+    /// Whether a channel starts linked when submixes are enabled on a profile which doesn't say
+    /// either way. Stored for the device, once the profile has its own setting that's used.
+    SetSubMixDefaultLinked(ChannelName, bool),
+
+    /// Links or unlinks every channel at once, linking keeps the volumes where they are.
+    SetSubMixLinkAll(bool),
+
     // Mix Monitoring
     SetMonitorMix(OutputDevice),
 }
@@ -0,0 +1,117 @@
+use serde_json::Value;
+
+/// The revision of the messages sent over the IPC socket and websocket (DaemonRequest,
+/// DaemonResponse and everything inside them). Clients and daemons of different versions can
+/// talk to each other as long as messages only ever grow:
+///
+/// - Requests, responses and their fields may be added, new fields need a serde default
+/// - Tuple variants may gain trailing fields with a serde default, older clients leave them out
+/// - Nothing is renamed, removed, or given a different shape, and names are never reused
+///
+/// Bump this once per release which adds messages. Messages added since the last release go in
+/// the fixture for the current protocol (see the tests below), the fixture for the released
+/// protocol is left as it shipped, so that later changes are checked against both.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The name of a request (or any externally tagged enum), as far as it can be told from its
+/// JSON, for when it can't be parsed.
+pub fn request_name(request: &Value) -> Option<&str> {
+    match request {
+        Value::String(name) => Some(name),
+        Value::Object(map) if map.len() == 1 => map.keys().next().map(String::as_str),
+        _ => None,
+    }
+}
+
+/// The error sent back in place of serde's when a request can't be parsed, most often because
+/// the client is newer than the daemon.
+pub fn unsupported_request(
+    request: &Value,
+    client_protocol: Option<u32>,
+    error: &serde_json::Error,
+) -> String {
+    let name = request_name(request).unwrap_or("(unnamed)");
+    let client = match client_protocol {
+        Some(protocol) => format!("protocol {}", protocol),
+        None => String::from("an unknown protocol"),
+    };
+    format!(
+        "Unsupported request {} for {}, this daemon speaks protocol {} ({})",
+        name, client, PROTOCOL_VERSION, error
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DaemonRequest, DaemonResponse, WebsocketRequest};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    // Protocol 0 is everything the last release (which predates protocol versions) sends, taken
+    // from its web UI and client, protocol 1 is what's been added for the current protocol
+    const FIXTURES: &[(u32, &str)] = &[
+        (0, include_str!("../fixtures/protocol/0.json")),
+        (1, include_str!("../fixtures/protocol/1.json")),
+    ];
+
+    // Whether everything in old is still in new, new may have gained fields (and tuple variants
+    // trailing elements, which old leaves to their defaults)
+    fn contains(new: &Value, old: &Value) -> bool {
+        match (new, old) {
+            (Value::Object(new), Value::Object(old)) => old
+                .iter()
+                .all(|(key, old)| new.get(key).is_some_and(|new| contains(new, old))),
+            (Value::Array(new), Value::Array(old)) => {
+                new.len() >= old.len() && new.iter().zip(old).all(|(new, old)| contains(new, old))
+            }
+            _ => new == old,
+        }
+    }
+
+    fn round_trip<T: DeserializeOwned + Serialize>(protocol: u32, messages: &Value) {
+        for message in messages.as_array().unwrap() {
+            let parsed: T = serde_json::from_value(message.clone()).unwrap_or_else(|e| {
+                panic!("Protocol {}: {} no longer parses: {}", protocol, message, e)
+            });
+            let written = serde_json::to_value(parsed).unwrap();
+            assert!(
+                contains(&written, message),
+                "Protocol {}: {} is now written as {}",
+                protocol,
+                message,
+                written
+            );
+        }
+    }
+
+    #[test]
+    fn earlier_protocols_are_still_understood() {
+        for (protocol, fixture) in FIXTURES {
+            assert!(*protocol <= PROTOCOL_VERSION);
+            let fixture: Value = serde_json::from_str(fixture).unwrap();
+            round_trip::<DaemonRequest>(*protocol, &fixture["requests"]);
+            round_trip::<DaemonResponse>(*protocol, &fixture["responses"]);
+            round_trip::<WebsocketRequest>(*protocol, &fixture["websocket"]);
+        }
+
+        // The current protocol must have been captured
+        assert!(FIXTURES
+            .iter()
+            .any(|(protocol, _)| *protocol == PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn unknown_requests_are_named() {
+        let request = serde_json::json!({ "FromTheFuture": ["S1", 2] });
+        let error = serde_json::from_value::<DaemonRequest>(request.clone()).unwrap_err();
+        let message = unsupported_request(&request, Some(PROTOCOL_VERSION + 1), &error);
+        assert!(message.starts_with(&format!(
+            "Unsupported request FromTheFuture for protocol {}",
+            PROTOCOL_VERSION + 1
+        )));
+
+        assert_eq!(request_name(&Value::from("Ping")), Some("Ping"));
+        assert_eq!(request_name(&serde_json::json!([1, 2])), None);
+    }
+}
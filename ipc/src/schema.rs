@@ -0,0 +1,92 @@
+use std::marker::PhantomData;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject};
+use schemars::{schema_for, JsonSchema};
+
+use crate::DaemonStatus;
+
+/// The revision of the DaemonStatus layout. This must be bumped whenever a change to the IPC
+/// structs (or the goxlr-types enums they contain) alters the generated schema, so that clients
+/// can tell they're talking to a daemon they may not understand.
+pub const STATUS_VERSION: u32 = 74;
+
+/// Generates the JSON Schema for DaemonStatus, patches sent to clients are JSON Pointer paths
+/// into this structure.
+pub fn status_schema() -> RootSchema {
+    schema_for!(DaemonStatus)
+}
+
+/// EnumMap doesn't implement JsonSchema, it serialises as an object keyed by the serialised
+/// names of K, so we describe it that way.
+pub(crate) struct EnumMapSchema<K, V>(PhantomData<(K, V)>);
+
+impl<K: JsonSchema, V: JsonSchema> JsonSchema for EnumMapSchema<K, V> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("Map_of_{}_to_{}", K::schema_name(), V::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                property_names: Some(Box::new(gen.subschema_for::<K>())),
+                additional_properties: Some(Box::new(gen.subschema_for::<V>())),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+
+    // The schema as of the STATUS_VERSION it was captured at
+    const FIXTURE: &str = include_str!("../fixtures/status_schema.json");
+
+    // Set to rewrite the fixture after a bump, this refuses to capture a changed schema under the
+    // version the previous one was captured at
+    const UPDATE: &str = "GOXLR_UPDATE_STATUS_SCHEMA";
+
+    #[test]
+    fn schema_changes_bump_the_version() {
+        let fixture: Value = serde_json::from_str(FIXTURE).unwrap();
+        let captured = fixture["version"].as_u64().unwrap() as u32;
+        let schema = serde_json::to_value(status_schema()).unwrap();
+        let changed = schema != fixture["schema"];
+
+        if std::env::var_os(UPDATE).is_some() {
+            assert!(
+                !changed || captured != STATUS_VERSION,
+                "The status schema has changed, bump STATUS_VERSION before capturing it"
+            );
+            let path =
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/status_schema.json");
+            let fixture = json!({ "version": STATUS_VERSION, "schema": schema });
+            let mut content = serde_json::to_string_pretty(&fixture).unwrap();
+            content.push('\n');
+            std::fs::write(path, content).unwrap();
+            return;
+        }
+
+        assert!(
+            !changed,
+            "The status schema has changed, bump STATUS_VERSION (currently {}) and re-run this \
+             test with {} set to capture it",
+            STATUS_VERSION, UPDATE
+        );
+        assert_eq!(
+            captured, STATUS_VERSION,
+            "STATUS_VERSION changed without the schema, re-run this test with {} set",
+            UPDATE
+        );
+    }
+}
@@ -14,24 +14,51 @@ use which::which;
 
 static SOCKET_PATH: &str = "/tmp/goxlr.socket";
 static NAMED_PIPE: &str = "@goxlr.socket";
+static SOCKET_PATH_ENV: &str = "GOXLR_SOCKET_PATH";
 static DAEMON_NAME: &str = "goxlr-daemon";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // First thing to do, is check to see if the Daemon is running..
-    if !is_daemon_running() {
-        launch_daemon()?;
+    let socket_path = get_socket_path_override();
+
+    // First thing to do, is check to see if the Daemon is running.. If we've been pointed at a
+    // specific socket, another daemon may well be running, so check the socket instead.
+    let running = match &socket_path {
+        Some(path) => get_connection(Some(path)).await.is_ok(),
+        None => is_daemon_running(),
+    };
+
+    if !running {
+        launch_daemon(socket_path.as_deref())?;
     }
 
-    open_ui().await?;
+    open_ui(socket_path.as_deref()).await?;
     Ok(())
 }
 
-async fn get_connection() -> Result<LocalSocketStream> {
+/// Either '--socket-path <path>' or the GOXLR_SOCKET_PATH environment variable.
+fn get_socket_path_override() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--socket-path" {
+            return args.next();
+        }
+        if let Some(path) = arg.strip_prefix("--socket-path=") {
+            return Some(path.to_string());
+        }
+    }
+    std::env::var(SOCKET_PATH_ENV).ok()
+}
+
+async fn get_connection(socket_path: Option<&str>) -> Result<LocalSocketStream> {
     let path = if cfg!(windows) {
-        NAMED_PIPE.to_ns_name::<GenericNamespaced>()
+        socket_path
+            .unwrap_or(NAMED_PIPE)
+            .to_ns_name::<GenericNamespaced>()
     } else {
-        SOCKET_PATH.to_fs_name::<GenericFilePath>()
+        socket_path
+            .unwrap_or(SOCKET_PATH)
+            .to_fs_name::<GenericFilePath>()
     };
 
     let path = match path {
@@ -47,7 +74,7 @@ async fn get_connection() -> Result<LocalSocketStream> {
 }
 
 #[cfg(unix)]
-fn launch_daemon() -> Result<()> {
+fn launch_daemon(socket_path: Option<&str>) -> Result<()> {
     use nix::unistd::execve;
     use std::env;
     use std::ffi::CString;
@@ -59,7 +86,11 @@ fn launch_daemon() -> Result<()> {
         let c_start_ui = CString::new("--start-ui")?;
 
         // TO-CONSIDER: Pass all env::args() through to the daemon?
-        let c_params = vec![c_daemon, c_start_ui];
+        let mut c_params = vec![c_daemon, c_start_ui];
+        if let Some(socket_path) = socket_path {
+            c_params.push(CString::new("--socket-path")?);
+            c_params.push(CString::new(socket_path)?);
+        }
 
         // Copy all environment variables for this into the new process..
         let mut c_env = vec![];
@@ -86,13 +117,16 @@ fn is_daemon_running() -> bool {
 }
 
 #[cfg(windows)]
-fn launch_daemon() -> Result<()> {
+fn launch_daemon(socket_path: Option<&str>) -> Result<()> {
     use std::process::{exit, Command, Stdio};
 
     // Ok, try a simple spawn and exit..
     if let Some(path) = locate_daemon_binary() {
         let mut command = Command::new(&path);
         command.arg("--start-ui");
+        if let Some(socket_path) = socket_path {
+            command.arg("--socket-path").arg(socket_path);
+        }
         command.stdin(Stdio::null());
         command.stdout(Stdio::null());
         command.stderr(Stdio::null());
@@ -108,11 +142,11 @@ fn launch_daemon() -> Result<()> {
     bail!("Unable to Locate GoXLR Daemon Binary");
 }
 
-async fn open_ui() -> Result<()> {
+async fn open_ui(socket_path: Option<&str>) -> Result<()> {
     // We kinda have to hope for the best here..
     let mut usable_connection = None;
 
-    if let Ok(connection) = get_connection().await {
+    if let Ok(connection) = get_connection(socket_path).await {
         usable_connection.replace(connection);
     }
 
@@ -1,4 +1,5 @@
 pub mod animation;
+pub mod base_profile;
 pub mod browser;
 pub mod colours;
 pub mod context;
@@ -20,3 +21,4 @@ pub mod sample;
 pub mod scribble;
 pub mod simple;
 pub mod submix;
+pub mod volume_scenes;
@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use log::warn;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 use strum::{EnumIter, IntoEnumIterator};
@@ -62,7 +62,7 @@ impl AnimationTree {
 
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("animationMode".to_string(), format!("{}", self.mode as u8));
         attributes.insert("mod1".to_string(), format!("{}", self.mod1));
         attributes.insert("mod2".to_string(), format!("{}", self.mod2));
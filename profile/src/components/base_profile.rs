@@ -0,0 +1,82 @@
+use crate::profile::Attribute;
+use crate::BaseSection;
+use anyhow::{bail, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Writer;
+use std::io::Write;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/**
+ * The base profile is a utility addition, it names another profile which this one is a variant
+ * of, and lists the sections this profile has its own copy of. Everything else is taken from the
+ * base (and whatever it's based on) each time the profile is loaded.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseProfile {
+    name: String,
+    owned: Vec<BaseSection>,
+}
+
+impl BaseProfile {
+    pub fn new(name: String, owned: Vec<BaseSection>) -> Self {
+        Self { name, owned }
+    }
+
+    pub fn parse_base(attributes: &Vec<Attribute>) -> Result<Self> {
+        let mut base = BaseProfile::new(String::new(), vec![]);
+
+        for attr in attributes {
+            if attr.name == "name" {
+                base.name = attr.value.clone();
+                continue;
+            }
+
+            if attr.name == "sections" {
+                for section in attr.value.split(',').filter(|value| !value.is_empty()) {
+                    match BaseSection::from_str(section) {
+                        Ok(section) => base.set_owned(section, true),
+                        Err(_) => bail!("Unknown Base Profile Section: {}", section),
+                    }
+                }
+                continue;
+            }
+
+            println!("Unknown Base Profile Attribute: {}", attr.name);
+        }
+
+        if base.name.is_empty() {
+            bail!("Base Profile is missing a name");
+        }
+        Ok(base)
+    }
+
+    pub fn write_base<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        let sections: Vec<String> = self.owned.iter().map(|s| s.to_string()).collect();
+
+        let mut elem = BytesStart::new("baseProfile");
+        elem.push_attribute(("name", self.name.as_str()));
+        elem.push_attribute(("sections", sections.join(",").as_str()));
+        writer.write_event(Event::Empty(elem))?;
+        Ok(())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn owns(&self, section: BaseSection) -> bool {
+        self.owned.contains(&section)
+    }
+
+    pub fn set_owned(&mut self, section: BaseSection, owned: bool) {
+        self.owned.retain(|existing| *existing != section);
+        if owned {
+            self.owned.push(section);
+        }
+
+        // Keep a consistent order, so the file doesn't change when nothing else has
+        let owned = &self.owned;
+        self.owned = BaseSection::iter().filter(|s| owned.contains(s)).collect();
+    }
+}
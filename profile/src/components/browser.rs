@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use anyhow::Result;
@@ -86,7 +86,7 @@ impl BrowserPreviewTree {
     pub fn write_browser<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("playing".to_string(), format!("{}", self.playing));
         attributes.insert("playToggle".to_string(), format!("{}", self.play_toggle));
         attributes.insert("file".to_string(), self.file.clone());
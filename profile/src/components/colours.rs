@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 #[derive(thiserror::Error, Debug)]
@@ -19,6 +19,9 @@ pub enum ParseError {
 
     #[error("[COLOURS] Expected Length: 8 (RRGGBB), Got: {0}")]
     InvalidRGBLength(String),
+
+    #[error("[COLOURS] Colour index out of range: {0}")]
+    InvalidColourIndex(String),
 }
 use crate::components::colours::ColourDisplay::{Gradient, GradientMeter, Meter};
 use crate::profile::Attribute;
@@ -123,7 +126,8 @@ impl ColourMap {
                 default
             });
 
-            // TODO: Tidy this monster up..
+            // The colours can appear in any order (or not at all), so they're placed by index
+            // rather than pushed, but the index still needs to fit.
             if let Some(index) = attribute
                 .name
                 .chars()
@@ -131,7 +135,10 @@ impl ColourMap {
                 .map(|s| usize::from_str(&s.to_string()))
                 .transpose()?
             {
-                color_list[index] = Some(Colour::new(&attribute.value)?);
+                match color_list.get_mut(index) {
+                    Some(colour) => *colour = Some(Colour::new(&attribute.value)?),
+                    None => return Err(ParseError::InvalidColourIndex(attribute.name.clone())),
+                }
             }
 
             return Ok(true);
@@ -154,14 +161,14 @@ impl ColourMap {
         self.colour_display = colour_map.colour_display;
     }
 
-    pub fn write_colours(&self, attributes: &mut HashMap<String, String>) {
+    pub fn write_colours(&self, attributes: &mut BTreeMap<String, String>) {
         self.write_colours_with_prefix(self.prefix.clone(), attributes)
     }
 
     pub fn write_colours_with_prefix(
         &self,
         prefix: String,
-        attributes: &mut HashMap<String, String>,
+        attributes: &mut BTreeMap<String, String>,
     ) {
         // Add the 'OffStyle'
         let mut key = format!("{prefix}offStyle");
@@ -219,7 +226,7 @@ impl ColourMap {
 
     pub fn colour_or_default(&self, index: u8) -> &Colour {
         if let Some(colour_list) = &self.colour_list {
-            if let Some(colour) = &colour_list[index as usize] {
+            if let Some(Some(colour)) = colour_list.get(index as usize) {
                 return colour;
             }
         }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use strum::EnumProperty;
@@ -106,7 +106,7 @@ impl Context {
     pub fn write_context<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("numselected".to_string(), format!("{}", self.selected));
 
         if let Some(selected_id) = self.selected_id {
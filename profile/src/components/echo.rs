@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 
@@ -40,6 +40,9 @@ pub struct EchoEncoderBase {
     colour_map: ColourMap,
     preset_map: EnumMap<Preset, EchoEncoder>,
     active_set: u8, // Not sure what this does?
+
+    // Utility only, the knob's value is shared between all presets
+    global_knob: bool,
 }
 
 impl EchoEncoderBase {
@@ -54,6 +57,7 @@ impl EchoEncoderBase {
             colour_map,
             preset_map: EnumMap::default(),
             active_set: 0,
+            global_knob: false,
         }
     }
 
@@ -64,6 +68,11 @@ impl EchoEncoderBase {
                 continue;
             }
 
+            if attr.name == "globalKnob" {
+                self.global_knob = attr.value == "true";
+                continue;
+            }
+
             if !self.colour_map.read_colours(attr)? {
                 println!("[EchoEncoder] Unparsed Attribute: {}", attr.name);
             }
@@ -153,8 +162,11 @@ impl EchoEncoderBase {
     pub fn write_echo<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("echoEncoder");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("active_set".to_string(), format!("{}", self.active_set));
+        if self.global_knob {
+            attributes.insert("globalKnob".to_string(), "true".to_string());
+        }
         self.colour_map.write_colours(&mut attributes);
 
         // Write out the attributes etc for this element, but don't close it yet..
@@ -182,8 +194,8 @@ impl EchoEncoderBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
@@ -234,6 +246,14 @@ impl EchoEncoderBase {
     pub fn get_preset_mut(&mut self, preset: Preset) -> &mut EchoEncoder {
         &mut self.preset_map[preset]
     }
+
+    pub fn is_global_knob(&self) -> bool {
+        self.global_knob
+    }
+
+    pub fn set_global_knob(&mut self, global_knob: bool) {
+        self.global_knob = global_knob;
+    }
 }
 
 #[derive(Debug, Default)]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use anyhow::{anyhow, Result};
@@ -33,6 +33,19 @@ pub struct Effects {
 
     // This is represented only in the UI.
     name: String,
+
+    // A utility addition, only present while the intensity is below 100.
+    intensity: Option<EffectsIntensity>,
+}
+
+/// How strongly the Reverb, Echo and Pitch amounts are applied, and the values they're scaled
+/// from (captured when the intensity was last at 100). These are stored as knob positions.
+#[derive(Debug, Copy, Clone)]
+pub struct EffectsIntensity {
+    pub intensity: u8,
+    pub reverb: i8,
+    pub echo: i8,
+    pub pitch: i8,
 }
 
 impl Effects {
@@ -56,33 +69,73 @@ impl Effects {
             element_name,
             colour_map,
             name: default_name,
+            intensity: None,
         }
     }
 
     pub fn parse_effect(&mut self, attributes: &Vec<Attribute>) -> Result<(), ParseError> {
+        let mut intensity = None;
+        let mut baselines: [Option<i8>; 3] = [None; 3];
+
         for attr in attributes {
             if attr.name.ends_with("Name") {
                 self.name.clone_from(&attr.value);
                 continue;
             }
 
+            if attr.name.ends_with("Intensity") {
+                intensity = Some(attr.value.parse()?);
+                continue;
+            }
+
+            let baseline = ["ReverbBaseline", "EchoBaseline", "PitchBaseline"]
+                .iter()
+                .position(|suffix| attr.name.ends_with(suffix));
+            if let Some(index) = baseline {
+                baselines[index] = Some(attr.value.parse()?);
+                continue;
+            }
+
             // Send the rest out for colouring..
             if !self.colour_map.read_colours(attr)? {
                 println!("[EFFECTS] Unparsed Attribute: {}", attr.name);
             }
         }
 
+        // If any part is missing, the amounts are simply treated as being at full intensity
+        if let (Some(intensity), [Some(reverb), Some(echo), Some(pitch)]) = (intensity, baselines) {
+            self.intensity = Some(EffectsIntensity {
+                intensity,
+                reverb,
+                echo,
+                pitch,
+            });
+        }
+
         Ok(())
     }
 
     pub fn write_effects<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(format!("{}Name", self.element_name), self.name.clone());
 
         self.colour_map.write_colours(&mut attributes);
 
+        if let Some(intensity) = &self.intensity {
+            let element = &self.element_name;
+            let values = [
+                ("Intensity", intensity.intensity.to_string()),
+                ("ReverbBaseline", intensity.reverb.to_string()),
+                ("EchoBaseline", intensity.echo.to_string()),
+                ("PitchBaseline", intensity.pitch.to_string()),
+            ];
+            for (suffix, value) in values {
+                attributes.insert(format!("{}{}", element, suffix), value);
+            }
+        }
+
         for (key, value) in &attributes {
             elem.push_attribute((key.as_str(), value.as_str()));
         }
@@ -101,6 +154,13 @@ impl Effects {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn intensity(&self) -> Option<EffectsIntensity> {
+        self.intensity
+    }
+    pub fn set_intensity(&mut self, intensity: Option<EffectsIntensity>) {
+        self.intensity = intensity;
+    }
     pub fn set_name(&mut self, name: String) -> Result<()> {
         // This is an artificial limit by me here..
         if name.len() > 32 {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use strum::{EnumProperty, IntoEnumIterator};
@@ -93,7 +93,7 @@ impl Fader {
         let element_name = fader.get_str("faderContext").unwrap();
         let mut elem = BytesStart::new(element_name);
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(
             format!("{element_name}listIndex"),
             self.channel.get_str("faderIndex").unwrap().to_string(),
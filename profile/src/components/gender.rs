@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 
@@ -39,6 +39,9 @@ pub struct GenderEncoderBase {
     colour_map: ColourMap,
     preset_map: EnumMap<Preset, GenderEncoder>,
     active_set: u8, // Not sure what this does?
+
+    // Utility only, the knob's value is shared between all presets
+    global_knob: bool,
 }
 
 impl GenderEncoderBase {
@@ -53,6 +56,7 @@ impl GenderEncoderBase {
             colour_map,
             preset_map: EnumMap::default(),
             active_set: 0,
+            global_knob: false,
         }
     }
 
@@ -63,6 +67,11 @@ impl GenderEncoderBase {
                 continue;
             }
 
+            if attr.name == "globalKnob" {
+                self.global_knob = attr.value == "true";
+                continue;
+            }
+
             if !self.colour_map.read_colours(attr)? {
                 println!("[GenderEncoder] Unparsed Attribute: {}", attr.name);
             }
@@ -108,8 +117,11 @@ impl GenderEncoderBase {
     pub fn write_gender<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("genderEncoder");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("active_set".to_string(), format!("{}", self.active_set));
+        if self.global_knob {
+            attributes.insert("globalKnob".to_string(), "true".to_string());
+        }
         self.colour_map.write_colours(&mut attributes);
 
         // Write out the attributes etc for this element, but don't close it yet..
@@ -137,8 +149,8 @@ impl GenderEncoderBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
@@ -169,6 +181,14 @@ impl GenderEncoderBase {
     pub fn get_preset_mut(&mut self, preset: Preset) -> &mut GenderEncoder {
         &mut self.preset_map[preset]
     }
+
+    pub fn is_global_knob(&self) -> bool {
+        self.global_knob
+    }
+
+    pub fn set_global_knob(&mut self, global_knob: bool) {
+        self.global_knob = global_knob;
+    }
 }
 
 #[derive(Debug, Default)]
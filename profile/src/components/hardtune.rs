@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 use std::str::FromStr;
@@ -137,7 +137,7 @@ impl HardtuneEffectBase {
     pub fn write_hardtune<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("hardtuneEffect");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("HARDTUNE_SOURCE".to_string(), self.source.to_string());
         self.colour_map.write_colours(&mut attributes);
 
@@ -166,8 +166,8 @@ impl HardtuneEffectBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 
@@ -160,7 +160,7 @@ impl MegaphoneEffectBase {
     pub fn write_megaphone<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("megaphoneEffect");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         self.colour_map.write_colours(&mut attributes);
 
         // Write out the attributes etc for this element, but don't close it yet..
@@ -188,8 +188,8 @@ impl MegaphoneEffectBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
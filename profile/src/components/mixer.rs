@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use enum_map::{Enum, EnumMap};
@@ -30,11 +30,26 @@ pub enum ParseError {
 
 type RoutingTable = EnumMap<InputChannels, EnumMap<OutputChannels, u16>>;
 
+/// The bands of the Headphone EQ, as they're named in the profile.
+pub const HEADPHONE_EQ_BANDS: [&str; 10] = [
+    "31Hz", "63Hz", "125Hz", "250Hz", "500Hz", "1KHz", "2KHz", "4KHz", "8KHz", "16KHz",
+];
+
 #[derive(Debug)]
 pub struct Mixers {
     mixer_table: RoutingTable,
     volume_table: EnumMap<FullChannelList, u8>,
     colour_map: ColourMap,
+
+    // Not part of the official profile, a Low Cut frequency (in Hz) for an input
+    low_cut: EnumMap<InputChannels, Option<u8>>,
+
+    // Not part of the official profile, the Left / Right balance of an input (-50 to 50)
+    balance: EnumMap<InputChannels, Option<i8>>,
+
+    // Not part of the official profile, the gain of each band of the Headphone EQ, in the order
+    // of HEADPHONE_EQ_BANDS
+    headphone_eq: [Option<i8>; 10],
 }
 
 impl Default for Mixers {
@@ -89,11 +104,42 @@ impl Mixers {
             mixer_table,
             volume_table,
             colour_map: ColourMap::new("mixerTree".to_string()),
+            low_cut: EnumMap::default(),
+            balance: EnumMap::default(),
+            headphone_eq: [None; 10],
         }
     }
 
     pub fn parse_mixers(&mut self, attributes: &Vec<Attribute>) -> Result<(), ParseError> {
         for attr in attributes {
+            let band = attr.name.strip_prefix("headphones");
+            if let Some(band) = band.and_then(|band| band.strip_suffix("EqGain")) {
+                let value: i8 = attr.value.parse()?;
+                match HEADPHONE_EQ_BANDS.iter().position(|name| *name == band) {
+                    Some(index) => self.headphone_eq[index] = Some(value),
+                    None => println!("Unable to find EQ Band: {band}"),
+                }
+                continue;
+            }
+
+            if let Some(channel) = attr.name.strip_suffix("LowCut") {
+                let value: u8 = attr.value.parse()?;
+                match InputChannels::iter().find(|input| input.get_str("Name") == Some(channel)) {
+                    Some(input) => self.low_cut[input] = Some(value),
+                    None => println!("Unable to find Channel: {channel}"),
+                }
+                continue;
+            }
+
+            if let Some(channel) = attr.name.strip_suffix("Balance") {
+                let value: i8 = attr.value.parse()?;
+                match InputChannels::iter().find(|input| input.get_str("Name") == Some(channel)) {
+                    Some(input) => self.balance[input] = Some(value),
+                    None => println!("Unable to find Channel: {channel}"),
+                }
+                continue;
+            }
+
             if attr.name.ends_with("Level") {
                 let mut found = false;
 
@@ -159,7 +205,7 @@ impl Mixers {
         let mut elem = BytesStart::new("mixerTree");
 
         // Create the values..
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         for volume in FullChannelList::iter() {
             let key = format!("{}Level", volume.get_str("Name").unwrap());
             let value = format!("{}", self.volume_table[volume]);
@@ -180,6 +226,23 @@ impl Mixers {
             }
         }
 
+        // Only written when set, so the official app never sees them
+        for input in InputChannels::iter() {
+            if let Some(frequency) = self.low_cut[input] {
+                let key = format!("{}LowCut", input.get_str("Name").unwrap());
+                attributes.insert(key, format!("{frequency}"));
+            }
+            if let Some(balance) = self.balance[input] {
+                let key = format!("{}Balance", input.get_str("Name").unwrap());
+                attributes.insert(key, format!("{balance}"));
+            }
+        }
+        for (band, gain) in HEADPHONE_EQ_BANDS.iter().zip(self.headphone_eq) {
+            if let Some(gain) = gain {
+                attributes.insert(format!("headphones{band}EqGain"), format!("{gain}"));
+            }
+        }
+
         self.colour_map.write_colours(&mut attributes);
 
         // Set the attributes into the XML object..
@@ -208,6 +271,31 @@ impl Mixers {
         self.volume_table[channel] = volume;
         Ok(())
     }
+
+    pub fn low_cut(&self, channel: InputChannels) -> Option<u8> {
+        self.low_cut[channel]
+    }
+
+    pub fn set_low_cut(&mut self, channel: InputChannels, frequency: Option<u8>) {
+        self.low_cut[channel] = frequency;
+    }
+
+    pub fn balance(&self, channel: InputChannels) -> Option<i8> {
+        self.balance[channel]
+    }
+
+    pub fn set_balance(&mut self, channel: InputChannels, balance: Option<i8>) {
+        self.balance[channel] = balance;
+    }
+
+    /// The gain of a band of the Headphone EQ, indexed as HEADPHONE_EQ_BANDS.
+    pub fn headphone_eq_gain(&self, band: usize) -> Option<i8> {
+        self.headphone_eq[band]
+    }
+
+    pub fn set_headphone_eq_gain(&mut self, band: usize, gain: Option<i8>) {
+        self.headphone_eq[band] = gain;
+    }
 }
 
 #[derive(Debug, EnumIter, Enum, EnumProperty, Clone, Copy)]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use enum_map_derive::Enum;
@@ -119,7 +119,7 @@ impl MuteButton {
         let element_name = fader.get_str("muteContext").unwrap();
         let mut elem = BytesStart::new(element_name);
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         let mute_value = if self.mute_function == MuteFunction::ToVoiceChat {
             String::from("Mute to Chat Mic")
         } else {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use enum_map::Enum;
@@ -24,6 +24,9 @@ pub enum ParseError {
 
     #[error("[COUGH] Invalid colours: {0}")]
     InvalidColours(#[from] crate::components::colours::ParseError),
+
+    #[error("[COUGH] Invalid value for {0}: {1}")]
+    InvalidValue(String, String),
 }
 use crate::profile::Attribute;
 use quick_xml::events::{BytesStart, Event};
@@ -86,7 +89,14 @@ impl MuteChat {
             }
 
             if attr.name == "coughButtonMuteSourceSelection" {
-                self.cough_mute_source = MuteFunction::from_usize(attr.value.parse()?);
+                let value: usize = attr.value.parse()?;
+                if value >= MuteFunction::LENGTH {
+                    return Err(ParseError::InvalidValue(
+                        attr.name.clone(),
+                        attr.value.clone(),
+                    ));
+                }
+                self.cough_mute_source = MuteFunction::from_usize(value);
                 continue;
             }
 
@@ -111,7 +121,7 @@ impl MuteChat {
     pub fn write_mute_chat<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
 
         attributes.insert(
             "micIsAnActiveFader".to_string(),
@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
+use std::ops::RangeInclusive;
 use std::os::raw::c_float;
 
 use enum_map::{Enum, EnumMap};
@@ -39,6 +40,9 @@ pub struct PitchEncoderBase {
     colour_map: ColourMap,
     preset_map: EnumMap<Preset, PitchEncoder>,
     active_set: u8, // Not sure what this does?
+
+    // Utility only, the knob's value is shared between all presets
+    global_knob: bool,
 }
 
 impl PitchEncoderBase {
@@ -53,6 +57,7 @@ impl PitchEncoderBase {
             colour_map,
             preset_map: EnumMap::default(),
             active_set: 0,
+            global_knob: false,
         }
     }
 
@@ -63,6 +68,11 @@ impl PitchEncoderBase {
                 continue;
             }
 
+            if attr.name == "globalKnob" {
+                self.global_knob = attr.value == "true";
+                continue;
+            }
+
             if !self.colour_map.read_colours(attr)? {
                 println!("[PitchEncoder] Unparsed Attribute: {}", attr.name);
             }
@@ -125,8 +135,11 @@ impl PitchEncoderBase {
     pub fn write_pitch<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("pitchEncoder");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("active_set".to_string(), format!("{}", self.active_set));
+        if self.global_knob {
+            attributes.insert("globalKnob".to_string(), "true".to_string());
+        }
         self.colour_map.write_colours(&mut attributes);
 
         // Write out the attributes etc for this element, but don't close it yet..
@@ -154,8 +167,8 @@ impl PitchEncoderBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
@@ -197,6 +210,14 @@ impl PitchEncoderBase {
     pub fn get_preset_mut(&mut self, preset: Preset) -> &mut PitchEncoder {
         &mut self.preset_map[preset]
     }
+
+    pub fn is_global_knob(&self) -> bool {
+        self.global_knob
+    }
+
+    pub fn set_global_knob(&mut self, global_knob: bool) {
+        self.global_knob = global_knob;
+    }
 }
 
 #[derive(Debug, Default)]
@@ -273,6 +294,15 @@ impl PitchEncoder {
         Ok(())
     }
 
+    /// The values set_knob_position will accept in the current style.
+    pub fn knob_range(&self, hardtune_enabled: bool) -> RangeInclusive<i8> {
+        match (hardtune_enabled, self.style) {
+            (true, PitchStyle::Narrow) => -1..=1,
+            (true, PitchStyle::Wide) => -2..=2,
+            (false, _) => -24..=24,
+        }
+    }
+
     // We pass in an encoder value, do any needed rounding, then return (currently only applicable
     // for PitchStyle::Narrow with hardtune disabled..
     pub fn calculate_encoder_value(&self, value: i8, hardtune_enabled: bool) -> i8 {
@@ -300,6 +330,11 @@ impl PitchEncoder {
         self.knob_position
     }
 
+    // Sets the stored value directly, used to put back a value previously read from here.
+    pub fn set_pitch_value(&mut self, value: i8) {
+        self.knob_position = value;
+    }
+
     pub fn style(&self) -> &PitchStyle {
         &self.style
     }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::os::raw::c_float;
 
@@ -40,6 +40,9 @@ pub struct ReverbEncoderBase {
     colour_map: ColourMap,
     preset_map: EnumMap<Preset, ReverbEncoder>,
     active_set: u8, // Not sure what this does?
+
+    // Utility only, the knob's value is shared between all presets
+    global_knob: bool,
 }
 
 impl ReverbEncoderBase {
@@ -54,6 +57,7 @@ impl ReverbEncoderBase {
             colour_map,
             preset_map: EnumMap::default(),
             active_set: 0,
+            global_knob: false,
         }
     }
 
@@ -64,6 +68,11 @@ impl ReverbEncoderBase {
                 continue;
             }
 
+            if attr.name == "globalKnob" {
+                self.global_knob = attr.value == "true";
+                continue;
+            }
+
             if !self.colour_map.read_colours(attr)? {
                 println!("[ReverbEncoder] Unparsed Attribute: {}", attr.name);
             }
@@ -162,8 +171,11 @@ impl ReverbEncoderBase {
     pub fn write_reverb<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("reverbEncoder");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("active_set".to_string(), format!("{}", self.active_set));
+        if self.global_knob {
+            attributes.insert("globalKnob".to_string(), "true".to_string());
+        }
         self.colour_map.write_colours(&mut attributes);
 
         // Write out the attributes etc for this element, but don't close it yet..
@@ -191,8 +203,8 @@ impl ReverbEncoderBase {
         Ok(())
     }
 
-    pub fn get_preset_attributes(&self, preset: Preset) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
+    pub fn get_preset_attributes(&self, preset: Preset) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
         let value = &self.preset_map[preset];
 
         attributes.insert(
@@ -256,6 +268,14 @@ impl ReverbEncoderBase {
     pub fn get_preset_mut(&mut self, preset: Preset) -> &mut ReverbEncoder {
         &mut self.preset_map[preset]
     }
+
+    pub fn is_global_knob(&self) -> bool {
+        self.global_knob
+    }
+
+    pub fn set_global_knob(&mut self, global_knob: bool) {
+        self.global_knob = global_knob;
+    }
 }
 
 #[derive(Debug, Default)]
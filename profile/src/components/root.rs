@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::ffi::c_float;
 use std::io::Write;
 
@@ -27,12 +27,16 @@ pub enum ParseError {
 /**
  * These have no special properties, they are literally just button colours..
  */
+// The version of the utility writing the profile, the official app doesn't know about this.
+const UTILITY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[derive(Debug)]
 pub struct RootElement {
     // Ok.
     version: u8,
     loudness: u8,
     device: u64,
+    utility_version: Option<String>,
 }
 
 impl Default for RootElement {
@@ -47,6 +51,7 @@ impl RootElement {
             version: 0,
             loudness: 0,
             device: 0,
+            utility_version: None,
         }
     }
 
@@ -64,6 +69,11 @@ impl RootElement {
 
             if attr.name == "device" {
                 self.device = attr.value.parse()?;
+                continue;
+            }
+
+            if attr.name == "utilityVersion" {
+                self.utility_version = Some(attr.value.clone());
             }
         }
 
@@ -74,10 +84,11 @@ impl RootElement {
         let mut elem = BytesStart::new("ValueTreeRoot");
 
         // Create the hashmap of values..
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("version".to_string(), "2".to_string());
         attributes.insert("loudness".to_string(), format!("{}", self.loudness));
         attributes.insert("device".to_string(), format!("{}", self.device));
+        attributes.insert("utilityVersion".to_string(), UTILITY_VERSION.to_string());
 
         for (key, value) in &attributes {
             elem.push_attribute((key.as_str(), value.as_str()));
@@ -91,7 +102,7 @@ impl RootElement {
     pub fn write_final<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("AppTree");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("ConnectedDeviceID".to_string(), format!("{}", &self.device));
         for (key, value) in &attributes {
             elem.push_attribute((key.as_str(), value.as_str()));
@@ -105,4 +116,25 @@ impl RootElement {
     pub fn get_version(&self) -> u8 {
         self.version
     }
+
+    pub fn get_utility_version(&self) -> Option<&str> {
+        self.utility_version.as_deref()
+    }
+
+    /// Returns true if the profile was last saved by a newer version of the utility.
+    pub fn is_from_newer_version(&self) -> bool {
+        match &self.utility_version {
+            Some(version) => parse_version(version) > parse_version(UTILITY_VERSION),
+            None => false,
+        }
+    }
+}
+
+// Versions are compared numerically per component, anything after a '-' (eg. -beta) is ignored.
+fn parse_version(version: &str) -> Vec<u32> {
+    let version = version.split('-').next().unwrap_or_default();
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or_default())
+        .collect()
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use std::str::FromStr;
 
@@ -8,9 +8,10 @@ use enum_map::{Enum, EnumMap};
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
 use ritelinked::LinkedHashMap;
-use strum::{Display, EnumIter, EnumProperty, EnumString};
+use strum::{Display, EnumIter, EnumProperty, EnumString, IntoEnumIterator};
 
 use crate::components::colours::{Colour, ColourMap, ColourOffStyle};
+use crate::components::mixer::FullChannelList;
 use crate::components::sample::PlayOrder::{Random, Sequential};
 use crate::profile::Attribute;
 use crate::SampleButtons;
@@ -101,11 +102,35 @@ impl SampleBase {
 
         // Pull out any 'extra' attributes which may be useful..
         if let Some(value) = map.get("playbackMode") {
-            sample_stack.playback_mode = Some(PlaybackMode::from_usize(value.parse::<usize>()?));
+            let mode = value.parse::<usize>()?;
+            if mode >= PlaybackMode::LENGTH {
+                bail!("Invalid playbackMode in sampleStack{}: {}", id, value);
+            }
+            sample_stack.playback_mode = Some(PlaybackMode::from_usize(mode));
         }
 
         if let Some(value) = map.get("playOrder") {
-            sample_stack.play_order = Some(PlayOrder::from_usize(value.parse::<usize>()?));
+            let order = value.parse::<usize>()?;
+            if order >= PlayOrder::LENGTH {
+                bail!("Invalid playOrder in sampleStack{}: {}", id, value);
+            }
+            sample_stack.play_order = Some(PlayOrder::from_usize(order));
+        }
+
+        if let Some(value) = map.get("guarded") {
+            sample_stack.guarded = value.as_str() != "0";
+        }
+
+        if let (Some(channels), Some(attenuation), Some(release)) = (
+            map.get("duckChannels"),
+            map.get("duckAttenuation"),
+            map.get("duckRelease"),
+        ) {
+            sample_stack.ducking = Some(SampleDucking {
+                channels: SampleDucking::parse_channels(channels)?,
+                attenuation: attenuation.parse()?,
+                release: release.parse()?,
+            });
         }
 
         // Ok, somewhere in here we should have a key that tells us how many tracks are configured..
@@ -139,7 +164,22 @@ impl SampleBase {
                         end = start;
                     }
 
-                    let track = Track::new(track.to_string(), start, end, gain.parse()?);
+                    let mut track = Track::new(track.to_string(), start, end, gain.parse()?);
+                    if let Some(markers) = map.get(&format!("track_{i}Markers")) {
+                        track.markers = TrackMarker::parse_list(markers);
+                    }
+                    if let Some(peak) = map.get(&format!("track_{i}RecordingPeak")) {
+                        track.recording_peak = Some(peak.parse()?);
+                    }
+                    if let Some(clipped) = map.get(&format!("track_{i}Clipped")) {
+                        track.clipped = clipped.as_str() != "0";
+                    }
+                    if let Some(duration) = map.get(&format!("track_{i}Duration")) {
+                        track.duration_ms = Some(duration.parse()?);
+                    }
+                    if let Some(source) = map.get(&format!("track_{i}Source")) {
+                        track.source = Some(source.to_string());
+                    }
                     sample_stack.tracks.push(track);
                 }
             }
@@ -152,7 +192,7 @@ impl SampleBase {
     pub fn write_sample<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new(self.element_name.as_str());
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         self.colour_map.write_colours(&mut attributes);
 
         // TODO: Solve the 'State' problem properly..
@@ -186,7 +226,7 @@ impl SampleBase {
 
             // Welcome to the only place where order seems to matter, the track_X attributes must all appear together
             // in an ordered, unbroken list, otherwise the GoXLR App will crash :D
-            let mut sub_attributes: LinkedHashMap<String, String> = Default::default();
+            let mut sub_attributes: LinkedBTreeMap<String, String> = Default::default();
 
             for i in 0..value.tracks.len() {
                 sub_attributes.insert(
@@ -217,6 +257,41 @@ impl SampleBase {
                 );
             }
 
+            // Markers are ours rather than the official app's, so they go after the track list.
+            for i in 0..value.tracks.len() {
+                let markers = &value.tracks.get(i).unwrap().markers;
+                if !markers.is_empty() {
+                    sub_attributes.insert(
+                        format!("track_{i}Markers"),
+                        TrackMarker::write_list(markers),
+                    );
+                }
+            }
+
+            // As are the levels of samples recorded on the GoXLR.
+            for i in 0..value.tracks.len() {
+                let track = value.tracks.get(i).unwrap();
+                if let Some(peak) = track.recording_peak {
+                    sub_attributes.insert(format!("track_{i}RecordingPeak"), format!("{}", peak));
+                    sub_attributes.insert(
+                        format!("track_{i}Clipped"),
+                        format!("{}", track.clipped as u8),
+                    );
+                }
+            }
+
+            for i in 0..value.tracks.len() {
+                if let Some(duration) = value.tracks.get(i).unwrap().duration_ms {
+                    sub_attributes.insert(format!("track_{i}Duration"), format!("{}", duration));
+                }
+            }
+
+            for i in 0..value.tracks.len() {
+                if let Some(source) = &value.tracks.get(i).unwrap().source {
+                    sub_attributes.insert(format!("track_{i}Source"), source.clone());
+                }
+            }
+
             if let Some(output) = &value.playback_mode {
                 sub_attributes.insert(
                     "playbackMode".to_string(),
@@ -231,6 +306,20 @@ impl SampleBase {
                 );
             }
 
+            // Not something the official app knows about, so only written when set
+            if value.guarded {
+                sub_attributes.insert("guarded".to_string(), "1".to_string());
+            }
+
+            if let Some(ducking) = &value.ducking {
+                sub_attributes.insert("duckChannels".to_string(), ducking.write_channels());
+                sub_attributes.insert(
+                    "duckAttenuation".to_string(),
+                    format!("{}", ducking.attenuation),
+                );
+                sub_attributes.insert("duckRelease".to_string(), format!("{}", ducking.release));
+            }
+
             // Write the attributes into the tag, and close it.
             for (key, value) in &sub_attributes {
                 sub_elem.push_attribute((key.as_str(), value.as_str()));
@@ -263,6 +352,12 @@ pub struct SampleStack {
     playback_mode: Option<PlaybackMode>,
     play_order: Option<PlayOrder>,
 
+    // Presses are ignored while the daemon's guard mode is active
+    guarded: bool,
+
+    // Channels lowered while the button plays, also unknown to the official app
+    ducking: Option<SampleDucking>,
+
     // Transient value, keep track of where we may be sequentially..
     transient_seq_position: usize,
 }
@@ -279,6 +374,8 @@ impl SampleStack {
             tracks: vec![],
             playback_mode: None,
             play_order: None,
+            guarded: false,
+            ducking: None,
 
             transient_seq_position: 0,
         }
@@ -353,6 +450,10 @@ impl SampleStack {
         Some(track)
     }
 
+    pub fn is_guarded(&self) -> bool {
+        self.guarded
+    }
+
     pub fn set_playback_mode(&mut self, playback_mode: Option<PlaybackMode>) {
         self.playback_mode = playback_mode;
     }
@@ -360,6 +461,17 @@ impl SampleStack {
         self.play_order = play_order;
     }
 
+    pub fn set_guarded(&mut self, guarded: bool) {
+        self.guarded = guarded;
+    }
+
+    pub fn get_ducking(&self) -> Option<&SampleDucking> {
+        self.ducking.as_ref()
+    }
+    pub fn set_ducking(&mut self, ducking: Option<SampleDucking>) {
+        self.ducking = ducking;
+    }
+
     pub fn add_track(&mut self, track: Track) -> &mut Track {
         self.tracks.push(track);
         let len = self.tracks.len();
@@ -383,12 +495,53 @@ impl SampleStack {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SampleDucking {
+    pub channels: Vec<FullChannelList>,
+    pub attenuation: u8,
+    pub release: u16,
+}
+
+impl SampleDucking {
+    // Channels are stored by their mixer names, separated by commas
+    fn parse_channels(value: &str) -> Result<Vec<FullChannelList>> {
+        let mut channels = vec![];
+        for name in value.split(',').filter(|name| !name.is_empty()) {
+            match FullChannelList::iter().find(|channel| channel.get_str("Name") == Some(name)) {
+                Some(channel) => channels.push(channel),
+                None => bail!("Unknown duck channel: {}", name),
+            }
+        }
+        Ok(channels)
+    }
+
+    fn write_channels(&self) -> String {
+        let names: Vec<&str> = self
+            .channels
+            .iter()
+            .map(|channel| channel.get_str("Name").unwrap())
+            .collect();
+        names.join(",")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Track {
     pub track: String,
     pub start_position: f32,
     pub end_position: f32,
     pub normalized_gain: f64,
+    pub markers: Vec<TrackMarker>,
+
+    // The peak level (in dBFS) of a sample recorded on the GoXLR, and whether it clipped
+    pub recording_peak: Option<f32>,
+    pub clipped: bool,
+
+    // The length of the file, found when it was analysed
+    pub duration_ms: Option<u64>,
+
+    // The file this track was converted from, when it was converted on import
+    pub source: Option<String>,
 }
 
 impl Track {
@@ -403,6 +556,11 @@ impl Track {
             start_position,
             end_position,
             normalized_gain,
+            markers: vec![],
+            recording_peak: None,
+            clipped: false,
+            duration_ms: None,
+            source: None,
         }
     }
 
@@ -446,6 +604,52 @@ impl Track {
     }
 }
 
+/// A cue point (or region, if it has an end) which was embedded in the sample file.
+#[derive(Debug, Clone)]
+pub struct TrackMarker {
+    pub label: String,
+    pub start_position: f32,
+    pub end_position: Option<f32>,
+}
+
+impl TrackMarker {
+    // Stored as 'start,end,label' separated by '|', the label comes last so it can contain commas
+    fn parse_list(value: &str) -> Vec<TrackMarker> {
+        let mut markers = vec![];
+        for marker in value.split('|') {
+            let mut parts = marker.splitn(3, ',');
+            let start = parts.next().and_then(|start| start.parse().ok());
+            let end = parts.next().map(|end| end.parse().ok());
+            let label = parts.next();
+
+            if let (Some(start_position), Some(end_position), Some(label)) = (start, end, label) {
+                markers.push(TrackMarker {
+                    label: label.to_string(),
+                    start_position,
+                    end_position,
+                });
+            }
+        }
+        markers
+    }
+
+    fn write_list(markers: &[TrackMarker]) -> String {
+        markers
+            .iter()
+            .map(|marker| {
+                let end = marker.end_position.map(|end| end.to_string());
+                format!(
+                    "{},{},{}",
+                    marker.start_position,
+                    end.unwrap_or_default(),
+                    marker.label.replace('|', "/")
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+}
+
 #[derive(Debug, Copy, Clone, Enum, EnumProperty)]
 pub enum PlaybackMode {
     #[strum(props(index = "0"))]
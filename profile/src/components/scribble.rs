@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::str::FromStr;
 
 use anyhow::Result;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use strum::EnumProperty;
+use strum::{Display, EnumProperty, EnumString};
 
 use crate::components::colours::{Colour, ColourMap};
 use crate::components::scribble::ScribbleStyle::{Inverted, Normal};
@@ -35,6 +35,10 @@ pub struct Scribble {
     // File provided to the GoXLR to handle (no path, just the filename)
     icon_file: Option<String>,
 
+    // Character(s) drawn in place of the icon file, only written when set, as the official app
+    // doesn't know about it.
+    icon_glyph: Option<String>,
+
     // This normally is just the channel number, rendered in the top left.
     text_top_left: String,
 
@@ -57,6 +61,10 @@ pub struct Scribble {
 
     // Filename in the .goxlr zip file to the prepared bitmap
     bitmap_file: String,
+
+    // What's shown in the top left, only written when it's not the static text, as the official
+    // app doesn't know about it.
+    number_mode: ScribbleNumberMode,
 }
 
 impl Scribble {
@@ -75,12 +83,14 @@ impl Scribble {
         Self {
             colour_map,
             icon_file: None,
+            icon_glyph: None,
             text_top_left: "".to_string(),
             text_bottom_middle: text.to_string(),
             text_size: 0,
             alpha: 0.0,
             style: Normal,
             bitmap_file: "".to_string(),
+            number_mode: ScribbleNumberMode::Static,
         }
     }
 
@@ -95,6 +105,15 @@ impl Scribble {
                 continue;
             }
 
+            if attr.name.ends_with("iconGlyph") {
+                if attr.value.is_empty() {
+                    self.icon_glyph = None;
+                } else {
+                    self.icon_glyph = Some(attr.value.clone());
+                }
+                continue;
+            }
+
             if attr.name.ends_with("string0") {
                 self.text_top_left.clone_from(&attr.value);
                 continue;
@@ -129,6 +148,11 @@ impl Scribble {
                 continue;
             }
 
+            if attr.name.ends_with("numberMode") {
+                self.number_mode = ScribbleNumberMode::from_str(attr.value.as_str())?;
+                continue;
+            }
+
             // Send the rest out for colouring..
             if !self.colour_map.read_colours(attr)? {
                 println!("[SCRIBBLE] Unparsed Attribute: {}", attr.name);
@@ -142,7 +166,7 @@ impl Scribble {
         let element_name = fader.get_str("scribbleContext").unwrap();
         let mut elem = BytesStart::new(element_name);
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(
             format!("{}iconFile", element_name),
             if self.icon_file.is_none() {
@@ -172,6 +196,17 @@ impl Scribble {
         );
         attributes.insert(format!("{}bitmap", element_name), self.bitmap_file.clone());
 
+        if let Some(glyph) = &self.icon_glyph {
+            attributes.insert(format!("{}iconGlyph", element_name), glyph.clone());
+        }
+
+        if self.number_mode != ScribbleNumberMode::Static {
+            attributes.insert(
+                format!("{}numberMode", element_name),
+                self.number_mode.to_string(),
+            );
+        }
+
         self.colour_map
             .write_colours_with_prefix(element_name.into(), &mut attributes);
 
@@ -193,6 +228,9 @@ impl Scribble {
     pub fn icon_file(&self) -> Option<String> {
         self.icon_file.clone()
     }
+    pub fn icon_glyph(&self) -> Option<String> {
+        self.icon_glyph.clone()
+    }
     pub fn text_top_left(&self) -> Option<String> {
         if self.text_top_left.is_empty() {
             return None;
@@ -213,9 +251,16 @@ impl Scribble {
         &self.style
     }
 
+    pub fn number_mode(&self) -> ScribbleNumberMode {
+        self.number_mode
+    }
+
     pub fn set_icon_file(&mut self, icon_file: Option<String>) {
         self.icon_file = icon_file;
     }
+    pub fn set_icon_glyph(&mut self, icon_glyph: Option<String>) {
+        self.icon_glyph = icon_glyph;
+    }
     pub fn set_text_top_left(&mut self, text_top_left: String) {
         self.text_top_left = text_top_left;
     }
@@ -226,6 +271,10 @@ impl Scribble {
     pub fn set_scribble_inverted(&mut self, inverted: bool) {
         self.style = if inverted { Inverted } else { Normal }
     }
+
+    pub fn set_number_mode(&mut self, number_mode: ScribbleNumberMode) {
+        self.number_mode = number_mode;
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -233,3 +282,18 @@ pub enum ScribbleStyle {
     Normal,
     Inverted,
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, Display)]
+pub enum ScribbleNumberMode {
+    #[strum(to_string = "static")]
+    Static,
+
+    #[strum(to_string = "volumePercent")]
+    VolumePercent,
+
+    #[strum(to_string = "volumeDb")]
+    VolumeDb,
+
+    #[strum(to_string = "activePreset")]
+    ActivePreset,
+}
@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use enum_map::EnumMap;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use strum::{EnumProperty, IntoEnumIterator};
 
@@ -12,6 +12,9 @@ use strum::{EnumProperty, IntoEnumIterator};
 pub struct LinkingTree {
     linked_list: EnumMap<InputChannels, bool>,
     linked_ratio: EnumMap<InputChannels, f64>,
+
+    // Whether the link was loaded from the profile or set since, rather than just the default
+    linked_set: EnumMap<InputChannels, bool>,
 }
 
 impl Default for LinkingTree {
@@ -25,6 +28,7 @@ impl LinkingTree {
         Self {
             linked_list: LinkingTree::get_default_linked_list(),
             linked_ratio: LinkingTree::get_default_linked_ratio(),
+            linked_set: Default::default(),
         }
     }
 
@@ -60,6 +64,7 @@ impl LinkingTree {
                     if chan_enum.get_str("Name").unwrap() == channel {
                         // Set the value..
                         self.linked_list[chan_enum] = value;
+                        self.linked_set[chan_enum] = true;
                         found = true;
                     }
                 }
@@ -102,8 +107,12 @@ impl LinkingTree {
         let mut elem = BytesStart::new("linkingTree");
 
         // This one's actually incredibly straight forward :)
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         for input in InputChannels::iter() {
+            // Links which were never set are left out, so a default can still be applied later
+            if !self.linked_set[input] {
+                continue;
+            }
             let key = format!("{}Linked", input.get_str("Name").unwrap());
             let value = format!("{}", self.linked_list[input] as u8);
 
@@ -129,6 +138,9 @@ impl LinkingTree {
     pub fn is_linked(&self, channel: InputChannels) -> bool {
         self.linked_list[channel]
     }
+    pub fn is_link_set(&self, channel: InputChannels) -> bool {
+        self.linked_set[channel]
+    }
     pub fn get_ratio(&self, channel: InputChannels) -> f64 {
         if self.linked_ratio[channel] == f64::INFINITY {
             // Caused by a divide by 0, return 255 / 1
@@ -143,6 +155,7 @@ impl LinkingTree {
 
     pub fn set_link_enabled(&mut self, channel: InputChannels, linked: bool) -> Result<()> {
         self.linked_list[channel] = linked;
+        self.linked_set[channel] = true;
         Ok(())
     }
 
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use crate::components::mixer::OutputChannels;
@@ -32,8 +32,9 @@ impl MixRoutingTree {
             // Normally, I'd add some fancy code to iterate the OutputChannel, but for the tree here
             // they have different names to anywhere else, so we'll do it by hand.
 
-            // Firstly, work out the Mix value..
-            if let Some(value) = Mix::iter().nth(attr.value.parse::<usize>()? - 1) {
+            // Firstly, work out the Mix value (these start at 1)..
+            let index = attr.value.parse::<usize>()?.checked_sub(1);
+            if let Some(value) = index.and_then(|index| Mix::iter().nth(index)) {
                 if attr.name == "headphone" {
                     self.mix[OutputChannels::Headphones] = value;
                     continue;
@@ -67,7 +68,7 @@ impl MixRoutingTree {
         let mut elem = BytesStart::new("mixRoutingTree");
 
         // This one's actually incredibly straight forward :)
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
 
         attributes.insert(
             String::from("headphone"),
@@ -5,7 +5,7 @@ use anyhow::Result;
 use enum_map::EnumMap;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use strum::{EnumProperty, IntoEnumIterator};
 
@@ -57,7 +57,8 @@ impl MonitorTree {
             }
 
             if attr.name == "headphoneMix" {
-                if let Some(mix) = Mix::iter().nth(attr.value.parse::<usize>()? - 1) {
+                let index = attr.value.parse::<usize>()?.checked_sub(1);
+                if let Some(mix) = index.and_then(|index| Mix::iter().nth(index)) {
                     self.headphone_mix = mix;
                 }
                 continue;
@@ -88,7 +89,7 @@ impl MonitorTree {
     pub fn write_monitor_tree<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("monitorTree");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(
             String::from("monitoredOutput"),
             format!("{}", self.monitored_output as usize),
@@ -6,7 +6,7 @@ use anyhow::Result;
 use enum_map::EnumMap;
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 use strum::{EnumProperty, IntoEnumIterator};
 
@@ -72,7 +72,7 @@ impl SubMixer {
         let mut elem = BytesStart::new("submixerTree");
 
         // Create the values..
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(
             String::from("submixMode"),
             (self.submix_enabled as u8).to_string(),
@@ -143,6 +143,11 @@ impl SubMixer {
         self.linking_tree.is_linked(channel)
     }
 
+    /// Whether the profile says if this channel is linked, rather than it being the default.
+    pub fn is_link_set(&self, channel: InputChannels) -> bool {
+        self.linking_tree.is_link_set(channel)
+    }
+
     pub fn monitor_tree(&self) -> &MonitorTree {
         &self.monitor_tree
     }
@@ -0,0 +1,154 @@
+use crate::components::mixer::{FullChannelList, InputChannels};
+use crate::profile::Attribute;
+use anyhow::{bail, Result};
+use enum_map::EnumMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Writer;
+use std::io::Write;
+use strum::{EnumProperty, IntoEnumIterator};
+
+/**
+ * Volume Scenes are a utility addition (the official app will simply ignore the tags), each scene
+ * is a named snapshot of the main and submix volumes which can be recalled later.
+ */
+#[derive(Debug, Default)]
+pub struct VolumeScenes {
+    scenes: Vec<VolumeScene>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VolumeScene {
+    name: String,
+    volumes: EnumMap<FullChannelList, Option<u8>>,
+    submix_volumes: EnumMap<InputChannels, Option<u8>>,
+}
+
+impl VolumeScenes {
+    pub fn new() -> Self {
+        Self { scenes: vec![] }
+    }
+
+    pub fn parse_scene(&mut self, attributes: &Vec<Attribute>) -> Result<()> {
+        let mut scene = VolumeScene::new(String::new());
+
+        for attr in attributes {
+            if attr.name == "name" {
+                scene.name = attr.value.clone();
+                continue;
+            }
+
+            // Keys are checked in full, 'mic2headphoneSub' would otherwise be confused for a
+            // submix volume.
+            let mut found = false;
+            for channel in FullChannelList::iter() {
+                if attr.name == format!("{}Volume", channel.get_str("Name").unwrap()) {
+                    scene.volumes[channel] = Some(attr.value.parse()?);
+                    found = true;
+                }
+            }
+
+            for channel in InputChannels::iter() {
+                if attr.name == format!("{}SubmixVolume", channel.get_str("Name").unwrap()) {
+                    scene.submix_volumes[channel] = Some(attr.value.parse()?);
+                    found = true;
+                }
+            }
+
+            if !found {
+                println!("Unknown Volume Scene Attribute: {}", attr.name);
+            }
+        }
+
+        if scene.name.is_empty() {
+            bail!("Volume Scene is missing a name");
+        }
+
+        // If the profile has been hand edited, the last definition wins.
+        self.scenes.retain(|existing| existing.name != scene.name);
+        self.scenes.push(scene);
+        Ok(())
+    }
+
+    pub fn write_scenes<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        for scene in &self.scenes {
+            let mut elem = BytesStart::new("volumeScene");
+            elem.push_attribute(("name", scene.name.as_str()));
+
+            for (channel, volume) in scene.volumes {
+                if let Some(volume) = volume {
+                    let key = format!("{}Volume", channel.get_str("Name").unwrap());
+                    elem.push_attribute((key.as_str(), volume.to_string().as_str()));
+                }
+            }
+
+            for (channel, volume) in scene.submix_volumes {
+                if let Some(volume) = volume {
+                    let key = format!("{}SubmixVolume", channel.get_str("Name").unwrap());
+                    elem.push_attribute((key.as_str(), volume.to_string().as_str()));
+                }
+            }
+
+            writer.write_event(Event::Empty(elem))?;
+        }
+        Ok(())
+    }
+
+    pub fn scene_names(&self) -> Vec<String> {
+        self.scenes.iter().map(|scene| scene.name.clone()).collect()
+    }
+
+    pub fn get_scene(&self, name: &str) -> Option<&VolumeScene> {
+        self.scenes.iter().find(|scene| scene.name == name)
+    }
+
+    /// Stores the scene, replacing any existing scene with the same name in place.
+    pub fn set_scene(&mut self, scene: VolumeScene) {
+        match self
+            .scenes
+            .iter_mut()
+            .find(|existing| existing.name == scene.name)
+        {
+            Some(existing) => *existing = scene,
+            None => self.scenes.push(scene),
+        }
+    }
+
+    pub fn remove_scene(&mut self, name: &str) -> Result<()> {
+        let count = self.scenes.len();
+        self.scenes.retain(|scene| scene.name != name);
+        if self.scenes.len() == count {
+            bail!("Volume Scene {} not found", name);
+        }
+        Ok(())
+    }
+}
+
+impl VolumeScene {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            volumes: Default::default(),
+            submix_volumes: Default::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn volume(&self, channel: FullChannelList) -> Option<u8> {
+        self.volumes[channel]
+    }
+
+    pub fn set_volume(&mut self, channel: FullChannelList, volume: u8) {
+        self.volumes[channel] = Some(volume);
+    }
+
+    pub fn submix_volume(&self, channel: InputChannels) -> Option<u8> {
+        self.submix_volumes[channel]
+    }
+
+    pub fn set_submix_volume(&mut self, channel: InputChannels, volume: u8) {
+        self.submix_volumes[channel] = Some(volume);
+    }
+}
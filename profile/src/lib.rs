@@ -1,11 +1,13 @@
 use enum_map::Enum;
-use strum::{Display, EnumIter, EnumProperty};
+use strum::{Display, EnumIter, EnumProperty, EnumString};
 
 pub mod components;
 pub mod error;
 pub mod mic_profile;
 pub mod microphone;
+mod official;
 pub mod profile;
+mod unknown;
 
 #[derive(Debug, Display, Enum, EnumIter, EnumProperty, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum SampleButtons {
@@ -52,6 +54,52 @@ pub enum Preset {
     Preset6,
 }
 
+/// The individual effects which make up a preset, these can be written and loaded on their own.
+#[derive(Debug, EnumIter, EnumProperty, Copy, Clone, PartialEq, Eq)]
+pub enum PresetSection {
+    #[strum(props(tagName = "reverbEncoder"))]
+    Reverb,
+
+    #[strum(props(tagName = "echoEncoder"))]
+    Echo,
+
+    #[strum(props(tagName = "pitchEncoder"))]
+    Pitch,
+
+    #[strum(props(tagName = "genderEncoder"))]
+    Gender,
+
+    #[strum(props(tagName = "megaphoneEffect"))]
+    Megaphone,
+
+    #[strum(props(tagName = "robotEffect"))]
+    Robot,
+
+    #[strum(props(tagName = "hardtuneEffect"))]
+    HardTune,
+}
+
+/// The parts of a profile which a child profile can either keep its own copy of, or take from
+/// its base profile. The names are as stored in the baseProfile element.
+#[derive(Debug, Display, EnumString, Enum, EnumIter, Copy, Clone, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum BaseSection {
+    /// The colours of every button, fader and encoder, and the animation settings
+    Lighting,
+
+    /// The routing table, and which mix each output and the monitor use
+    Routing,
+
+    /// Fader channel assignments, mute buttons and the scribble strips
+    Faders,
+
+    /// The effect presets, along with the effect and encoder settings
+    Effects,
+
+    /// The samples assigned to each button
+    Sampler,
+}
+
 #[derive(Debug, Enum, EnumIter, EnumProperty, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Faders {
     #[strum(props(
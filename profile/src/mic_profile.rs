@@ -6,10 +6,10 @@ use crate::microphone::mic_setup::MicSetup;
 use crate::microphone::ui_setup::UiSetup;
 use crate::profile::wrap_start_event;
 use anyhow::{anyhow, bail, Result};
-use log::{debug, warn};
+use log::debug;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
 use quick_xml::{Reader, Writer};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
@@ -154,13 +154,7 @@ impl MicProfileSettings {
         // Sync the write to disk..
         temp_file.sync_all()?;
 
-        debug!("Save Complete and synced, renaming to {:?}", path.as_ref());
-        if path.as_ref().exists() {
-            debug!("Target mic profile exists, removing..");
-            fs::remove_file(&path).unwrap_or_else(|e| {
-                warn!("Error Removing File: {}", e);
-            });
-        }
+        // Renaming replaces the existing file in one step
         debug!("Renaming {:?} to {:?}", tmp_file_name, path.as_ref());
         fs::rename(tmp_file_name, path)?;
 
@@ -174,7 +168,7 @@ impl MicProfileSettings {
         writer.write_event(Event::Start(BytesStart::new("MicProfileTree")))?;
 
         // First, we need to write the EQ, Compressor and Gate..
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
 
         // The mini and main can both have configs in the same file.
         self.equalizer.write_equaliser(&mut attributes);
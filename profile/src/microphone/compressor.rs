@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::os::raw::c_float;
 
 use crate::profile::Attribute;
@@ -17,7 +17,7 @@ pub enum ParseError {
     Error(#[from] anyhow::Error),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Compressor {
     threshold: i8,
     ratio: u8,
@@ -86,7 +86,7 @@ impl Compressor {
         Ok(())
     }
 
-    pub fn write_compressor(&self, attributes: &mut HashMap<String, String>) {
+    pub fn write_compressor(&self, attributes: &mut BTreeMap<String, String>) {
         attributes.insert(
             "MIC_COMP_THRESHOLD".to_string(),
             format!("{}", self.threshold),
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::os::raw::c_float;
 use std::str::FromStr;
 
@@ -20,7 +20,7 @@ pub enum ParseError {
 
 // The EQ has a crap load of values (20 total), we could consider splitting
 // them into Gain and Freq to keep stuff tidy?
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Equalizer {
     eq_31h_gain: i8,
     eq_63h_gain: i8,
@@ -163,7 +163,7 @@ impl Equalizer {
         Ok(())
     }
 
-    pub fn write_equaliser(&self, attributes: &mut HashMap<String, String>) {
+    pub fn write_equaliser(&self, attributes: &mut BTreeMap<String, String>) {
         attributes.insert(
             "MIC_EQ_31.5HZ_GAIN".to_string(),
             format!("{}", self.eq_31h_gain),
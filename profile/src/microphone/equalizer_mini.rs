@@ -1,7 +1,7 @@
 use crate::microphone::equalizer::validate_gain;
 use crate::profile::Attribute;
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::os::raw::c_float;
 use std::str::FromStr;
 
@@ -19,7 +19,7 @@ pub enum ParseError {
 }
 
 // Mini processes mostly the same way as the main, although has a smaller frequency set.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EqualizerMini {
     eq_90h_gain: i8,
     eq_250h_gain: i8,
@@ -117,7 +117,7 @@ impl EqualizerMini {
         Ok(())
     }
 
-    pub fn write_equaliser(&self, attributes: &mut HashMap<String, String>) {
+    pub fn write_equaliser(&self, attributes: &mut BTreeMap<String, String>) {
         attributes.insert(
             "MIC_MINI_EQ_90HZ_GAIN".to_string(),
             format!("{}", self.eq_90h_gain),
@@ -1,6 +1,6 @@
 use crate::profile::Attribute;
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::os::raw::c_float;
 
 #[derive(thiserror::Error, Debug)]
@@ -16,7 +16,7 @@ pub enum ParseError {
     Error(#[from] anyhow::Error),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Gate {
     amount: u8,
     threshold: i8,
@@ -94,7 +94,7 @@ impl Gate {
         Ok(())
     }
 
-    pub fn write_gate(&self, attributes: &mut HashMap<String, String>) {
+    pub fn write_gate(&self, attributes: &mut BTreeMap<String, String>) {
         attributes.insert(
             "MIC_GATE_MACRO_AMOUNT".to_string(),
             format!("{}", self.amount),
@@ -2,7 +2,7 @@ use crate::profile::Attribute;
 use anyhow::{anyhow, Result};
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::ffi::c_float;
 use std::io::Write;
 
@@ -19,7 +19,7 @@ pub enum ParseError {
     Error(#[from] anyhow::Error),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MicSetup {
     mic_type: u8,
 
@@ -76,7 +76,7 @@ impl MicSetup {
     pub fn write_config<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("setupTreeMicProfile");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert("MIC_TYPE".to_string(), format!("{}", self.mic_type));
         attributes.insert(
             "DYNAMIC_MIC_GAIN".to_string(),
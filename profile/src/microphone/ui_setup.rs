@@ -2,7 +2,7 @@ use crate::profile::Attribute;
 use anyhow::Result;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Writer;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 #[derive(thiserror::Error, Debug)]
@@ -73,7 +73,7 @@ impl UiSetup {
     pub fn write_ui<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
         let mut elem = BytesStart::new("micProfileUIMicProfile");
 
-        let mut attributes: HashMap<String, String> = HashMap::default();
+        let mut attributes: BTreeMap<String, String> = BTreeMap::default();
         attributes.insert(
             "eqAdvanced".to_string(),
             format!("{}", self.eq_advanced as u8),
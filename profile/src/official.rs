@@ -0,0 +1,102 @@
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use quick_xml::events::{BytesDecl, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+/**
+ * The official app ignores most things it doesn't recognise, but there's no guarantee that it
+ * always will, so when exporting a profile for it we remove everything the utility has added to
+ * the format. Everything the official app expects is always written by the profile itself (with
+ * defaults where the utility has nothing better), so only removal is needed here.
+ */
+// Elements which only exist in profiles written by the utility
+const UTILITY_ELEMENTS: [&str; 2] = ["volumeScene", "baseProfile"];
+
+// Attributes added by the utility, as the prefix of the element they belong to and the suffix of
+// the attribute's name.
+const UTILITY_ATTRIBUTES: [(&str, &str); 18] = [
+    ("ValueTreeRoot", "utilityVersion"),
+    ("mixerTree", "LowCut"),
+    ("mixerTree", "Balance"),
+    ("mixerTree", "EqGain"),
+    ("scribble", "numberMode"),
+    ("effects", "Intensity"),
+    ("effects", "ReverbBaseline"),
+    ("effects", "EchoBaseline"),
+    ("effects", "PitchBaseline"),
+    ("reverbEncoder", "globalKnob"),
+    ("echoEncoder", "globalKnob"),
+    ("pitchEncoder", "globalKnob"),
+    ("genderEncoder", "globalKnob"),
+    ("sampleStack", "Markers"),
+    ("sampleStack", "RecordingPeak"),
+    ("sampleStack", "Clipped"),
+    ("sampleStack", "Duration"),
+    ("sampleStack", "Source"),
+];
+
+/// Rewrites a profile written by the utility, without any of the utility's additions.
+pub fn strip_utility_content<W: Write>(written: &[u8], sink: W) -> Result<()> {
+    let mut reader = Reader::from_reader(written);
+    let mut writer = Writer::new_with_indent(sink, u8::try_from('\t')?, 1);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    // How deep we are inside an element that's being dropped
+    let mut skipping = 0;
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                if skipping > 0 || is_utility_element(&e) {
+                    skipping += 1;
+                } else {
+                    writer.write_event(Event::Start(strip_attributes(&e)))?;
+                }
+            }
+            Event::Empty(e) if skipping == 0 && !is_utility_element(&e) => {
+                writer.write_event(Event::Empty(strip_attributes(&e)))?;
+            }
+            Event::End(e) => {
+                if skipping > 0 {
+                    skipping -= 1;
+                } else {
+                    writer.write_event(Event::End(e))?;
+                }
+            }
+            Event::Eof => break,
+
+            // The declaration is replaced, and whitespace comes from the writer's indentation
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if skipping > 0 {
+        bail!("Unexpected end of profile");
+    }
+    Ok(())
+}
+
+fn is_utility_element(element: &BytesStart) -> bool {
+    UTILITY_ELEMENTS
+        .iter()
+        .any(|name| element.name().as_ref() == name.as_bytes())
+}
+
+fn strip_attributes(element: &BytesStart) -> BytesStart<'static> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+    let mut stripped = BytesStart::new(name.clone());
+
+    for attribute in element.attributes().flatten() {
+        let key = String::from_utf8_lossy(attribute.key.as_ref());
+        let utility = UTILITY_ATTRIBUTES
+            .iter()
+            .any(|(prefix, suffix)| name.starts_with(prefix) && key.ends_with(suffix));
+        if !utility {
+            stripped.push_attribute(attribute);
+        }
+    }
+    stripped
+}
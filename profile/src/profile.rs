@@ -1,6 +1,9 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufReader, Read, Seek, Write};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use std::str::FromStr;
 
@@ -14,7 +17,9 @@ use strum::IntoEnumIterator;
 use zip::write::SimpleFileOptions;
 
 use crate::components::animation::AnimationTree;
+use crate::components::base_profile::BaseProfile;
 use crate::components::browser::BrowserPreviewTree;
+use crate::components::colours::ColourMap;
 use crate::components::context::Context;
 use crate::components::echo::EchoEncoderBase;
 use crate::components::effects::Effects;
@@ -35,8 +40,11 @@ use crate::components::scribble::Scribble;
 use crate::components::simple::{SimpleElement, SimpleElements};
 use crate::components::submix::mix_routing_tree::{Mix, MixRoutingTree};
 use crate::components::submix::submixer::SubMixer;
+use crate::components::volume_scenes::VolumeScenes;
+use crate::official::strip_utility_content;
+use crate::unknown::{get_attributes, RawAttribute, UnknownContent};
 use crate::SampleButtons::{BottomLeft, BottomRight, Clear, TopLeft, TopRight};
-use crate::{Faders, Preset, SampleButtons};
+use crate::{BaseSection, Faders, Preset, PresetSection, SampleButtons};
 
 #[derive(Debug)]
 pub struct Profile {
@@ -44,6 +52,13 @@ pub struct Profile {
     scribbles: [Vec<u8>; 4],
 }
 
+/// A section of a profile as it was at some point, to check whether it has since changed.
+#[derive(Debug, PartialEq)]
+pub struct SectionSnapshot {
+    values: SectionValues,
+    scribbles: Option<[Vec<u8>; 4]>,
+}
+
 #[derive(Debug)]
 pub struct Attribute {
     pub(crate) name: String,
@@ -68,7 +83,12 @@ impl Profile {
         }
 
         debug!("Attempting to read profile.xml..");
-        let result = ProfileSettings::load(archive.by_name("profile.xml")?);
+        let file = archive.by_name("profile.xml")?;
+
+        // Anything in the profile the parser doesn't handle should fail the load, rather than
+        // take whatever's loading it down too.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| ProfileSettings::load(file)))
+            .unwrap_or_else(|_| Err(anyhow!("The Profile Parser Panicked")));
         match result {
             Ok(settings) => Ok(Profile {
                 settings,
@@ -94,37 +114,56 @@ impl Profile {
         let temp_file = File::create(&tmp_file_name)?;
 
         // Create a new ZipFile at the requested location
-        let mut archive = zip::ZipWriter::new(&temp_file);
+        self.write_archive(&temp_file, false)?;
+
+        // The archive has finished writing, we don't need it anymore (keeping it live prevents
+        // us from removing the temporary file).
+        temp_file.sync_all()?;
+
+        // Once complete, we simply move the file over the existing file (which replaces it in one
+        // step, so there's never a moment without a profile)..
+        debug!("Renaming {:?} to {:?}", tmp_file_name, path.as_ref());
+        fs::rename(tmp_file_name, &path)?;
+        Ok(())
+    }
+
+    /// Writes the profile archive to any destination, such as a buffer to be saved elsewhere.
+    pub fn write_to<W: Write + Seek>(&mut self, sink: W) -> Result<()> {
+        self.write_archive(sink, false)
+    }
+
+    /// Writes the profile in a form the official app will load, without any of the utility's
+    /// additions. The profile itself is left untouched.
+    pub fn export_official(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path)?;
+        self.write_archive(&file, true)?;
+        file.sync_all().context("Unable to Sync File")
+    }
+
+    fn write_archive<W: Write + Seek>(&mut self, sink: W, official: bool) -> Result<()> {
+        let mut archive = zip::ZipWriter::new(sink);
 
         // Store the profile..
         archive.start_file("profile.xml", SimpleFileOptions::default())?;
-        self.settings.write_to(&mut archive)?;
+        match official {
+            true => self.settings.write_official_to(&mut archive)?,
+            false => self.settings.write_to(&mut archive)?,
+        }
 
-        // Write the scribbles..
+        // Write the scribbles, unless they come from a base profile along with the faders..
+        let inherited = match self.settings.base_profile() {
+            Some(base) => !official && !base.owns(BaseSection::Faders),
+            None => false,
+        };
         for (i, scribble) in self.scribbles.iter().enumerate() {
             // Only write if there's actually data stored..
-            if !self.scribbles[i].is_empty() {
+            if !inherited && !self.scribbles[i].is_empty() {
                 let filename = format!("scribble{}.png", i + 1);
                 archive.start_file(filename, SimpleFileOptions::default())?;
                 archive.write_all(scribble)?;
             }
         }
         archive.finish()?;
-
-        // The archive has finished writing, we don't need it anymore (keeping it live prevents
-        // us from removing the temporary file).
-        temp_file.sync_all()?;
-
-        // Once complete, we simply move the file over the existing file..
-        debug!("Save Complete and synced, renaming to {:?}", path.as_ref());
-        if path.as_ref().exists() {
-            debug!("Target profile exists, removing..");
-            fs::remove_file(&path).unwrap_or_else(|e| {
-                warn!("Error Removing File: {}", e);
-            });
-        }
-        debug!("Renaming {:?} to {:?}", tmp_file_name, path.as_ref());
-        fs::rename(tmp_file_name, &path)?;
         Ok(())
     }
 
@@ -144,6 +183,42 @@ impl Profile {
     pub fn get_scribble(&self, id: usize) -> &Vec<u8> {
         &self.scribbles[id]
     }
+
+    /// Swaps a section with another profile, the scribble images go along with the faders.
+    pub fn swap_section(&mut self, section: BaseSection, other: &mut Profile) {
+        self.settings.swap_section(section, &mut other.settings);
+        if section == BaseSection::Faders {
+            mem::swap(&mut self.scribbles, &mut other.scribbles);
+        }
+    }
+
+    /// Takes the values of a section, the scribble images are part of the faders.
+    pub fn snapshot_section(&mut self, section: BaseSection) -> Result<SectionSnapshot> {
+        let scribbles = (section == BaseSection::Faders).then(|| self.scribbles.clone());
+        let values = self.settings.snapshot_section(section)?;
+        Ok(SectionSnapshot { values, scribbles })
+    }
+
+    pub fn section_matches(&mut self, section: BaseSection, other: &mut Profile) -> Result<bool> {
+        if section == BaseSection::Faders && self.scribbles != other.scribbles {
+            return Ok(false);
+        }
+        self.settings.section_matches(section, &mut other.settings)
+    }
+}
+
+// An element's name, and its attributes as (name, value) pairs
+type KnownElement = (String, Vec<(String, String)>);
+
+// A profile with nothing in it, loading this gives the default for everything
+const EMPTY_PROFILE: &[u8] = b"<ValueTreeRoot version=\"2\"></ValueTreeRoot>";
+
+/// The values in a profile which belong to some of its sections, by the path of their element.
+/// Whole elements belong to the sections when they aren't written at all without them.
+#[derive(Debug, Default, PartialEq)]
+pub struct SectionValues {
+    elements: HashMap<String, Vec<RawAttribute>>,
+    attributes: HashMap<String, Vec<RawAttribute>>,
 }
 
 #[derive(Debug)]
@@ -172,13 +247,20 @@ pub struct ProfileSettings {
     echo_encoder: EchoEncoderBase,
     pitch_encoder: PitchEncoderBase,
     gender_encoder: GenderEncoderBase,
+
+    volume_scenes: VolumeScenes,
+    base_profile: Option<BaseProfile>,
+
+    // Anything in the profile we didn't understand, which needs to be written back on save
+    unknown: UnknownContent,
 }
 
 impl ProfileSettings {
-    pub fn load<R: Read>(read: R) -> Result<Self> {
-        // Wrap our reader into a Buffered Reader for parsing..
-        let buf_reader = BufReader::new(read);
-        let mut reader = Reader::from_reader(buf_reader);
+    pub fn load<R: Read>(mut read: R) -> Result<Self> {
+        // Keep hold of the original XML, we need it to find anything we don't understand..
+        let mut original = Vec::new();
+        read.read_to_end(&mut original)?;
+        let mut reader = Reader::from_reader(original.as_slice());
 
         debug!("Preparing Structure..");
 
@@ -189,6 +271,8 @@ impl ProfileSettings {
 
         let mut mix_routing = MixRoutingTree::new();
         let mut submix_tree = SubMixer::new();
+        let mut volume_scenes = VolumeScenes::new();
+        let mut base_profile = None;
 
         let mut mixer = Mixers::new();
         let mut context = Context::new("selectedContext".to_string());
@@ -287,6 +371,16 @@ impl ProfileSettings {
                         continue;
                     }
 
+                    if name == "volumeScene" {
+                        volume_scenes.parse_scene(&attributes)?;
+                        continue;
+                    }
+
+                    if name == "baseProfile" {
+                        base_profile = Some(BaseProfile::parse_base(&attributes)?);
+                        continue;
+                    }
+
                     if name == "mixerTree" {
                         mixer.parse_mixers(&attributes)?;
                         continue;
@@ -435,6 +529,13 @@ impl ProfileSettings {
                         if root.get_version() > 3 {
                             bail!("Unsupported Profile Version {}", root.get_version());
                         }
+
+                        if root.is_from_newer_version() {
+                            warn!(
+                                "Profile was saved by a newer version of the utility ({}), some settings may not be available",
+                                root.get_utility_version().unwrap_or_default()
+                            );
+                        }
                         continue;
                     }
 
@@ -525,7 +626,7 @@ impl ProfileSettings {
         debug!("{:?}", mix_routing);
         debug!("{:?}", submix_tree);
 
-        Ok(Self {
+        let mut settings = Self {
             root,
             browser,
             animation_tree,
@@ -547,7 +648,25 @@ impl ProfileSettings {
             echo_encoder,
             pitch_encoder,
             gender_encoder,
-        })
+            volume_scenes,
+            base_profile,
+            unknown: UnknownContent::default(),
+        };
+
+        // Work out what we don't understand, by comparing the original to what we'd write.
+        let mut written = Vec::new();
+        settings.write_known_to(&mut written)?;
+        match UnknownContent::find(&original, &written) {
+            Ok(unknown) => {
+                if !unknown.is_empty() {
+                    debug!("Profile contains unknown content: {:?}", unknown);
+                }
+                settings.unknown = unknown;
+            }
+            Err(e) => warn!("Unable to check the profile for unknown content: {}", e),
+        }
+
+        Ok(settings)
     }
 
     pub fn load_preset<R: Read>(&mut self, read: R) -> Result<()> {
@@ -566,29 +685,14 @@ impl ProfileSettings {
                 Ok(Event::Empty(ref e)) => {
                     let (name, attributes) = wrap_start_event(e)?;
 
-                    match name.as_str() {
-                        "reverbEncoder" => self
-                            .reverb_encoder
-                            .parse_reverb_preset(current, &attributes)?,
-                        "echoEncoder" => {
-                            self.echo_encoder.parse_echo_preset(current, &attributes)?
-                        }
-                        "pitchEncoder" => self
-                            .pitch_encoder
-                            .parse_pitch_preset(current, &attributes)?,
-                        "genderEncoder" => self
-                            .gender_encoder
-                            .parse_gender_preset(current, &attributes)?,
-                        "megaphoneEffect" => self
-                            .megaphone_effect
-                            .parse_megaphone_preset(current, &attributes)?,
-                        "robotEffect" => {
-                            self.robot_effect.parse_robot_preset(current, &attributes)?
+                    let section = PresetSection::iter()
+                        .find(|section| section.get_str("tagName") == Some(name.as_str()));
+
+                    match section {
+                        Some(section) => {
+                            self.parse_preset_section(section, current, &attributes)?
                         }
-                        "hardtuneEffect" => self
-                            .hardtune_effect
-                            .parse_hardtune_preset(current, &attributes)?,
-                        _ => warn!("Unexpected Start Tag {}", name),
+                        None => warn!("Unexpected Start Tag {}", name),
                     }
                 }
 
@@ -629,7 +733,71 @@ impl ProfileSettings {
         self.write_to(out_file)
     }
 
-    pub fn write_to<W: Write>(&mut self, sink: W) -> Result<()> {
+    pub fn write_to<W: Write>(&mut self, mut sink: W) -> Result<()> {
+        let mut written = Vec::new();
+        match self.base_profile.clone() {
+            Some(base) => self.write_owned_to(&base, &mut written)?,
+            None => self.write_known_to(&mut written)?,
+        }
+
+        if self.unknown.is_empty() {
+            sink.write_all(&written)?;
+            return Ok(());
+        }
+        self.unknown.merge(&written, sink)
+    }
+
+    // A profile with a base only writes the sections it owns, everything else is left out and
+    // filled in from the base when it's loaded.
+    fn write_owned_to<W: Write>(&mut self, base: &BaseProfile, sink: W) -> Result<()> {
+        let mut written = Vec::new();
+        self.write_known_to(&mut written)?;
+
+        let inherited: Vec<BaseSection> = BaseSection::iter()
+            .filter(|section| !base.owns(*section))
+            .collect();
+        let values = self.section_values(&written, &inherited)?;
+
+        let mut writer = Writer::new_with_indent(sink, u8::try_from('\t')?, 1);
+        let mut skipping = 0;
+        visit_elements(&written, |event, path| {
+            match event {
+                Event::Start(_) if skipping > 0 || values.elements.contains_key(path) => {
+                    skipping += 1;
+                }
+                Event::End(_) if skipping > 0 => skipping -= 1,
+                Event::Empty(_) if skipping > 0 || values.elements.contains_key(path) => {}
+                Event::Start(e) => {
+                    let start = without_attributes(&e, values.attributes.get(path));
+                    writer.write_event(Event::Start(start))?;
+                }
+                Event::Empty(e) => {
+                    // An element left with nothing of its own isn't needed at all
+                    let inherited = values.attributes.get(path);
+                    let start = without_attributes(&e, inherited);
+                    if inherited.is_none() || start.attributes().next().is_some() {
+                        writer.write_event(Event::Empty(start))?;
+                    }
+                }
+
+                // The only text is the indentation, which the writer does itself
+                Event::Text(_) => {}
+                event => writer.write_event(event)?,
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Writes the profile for the official app, anything unknown (which can only have come from
+    /// a newer version of the utility) is left out along with the utility's own additions.
+    pub fn write_official_to<W: Write>(&mut self, sink: W) -> Result<()> {
+        let mut written = Vec::new();
+        self.write_known_to(&mut written)?;
+        strip_utility_content(&written, sink)
+    }
+
+    fn write_known_to<W: Write>(&mut self, sink: W) -> Result<()> {
         let mut writer = Writer::new_with_indent(sink, u8::try_from('\t')?, 1);
         writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
 
@@ -660,6 +828,10 @@ impl ProfileSettings {
 
         self.mix_routing.write_mix_tree(&mut writer)?;
         self.submix_tree.write_submixer(&mut writer)?;
+        self.volume_scenes.write_scenes(&mut writer)?;
+        if let Some(base_profile) = &self.base_profile {
+            base_profile.write_base(&mut writer)?;
+        }
 
         self.mixer.write_mixers(&mut writer)?;
         self.context.write_context(&mut writer)?;
@@ -735,64 +907,139 @@ impl ProfileSettings {
         let current = self.context().selected_effects();
         let preset_writer = PresetWriter::new(String::from(self.effects(current).name()));
         preset_writer.write_initial(&mut writer)?;
-        preset_writer.write_tag(
-            &mut writer,
-            "reverbEncoder",
-            self.reverb_encoder.get_preset_attributes(current),
-        )?;
+        for section in PresetSection::iter() {
+            preset_writer.write_tag(
+                &mut writer,
+                section.get_str("tagName").unwrap(),
+                self.get_preset_section_attributes(section, current),
+            )?;
+        }
 
-        preset_writer.write_tag(
-            &mut writer,
-            "echoEncoder",
-            self.echo_encoder.get_preset_attributes(current),
-        )?;
+        preset_writer.write_final(&mut writer)?;
+        Ok(())
+    }
 
-        preset_writer.write_tag(
-            &mut writer,
-            "pitchEncoder",
-            self.pitch_encoder.get_preset_attributes(current),
-        )?;
+    pub fn load_preset_section<R: Read>(&mut self, read: R, section: PresetSection) -> Result<()> {
+        let buf_reader = BufReader::new(read);
+        let mut reader = Reader::from_reader(buf_reader);
 
-        preset_writer.write_tag(
-            &mut writer,
-            "genderEncoder",
-            self.gender_encoder.get_preset_attributes(current),
-        )?;
+        // Unlike a full preset, we don't touch the name of the current preset here, we simply
+        // look for the section's tag and replace the current values with it.
+        let current = self.context().selected_effects();
+        let tag_name = section.get_str("tagName").unwrap();
+        let mut found = false;
 
-        preset_writer.write_tag(
-            &mut writer,
-            "megaphoneEffect",
-            self.megaphone_effect.get_preset_attributes(current),
-        )?;
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Empty(ref e)) => {
+                    let (name, attributes) = wrap_start_event(e)?;
+                    if name == tag_name {
+                        self.parse_preset_section(section, current, &attributes)?;
+                        found = true;
+                    }
+                }
+                Ok(Event::Eof) => {
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => bail!("Unable to parse Preset Section: {}", e),
+            }
+        }
 
-        preset_writer.write_tag(
-            &mut writer,
-            "robotEffect",
-            self.robot_effect.get_preset_attributes(current),
-        )?;
+        if !found {
+            bail!("Preset Section {} not found in file", tag_name);
+        }
+        Ok(())
+    }
 
+    pub fn write_preset_section<P: AsRef<Path>>(
+        &self,
+        path: P,
+        section: PresetSection,
+        name: String,
+    ) -> Result<()> {
+        let out_file = File::create(path)?;
+        self.write_preset_section_to(&out_file, section, name)?;
+        out_file.sync_all().context("Unable to Sync File")
+    }
+
+    pub fn write_preset_section_to<W: Write>(
+        &self,
+        sink: W,
+        section: PresetSection,
+        name: String,
+    ) -> Result<()> {
+        let mut writer = Writer::new_with_indent(sink, u8::try_from('\t')?, 1);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+        let current = self.context().selected_effects();
+        let preset_writer = PresetWriter::new(name);
+        preset_writer.write_initial(&mut writer)?;
         preset_writer.write_tag(
             &mut writer,
-            "hardtuneEffect",
-            self.hardtune_effect.get_preset_attributes(current),
+            section.get_str("tagName").unwrap(),
+            self.get_preset_section_attributes(section, current),
         )?;
-
         preset_writer.write_final(&mut writer)?;
         Ok(())
     }
 
+    fn get_preset_section_attributes(
+        &self,
+        section: PresetSection,
+        current: Preset,
+    ) -> BTreeMap<String, String> {
+        match section {
+            PresetSection::Reverb => self.reverb_encoder.get_preset_attributes(current),
+            PresetSection::Echo => self.echo_encoder.get_preset_attributes(current),
+            PresetSection::Pitch => self.pitch_encoder.get_preset_attributes(current),
+            PresetSection::Gender => self.gender_encoder.get_preset_attributes(current),
+            PresetSection::Megaphone => self.megaphone_effect.get_preset_attributes(current),
+            PresetSection::Robot => self.robot_effect.get_preset_attributes(current),
+            PresetSection::HardTune => self.hardtune_effect.get_preset_attributes(current),
+        }
+    }
+
+    fn parse_preset_section(
+        &mut self,
+        section: PresetSection,
+        current: Preset,
+        attributes: &Vec<Attribute>,
+    ) -> Result<()> {
+        match section {
+            PresetSection::Reverb => self
+                .reverb_encoder
+                .parse_reverb_preset(current, attributes)?,
+            PresetSection::Echo => self.echo_encoder.parse_echo_preset(current, attributes)?,
+            PresetSection::Pitch => self.pitch_encoder.parse_pitch_preset(current, attributes)?,
+            PresetSection::Gender => self
+                .gender_encoder
+                .parse_gender_preset(current, attributes)?,
+            PresetSection::Megaphone => self
+                .megaphone_effect
+                .parse_megaphone_preset(current, attributes)?,
+            PresetSection::Robot => self.robot_effect.parse_robot_preset(current, attributes)?,
+            PresetSection::HardTune => self
+                .hardtune_effect
+                .parse_hardtune_preset(current, attributes)?,
+        }
+        Ok(())
+    }
+
     pub fn parse_preset(key: String) -> Result<Preset> {
         if let Some(id) = key
             .chars()
             .last()
-            .map(|s| u8::from_str(&s.to_string()))
+            .map(|s| usize::from_str(&s.to_string()))
             .transpose()?
         {
-            if let Some(preset) = Preset::iter().nth((id - 1) as usize) {
+            // Presets are numbered from 1
+            if let Some(preset) = id.checked_sub(1).and_then(|id| Preset::iter().nth(id)) {
                 return Ok(preset);
             }
         }
-        Err(anyhow!("Unable to Parse Preset from Number"))
+        Err(anyhow!("Unable to Parse Preset from {}", key))
     }
 
     pub fn animation(&self) -> &AnimationTree {
@@ -950,12 +1197,280 @@ impl ProfileSettings {
         &mut self.submix_tree
     }
 
+    pub fn is_from_newer_version(&self) -> bool {
+        self.root.is_from_newer_version()
+    }
+
+    pub fn get_version(&self) -> u8 {
+        self.root.get_version()
+    }
+
+    pub fn get_utility_version(&self) -> Option<&str> {
+        self.root.get_utility_version()
+    }
+
+    pub fn volume_scenes(&self) -> &VolumeScenes {
+        &self.volume_scenes
+    }
+    pub fn volume_scenes_mut(&mut self) -> &mut VolumeScenes {
+        &mut self.volume_scenes
+    }
+
     pub fn mix_routing(&self) -> &MixRoutingTree {
         &self.mix_routing
     }
     pub fn mix_routing_mut(&mut self) -> &mut MixRoutingTree {
         &mut self.mix_routing
     }
+
+    pub fn base_profile(&self) -> Option<&BaseProfile> {
+        self.base_profile.as_ref()
+    }
+    pub fn set_base_profile(&mut self, base_profile: Option<BaseProfile>) {
+        self.base_profile = base_profile;
+    }
+
+    /// Swaps a section of this profile with the same section of another. The colours of the
+    /// faders, buttons and encoders belong to the lighting section, so they only move with it.
+    pub fn swap_section(&mut self, section: BaseSection, other: &mut ProfileSettings) {
+        // For lighting this is the whole job, for any other section it means the colours are
+        // back where they started once the section itself has been swapped.
+        let theirs = other.colour_maps_mut(section);
+        for (ours, theirs) in self.colour_maps_mut(section).into_iter().zip(theirs) {
+            mem::swap(ours, theirs);
+        }
+
+        match section {
+            BaseSection::Lighting => {
+                mem::swap(&mut self.animation_tree, &mut other.animation_tree);
+            }
+            BaseSection::Routing => {
+                mem::swap(self.mixer.mixer_table_mut(), other.mixer.mixer_table_mut());
+                mem::swap(&mut self.mix_routing, &mut other.mix_routing);
+                mem::swap(
+                    self.submix_tree.monitor_tree_mut(),
+                    other.submix_tree.monitor_tree_mut(),
+                );
+            }
+            BaseSection::Faders => {
+                mem::swap(&mut self.faders, &mut other.faders);
+                mem::swap(&mut self.mute_buttons, &mut other.mute_buttons);
+                mem::swap(&mut self.scribbles, &mut other.scribbles);
+            }
+            BaseSection::Effects => {
+                mem::swap(&mut self.effects, &mut other.effects);
+                mem::swap(&mut self.megaphone_effect, &mut other.megaphone_effect);
+                mem::swap(&mut self.robot_effect, &mut other.robot_effect);
+                mem::swap(&mut self.hardtune_effect, &mut other.hardtune_effect);
+                mem::swap(&mut self.reverb_encoder, &mut other.reverb_encoder);
+                mem::swap(&mut self.echo_encoder, &mut other.echo_encoder);
+                mem::swap(&mut self.pitch_encoder, &mut other.pitch_encoder);
+                mem::swap(&mut self.gender_encoder, &mut other.gender_encoder);
+            }
+            BaseSection::Sampler => {
+                mem::swap(&mut self.sampler_map, &mut other.sampler_map);
+            }
+        }
+    }
+
+    /// Whether a section of this profile is the same as it is in another, this is checked by
+    /// swapping the section in and comparing what would be written.
+    pub fn section_matches(
+        &mut self,
+        section: BaseSection,
+        other: &mut ProfileSettings,
+    ) -> Result<bool> {
+        let ours = self.known_elements();
+        self.swap_section(section, other);
+        let swapped = self.known_elements();
+        self.swap_section(section, other);
+        Ok(ours? == swapped?)
+    }
+
+    // The colour maps of everything in a section, always in the same order
+    fn colour_maps_mut(&mut self, section: BaseSection) -> Vec<&mut ColourMap> {
+        let lighting = section == BaseSection::Lighting;
+        let mut maps = vec![];
+
+        if lighting || section == BaseSection::Faders {
+            maps.extend(self.faders.values_mut().map(Fader::colour_map_mut));
+            maps.extend(
+                self.mute_buttons
+                    .values_mut()
+                    .map(MuteButton::colour_map_mut),
+            );
+            maps.extend(self.scribbles.values_mut().map(Scribble::colour_map_mut));
+        }
+
+        if lighting || section == BaseSection::Effects {
+            maps.extend(self.effects.values_mut().map(Effects::colour_map_mut));
+            maps.push(self.megaphone_effect.colour_map_mut());
+            maps.push(self.robot_effect.colour_map_mut());
+            maps.push(self.hardtune_effect.colour_map_mut());
+            maps.push(self.reverb_encoder.colour_map_mut());
+            maps.push(self.echo_encoder.colour_map_mut());
+            maps.push(self.pitch_encoder.colour_map_mut());
+            maps.push(self.gender_encoder.colour_map_mut());
+        }
+
+        if lighting || section == BaseSection::Sampler {
+            maps.extend(
+                self.sampler_map
+                    .values_mut()
+                    .map(SampleBase::colour_map_mut),
+            );
+        }
+
+        if lighting {
+            maps.extend(
+                self.simple_elements
+                    .values_mut()
+                    .map(SimpleElement::colour_map_mut),
+            );
+            maps.push(self.mute_chat.colour_map_mut());
+        }
+        maps
+    }
+
+    /// Takes the values of a section as they are now, so they can be checked for changes later.
+    pub fn snapshot_section(&mut self, section: BaseSection) -> Result<SectionValues> {
+        let mut written = Vec::new();
+        self.write_known_to(&mut written)?;
+        self.section_values(&written, &[section])
+    }
+
+    // Finds what in the written profile belongs to the sections, by replacing them with their
+    // defaults and seeing what changes. Values which happen to match the defaults aren't found,
+    // but they'd be the same either way.
+    fn section_values(
+        &mut self,
+        written: &[u8],
+        sections: &[BaseSection],
+    ) -> Result<SectionValues> {
+        let mut empty = ProfileSettings::load(EMPTY_PROFILE)?;
+        for section in sections {
+            self.swap_section(*section, &mut empty);
+        }
+        let mut replaced = Vec::new();
+        let result = self.write_known_to(&mut replaced);
+        for section in sections {
+            self.swap_section(*section, &mut empty);
+        }
+        result?;
+
+        let mut defaults = HashMap::new();
+        visit_elements(&replaced, |event, path| {
+            if let Event::Start(e) | Event::Empty(e) = event {
+                defaults.insert(path.to_string(), get_attributes(&e));
+            }
+            Ok(())
+        })?;
+
+        let mut values = SectionValues::default();
+        visit_elements(written, |event, path| {
+            if let Event::Start(e) | Event::Empty(e) = event {
+                let attributes = get_attributes(&e);
+                match defaults.get(path) {
+                    None => {
+                        values.elements.insert(path.to_string(), attributes);
+                    }
+                    Some(replaced) => {
+                        let changed: Vec<RawAttribute> = attributes
+                            .into_iter()
+                            .filter(|attribute| !replaced.contains(attribute))
+                            .collect();
+                        if !changed.is_empty() {
+                            values.attributes.insert(path.to_string(), changed);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(values)
+    }
+
+    // Every element we'd write, with the attributes in a consistent order
+    fn known_elements(&mut self) -> Result<Vec<KnownElement>> {
+        let mut written = Vec::new();
+        self.write_known_to(&mut written)?;
+
+        let mut reader = Reader::from_reader(written.as_slice());
+        let mut elements = vec![];
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) => {
+                    let (name, attributes) = wrap_start_event(&e)?;
+                    let mut attributes: Vec<(String, String)> = attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name, attribute.value))
+                        .collect();
+                    attributes.sort();
+                    elements.push((name, attributes));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(elements)
+    }
+}
+
+// Reads through written XML, passing each event along with the path of the element it belongs
+// to. Paths are built from each element's name, and how many with that name came before it.
+fn visit_elements(xml: &[u8], mut visit: impl FnMut(Event, &str) -> Result<()>) -> Result<()> {
+    let mut reader = Reader::from_reader(xml);
+    let mut stack: Vec<(String, HashMap<String, usize>)> = vec![(String::new(), HashMap::new())];
+
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                let (parent, counts) = stack.last_mut().context("Unexpected element")?;
+                let count = counts.entry(name.clone()).or_default();
+                let path = format!("{}/{}#{}", parent, name, count);
+                *count += 1;
+
+                let children = matches!(event, Event::Start(_));
+                visit(event, &path)?;
+                if children {
+                    stack.push((path, HashMap::new()));
+                }
+            }
+            Event::End(_) => {
+                if stack.len() < 2 {
+                    bail!("Unexpected closing tag");
+                }
+                if let Some((path, _)) = stack.pop() {
+                    visit(event, &path)?;
+                }
+            }
+            Event::Eof => break,
+            event => visit(event, "")?,
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+// A copy of an element, without the attributes which belong to something else
+fn without_attributes(
+    start: &BytesStart,
+    remove: Option<&Vec<RawAttribute>>,
+) -> BytesStart<'static> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).to_string();
+    let mut kept = BytesStart::new(name);
+    for attribute in start.attributes().flatten() {
+        let raw = (attribute.key.as_ref().to_vec(), attribute.value.to_vec());
+        if !remove.is_some_and(|remove| remove.contains(&raw)) {
+            kept.push_attribute(attribute);
+        }
+    }
+    kept
 }
 
 /// This will wrap a 'Start' XML event into a name, and attribute Vec. We're using
@@ -0,0 +1,291 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesDecl, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+/**
+ * Profiles may be written by a newer version of the utility, containing elements and attributes
+ * that we don't understand. Rather than dropping these when saving, we work out what they are on
+ * load (by comparing the original XML against what we would have written), then add them back
+ * into anything we write.
+ */
+// An attribute's name, and its value as it appeared in the XML (still escaped)
+pub(crate) type RawAttribute = (Vec<u8>, Vec<u8>);
+
+// Unknown nodes are placed by the path of their parent, and the path of the known element they
+// followed (None if they came before all of them)
+type Position = (String, Option<String>);
+
+#[derive(Debug, Default)]
+pub struct UnknownContent {
+    // Keyed by the path of the element they belong to
+    attributes: HashMap<String, Vec<RawAttribute>>,
+    nodes: HashMap<Position, Vec<Node>>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Element(Element),
+
+    // Text, CDATA, comments and processing instructions, kept exactly as they were read
+    Other(Event<'static>),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    start: BytesStart<'static>,
+    children: Vec<Node>,
+    empty: bool,
+}
+
+impl UnknownContent {
+    /// Finds everything in the original XML that isn't present in what we wrote from it.
+    pub fn find(original: &[u8], written: &[u8]) -> Result<Self> {
+        let mut unknown = Self::default();
+
+        let original = parse_document(original)?;
+        let written = parse_document(written)?;
+        if get_root_name(&original).is_some() && get_root_name(&original) == get_root_name(&written)
+        {
+            unknown.collect_children(&original, &written, "");
+        }
+        Ok(unknown)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.nodes.is_empty()
+    }
+
+    /// Writes out the XML, with the unknown content merged back in.
+    pub fn merge<W: Write>(&self, written: &[u8], sink: W) -> Result<()> {
+        let mut document = parse_document(written)?;
+        if get_root_name(&document).is_none() {
+            bail!("Unable to parse written profile");
+        }
+        self.merge_children(&mut document, "")?;
+
+        let mut writer = Writer::new_with_indent(sink, u8::try_from('\t')?, 1);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        for node in &document {
+            write_node(&mut writer, node)?;
+        }
+        Ok(())
+    }
+
+    fn collect(&mut self, original: &Element, written: &Element, path: &str) {
+        let known: HashSet<Vec<u8>> = get_attributes(&written.start)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+
+        for (key, value) in get_attributes(&original.start) {
+            if !known.contains(&key) {
+                self.attributes
+                    .entry(path.to_string())
+                    .or_default()
+                    .push((key, value));
+            }
+        }
+        self.collect_children(&original.children, &written.children, path);
+    }
+
+    fn collect_children(&mut self, original: &[Node], written: &[Node], path: &str) {
+        let written = with_paths(written, path);
+
+        let mut after = None;
+        for (child, child_path) in with_paths(original, path) {
+            let known = match (child, &child_path) {
+                (Node::Element(element), Some(child_path)) => written
+                    .iter()
+                    .find(|(_, written_path)| written_path.as_ref() == Some(child_path))
+                    .and_then(|(node, _)| match node {
+                        Node::Element(written) => Some((element, written)),
+                        Node::Other(_) => None,
+                    }),
+                _ => None,
+            };
+
+            match (known, child_path) {
+                (Some((element, written)), Some(child_path)) => {
+                    self.collect(element, written, &child_path);
+                    after = Some(child_path);
+                }
+                _ => self
+                    .nodes
+                    .entry((path.to_string(), after.clone()))
+                    .or_default()
+                    .push(child.clone()),
+            }
+        }
+    }
+
+    fn merge_element(&self, element: &mut Element, path: &str) -> Result<()> {
+        if let Some(attributes) = self.attributes.get(path) {
+            for (key, value) in attributes {
+                element.start.push_attribute(Attribute {
+                    key: quick_xml::name::QName(key),
+                    value: value.as_slice().into(),
+                });
+            }
+        }
+
+        self.merge_children(&mut element.children, path)?;
+        if !element.children.is_empty() {
+            element.empty = false;
+        }
+        Ok(())
+    }
+
+    fn merge_children(&self, children: &mut Vec<Node>, path: &str) -> Result<()> {
+        let paths: Vec<Option<String>> = with_paths(children, path)
+            .into_iter()
+            .map(|(_, child_path)| child_path)
+            .collect();
+        let known = std::mem::take(children);
+
+        let mut placed = HashSet::new();
+        let mut place = |children: &mut Vec<Node>, after: Option<String>| {
+            let position = (path.to_string(), after);
+            if let Some(nodes) = self.nodes.get(&position) {
+                children.extend(nodes.iter().cloned());
+            }
+            placed.insert(position);
+        };
+
+        place(children, None);
+        for (mut child, child_path) in known.into_iter().zip(paths) {
+            if let (Node::Element(element), Some(child_path)) = (&mut child, &child_path) {
+                self.merge_element(element, child_path)?;
+            }
+            children.push(child);
+            if child_path.is_some() {
+                place(children, child_path);
+            }
+        }
+
+        // Anything which followed an element that's no longer written goes at the end
+        let mut remaining: Vec<&Position> = self
+            .nodes
+            .keys()
+            .filter(|position| position.0 == path && !placed.contains(*position))
+            .collect();
+        remaining.sort();
+        for position in remaining {
+            children.extend(self.nodes[position].iter().cloned());
+        }
+        Ok(())
+    }
+}
+
+// The top level nodes of the document, the declaration is left out as we always write our own,
+// as is any whitespace between elements, which the writer's indentation replaces.
+fn parse_document(data: &[u8]) -> Result<Vec<Node>> {
+    let mut reader = Reader::from_reader(data);
+    let mut stack: Vec<Element> = vec![];
+    let mut document = vec![];
+
+    let mut buf = Vec::new();
+    loop {
+        let node = match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                stack.push(Element {
+                    start: e.into_owned(),
+                    children: vec![],
+                    empty: false,
+                });
+                None
+            }
+            Event::Empty(e) => Some(Node::Element(Element {
+                start: e.into_owned(),
+                children: vec![],
+                empty: true,
+            })),
+            Event::End(_) => match stack.pop() {
+                Some(element) => Some(Node::Element(element)),
+                None => bail!("Unexpected closing tag"),
+            },
+            Event::Text(e) if e.iter().all(u8::is_ascii_whitespace) => None,
+            Event::Decl(_) => None,
+            Event::Eof => break,
+            event => Some(Node::Other(event.into_owned())),
+        };
+
+        if let Some(node) = node {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => document.push(node),
+            }
+        }
+        buf.clear();
+    }
+
+    if !stack.is_empty() {
+        bail!("Missing closing tag");
+    }
+    Ok(document)
+}
+
+fn write_node<W: Write>(writer: &mut Writer<W>, node: &Node) -> Result<()> {
+    let element = match node {
+        Node::Element(element) => element,
+        Node::Other(event) => {
+            writer.write_event(event.clone())?;
+            return Ok(());
+        }
+    };
+
+    if element.empty {
+        writer.write_event(Event::Empty(element.start.borrow()))?;
+        return Ok(());
+    }
+
+    writer.write_event(Event::Start(element.start.borrow()))?;
+    for child in &element.children {
+        write_node(writer, child)?;
+    }
+    writer.write_event(Event::End(element.start.to_end()))?;
+    Ok(())
+}
+
+// Some elements (such as volumeScene) can appear more than once, so the path of an element
+// includes how many elements with the same name came before it. Other nodes don't have a path,
+// and aren't counted.
+fn with_paths<'a>(nodes: &'a [Node], parent: &str) -> Vec<(&'a Node, Option<String>)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Element(element) => {
+                let name = get_name(&element.start);
+                let count = seen.entry(name.clone()).or_default();
+                let path = format!("{}/{}#{}", parent, name, count);
+                *count += 1;
+                (node, Some(path))
+            }
+            Node::Other(_) => (node, None),
+        })
+        .collect()
+}
+
+fn get_root_name(document: &[Node]) -> Option<String> {
+    document.iter().find_map(|node| match node {
+        Node::Element(element) => Some(get_name(&element.start)),
+        Node::Other(_) => None,
+    })
+}
+
+fn get_name(start: &BytesStart) -> String {
+    String::from_utf8_lossy(start.name().as_ref()).to_string()
+}
+
+// The values are kept raw, so they're written back exactly as they were read.
+pub(crate) fn get_attributes(start: &BytesStart) -> Vec<RawAttribute> {
+    start
+        .attributes()
+        .flatten()
+        .map(|attribute| (attribute.key.as_ref().to_vec(), attribute.value.to_vec()))
+        .collect()
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use goxlr_profile_loader::components::mixer::{FullChannelList, InputChannels};
+use goxlr_profile_loader::components::volume_scenes::VolumeScene;
+use goxlr_profile_loader::profile::ProfileSettings;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+// Elements the official app's fixture predates, which we fill with defaults
+const SYNTHESISED: [&str; 5] = [
+    "animationTree",
+    "linkingTree",
+    "mixRoutingTree",
+    "monitorTree",
+    "submixerTree",
+];
+
+fn read(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data")
+        .join(name);
+    fs::read(path).expect("test data should exist")
+}
+
+fn export(xml: &[u8]) -> Result<Vec<u8>> {
+    let mut settings = ProfileSettings::load(xml)?;
+    let mut exported = Vec::new();
+    settings.write_official_to(&mut exported)?;
+    Ok(exported)
+}
+
+/// Every element in the XML by name, and every attribute as element@attribute.
+fn coverage(xml: &[u8]) -> Result<BTreeSet<String>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut found = BTreeSet::new();
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                for attribute in e.attributes() {
+                    let key = String::from_utf8_lossy(attribute?.key.as_ref()).to_string();
+                    found.insert(format!("{}@{}", name, key));
+                }
+                found.insert(name);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(found)
+}
+
+#[test]
+fn official_profile_round_trips() {
+    let original = read("profile.xml");
+    let exported = export(&original).expect("profile.xml should export");
+
+    let original = coverage(&original).unwrap();
+    let exported = coverage(&exported).unwrap();
+
+    let lost: Vec<&String> = original.difference(&exported).collect();
+    assert!(lost.is_empty(), "Not preserved: {:?}", lost);
+
+    // Anything new is either a whole element the fixture didn't have, or a button's blink and
+    // lighting state
+    let synthesised: Vec<&String> = exported
+        .difference(&original)
+        .filter(|entry| {
+            let element = entry.split('@').next().unwrap_or_default();
+            !SYNTHESISED.contains(&element)
+                && !entry.ends_with("blink")
+                && !entry.ends_with("state")
+        })
+        .collect();
+    assert!(
+        synthesised.is_empty(),
+        "Unexpectedly synthesised: {:?}",
+        synthesised
+    );
+}
+
+#[test]
+fn sparse_profile_exports_every_element() {
+    let official = coverage(&read("profile.xml")).unwrap();
+    let exported = export(&read("missing.xml")).expect("missing.xml should export");
+    let exported = coverage(&exported).unwrap();
+
+    let missing: Vec<&String> = official
+        .iter()
+        .filter(|entry| !entry.contains('@') && !exported.contains(*entry))
+        .collect();
+    assert!(missing.is_empty(), "Elements not written: {:?}", missing);
+
+    for element in ["selectedContext", "AppTree", "browserPreviewTree"] {
+        assert!(exported.contains(element), "{} not written", element);
+    }
+}
+
+#[test]
+fn utility_additions_are_removed() {
+    let mut settings = ProfileSettings::load(read("profile.xml").as_slice()).unwrap();
+    settings
+        .mixer_mut()
+        .set_low_cut(InputChannels::LineIn, Some(80));
+    settings.mixer_mut().set_headphone_eq_gain(5, Some(3));
+
+    let mut scene = VolumeScene::new(String::from("Streaming"));
+    scene.set_volume(FullChannelList::Music, 100);
+    settings.volume_scenes_mut().set_scene(scene);
+
+    let mut saved = Vec::new();
+    settings.write_to(&mut saved).unwrap();
+    let mut exported = Vec::new();
+    settings.write_official_to(&mut exported).unwrap();
+
+    let saved = coverage(&saved).unwrap();
+    let exported = coverage(&exported).unwrap();
+    for entry in [
+        "ValueTreeRoot@utilityVersion",
+        "mixerTree@lineInLowCut",
+        "mixerTree@headphones1KHzEqGain",
+        "volumeScene",
+    ] {
+        assert!(saved.contains(entry), "{} not saved", entry);
+        assert!(!exported.contains(entry), "{} exported", entry);
+    }
+
+    // Nothing else should differ between the two
+    let removed: Vec<&String> = saved
+        .difference(&exported)
+        .filter(|entry| !entry.starts_with("volumeScene"))
+        .collect();
+    assert_eq!(removed.len(), 3, "Removed: {:?}", removed);
+}
@@ -0,0 +1,126 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use goxlr_profile_loader::components::base_profile::BaseProfile;
+use goxlr_profile_loader::components::colours::Colour;
+use goxlr_profile_loader::components::mixer::FullChannelList;
+use goxlr_profile_loader::profile::ProfileSettings;
+use goxlr_profile_loader::{BaseSection, Faders};
+use strum::IntoEnumIterator;
+
+fn load(name: &str) -> Result<ProfileSettings> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data")
+        .join(name);
+    ProfileSettings::load(File::open(path)?)
+}
+
+/// The full profile, with fader A's channel and colour changed.
+fn variant() -> Result<ProfileSettings> {
+    let mut settings = load("profile.xml")?;
+    let fader = settings.fader_mut(Faders::A);
+    fader.set_channel(FullChannelList::Sample);
+    fader
+        .colour_map_mut()
+        .set_colour(0, Colour::new("FF123456")?);
+    Ok(settings)
+}
+
+#[test]
+fn base_profile_round_trips() -> Result<()> {
+    let mut settings = load("profile.xml")?;
+    let base = BaseProfile::new(
+        "Streaming".to_string(),
+        vec![BaseSection::Faders, BaseSection::Lighting],
+    );
+    settings.set_base_profile(Some(base));
+
+    let mut written = Vec::new();
+    settings.write_to(&mut written)?;
+    let reloaded = ProfileSettings::load(written.as_slice())?;
+
+    let base = reloaded
+        .base_profile()
+        .expect("base profile should be kept");
+    assert_eq!(base.name(), "Streaming");
+    for section in BaseSection::iter() {
+        let expected = matches!(section, BaseSection::Faders | BaseSection::Lighting);
+        assert_eq!(base.owns(section), expected, "{}", section);
+    }
+    Ok(())
+}
+
+#[test]
+fn swapping_a_section_leaves_its_lighting() -> Result<()> {
+    let mut settings = load("profile.xml")?;
+    let mut other = variant()?;
+    let original = settings.fader(Faders::A).colour_map().colour(0).to_argb();
+
+    settings.swap_section(BaseSection::Faders, &mut other);
+    assert!(matches!(
+        settings.fader(Faders::A).channel(),
+        FullChannelList::Sample
+    ));
+    assert_eq!(
+        settings.fader(Faders::A).colour_map().colour(0).to_argb(),
+        original
+    );
+
+    settings.swap_section(BaseSection::Lighting, &mut other);
+    assert_eq!(
+        settings.fader(Faders::A).colour_map().colour(0).to_argb(),
+        "FF123456"
+    );
+    assert_eq!(
+        other.fader(Faders::A).colour_map().colour(0).to_argb(),
+        original
+    );
+    Ok(())
+}
+
+#[test]
+fn only_changed_sections_differ() -> Result<()> {
+    let mut settings = load("profile.xml")?;
+    let mut other = variant()?;
+
+    for section in BaseSection::iter() {
+        let changed = matches!(section, BaseSection::Faders | BaseSection::Lighting);
+        assert_eq!(
+            settings.section_matches(section, &mut other)?,
+            !changed,
+            "{}",
+            section
+        );
+    }
+
+    // Checking shouldn't have moved anything
+    let channel = settings.fader(Faders::A).channel();
+    assert!(!matches!(channel, FullChannelList::Sample));
+    let channel = other.fader(Faders::A).channel();
+    assert!(matches!(channel, FullChannelList::Sample));
+    Ok(())
+}
+
+#[test]
+fn children_only_write_what_they_own() -> Result<()> {
+    let mut settings = variant()?;
+    let base = BaseProfile::new("Streaming".to_string(), vec![BaseSection::Lighting]);
+    settings.set_base_profile(Some(base));
+
+    let mut written = Vec::new();
+    settings.write_to(&mut written)?;
+    let reloaded = ProfileSettings::load(written.as_slice())?;
+
+    // The colour is kept, but the channel comes from the base so it's left at the default
+    assert_eq!(
+        reloaded.fader(Faders::A).colour_map().colour(0).to_argb(),
+        "FF123456"
+    );
+    assert!(matches!(
+        reloaded.fader(Faders::A).channel(),
+        FullChannelList::Mic
+    ));
+    assert!(reloaded.base_profile().is_some());
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use goxlr_profile_loader::components::mute::MuteFunction;
+use goxlr_profile_loader::profile::ProfileSettings;
+use goxlr_profile_loader::{Faders, Preset};
+
+fn load(name: &str) -> Result<ProfileSettings> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data")
+        .join(name);
+    ProfileSettings::load(File::open(path)?)
+}
+
+#[test]
+fn loads_the_full_profile() {
+    let settings = load("profile.xml").expect("profile.xml should load");
+    assert_eq!(settings.mute_button(Faders::A).previous_volume(), 158);
+}
+
+#[test]
+fn elements_and_colours_out_of_order() {
+    let settings = load("reordered.xml").expect("reordered.xml should load");
+
+    let mute_a = settings.mute_button(Faders::A);
+    assert_eq!(*mute_a.mute_function(), MuteFunction::All);
+    assert_eq!(mute_a.colour_map().colour(0).to_argb(), "FF00FF00");
+    assert_eq!(mute_a.colour_map().colour(1).to_argb(), "FFFFFFFF");
+
+    let mute_d = settings.mute_button(Faders::D);
+    assert_eq!(*mute_d.mute_function(), MuteFunction::ToStream);
+    assert_eq!(mute_d.colour_map().colour(0).to_argb(), "FF0000FF");
+
+    let fader = settings.fader(Faders::D).colour_map();
+    assert_eq!(fader.colour(0).to_argb(), "FFFF0055");
+    assert_eq!(fader.colour(1).to_argb(), "FF00FFFF");
+
+    assert_eq!(settings.effects(Preset::Preset1).name(), "First");
+    assert_eq!(settings.effects(Preset::Preset3).name(), "Third");
+    assert_eq!(
+        settings.scribble(Faders::C).text_bottom_middle().as_deref(),
+        Some("Music")
+    );
+}
+
+#[test]
+fn missing_elements_and_colours() {
+    let settings = load("missing.xml").expect("missing.xml should load");
+
+    let mute_b = settings.mute_button(Faders::B);
+    assert_eq!(*mute_b.mute_function(), MuteFunction::ToVoiceChat);
+    assert_eq!(mute_b.colour_map().colour(1).to_argb(), "FFFFFFFF");
+
+    // Colours which were never set (or can't exist) fall back to the default
+    assert_eq!(mute_b.colour_map().colour(2).to_argb(), "00000000");
+    assert_eq!(mute_b.colour_map().colour(5).to_argb(), "00000000");
+    assert_eq!(settings.effects(Preset::Preset6).name(), "Sixth");
+}
+
+#[test]
+fn colour_index_out_of_range() {
+    let error = load("bad-colour-index.xml").expect_err("colour5 should be rejected");
+    assert!(format!("{:#}", error).contains("mute1colour5"));
+}
+
+#[test]
+fn mute_function_out_of_range() {
+    let error = load("bad-mute-function.xml").expect_err("a mute source of 9 should be rejected");
+    assert!(format!("{:#}", error).contains("coughButtonMuteSourceSelection"));
+}
+
+#[test]
+fn zero_indexed_mixes_and_presets() {
+    // Mixes and presets are numbered from 1, a 0 is ignored rather than underflowing
+    load("bad-values.xml").expect("bad-values.xml should load");
+    assert!(ProfileSettings::parse_preset(String::from("reverbEncoderpreset0")).is_err());
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use goxlr_profile_loader::profile::ProfileSettings;
+
+fn read(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data")
+        .join(name);
+    fs::read(path).expect("test data should exist")
+}
+
+fn round_trip(xml: &[u8]) -> Result<String> {
+    let mut settings = ProfileSettings::load(xml)?;
+    let mut written = Vec::new();
+    settings.write_to(&mut written)?;
+    Ok(String::from_utf8(written)?)
+}
+
+// Everything written after the end of the named element's (empty) tag
+fn following<'a>(xml: &'a str, element: &str) -> &'a str {
+    let start = xml
+        .find(&format!("<{} ", element))
+        .expect("element is written");
+    let end = start + xml[start..].find("/>").expect("element is empty") + 2;
+    xml[end..].trim_start()
+}
+
+#[test]
+fn unknown_content_round_trips() -> Result<()> {
+    let first = round_trip(&read("unknown.xml"))?;
+    let second = round_trip(first.as_bytes())?;
+    assert_eq!(first, second);
+
+    assert!(first.contains("<!-- Saved by a newer version of the utility -->"));
+    assert!(first.contains(" futureRootSetting=\"1\""));
+    assert!(first.contains(" mute2futureSetting=\"Yes &amp; No\""));
+
+    // Nodes stay after the element they followed, with text and CDATA written as they were
+    let after_mute = following(&first, "mute2");
+    assert!(after_mute.starts_with("<!-- Mute 2 settings end here -->"));
+    assert!(after_mute
+        .trim_start_matches("<!-- Mute 2 settings end here -->")
+        .trim_start()
+        .starts_with("<futureTree enabled=\"1\">"));
+    assert!(first.contains("<futureNote>Some &lt;escaped&gt; text</futureNote>"));
+    assert!(first.contains("<futureData><![CDATA[raw <data> & more]]></futureData>"));
+    Ok(())
+}
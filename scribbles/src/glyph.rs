@@ -0,0 +1,219 @@
+use ab_glyph::{Font, FontVec, PxScale};
+use anyhow::{bail, Result};
+use image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+use imageproc::drawing::{draw_text_mut, text_size};
+use log::debug;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::FONT;
+
+// Glyphs are drawn large and scaled down into the icon slot, the same as an image would be
+const GLYPH_SIZE: u32 = 96;
+const MAX_GLYPH_CHARS: usize = 8;
+
+// How far into the font directories to look, some distributions nest them by vendor and family
+const MAX_FONT_DEPTH: usize = 4;
+
+// Finding a font for a glyph can mean opening every font on the system, so each is only drawn once
+static GLYPH_CACHE: Mutex<BTreeMap<(String, u32), DynamicImage>> = Mutex::new(BTreeMap::new());
+
+enum Coverage {
+    Outlined,
+    ColourOnly,
+    Missing,
+}
+
+/// Draws the glyph(s) black on white, cropped to what was drawn, ready to be used as an icon.
+pub(crate) fn render_glyph(glyph: &str) -> Result<DynamicImage> {
+    let text = get_glyph_text(glyph)?;
+
+    let key = (text.clone(), GLYPH_SIZE);
+    if let Some(image) = GLYPH_CACHE.lock().unwrap().get(&key) {
+        return Ok(image.clone());
+    }
+
+    let font = find_font(&text)?;
+    let image = draw_glyph(&font, &text);
+    GLYPH_CACHE.lock().unwrap().insert(key, image.clone());
+    Ok(image)
+}
+
+fn get_glyph_text(glyph: &str) -> Result<String> {
+    // Emoji presentation selectors and joiners have no outline of their own, so are dropped
+    let text: String = glyph
+        .chars()
+        .filter(|c| !matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{200D}'))
+        .collect();
+    let text = text.trim();
+
+    if text.is_empty() {
+        bail!("No glyph was provided");
+    }
+    if text.chars().count() > MAX_GLYPH_CHARS {
+        bail!("Glyphs can be at most {} characters", MAX_GLYPH_CHARS);
+    }
+    Ok(text.to_string())
+}
+
+fn find_font(text: &str) -> Result<FontVec> {
+    let mut colour_only = false;
+
+    // Our own font first, so anything it has looks the same everywhere
+    let fonts = std::iter::once(FONT.to_vec()).chain(
+        get_system_fonts()
+            .into_iter()
+            .filter_map(|path| fs::read(path).ok()),
+    );
+
+    for data in fonts {
+        let Ok(font) = FontVec::try_from_vec(data) else {
+            continue;
+        };
+
+        match get_coverage(&font, text) {
+            Coverage::Outlined => return Ok(font),
+            Coverage::ColourOnly => colour_only = true,
+            Coverage::Missing => {}
+        }
+    }
+
+    if colour_only {
+        bail!(
+            "'{}' is only available as a colour emoji, which the scribbles can't display. Try \
+            a monochrome glyph, or install a monochrome emoji font (such as Noto Emoji)",
+            text
+        );
+    }
+    bail!("Unable to find a font containing '{}'", text);
+}
+
+fn get_coverage(font: &FontVec, text: &str) -> Coverage {
+    let mut coverage = Coverage::Outlined;
+    for character in text.chars().filter(|c| !c.is_whitespace()) {
+        let id = font.glyph_id(character);
+        if id.0 == 0 {
+            return Coverage::Missing;
+        }
+
+        // Colour emoji fonts store their glyphs as bitmaps, with no outline to draw
+        if font.outline(id).is_none() {
+            coverage = Coverage::ColourOnly;
+        }
+    }
+    coverage
+}
+
+fn draw_glyph(font: &FontVec, text: &str) -> DynamicImage {
+    let scale = PxScale::from(GLYPH_SIZE as f32);
+    let (width, height) = text_size(scale, font, text);
+
+    let mut image = DynamicImage::new_rgb8(width.max(1), height.max(1));
+    image
+        .clone()
+        .pixels()
+        .for_each(|(x, y, _pixel)| image.put_pixel(x, y, Rgba::from([255, 255, 255, 255])));
+
+    draw_text_mut(
+        &mut image,
+        Rgba::from([0, 0, 0, 0]),
+        0,
+        0,
+        scale,
+        font,
+        text,
+    );
+    crop_to_content(image)
+}
+
+// The text layout leaves room for ascenders and descenders, the icon should only be the glyph
+fn crop_to_content(image: DynamicImage) -> DynamicImage {
+    let white = Rgba::from([255, 255, 255, 255]);
+    let bounds =
+        image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel != white)
+            .fold(None, |bounds, (x, y, _)| match bounds {
+                None => Some((x, y, x, y)),
+                Some((left, top, right, bottom)) => {
+                    Some((left.min(x), top.min(y), right.max(x), bottom.max(y)))
+                }
+            });
+
+    match bounds {
+        Some((left, top, right, bottom)) => {
+            image.crop_imm(left, top, right - left + 1, bottom - top + 1)
+        }
+        None => image,
+    }
+}
+
+fn get_system_fonts() -> Vec<PathBuf> {
+    let mut fonts = vec![];
+    for directory in get_font_directories() {
+        find_fonts(&directory, 0, &mut fonts);
+    }
+    fonts.sort();
+    debug!("Searching {} system fonts for a glyph", fonts.len());
+    fonts
+}
+
+fn find_fonts(directory: &Path, depth: usize, fonts: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_FONT_DEPTH {
+                find_fonts(&path, depth + 1, fonts);
+            }
+            continue;
+        }
+
+        let extension = path.extension().and_then(OsStr::to_str);
+        if let Some(extension) = extension {
+            if ["ttf", "otf", "ttc"].contains(&extension.to_lowercase().as_str()) {
+                fonts.push(path);
+            }
+        }
+    }
+}
+
+fn get_font_directories() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    if cfg!(target_os = "windows") {
+        let mut directories = vec![];
+        if let Some(windows) = env::var_os("WINDIR") {
+            directories.push(PathBuf::from(windows).join("Fonts"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            directories.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+        }
+        return directories;
+    }
+
+    let (system, user) = if cfg!(target_os = "macos") {
+        (
+            vec!["/System/Library/Fonts", "/Library/Fonts"],
+            vec!["Library/Fonts"],
+        )
+    } else {
+        (
+            vec!["/usr/share/fonts", "/usr/local/share/fonts"],
+            vec![".local/share/fonts", ".fonts"],
+        )
+    };
+
+    let mut directories: Vec<PathBuf> = system.into_iter().map(PathBuf::from).collect();
+    if let Some(home) = home {
+        directories.extend(user.into_iter().map(|directory| home.join(directory)));
+    }
+    directories
+}
@@ -1,29 +1,50 @@
 use ab_glyph::{FontRef, PxScale};
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use image::imageops::{dither, overlay, BiLevel, FilterType};
-use image::ImageFormat::Png;
-use image::{ColorType, DynamicImage, GenericImage, GenericImageView, GrayImage, Luma, Rgba};
+use image::ImageFormat::{Gif, Jpeg, Png};
+use image::{
+    ColorType, DynamicImage, GenericImage, GenericImageView, GrayImage, ImageReader, Limits, Luma,
+    Rgba,
+};
 use imageproc::drawing::{draw_text_mut, text_size};
-use log::warn;
 use std::borrow::BorrowMut;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::glyph::render_glyph;
+
+mod glyph;
 
 static FONT: &[u8] = include_bytes!("../fonts/Play-Bold.ttf");
 
+// Icons are scaled down to at most 120x60, anything much larger than this is just wasted space
+const MAX_ICON_DIMENSION: u32 = 2048;
+
+/// What's drawn in the middle of a scribble.
+#[derive(Debug, Clone)]
+pub enum ScribbleIcon {
+    /// An image, converted to greyscale
+    File(PathBuf),
+
+    /// One or more characters (such as an emoji), drawn from whichever font has them
+    Glyph(String),
+}
+
+/// Renders a scribble as it's sent to the GoXLR. Anything which can't be drawn (such as an icon
+/// which is missing or can't be decoded) is an error, rather than being left out.
 pub fn get_scribble(
-    path: Option<PathBuf>,
+    icon: Option<ScribbleIcon>,
     bottom: Option<String>,
     top: Option<String>,
     invert: bool,
-) -> [u8; 1024] {
-    let image = get_scribble_base(path, bottom, top);
+) -> Result<[u8; 1024]> {
+    let image = get_scribble_base(icon, bottom, top)?;
 
-    to_goxlr(image, invert).unwrap_or([0; 1024])
+    to_goxlr(image, invert)
 }
 
 pub fn get_scribble_png(
-    path: Option<PathBuf>,
+    icon: Option<ScribbleIcon>,
     bottom: Option<String>,
     top: Option<String>,
     invert: bool,
@@ -31,7 +52,7 @@ pub fn get_scribble_png(
     height: u32,
 ) -> Result<Vec<u8>> {
     // First, get the GrayScale version..
-    let mut image = get_scribble_base(path, bottom, top);
+    let mut image = get_scribble_base(icon, bottom, top)?;
 
     let white = Luma::from([255_u8]);
     let black = Luma::from([0_u8]);
@@ -66,42 +87,89 @@ pub fn get_scribble_png(
 }
 
 pub fn get_scribble_base(
-    path: Option<PathBuf>,
+    icon: Option<ScribbleIcon>,
     bottom: Option<String>,
     top: Option<String>,
-) -> GrayImage {
-    let mut processed_image = None;
-    let mut bottom_image = None;
-    let mut top_right_image = None;
+) -> Result<GrayImage> {
+    let processed_image = match icon {
+        Some(ScribbleIcon::File(path)) => Some(load_grayscale_image(path)?),
+        Some(ScribbleIcon::Glyph(glyph)) => Some(
+            render_glyph(&glyph).with_context(|| format!("Unable to draw the glyph {}", glyph))?,
+        ),
+        None => None,
+    };
 
-    if let Some(path) = path {
-        if let Ok(image) = load_grayscale_image(path) {
-            processed_image = Some(image);
+    let text_image = |text: Option<String>| -> Result<Option<DynamicImage>> {
+        match text {
+            Some(text) => create_text_image(&text)
+                .with_context(|| format!("Unable to draw the text '{}'", text))
+                .map(Some),
+            None => Ok(None),
         }
-    }
+    };
+    let bottom_image = text_image(bottom)?;
+    let top_right_image = text_image(top)?;
+
+    Ok(create_final_image(
+        processed_image,
+        bottom_image,
+        top_right_image,
+    ))
+}
 
-    if let Some(text) = bottom {
-        if let Ok(image) = create_text_image(&text) {
-            bottom_image = Some(image);
-        }
+/// Converts an uploaded image (PNG, JPEG or GIF) into a greyscale PNG, which is what the
+/// scribble will be rendered from, so what's stored is close to what's displayed.
+pub fn convert_icon(data: &[u8]) -> Result<Vec<u8>> {
+    let format = image::guess_format(data)?;
+    if ![Png, Jpeg, Gif].contains(&format) {
+        bail!("Unsupported image type, icons must be PNG, JPEG or GIF");
     }
 
-    if let Some(text) = top {
-        if let Ok(image) = create_text_image(&text) {
-            top_right_image = Some(image);
-        }
+    // Check the size from the header, a small file can still claim to be enormous, so it isn't
+    // decoded until it's known to fit (and the decoder is held to the same limits)
+    let (width, height) = ImageReader::with_format(Cursor::new(data), format).into_dimensions()?;
+    if width > MAX_ICON_DIMENSION || height > MAX_ICON_DIMENSION {
+        bail!(
+            "Image is {}x{}, icons can be at most {}x{}",
+            width,
+            height,
+            MAX_ICON_DIMENSION,
+            MAX_ICON_DIMENSION
+        );
     }
 
-    create_final_image(processed_image, bottom_image, top_right_image)
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_ICON_DIMENSION);
+    limits.max_image_height = Some(MAX_ICON_DIMENSION);
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    let img = reader.decode()?;
+
+    let mut bytes = Vec::new();
+    to_grayscale(img).write_to(&mut Cursor::new(&mut bytes), Png)?;
+    Ok(bytes)
+}
+
+/// Makes sure a glyph can be drawn, so a scribble isn't silently left without its icon.
+pub fn check_glyph(glyph: &str) -> Result<()> {
+    render_glyph(glyph).map(|_| ())
+}
+
+pub fn get_icon_dimensions(path: &Path) -> Result<(u32, u32)> {
+    Ok(image::image_dimensions(path)?)
 }
 
 fn load_grayscale_image(path: PathBuf) -> Result<DynamicImage> {
     if !path.exists() {
-        warn!("Unable to Load {}, file not found", path.to_string_lossy());
-        bail!("File not Found")
+        bail!("Icon not found: {}", path.to_string_lossy());
     }
 
-    let img = image::open(path)?;
+    let image = image::open(&path)
+        .with_context(|| format!("Unable to load the icon {}", path.to_string_lossy()))?;
+    Ok(to_grayscale(image))
+}
+
+fn to_grayscale(img: DynamicImage) -> DynamicImage {
     let mut img = img.grayscale();
 
     if img.color() == ColorType::La8 || img.color() == ColorType::L16 {
@@ -113,7 +181,7 @@ fn load_grayscale_image(path: PathBuf) -> Result<DynamicImage> {
         }
     }
 
-    Ok(img)
+    img
 }
 
 fn create_text_image(text: &str) -> Result<DynamicImage> {
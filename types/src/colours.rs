@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The basic CSS colour keywords, which can be used in place of a hex colour.
+const NAMED_COLOURS: [(&str, &str); 16] = [
+    ("black", "000000"),
+    ("silver", "C0C0C0"),
+    ("gray", "808080"),
+    ("white", "FFFFFF"),
+    ("maroon", "800000"),
+    ("red", "FF0000"),
+    ("purple", "800080"),
+    ("fuchsia", "FF00FF"),
+    ("green", "008000"),
+    ("lime", "00FF00"),
+    ("olive", "808000"),
+    ("yellow", "FFFF00"),
+    ("navy", "000080"),
+    ("blue", "0000FF"),
+    ("teal", "008080"),
+    ("aqua", "00FFFF"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidColour(pub String);
+
+impl Display for InvalidColour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = NAMED_COLOURS.iter().map(|(name, _)| *name).collect();
+        write!(
+            f,
+            "'{}' is not a valid colour, expected RRGGBB, #RRGGBB or one of: {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl Error for InvalidColour {}
+
+/// Parses a colour given by a user, returning it as the upper case RRGGBB hex string used by
+/// profiles.
+pub fn parse_colour(value: &str) -> Result<String, InvalidColour> {
+    let trimmed = value.trim();
+
+    if let Some((_, hex)) = NAMED_COLOURS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(trimmed))
+    {
+        return Ok(hex.to_string());
+    }
+
+    let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(InvalidColour(value.to_string()));
+    }
+    Ok(hex.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_are_normalised() {
+        assert_eq!(parse_colour("00ffaa"), Ok(String::from("00FFAA")));
+        assert_eq!(parse_colour(" #00ffaa "), Ok(String::from("00FFAA")));
+        assert_eq!(parse_colour("Fuchsia"), Ok(String::from("FF00FF")));
+    }
+
+    #[test]
+    fn invalid_colours_are_explained() {
+        for value in ["", "#", "00FFA", "00FFAAB", "GG0000", "##00FFAA", "orange"] {
+            assert_eq!(parse_colour(value), Err(InvalidColour(value.to_string())));
+        }
+
+        let message = parse_colour("orange").unwrap_err().to_string();
+        assert!(message.starts_with("'orange' is not a valid colour, expected RRGGBB, #RRGGBB"));
+        assert!(message.ends_with("teal, aqua"));
+    }
+}
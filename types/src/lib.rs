@@ -2,6 +2,8 @@
 use clap::ValueEnum;
 use derivative::Derivative;
 use enum_map::Enum;
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, JsonSchema_repr};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -9,11 +11,14 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use std::fmt::{Display, Formatter};
-use strum::{Display, EnumCount, EnumIter};
+use strum::{Display, EnumCount, EnumIter, IntoEnumIterator};
+
+pub mod colours;
 
 #[derive(Default, Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum ChannelName {
     #[default]
     Mic,
@@ -32,15 +37,17 @@ pub enum ChannelName {
 #[derive(Debug, Default, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum Mix {
     #[default]
     A,
     B,
 }
 
-#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SubMixChannelName {
     Mic,
     LineIn,
@@ -55,6 +62,7 @@ pub enum SubMixChannelName {
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum FaderName {
     A,
     B,
@@ -65,6 +73,7 @@ pub enum FaderName {
 #[derive(Copy, Clone, Debug, Display, Enum, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EncoderName {
     Pitch = 0x00,
     Gender = 0x01,
@@ -74,6 +83,7 @@ pub enum EncoderName {
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct FirmwareVersions {
     pub firmware: VersionNumber,
     pub fpga_count: u32,
@@ -82,6 +92,7 @@ pub struct FirmwareVersions {
 
 #[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct VersionNumber(pub u32, pub u32, pub Option<u32>, pub Option<u32>);
 
 impl std::fmt::Display for VersionNumber {
@@ -143,6 +154,7 @@ impl From<String> for VersionNumber {
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum OutputDevice {
     Headphones,
     BroadcastMix,
@@ -154,6 +166,7 @@ pub enum OutputDevice {
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum InputDevice {
     Microphone,
     Chat,
@@ -172,6 +185,11 @@ impl InputDevice {
             ChannelName::Headphones | ChannelName::MicMonitor | ChannelName::LineOut
         )
     }
+
+    /// Whether this input can be routed to an output, Chat can't be sent back to the Chat Mic.
+    pub fn can_route_to(&self, output: OutputDevice) -> bool {
+        !(*self == InputDevice::Chat && output == OutputDevice::ChatMic)
+    }
 }
 
 impl From<ChannelName> for InputDevice {
@@ -194,6 +212,7 @@ impl From<ChannelName> for InputDevice {
 #[derivative(PartialEq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EffectKey {
     MicInputMute = 0x0158,
     BleepLevel = 0x0073,
@@ -310,6 +329,7 @@ pub enum EffectKey {
 #[derivative(PartialEq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MicrophoneParamKey {
     MicType = 0x000,
     DynamicGain = 0x001,
@@ -347,6 +367,7 @@ pub enum MicrophoneParamKey {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum FaderDisplayStyle {
     TwoColour,
     Gradient,
@@ -357,6 +378,7 @@ pub enum FaderDisplayStyle {
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum Button {
     // These are all the buttons from the GoXLR Mini.
     Fader1Mute,
@@ -394,6 +416,7 @@ pub enum Button {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SimpleColourTargets {
     Global,
     Accent,
@@ -406,6 +429,7 @@ pub enum SimpleColourTargets {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SamplerColourTargets {
     SamplerSelectA,
     SamplerSelectB,
@@ -415,6 +439,7 @@ pub enum SamplerColourTargets {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EncoderColourTargets {
     Reverb,
     Pitch,
@@ -425,6 +450,7 @@ pub enum EncoderColourTargets {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum ButtonColourGroups {
     FaderMute,
     EffectSelector,
@@ -434,16 +460,33 @@ pub enum ButtonColourGroups {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum ButtonColourOffStyle {
     Dimmed,
     Colour2,
     DimmedColour2,
 }
 
+// The buttons which can be given their own levels in High Contrast mode
+#[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum HighContrastGroup {
+    FaderMute,
+    Cough,
+    Bleep,
+    EffectSelector,
+    EffectTypes,
+    SamplerSelector,
+    Sampler,
+}
+
 // MuteChat
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MuteFunction {
     All,
     ToStream,
@@ -452,9 +495,41 @@ pub enum MuteFunction {
     ToLineOut,
 }
 
+impl MuteFunction {
+    /// The output a channel is taken away from, 'All' mutes the channel itself.
+    pub fn output(&self) -> Option<OutputDevice> {
+        match self {
+            MuteFunction::All => None,
+            MuteFunction::ToStream => Some(OutputDevice::BroadcastMix),
+            MuteFunction::ToVoiceChat => Some(OutputDevice::ChatMic),
+            MuteFunction::ToPhones => Some(OutputDevice::Headphones),
+            MuteFunction::ToLineOut => Some(OutputDevice::LineOut),
+        }
+    }
+
+    /// The mute behaviours which actually do something for a channel. Output channels
+    /// (Headphones, Mic Monitor, Line Out) aren't routed anywhere, so only have 'All', inputs can
+    /// only be muted to the outputs they can be routed to.
+    pub fn valid_for_channel(channel: ChannelName) -> Vec<MuteFunction> {
+        MuteFunction::iter()
+            .filter(|function| function.is_valid_for_channel(channel))
+            .collect()
+    }
+
+    pub fn is_valid_for_channel(&self, channel: ChannelName) -> bool {
+        match self.output() {
+            None => true,
+            Some(output) => {
+                InputDevice::can_from(channel) && InputDevice::from(channel).can_route_to(output)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MicrophoneType {
     Dynamic,
     Condenser,
@@ -478,6 +553,7 @@ impl MicrophoneType {
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EffectBankPresets {
     Preset1,
     Preset2,
@@ -490,6 +566,33 @@ pub enum EffectBankPresets {
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum EffectSection {
+    Reverb,
+    Echo,
+    Pitch,
+    Gender,
+    Megaphone,
+    Robot,
+    HardTune,
+}
+
+// The effects which have their own button
+#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum EffectName {
+    Megaphone,
+    Robot,
+    HardTune,
+    Fx,
+}
+
+#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SampleBank {
     A,
     B,
@@ -499,6 +602,7 @@ pub enum SampleBank {
 #[derive(Debug, Copy, Clone, Display, EnumIter, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MiniEqFrequencies {
     Equalizer90Hz,
     Equalizer250Hz,
@@ -511,6 +615,7 @@ pub enum MiniEqFrequencies {
 #[derive(Debug, Copy, Clone, Display, EnumIter, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EqFrequencies {
     Equalizer31Hz,
     Equalizer63Hz,
@@ -537,6 +642,7 @@ These enums are essentially the same maps, and use 'as usize' and strum::iter().
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize_repr, Deserialize_repr))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum CompressorRatio {
     Ratio1_0,
@@ -559,6 +665,7 @@ pub enum CompressorRatio {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize_repr, Deserialize_repr))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum GateTimes {
     Gate10ms,
@@ -612,6 +719,7 @@ pub enum GateTimes {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize_repr, Deserialize_repr))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum CompressorAttackTime {
     // Note: 0ms is technically 0.001ms
@@ -640,6 +748,7 @@ pub enum CompressorAttackTime {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize_repr, Deserialize_repr))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum CompressorReleaseTime {
     // Note: 0 is technically 15 :)
@@ -668,6 +777,7 @@ pub enum CompressorReleaseTime {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum ReverbStyle {
     Library,
     DarkBloom,
@@ -680,6 +790,7 @@ pub enum ReverbStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum EchoStyle {
     Quarter,
     Eighth,
@@ -692,6 +803,7 @@ pub enum EchoStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum PitchStyle {
     Narrow,
     Wide,
@@ -700,6 +812,7 @@ pub enum PitchStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum GenderStyle {
     Narrow,
     Medium,
@@ -709,6 +822,7 @@ pub enum GenderStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MegaphoneStyle {
     Megaphone,
     Radio,
@@ -721,6 +835,7 @@ pub enum MegaphoneStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum RobotStyle {
     Robot1,
     Robot2,
@@ -730,6 +845,7 @@ pub enum RobotStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum RobotRange {
     Low,
     Medium,
@@ -739,6 +855,7 @@ pub enum RobotRange {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum HardTuneStyle {
     Natural,
     Medium,
@@ -748,6 +865,7 @@ pub enum HardTuneStyle {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum HardTuneSource {
     All,
     Music,
@@ -759,6 +877,7 @@ pub enum HardTuneSource {
 #[derive(Debug, Copy, Clone, Enum, EnumIter, Display, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SampleButtons {
     TopLeft,
     TopRight,
@@ -769,6 +888,7 @@ pub enum SampleButtons {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SamplePlaybackMode {
     PlayNext,
     PlayStop,
@@ -781,6 +901,7 @@ pub enum SamplePlaybackMode {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SamplePlayOrder {
     Sequential,
     Random,
@@ -789,6 +910,7 @@ pub enum SamplePlayOrder {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum DisplayMode {
     Simple,
     Advanced,
@@ -797,6 +919,42 @@ pub enum DisplayMode {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum MicProfileSection {
+    /// The Microphone Type, and the Gain for each type
+    Microphone,
+    Gate,
+    Compressor,
+    Equaliser,
+    DeEsser,
+    Bleep,
+}
+
+/// The parts of a profile which can be inherited from a base profile
+#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum ProfileSection {
+    /// Colours and animation
+    Lighting,
+
+    /// The routing table, submix assignments and monitoring
+    Routing,
+
+    /// Fader channels, mute buttons and scribbles
+    Faders,
+
+    /// Effect presets and encoders
+    Effects,
+
+    Sampler,
+}
+
+#[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum DisplayModeComponents {
     NoiseGate,
     Equaliser,
@@ -807,6 +965,7 @@ pub enum DisplayModeComponents {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum MuteState {
     Unmuted,
     MutedToX,
@@ -816,6 +975,7 @@ pub enum MuteState {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum AnimationMode {
     RetroRainbow,
     RainbowDark,
@@ -828,6 +988,7 @@ pub enum AnimationMode {
 #[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum WaterfallDirection {
     Down,
     Up,
@@ -837,6 +998,7 @@ pub enum WaterfallDirection {
 #[derive(Default, Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum VodMode {
     #[default]
     Routable,
@@ -846,6 +1008,7 @@ pub enum VodMode {
 #[derive(Default, Debug, Clone, Enum, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum DeviceType {
     #[default]
     Unknown,
@@ -856,6 +1019,7 @@ pub enum DeviceType {
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum DriverInterface {
     #[default]
     TUSB,
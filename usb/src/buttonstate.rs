@@ -2,7 +2,7 @@ use enum_map::Enum;
 use enumset::{EnumSet, EnumSetType};
 use strum::EnumIter;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ButtonStates {
     Colour1 = 0x01,
     Colour2 = 0x00,
@@ -7,6 +7,7 @@ use crate::commands::{
     Command, FirmwareAction, FirmwareCommand, HardwareInfoCommand, SystemInfoCommand,
 };
 use crate::dcp::DCPCategory;
+use crate::jackstate::JackStates;
 use crate::routing::InputDevice;
 use anyhow::{bail, Result};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -16,6 +17,7 @@ use goxlr_types::{
     MicrophoneType, Mix, SubMixChannelName, VersionNumber,
 };
 use std::io::{Cursor, Write};
+use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 
 // This is a basic SuperTrait which defines all the 'Parts' of the GoXLR for use.
@@ -34,6 +36,13 @@ pub trait AttachGoXLR {
     fn set_unique_identifier(&mut self, identifier: String);
     fn is_connected(&mut self) -> bool;
     fn stop_polling(&mut self);
+
+    /// How often the device is checked for button, fader and encoder changes.
+    fn set_poll_interval(&mut self, interval: Duration);
+
+    /// Whether the device is read for changes every poll interval, rather than being told about
+    /// them by the driver, so a device which stops being read has stalled.
+    fn is_polled(&self) -> bool;
 }
 
 pub trait ExecutableGoXLR {
@@ -43,6 +52,11 @@ pub trait ExecutableGoXLR {
 
     fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>>;
     fn get_descriptor(&self) -> Result<UsbData>;
+
+    /// The most recent request sent to the device, which may still be in progress.
+    fn last_request(&self) -> Option<Command> {
+        None
+    }
 }
 
 // These are commands that can be executed, but perform_request must be implemented..
@@ -281,6 +295,12 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         })
     }
 
+    /// No firmware has been found to report jack sense, the button state response only carries
+    /// the buttons, encoders and faders, so there's nothing to request yet and this is None.
+    fn get_jack_states(&mut self) -> Result<Option<JackStates>> {
+        Ok(None)
+    }
+
     fn set_animation_mode(
         &mut self,
         enabled: bool,
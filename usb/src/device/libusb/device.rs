@@ -2,6 +2,7 @@ use crate::commands::Command;
 use crate::device::base::{
     AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
 };
+use crate::device::UsbAccess;
 use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
 use anyhow::{anyhow, bail, Error, Result};
 use byteorder::{ByteOrder, LittleEndian};
@@ -12,7 +13,7 @@ use rusb::{
     Device, DeviceDescriptor, DeviceHandle, Direction, GlobalContext, Language, Recipient,
     RequestType,
 };
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::sync::Arc;
 use std::thread::sleep;
 use std::time::Duration;
@@ -31,11 +32,15 @@ pub struct GoXLRUSB {
     pause_polling: Arc<AtomicBool>,
     stop_polling: Arc<AtomicBool>,
 
+    // Milliseconds between each poll, can be changed while polling
+    poll_interval: Arc<AtomicU16>,
+
     stopping: Arc<AtomicBool>,
     disconnecting: bool,
 
     language: Language,
     command_count: u16,
+    last_request: Option<Command>,
     timeout: Duration,
 }
 
@@ -171,11 +176,13 @@ impl AttachGoXLR for GoXLRUSB {
             event_sender,
             identifier: None,
             command_count: 0,
+            last_request: None,
             stopping: Arc::new(AtomicBool::new(false)),
             disconnecting: false,
             timeout,
             pause_polling: Arc::new(AtomicBool::new(false)),
             stop_polling: Arc::new(AtomicBool::new(false)),
+            poll_interval: Arc::new(AtomicU16::new(20)),
         };
 
         // Resets the state of the device (unconfirmed - Might just be the command id counter)
@@ -234,14 +241,16 @@ impl AttachGoXLR for GoXLRUSB {
         let stopping = self.stopping.clone();
         let paused = self.pause_polling.clone();
         let stopped = self.stop_polling.clone();
+        let interval = self.poll_interval.clone();
 
-        let poll_millis = 20;
         task::spawn(async move {
             loop {
                 if stopping.load(Ordering::Relaxed) {
                     break;
                 }
 
+                let poll_millis = interval.load(Ordering::Relaxed) as u64;
+
                 if paused.load(Ordering::Relaxed) || stopped.load(Ordering::Relaxed) {
                     tokio::time::sleep(Duration::from_millis(poll_millis)).await;
                     continue;
@@ -284,11 +293,21 @@ impl AttachGoXLR for GoXLRUSB {
         warn!("Disabling GoXLR Value Polling");
         self.stop_polling.store(true, Ordering::Relaxed);
     }
+
+    fn set_poll_interval(&mut self, interval: Duration) {
+        let millis = interval.as_millis().clamp(1, u16::MAX as u128) as u16;
+        self.poll_interval.store(millis, Ordering::Relaxed);
+    }
+
+    fn is_polled(&self) -> bool {
+        true
+    }
 }
 
 impl ExecutableGoXLR for GoXLRUSB {
     fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>> {
         self.pause_polling.store(true, Ordering::Relaxed);
+        self.last_request = Some(command);
 
         if command == Command::ResetCommandIndex {
             self.command_count = 0;
@@ -405,6 +424,10 @@ impl ExecutableGoXLR for GoXLRUSB {
         Ok(response)
     }
 
+    fn last_request(&self) -> Option<Command> {
+        self.last_request
+    }
+
     fn get_descriptor(&self) -> Result<UsbData> {
         let version = self.descriptor.usb_version();
         let usb_version = (version.0, version.1, version.2);
@@ -460,6 +483,34 @@ pub fn find_devices() -> Vec<GoXLRDevice> {
     found_devices
 }
 
+pub fn check_usb_access() -> UsbAccess {
+    let devices = match rusb::devices() {
+        Ok(devices) => devices,
+        Err(rusb::Error::Access) => return UsbAccess::PermissionDenied,
+        Err(e) => return UsbAccess::Failed(e.to_string()),
+    };
+
+    let mut result = UsbAccess::NotConnected;
+    for device in devices.iter() {
+        if let Ok(descriptor) = device.device_descriptor() {
+            if descriptor.vendor_id() != VID_GOXLR
+                || (descriptor.product_id() != PID_GOXLR_FULL
+                    && descriptor.product_id() != PID_GOXLR_MINI)
+            {
+                continue;
+            }
+
+            // Opening the device doesn't claim it, so this won't upset anything else using it.
+            result = match device.open() {
+                Ok(_) => return UsbAccess::Available,
+                Err(rusb::Error::Access) => UsbAccess::PermissionDenied,
+                Err(e) => UsbAccess::Failed(e.to_string()),
+            };
+        }
+    }
+    result
+}
+
 pub fn get_interface_version() -> (DriverInterface, VersionNumber) {
     let version = rusb::version();
     (
@@ -1,6 +1,8 @@
 use crate::device::base::AttachGoXLR;
 use crate::device::base::FullGoXLRDevice;
 use crate::device::base::GoXLRDevice;
+use crate::error::DriverError;
+use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
 use anyhow::Result;
 use goxlr_types::{DriverInterface, VersionNumber};
 use tokio::sync::mpsc::Sender;
@@ -17,6 +19,22 @@ cfg_if::cfg_if! {
             device::get_interface_version()
         }
 
+        pub fn check_driver() -> Result<(), DriverError> {
+            device::check_driver()
+        }
+
+        pub fn check_usb_access() -> UsbAccess {
+            // Access is managed by the driver, so if it's happy, we are too.
+            if !is_goxlr_connected() {
+                return UsbAccess::NotConnected;
+            }
+
+            match device::check_driver() {
+                Ok(()) => UsbAccess::Available,
+                Err(e) => UsbAccess::Failed(e.to_string()),
+            }
+        }
+
         pub fn find_devices() -> Vec<GoXLRDevice> {
             device::find_devices()
         }
@@ -38,6 +56,15 @@ cfg_if::cfg_if! {
             device::get_interface_version()
         }
 
+        pub fn check_driver() -> Result<(), DriverError> {
+            // libUSB is bundled, there's nothing to check.
+            Ok(())
+        }
+
+        pub fn check_usb_access() -> UsbAccess {
+            device::check_usb_access()
+        }
+
         pub fn find_devices() -> Vec<GoXLRDevice> {
             device::find_devices()
         }
@@ -52,3 +79,31 @@ cfg_if::cfg_if! {
         }
     }
 }
+
+/// Whether any attached GoXLR can actually be opened, used to explain why a device which is
+/// plugged in isn't showing up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsbAccess {
+    NotConnected,
+    Available,
+    PermissionDenied,
+    Failed(String),
+}
+
+/// Checks for a GoXLR by enumerating the USB bus directly, unlike find_devices this doesn't
+/// depend on the platform driver, so can be used to tell whether one is attached but unusable.
+pub fn is_goxlr_connected() -> bool {
+    let devices = match rusb::devices() {
+        Ok(devices) => devices,
+        Err(_) => return false,
+    };
+
+    devices.iter().any(|device| {
+        if let Ok(descriptor) = device.device_descriptor() {
+            return descriptor.vendor_id() == VID_GOXLR
+                && (descriptor.product_id() == PID_GOXLR_FULL
+                    || descriptor.product_id() == PID_GOXLR_MINI);
+        }
+        false
+    })
+}
@@ -3,9 +3,10 @@ use crate::device::base::{
     AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
 };
 use crate::device::tusb::tusbaudio::{
-    get_devices, get_version, DeviceHandle, EventChannelReceiver, EventChannelSender,
-    TUSB_INTERFACE,
+    get_devices, get_version, is_driver_installed, DeviceHandle, EventChannelReceiver,
+    EventChannelSender, TUSB_INTERFACE,
 };
+use crate::error::DriverError;
 use anyhow::{bail, Result};
 use byteorder::{ByteOrder, LittleEndian};
 use goxlr_types::{DriverInterface, VersionNumber};
@@ -19,6 +20,9 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::Sender;
 
+// The oldest driver release known to work with the utility.
+const MINIMUM_DRIVER_VERSION: VersionNumber = VersionNumber(5, 12, Some(0), None);
+
 pub struct TUSBAudioGoXLR {
     // Basic Device Information..
     handle: DeviceHandle,
@@ -243,6 +247,14 @@ impl AttachGoXLR for TUSBAudioGoXLR {
     fn stop_polling(&mut self) {
         // The TUSB implementation is event driven, so there's no polling to stop.
     }
+
+    fn set_poll_interval(&mut self, _interval: Duration) {
+        // As above, the driver tells us when something changes, so there's no interval to set.
+    }
+
+    fn is_polled(&self) -> bool {
+        false
+    }
 }
 
 impl ExecutableGoXLR for TUSBAudioGoXLR {
@@ -370,9 +382,33 @@ impl GoXLRCommands for TUSBAudioGoXLR {}
 impl FullGoXLRDevice for TUSBAudioGoXLR {}
 
 pub fn find_devices() -> Vec<GoXLRDevice> {
+    // Without the driver, touching the interface would panic.
+    if !is_driver_installed() {
+        return vec![];
+    }
     get_devices()
 }
 
 pub fn get_interface_version() -> (DriverInterface, VersionNumber) {
+    if !is_driver_installed() {
+        return (DriverInterface::TUSB, VersionNumber::default());
+    }
     (DriverInterface::TUSB, get_version())
 }
+
+pub fn check_driver() -> Result<(), DriverError> {
+    if !is_driver_installed() {
+        return Err(DriverError::NotInstalled {
+            required: MINIMUM_DRIVER_VERSION,
+        });
+    }
+
+    let found = get_version();
+    if found < MINIMUM_DRIVER_VERSION {
+        return Err(DriverError::Outdated {
+            found,
+            required: MINIMUM_DRIVER_VERSION,
+        });
+    }
+    Ok(())
+}
@@ -29,6 +29,7 @@ use winreg::RegKey;
 use goxlr_types::VersionNumber;
 
 use crate::device::base::GoXLRDevice;
+use crate::error::DriverError;
 use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
 
 // Define the Types of the various methods..
@@ -58,6 +59,9 @@ lazy_static! {
        Library::new(locate_library().as_str()).expect("Unable to Load GoXLR API Driver")
     };
     pub static ref TUSB_INTERFACE: TUSBAudio<'static> = TUSBAudio::new().expect("Unable to Parse GoXLR API Driver");
+
+    // Installing the driver requires a reboot, so we only need to check this once.
+    static ref DRIVER_INSTALLED: bool = PathBuf::from(locate_library()).exists();
 }
 
 fn locate_library() -> String {
@@ -78,6 +82,11 @@ fn locate_library() -> String {
     String::from("C:/Program Files/TC-HELICON/GoXLR_Audio_Driver/W10_x64/goxlr_audioapi_x64.dll")
 }
 
+/// Checks whether the driver's API library is present, if it isn't, loading LIBRARY will panic.
+pub fn is_driver_installed() -> bool {
+    *DRIVER_INSTALLED
+}
+
 #[allow(dead_code)]
 pub struct TUSBAudio<'lib> {
     // DriverInfo
@@ -397,7 +406,17 @@ impl TUSBAudio<'_> {
             return Ok(handle);
         }
 
-        bail!("Unable to Open Device: {}", result)
+        // The numeric codes aren't documented, but their names are (TSTATUS_*), so we use those
+        // to work out whether this is something the user can resolve.
+        let error = self.get_error(result);
+        if error.contains("BUSY") || error.contains("IN_USE") || error.contains("ALREADY_OPEN") {
+            return Err(DriverError::DeviceBusy.into());
+        }
+        if error.contains("ACCESS_DENIED") {
+            return Err(DriverError::AccessDenied.into());
+        }
+
+        bail!("Unable to Open Device: {} ({})", error, result)
     }
 
     pub fn close_device(&self, handle: u32) -> Result<()> {
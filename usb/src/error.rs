@@ -1,3 +1,5 @@
+use goxlr_types::VersionNumber;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ConnectError {
     #[error("No GoXLR device was found")]
@@ -13,6 +15,26 @@ pub enum ConnectError {
     DeviceNotClaimed,
 }
 
+/// Problems with the platform driver, these are surfaced to the user as they can't be resolved
+/// without them doing something (installing / updating the driver, closing another app, etc).
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DriverError {
+    #[error("GoXLR driver not installed, version {required} or later is required")]
+    NotInstalled { required: VersionNumber },
+
+    #[error("GoXLR driver too old, version {found} found, {required} required")]
+    Outdated {
+        found: VersionNumber,
+        required: VersionNumber,
+    },
+
+    #[error("The GoXLR is busy, please close any other application using it")]
+    DeviceBusy,
+
+    #[error("Access to the GoXLR was denied by the driver")]
+    AccessDenied,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum CommandError {
     #[error("USB error: {0}")]
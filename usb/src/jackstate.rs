@@ -0,0 +1,7 @@
+/// Whether something is plugged into each of the GoXLR's physical jacks.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct JackStates {
+    pub headphones: bool,
+    pub line_out: bool,
+    pub microphone: bool,
+}
@@ -6,6 +6,7 @@ pub mod commands;
 pub mod dcp;
 pub mod devices;
 pub mod error;
+pub mod jackstate;
 pub mod microphone;
 pub mod routing;
 